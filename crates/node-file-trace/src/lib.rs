@@ -7,9 +7,10 @@ use std::{
     env::current_dir,
     fs,
     future::Future,
+    mem::take,
     path::{Path, PathBuf},
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
@@ -20,11 +21,8 @@ use clap::Parser;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::channel;
 use turbo_tasks::{
-    backend::Backend,
-    primitives::{OptionStringVc, StringsVc},
-    util::FormatDuration,
-    NothingVc, TaskId, TransientInstance, TransientValue, TurboTasks, TurboTasksBackendApi,
-    UpdateInfo, Value,
+    backend::Backend, primitives::OptionStringVc, util::FormatDuration, NothingVc, TaskId,
+    TransientInstance, TransientValue, TurboTasks, TurboTasksBackendApi, UpdateInfo, Value,
 };
 use turbo_tasks_fs::{
     glob::GlobVc, DirectoryEntry, DiskFileSystemVc, FileSystem, FileSystemPathVc, FileSystemVc,
@@ -42,13 +40,14 @@ use turbopack::{
 use turbopack_cli_utils::issue::{ConsoleUiVc, IssueSeverityCliOption, LogOptions};
 use turbopack_core::{
     asset::{Asset, AssetVc, AssetsVc},
+    build_summary::{entry_summary, BuildSummary, EntrySummaryVc},
     compile_time_info::CompileTimeInfo,
     context::{AssetContext, AssetContextVc},
     environment::{EnvironmentVc, ExecutionEnvironment, NodeJsEnvironment},
     file_source::FileSourceVc,
-    issue::{IssueContextExt, IssueReporter, IssueSeverity, IssueVc},
+    issue::{IssueContextExt, IssueReporter, IssueReporterProvider, IssueSeverity, IssueVc},
     reference::all_assets,
-    resolve::options::{ImportMapping, ResolvedMap},
+    resolve::options::{ImportMapping, ResolvedMap, ResolvedMapVc, ResolvedMatcher},
 };
 
 use crate::nft_json::NftJsonAssetVc;
@@ -130,6 +129,18 @@ pub struct CommonArgs {
     #[cfg_attr(feature = "node-api", serde(default))]
     exact: bool,
 
+    /// Don't trace into the files of these packages, e.g. because they are
+    /// provided by the runtime.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "node-api", serde(default))]
+    ignore_package: Vec<String>,
+
+    /// Don't trace files of these content types, e.g. `image/*`. The content
+    /// type is guessed from the extension.
+    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "node-api", serde(default))]
+    ignore_content_type: Vec<String>,
+
     /// Enable experimental garbage collection with the provided memory limit in
     /// MB.
     #[cfg_attr(feature = "cli", clap(long))]
@@ -169,6 +180,13 @@ pub enum Args {
         #[cfg_attr(feature = "cli", clap(short, long, default_value_t = String::from("dist")))]
         #[cfg_attr(feature = "node-api", serde(default = "default_output_directory"))]
         output_directory: String,
+
+        /// Write a JSON summary of the build to this file, with the durations
+        /// of its phases, task cache statistics, the size of each entry and
+        /// its referenced files, and the number of issues per severity.
+        #[cfg_attr(feature = "cli", clap(long))]
+        #[cfg_attr(feature = "node-api", serde(default))]
+        summary: Option<String>,
     },
 
     // Print total size of input and referenced files
@@ -247,6 +265,7 @@ async fn input_to_modules<'a>(
     context: String,
     module_options: TransientInstance<ModuleOptionsContext>,
     resolve_options: TransientInstance<ResolveOptionsContext>,
+    ignored: ResolvedMapVc,
 ) -> Result<AssetsVc> {
     let root = fs.root();
     let process_cwd = process_cwd
@@ -254,7 +273,7 @@ async fn input_to_modules<'a>(
         .map(|p| p.trim_start_matches(&context).to_owned());
 
     let context: AssetContextVc =
-        create_module_asset(root, process_cwd, module_options, resolve_options).into();
+        create_module_asset(root, process_cwd, module_options, resolve_options, ignored).into();
 
     let mut list = Vec::new();
     for input in input.iter() {
@@ -320,6 +339,7 @@ pub async fn start(
     turbo_tasks: Option<&Arc<TurboTasks<MemoryBackend>>>,
     module_options: Option<ModuleOptionsContext>,
     resolve_options: Option<ResolveOptionsContext>,
+    issue_reporter: Option<Box<dyn IssueReporterProvider>>,
 ) -> Result<Vec<String>> {
     register();
     let &CommonArgs {
@@ -335,16 +355,15 @@ pub async fn start(
     #[cfg(feature = "persistent_cache")]
     if let Some(cache) = cache {
         use tokio::time::timeout;
+        use turbo_tasks_disk_cache::DiskPersistedGraph;
         use turbo_tasks_memory::MemoryBackendWithPersistedGraph;
-        use turbo_tasks_rocksdb::RocksDbPersistedGraph;
 
-        run(
-            &args,
+        return run(
+            args.clone(),
             || {
                 let start = Instant::now();
-                let backend = MemoryBackendWithPersistedGraph::new(
-                    RocksDbPersistedGraph::new(cache).unwrap(),
-                );
+                let backend =
+                    MemoryBackendWithPersistedGraph::new(DiskPersistedGraph::new(cache).unwrap());
                 let tt = TurboTasks::new(backend);
                 let elapsed = start.elapsed();
                 println!("restored cache {}", FormatDuration(elapsed));
@@ -376,9 +395,11 @@ pub async fn start(
                 let elapsed = start.elapsed();
                 println!("writing cache {}", FormatDuration(elapsed));
             },
+            module_options,
+            resolve_options,
+            issue_reporter,
         )
         .await;
-        return;
     }
 
     run(
@@ -406,6 +427,7 @@ pub async fn start(
         },
         module_options,
         resolve_options,
+        issue_reporter,
     )
     .await
 }
@@ -416,6 +438,7 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
     final_finish: impl FnOnce(Arc<TurboTasks<B>>, TaskId, Duration) -> F,
     module_options: Option<ModuleOptionsContext>,
     resolve_options: Option<ResolveOptionsContext>,
+    issue_reporter: Option<Box<dyn IssueReporterProvider>>,
 ) -> Result<Vec<String>> {
     let &CommonArgs {
         watch,
@@ -425,6 +448,14 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
         ..
     } = args.common();
 
+    let summary_path = match &*args {
+        Args::Build { summary, .. } => summary.clone(),
+        _ => None,
+    };
+    let has_summary = summary_path.is_some();
+    let build_summary = Arc::new(Mutex::new(BuildSummary::default()));
+    let task_build_summary = build_summary.clone();
+
     let start = Instant::now();
     let finish = |tt: Arc<TurboTasks<B>>, root_task: TaskId| async move {
         if watch {
@@ -459,8 +490,17 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
             } = tt
                 .get_or_wait_aggregated_update_info(Duration::from_millis(100))
                 .await;
+            let task_statistics = summary_path.as_ref().map(|_| tt.task_statistics());
             final_finish(tt, root_task, dur).await;
             let dur2 = start.elapsed();
+            if let (Some(summary_path), Some(task_statistics)) = (&summary_path, task_statistics) {
+                let mut summary = take(&mut *build_summary.lock().unwrap());
+                summary.add_phase("compilation", dur);
+                summary.add_phase("finish", dur2 - dur);
+                summary.set_task_statistics(&task_statistics);
+                fs::write(summary_path, summary.to_json()?)
+                    .with_context(|| format!("writing the build summary to {summary_path}"))?;
+            }
             println!(
                 "done in {} ({} compilation, {} task execution, {} tasks)",
                 FormatDuration(dur2),
@@ -485,13 +525,19 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
         log_detail,
         log_level: log_level.map_or_else(|| IssueSeverity::Error, |l| l.0),
     });
+    let issue_provider: Arc<dyn IssueReporterProvider> = match issue_reporter {
+        Some(issue_reporter) => issue_reporter.into(),
+        // Initialize a ConsoleUi reporter if no custom reporter was provided
+        None => Arc::new(move || ConsoleUiVc::new(log_options.clone()).into()),
+    };
     let task = tt.spawn_root_task(move || {
         let dir = dir.clone();
         let args = args.clone();
         let sender = sender.clone();
         let module_options = module_options.clone();
         let resolve_options = resolve_options.clone();
-        let log_options = log_options.clone();
+        let issue_provider = issue_provider.clone();
+        let build_summary = task_build_summary.clone();
         Box::pin(async move {
             let output = main_operation(
                 TransientValue::new(dir.clone()),
@@ -506,16 +552,23 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
                 .strongly_consistent()
                 .await?;
 
-            let console_ui = ConsoleUiVc::new(log_options);
-            console_ui
-                .as_issue_reporter()
-                .report_issues(TransientInstance::new(issues), source)
+            issue_provider
+                .get_issue_reporter()
+                .report_issues(TransientInstance::new(issues.clone()), source)
                 .await?;
 
+            let output = output.await?;
+            if has_summary {
+                let mut summary = BuildSummary::default();
+                summary.add_issues(&issues).await?;
+                for entry in output.entries.iter() {
+                    summary.add_entry(entry.await?.clone_value());
+                }
+                *build_summary.lock().unwrap() = summary;
+            }
+
             if has_return_value {
-                let output_read_ref = output.await?;
-                let output_iter = output_read_ref.iter().cloned();
-                sender.send(output_iter.collect::<Vec<String>>()).await?;
+                sender.send(output.files.clone()).await?;
                 drop(sender);
             }
             Ok(NothingVc::new().into())
@@ -530,13 +583,32 @@ async fn run<B: Backend + 'static, F: Future<Output = ()>>(
     Ok(output)
 }
 
+/// The result of an action: The files to print or the annotations which were
+/// written, and the summaries of the entries when a build summary was
+/// requested.
+#[turbo_tasks::value]
+struct MainOperationOutput {
+    files: Vec<String>,
+    entries: Vec<EntrySummaryVc>,
+}
+
+impl MainOperationOutput {
+    fn files(files: Vec<String>) -> MainOperationOutputVc {
+        MainOperationOutput {
+            files,
+            entries: Vec::new(),
+        }
+        .cell()
+    }
+}
+
 #[turbo_tasks::function]
 async fn main_operation(
     current_dir: TransientValue<PathBuf>,
     args: TransientInstance<Args>,
     module_options: TransientInstance<ModuleOptionsContext>,
     resolve_options: TransientInstance<ResolveOptionsContext>,
-) -> Result<StringsVc> {
+) -> Result<MainOperationOutputVc> {
     let dir = current_dir.into_value();
     let args = &*args;
     let &CommonArgs {
@@ -545,9 +617,25 @@ async fn main_operation(
         exact,
         ref context_directory,
         ref process_cwd,
+        ref ignore_package,
+        ref ignore_content_type,
         ..
     } = args.common();
     let context = process_context(&dir, context_directory.as_ref()).unwrap();
+    let ignored = ResolvedMap {
+        by_glob: Vec::new(),
+        by_matcher: ignore_package
+            .iter()
+            .map(|name| ResolvedMatcher::Package(name.clone()))
+            .chain(
+                ignore_content_type
+                    .iter()
+                    .map(|content_type| ResolvedMatcher::ContentType(content_type.clone())),
+            )
+            .map(|matcher| (matcher, ImportMapping::Ignore.into()))
+            .collect(),
+    }
+    .cell();
     let fs = create_fs("context directory", &context, watch).await?;
 
     match *args {
@@ -562,6 +650,7 @@ async fn main_operation(
                 context,
                 module_options,
                 resolve_options,
+                ignored,
             )
             .await?;
             for module in modules.iter() {
@@ -574,7 +663,7 @@ async fn main_operation(
                 }
             }
 
-            return Ok(StringsVc::cell(result.into_iter().collect::<Vec<_>>()));
+            return Ok(MainOperationOutput::files(result.into_iter().collect()));
         }
         Args::Annotate { common: _ } => {
             let input = process_input(&dir, &context, input).unwrap();
@@ -588,6 +677,7 @@ async fn main_operation(
                 context,
                 module_options,
                 resolve_options,
+                ignored,
             )
             .await?
             .iter()
@@ -601,10 +691,11 @@ async fn main_operation(
             for emit in emits {
                 emit.await?;
             }
-            return Ok(StringsVc::cell(output_nft_assets));
+            return Ok(MainOperationOutput::files(output_nft_assets));
         }
         Args::Build {
             ref output_directory,
+            ref summary,
             common: _,
         } => {
             let output = process_context(&dir, Some(output_directory)).unwrap();
@@ -613,6 +704,7 @@ async fn main_operation(
             let input_dir = fs.root();
             let output_dir = out_fs.root();
             let mut emits = Vec::new();
+            let mut entries = Vec::new();
             for module in input_to_modules(
                 fs,
                 input,
@@ -621,21 +713,29 @@ async fn main_operation(
                 context,
                 module_options,
                 resolve_options,
+                ignored,
             )
             .await?
             .iter()
             {
                 let rebased = RebasedAssetVc::new(*module, input_dir, output_dir).into();
                 emits.push(emit_with_completion(rebased, output_dir));
+                if summary.is_some() {
+                    entries.push(entry_summary(*module));
+                }
             }
             // Wait for all files to be emitted
             for emit in emits {
                 emit.await?;
             }
+            return Ok(MainOperationOutput {
+                files: Vec::new(),
+                entries,
+            }
+            .cell());
         }
         Args::Size { common: _ } => todo!(),
     }
-    Ok(StringsVc::cell(Vec::new()))
 }
 
 #[turbo_tasks::function]
@@ -644,6 +744,7 @@ async fn create_module_asset(
     process_cwd: Option<String>,
     module_options: TransientInstance<ModuleOptionsContext>,
     resolve_options: TransientInstance<ResolveOptionsContext>,
+    ignored: ResolvedMapVc,
 ) -> Result<ModuleAssetContextVc> {
     let env = EnvironmentVc::new(Value::new(ExecutionEnvironment::NodeJsLambda(
         NodeJsEnvironment {
@@ -669,14 +770,17 @@ async fn create_module_asset(
     if resolve_options.emulate_environment.is_none() {
         resolve_options.emulate_environment = Some(env);
     }
-    if resolve_options.resolved_map.is_none() {
-        resolve_options.resolved_map = Some(
-            ResolvedMap {
-                by_glob: glob_mappings,
-            }
-            .cell(),
-        );
-    }
+    let mut resolved_map = match resolve_options.resolved_map {
+        Some(resolved_map) => resolved_map.await?.clone_value(),
+        None => ResolvedMap {
+            by_glob: glob_mappings,
+            by_matcher: Vec::new(),
+        },
+    };
+    resolved_map
+        .by_matcher
+        .extend(ignored.await?.by_matcher.iter().cloned());
+    resolve_options.resolved_map = Some(resolved_map.cell());
 
     Ok(ModuleAssetContextVc::new(
         TransitionsByNameVc::cell(HashMap::new()),
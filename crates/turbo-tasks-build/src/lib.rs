@@ -206,7 +206,8 @@ impl<'a> RegisterContext<'a> {
     }
 
     fn process_enum(&mut self, enum_item: ItemEnum) -> Result<()> {
-        if has_attribute(&enum_item.attrs, "value") {
+        // Generic values are registered on first use.
+        if has_attribute(&enum_item.attrs, "value") && enum_item.generics.params.is_empty() {
             self.add_value(&enum_item.ident);
         }
         Ok(())
@@ -295,7 +296,8 @@ impl<'a> RegisterContext<'a> {
     }
 
     fn process_struct(&mut self, struct_item: ItemStruct) -> Result<()> {
-        if has_attribute(&struct_item.attrs, "value") {
+        // Generic values are registered on first use.
+        if has_attribute(&struct_item.attrs, "value") && struct_item.generics.params.is_empty() {
             self.add_value(&struct_item.ident);
         }
         Ok(())
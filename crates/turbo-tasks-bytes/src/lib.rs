@@ -1,9 +1,13 @@
 pub mod bytes;
 pub mod stream;
+pub mod value_stream;
 
 pub use crate::{
     bytes::{Bytes, BytesVc},
     stream::{Stream, StreamRead},
+    value_stream::{
+        ValueStream, ValueStreamSender, ValueStreamSenderVc, ValueStreamVc, ValueStreamWriter,
+    },
 };
 
 pub fn register() {
@@ -0,0 +1,114 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{unbounded, UnboundedSender},
+    Stream as StreamTrait, StreamExt,
+};
+use turbo_tasks::{mark_finished, util::SharedError, CompletionVc, RawVc};
+
+use crate::stream::Stream;
+
+type ValueStreamItem = Result<RawVc, SharedError>;
+
+/// A sequence of values which a task emits while it's executing, e.g. the
+/// assets discovered while crawling a module graph. Consumers can process
+/// the values before the producing task has finished.
+///
+/// A stream is created with [ValueStreamVc::new] and read with
+/// [ValueStream::read].
+#[turbo_tasks::value(eq = "manual", cell = "new", serialization = "none")]
+#[derive(Clone, Debug)]
+pub struct ValueStream(#[turbo_tasks(trace_ignore)] Stream<ValueStreamItem>);
+
+impl ValueStream {
+    /// Returns a [StreamTrait] over the values of the stream. Every reader
+    /// sees all values from the start.
+    pub fn read<T: From<RawVc>>(&self) -> impl StreamTrait<Item = Result<T>> + Send {
+        self.0
+            .read()
+            .map(|item| item.map(T::from).map_err(anyhow::Error::from))
+    }
+}
+
+impl ValueStreamVc {
+    /// Creates a stream whose values are emitted by the task returned by
+    /// `producer`. It has to be called within a turbo_tasks function, and the
+    /// returned Vc has to be its result.
+    ///
+    /// The producer task gets a [ValueStreamSenderVc] to send the values.
+    /// When only the producer task is re-executed, the stream is replaced
+    /// by a new one, which invalidates all readers.
+    pub fn new(producer: impl FnOnce(ValueStreamSenderVc) -> CompletionVc) -> Self {
+        // The cell is created in the current task and updated from the producer
+        // task.
+        let cell = turbo_tasks::macro_helpers::find_cell_by_type(*VALUESTREAM_VALUE_TYPE_ID);
+
+        // The cell initially holds an open stream without values. The first
+        // execution of the producer picks up that stream.
+        let (sender, receiver) = unbounded();
+        cell.update_shared(ValueStream(Stream::new_open(vec![], Box::new(receiver))));
+        let initial = Mutex::new(Some(sender));
+
+        let _ = producer(
+            ValueStreamSender {
+                get: Box::new(move || {
+                    if let Some(sender) = initial.lock().unwrap().take() {
+                        sender
+                    } else {
+                        let (sender, receiver) = unbounded();
+                        cell.update_shared(ValueStream(Stream::new_open(
+                            vec![],
+                            Box::new(receiver),
+                        )));
+                        sender
+                    }
+                }),
+            }
+            .cell(),
+        );
+
+        let raw: RawVc = cell.into();
+        raw.into()
+    }
+}
+
+/// Passed to the producer task of a [ValueStream].
+#[turbo_tasks::value(eq = "manual", cell = "new", serialization = "none")]
+pub struct ValueStreamSender {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    get: Box<dyn Fn() -> UnboundedSender<ValueStreamItem> + Send + Sync>,
+}
+
+impl ValueStreamSender {
+    /// Starts emitting the values of the current execution of the producer
+    /// task. The stream is closed when the returned writer is dropped.
+    ///
+    /// This marks the producer task as finished, so that strongly consistent
+    /// reads of the stream don't wait for the producer.
+    pub fn start(&self) -> ValueStreamWriter {
+        mark_finished();
+        ValueStreamWriter {
+            sender: (self.get)(),
+        }
+    }
+}
+
+/// Emits the values of a [ValueStream], see [ValueStreamSender::start].
+pub struct ValueStreamWriter {
+    sender: UnboundedSender<ValueStreamItem>,
+}
+
+impl ValueStreamWriter {
+    /// Emits a value. Returns false when the stream is not read anymore.
+    pub fn send(&self, value: impl Into<RawVc>) -> bool {
+        self.sender.unbounded_send(Ok(value.into())).is_ok()
+    }
+
+    /// Emits an error, which is returned to all readers.
+    pub fn send_error(&self, error: anyhow::Error) -> bool {
+        self.sender
+            .unbounded_send(Err(SharedError::new(error)))
+            .is_ok()
+    }
+}
@@ -0,0 +1,53 @@
+#![feature(min_specialization)]
+
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::StreamExt;
+use tokio::sync::Notify;
+use turbo_tasks::CompletionVc;
+use turbo_tasks_bytes::{ValueStreamSenderVc, ValueStreamVc};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+static FIRST_VALUE_READ: Notify = Notify::const_new();
+
+#[tokio::test]
+async fn values_are_read_before_the_producer_finishes() {
+    turbo_tasks_bytes::register();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        run! {
+            let stream = numbers().strongly_consistent().await?;
+            let mut values = stream.read::<NumberVc>();
+
+            let first = values.next().await.unwrap()?;
+            assert_eq!(*first.await?, 1);
+            FIRST_VALUE_READ.notify_one();
+
+            let second = values.next().await.unwrap()?;
+            assert_eq!(*second.await?, 2);
+            assert!(values.next().await.is_none());
+        }
+    })
+    .await
+    .expect("the stream should be readable while the producer is running");
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function]
+fn numbers() -> ValueStreamVc {
+    ValueStreamVc::new(produce_numbers)
+}
+
+#[turbo_tasks::function]
+async fn produce_numbers(sender: ValueStreamSenderVc) -> Result<CompletionVc> {
+    let writer = sender.await?.start();
+    writer.send(NumberVc::cell(1));
+    // The second value is only produced after the first one has been read.
+    FIRST_VALUE_READ.notified().await;
+    writer.send(NumberVc::cell(2));
+    Ok(CompletionVc::new())
+}
@@ -0,0 +1,998 @@
+//! A [PersistedGraph] which keeps the task graph in memory and writes it to a
+//! file on disk when turbo-tasks is stopped. The next session restores the
+//! graph from the file, so unchanged tasks don't need to be recomputed.
+//!
+//! Task ids are only valid within a session, so tasks are stored with their
+//! own persistent ids and are mapped to the task ids of the current session
+//! when they are needed. Tasks which depend on state outside of the task
+//! graph (see [turbo_tasks::mark_session_dependent]) are restored as dirty, so
+//! changes made while the process was down are picked up.
+//!
+//! The graph is stored in a [KeyValueStore], with one entry per task. Only
+//! tasks which have changed are written when turbo-tasks is stopped. By
+//! default it's a [FileStore], other stores can be used with
+//! [DiskPersistedGraph::with_store].
+
+mod store;
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Mutex, MutexGuard},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use turbo_tasks::{
+    backend::PersistentTaskType,
+    persisted_graph::{
+        ActivateResult, DeactivateResult, PersistResult, PersistTaskState, PersistedGraph,
+        PersistedGraphApi, ReadTaskState, TaskData,
+    },
+    with_task_id_mapping, CellId, IdMapping, RawVc, TaskId,
+};
+
+pub use crate::store::{FileStore, KeyValueStore, WriteBatch};
+
+/// The version of the layout of the stored entries. Caches with a different
+/// version are discarded.
+const FORMAT_VERSION: u32 = 2;
+
+const VERSION_KEY: &[u8] = b"version";
+const TASK_KEY_PREFIX: &[u8] = b"task:";
+
+type PersistentId = usize;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+enum PersistedVc {
+    Output(PersistentId),
+    Cell(PersistentId, CellId),
+}
+
+fn task_key(id: PersistentId) -> Vec<u8> {
+    [TASK_KEY_PREFIX, &(id as u64).to_be_bytes()].concat()
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedTask {
+    /// The [PersistentTaskType], serialized with persistent ids.
+    task_type: Vec<u8>,
+    /// The persistent ids referenced by `task_type`.
+    task_type_refs: Vec<PersistentId>,
+    /// Only tasks which have been persisted have data. Other tasks are only
+    /// referenced by persisted tasks.
+    data: Option<PersistedData>,
+    /// Activeness is tracked per session.
+    #[serde(skip)]
+    activeness: Activeness,
+}
+
+impl PersistedTask {
+    fn should_be_active(&self) -> bool {
+        self.activeness.externally_active || self.activeness.active_parents > 0
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedData {
+    /// The [TaskData], serialized with persistent ids.
+    content: Vec<u8>,
+    /// The persistent ids referenced by `content`.
+    content_refs: Vec<PersistentId>,
+    children: Vec<PersistentId>,
+    dependencies: Vec<PersistedVc>,
+    clean: bool,
+    session_dependent: bool,
+}
+
+#[derive(Default)]
+struct Activeness {
+    active: bool,
+    /// Kept active by an active task of the memory graph.
+    externally_active: bool,
+    /// The number of active persisted tasks which have this task as child.
+    active_parents: u32,
+}
+
+/// Records the task ids referenced by a serialized value.
+#[derive(Default)]
+struct CollectTaskIds(RefCell<Vec<TaskId>>);
+
+impl IdMapping<TaskId> for CollectTaskIds {
+    fn forward(&self, id: TaskId) -> usize {
+        self.0.borrow_mut().push(id);
+        0
+    }
+
+    fn backward(&self, _id: usize) -> TaskId {
+        unreachable!("CollectTaskIds is only used for serialization")
+    }
+}
+
+struct ToPersistentIds<'a>(&'a HashMap<TaskId, PersistentId>);
+
+impl<'a> IdMapping<TaskId> for ToPersistentIds<'a> {
+    fn forward(&self, id: TaskId) -> usize {
+        *self
+            .0
+            .get(&id)
+            .expect("task ids must have a persistent id before serialization")
+    }
+
+    fn backward(&self, _id: usize) -> TaskId {
+        unreachable!("ToPersistentIds is only used for serialization")
+    }
+}
+
+struct ToTaskIds<'a>(&'a [Option<TaskId>]);
+
+impl<'a> IdMapping<TaskId> for ToTaskIds<'a> {
+    fn forward(&self, _id: TaskId) -> usize {
+        unreachable!("ToTaskIds is only used for deserialization")
+    }
+
+    fn backward(&self, id: usize) -> TaskId {
+        self.0
+            .get(id)
+            .copied()
+            .flatten()
+            .expect("persistent ids must have a task id before deserialization")
+    }
+}
+
+#[derive(Default)]
+struct Graph {
+    tasks: Vec<PersistedTask>,
+    by_type: HashMap<Vec<u8>, PersistentId>,
+    dependents: HashMap<PersistedVc, HashSet<PersistentId>>,
+    persistent_ids: HashMap<TaskId, PersistentId>,
+    task_ids: Vec<Option<TaskId>>,
+    /// Tasks which need to be written to the store.
+    changed: HashSet<PersistentId>,
+}
+
+impl Graph {
+    fn new(mut tasks: Vec<PersistedTask>) -> Self {
+        let mut by_type = HashMap::new();
+        let mut dependents: HashMap<PersistedVc, HashSet<PersistentId>> = HashMap::new();
+        for (id, task) in tasks.iter_mut().enumerate() {
+            by_type.insert(task.task_type.clone(), id);
+            if let Some(data) = &mut task.data {
+                // The outside state might have changed while the process was down.
+                if data.session_dependent {
+                    data.clean = false;
+                }
+                for dependency in data.dependencies.iter() {
+                    dependents.entry(*dependency).or_default().insert(id);
+                }
+            }
+        }
+        Self {
+            task_ids: vec![None; tasks.len()],
+            tasks,
+            by_type,
+            dependents,
+            persistent_ids: HashMap::new(),
+            changed: HashSet::new(),
+        }
+    }
+
+    /// Serializes a value with persistent ids. Returns the persistent ids
+    /// referenced by the value too. When `allocate` is false, values which
+    /// reference tasks unknown to the graph can't be serialized.
+    fn serialize<T: Serialize>(
+        &mut self,
+        value: &T,
+        allocate: bool,
+        api: &dyn PersistedGraphApi,
+    ) -> Option<(Vec<u8>, Vec<PersistentId>)> {
+        let collector = CollectTaskIds::default();
+        with_task_id_mapping(&collector, || bincode::serialized_size(value)).ok()?;
+        let mut refs = Vec::new();
+        for task in collector.0.into_inner() {
+            let id = if allocate {
+                self.ensure_persistent_id(task, api)?
+            } else {
+                *self.persistent_ids.get(&task)?
+            };
+            refs.push(id);
+        }
+        refs.sort_unstable();
+        refs.dedup();
+        let bytes = with_task_id_mapping(ToPersistentIds(&self.persistent_ids), || {
+            bincode::serialize(value)
+        })
+        .ok()?;
+        Some((bytes, refs))
+    }
+
+    /// Deserializes a value with persistent ids. All referenced ids must have
+    /// been mapped with [Graph::ensure_task_id] before.
+    fn deserialize<T: DeserializeOwned>(&self, bytes: &[u8]) -> Option<T> {
+        with_task_id_mapping(ToTaskIds(&self.task_ids), || bincode::deserialize(bytes)).ok()
+    }
+
+    /// Returns the persistent id of a task, adding the task to the graph if
+    /// needed. Transient tasks can't be added.
+    fn ensure_persistent_id(
+        &mut self,
+        task: TaskId,
+        api: &dyn PersistedGraphApi,
+    ) -> Option<PersistentId> {
+        if let Some(id) = self.persistent_ids.get(&task) {
+            return Some(*id);
+        }
+        let task_type = api.lookup_task_type(task)?;
+        let (bytes, refs) = self.serialize(task_type, true, api)?;
+        let id = match self.by_type.get(&bytes) {
+            Some(id) => *id,
+            None => {
+                let id = self.tasks.len();
+                self.tasks.push(PersistedTask {
+                    task_type: bytes.clone(),
+                    task_type_refs: refs,
+                    data: None,
+                    activeness: Activeness::default(),
+                });
+                self.task_ids.push(None);
+                self.by_type.insert(bytes, id);
+                self.changed.insert(id);
+                id
+            }
+        };
+        self.persistent_ids.insert(task, id);
+        self.task_ids[id] = Some(task);
+        Some(id)
+    }
+
+    /// Returns the task id of a persistent id in the current session,
+    /// creating the task if needed. Fails when the task type can't be
+    /// deserialized anymore, e.g. because the function was removed.
+    fn ensure_task_id(&mut self, id: PersistentId, api: &dyn PersistedGraphApi) -> Option<TaskId> {
+        if let Some(task) = *self.task_ids.get(id)? {
+            return Some(task);
+        }
+        for reference in self.tasks[id].task_type_refs.clone() {
+            self.ensure_task_id(reference, api)?;
+        }
+        let task_type: PersistentTaskType = self.deserialize(&self.tasks[id].task_type)?;
+        let task = api.get_or_create_task_type(task_type);
+        self.persistent_ids.entry(task).or_insert(id);
+        self.task_ids[id] = Some(task);
+        Some(task)
+    }
+
+    fn resolve_task_ids(
+        &mut self,
+        ids: impl IntoIterator<Item = PersistentId>,
+        api: &dyn PersistedGraphApi,
+    ) -> Vec<TaskId> {
+        ids.into_iter()
+            .filter_map(|id| self.ensure_task_id(id, api))
+            .collect()
+    }
+
+    fn persisted_vc(
+        &mut self,
+        vc: RawVc,
+        allocate: bool,
+        api: &dyn PersistedGraphApi,
+    ) -> Option<PersistedVc> {
+        let task = vc.get_task_id();
+        let id = if allocate {
+            self.ensure_persistent_id(task, api)?
+        } else {
+            *self.persistent_ids.get(&task)?
+        };
+        Some(match vc {
+            RawVc::TaskOutput(_) => PersistedVc::Output(id),
+            RawVc::TaskCell(_, cell) => PersistedVc::Cell(id, cell),
+        })
+    }
+
+    /// Replaces the data of a task, updating the dependents and the
+    /// activeness of the children. Returns the tasks which need to be
+    /// activated and deactivated.
+    fn set_data(
+        &mut self,
+        id: PersistentId,
+        data: Option<PersistedData>,
+    ) -> (Vec<PersistentId>, Vec<PersistentId>) {
+        let new_children = data
+            .as_ref()
+            .map(|data| data.children.iter().copied().collect::<HashSet<_>>())
+            .unwrap_or_default();
+        if let Some(data) = &data {
+            for dependency in data.dependencies.iter() {
+                self.dependents.entry(*dependency).or_default().insert(id);
+            }
+        }
+        let old = std::mem::replace(&mut self.tasks[id].data, data);
+        self.changed.insert(id);
+        let old_children = match old {
+            Some(old) => {
+                for dependency in old.dependencies.iter() {
+                    let still_dependent = self.tasks[id]
+                        .data
+                        .as_ref()
+                        .map_or(false, |data| data.dependencies.contains(dependency));
+                    if !still_dependent {
+                        if let Some(dependents) = self.dependents.get_mut(dependency) {
+                            dependents.remove(&id);
+                        }
+                    }
+                }
+                old.children.into_iter().collect()
+            }
+            None => HashSet::new(),
+        };
+
+        let mut to_activate = Vec::new();
+        let mut to_deactivate = Vec::new();
+        if self.tasks[id].activeness.active {
+            for &child in new_children.difference(&old_children) {
+                if self.increment_active_parents(child) {
+                    to_activate.push(child);
+                }
+            }
+            for &child in old_children.difference(&new_children) {
+                if self.decrement_active_parents(child) {
+                    to_deactivate.push(child);
+                }
+            }
+        }
+        (to_activate, to_deactivate)
+    }
+
+    /// Returns true when the task needs to be activated.
+    fn increment_active_parents(&mut self, id: PersistentId) -> bool {
+        let activeness = &mut self.tasks[id].activeness;
+        activeness.active_parents += 1;
+        activeness.active_parents == 1
+    }
+
+    /// Returns true when the task needs to be deactivated.
+    fn decrement_active_parents(&mut self, id: PersistentId) -> bool {
+        let activeness = &mut self.tasks[id].activeness;
+        activeness.active_parents = activeness.active_parents.saturating_sub(1);
+        activeness.active_parents == 0
+    }
+
+    fn children(&self, id: PersistentId) -> Vec<PersistentId> {
+        self.tasks[id]
+            .data
+            .as_ref()
+            .map(|data| data.children.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// A [PersistedGraph] which is stored in a [KeyValueStore]. See the crate
+/// documentation for details.
+pub struct DiskPersistedGraph<S: KeyValueStore = FileStore> {
+    store: S,
+    version: String,
+    graph: Mutex<Graph>,
+    /// Entries of the store which belong to a different version and are
+    /// removed with the next write.
+    stale_keys: Mutex<Vec<Vec<u8>>>,
+}
+
+impl DiskPersistedGraph {
+    /// Restores the graph from the cache in `path`. The cache is only valid
+    /// for the executable which has written it, since the layout of values
+    /// might change with every build.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_version(path, executable_version())
+    }
+
+    /// Restores the graph from the cache in `path`, when it has been written
+    /// with the same `version`. Values are serialized without any schema, so
+    /// the version needs to change whenever the layout of a value changes.
+    pub fn with_version(path: impl AsRef<Path>, version: impl Into<String>) -> Result<Self> {
+        Self::with_store(FileStore::new(path)?, version)
+    }
+}
+
+impl<S: KeyValueStore> DiskPersistedGraph<S> {
+    /// Restores the graph from the `store`, when it has been written with the
+    /// same `version`. Otherwise the entries of the store are replaced with
+    /// the next write.
+    pub fn with_store(store: S, version: impl Into<String>) -> Result<Self> {
+        let version = version.into();
+        let entries = store
+            .read_all()
+            .context("unable to read the persisted graph")?;
+        let (tasks, stale_keys) = match read_tasks(&entries, &version) {
+            Some(tasks) => (tasks, Vec::new()),
+            None => (
+                Vec::new(),
+                entries.into_iter().map(|(key, _)| key).collect(),
+            ),
+        };
+        Ok(Self {
+            store,
+            version,
+            graph: Mutex::new(Graph::new(tasks)),
+            stale_keys: Mutex::new(stale_keys),
+        })
+    }
+
+    /// Returns the number of tasks which are stored with their data.
+    pub fn persisted_tasks(&self) -> usize {
+        self.graph()
+            .tasks
+            .iter()
+            .filter(|task| task.data.is_some())
+            .count()
+    }
+
+    fn graph(&self) -> MutexGuard<'_, Graph> {
+        self.graph.lock().unwrap()
+    }
+
+    /// Writes the tasks which have changed since the last write.
+    fn write(&self) -> Result<()> {
+        let mut graph = self.graph();
+        let mut stale_keys = self.stale_keys.lock().unwrap();
+        let mut batch: WriteBatch = stale_keys.iter().map(|key| (key.clone(), None)).collect();
+        batch.push((
+            VERSION_KEY.to_vec(),
+            Some(bincode::serialize(&(FORMAT_VERSION, &self.version))?),
+        ));
+        let mut changed = graph.changed.iter().copied().collect::<Vec<_>>();
+        changed.sort_unstable();
+        for id in changed {
+            batch.push((task_key(id), Some(bincode::serialize(&graph.tasks[id])?)));
+        }
+        self.store.write_batch(batch)?;
+        stale_keys.clear();
+        graph.changed.clear();
+        Ok(())
+    }
+}
+
+/// Reads the tasks from the entries of a store. Returns `None` when they
+/// were written by a different version or are corrupted.
+fn read_tasks(entries: &[(Vec<u8>, Vec<u8>)], version: &str) -> Option<Vec<PersistedTask>> {
+    let mut tasks = Vec::new();
+    let mut valid_version = false;
+    for (key, value) in entries {
+        if key == VERSION_KEY {
+            let (format_version, cache_version): (u32, String) =
+                bincode::deserialize(value).ok()?;
+            valid_version = format_version == FORMAT_VERSION && cache_version == version;
+        } else {
+            let id = key.strip_prefix(TASK_KEY_PREFIX)?;
+            let id = u64::from_be_bytes(id.try_into().ok()?) as PersistentId;
+            tasks.push((id, bincode::deserialize(value).ok()?));
+        }
+    }
+    if !valid_version {
+        return None;
+    }
+    tasks.sort_unstable_by_key(|(id, _)| *id);
+    // Persistent ids are indices, so there must not be any gaps.
+    if tasks
+        .iter()
+        .enumerate()
+        .any(|(index, (id, _))| index != *id)
+    {
+        return None;
+    }
+    Some(tasks.into_iter().map(|(_, task)| task).collect())
+}
+
+/// A version which changes whenever the executable is rebuilt.
+fn executable_version() -> String {
+    let Ok(metadata) = std::env::current_exe().and_then(|path| path.metadata()) else {
+        return String::new();
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    format!("{}-{}", metadata.len(), modified)
+}
+
+impl<S: KeyValueStore> PersistedGraph for DiskPersistedGraph<S> {
+    fn read(
+        &self,
+        task: TaskId,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<(TaskData, ReadTaskState)>> {
+        let mut graph = self.graph();
+        let Some(&id) = graph.persistent_ids.get(&task) else {
+            return Ok(None);
+        };
+        let Some(data) = &graph.tasks[id].data else {
+            return Ok(None);
+        };
+        let clean = data.clean;
+        for reference in data.content_refs.clone() {
+            if graph.ensure_task_id(reference, api).is_none() {
+                graph.set_data(id, None);
+                return Ok(None);
+            }
+        }
+        let content = graph.tasks[id].data.as_ref().map(|data| &data.content);
+        let Some(task_data) = content.and_then(|content| graph.deserialize::<TaskData>(content))
+        else {
+            // The data can't be deserialized anymore, so the task needs to be
+            // recomputed.
+            graph.set_data(id, None);
+            return Ok(None);
+        };
+        let activeness = &graph.tasks[id].activeness;
+        Ok(Some((
+            task_data,
+            ReadTaskState {
+                clean,
+                keeps_external_active: activeness.active && activeness.active_parents > 0,
+            },
+        )))
+    }
+
+    fn lookup(
+        &self,
+        _partial_task_type: &PersistentTaskType,
+        _api: &dyn PersistedGraphApi,
+    ) -> Result<bool> {
+        // Partial lookups are not supported, every task type is looked up on
+        // its own.
+        Ok(false)
+    }
+
+    fn lookup_one(
+        &self,
+        task_type: &PersistentTaskType,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<TaskId>> {
+        let mut graph = self.graph();
+        let Some((bytes, _)) = graph.serialize(task_type, false, api) else {
+            return Ok(None);
+        };
+        let Some(&id) = graph.by_type.get(&bytes) else {
+            return Ok(None);
+        };
+        if graph.tasks[id].data.is_none() {
+            return Ok(None);
+        }
+        Ok(graph.ensure_task_id(id, api))
+    }
+
+    fn is_persisted(&self, task: TaskId, _api: &dyn PersistedGraphApi) -> Result<bool> {
+        let graph = self.graph();
+        Ok(graph
+            .persistent_ids
+            .get(&task)
+            .map_or(false, |&id| graph.tasks[id].data.is_some()))
+    }
+
+    fn persist(
+        &self,
+        task: TaskId,
+        mut data: TaskData,
+        state: PersistTaskState,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<PersistResult>> {
+        // Cells which can't be stored are recomputed when they are read.
+        data.make_serializable();
+        let mut graph = self.graph();
+        let Some(id) = graph.ensure_persistent_id(task, api) else {
+            return Ok(None);
+        };
+        let mut children = Vec::new();
+        for child in data.children.iter() {
+            // Transient children are not tracked by the persisted graph.
+            if let Some(child) = graph.ensure_persistent_id(*child, api) {
+                children.push(child);
+            }
+        }
+        let mut dependencies = Vec::new();
+        for dependency in data.dependencies.iter() {
+            // Changes of transient tasks wouldn't invalidate the task.
+            let Some(dependency) = graph.persisted_vc(*dependency, true, api) else {
+                return Ok(None);
+            };
+            dependencies.push(dependency);
+        }
+        let Some((content, content_refs)) = graph.serialize(&data, true, api) else {
+            return Ok(None);
+        };
+        let (mut to_activate, mut to_deactivate) = graph.set_data(
+            id,
+            Some(PersistedData {
+                content,
+                content_refs,
+                children,
+                dependencies,
+                clean: true,
+                session_dependent: state.session_dependent,
+            }),
+        );
+        let task = &mut graph.tasks[id];
+        task.activeness.externally_active = state.externally_active;
+        if task.should_be_active() != task.activeness.active {
+            if task.activeness.active {
+                to_deactivate.push(id);
+            } else {
+                to_activate.push(id);
+            }
+        }
+        Ok(Some(PersistResult {
+            tasks_to_activate: graph.resolve_task_ids(to_activate, api),
+            tasks_to_deactivate: graph.resolve_task_ids(to_deactivate, api),
+        }))
+    }
+
+    fn activate_when_needed(
+        &self,
+        task: TaskId,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<ActivateResult>> {
+        let mut graph = self.graph();
+        let Some(&id) = graph.persistent_ids.get(&task) else {
+            return Ok(None);
+        };
+        if !graph.tasks[id].should_be_active() {
+            return Ok(None);
+        }
+        let mut more_tasks_to_activate = Vec::new();
+        if !graph.tasks[id].activeness.active {
+            graph.tasks[id].activeness.active = true;
+            for child in graph.children(id) {
+                if graph.increment_active_parents(child) {
+                    more_tasks_to_activate.push(child);
+                }
+            }
+        }
+        let persisted_task = &graph.tasks[id];
+        let keeps_external_active = persisted_task.activeness.active_parents > 0;
+        let external = persisted_task.data.is_none();
+        let dirty = persisted_task
+            .data
+            .as_ref()
+            .map_or(false, |data| !data.clean);
+        Ok(Some(ActivateResult {
+            keeps_external_active,
+            external,
+            dirty,
+            more_tasks_to_activate: graph.resolve_task_ids(more_tasks_to_activate, api),
+        }))
+    }
+
+    fn deactivate_when_needed(
+        &self,
+        task: TaskId,
+        api: &dyn PersistedGraphApi,
+    ) -> Result<Option<DeactivateResult>> {
+        let mut graph = self.graph();
+        let Some(&id) = graph.persistent_ids.get(&task) else {
+            return Ok(None);
+        };
+        if graph.tasks[id].activeness.active_parents > 0 {
+            return Ok(None);
+        }
+        let mut more_tasks_to_deactivate = Vec::new();
+        if graph.tasks[id].activeness.active && !graph.tasks[id].should_be_active() {
+            graph.tasks[id].activeness.active = false;
+            for child in graph.children(id) {
+                if graph.decrement_active_parents(child) {
+                    more_tasks_to_deactivate.push(child);
+                }
+            }
+        }
+        Ok(Some(DeactivateResult {
+            more_tasks_to_deactivate: graph.resolve_task_ids(more_tasks_to_deactivate, api),
+        }))
+    }
+
+    fn set_externally_active(&self, task: TaskId, _api: &dyn PersistedGraphApi) -> Result<bool> {
+        let mut graph = self.graph();
+        let Some(&id) = graph.persistent_ids.get(&task) else {
+            return Ok(false);
+        };
+        let activeness = &mut graph.tasks[id].activeness;
+        activeness.externally_active = true;
+        Ok(!activeness.active)
+    }
+
+    fn unset_externally_active(&self, task: TaskId, _api: &dyn PersistedGraphApi) -> Result<bool> {
+        let mut graph = self.graph();
+        let Some(&id) = graph.persistent_ids.get(&task) else {
+            return Ok(false);
+        };
+        let persisted_task = &mut graph.tasks[id];
+        persisted_task.activeness.externally_active = false;
+        Ok(persisted_task.activeness.active && !persisted_task.should_be_active())
+    }
+
+    fn remove_outdated_externally_active(
+        &self,
+        _api: &dyn PersistedGraphApi,
+    ) -> Result<Vec<TaskId>> {
+        // Activeness is not restored from disk, so there are no outdated
+        // external keep alives.
+        Ok(Vec::new())
+    }
+
+    fn make_dirty(&self, task: TaskId, _api: &dyn PersistedGraphApi) -> Result<bool> {
+        let mut graph = self.graph();
+        let Some(&id) = graph.persistent_ids.get(&task) else {
+            return Ok(false);
+        };
+        let persisted_task = &mut graph.tasks[id];
+        let Some(data) = &mut persisted_task.data else {
+            return Ok(false);
+        };
+        data.clean = false;
+        let active = persisted_task.activeness.active;
+        graph.changed.insert(id);
+        Ok(active)
+    }
+
+    fn make_clean(&self, task: TaskId, _api: &dyn PersistedGraphApi) -> Result<()> {
+        let mut graph = self.graph();
+        if let Some(&id) = graph.persistent_ids.get(&task) {
+            if let Some(data) = &mut graph.tasks[id].data {
+                data.clean = true;
+                graph.changed.insert(id);
+            }
+        }
+        Ok(())
+    }
+
+    fn make_dependent_dirty(&self, vc: RawVc, api: &dyn PersistedGraphApi) -> Result<Vec<TaskId>> {
+        let mut graph = self.graph();
+        let Some(vc) = graph.persisted_vc(vc, false, api) else {
+            return Ok(Vec::new());
+        };
+        let dependents = graph
+            .dependents
+            .get(&vc)
+            .map(|dependents| dependents.iter().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let mut active_dependents = Vec::new();
+        for id in dependents {
+            let persisted_task = &mut graph.tasks[id];
+            if let Some(data) = &mut persisted_task.data {
+                if data.clean {
+                    data.clean = false;
+                    if persisted_task.activeness.active {
+                        active_dependents.push(id);
+                    }
+                    graph.changed.insert(id);
+                }
+            }
+        }
+        Ok(graph.resolve_task_ids(active_dependents, api))
+    }
+
+    fn get_active_external_tasks(&self, api: &dyn PersistedGraphApi) -> Result<Vec<TaskId>> {
+        let mut graph = self.graph();
+        let ids = graph
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.activeness.active && task.data.is_none())
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        Ok(graph.resolve_task_ids(ids, api))
+    }
+
+    fn get_dirty_active_tasks(&self, api: &dyn PersistedGraphApi) -> Result<Vec<TaskId>> {
+        let mut graph = self.graph();
+        let ids = graph
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| {
+                task.activeness.active && task.data.as_ref().map_or(false, |data| !data.clean)
+            })
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        Ok(graph.resolve_task_ids(ids, api))
+    }
+
+    fn get_pending_active_update(
+        &self,
+        _api: &dyn PersistedGraphApi,
+    ) -> Result<(Vec<TaskId>, Vec<TaskId>)> {
+        // Activeness updates are applied immediately.
+        Ok((Vec::new(), Vec::new()))
+    }
+
+    fn stop(&self, _api: &dyn PersistedGraphApi) -> Result<()> {
+        self.write().context("unable to write the persisted graph")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, mem::take, sync::Mutex};
+
+    use anyhow::Result;
+    use turbo_tasks::{
+        backend::PersistentTaskType,
+        persisted_graph::{PersistTaskState, PersistedGraph, PersistedGraphApi, TaskData},
+        GenericNativeFunction, NativeFunction, RawVc, TaskId,
+    };
+
+    use super::{
+        DiskPersistedGraph, KeyValueStore, PersistedData, PersistedTask, WriteBatch,
+        TASK_KEY_PREFIX,
+    };
+
+    struct NoTasks;
+
+    impl PersistedGraphApi for NoTasks {
+        fn get_or_create_task_type(&self, _ty: PersistentTaskType) -> TaskId {
+            unreachable!()
+        }
+
+        fn lookup_task_type(&self, _id: TaskId) -> Option<&PersistentTaskType> {
+            None
+        }
+    }
+
+    /// Knows the type of a single task.
+    struct OneTask(PersistentTaskType);
+
+    impl PersistedGraphApi for OneTask {
+        fn get_or_create_task_type(&self, _ty: PersistentTaskType) -> TaskId {
+            unreachable!()
+        }
+
+        fn lookup_task_type(&self, id: TaskId) -> Option<&PersistentTaskType> {
+            (*id == 1).then_some(&self.0)
+        }
+    }
+
+    /// Records the batches written to it.
+    #[derive(Default)]
+    struct RecordingStore {
+        entries: Vec<(Vec<u8>, Vec<u8>)>,
+        batches: Mutex<Vec<WriteBatch>>,
+    }
+
+    impl KeyValueStore for RecordingStore {
+        fn read_all(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+            Ok(self.entries.clone())
+        }
+
+        fn write_batch(&self, batch: WriteBatch) -> Result<()> {
+            self.batches.lock().unwrap().push(batch);
+            Ok(())
+        }
+    }
+
+    fn written_tasks(batch: &WriteBatch) -> usize {
+        batch
+            .iter()
+            .filter(|(key, value)| key.starts_with(TASK_KEY_PREFIX) && value.is_some())
+            .count()
+    }
+
+    fn add_task<S: KeyValueStore>(graph: &DiskPersistedGraph<S>, session_dependent: bool) {
+        let mut graph = graph.graph();
+        let id = graph.tasks.len();
+        graph.changed.insert(id);
+        graph.tasks.push(PersistedTask {
+            task_type: vec![id as u8],
+            task_type_refs: Vec::new(),
+            data: Some(PersistedData {
+                content: Vec::new(),
+                content_refs: Vec::new(),
+                children: Vec::new(),
+                dependencies: Vec::new(),
+                clean: true,
+                session_dependent,
+            }),
+            activeness: Default::default(),
+        });
+    }
+
+    #[test]
+    fn restores_tasks_of_the_same_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph = DiskPersistedGraph::with_version(dir.path(), "a").unwrap();
+        add_task(&graph, false);
+        add_task(&graph, true);
+        graph.write().unwrap();
+
+        let restored = DiskPersistedGraph::with_version(dir.path(), "a").unwrap();
+        assert_eq!(restored.persisted_tasks(), 2);
+        let clean = take(&mut restored.graph().tasks)
+            .into_iter()
+            .map(|task| task.data.unwrap().clean)
+            .collect::<Vec<_>>();
+        assert_eq!(clean, vec![true, false]);
+
+        let other_version = DiskPersistedGraph::with_version(dir.path(), "b").unwrap();
+        assert_eq!(other_version.persisted_tasks(), 0);
+    }
+
+    #[test]
+    fn stop_writes_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph = DiskPersistedGraph::with_version(dir.path(), "a").unwrap();
+        add_task(&graph, false);
+        graph.stop(&NoTasks).unwrap();
+        let restored = DiskPersistedGraph::with_version(dir.path(), "a").unwrap();
+        assert_eq!(restored.persisted_tasks(), 1);
+    }
+
+    #[test]
+    fn writes_only_changed_tasks() {
+        let graph = DiskPersistedGraph::with_store(RecordingStore::default(), "a").unwrap();
+        add_task(&graph, false);
+        add_task(&graph, false);
+        graph.write().unwrap();
+        add_task(&graph, false);
+        graph.write().unwrap();
+        graph.write().unwrap();
+        let batches = take(&mut *graph.store.batches.lock().unwrap());
+        let written = batches.iter().map(written_tasks).collect::<Vec<_>>();
+        assert_eq!(written, vec![2, 1, 0]);
+
+        // The tasks are restored from the written entries, but only with the
+        // same version.
+        let entries = batches
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let store = RecordingStore {
+            entries: entries.clone(),
+            ..Default::default()
+        };
+        let restored = DiskPersistedGraph::with_store(store, "a").unwrap();
+        assert_eq!(restored.persisted_tasks(), 3);
+
+        let store = RecordingStore {
+            entries,
+            ..Default::default()
+        };
+        let other_version = DiskPersistedGraph::with_store(store, "b").unwrap();
+        assert_eq!(other_version.persisted_tasks(), 0);
+        other_version.write().unwrap();
+        let batches = other_version.store.batches.lock().unwrap();
+        let removed = batches[0].iter().filter(|(_, value)| value.is_none());
+        assert_eq!(removed.count(), 4);
+    }
+
+    #[test]
+    fn doesnt_persist_generic_instances() {
+        let function: &'static GenericNativeFunction =
+            Box::leak(Box::new(GenericNativeFunction::new("generic")));
+        function.register("tests::generic");
+        let function_id = function.get_function_id::<(u32,)>(&["u32"], |name| {
+            NativeFunction::new(name, Box::new(|_| unreachable!()))
+        });
+
+        // The instantiation isn't registered in a fresh process before it's
+        // used, so the task can't be restored.
+        let graph = DiskPersistedGraph::with_store(RecordingStore::default(), "a").unwrap();
+        let task = TaskId::from(1);
+        let api = OneTask(PersistentTaskType::Native(function_id, Vec::new()));
+        let data = TaskData {
+            children: Vec::new(),
+            dependencies: Vec::new(),
+            cells: Vec::new(),
+            output: RawVc::TaskOutput(task),
+        };
+        let state = PersistTaskState {
+            externally_active: false,
+            session_dependent: false,
+        };
+        assert!(graph.persist(task, data, state, &api).unwrap().is_none());
+        assert_eq!(graph.persisted_tasks(), 0);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{Context, Result};
+
+/// Changes to a [KeyValueStore]. A `None` value removes the entry.
+pub type WriteBatch = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+/// The storage of a [DiskPersistedGraph](crate::DiskPersistedGraph). It can
+/// be implemented on top of any key-value store, e.g. SQLite, RocksDB or LMDB.
+///
+/// The graph reads all entries when it's created and only writes the entries
+/// of tasks which have changed when it's stopped.
+pub trait KeyValueStore: Send + Sync {
+    /// Returns all entries of the store.
+    fn read_all(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>>;
+
+    /// Applies all changes at once. When it fails, none of the changes must
+    /// have been applied, so the store stays consistent.
+    fn write_batch(&self, batch: WriteBatch) -> Result<()>;
+}
+
+const CACHE_FILE_NAME: &str = "tasks.bin";
+
+/// A [KeyValueStore] which keeps all entries in a single file. The file is
+/// replaced at once on every write.
+pub struct FileStore {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl FileStore {
+    /// Opens the store in the directory `path`, which is created when it
+    /// doesn't exist. A corrupted file is treated like an empty store.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        fs::create_dir_all(&path)
+            .with_context(|| format!("unable to create cache directory {}", path.display()))?;
+        let entries = read_entries(&path.join(CACHE_FILE_NAME)).unwrap_or_default();
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    fn write(&self, entries: &BTreeMap<Vec<u8>, Vec<u8>>) -> Result<()> {
+        let file_path = self.path.join(CACHE_FILE_NAME);
+        let temp_path = self.path.join(format!("{CACHE_FILE_NAME}.tmp"));
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        bincode::serialize_into(&mut writer, entries)?;
+        writer.flush()?;
+        drop(writer);
+        // Replacing the file at once avoids leaving a partially written cache.
+        fs::rename(&temp_path, file_path)?;
+        Ok(())
+    }
+}
+
+fn read_entries(path: &Path) -> Option<BTreeMap<Vec<u8>, Vec<u8>>> {
+    let reader = BufReader::new(File::open(path).ok()?);
+    bincode::deserialize_from(reader).ok()
+}
+
+impl KeyValueStore for FileStore {
+    fn read_all(&self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+    }
+
+    fn write_batch(&self, batch: WriteBatch) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut new_entries = entries.clone();
+        for (key, value) in batch {
+            match value {
+                Some(value) => new_entries.insert(key, value),
+                None => new_entries.remove(&key),
+            };
+        }
+        self.write(&new_entries)
+            .with_context(|| format!("unable to write cache to {}", self.path.display()))?;
+        *entries = new_entries;
+        Ok(())
+    }
+}
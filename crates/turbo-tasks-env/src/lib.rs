@@ -4,6 +4,7 @@ mod command_line;
 mod custom;
 mod dotenv;
 mod filter;
+mod record;
 
 use std::{env, sync::Mutex};
 
@@ -12,8 +13,11 @@ use indexmap::IndexMap;
 use turbo_tasks::primitives::OptionStringVc;
 
 pub use self::{
-    command_line::CommandLineProcessEnvVc, custom::CustomProcessEnvVc, dotenv::DotenvProcessEnvVc,
+    command_line::CommandLineProcessEnvVc,
+    custom::CustomProcessEnvVc,
+    dotenv::DotenvProcessEnvVc,
     filter::FilterProcessEnvVc,
+    record::{RecordingProcessEnvVc, ReplayProcessEnvVc},
 };
 
 #[turbo_tasks::value(transparent)]
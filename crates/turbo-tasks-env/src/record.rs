@@ -0,0 +1,72 @@
+use anyhow::Result;
+use turbo_tasks::{primitives::OptionStringVc, TransientInstance};
+use turbo_tasks_fs::record::{IoFixture, IoRecording};
+
+use crate::{EnvMapVc, ProcessEnv, ProcessEnvVc};
+
+/// Records the env variables read from the prior env into an [IoRecording],
+/// usually together with the files read through a
+/// [RecordingFileSystem](turbo_tasks_fs::record::RecordingFileSystem).
+#[turbo_tasks::value(serialization = "none")]
+pub struct RecordingProcessEnv {
+    prior: ProcessEnvVc,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    recording: TransientInstance<IoRecording>,
+}
+
+#[turbo_tasks::value_impl]
+impl RecordingProcessEnvVc {
+    #[turbo_tasks::function]
+    pub fn new(prior: ProcessEnvVc, recording: TransientInstance<IoRecording>) -> Self {
+        RecordingProcessEnv { prior, recording }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ProcessEnv for RecordingProcessEnv {
+    #[turbo_tasks::function]
+    async fn read_all(&self) -> Result<EnvMapVc> {
+        let env = self.prior.read_all();
+        self.recording.record_env(env.await?.clone_value());
+        Ok(env)
+    }
+
+    #[turbo_tasks::function]
+    async fn read(&self, name: &str) -> Result<OptionStringVc> {
+        let value = self.prior.read(name);
+        if let Some(value) = &*value.await? {
+            self.recording
+                .record_env([(name.to_string(), value.clone())]);
+        }
+        Ok(value)
+    }
+}
+
+/// Serves the env variables of an [IoFixture].
+#[turbo_tasks::value(serialization = "none")]
+pub struct ReplayProcessEnv {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    fixture: TransientInstance<IoFixture>,
+}
+
+#[turbo_tasks::value_impl]
+impl ReplayProcessEnvVc {
+    #[turbo_tasks::function]
+    pub fn new(fixture: TransientInstance<IoFixture>) -> Self {
+        ReplayProcessEnv { fixture }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ProcessEnv for ReplayProcessEnv {
+    #[turbo_tasks::function]
+    fn read_all(&self) -> EnvMapVc {
+        EnvMapVc::cell(
+            self.fixture
+                .env
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use reqwest::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{
+    get_invalidator,
+    primitives::{OptionStringVc, StringVc},
+};
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+
+use crate::{
+    request, FetchError, FetchErrorKind, FetchResultVc, HttpResponse, HttpResponseBody,
+    HttpResponseBodyVc,
+};
+
+/// Where and for how long [fetch_cached] caches responses.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub struct FetchCacheOptions {
+    /// The directory the responses are stored in. It's created when it
+    /// doesn't exist.
+    pub directory: String,
+    /// How long a cached response is used without revalidating it with the
+    /// server.
+    pub ttl: Duration,
+}
+
+/// The metadata of a cached response, stored next to the body.
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheEntry {
+    status: u16,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// When the response has been fetched or revalidated, in seconds since
+    /// the unix epoch.
+    fetched_at: u64,
+}
+
+struct CachePaths {
+    entry: PathBuf,
+    body: PathBuf,
+}
+
+impl CachePaths {
+    fn new(directory: &Path, url: &str, user_agent: Option<&str>) -> Self {
+        let key = encode_hex(hash_xxh3_hash64(
+            [url, user_agent.unwrap_or_default()].join("\n"),
+        ));
+        Self {
+            entry: directory.join(format!("{key}.json")),
+            body: directory.join(format!("{key}.body")),
+        }
+    }
+
+    async fn read(&self) -> Option<(CacheEntry, Vec<u8>)> {
+        let entry = tokio::fs::read(&self.entry).await.ok()?;
+        let entry = serde_json::from_slice(&entry).ok()?;
+        let body = tokio::fs::read(&self.body).await.ok()?;
+        Some((entry, body))
+    }
+
+    async fn write_entry(&self, entry: &CacheEntry) -> Result<()> {
+        tokio::fs::write(&self.entry, serde_json::to_vec(entry)?).await?;
+        Ok(())
+    }
+
+    async fn write(&self, entry: &CacheEntry, body: &[u8]) -> Result<()> {
+        if let Some(directory) = self.entry.parent() {
+            tokio::fs::create_dir_all(directory).await?;
+        }
+        tokio::fs::write(&self.body, body).await?;
+        self.write_entry(entry).await
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn response(status: u16, body: Vec<u8>) -> FetchResultVc {
+    FetchResultVc::cell(Ok(HttpResponse {
+        status,
+        body: HttpResponseBodyVc::cell(HttpResponseBody(body)),
+    }
+    .cell()))
+}
+
+/// Like [fetch](crate::fetch), but caches responses on disk, so they survive
+/// restarts.
+///
+/// A cached response is used without a request until it's older than the
+/// `ttl`. After that it's revalidated with its `ETag` and `Last-Modified`
+/// headers, and only downloaded again when it has changed. The task is
+/// re-executed when a non-zero `ttl` expires, which only invalidates dependent
+/// tasks when the response has changed. When the server can't be reached, a
+/// stale response is used.
+#[turbo_tasks::function]
+pub async fn fetch_cached(
+    url: StringVc,
+    user_agent: OptionStringVc,
+    options: FetchCacheOptionsVc,
+) -> Result<FetchResultVc> {
+    let url = &*url.await?;
+    let user_agent = user_agent.await?;
+    let options = &*options.await?;
+    let paths = CachePaths::new(Path::new(&options.directory), url, user_agent.as_deref());
+
+    // With a zero ttl, the response is only revalidated when the task is
+    // executed for another reason.
+    if !options.ttl.is_zero() {
+        let invalidator = get_invalidator();
+        let ttl = options.ttl;
+        tokio::spawn(async move {
+            tokio::time::sleep(ttl).await;
+            invalidator.invalidate();
+        });
+    }
+
+    let cached = paths.read().await;
+    if let Some((entry, body)) = &cached {
+        if now().saturating_sub(Duration::from_secs(entry.fetched_at)) < options.ttl {
+            return Ok(response(entry.status, body.clone()));
+        }
+    }
+
+    let mut builder = request(url, user_agent.as_deref());
+    if let Some((entry, _)) = &cached {
+        if let Some(etag) = &entry.etag {
+            builder = builder.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            builder = builder.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    match builder.send().await.and_then(|r| r.error_for_status()) {
+        Ok(fetched) if fetched.status() == StatusCode::NOT_MODIFIED && cached.is_some() => {
+            let (mut entry, body) = cached.unwrap();
+            entry.fetched_at = now().as_secs();
+            paths.write_entry(&entry).await?;
+            Ok(response(entry.status, body))
+        }
+        Ok(fetched) => {
+            let header = |name| {
+                fetched
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.to_string())
+            };
+            let entry = CacheEntry {
+                status: fetched.status().as_u16(),
+                etag: header(ETAG),
+                last_modified: header(LAST_MODIFIED),
+                fetched_at: now().as_secs(),
+            };
+            let body = fetched.bytes().await?.to_vec();
+            paths.write(&entry, &body).await?;
+            Ok(response(entry.status, body))
+        }
+        Err(err) => {
+            let error = FetchError::from_reqwest_error(&err, url);
+            match (&*error.kind.await?, cached) {
+                (FetchErrorKind::Connect | FetchErrorKind::Timeout, Some((entry, body))) => {
+                    Ok(response(entry.status, body))
+                }
+                _ => Ok(FetchResultVc::cell(Err(error.cell()))),
+            }
+        }
+    }
+}
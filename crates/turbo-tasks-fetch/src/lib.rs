@@ -1,10 +1,14 @@
 #![feature(min_specialization)]
 
+mod cache;
+
 use anyhow::Result;
 use turbo_tasks::primitives::{OptionStringVc, StringVc};
 use turbo_tasks_fs::FileSystemPathVc;
 use turbopack_core::issue::{Issue, IssueSeverityVc, IssueVc};
 
+pub use crate::cache::{fetch_cached, FetchCacheOptions, FetchCacheOptionsVc};
+
 pub fn register() {
     turbo_tasks::register();
     turbo_tasks_fs::register();
@@ -39,14 +43,11 @@ impl HttpResponseBodyVc {
 pub async fn fetch(url: StringVc, user_agent: OptionStringVc) -> Result<FetchResultVc> {
     let url = &*url.await?;
     let user_agent = &*user_agent.await?;
-    let client = reqwest::Client::new();
-
-    let mut builder = client.get(url);
-    if let Some(user_agent) = user_agent {
-        builder = builder.header("User-Agent", user_agent);
-    }
 
-    let response = builder.send().await.and_then(|r| r.error_for_status());
+    let response = request(url, user_agent.as_deref())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status());
     match response {
         Ok(response) => {
             let status = response.status().as_u16();
@@ -65,6 +66,14 @@ pub async fn fetch(url: StringVc, user_agent: OptionStringVc) -> Result<FetchRes
     }
 }
 
+fn request(url: &str, user_agent: Option<&str>) -> reqwest::RequestBuilder {
+    let mut builder = reqwest::Client::new().get(url);
+    if let Some(user_agent) = user_agent {
+        builder = builder.header("User-Agent", user_agent);
+    }
+    builder
+}
+
 #[derive(Debug)]
 #[turbo_tasks::value(shared)]
 pub enum FetchErrorKind {
@@ -1,7 +1,9 @@
 #![cfg(test)]
 
+use std::time::Duration;
+
 use turbo_tasks::primitives::{OptionStringVc, StringVc};
-use turbo_tasks_fetch::{fetch, register, FetchErrorKind};
+use turbo_tasks_fetch::{fetch, fetch_cached, register, FetchCacheOptions, FetchErrorKind};
 use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemPathVc, FileSystemVc};
 use turbo_tasks_testing::{register, run};
 use turbopack_core::issue::{Issue, IssueSeverity};
@@ -141,6 +143,64 @@ async fn errors_on_404() {
     }
 }
 
+#[tokio::test]
+async fn cached_fetch_revalidates() {
+    run! {
+        register();
+
+        let cache_dir = tempfile::tempdir()?;
+        let server = httpmock::MockServer::start();
+        let resource_mock = server.mock(|when, then| {
+            when.path("/foo.woff").matches(|request| {
+                !request.headers.iter().flatten().any(|(name, _)| name.eq_ignore_ascii_case("if-none-match"))
+            });
+            then.status(200)
+                .header("ETag", "\"v1\"")
+                .body("responsebody");
+        });
+        let revalidate_mock = server.mock(|when, then| {
+            when.path("/foo.woff").header("If-None-Match", "\"v1\"");
+            then.status(304);
+        });
+
+        let url = StringVc::cell(server.url("/foo.woff"));
+        let user_agent = OptionStringVc::cell(None);
+        let options = |ttl| FetchCacheOptions {
+            directory: cache_dir.path().to_string_lossy().to_string(),
+            ttl,
+        }
+        .cell();
+
+        // Nothing is cached yet, so the response is downloaded.
+        let result = &*fetch_cached(url, user_agent, options(Duration::ZERO)).await?;
+        let Ok(response) = result else {
+            panic!()
+        };
+        assert_eq!(*response.await?.body.to_string().await?, "responsebody");
+        resource_mock.assert_hits(1);
+
+        // The cached response is expired, so it's revalidated.
+        let result = &*fetch_cached(url, user_agent, options(Duration::ZERO)).await?;
+        let Ok(response) = result else {
+            panic!()
+        };
+        let response = response.await?;
+        assert_eq!(response.status, 200);
+        assert_eq!(*response.body.to_string().await?, "responsebody");
+        resource_mock.assert_hits(1);
+        revalidate_mock.assert_hits(1);
+
+        // The cached response is fresh, so no request is sent.
+        let result = &*fetch_cached(url, user_agent, options(Duration::from_secs(3600))).await?;
+        let Ok(response) = result else {
+            panic!()
+        };
+        assert_eq!(*response.await?.body.to_string().await?, "responsebody");
+        resource_mock.assert_hits(1);
+        revalidate_mock.assert_hits(1);
+    }
+}
+
 fn get_issue_context() -> FileSystemPathVc {
     std::convert::Into::<FileSystemVc>::into(DiskFileSystemVc::new(
         "root".to_owned(),
@@ -0,0 +1,481 @@
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read},
+};
+
+use anyhow::{bail, Context, Result};
+use auto_hash_map::AutoMap;
+use flate2::read::{DeflateDecoder, GzDecoder};
+use turbo_tasks::{primitives::StringVc, CompletionVc, ValueToString, ValueToStringVc};
+
+use crate::{
+    util::normalize_path, DirectoryContentVc, DirectoryEntry, File, FileContent, FileContentVc,
+    FileMeta, FileMetaVc, FileSystem, FileSystemPathVc, FileSystemVc, LinkContent, LinkContentVc,
+    LinkType,
+};
+
+/// A read-only [FileSystem] with the contents of a zip or (gzipped) tar
+/// archive, e.g. a package of a Yarn PnP zip install. The format is detected
+/// from the file extension of the archive.
+///
+/// The archive is read and unpacked into memory at once. When the archive
+/// changes, the whole file system is invalidated.
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new")]
+pub struct ArchiveFileSystem {
+    archive: FileSystemPathVc,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    entries: BTreeMap<String, ArchiveEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArchiveEntry {
+    File(Vec<u8>),
+    Directory,
+    Symlink(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    fn from_path(path: &str) -> Option<Self> {
+        if path.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if path.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ArchiveFileSystemVc {
+    /// Creates a [FileSystem] with the contents of the `archive`, which must
+    /// be a `.zip`, `.tar`, `.tar.gz` or `.tgz` file.
+    #[turbo_tasks::function]
+    pub async fn new(archive: FileSystemPathVc) -> Result<Self> {
+        let path = archive.await?;
+        let Some(format) = ArchiveFormat::from_path(&path.path) else {
+            bail!("{} is not a zip or tar archive", path.path);
+        };
+        let FileContent::Content(file) = &*archive.read().await? else {
+            bail!("archive {} not found", path.path);
+        };
+        let bytes = file.content().to_bytes()?;
+        let entries = read_archive(format, &bytes)
+            .with_context(|| format!("unable to read archive {}", path.path))?;
+        Ok(Self::cell(ArchiveFileSystem { archive, entries }))
+    }
+}
+
+impl ArchiveFileSystem {
+    /// Returns the entry at `path`. The root is always a directory.
+    fn entry(&self, path: &str) -> Option<&ArchiveEntry> {
+        if path.is_empty() {
+            return Some(&ArchiveEntry::Directory);
+        }
+        self.entries.get(path)
+    }
+
+    fn link_type(&self, path: &str, target: &str) -> Option<LinkType> {
+        if target.starts_with('/') {
+            return None;
+        }
+        let parent = path.rsplit_once('/').map_or("", |(parent, _)| parent);
+        let target_path = normalize_path(&format!("{parent}/{target}"))?;
+        Some(match self.entry(&target_path) {
+            Some(ArchiveEntry::Directory) => LinkType::DIRECTORY,
+            _ => LinkType::empty(),
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for ArchiveFileSystem {
+    #[turbo_tasks::function]
+    async fn read(&self, fs_path: FileSystemPathVc) -> Result<FileContentVc> {
+        let path = fs_path.await?;
+        Ok(match self.entry(&path.path) {
+            Some(ArchiveEntry::File(content)) => {
+                FileContent::Content(File::from(content.clone())).cell()
+            }
+            _ => FileContent::NotFound.cell(),
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn read_link(&self, fs_path: FileSystemPathVc) -> Result<LinkContentVc> {
+        let path = fs_path.await?;
+        let Some(ArchiveEntry::Symlink(target)) = self.entry(&path.path) else {
+            return Ok(LinkContent::NotFound.cell());
+        };
+        Ok(match self.link_type(&path.path, target) {
+            Some(link_type) => LinkContent::Link {
+                target: target.clone(),
+                link_type,
+            }
+            .cell(),
+            None => LinkContent::Invalid.cell(),
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn read_dir(&self, fs_path: FileSystemPathVc) -> Result<DirectoryContentVc> {
+        let path = fs_path.await?;
+        let Some(ArchiveEntry::Directory) = self.entry(&path.path) else {
+            return Ok(DirectoryContentVc::not_found());
+        };
+        let prefix = if path.path.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", path.path)
+        };
+        let mut entries = AutoMap::new();
+        for (key, entry) in self
+            .entries
+            .range(prefix.clone()..)
+            .take_while(|(key, _)| key.starts_with(&prefix))
+        {
+            let name = &key[prefix.len()..];
+            if name.contains('/') {
+                continue;
+            }
+            let entry_path = fs_path.join(name);
+            let entry = match entry {
+                ArchiveEntry::File(_) => DirectoryEntry::File(entry_path),
+                ArchiveEntry::Directory => DirectoryEntry::Directory(entry_path),
+                ArchiveEntry::Symlink(_) => DirectoryEntry::Symlink(entry_path),
+            };
+            entries.insert(name.to_string(), entry);
+        }
+        Ok(DirectoryContentVc::new(entries))
+    }
+
+    #[turbo_tasks::function]
+    fn track(&self, _fs_path: FileSystemPathVc) -> CompletionVc {
+        // Reading `self` already depends on the archive
+        CompletionVc::new()
+    }
+
+    #[turbo_tasks::function]
+    async fn write(
+        &self,
+        fs_path: FileSystemPathVc,
+        _content: FileContentVc,
+    ) -> Result<CompletionVc> {
+        bail!(
+            "Writing {} is not possible on the archive file system",
+            fs_path.to_string().await?
+        )
+    }
+
+    #[turbo_tasks::function]
+    async fn write_link(
+        &self,
+        fs_path: FileSystemPathVc,
+        _target: LinkContentVc,
+    ) -> Result<CompletionVc> {
+        bail!(
+            "Writing {} is not possible on the archive file system",
+            fs_path.to_string().await?
+        )
+    }
+
+    #[turbo_tasks::function]
+    async fn metadata(&self, fs_path: FileSystemPathVc) -> Result<FileMetaVc> {
+        let path = fs_path.await?;
+        if self.entry(&path.path).is_none() {
+            bail!("{} not found in the archive", path.path);
+        }
+        Ok(FileMeta::default().cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ArchiveFileSystem {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<StringVc> {
+        Ok(StringVc::cell(format!(
+            "archive {}",
+            self.archive.to_string().await?
+        )))
+    }
+}
+
+fn read_archive(format: ArchiveFormat, bytes: &[u8]) -> Result<BTreeMap<String, ArchiveEntry>> {
+    let mut entries = BTreeMap::new();
+    match format {
+        ArchiveFormat::Zip => read_zip(bytes, &mut entries)?,
+        ArchiveFormat::Tar => read_tar(bytes, &mut entries)?,
+        ArchiveFormat::TarGz => read_tar(GzDecoder::new(bytes), &mut entries)?,
+    }
+    Ok(entries)
+}
+
+/// Adds an entry and all its parent directories, which archives don't
+/// necessarily contain.
+fn insert_entry(entries: &mut BTreeMap<String, ArchiveEntry>, path: &str, entry: ArchiveEntry) {
+    let Some(path) = normalize_path(path.trim_start_matches('/')) else {
+        return;
+    };
+    if path.is_empty() {
+        return;
+    }
+    let mut parent = path.as_str();
+    while let Some((dir, _)) = parent.rsplit_once('/') {
+        entries
+            .entry(dir.to_string())
+            .or_insert(ArchiveEntry::Directory);
+        parent = dir;
+    }
+    entries.insert(path, entry);
+}
+
+fn read_tar(reader: impl Read, entries: &mut BTreeMap<String, ArchiveEntry>) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        let entry = match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Continuous => {
+                let mut content = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut content)?;
+                ArchiveEntry::File(content)
+            }
+            tar::EntryType::Directory => ArchiveEntry::Directory,
+            tar::EntryType::Symlink => {
+                let Some(target) = entry.link_name()? else {
+                    continue;
+                };
+                ArchiveEntry::Symlink(target.to_string_lossy().to_string())
+            }
+            // Hard links, devices, extended headers, ...
+            _ => continue,
+        };
+        insert_entry(entries, &path, entry);
+    }
+    Ok(())
+}
+
+const ZIP_END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+const ZIP_CENTRAL_DIRECTORY_HEADER: u32 = 0x02014b50;
+const ZIP_LOCAL_FILE_HEADER: u32 = 0x04034b50;
+const ZIP_STORED: u16 = 0;
+const ZIP_DEFLATED: u16 = 8;
+const UNIX_FILE_TYPE_MASK: u32 = 0o170000;
+const UNIX_SYMLINK: u32 = 0o120000;
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16> {
+    let slice = bytes
+        .get(offset..offset + 2)
+        .context("unexpected end of zip archive")?;
+    Ok(u16::from_le_bytes([slice[0], slice[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32> {
+    let slice = bytes
+        .get(offset..offset + 4)
+        .context("unexpected end of zip archive")?;
+    Ok(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+/// Reads a zip archive via its central directory. Zip64 archives (larger than
+/// 4 GiB or with more than 65535 entries) are not supported.
+fn read_zip(bytes: &[u8], entries: &mut BTreeMap<String, ArchiveEntry>) -> Result<()> {
+    // The end of central directory record is followed by a comment of up to
+    // 64 KiB.
+    let search_start = bytes.len().saturating_sub(22 + u16::MAX as usize);
+    let end = (search_start..bytes.len().saturating_sub(21))
+        .rev()
+        .find(|&offset| read_u32(bytes, offset).ok() == Some(ZIP_END_OF_CENTRAL_DIRECTORY))
+        .context("not a zip archive")?;
+    let count = read_u16(bytes, end + 10)?;
+    let mut offset = read_u32(bytes, end + 16)? as usize;
+    if count == u16::MAX || offset == u32::MAX as usize {
+        bail!("zip64 archives are not supported");
+    }
+    for _ in 0..count {
+        if read_u32(bytes, offset)? != ZIP_CENTRAL_DIRECTORY_HEADER {
+            bail!("invalid central directory header at {}", offset);
+        }
+        let compression = read_u16(bytes, offset + 10)?;
+        let compressed_size = read_u32(bytes, offset + 20)? as usize;
+        let uncompressed_size = read_u32(bytes, offset + 24)? as usize;
+        let name_len = read_u16(bytes, offset + 28)? as usize;
+        let extra_len = read_u16(bytes, offset + 30)? as usize;
+        let comment_len = read_u16(bytes, offset + 32)? as usize;
+        let external_attributes = read_u32(bytes, offset + 38)?;
+        let local_offset = read_u32(bytes, offset + 42)? as usize;
+        let name = bytes
+            .get(offset + 46..offset + 46 + name_len)
+            .context("unexpected end of zip archive")?;
+        let name = String::from_utf8_lossy(name).to_string();
+        offset += 46 + name_len + extra_len + comment_len;
+
+        if name.ends_with('/') {
+            insert_entry(entries, &name, ArchiveEntry::Directory);
+            continue;
+        }
+
+        if read_u32(bytes, local_offset)? != ZIP_LOCAL_FILE_HEADER {
+            bail!("invalid local file header of {}", name);
+        }
+        let data_start = local_offset
+            + 30
+            + read_u16(bytes, local_offset + 26)? as usize
+            + read_u16(bytes, local_offset + 28)? as usize;
+        let data = bytes
+            .get(data_start..data_start + compressed_size)
+            .with_context(|| format!("unexpected end of zip archive in {}", name))?;
+        let content = match compression {
+            ZIP_STORED => data.to_vec(),
+            ZIP_DEFLATED => {
+                let mut content = Vec::with_capacity(uncompressed_size);
+                DeflateDecoder::new(Cursor::new(data))
+                    .read_to_end(&mut content)
+                    .with_context(|| format!("unable to inflate {}", name))?;
+                content
+            }
+            _ => bail!("unsupported compression method {} of {}", compression, name),
+        };
+        let entry = if (external_attributes >> 16) & UNIX_FILE_TYPE_MASK == UNIX_SYMLINK {
+            ArchiveEntry::Symlink(String::from_utf8_lossy(&content).to_string())
+        } else {
+            ArchiveEntry::File(content)
+        };
+        insert_entry(entries, &name, entry);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, io::Write};
+
+    use flate2::{write::DeflateEncoder, Compression};
+
+    use super::{read_archive, ArchiveEntry, ArchiveFormat};
+
+    fn file(content: &str) -> ArchiveEntry {
+        ArchiveEntry::File(content.as_bytes().to_vec())
+    }
+
+    /// Writes a zip archive with deflated files.
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        let mut central_directory = Vec::new();
+        for (name, content) in files {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(content.as_bytes()).unwrap();
+            let data = encoder.finish().unwrap();
+            let local_offset = archive.len() as u32;
+            let header = |signature: u32, out: &mut Vec<u8>| {
+                out.extend(signature.to_le_bytes());
+                if signature == 0x02014b50 {
+                    // version made by
+                    out.extend(0u16.to_le_bytes());
+                }
+                // version needed, flags
+                out.extend([0u8; 4]);
+                out.extend(8u16.to_le_bytes());
+                // time, date, crc
+                out.extend([0u8; 8]);
+                out.extend((data.len() as u32).to_le_bytes());
+                out.extend((content.len() as u32).to_le_bytes());
+                out.extend((name.len() as u16).to_le_bytes());
+                // extra length
+                out.extend(0u16.to_le_bytes());
+            };
+            header(0x04034b50, &mut archive);
+            archive.extend(name.as_bytes());
+            archive.extend(&data);
+            header(0x02014b50, &mut central_directory);
+            // comment length, disk, internal and external attributes
+            central_directory.extend([0u8; 10]);
+            central_directory.extend(local_offset.to_le_bytes());
+            central_directory.extend(name.as_bytes());
+        }
+        let central_directory_offset = archive.len() as u32;
+        archive.extend(&central_directory);
+        archive.extend(0x06054b50u32.to_le_bytes());
+        archive.extend([0u8; 4]);
+        archive.extend((files.len() as u16).to_le_bytes());
+        archive.extend((files.len() as u16).to_le_bytes());
+        archive.extend((central_directory.len() as u32).to_le_bytes());
+        archive.extend(central_directory_offset.to_le_bytes());
+        archive.extend(0u16.to_le_bytes());
+        archive
+    }
+
+    #[test]
+    fn reads_zip_archives() {
+        let archive = zip(&[
+            ("node_modules/react/package.json", "{}"),
+            ("node_modules/react/index.js", "module.exports = {}"),
+        ]);
+        let entries = read_archive(ArchiveFormat::Zip, &archive).unwrap();
+        assert_eq!(
+            entries,
+            BTreeMap::from([
+                ("node_modules".to_string(), ArchiveEntry::Directory),
+                ("node_modules/react".to_string(), ArchiveEntry::Directory),
+                (
+                    "node_modules/react/index.js".to_string(),
+                    file("module.exports = {}")
+                ),
+                ("node_modules/react/package.json".to_string(), file("{}")),
+            ])
+        );
+    }
+
+    #[test]
+    fn reads_tar_archives() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(2);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "package/package.json", &b"{}"[..])
+            .unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "package/index.json", "package.json")
+            .unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let entries = read_archive(ArchiveFormat::Tar, &archive).unwrap();
+        assert_eq!(
+            entries,
+            BTreeMap::from([
+                ("package".to_string(), ArchiveEntry::Directory),
+                (
+                    "package/index.json".to_string(),
+                    ArchiveEntry::Symlink("package.json".to_string())
+                ),
+                ("package/package.json".to_string(), file("{}")),
+            ])
+        );
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            ArchiveFormat::from_path("cache/react-npm-18.2.0.zip"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path("react-18.2.0.tgz"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(ArchiveFormat::from_path("react/index.js"), None);
+    }
+}
@@ -31,7 +31,7 @@ impl EmbeddedFileSystemVc {
 impl FileSystem for EmbeddedFileSystem {
     #[turbo_tasks::function]
     async fn read(&self, path: FileSystemPathVc) -> Result<FileContentVc> {
-        let file = match self.dir.get_file(&path.await?.path) {
+        let file = match self.dir.get_file(path.await?.path.as_str()) {
             Some(file) => file,
             None => return Ok(FileContent::NotFound.cell()),
         };
@@ -47,7 +47,7 @@ impl FileSystem for EmbeddedFileSystem {
     #[turbo_tasks::function]
     async fn read_dir(&self, path: FileSystemPathVc) -> Result<DirectoryContentVc> {
         let path_str = &path.await?.path;
-        let dir = match (path_str.as_str(), self.dir.get_dir(path_str)) {
+        let dir = match (path_str.as_str(), self.dir.get_dir(path_str.as_str())) {
             ("", _) => *self.dir,
             (_, Some(dir)) => dir,
             (_, None) => return Ok(DirectoryContent::NotFound.cell()),
@@ -90,7 +90,7 @@ impl FileSystem for EmbeddedFileSystem {
 
     #[turbo_tasks::function]
     async fn metadata(&self, path: FileSystemPathVc) -> Result<FileMetaVc> {
-        if self.dir.get_entry(&path.await?.path).is_none() {
+        if self.dir.get_entry(path.await?.path.as_str()).is_none() {
             bail!("path not found, can't read metadata");
         }
 
@@ -105,3 +105,59 @@ impl ValueToString for EmbeddedFileSystem {
         StringVc::cell(self.name.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use include_dir::{include_dir, Dir};
+    use turbo_tasks::TransientInstance;
+    use turbo_tasks_memory::MemoryBackend;
+    use turbo_tasks_testing::TestHarness;
+
+    use crate::{embed::directory_from_include_dir, DirectoryContent, FileContent, FileSystem};
+
+    static DIR: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures/embed");
+
+    #[tokio::test]
+    async fn serves_embedded_files() -> Result<()> {
+        crate::register();
+        let harness = TestHarness::new(MemoryBackend::default());
+        harness
+            .run(async {
+                let fs = directory_from_include_dir("embedded", TransientInstance::new(&DIR));
+                let root = fs.root();
+
+                let content = root.join("hello.txt").read().await?;
+                let FileContent::Content(file) = &*content else {
+                    panic!("hello.txt should be embedded");
+                };
+                assert_eq!(file.content().to_str()?, "Hello, embedded world!\n");
+
+                let content = root.join("nested/data.json").read().await?;
+                let FileContent::Content(file) = &*content else {
+                    panic!("nested/data.json should be embedded");
+                };
+                assert_eq!(file.content().to_str()?, "{ \"nested\": true }\n");
+
+                assert!(matches!(
+                    &*root.join("missing.txt").read().await?,
+                    FileContent::NotFound
+                ));
+
+                let dir_content = root.read_dir().await?;
+                let DirectoryContent::Entries(entries) = &*dir_content else {
+                    panic!("the root should be a directory");
+                };
+                let mut names = entries
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>();
+                names.sort_unstable();
+                assert_eq!(names, ["hello.txt", "nested"]);
+
+                assert!(root.join("missing.txt").metadata().await.is_err());
+                Ok(())
+            })
+            .await
+    }
+}
@@ -37,6 +37,9 @@ enum GlobPart {
 // - {a/**,*}/file = Alternatives([File(a), PathSeparator, AnyDirectories],
 //   [AnyFile]), PathSeparator, File(file)
 
+// - !**/*.test.js = negated: AnyDirectories, PathSeparator, AnyFile,
+//   File(.test.js)
+
 // Note: a/**/b does match a/b, so we need some special logic about path
 // separators
 
@@ -44,13 +47,57 @@ enum GlobPart {
 #[derive(Debug, Clone)]
 pub struct Glob {
     expression: Vec<GlobPart>,
+    /// `!` at the start: Matches every path which the expression doesn't
+    /// match
+    negated: bool,
 }
 
 impl Glob {
     pub fn execute(&self, path: &str) -> bool {
         let match_partial = path.ends_with('/');
-        self.iter_matches(path, true, match_partial)
-            .any(|result| matches!(result, ("", _)))
+        if self.negated && match_partial {
+            // A directory might contain paths which are not matched by the
+            // expression
+            return true;
+        }
+        let matches = self
+            .iter_matches(path, true, match_partial)
+            .any(|result| matches!(result, ("", _)));
+        matches != self.negated
+    }
+
+    /// The leading directories of the glob which are literal names and can't
+    /// be matched themselves, e.g. `src/pages` for `src/pages/**/*.js`. A
+    /// traversal can start there, as all other entries on the way would be
+    /// filtered out anyway.
+    pub fn literal_directory_prefix(&self) -> String {
+        if self.negated {
+            return String::new();
+        }
+        let mut literal = String::new();
+        for part in &self.expression {
+            match part {
+                GlobPart::File(name) if !name.contains('/') => literal.push_str(name),
+                GlobPart::PathSeparator => literal.push('/'),
+                _ => break,
+            }
+        }
+        // The last segment is a file name or the start of a pattern
+        let Some((dirs, _)) = literal.rsplit_once('/') else {
+            return String::new();
+        };
+        let mut prefix_len = 0;
+        for (index, c) in dirs.char_indices().chain([(dirs.len(), '/')]) {
+            if c != '/' {
+                continue;
+            }
+            let dir = &dirs[..index];
+            if dir.is_empty() || self.execute(dir) {
+                break;
+            }
+            prefix_len = index;
+        }
+        dirs[..prefix_len].to_string()
     }
 
     fn iter_matches<'a>(
@@ -70,7 +117,10 @@ impl Glob {
     }
 
     pub fn parse(input: &str) -> Result<Glob> {
-        let mut current = input;
+        // Like in most glob libraries, every leading `!` inverts the glob
+        let expression_start = input.len() - input.trim_start_matches('!').len();
+        let negated = expression_start % 2 == 1;
+        let mut current = &input[expression_start..];
         let mut expression = Vec::new();
 
         while !current.is_empty() {
@@ -80,7 +130,10 @@ impl Glob {
             current = remainder;
         }
 
-        Ok(Glob { expression })
+        Ok(Glob {
+            expression,
+            negated,
+        })
     }
 }
 
@@ -188,12 +241,14 @@ impl GlobPart {
                         Some(',') => {
                             alternatives.push(Glob {
                                 expression: take(&mut expression),
+                                negated: false,
                             });
                             current = &current[1..];
                         }
                         Some('}') => {
                             alternatives.push(Glob {
                                 expression: take(&mut expression),
+                                negated: false,
                             });
                             current = &current[1..];
                             break;
@@ -218,7 +273,10 @@ impl GlobPart {
                     if is_escaped {
                         is_escaped = false;
                     } else if c == '\\' {
+                        // The escaped char is part of the literal, but not the backslash
                         is_escaped = true;
+                        index += 1;
+                        continue;
                     } else if c == '/'
                         || c == '*'
                         || c == '?'
@@ -229,7 +287,7 @@ impl GlobPart {
                         break;
                     }
                     literal.push(c);
-                    index += 1;
+                    index += c.len_utf8();
                 }
                 Ok((GlobPart::File(literal), &input[index..]))
             }
@@ -408,6 +466,16 @@ mod tests {
     #[case::alternatives_nested2("{a,b/c,d/e/{f,g/h}}", "b/c")]
     #[case::alternatives_nested3("{a,b/c,d/e/{f,g/h}}", "d/e/f")]
     #[case::alternatives_nested4("{a,b/c,d/e/{f,g/h}}", "d/e/g/h")]
+    #[case::alternatives_nested_braces("{a,{b,c}}.js", "c.js")]
+    #[case::alternatives_empty("file{,.min}.js", "file.js")]
+    #[case::alternatives_empty("file{,.min}.js", "file.min.js")]
+    #[case::alternatives_globstar("{src,lib}/**/*.{js,ts}", "lib/a/b.ts")]
+    #[case::negated("!*.js", "file.ts")]
+    #[case::negated_globstar("!**/*.test.js", "dir/file.js")]
+    #[case::negated_alternatives("!{dist,build}/**", "src/index.js")]
+    #[case::negated_partial("!node_modules/**", "src/")]
+    #[case::double_negated("!!*.js", "file.js")]
+    #[case::escaped_negation("\\!file.js", "!file.js")]
     fn glob_match(#[case] glob: &str, #[case] path: &str) {
         let glob = Glob::parse(glob).unwrap();
 
@@ -418,6 +486,11 @@ mod tests {
 
     #[rstest]
     #[case::early_end("*.raw", "hello.raw.js")]
+    #[case::alternatives_nested_braces("{a,{b,c}}.js", "d.js")]
+    #[case::negated("!*.js", "file.js")]
+    #[case::negated_globstar("!**/*.test.js", "dir/sub/file.test.js")]
+    #[case::negated_alternatives("!{dist,build}/**", "build/index.js")]
+    #[case::double_negated("!!*.js", "file.ts")]
     fn glob_not_matching(#[case] glob: &str, #[case] path: &str) {
         let glob = Glob::parse(glob).unwrap();
 
@@ -425,4 +498,19 @@ mod tests {
 
         assert!(!glob.execute(path));
     }
+
+    #[rstest]
+    #[case::file("index.js", "")]
+    #[case::dir_and_file("src/index.js", "src")]
+    #[case::nested_globstar("src/pages/**/*.js", "src/pages")]
+    #[case::globstar_in_dir("src/pages/**", "src/pages")]
+    #[case::partial_name("src/page*/index.js", "src")]
+    #[case::globstar("**/*.js", "")]
+    #[case::alternatives("{src,lib}/*.js", "")]
+    #[case::negated("!src/**", "")]
+    fn literal_directory_prefix(#[case] glob: &str, #[case] prefix: &str) {
+        let glob = Glob::parse(glob).unwrap();
+
+        assert_eq!(glob.literal_directory_prefix(), prefix);
+    }
 }
@@ -0,0 +1,159 @@
+use std::{io::ErrorKind, time::UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Result};
+use bytes::Bytes;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use tokio::fs;
+use tokio_util::io::ReaderStream;
+use turbo_tasks::ValueToString;
+
+use crate::{retry::retry_future, DiskFileSystemVc, File, FileContent, FileSystemPathVc};
+
+/// The content of a file which might be too large to be loaded into memory,
+/// e.g. videos, wasm modules or databases which are imported as static
+/// assets.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub enum LargeFileContent {
+    /// The file is not larger than the threshold and loaded into memory.
+    Content(File),
+    /// The file is larger than the threshold. It has to be read with
+    /// [read_stream] when it's needed.
+    Reference(FileReference),
+    NotFound,
+}
+
+/// A file which hasn't been loaded into memory. The size and modification
+/// time change with the file, so tasks which depend on it are invalidated.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub struct FileReference {
+    pub path: FileSystemPathVc,
+    pub size: u64,
+    /// Nanoseconds since the unix epoch, if the file system supports it.
+    pub modified: Option<u64>,
+}
+
+/// Reads the file at `path`, but only loads it into memory when it's not
+/// larger than `max_size` bytes. Larger files are returned as
+/// [LargeFileContent::Reference]. Only files of a
+/// [DiskFileSystem](crate::DiskFileSystem) are ever referenced, other file
+/// systems already hold their files in memory.
+#[turbo_tasks::function]
+pub async fn read_large(path: FileSystemPathVc, max_size: u64) -> Result<LargeFileContentVc> {
+    let Some(disk_fs) = DiskFileSystemVc::resolve_from(path.fs()).await? else {
+        return Ok(match &*path.read().await? {
+            FileContent::Content(file) => LargeFileContent::Content(file.clone()),
+            FileContent::NotFound => LargeFileContent::NotFound,
+        }
+        .cell());
+    };
+    let fs = disk_fs.await?;
+    let full_path = fs.to_sys_path(path).await?;
+    fs.register_invalidator(disk_fs, &full_path).await?;
+
+    let lock = fs.lock_path(&full_path).await;
+    let metadata = match retry_future(|| fs::metadata(full_path.clone())).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        Ok(_) => return Ok(LargeFileContent::NotFound.cell()),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Ok(LargeFileContent::NotFound.cell());
+        }
+        Err(e) => bail!(anyhow!(e).context(format!("reading file {}", full_path.display()))),
+    };
+    if metadata.len() <= max_size {
+        drop(lock);
+        return Ok(match &*path.read().await? {
+            FileContent::Content(file) => LargeFileContent::Content(file.clone()),
+            FileContent::NotFound => LargeFileContent::NotFound,
+        }
+        .cell());
+    }
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64);
+    Ok(LargeFileContent::Reference(FileReference {
+        path,
+        size: metadata.len(),
+        modified,
+    })
+    .cell())
+}
+
+/// Reads the file at `path` in chunks, without loading it into memory at
+/// once. This is meant for serving or copying a [FileReference] and the read
+/// is not tracked, so it's usually called outside of a task.
+pub async fn read_stream(path: FileSystemPathVc) -> Result<BoxStream<'static, Result<Bytes>>> {
+    let Some(disk_fs) = DiskFileSystemVc::resolve_from(path.fs()).await? else {
+        let content = path.read().await?;
+        let FileContent::Content(file) = &*content else {
+            bail!("{} not found", path.to_string().await?);
+        };
+        let bytes = Bytes::from(file.content().to_bytes()?.into_owned());
+        return Ok(stream::once(async move { Ok(bytes) }).boxed());
+    };
+    let full_path = disk_fs.await?.to_sys_path(path).await?;
+    let file = match fs::File::open(&full_path).await {
+        Ok(file) => file,
+        Err(e) => bail!(anyhow!(e).context(format!("reading file {}", full_path.display()))),
+    };
+    Ok(ReaderStream::new(file)
+        .map(|chunk| chunk.map_err(anyhow::Error::from))
+        .boxed())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use futures::TryStreamExt;
+    use turbo_tasks_memory::MemoryBackend;
+    use turbo_tasks_testing::TestHarness;
+
+    use super::{read_large, read_stream, LargeFileContent};
+    use crate::{DiskFileSystemVc, FileSystem};
+
+    #[tokio::test]
+    async fn references_large_files() -> Result<()> {
+        crate::register();
+        let dir = tempfile::TempDir::new()?;
+        std::fs::write(dir.path().join("small.txt"), "small")?;
+        std::fs::write(dir.path().join("large.bin"), vec![7u8; 100_000])?;
+        let root = dir.path().to_string_lossy().to_string();
+
+        let harness = TestHarness::new(MemoryBackend::default());
+        harness
+            .run(async move {
+                let fs = DiskFileSystemVc::new("test".to_string(), root);
+                let root = fs.root();
+
+                let small = read_large(root.join("small.txt"), 1024).await?;
+                assert!(matches!(&*small, LargeFileContent::Content(_)));
+
+                let large_path = root.join("large.bin");
+                let large = read_large(large_path, 1024).await?;
+                let LargeFileContent::Reference(reference) = &*large else {
+                    panic!("large files should only be referenced");
+                };
+                assert_eq!(reference.size, 100_000);
+
+                let chunks = read_stream(reference.path)
+                    .await?
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                assert_eq!(
+                    chunks.iter().map(|chunk| chunk.len()).sum::<usize>(),
+                    100_000
+                );
+
+                let missing = read_large(root.join("missing.bin"), 1024).await?;
+                assert!(matches!(&*missing, LargeFileContent::NotFound));
+                Ok(())
+            })
+            .await
+    }
+}
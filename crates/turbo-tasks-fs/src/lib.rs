@@ -5,19 +5,29 @@
 #![feature(io_error_more)]
 #![feature(round_char_boundary)]
 
+pub mod archive;
 pub mod attach;
 pub mod embed;
 pub mod glob;
 mod invalidation;
 mod invalidator_map;
 pub mod json;
+pub mod large_file;
+pub mod lock;
 mod mutex_map;
+pub mod overlay;
 mod read_glob;
+pub mod readonly;
+pub mod record;
 mod retry;
 pub mod rope;
 pub mod source_context;
+pub mod union;
 pub mod util;
 pub(crate) mod virtual_fs;
+mod watch_events;
+mod watch_options;
+pub mod watchman;
 
 use std::{
     borrow::Cow,
@@ -29,41 +39,47 @@ use std::{
     mem::take,
     path::{Path, PathBuf, MAIN_SEPARATOR},
     sync::{
-        mpsc::{channel, RecvError, TryRecvError},
-        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{channel, RecvError, RecvTimeoutError, Sender, TryRecvError},
+        Arc, Mutex, OnceLock,
     },
-    time::Duration,
+    time::{Duration, Instant, UNIX_EPOCH},
 };
 
 use anyhow::{anyhow, bail, Context, Result};
 use auto_hash_map::AutoMap;
 use bitflags::bitflags;
+use dashmap::DashMap;
 use dunce::simplified;
-use glob::GlobVc;
+use glob::{Glob, GlobVc};
 use invalidator_map::InvalidatorMap;
 use jsonc_parser::{parse_to_serde_value, ParseOptions};
+use lock::{FileLock, FileLockOptions};
 use mime::Mime;
-use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
-use read_glob::read_glob;
+use notify::{watcher, DebouncedEvent, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use read_glob::{read_glob, read_glob_respecting_ignore_files};
 pub use read_glob::{ReadGlobResult, ReadGlobResultVc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::{
     fs,
     io::{AsyncBufReadExt, AsyncReadExt, BufReader},
-    sync::{RwLock, RwLockReadGuard},
+    sync::{RwLock, RwLockReadGuard, Semaphore},
 };
 use tracing::{instrument, Level};
 use turbo_tasks::{
     mark_stateful,
-    primitives::{BoolVc, OptionStringVc, StringReadRef, StringVc},
+    primitives::{BoolVc, OptionStringVc, StringReadRef, StringVc, U64Vc},
     spawn_thread,
     trace::TraceRawVcs,
-    CompletionVc, InvalidationReason, Invalidator, ValueToString, ValueToStringVc,
+    CompletionVc, InternedString, InvalidationBatch, InvalidationReason, Invalidator,
+    ValueToString, ValueToStringVc,
 };
-use turbo_tasks_hash::hash_xxh3_hash64;
+use turbo_tasks_hash::{hash_xxh3_hash64, Xxh3Hash64Hasher};
 use util::{extract_disk_access, join_path, normalize_path, sys_to_unix, unix_to_sys};
 pub use virtual_fs::VirtualFileSystemVc;
+use watch_events::{BatchedInvalidations, CoalescedEvents};
+pub use watch_options::{WatchBackend, WatchLimitReached, WatchOptions, DEFAULT_POLL_INTERVAL};
 
 use self::{invalidation::WatchStart, json::UnparseableJson, mutex_map::MutexMap};
 use crate::{
@@ -71,6 +87,7 @@ use crate::{
     invalidation::WatchChange,
     retry::{retry_blocking, retry_future},
     rope::{Rope, RopeReadRef, RopeReader},
+    watchman::WatchmanWatcher,
 };
 
 #[turbo_tasks::value_trait]
@@ -91,14 +108,110 @@ pub trait FileSystem: ValueToString {
 #[derive(Default)]
 struct DiskWatcher {
     watcher: Mutex<Option<RecommendedWatcher>>,
-    /// Keeps track of which directories are currently watched. This is only
-    /// used on a OS that doesn't support recursive watching.
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    /// Used instead of `watcher` when watching with Watchman.
+    watchman: Mutex<Option<WatchmanWatcher>>,
+    /// Used instead of `watcher` when polling.
+    poll_watcher: Mutex<Option<PollWatcher>>,
+    /// Only the directories which have been read are watched, instead of the
+    /// whole root recursively, see [WatchOptions::lazy].
+    lazy: AtomicBool,
+    /// Keeps track of which directories have been read and are watched in
+    /// `lazy` mode.
     watching: dashmap::DashSet<PathBuf>,
+    /// Keeps track of which opaque directories are watched recursively in
+    /// `lazy` mode.
+    watching_recursive: dashmap::DashSet<PathBuf>,
+    /// Polls the directories which `watcher` couldn't watch, because the
+    /// limit of watches of the OS was reached.
+    poll_fallback: Mutex<Option<PollWatcher>>,
+    /// The channel of `watcher`, which is shared with `poll_fallback`.
+    fallback_tx: Mutex<Option<Sender<DebouncedEvent>>>,
+    watch_limit_reached: AtomicBool,
+    /// Skips invalidations of files whose content didn't change, see
+    /// [WatchOptions::compare_content].
+    compare_content: AtomicBool,
+    /// The hashes of the contents of the files which have been read, by
+    /// [invalidator_key], when `compare_content` is enabled.
+    content_hashes: DashMap<String, u64>,
 }
 
 impl DiskWatcher {
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    /// Warns about the exhausted watch limit, once per file system.
+    fn report_watch_limit(&self, path: &Path) {
+        if !self.watch_limit_reached.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                path = %path.display(),
+                "{}",
+                WatchLimitReached {
+                    path: path.to_path_buf()
+                }
+            );
+        }
+    }
+
+    /// Watches `path` with the OS watcher, but polls it instead when the limit
+    /// of watches of the OS is reached, so no invalidation is missed.
+    fn watch_or_poll(
+        &self,
+        watcher: &mut RecommendedWatcher,
+        path: &Path,
+        mode: RecursiveMode,
+    ) -> notify::Result<()> {
+        match watcher.watch(path, mode) {
+            Err(err) if watch_options::is_watch_limit_error(&err) => {
+                self.report_watch_limit(path);
+                let mut poll_fallback = self.poll_fallback.lock().unwrap();
+                if poll_fallback.is_none() {
+                    let Some(tx) = self.fallback_tx.lock().unwrap().clone() else {
+                        return Err(err);
+                    };
+                    *poll_fallback = Some(PollWatcher::new(tx, DEFAULT_POLL_INTERVAL)?);
+                }
+                poll_fallback.as_mut().unwrap().watch(path, mode)
+            }
+            result => result,
+        }
+    }
+
+    /// Remembers the hash of the `content` which has been read from the file
+    /// with the invalidator `key`, when comparing contents.
+    fn remember_content(&self, key: String, content: &FileContent) -> Result<()> {
+        if !self.compare_content.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match content {
+            FileContent::Content(file) => {
+                let hash = hash_xxh3_hash64(&*file.content().to_bytes()?);
+                self.content_hashes.insert(key, hash);
+            }
+            FileContent::NotFound => {
+                self.content_hashes.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true when the content of the file at `path` has been read while
+    /// comparing contents, so it needs to be compared before invalidating the
+    /// readers of its content.
+    fn has_content_hash(&self, path: &Path, case_insensitive: bool) -> bool {
+        self.content_hashes
+            .contains_key(&invalidator_key(path, case_insensitive))
+    }
+
+    /// Returns true when the content of the file at `path` is the same as
+    /// when it has been read, so its readers don't need to be invalidated.
+    fn is_content_unchanged(&self, path: &Path, case_insensitive: bool) -> bool {
+        let key = invalidator_key(path, case_insensitive);
+        let Some(hash) = self.content_hashes.get(&key).map(|hash| *hash) else {
+            return false;
+        };
+        match std::fs::read(path) {
+            Ok(content) => hash_xxh3_hash64(content.as_slice()) == hash,
+            Err(_) => false,
+        }
+    }
+
     fn restore_if_watching(&self, dir_path: &Path, root_path: &Path) -> Result<()> {
         if self.watching.contains(dir_path) {
             let mut watcher = self.watcher.lock().unwrap();
@@ -107,7 +220,6 @@ impl DiskWatcher {
         Ok(())
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     fn ensure_watching(&self, dir_path: &Path, root_path: &Path) -> Result<()> {
         if self.watching.contains(dir_path) {
             return Ok(());
@@ -119,16 +231,35 @@ impl DiskWatcher {
         Ok(())
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    fn ensure_watching_recursive(&self, dir_path: &Path) -> Result<()> {
+        if self.watching_recursive.contains(dir_path) {
+            return Ok(());
+        }
+        let mut watcher = self.watcher.lock().unwrap();
+        if self.watching_recursive.insert(dir_path.to_path_buf())
+            && self.lazy.load(Ordering::Relaxed)
+        {
+            if let Some(watcher) = watcher.as_mut() {
+                self.watch_or_poll(watcher, dir_path, RecursiveMode::Recursive)
+                    .with_context(|| format!("Unable to watch {}", dir_path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
     fn start_watching(
         &self,
         watcher: &mut std::sync::MutexGuard<Option<RecommendedWatcher>>,
         dir_path: &Path,
         root_path: &Path,
     ) -> Result<()> {
+        // Otherwise the whole root is watched recursively
+        if !self.lazy.load(Ordering::Relaxed) {
+            return Ok(());
+        }
         if let Some(watcher) = watcher.as_mut() {
             let mut path = dir_path;
-            while let Err(err) = watcher.watch(path, RecursiveMode::NonRecursive) {
+            while let Err(err) = self.watch_or_poll(watcher, path, RecursiveMode::NonRecursive) {
                 if path == root_path {
                     return Err(err).context(format!(
                         "Unable to watch {} (tried up to {})",
@@ -150,6 +281,60 @@ impl DiskWatcher {
     }
 }
 
+#[instrument(parent = None, level = Level::INFO, name = "DiskFileSystem file change", skip_all, fields(name = display(path.display())))]
+fn invalidate(
+    report_invalidation_reason: &Option<(String, PathBuf)>,
+    path: &Path,
+    invalidator: Invalidator,
+    batch: &mut InvalidationBatch,
+) {
+    if let Some((name, root_path)) = report_invalidation_reason {
+        if let Some(path) = format_absolute_fs_path(path, name, root_path) {
+            batch.add_with_reason(invalidator, WatchChange { path });
+            return;
+        }
+    }
+    batch.add(invalidator);
+}
+fn invalidate_path(
+    report_invalidation_reason: &Option<(String, PathBuf)>,
+    batch: &mut InvalidationBatch,
+    invalidator_map: &mut HashMap<String, HashSet<Invalidator>>,
+    case_insensitive: bool,
+    paths: impl Iterator<Item = PathBuf>,
+) {
+    for path in paths {
+        let key = invalidator_key(&path, case_insensitive);
+        if let Some(invalidators) = invalidator_map.remove(&key) {
+            invalidators
+                .into_iter()
+                .for_each(|i| invalidate(report_invalidation_reason, &path, i, batch));
+        }
+    }
+}
+
+/// Options of a [DiskFileSystem], see [DiskFileSystemVc::new_with_options].
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone, Default)]
+pub struct DiskFileSystemOptions {
+    /// Directories (relative to the root) which are treated as opaque units,
+    /// see [DiskFileSystemVc::new_with_opaque_directories].
+    pub opaque_directories: Vec<String>,
+    /// Globs of files and directories (relative to the root) which are
+    /// neither watched nor invalidated, e.g. `.git`, `node_modules/.cache` or
+    /// the output directory. A directory which matches ignores everything
+    /// within it. Reads from ignored paths are never recomputed, so they
+    /// should only contain files the build doesn't depend on.
+    pub ignored: Vec<String>,
+    /// Writes files to a temporary file next to them first and renames it into
+    /// place, so readers (e.g. a dev server) never see a partially written
+    /// file, even when the process crashes during the write.
+    pub atomic_writes: bool,
+    /// Flushes written files (and with `atomic_writes` the renames) to the
+    /// disk before a write completes.
+    pub fsync: bool,
+}
+
 #[turbo_tasks::value(cell = "new", eq = "manual")]
 pub struct DiskFileSystem {
     pub name: String,
@@ -159,8 +344,29 @@ pub struct DiskFileSystem {
     mutex_map: MutexMap<PathBuf>,
     #[turbo_tasks(debug_ignore, trace_ignore)]
     invalidator_map: Arc<InvalidatorMap>,
+    /// The readers of the content of files. They are kept apart from the
+    /// readers of metadata and links, which need to be invalidated even when
+    /// the content didn't change, see [WatchOptions::compare_content].
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    content_invalidator_map: Arc<InvalidatorMap>,
     #[turbo_tasks(debug_ignore, trace_ignore)]
     dir_invalidator_map: Arc<InvalidatorMap>,
+    /// Directories which are treated as a single unit for invalidation. Any
+    /// change within them invalidates everything read from them at once.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    opaque_directories: Vec<PathBuf>,
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    opaque_invalidator_map: Arc<InvalidatorMap>,
+    /// Paths (relative to `root`) which are neither watched nor invalidated.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    ignored: Arc<Vec<Glob>>,
+    /// Whether the file system at `root` ignores the casing of paths, which is
+    /// usually the case on macOS and Windows.
+    case_insensitive: bool,
+    /// See [DiskFileSystemOptions::atomic_writes].
+    atomic_writes: bool,
+    /// See [DiskFileSystemOptions::fsync].
+    fsync: bool,
     /// Lock that makes invalidation atomic. It will keep a write lock during
     /// watcher invalidation and a read lock during other operations.
     #[turbo_tasks(debug_ignore, trace_ignore)]
@@ -169,6 +375,31 @@ pub struct DiskFileSystem {
     #[turbo_tasks(debug_ignore, trace_ignore)]
     #[serde(skip)]
     watcher: Arc<DiskWatcher>,
+    /// The files written by this file system, to skip writing unchanged
+    /// content without comparing it to the file on disk.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    #[serde(skip)]
+    written_files: DashMap<PathBuf, WrittenFile>,
+}
+
+/// A file as it was left on disk by [DiskFileSystem::write].
+struct WrittenFile {
+    content_hash: u64,
+    permissions: Permissions,
+    /// The size and modification time identify the written file, so a file
+    /// which was changed by someone else is written again.
+    size: u64,
+    modified: Option<std::time::SystemTime>,
+}
+
+/// The maximum number of files which are written at the same time, across all
+/// [DiskFileSystem]s. Emitting many assets at once would otherwise run out of
+/// file descriptors and thrash the disk.
+const MAX_PARALLEL_WRITES: usize = 64;
+
+fn write_semaphore() -> &'static Semaphore {
+    static WRITE_SEMAPHORE: OnceLock<Semaphore> = OnceLock::new();
+    WRITE_SEMAPHORE.get_or_init(|| Semaphore::new(MAX_PARALLEL_WRITES))
 }
 
 impl DiskFileSystem {
@@ -177,12 +408,47 @@ impl DiskFileSystem {
         simplified(Path::new(&self.root))
     }
 
+    /// Returns the opaque directory `path` is located in, if any.
+    fn opaque_directory(&self, path: &Path) -> Option<&Path> {
+        self.opaque_directories
+            .iter()
+            .find(|dir| path.starts_with(dir))
+            .map(|dir| dir.as_path())
+    }
+
     /// registers the path as an invalidator for the current task,
     /// has to be called within a turbo-tasks function
-    fn register_invalidator(&self, path: &Path) -> Result<()> {
+    async fn register_invalidator(&self, self_vc: DiskFileSystemVc, path: &Path) -> Result<()> {
+        self.register_file_invalidator(self_vc, &self.invalidator_map, path)
+            .await
+    }
+
+    /// Like [DiskFileSystem::register_invalidator], but for tasks which only
+    /// depend on the content of the file.
+    async fn register_content_invalidator(
+        &self,
+        self_vc: DiskFileSystemVc,
+        path: &Path,
+    ) -> Result<()> {
+        self.register_file_invalidator(self_vc, &self.content_invalidator_map, path)
+            .await
+    }
+
+    async fn register_file_invalidator(
+        &self,
+        self_vc: DiskFileSystemVc,
+        invalidator_map: &InvalidatorMap,
+        path: &Path,
+    ) -> Result<()> {
+        if is_ignored(&self.ignored, self.root_path(), path) {
+            return Ok(());
+        }
+        if let Some(dir) = self.opaque_directory(path) {
+            self_vc.opaque_directory(path_to_key(dir)).await?;
+            return Ok(());
+        }
         let invalidator = turbo_tasks::get_invalidator();
-        self.invalidator_map.insert(path_to_key(path), invalidator);
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        invalidator_map.insert(invalidator_key(path, self.case_insensitive), invalidator);
         if let Some(dir) = path.parent() {
             self.watcher.ensure_watching(dir, self.root_path())?;
         }
@@ -191,60 +457,159 @@ impl DiskFileSystem {
 
     /// registers the path as an invalidator for the current task,
     /// has to be called within a turbo-tasks function
-    fn register_dir_invalidator(&self, path: &Path) -> Result<()> {
+    async fn register_dir_invalidator(&self, self_vc: DiskFileSystemVc, path: &Path) -> Result<()> {
+        if is_ignored(&self.ignored, self.root_path(), path) {
+            return Ok(());
+        }
+        if let Some(dir) = self.opaque_directory(path) {
+            self_vc.opaque_directory(path_to_key(dir)).await?;
+            return Ok(());
+        }
         let invalidator = turbo_tasks::get_invalidator();
         self.dir_invalidator_map
-            .insert(path_to_key(path), invalidator);
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+            .insert(invalidator_key(path, self.case_insensitive), invalidator);
         self.watcher.ensure_watching(path, self.root_path())?;
         Ok(())
     }
 
+    /// Returns true when `file` with the `content_hash` was written to `path`
+    /// before and the file on disk hasn't changed since then.
+    async fn is_written(&self, path: &Path, content_hash: u64, file: &File) -> bool {
+        let Some(written) = self.written_files.get(path) else {
+            return false;
+        };
+        if written.content_hash != content_hash || written.permissions != file.meta.permissions {
+            return false;
+        }
+        let (size, modified) = (written.size, written.modified);
+        drop(written);
+        match fs::metadata(path).await {
+            Ok(meta) => meta.len() == size && meta.modified().ok() == modified,
+            Err(_) => false,
+        }
+    }
+
+    async fn remember_written(&self, path: PathBuf, content_hash: u64, file: &File) {
+        let Ok(meta) = fs::metadata(&path).await else {
+            self.written_files.remove(&path);
+            return;
+        };
+        self.written_files.insert(
+            path,
+            WrittenFile {
+                content_hash,
+                permissions: file.meta.permissions,
+                size: meta.len(),
+                modified: meta.modified().ok(),
+            },
+        );
+    }
+
     async fn lock_path(&self, full_path: &Path) -> PathLockGuard<'_> {
         let lock1 = self.invalidation_lock.read().await;
         let lock2 = self.mutex_map.lock(full_path.to_path_buf()).await;
         PathLockGuard(lock1, lock2)
     }
 
+    fn invalidator_maps(&self) -> [&InvalidatorMap; 4] {
+        [
+            &self.invalidator_map,
+            &self.content_invalidator_map,
+            &self.dir_invalidator_map,
+            &self.opaque_invalidator_map,
+        ]
+    }
+
     pub fn invalidate(&self) {
-        for (_, invalidators) in take(&mut *self.invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| i.invalidate());
-        }
-        for (_, invalidators) in take(&mut *self.dir_invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| i.invalidate());
+        for invalidator_map in self.invalidator_maps() {
+            for (_, invalidators) in take(&mut *invalidator_map.lock().unwrap()).into_iter() {
+                invalidators.into_iter().for_each(|i| i.invalidate());
+            }
         }
     }
 
     pub fn invalidate_with_reason<T: InvalidationReason + Clone>(&self, reason: T) {
-        for (_, invalidators) in take(&mut *self.invalidator_map.lock().unwrap()).into_iter() {
-            invalidators
-                .into_iter()
-                .for_each(|i| i.invalidate_with_reason(reason.clone()));
-        }
-        for (_, invalidators) in take(&mut *self.dir_invalidator_map.lock().unwrap()).into_iter() {
-            invalidators
-                .into_iter()
-                .for_each(|i| i.invalidate_with_reason(reason.clone()));
+        for invalidator_map in self.invalidator_maps() {
+            for (_, invalidators) in take(&mut *invalidator_map.lock().unwrap()).into_iter() {
+                invalidators
+                    .into_iter()
+                    .for_each(|i| i.invalidate_with_reason(reason.clone()));
+            }
         }
     }
 
     pub fn start_watching(&self) -> Result<()> {
-        self.start_watching_internal(false)
+        self.start_watching_with_options(WatchOptions::default())
     }
 
     pub fn start_watching_with_invalidation_reason(&self) -> Result<()> {
-        self.start_watching_internal(true)
+        self.start_watching_with_options(WatchOptions {
+            report_invalidation_reason: true,
+            ..Default::default()
+        })
     }
 
-    fn start_watching_internal(&self, report_invalidation_reason: bool) -> Result<()> {
+    /// Like [DiskFileSystem::start_watching], but collects file changes until
+    /// no change happened for `debounce` and applies them as one batch. This
+    /// avoids recomputing everything multiple times during bulk operations,
+    /// e.g. a `git checkout` or `npm install`. A batch is applied at the
+    /// latest after 10 times the `debounce` window.
+    pub fn start_watching_with_debounce(
+        &self,
+        report_invalidation_reason: bool,
+        debounce: Duration,
+    ) -> Result<()> {
+        self.start_watching_with_options(WatchOptions {
+            report_invalidation_reason,
+            debounce: Some(debounce),
+            ..Default::default()
+        })
+    }
+
+    /// Like [DiskFileSystem::start_watching], but uses Watchman when it's
+    /// installed. Watchman scales to huge repositories, where the built-in
+    /// watcher runs into inotify limits or takes too long to crawl all
+    /// directories. Falls back to the built-in watcher otherwise.
+    pub fn start_watching_with_watchman(
+        &self,
+        report_invalidation_reason: bool,
+        debounce: Option<Duration>,
+    ) -> Result<()> {
+        self.start_watching_with_options(WatchOptions {
+            report_invalidation_reason,
+            debounce,
+            backend: WatchBackend::Watchman,
+            ..Default::default()
+        })
+    }
+
+    /// Starts watching with the given [WatchOptions]. Does nothing when the
+    /// file system is already watched.
+    pub fn start_watching_with_options(&self, options: WatchOptions) -> Result<()> {
+        let WatchOptions {
+            report_invalidation_reason,
+            debounce,
+            backend,
+            lazy,
+            compare_content,
+        } = options;
         let mut watcher_guard = self.watcher.watcher.lock().unwrap();
-        if watcher_guard.is_some() {
+        let mut watchman_guard = self.watcher.watchman.lock().unwrap();
+        let mut poll_watcher_guard = self.watcher.poll_watcher.lock().unwrap();
+        if watcher_guard.is_some() || watchman_guard.is_some() || poll_watcher_guard.is_some() {
             return Ok(());
         }
+        self.watcher
+            .compare_content
+            .store(compare_content, Ordering::Relaxed);
         let invalidator_map = self.invalidator_map.clone();
+        let content_invalidator_map = self.content_invalidator_map.clone();
         let dir_invalidator_map = self.dir_invalidator_map.clone();
+        let opaque_invalidator_map = self.opaque_invalidator_map.clone();
         let root = self.root.clone();
         let root_path = self.root_path().to_path_buf();
+        let ignored = self.ignored.clone();
+        let case_insensitive = self.case_insensitive;
 
         let report_invalidation_reason =
             report_invalidation_reason.then(|| (self.name.clone(), root_path.clone()));
@@ -252,117 +617,114 @@ impl DiskFileSystem {
         let invalidation_lock = self.invalidation_lock.clone();
         // Create a channel to receive the events.
         let (tx, rx) = channel();
-        // Create a watcher object, delivering debounced events.
-        // The notification back-end is selected based on the platform.
-        let mut watcher = watcher(tx, Duration::from_millis(1))?;
-        // Add a path to be watched. All files and directories at that path and
-        // below will be monitored for changes.
-        #[cfg(any(target_os = "macos", target_os = "windows"))]
-        watcher.watch(&root_path, RecursiveMode::Recursive)?;
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-        for dir_path in self.watcher.watching.iter() {
-            watcher.watch(&*dir_path, RecursiveMode::NonRecursive)?;
+        let backend = match backend {
+            WatchBackend::Watchman if !watchman::is_watchman_available() => WatchBackend::Auto,
+            WatchBackend::Auto if watch_options::needs_polling(&root_path) => WatchBackend::Poll {
+                interval: DEFAULT_POLL_INTERVAL,
+            },
+            backend => backend,
+        };
+        match backend {
+            WatchBackend::Watchman => {
+                // Watchman always watches the whole root recursively.
+                watchman_guard.replace(WatchmanWatcher::new(&root_path, tx)?);
+            }
+            WatchBackend::Poll { interval } => {
+                poll_watcher_guard.replace(self.start_poll_watcher(tx, interval)?);
+            }
+            WatchBackend::Native => {
+                watcher_guard.replace(self.start_native_watcher(tx, lazy)?);
+            }
+            WatchBackend::Auto => match self.start_native_watcher(tx.clone(), lazy) {
+                Ok(watcher) => {
+                    watcher_guard.replace(watcher);
+                }
+                Err(err) => {
+                    if err
+                        .downcast_ref::<notify::Error>()
+                        .map_or(false, watch_options::is_watch_limit_error)
+                    {
+                        self.watcher.report_watch_limit(&root_path);
+                    } else {
+                        tracing::warn!(
+                            "unable to watch {} ({:#}), falling back to polling",
+                            root_path.display(),
+                            err
+                        );
+                    }
+                    poll_watcher_guard.replace(self.start_poll_watcher(tx, DEFAULT_POLL_INTERVAL)?);
+                }
+            },
         }
+        drop(watcher_guard);
+        drop(watchman_guard);
+        drop(poll_watcher_guard);
 
         // We need to invalidate all reads that happened before watching
         // Best is to start_watching before starting to read
-        for (_, invalidators) in take(&mut *invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| {
-                if report_invalidation_reason.is_some() {
-                    i.invalidate_with_reason(WatchStart {
-                        name: self.name.clone(),
-                    })
-                } else {
-                    i.invalidate();
-                }
-            });
-        }
-        for (_, invalidators) in take(&mut *dir_invalidator_map.lock().unwrap()).into_iter() {
-            invalidators.into_iter().for_each(|i| {
-                if report_invalidation_reason.is_some() {
-                    i.invalidate_with_reason(WatchStart {
-                        name: self.name.clone(),
-                    })
-                } else {
-                    i.invalidate();
-                }
+        if report_invalidation_reason.is_some() {
+            self.invalidate_with_reason(WatchStart {
+                name: self.name.clone(),
             });
+        } else {
+            self.invalidate();
         }
 
-        watcher_guard.replace(watcher);
-        drop(watcher_guard);
-
-        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
         let disk_watcher = self.watcher.clone();
 
+        // Hashing the changed files can take a while, so the contents are
+        // compared on another thread, which doesn't delay the invalidation of
+        // other readers.
+        let compare_tx = compare_content.then(|| {
+            let (compare_tx, compare_rx) = channel::<Vec<PathBuf>>();
+            let disk_watcher = disk_watcher.clone();
+            let content_invalidator_map = content_invalidator_map.clone();
+            let invalidation_lock = invalidation_lock.clone();
+            let report_invalidation_reason = report_invalidation_reason.clone();
+            spawn_thread(move || {
+                // Exits when the watcher thread is gone
+                while let Ok(paths) = compare_rx.recv() {
+                    let changed_paths = paths
+                        .into_iter()
+                        .filter(|path| !disk_watcher.is_content_unchanged(path, case_insensitive))
+                        .collect::<Vec<_>>();
+                    if changed_paths.is_empty() {
+                        continue;
+                    }
+                    let _lock = invalidation_lock.blocking_write();
+                    let mut batch = InvalidationBatch::new();
+                    invalidate_path(
+                        &report_invalidation_reason,
+                        &mut batch,
+                        &mut content_invalidator_map.lock().unwrap(),
+                        case_insensitive,
+                        changed_paths.into_iter(),
+                    );
+                    batch.apply();
+                }
+            });
+            compare_tx
+        });
+
         spawn_thread(move || {
-            let mut batched_invalidate_path = HashSet::new();
-            let mut batched_invalidate_path_dir = HashSet::new();
-            let mut batched_invalidate_path_and_children = HashSet::new();
-            let mut batched_invalidate_path_and_children_dir = HashSet::new();
-            #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-            let mut batched_new_paths = HashSet::new();
+            let mut events = CoalescedEvents::default();
 
             'outer: loop {
                 let mut event = rx.recv().map_err(|e| match e {
                     RecvError => TryRecvError::Disconnected,
                 });
+                let batch_deadline = debounce.map(|debounce| Instant::now() + debounce * 10);
                 loop {
+                    #[cfg(target_family = "windows")]
+                    {
+                        event = event.map(normalize_event_paths);
+                    }
                     match event {
-                        Ok(DebouncedEvent::Write(path)) => {
-                            batched_invalidate_path.insert(path);
-                        }
-                        Ok(DebouncedEvent::Create(path)) => {
-                            batched_invalidate_path_and_children.insert(path.clone());
-                            batched_invalidate_path_and_children_dir.insert(path.clone());
-                            if let Some(parent) = path.parent() {
-                                batched_invalidate_path_dir.insert(PathBuf::from(parent));
+                        Ok(event) => {
+                            if let DebouncedEvent::Error(err, path) = &event {
+                                println!("watch error ({:?}): {:?} ", path, err);
                             }
-                            #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-                            batched_new_paths.insert(path.clone());
-                        }
-                        Ok(DebouncedEvent::Remove(path)) => {
-                            batched_invalidate_path_and_children.insert(path.clone());
-                            batched_invalidate_path_and_children_dir.insert(path.clone());
-                            if let Some(parent) = path.parent() {
-                                batched_invalidate_path_dir.insert(PathBuf::from(parent));
-                            }
-                        }
-                        Ok(DebouncedEvent::Rename(source, destination)) => {
-                            batched_invalidate_path_and_children.insert(source.clone());
-                            if let Some(parent) = source.parent() {
-                                batched_invalidate_path_dir.insert(PathBuf::from(parent));
-                            }
-                            batched_invalidate_path_and_children.insert(destination.clone());
-                            if let Some(parent) = destination.parent() {
-                                batched_invalidate_path_dir.insert(PathBuf::from(parent));
-                            }
-                            #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-                            batched_new_paths.insert(destination.clone());
-                        }
-                        Ok(DebouncedEvent::Rescan) => {
-                            batched_invalidate_path_and_children.insert(PathBuf::from(&root));
-                            batched_invalidate_path_and_children_dir.insert(PathBuf::from(&root));
-                        }
-                        Ok(DebouncedEvent::Error(err, path)) => {
-                            println!("watch error ({:?}): {:?} ", path, err);
-                            match path {
-                                Some(path) => {
-                                    batched_invalidate_path_and_children.insert(path.clone());
-                                    batched_invalidate_path_and_children_dir.insert(path);
-                                }
-                                None => {
-                                    batched_invalidate_path_and_children
-                                        .insert(PathBuf::from(&root));
-                                    batched_invalidate_path_and_children_dir
-                                        .insert(PathBuf::from(&root));
-                                }
-                            }
-                        }
-                        Ok(DebouncedEvent::Chmod(_))
-                        | Ok(DebouncedEvent::NoticeRemove(_))
-                        | Ok(DebouncedEvent::NoticeWrite(_)) => {
-                            // ignored
+                            events.add(event, Path::new(&root));
                         }
                         Err(TryRecvError::Disconnected) => {
                             // Sender has been disconnected
@@ -374,86 +736,178 @@ impl DiskFileSystem {
                             break;
                         }
                     }
-                    event = rx.try_recv();
-                }
-                #[instrument(parent = None, level = Level::INFO, name = "DiskFileSystem file change", skip_all, fields(name = display(path.display())))]
-                fn invalidate(
-                    report_invalidation_reason: &Option<(String, PathBuf)>,
-                    path: &Path,
-                    invalidator: Invalidator,
-                ) {
-                    if let Some((name, root_path)) = report_invalidation_reason {
-                        if let Some(path) = format_absolute_fs_path(path, name, root_path) {
-                            invalidator.invalidate_with_reason(WatchChange { path });
-                            return;
+                    event = match (debounce, batch_deadline) {
+                        (Some(debounce), Some(batch_deadline)) => {
+                            let timeout = debounce
+                                .min(batch_deadline.saturating_duration_since(Instant::now()));
+                            rx.recv_timeout(timeout).map_err(|e| match e {
+                                RecvTimeoutError::Timeout => TryRecvError::Empty,
+                                RecvTimeoutError::Disconnected => TryRecvError::Disconnected,
+                            })
                         }
-                    }
-                    invalidator.invalidate();
+                        _ => rx.try_recv(),
+                    };
                 }
-                fn invalidate_path(
+                fn invalidate_path_and_children_execute(
                     report_invalidation_reason: &Option<(String, PathBuf)>,
+                    batch: &mut InvalidationBatch,
                     invalidator_map: &mut HashMap<String, HashSet<Invalidator>>,
+                    case_insensitive: bool,
                     paths: impl Iterator<Item = PathBuf>,
                 ) {
                     for path in paths {
-                        let key = path_to_key(&path);
-                        if let Some(invalidators) = invalidator_map.remove(&key) {
-                            invalidators
-                                .into_iter()
-                                .for_each(|i| invalidate(report_invalidation_reason, &path, i));
+                        let path_key = invalidator_key(&path, case_insensitive);
+                        for (_, invalidators) in
+                            invalidator_map.extract_if(|key, _| key.starts_with(&path_key))
+                        {
+                            invalidators.into_iter().for_each(|i| {
+                                invalidate(report_invalidation_reason, &path, i, batch)
+                            });
                         }
                     }
                 }
-                fn invalidate_path_and_children_execute(
+                fn invalidate_opaque_directories(
                     report_invalidation_reason: &Option<(String, PathBuf)>,
+                    batch: &mut InvalidationBatch,
                     invalidator_map: &mut HashMap<String, HashSet<Invalidator>>,
-                    paths: impl Iterator<Item = PathBuf>,
+                    paths: &[PathBuf],
                 ) {
-                    for path in paths {
-                        let path_key = path_to_key(&path);
-                        for (_, invalidators) in
-                            invalidator_map.extract_if(|key, _| key.starts_with(&path_key))
-                        {
-                            invalidators
-                                .into_iter()
-                                .for_each(|i| invalidate(report_invalidation_reason, &path, i));
-                        }
+                    // A change anywhere within an opaque directory, or of one of its
+                    // ancestors, invalidates the whole directory.
+                    for (key, invalidators) in invalidator_map.extract_if(|key, _| {
+                        let dir = Path::new(key);
+                        paths
+                            .iter()
+                            .any(|path| path.starts_with(dir) || dir.starts_with(path))
+                    }) {
+                        let dir = PathBuf::from(key);
+                        invalidators
+                            .into_iter()
+                            .for_each(|i| invalidate(report_invalidation_reason, &dir, i, batch));
                     }
                 }
+                // Nothing changed, e.g. when only temporary files were created and removed
+                if events.is_empty() {
+                    continue;
+                }
+                let BatchedInvalidations {
+                    path: mut batched_invalidate_path,
+                    path_dir: mut batched_invalidate_path_dir,
+                    path_and_children: mut batched_invalidate_path_and_children,
+                    path_and_children_dir: mut batched_invalidate_path_and_children_dir,
+                    new_paths: mut batched_new_paths,
+                } = events.take_invalidations();
+                if !ignored.is_empty() {
+                    for paths in [
+                        &mut batched_invalidate_path,
+                        &mut batched_invalidate_path_dir,
+                        &mut batched_invalidate_path_and_children,
+                        &mut batched_invalidate_path_and_children_dir,
+                    ] {
+                        paths.retain(|path| !is_ignored(&ignored, &root_path, path));
+                    }
+                    batched_new_paths.retain(|path| !is_ignored(&ignored, &root_path, path));
+                }
                 // We need to start watching first before invalidating the changed paths
-                #[cfg(not(any(target_os = "macos", target_os = "windows")))]
                 {
                     for path in batched_new_paths.drain() {
                         let _ = disk_watcher.restore_if_watching(&path, &root_path);
                     }
                 }
+                // Touched files and atomic saves of unchanged content don't need to
+                // invalidate the readers of the content, but the readers of the
+                // metadata still do. Files which have been read are compared on
+                // the other thread before invalidating the readers of their content.
+                let mut content_paths = batched_invalidate_path.clone();
+                let mut content_paths_and_children = batched_invalidate_path_and_children.clone();
+                let mut compared_paths = Vec::new();
+                if compare_tx.is_some() {
+                    for paths in [&mut content_paths, &mut content_paths_and_children] {
+                        paths.retain(|path| {
+                            if disk_watcher.has_content_hash(path, case_insensitive) {
+                                compared_paths.push(path.clone());
+                                false
+                            } else {
+                                true
+                            }
+                        });
+                    }
+                }
                 let _lock = invalidation_lock.blocking_write();
+                let mut batch = InvalidationBatch::new();
+                {
+                    let mut opaque_invalidator_map = opaque_invalidator_map.lock().unwrap();
+                    if !opaque_invalidator_map.is_empty() {
+                        let changed_paths = batched_invalidate_path
+                            .iter()
+                            .chain(batched_invalidate_path_and_children.iter())
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        invalidate_opaque_directories(
+                            &report_invalidation_reason,
+                            &mut batch,
+                            &mut opaque_invalidator_map,
+                            &changed_paths,
+                        );
+                    }
+                }
                 {
                     let mut invalidator_map = invalidator_map.lock().unwrap();
                     invalidate_path(
                         &report_invalidation_reason,
+                        &mut batch,
                         &mut invalidator_map,
+                        case_insensitive,
                         batched_invalidate_path.drain(),
                     );
                     invalidate_path_and_children_execute(
                         &report_invalidation_reason,
+                        &mut batch,
                         &mut invalidator_map,
+                        case_insensitive,
                         batched_invalidate_path_and_children.drain(),
                     );
                 }
+                {
+                    let mut content_invalidator_map = content_invalidator_map.lock().unwrap();
+                    invalidate_path(
+                        &report_invalidation_reason,
+                        &mut batch,
+                        &mut content_invalidator_map,
+                        case_insensitive,
+                        content_paths.into_iter(),
+                    );
+                    invalidate_path_and_children_execute(
+                        &report_invalidation_reason,
+                        &mut batch,
+                        &mut content_invalidator_map,
+                        case_insensitive,
+                        content_paths_and_children.into_iter(),
+                    );
+                }
                 {
                     let mut dir_invalidator_map = dir_invalidator_map.lock().unwrap();
                     invalidate_path(
                         &report_invalidation_reason,
+                        &mut batch,
                         &mut dir_invalidator_map,
+                        case_insensitive,
                         batched_invalidate_path_dir.drain(),
                     );
                     invalidate_path_and_children_execute(
                         &report_invalidation_reason,
+                        &mut batch,
                         &mut dir_invalidator_map,
+                        case_insensitive,
                         batched_invalidate_path_and_children_dir.drain(),
                     );
                 }
+                batch.apply();
+                if let Some(compare_tx) = &compare_tx {
+                    if !compared_paths.is_empty() {
+                        let _ = compare_tx.send(compared_paths);
+                    }
+                }
             }
         });
         Ok(())
@@ -464,6 +918,82 @@ impl DiskFileSystem {
             drop(watcher);
             // thread will detect the stop because the channel is disconnected
         }
+        if let Some(watcher) = self.watcher.watchman.lock().unwrap().take() {
+            drop(watcher);
+        }
+        if let Some(watcher) = self.watcher.poll_watcher.lock().unwrap().take() {
+            drop(watcher);
+        }
+        {
+            // The channel is only disconnected when all senders are dropped
+            self.watcher.fallback_tx.lock().unwrap().take();
+            if let Some(watcher) = self.watcher.poll_fallback.lock().unwrap().take() {
+                drop(watcher);
+            }
+        }
+    }
+
+    /// Returns true when the OS refused to watch some directories and they
+    /// are polled instead, see [WatchLimitReached].
+    pub fn watch_limit_reached(&self) -> bool {
+        self.watcher.watch_limit_reached.load(Ordering::Relaxed)
+    }
+
+    /// Acquires the advisory [FileLock] `name`, relative to the root, which
+    /// coordinates writes with other processes using the same root, e.g. a
+    /// shared output or cache directory.
+    pub async fn lock(&self, name: &str, options: FileLockOptions) -> Result<FileLock> {
+        FileLock::acquire(Path::new(&self.root).join(name), options).await
+    }
+
+    fn start_native_watcher(
+        &self,
+        tx: Sender<DebouncedEvent>,
+        lazy: bool,
+    ) -> Result<RecommendedWatcher> {
+        // Create a watcher object, delivering debounced events.
+        // The notification back-end is selected based on the platform.
+        self.watcher.fallback_tx.lock().unwrap().replace(tx.clone());
+        let mut watcher = watcher(tx, Duration::from_millis(1))?;
+        // inotify can't watch recursively
+        let lazy = lazy || cfg!(not(any(target_os = "macos", target_os = "windows")));
+        self.watcher.lazy.store(lazy, Ordering::Relaxed);
+        if !lazy {
+            // Add a path to be watched. All files and directories at that path and
+            // below will be monitored for changes.
+            #[cfg(not(target_os = "windows"))]
+            watcher.watch(self.root_path(), RecursiveMode::Recursive)?;
+            // The watcher uses the Windows API directly, which needs the verbatim
+            // prefix for long paths
+            #[cfg(target_os = "windows")]
+            watcher.watch(
+                &*util::to_long_windows_path(&self.root_path().to_string_lossy()),
+                RecursiveMode::Recursive,
+            )?;
+            return Ok(watcher);
+        }
+        for dir_path in self.watcher.watching.iter() {
+            self.watcher
+                .watch_or_poll(&mut watcher, &dir_path, RecursiveMode::NonRecursive)?;
+        }
+        for dir_path in self.watcher.watching_recursive.iter() {
+            self.watcher
+                .watch_or_poll(&mut watcher, &dir_path, RecursiveMode::Recursive)?;
+        }
+        Ok(watcher)
+    }
+
+    /// The poll watcher always watches the whole root recursively.
+    fn start_poll_watcher(
+        &self,
+        tx: Sender<DebouncedEvent>,
+        interval: Duration,
+    ) -> Result<PollWatcher> {
+        let mut watcher = PollWatcher::new(tx, interval)?;
+        watcher
+            .watch(self.root_path(), RecursiveMode::Recursive)
+            .with_context(|| format!("Unable to poll {}", self.root_path().display()))?;
+        Ok(watcher)
     }
 
     pub async fn to_sys_path(&self, fs_path: FileSystemPathVc) -> Result<PathBuf> {
@@ -498,30 +1028,207 @@ fn format_absolute_fs_path(path: &Path, name: &str, root_path: &PathBuf) -> Opti
     path
 }
 
+/// Returns true when `path` or one of its ancestors matches one of the
+/// `ignored` globs, which are relative to `root_path`.
+fn is_ignored(ignored: &[Glob], root_path: &Path, path: &Path) -> bool {
+    if ignored.is_empty() {
+        return false;
+    }
+    let Ok(relative_path) = path.strip_prefix(root_path) else {
+        return false;
+    };
+    let relative_path = relative_path.to_string_lossy();
+    let relative_path = sys_to_unix(&relative_path);
+    let mut ancestors = relative_path
+        .match_indices('/')
+        .map(|(index, _)| &relative_path[..index])
+        .chain(std::iter::once(&*relative_path));
+    ancestors
+        .any(|ancestor| !ancestor.is_empty() && ignored.iter().any(|glob| glob.execute(ancestor)))
+}
+
 pub fn path_to_key(path: impl AsRef<Path>) -> String {
     path.as_ref().to_string_lossy().to_string()
 }
 
+/// Normalizes the paths of a watcher event on Windows, so they match the
+/// paths of the [DiskFileSystem], see [util::normalize_windows_path].
+#[cfg(target_family = "windows")]
+fn normalize_event_paths(event: DebouncedEvent) -> DebouncedEvent {
+    fn normalize(path: PathBuf) -> PathBuf {
+        match path.to_str() {
+            Some(str) => PathBuf::from(&*util::normalize_windows_path(str)),
+            None => path,
+        }
+    }
+    match event {
+        DebouncedEvent::NoticeWrite(path) => DebouncedEvent::NoticeWrite(normalize(path)),
+        DebouncedEvent::NoticeRemove(path) => DebouncedEvent::NoticeRemove(normalize(path)),
+        DebouncedEvent::Create(path) => DebouncedEvent::Create(normalize(path)),
+        DebouncedEvent::Write(path) => DebouncedEvent::Write(normalize(path)),
+        DebouncedEvent::Chmod(path) => DebouncedEvent::Chmod(normalize(path)),
+        DebouncedEvent::Remove(path) => DebouncedEvent::Remove(normalize(path)),
+        DebouncedEvent::Rename(source, destination) => {
+            DebouncedEvent::Rename(normalize(source), normalize(destination))
+        }
+        DebouncedEvent::Error(err, path) => DebouncedEvent::Error(err, path.map(normalize)),
+        DebouncedEvent::Rescan => DebouncedEvent::Rescan,
+    }
+}
+
+/// The key of `path` in the invalidator maps. On a case-insensitive file
+/// system, a path can be read with a different casing than the watcher
+/// reports, so the key ignores the casing.
+fn invalidator_key(path: &Path, case_insensitive: bool) -> String {
+    let key = path_to_key(path);
+    // Watchers report long paths with the verbatim prefix on Windows
+    #[cfg(target_family = "windows")]
+    let key = util::normalize_windows_path(&key).into_owned();
+    if case_insensitive {
+        key.to_lowercase()
+    } else {
+        key
+    }
+}
+
+/// Detects whether the file system at `path`, which must exist, ignores the
+/// casing of paths. It looks up the closest ancestor with cased characters
+/// in its name with the inverted casing, so nothing is written.
+pub fn is_case_insensitive(path: &Path) -> bool {
+    for ancestor in path.ancestors() {
+        let Some(name) = ancestor.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let inverted = name
+            .chars()
+            .flat_map(|c| {
+                if c.is_uppercase() {
+                    c.to_lowercase().collect::<Vec<_>>()
+                } else {
+                    c.to_uppercase().collect::<Vec<_>>()
+                }
+            })
+            .collect::<String>();
+        if inverted == name {
+            continue;
+        }
+        let Ok(metadata) = std::fs::metadata(ancestor) else {
+            continue;
+        };
+        return match std::fs::metadata(ancestor.with_file_name(inverted)) {
+            Ok(inverted_metadata) => is_same_file(&metadata, &inverted_metadata),
+            Err(_) => false,
+        };
+    }
+    // There is nothing to compare, so fall back to the default of the OS
+    cfg!(any(target_os = "macos", target_os = "windows"))
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &std::fs::Metadata, b: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_a: &std::fs::Metadata, _b: &std::fs::Metadata) -> bool {
+    // There are no inode numbers to compare. Entries which only differ in
+    // casing can only exist next to each other on a case-sensitive file
+    // system, which is rare outside of unix.
+    true
+}
+
 #[turbo_tasks::value_impl]
 impl DiskFileSystemVc {
     #[turbo_tasks::function]
-    pub async fn new(name: String, root: String) -> Result<Self> {
+    pub fn new(name: String, root: String) -> Self {
+        Self::new_with_opaque_directories(name, root, Vec::new())
+    }
+
+    /// Creates a file system which treats the given directories (relative to
+    /// `root`) as opaque units: Instead of tracking every file and directory
+    /// within them, a change anywhere within such a directory invalidates
+    /// everything read from it. This trades invalidation granularity for
+    /// much lower memory usage, which is useful for huge generated
+    /// directories.
+    #[turbo_tasks::function]
+    pub fn new_with_opaque_directories(
+        name: String,
+        root: String,
+        opaque_directories: Vec<String>,
+    ) -> Self {
+        Self::new_with_options(
+            name,
+            root,
+            DiskFileSystemOptions {
+                opaque_directories,
+                ..Default::default()
+            }
+            .cell(),
+        )
+    }
+
+    /// Whether the file system at the root ignores the casing of paths.
+    #[turbo_tasks::function]
+    pub async fn is_case_insensitive(self) -> Result<BoolVc> {
+        Ok(BoolVc::cell(self.await?.case_insensitive))
+    }
+
+    #[turbo_tasks::function]
+    pub async fn new_with_options(
+        name: String,
+        root: String,
+        options: DiskFileSystemOptionsVc,
+    ) -> Result<Self> {
         mark_stateful();
         // create the directory for the filesystem on disk, if it doesn't exist
         fs::create_dir_all(&root).await?;
 
+        let options = options.await?;
+        let root_path = simplified(Path::new(&root)).to_path_buf();
+        let opaque_directories = options
+            .opaque_directories
+            .iter()
+            .map(|dir| root_path.join(&*unix_to_sys(dir)))
+            .collect();
+        let ignored = options
+            .ignored
+            .iter()
+            .map(|glob| Glob::parse(glob))
+            .collect::<Result<Vec<_>>>()?;
+
         let instance = DiskFileSystem {
             name,
             root,
             mutex_map: Default::default(),
             invalidation_lock: Default::default(),
             invalidator_map: Arc::new(InvalidatorMap::new()),
+            content_invalidator_map: Arc::new(InvalidatorMap::new()),
             dir_invalidator_map: Arc::new(InvalidatorMap::new()),
+            opaque_directories,
+            opaque_invalidator_map: Arc::new(InvalidatorMap::new()),
+            ignored: Arc::new(ignored),
+            case_insensitive: is_case_insensitive(&root_path),
+            atomic_writes: options.atomic_writes,
+            fsync: options.fsync,
             watcher: Default::default(),
+            written_files: Default::default(),
         };
 
         Ok(Self::cell(instance))
     }
+
+    /// The aggregate cell of an opaque directory. It's invalidated when
+    /// anything within the directory changes, which invalidates all reads
+    /// from the directory.
+    #[turbo_tasks::function]
+    async fn opaque_directory(self, dir: String) -> Result<CompletionVc> {
+        let this = self.await?;
+        let invalidator = turbo_tasks::get_invalidator();
+        this.opaque_invalidator_map.insert(dir.clone(), invalidator);
+        this.watcher.ensure_watching_recursive(Path::new(&dir))?;
+        Ok(CompletionVc::new())
+    }
 }
 
 impl Debug for DiskFileSystem {
@@ -530,14 +1237,60 @@ impl Debug for DiskFileSystem {
     }
 }
 
+/// Writes the content and the permissions of `file` to `path`.
+async fn write_file(path: &Path, file: &File, fsync: bool) -> io::Result<()> {
+    let mut f = fs::File::create(path).await?;
+    tokio::io::copy(&mut file.read(), &mut f).await?;
+    #[cfg(target_family = "unix")]
+    f.set_permissions(file.meta.permissions.into()).await?;
+    if fsync {
+        f.sync_all().await?;
+    }
+    Ok(())
+}
+
+/// Writes `file` to a temporary file in the directory of `path` and renames it
+/// to `path`. A rename within a directory replaces the file atomically, so
+/// `path` either contains the old or the new content, never a part of it.
+async fn write_file_atomic(path: &Path, file: &File, fsync: bool) -> io::Result<()> {
+    static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(
+        ".{file_name}.{}.{}.tmp",
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = async {
+        write_file(&temp_path, file, fsync).await?;
+        fs::rename(&temp_path, path).await
+    }
+    .await;
+    if let Err(err) = result {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(err);
+    }
+    // The rename is only persisted when the directory is flushed too. Windows
+    // can't open directories, but renames are journaled by NTFS.
+    #[cfg(target_family = "unix")]
+    if fsync {
+        if let Some(parent) = path.parent() {
+            fs::File::open(parent).await?.sync_all().await?;
+        }
+    }
+    Ok(())
+}
+
 #[turbo_tasks::value_impl]
 impl FileSystem for DiskFileSystem {
     #[turbo_tasks::function]
-    async fn read(&self, fs_path: FileSystemPathVc) -> Result<FileContentVc> {
-        let full_path = self.to_sys_path(fs_path).await?;
-        self.register_invalidator(&full_path)?;
+    async fn read(self_vc: DiskFileSystemVc, fs_path: FileSystemPathVc) -> Result<FileContentVc> {
+        let this = self_vc.await?;
+        let full_path = this.to_sys_path(fs_path).await?;
+        this.register_content_invalidator(self_vc, &full_path)
+            .await?;
 
-        let _lock = self.lock_path(&full_path).await;
+        let _lock = this.lock_path(&full_path).await;
         let content = match retry_future(|| File::from_path(full_path.clone())).await {
             Ok(file) => FileContent::new(file),
             Err(e) if e.kind() == ErrorKind::NotFound => FileContent::NotFound,
@@ -545,13 +1298,19 @@ impl FileSystem for DiskFileSystem {
                 bail!(anyhow!(e).context(format!("reading file {}", full_path.display())))
             }
         };
+        this.watcher
+            .remember_content(invalidator_key(&full_path, this.case_insensitive), &content)?;
         Ok(content.cell())
     }
 
     #[turbo_tasks::function]
-    async fn read_dir(&self, fs_path: FileSystemPathVc) -> Result<DirectoryContentVc> {
-        let full_path = self.to_sys_path(fs_path).await?;
-        self.register_dir_invalidator(&full_path)?;
+    async fn read_dir(
+        self_vc: DiskFileSystemVc,
+        fs_path: FileSystemPathVc,
+    ) -> Result<DirectoryContentVc> {
+        let this = self_vc.await?;
+        let full_path = this.to_sys_path(fs_path).await?;
+        this.register_dir_invalidator(self_vc, &full_path).await?;
         let fs_path = fs_path.await?;
 
         // we use the sync std function here as it's a lot faster (600%) in
@@ -581,7 +1340,7 @@ impl FileSystem for DiskFileSystem {
 
                 // we filter out any non unicode names and paths without the same root here
                 let file_name = path.file_name()?.to_str()?.to_string();
-                let path_to_root = sys_to_unix(path.strip_prefix(&self.root).ok()?.to_str()?);
+                let path_to_root = sys_to_unix(path.strip_prefix(&this.root).ok()?.to_str()?);
 
                 let fs_path =
                     FileSystemPathVc::new_normalized(fs_path.fs, path_to_root.to_string());
@@ -603,11 +1362,15 @@ impl FileSystem for DiskFileSystem {
     }
 
     #[turbo_tasks::function]
-    async fn read_link(&self, fs_path: FileSystemPathVc) -> Result<LinkContentVc> {
-        let full_path = self.to_sys_path(fs_path).await?;
-        self.register_invalidator(&full_path)?;
+    async fn read_link(
+        self_vc: DiskFileSystemVc,
+        fs_path: FileSystemPathVc,
+    ) -> Result<LinkContentVc> {
+        let this = self_vc.await?;
+        let full_path = this.to_sys_path(fs_path).await?;
+        this.register_invalidator(self_vc, &full_path).await?;
 
-        let _lock = self.lock_path(&full_path).await;
+        let _lock = this.lock_path(&full_path).await;
         let link_path = match retry_future(|| fs::read_link(&full_path)).await {
             Ok(res) => res,
             Err(_) => return Ok(LinkContent::NotFound.cell()),
@@ -640,7 +1403,7 @@ impl FileSystem for DiskFileSystem {
         //
         // we use `dunce::simplify` to strip a potential UNC prefix on windows, on any
         // other OS this gets compiled away
-        let result = simplified(&file).strip_prefix(simplified(Path::new(&self.root)));
+        let result = simplified(&file).strip_prefix(simplified(Path::new(&this.root)));
 
         let relative_to_root_path = match result {
             Ok(file) => PathBuf::from(sys_to_unix(&file.to_string_lossy()).as_ref()),
@@ -685,9 +1448,10 @@ impl FileSystem for DiskFileSystem {
     }
 
     #[turbo_tasks::function]
-    async fn track(&self, fs_path: FileSystemPathVc) -> Result<CompletionVc> {
-        let full_path = self.to_sys_path(fs_path).await?;
-        self.register_invalidator(&full_path)?;
+    async fn track(self_vc: DiskFileSystemVc, fs_path: FileSystemPathVc) -> Result<CompletionVc> {
+        let this = self_vc.await?;
+        let full_path = this.to_sys_path(fs_path).await?;
+        this.register_invalidator(self_vc, &full_path).await?;
         Ok(CompletionVc::new())
     }
 
@@ -698,12 +1462,20 @@ impl FileSystem for DiskFileSystem {
         content: FileContentVc,
     ) -> Result<CompletionVc> {
         let full_path = self.to_sys_path(fs_path).await?;
+        let content_hash = *content.hash().await?;
         let content = content.await?;
 
         // Track the file, so that we will rewrite it if it ever changes.
         fs_path.track().await?;
 
         let _lock = self.lock_path(&full_path).await;
+        let _permit = write_semaphore().acquire().await?;
+
+        if let FileContent::Content(file) = &*content {
+            if self.is_written(&full_path, content_hash, file).await {
+                return Ok(CompletionVc::unchanged());
+            }
+        }
 
         // We perform an untracked comparison here, so that this write is not dependent
         // on a read's FileContentVc (and the memory it holds). Our untracked read can
@@ -712,6 +1484,9 @@ impl FileSystem for DiskFileSystem {
         // wasting cycles.
         let compare = content.streaming_compare(full_path.clone()).await?;
         if compare == FileComparison::Equal {
+            if let FileContent::Content(file) = &*content {
+                self.remember_written(full_path, content_hash, file).await;
+            }
             return Ok(CompletionVc::unchanged());
         }
 
@@ -732,20 +1507,23 @@ impl FileSystem for DiskFileSystem {
                     }
                 }
                 let full_path_to_write = full_path.clone();
+                let (atomic, fsync) = (self.atomic_writes, self.fsync);
                 retry_future(move || {
                     let full_path = full_path_to_write.clone();
                     async move {
-                        let mut f = fs::File::create(&full_path).await?;
-                        tokio::io::copy(&mut file.read(), &mut f).await?;
-                        #[cfg(target_family = "unix")]
-                        f.set_permissions(file.meta.permissions.into()).await?;
-                        Ok::<(), io::Error>(())
+                        if atomic {
+                            write_file_atomic(&full_path, file, fsync).await
+                        } else {
+                            write_file(&full_path, file, fsync).await
+                        }
                     }
                 })
                 .await
                 .with_context(|| format!("failed to write to {}", full_path.display()))?;
+                self.remember_written(full_path, content_hash, file).await;
             }
             FileContent::NotFound => {
+                self.written_files.remove(&full_path);
                 retry_future(|| fs::remove_file(full_path.clone()))
                     .await
                     .or_else(|err| {
@@ -840,16 +1618,31 @@ impl FileSystem for DiskFileSystem {
     }
 
     #[turbo_tasks::function]
-    async fn metadata(&self, fs_path: FileSystemPathVc) -> Result<FileMetaVc> {
-        let full_path = self.to_sys_path(fs_path).await?;
-        self.register_invalidator(&full_path)?;
+    async fn metadata(self_vc: DiskFileSystemVc, fs_path: FileSystemPathVc) -> Result<FileMetaVc> {
+        let this = self_vc.await?;
+        let full_path = this.to_sys_path(fs_path).await?;
+        this.register_invalidator(self_vc, &full_path).await?;
 
-        let _lock = self.lock_path(&full_path).await;
-        let meta = retry_future(|| fs::metadata(full_path.clone()))
+        let _lock = this.lock_path(&full_path).await;
+        let link_meta = retry_future(|| fs::symlink_metadata(full_path.clone()))
             .await
             .with_context(|| format!("reading metadata for {}", full_path.display()))?;
+        if !link_meta.is_symlink() {
+            return Ok(FileMetaVc::cell(link_meta.into()));
+        }
 
-        Ok(FileMetaVc::cell(meta.into()))
+        let target = retry_future(|| fs::read_link(full_path.clone()))
+            .await
+            .with_context(|| format!("reading link {}", full_path.display()))?;
+        // The size and permissions are the ones of the target, unless the link
+        // is dangling.
+        let mut meta: FileMeta = match retry_future(|| fs::metadata(full_path.clone())).await {
+            Ok(meta) => meta.into(),
+            Err(_) => link_meta.into(),
+        };
+        meta.file_type = Some(FileSystemEntryType::Symlink);
+        meta.symlink_target = Some(sys_to_unix(&target.to_string_lossy()).into_owned());
+        Ok(meta.cell())
     }
 }
 
@@ -865,12 +1658,12 @@ impl ValueToString for DiskFileSystem {
 #[derive(Debug, Clone)]
 pub struct FileSystemPath {
     pub fs: FileSystemVc,
-    pub path: String,
+    pub path: InternedString,
 }
 
 impl FileSystemPath {
     pub fn is_inside(&self, context: &FileSystemPath) -> bool {
-        if self.fs == context.fs && self.path.starts_with(&context.path) {
+        if self.fs == context.fs && self.path.starts_with(&*context.path) {
             if context.path.is_empty() {
                 true
             } else {
@@ -882,7 +1675,7 @@ impl FileSystemPath {
     }
 
     pub fn is_inside_or_equal(&self, context: &FileSystemPath) -> bool {
-        if self.fs == context.fs && self.path.starts_with(&context.path) {
+        if self.fs == context.fs && self.path.starts_with(&*context.path) {
             if context.path.is_empty() {
                 true
             } else {
@@ -907,7 +1700,7 @@ impl FileSystemPath {
         if self.fs != inner.fs {
             return None;
         }
-        let path = inner.path.strip_prefix(&self.path)?;
+        let path = inner.path.strip_prefix(&*self.path)?;
         if self.path.is_empty() {
             Some(path)
         } else if let Some(stripped) = path.strip_prefix('/') {
@@ -1031,7 +1824,10 @@ impl FileSystemPathVc {
             "path {} must be normalized",
             path,
         );
-        Self::cell(FileSystemPath { fs, path })
+        Self::cell(FileSystemPath {
+            fs,
+            path: path.into(),
+        })
     }
 
     /// Adds a subpath to the current path. The /-separate path argument might
@@ -1112,7 +1908,7 @@ impl FileSystemPathVc {
     pub async fn try_join_inside(self, path: &str) -> Result<FileSystemPathOptionVc> {
         let this = self.await?;
         if let Some(path) = join_path(&this.path, path) {
-            if path.starts_with(&this.path) {
+            if path.starts_with(&*this.path) {
                 return Ok(FileSystemPathOptionVc::cell(Some(
                     Self::new_normalized(this.fs, path).resolve().await?,
                 )));
@@ -1126,6 +1922,18 @@ impl FileSystemPathVc {
         read_glob(self, glob, include_dot_files)
     }
 
+    /// Like [FileSystemPathVc::read_glob], but doesn't descend into
+    /// directories and doesn't return files which are ignored by the
+    /// `.gitignore` and `.ignore` files within this directory.
+    #[turbo_tasks::function]
+    pub async fn read_glob_respecting_ignore_files(
+        self,
+        glob: GlobVc,
+        include_dot_files: bool,
+    ) -> ReadGlobResultVc {
+        read_glob_respecting_ignore_files(self, glob, include_dot_files)
+    }
+
     #[turbo_tasks::function]
     pub fn root(self) -> Self {
         self.fs().root()
@@ -1207,12 +2015,12 @@ pub async fn rebase(
     let new_path;
     if old_base.path.is_empty() {
         if new_base.path.is_empty() {
-            new_path = fs_path.path.clone();
+            new_path = fs_path.path.to_string();
         } else {
             new_path = [new_base.path.as_str(), "/", &fs_path.path].concat();
         }
     } else {
-        let base_path = [&old_base.path, "/"].concat();
+        let base_path = [old_base.path.as_str(), "/"].concat();
         if !fs_path.path.starts_with(&base_path) {
             bail!(
                 "rebasing {} from {} onto {} doesn't work because it's not part of the source path",
@@ -1242,6 +2050,81 @@ impl FileSystemPathVc {
         self.fs().read_link(self)
     }
 
+    /// Returns the path with the casing of the actual entries, e.g. to
+    /// report imports with a wrong casing, which only work on a
+    /// case-insensitive file system. Entries are matched case-insensitively,
+    /// preferring an exact match. Returns None when there is no such entry.
+    #[turbo_tasks::function]
+    pub async fn canonical_casing(self) -> Result<FileSystemPathOptionVc> {
+        let this = self.await?;
+        if this.is_root() {
+            return Ok(FileSystemPathOptionVc::cell(Some(self)));
+        }
+        let Some(parent) = *self.parent().canonical_casing().await? else {
+            return Ok(FileSystemPathOptionVc::cell(None));
+        };
+        let (_, file_name) = this.split_file_name();
+        let dir_content = parent.read_dir().await?;
+        let DirectoryContent::Entries(entries) = &*dir_content else {
+            return Ok(FileSystemPathOptionVc::cell(None));
+        };
+        let name = if entries.get(file_name).is_some() {
+            Some(file_name)
+        } else {
+            let lowercase_name = file_name.to_lowercase();
+            entries
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .filter(|name| name.to_lowercase() == lowercase_name)
+                .min()
+        };
+        Ok(FileSystemPathOptionVc::cell(match name {
+            Some(name) => Some(parent.join(name).resolve().await?),
+            None => None,
+        }))
+    }
+
+    /// A xxh3 hash of the content of the file, or of the names and contents
+    /// of everything within the directory, recursively. Symlinks are hashed by
+    /// their target and not followed.
+    ///
+    /// Every directory is hashed in its own task, so a change only rehashes
+    /// the directories up to the root.
+    #[turbo_tasks::function]
+    pub async fn content_hash(self) -> Result<U64Vc> {
+        let dir_content = self.read_dir().await?;
+        let DirectoryContent::Entries(entries) = &*dir_content else {
+            return Ok(self.read().hash());
+        };
+        let mut entries = entries.iter().collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut hasher = Xxh3Hash64Hasher::new();
+        hasher.write_value(HASH_TAG_DIRECTORY);
+        for (name, entry) in entries {
+            hasher.write_ref(name);
+            match entry {
+                DirectoryEntry::File(path) => {
+                    hasher.write_value(HASH_TAG_FILE);
+                    hasher.write_value(*path.read().hash().await?);
+                }
+                DirectoryEntry::Directory(path) => {
+                    hasher.write_value(HASH_TAG_DIRECTORY);
+                    hasher.write_value(*path.content_hash().await?);
+                }
+                DirectoryEntry::Symlink(path) => {
+                    hasher.write_value(HASH_TAG_SYMLINK);
+                    if let LinkContent::Link { target, .. } = &*path.read_link().await? {
+                        hasher.write_ref(target);
+                    }
+                }
+                DirectoryEntry::Other(_) | DirectoryEntry::Error => {
+                    hasher.write_value(HASH_TAG_OTHER);
+                }
+            }
+        }
+        Ok(U64Vc::cell(hasher.finish()))
+    }
+
     #[turbo_tasks::function]
     pub fn read_json(self) -> FileJsonContentVc {
         self.fs().read(self).parse_json()
@@ -1511,7 +2394,7 @@ impl FileContent {
             return Ok(FileComparison::Create);
         };
         // If the meta is different, we need to rewrite the file to update it.
-        if new_file.meta != old_meta.into() {
+        if new_file.meta != FileMeta::from(Permissions::from(old_meta.permissions())) {
             return Ok(FileComparison::NotEqual);
         }
 
@@ -1553,7 +2436,7 @@ bitflags! {
 }
 
 #[turbo_tasks::value(shared)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum LinkContent {
     // for the relative link, the target is raw value read from the link
     // for the absolute link, the target is stripped of the root path while reading
@@ -1584,7 +2467,7 @@ impl File {
         file.read_to_end(&mut output).await?;
 
         Ok(File {
-            meta: metadata.into(),
+            meta: Permissions::from(metadata.permissions()).into(),
             content: Rope::from(output),
         })
     }
@@ -1746,15 +2629,87 @@ pub struct FileMeta {
     #[serde(with = "mime_option_serde")]
     #[turbo_tasks(trace_ignore)]
     content_type: Option<Mime>,
+    /// The following fields are only set when the [FileMeta] is read with
+    /// [FileSystemPathVc::metadata]. The [FileMeta] of a [File] only carries
+    /// the permissions and the content type, so touching a file doesn't
+    /// change its content.
+    size: u64,
+    /// Nanoseconds since the unix epoch.
+    modified: Option<u64>,
+    file_type: Option<FileSystemEntryType>,
+    /// The unix mode, including the file type bits.
+    mode: Option<u32>,
+    symlink_target: Option<String>,
+}
+
+impl FileMeta {
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
+    pub fn content_type(&self) -> Option<&Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// The size in bytes. For symlinks this is the size of the target.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The time of the last modification in nanoseconds since the unix
+    /// epoch, if the file system supports it.
+    pub fn modified(&self) -> Option<u64> {
+        self.modified
+    }
+
+    pub fn file_type(&self) -> Option<FileSystemEntryType> {
+        self.file_type
+    }
+
+    /// The unix mode, e.g. to preserve the executable bit when emitting a
+    /// file. Always `None` on other platforms.
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+
+    /// The target of the symlink, as it's stored in the link.
+    pub fn symlink_target(&self) -> Option<&str> {
+        self.symlink_target.as_deref()
+    }
+}
+
+impl From<Permissions> for FileMeta {
+    fn from(permissions: Permissions) -> Self {
+        Self {
+            permissions,
+            ..Default::default()
+        }
+    }
 }
 
 impl From<std::fs::Metadata> for FileMeta {
     fn from(meta: std::fs::Metadata) -> Self {
-        let permissions = meta.permissions().into();
+        #[cfg(target_family = "unix")]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(meta.permissions().mode())
+        };
+        #[cfg(not(target_family = "unix"))]
+        let mode = None;
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos() as u64);
 
         Self {
-            permissions,
+            permissions: meta.permissions().into(),
             content_type: None,
+            size: meta.len(),
+            modified,
+            file_type: Some(meta.file_type().into()),
+            mode,
+            symlink_target: None,
         }
     }
 }
@@ -1860,8 +2815,29 @@ impl FileContentVc {
         let this = self.await?;
         Ok(this.lines().into())
     }
+
+    /// A xxh3 hash of the content, e.g. for fingerprinting. Files with equal
+    /// content hash the same, regardless of their metadata.
+    #[turbo_tasks::function]
+    pub async fn hash(self) -> Result<U64Vc> {
+        let mut hasher = Xxh3Hash64Hasher::new();
+        match &*self.await? {
+            FileContent::Content(file) => {
+                hasher.write_value(HASH_TAG_FILE);
+                hasher.write_ref(file.content());
+            }
+            FileContent::NotFound => hasher.write_value(HASH_TAG_NOT_FOUND),
+        }
+        Ok(U64Vc::cell(hasher.finish()))
+    }
 }
 
+const HASH_TAG_NOT_FOUND: u8 = 0;
+const HASH_TAG_FILE: u8 = 1;
+const HASH_TAG_DIRECTORY: u8 = 2;
+const HASH_TAG_SYMLINK: u8 = 3;
+const HASH_TAG_OTHER: u8 = 4;
+
 /// A file's content interpreted as a JSON value.
 #[turbo_tasks::value(shared, serialization = "none")]
 pub enum FileJsonContent {
@@ -2059,6 +3035,31 @@ pub fn register() {
 mod tests {
     use super::{virtual_fs::VirtualFileSystemVc, *};
 
+    #[test]
+    fn compares_content_of_changed_files() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("index.js");
+        std::fs::write(&path, "export default 1")?;
+        let key = invalidator_key(&path, false);
+        let watcher = DiskWatcher::default();
+
+        watcher.remember_content(key.clone(), &File::from("export default 1").into())?;
+        assert!(!watcher.is_content_unchanged(&path, false));
+
+        watcher.compare_content.store(true, Ordering::Relaxed);
+        watcher.remember_content(key.clone(), &File::from("export default 1").into())?;
+        assert!(watcher.is_content_unchanged(&path, false));
+        std::fs::write(&path, "export default 2")?;
+        assert!(!watcher.is_content_unchanged(&path, false));
+        std::fs::remove_file(&path)?;
+        assert!(!watcher.is_content_unchanged(&path, false));
+
+        watcher.remember_content(key, &FileContent::NotFound)?;
+        std::fs::write(&path, "export default 1")?;
+        assert!(!watcher.is_content_unchanged(&path, false));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn with_extension() {
         crate::register();
@@ -2124,4 +3125,364 @@ mod tests {
         .await
         .unwrap()
     }
+
+    #[tokio::test]
+    async fn content_hash() -> Result<()> {
+        crate::register();
+
+        let write_tree = |files: &[(&str, &str)]| -> Result<tempfile::TempDir> {
+            let dir = tempfile::TempDir::new()?;
+            for (path, content) in files {
+                let path = dir.path().join(path);
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                std::fs::write(path, content)?;
+            }
+            Ok(dir)
+        };
+        let a = write_tree(&[("src/index.js", "a"), ("package.json", "{}")])?;
+        let b = write_tree(&[("src/index.js", "a"), ("package.json", "{}")])?;
+        let c = write_tree(&[("src/index.js", "c"), ("package.json", "{}")])?;
+        let roots = [a.path(), b.path(), c.path()].map(|path| path.to_string_lossy().to_string());
+
+        let harness =
+            turbo_tasks_testing::TestHarness::new(turbo_tasks_memory::MemoryBackend::default());
+        let hashes = harness
+            .run(async move {
+                let mut hashes = Vec::new();
+                for root in roots {
+                    let fs = DiskFileSystemVc::new("test".to_string(), root);
+                    let root = fs.root();
+                    hashes.push((
+                        *root.content_hash().await?,
+                        *root.join("src/index.js").read().hash().await?,
+                    ));
+                }
+                Ok(hashes)
+            })
+            .await?;
+
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0].0, hashes[2].0);
+        assert_ne!(hashes[0].1, hashes[2].1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn canonical_casing() -> Result<()> {
+        crate::register();
+
+        let dir = tempfile::TempDir::new()?;
+        std::fs::create_dir(dir.path().join("Src"))?;
+        std::fs::write(dir.path().join("Src/Index.js"), "")?;
+        let root = dir.path().to_string_lossy().to_string();
+        #[cfg(target_os = "linux")]
+        assert!(!is_case_insensitive(dir.path()));
+
+        let harness =
+            turbo_tasks_testing::TestHarness::new(turbo_tasks_memory::MemoryBackend::default());
+        harness
+            .run(async move {
+                let fs = DiskFileSystemVc::new("test".to_string(), root).as_file_system();
+                let root = fs.root();
+                let canonical = root.join("src/index.js").canonical_casing().await?;
+                assert_eq!(
+                    &*canonical.unwrap().await?.path,
+                    "Src/Index.js",
+                    "the casing of the entries on disk should be used"
+                );
+                let missing = root.join("src/other.js").canonical_casing().await?;
+                assert!(missing.is_none());
+                Ok(())
+            })
+            .await
+    }
+
+    #[cfg(target_family = "unix")]
+    #[tokio::test]
+    async fn metadata() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        crate::register();
+
+        let dir = tempfile::TempDir::new()?;
+        std::fs::write(dir.path().join("run.sh"), "#!/bin/sh\n")?;
+        std::fs::set_permissions(
+            dir.path().join("run.sh"),
+            std::fs::Permissions::from_mode(0o755),
+        )?;
+        std::os::unix::fs::symlink("run.sh", dir.path().join("link"))?;
+        let root = dir.path().to_string_lossy().to_string();
+
+        let harness =
+            turbo_tasks_testing::TestHarness::new(turbo_tasks_memory::MemoryBackend::default());
+        harness
+            .run(async move {
+                let fs = DiskFileSystemVc::new("test".to_string(), root).as_file_system();
+                let root = fs.root();
+
+                let meta = root.join("run.sh").metadata().await?;
+                assert_eq!(meta.size(), 10);
+                assert_eq!(meta.file_type(), Some(FileSystemEntryType::File));
+                assert_eq!(meta.permissions(), Permissions::Executable);
+                assert_eq!(meta.mode().map(|mode| mode & 0o777), Some(0o755));
+                assert!(meta.modified().is_some());
+                assert_eq!(meta.symlink_target(), None);
+
+                let meta = root.join("link").metadata().await?;
+                assert_eq!(meta.file_type(), Some(FileSystemEntryType::Symlink));
+                assert_eq!(meta.symlink_target(), Some("run.sh"));
+                assert_eq!(meta.size(), 10);
+
+                let meta = root.metadata().await?;
+                assert_eq!(meta.file_type(), Some(FileSystemEntryType::Directory));
+                Ok(())
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn opaque_directories() -> Result<()> {
+        crate::register();
+
+        let dir = tempfile::TempDir::new()?;
+        for path in [
+            "generated/a.txt",
+            "generated/nested/b.txt",
+            "generated-other/c.txt",
+            "src/d.txt",
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "content")?;
+        }
+        let root = dir.path().to_string_lossy().to_string();
+        let root_path = dir.path().to_path_buf();
+
+        let harness =
+            turbo_tasks_testing::TestHarness::new(turbo_tasks_memory::MemoryBackend::default());
+        harness
+            .run(async move {
+                let fs = DiskFileSystemVc::new_with_opaque_directories(
+                    "test".to_string(),
+                    root,
+                    vec!["generated".to_string()],
+                );
+                let root = fs.root();
+                for path in [
+                    "generated/a.txt",
+                    "generated/nested/b.txt",
+                    "generated-other/c.txt",
+                    "src/d.txt",
+                ] {
+                    let content = root.join(path).read().await?;
+                    let FileContent::Content(file) = &*content else {
+                        panic!("{path} should exist");
+                    };
+                    assert_eq!(file.content().to_str()?, "content");
+                }
+                let DirectoryContent::Entries(entries) =
+                    &*root.join("generated").read_dir().await?
+                else {
+                    panic!("generated should be a directory");
+                };
+                assert_eq!(entries.len(), 2);
+
+                let fs = fs.await?;
+                // Reads within the opaque directory share a single invalidator
+                // for the whole directory.
+                let opaque_keys = fs
+                    .opaque_invalidator_map
+                    .lock()
+                    .unwrap()
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                assert_eq!(opaque_keys, [path_to_key(root_path.join("generated"))]);
+                let mut keys = fs
+                    .content_invalidator_map
+                    .lock()
+                    .unwrap()
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                keys.sort();
+                assert_eq!(
+                    keys,
+                    [
+                        path_to_key(root_path.join("generated-other/c.txt")),
+                        path_to_key(root_path.join("src/d.txt")),
+                    ]
+                );
+                assert!(fs.dir_invalidator_map.lock().unwrap().is_empty());
+                Ok(())
+            })
+            .await
+    }
+
+    #[cfg(target_family = "unix")]
+    #[tokio::test]
+    async fn compare_content_invalidates_metadata() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        crate::register();
+
+        let dir = tempfile::TempDir::new()?;
+        let file_path = dir.path().join("index.js");
+        std::fs::write(&file_path, "export default 1")?;
+        std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o644))?;
+        let root = dir.path().to_string_lossy().to_string();
+
+        let harness =
+            turbo_tasks_testing::TestHarness::new(turbo_tasks_memory::MemoryBackend::default());
+        let fs = harness
+            .run(async move {
+                let fs = DiskFileSystemVc::new("test".to_string(), root);
+                fs.await?.start_watching_with_options(WatchOptions {
+                    compare_content: true,
+                    ..Default::default()
+                })?;
+                Ok(fs)
+            })
+            .await?;
+        let read = move || async move {
+            let path = fs.root().join("index.js");
+            let content = path.read().await?;
+            assert!(matches!(&*content, FileContent::Content(_)));
+            Ok(path.metadata().await?.mode().unwrap() & 0o777)
+        };
+        assert_eq!(harness.run(read()).await?, 0o644);
+
+        // Like a touch, a chmod leaves the content as it is.
+        std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o755))?;
+        let (mode, executions) = turbo_tasks_testing::retry::retry_async(
+            (),
+            |_| async {
+                let (mode, executions) = harness.run_counting_executions(read()).await?;
+                if mode == 0o644 {
+                    bail!("the metadata hasn't been invalidated");
+                }
+                Ok::<_, anyhow::Error>((mode, executions))
+            },
+            10,
+            Duration::from_millis(50),
+        )
+        .await?;
+        assert_eq!(mode, 0o755);
+        assert_eq!(executions.functions(), ["DiskFileSystem::metadata"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn atomic_writes() -> Result<()> {
+        crate::register();
+
+        let dir = tempfile::TempDir::new()?;
+        std::fs::write(dir.path().join("chunk.js"), "old")?;
+        let root = dir.path().to_string_lossy().to_string();
+
+        let harness =
+            turbo_tasks_testing::TestHarness::new(turbo_tasks_memory::MemoryBackend::default());
+        harness
+            .run(async move {
+                let options = DiskFileSystemOptions {
+                    atomic_writes: true,
+                    fsync: true,
+                    ..Default::default()
+                };
+                let fs =
+                    DiskFileSystemVc::new_with_options("test".to_string(), root, options.cell())
+                        .as_file_system();
+                let content: FileContentVc = File::from("new").into();
+                fs.root()
+                    .join("chunk.js")
+                    .write(content)
+                    .strongly_consistent()
+                    .await?;
+                fs.root()
+                    .join("nested/chunk.js")
+                    .write(content)
+                    .strongly_consistent()
+                    .await?;
+                Ok(())
+            })
+            .await?;
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("chunk.js"))?, "new");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("nested/chunk.js"))?,
+            "new"
+        );
+        let names = std::fs::read_dir(dir.path())?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        assert!(
+            names.iter().all(|name| !name.ends_with(".tmp")),
+            "temporary files should be renamed: {names:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rewrites_externally_changed_files() -> Result<()> {
+        crate::register();
+
+        let dir = tempfile::TempDir::new()?;
+        let root = dir.path().to_string_lossy().to_string();
+        let chunk_path = dir.path().join("chunk.js");
+
+        let harness =
+            turbo_tasks_testing::TestHarness::new(turbo_tasks_memory::MemoryBackend::default());
+        let fs = harness
+            .run(
+                async move { Ok(DiskFileSystemVc::new("test".to_string(), root).as_file_system()) },
+            )
+            .await?;
+        let write = |text: &'static str| {
+            harness.run(async move {
+                let content: FileContentVc = File::from(text).into();
+                fs.root()
+                    .join("chunk.js")
+                    .write(content)
+                    .strongly_consistent()
+                    .await?;
+                Ok(())
+            })
+        };
+
+        write("emitted").await?;
+        assert_eq!(std::fs::read_to_string(&chunk_path)?, "emitted");
+        let modified = std::fs::metadata(&chunk_path)?.modified()?;
+
+        // unchanged content isn't written again
+        write("emitted").await?;
+        assert_eq!(std::fs::metadata(&chunk_path)?.modified()?, modified);
+
+        std::fs::write(&chunk_path, "changed by someone else")?;
+        write("emitted").await?;
+        assert_eq!(std::fs::read_to_string(&chunk_path)?, "emitted");
+        Ok(())
+    }
+
+    #[test]
+    fn ignored_paths() {
+        let ignored = [".git", "node_modules/.cache", "**/dist"]
+            .into_iter()
+            .map(|glob| Glob::parse(glob).unwrap())
+            .collect::<Vec<_>>();
+        let root = Path::new("/root/project");
+        let is_ignored = |path: &str| super::is_ignored(&ignored, root, &root.join(path));
+
+        assert!(is_ignored(".git"));
+        assert!(is_ignored(".git/objects/ab"));
+        assert!(is_ignored("node_modules/.cache/babel/index.json"));
+        assert!(is_ignored("packages/app/dist/index.js"));
+        assert!(!is_ignored("node_modules/react/index.js"));
+        assert!(!is_ignored("src/.gitignore"));
+        assert!(!super::is_ignored(&ignored, root, root));
+        assert!(!super::is_ignored(
+            &ignored,
+            root,
+            Path::new("/elsewhere/.git")
+        ));
+    }
 }
@@ -0,0 +1,231 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    fs::{self, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::{Context, Result};
+
+/// How often [FileLock::acquire] checks whether a held lock has been
+/// released, unless configured otherwise.
+pub const DEFAULT_LOCK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Options for [FileLock::acquire].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FileLockOptions {
+    /// Gives up with a [FileLockTimeout] error when the lock couldn't be
+    /// acquired within this time. Waits forever when `None`.
+    pub timeout: Option<Duration>,
+    /// Considers a lock as abandoned, e.g. by a crashed process, when the lock
+    /// file is older than this, and takes it over. Should be longer than any
+    /// process holds the lock. Never takes over locks when `None`.
+    pub stale_after: Option<Duration>,
+    pub poll_interval: Duration,
+}
+
+impl Default for FileLockOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            stale_after: None,
+            poll_interval: DEFAULT_LOCK_POLL_INTERVAL,
+        }
+    }
+}
+
+/// Returned by [FileLock::acquire] when another process held the lock for
+/// longer than [FileLockOptions::timeout].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileLockTimeout {
+    pub path: PathBuf,
+    /// The process id written by the holder of the lock, if it could be read.
+    pub holder: Option<u32>,
+}
+
+impl Display for FileLockTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out waiting for the lock {}", self.path.display())?;
+        if let Some(holder) = self.holder {
+            write!(f, " held by process {holder}")?;
+        }
+        write!(
+            f,
+            ". If no other process is using this directory, the lock file can be deleted."
+        )
+    }
+}
+
+impl std::error::Error for FileLockTimeout {}
+
+/// An advisory lock which allows multiple processes sharing a directory, e.g.
+/// an output or cache directory, to coordinate their writes.
+///
+/// The lock is a file which is created exclusively and contains the id of the
+/// holding process. It's released by deleting the file when the [FileLock] is
+/// dropped. Being advisory, it only protects against processes which use the
+/// same lock file.
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Acquires the lock at `path`, waiting for other holders to release it
+    /// according to the `options`.
+    pub async fn acquire(path: impl Into<PathBuf>, options: FileLockOptions) -> Result<Self> {
+        let path = path.into();
+        let start = Instant::now();
+        loop {
+            if let Some(lock) = Self::try_acquire(&path, options.stale_after)? {
+                return Ok(lock);
+            }
+            if let Some(timeout) = options.timeout {
+                if start.elapsed() >= timeout {
+                    let holder = read_holder(&path);
+                    return Err(FileLockTimeout { path, holder }.into());
+                }
+            }
+            tokio::time::sleep(options.poll_interval).await;
+        }
+    }
+
+    /// Acquires the lock at `path` when it's not held by another process,
+    /// or has been held for longer than `stale_after`.
+    pub fn try_acquire(
+        path: impl Into<PathBuf>,
+        stale_after: Option<Duration>,
+    ) -> Result<Option<Self>> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory {}", parent.display()))?;
+        }
+        match create_lock_file(&path) {
+            Ok(()) => return Ok(Some(Self { path })),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to create lock file {}", path.display()))
+            }
+        }
+        let Some(stale_after) = stale_after else {
+            return Ok(None);
+        };
+        if !is_stale(&path, stale_after) {
+            return Ok(None);
+        }
+        // Another process might take over the stale lock at the same time, but
+        // only one of them can create the new lock file
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to remove stale lock {}", path.display()))
+            }
+        }
+        match create_lock_file(&path) {
+            Ok(()) => Ok(Some(Self { path })),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => Ok(None),
+            Err(err) => {
+                Err(err).with_context(|| format!("failed to create lock file {}", path.display()))
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            if err.kind() != ErrorKind::NotFound {
+                tracing::warn!("failed to release lock {}: {}", self.path.display(), err);
+            }
+        }
+    }
+}
+
+fn create_lock_file(path: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    write!(file, "{}", std::process::id())
+}
+
+fn is_stale(path: &Path, stale_after: Duration) -> bool {
+    let Ok(modified) = fs::metadata(path).and_then(|metadata| metadata.modified()) else {
+        // The lock has been released in the meantime
+        return false;
+    };
+    SystemTime::now()
+        .duration_since(modified)
+        .map_or(false, |age| age >= stale_after)
+}
+
+fn read_holder(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+
+    use super::{FileLock, FileLockOptions, FileLockTimeout};
+
+    #[tokio::test]
+    async fn excludes_other_holders() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("cache/.lock");
+
+        let lock = FileLock::acquire(&path, FileLockOptions::default()).await?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            std::process::id().to_string()
+        );
+        assert!(FileLock::try_acquire(&path, None)?.is_none());
+
+        let err = FileLock::acquire(
+            &path,
+            FileLockOptions {
+                timeout: Some(Duration::from_millis(100)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+        let timeout = err.downcast_ref::<FileLockTimeout>().unwrap();
+        assert_eq!(timeout.holder, Some(std::process::id()));
+
+        let waiting = tokio::spawn({
+            let path = path.clone();
+            async move { FileLock::acquire(path, FileLockOptions::default()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        drop(lock);
+        let lock = waiting.await??;
+        drop(lock);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn takes_over_stale_locks() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join(".lock");
+        std::fs::write(&path, "0")?;
+
+        assert!(FileLock::try_acquire(&path, Some(Duration::from_secs(60)))?.is_none());
+        let lock = FileLock::try_acquire(&path, Some(Duration::ZERO))?;
+        assert!(lock.is_some());
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            std::process::id().to_string()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,347 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+use anyhow::{bail, Result};
+use auto_hash_map::{AutoMap, AutoSet};
+use turbo_tasks::{
+    get_invalidator, primitives::StringVc, CompletionVc, InvalidationBatch, Invalidator,
+    ValueToString, ValueToStringVc,
+};
+
+use crate::{
+    util::normalize_path, DirectoryContent, DirectoryContentVc, DirectoryEntry, FileContent,
+    FileContentVc, FileMetaVc, FileSystem, FileSystemPathVc, FileSystemVc, LinkContent,
+    LinkContentVc,
+};
+
+/// A [FileSystem] which layers in-memory files on top of a `base`
+/// [FileSystem], e.g. a [DiskFileSystem](crate::DiskFileSystem). Frameworks
+/// use it for generated modules (route manifests, env modules, ...) which
+/// shouldn't be written to disk.
+///
+/// Reads prefer the overlay. An entry of [FileContent::NotFound] hides the
+/// file of the `base`. Changing an entry invalidates all tasks which have read
+/// it or listed one of its parent directories. Writes always go to the `base`.
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new")]
+pub struct OverlayFileSystem {
+    name: String,
+    base: FileSystemVc,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    state: Mutex<OverlayState>,
+}
+
+#[derive(Default)]
+struct OverlayState {
+    /// Keyed by normalized paths relative to the root.
+    entries: BTreeMap<String, FileContent>,
+    /// Tasks which have read a path, keyed by that path.
+    readers: HashMap<String, AutoSet<Invalidator>>,
+    /// Tasks which have read a directory, keyed by that directory.
+    dir_readers: HashMap<String, AutoSet<Invalidator>>,
+}
+
+impl OverlayFileSystemVc {
+    /// Creates a new [OverlayFileSystemVc] without any entries.
+    ///
+    /// NOTE: This function is not a `turbo_tasks::function`, as every
+    /// overlay has its own entries and must not be shared with another call.
+    pub fn new(name: String, base: FileSystemVc) -> Self {
+        Self::cell(OverlayFileSystem {
+            name,
+            base,
+            state: Mutex::new(OverlayState::default()),
+        })
+    }
+}
+
+impl OverlayFileSystem {
+    /// Adds or replaces the entry at `path`, which is relative to the root.
+    pub fn set(&self, path: &str, content: FileContent) -> Result<()> {
+        let path = normalize(path)?;
+        let mut state = self.state.lock().unwrap();
+        state.entries.insert(path.clone(), content);
+        invalidate(&mut state, &path);
+        Ok(())
+    }
+
+    /// Removes the entry at `path`, so the file of the `base` is visible
+    /// again.
+    pub fn remove(&self, path: &str) -> Result<()> {
+        let path = normalize(path)?;
+        let mut state = self.state.lock().unwrap();
+        if state.entries.remove(&path).is_some() {
+            invalidate(&mut state, &path);
+        }
+        Ok(())
+    }
+
+    /// Returns the entry at `path` and invalidates the current task when it
+    /// changes, including when it's added or removed.
+    fn read_entry(&self, path: &str) -> Option<FileContent> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .readers
+            .entry(path.to_string())
+            .or_default()
+            .insert(get_invalidator());
+        state.entries.get(path).cloned()
+    }
+
+    async fn base_path(&self, path: &str) -> Result<FileSystemPathVc> {
+        Ok(self.base.root().resolve().await?.join(path))
+    }
+}
+
+fn normalize(path: &str) -> Result<String> {
+    let Some(normalized) = normalize_path(path) else {
+        bail!("path {} leaves the root of the overlay file system", path);
+    };
+    Ok(normalized)
+}
+
+/// Invalidates the readers of `path` and of all its parent directories, as
+/// the entry might add a new directory at any level.
+fn invalidate(state: &mut OverlayState, path: &str) {
+    let mut batch = InvalidationBatch::new();
+    for reader in state.readers.remove(path).into_iter().flatten() {
+        batch.add(reader);
+    }
+    let mut dir = path;
+    loop {
+        dir = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+        for reader in state.dir_readers.remove(dir).into_iter().flatten() {
+            batch.add(reader);
+        }
+        if dir.is_empty() {
+            break;
+        }
+    }
+    batch.apply();
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for OverlayFileSystem {
+    #[turbo_tasks::function]
+    async fn read(&self, fs_path: FileSystemPathVc) -> Result<FileContentVc> {
+        let path = fs_path.await?;
+        if let Some(content) = self.read_entry(&path.path) {
+            return Ok(content.cell());
+        }
+        Ok(self.base_path(&path.path).await?.read())
+    }
+
+    #[turbo_tasks::function]
+    async fn read_link(&self, fs_path: FileSystemPathVc) -> Result<LinkContentVc> {
+        let path = fs_path.await?;
+        Ok(match self.read_entry(&path.path) {
+            Some(FileContent::Content(_)) => LinkContent::Invalid.cell(),
+            Some(FileContent::NotFound) => LinkContent::NotFound.cell(),
+            None => self.base_path(&path.path).await?.read_link(),
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn read_dir(&self, fs_path: FileSystemPathVc) -> Result<DirectoryContentVc> {
+        let path = fs_path.await?;
+        let dir = &*path.path;
+        let overlay_entries = {
+            let mut state = self.state.lock().unwrap();
+            state
+                .dir_readers
+                .entry(dir.to_string())
+                .or_default()
+                .insert(get_invalidator());
+            let prefix = if dir.is_empty() {
+                String::new()
+            } else {
+                format!("{dir}/")
+            };
+            state
+                .entries
+                .range(prefix.clone()..)
+                .take_while(|(key, _)| key.starts_with(&prefix))
+                .map(|(key, content)| (key[prefix.len()..].to_string(), content.is_content()))
+                .collect::<Vec<_>>()
+        };
+
+        let base_content = self.base_path(dir).await?.read_dir().await?;
+        let mut entries = match &*base_content {
+            DirectoryContent::Entries(entries) => {
+                let mut converted = AutoMap::with_capacity(entries.len());
+                for (name, entry) in entries {
+                    let path = fs_path.join(name);
+                    let entry = match entry {
+                        DirectoryEntry::File(_) => DirectoryEntry::File(path),
+                        DirectoryEntry::Directory(_) => DirectoryEntry::Directory(path),
+                        DirectoryEntry::Symlink(_) => DirectoryEntry::Symlink(path),
+                        DirectoryEntry::Other(_) => DirectoryEntry::Other(path),
+                        DirectoryEntry::Error => DirectoryEntry::Error,
+                    };
+                    converted.insert(name.clone(), entry);
+                }
+                converted
+            }
+            DirectoryContent::NotFound => {
+                if !overlay_entries.iter().any(|(_, exists)| *exists) {
+                    return Ok(DirectoryContentVc::not_found());
+                }
+                AutoMap::new()
+            }
+        };
+        for (relative_path, exists) in overlay_entries {
+            match relative_path.split_once('/') {
+                Some((name, _)) => {
+                    if exists && !entries.contains_key(&name.to_string()) {
+                        entries.insert(
+                            name.to_string(),
+                            DirectoryEntry::Directory(fs_path.join(name)),
+                        );
+                    }
+                }
+                None if exists => {
+                    let entry = DirectoryEntry::File(fs_path.join(&relative_path));
+                    entries.insert(relative_path, entry);
+                }
+                None => {
+                    entries.remove(&relative_path);
+                }
+            }
+        }
+        Ok(DirectoryContentVc::new(entries))
+    }
+
+    #[turbo_tasks::function]
+    async fn track(&self, fs_path: FileSystemPathVc) -> Result<CompletionVc> {
+        let path = fs_path.await?;
+        if self.read_entry(&path.path).is_some() {
+            return Ok(CompletionVc::new());
+        }
+        Ok(self.base_path(&path.path).await?.track())
+    }
+
+    #[turbo_tasks::function]
+    async fn write(
+        &self,
+        fs_path: FileSystemPathVc,
+        content: FileContentVc,
+    ) -> Result<CompletionVc> {
+        Ok(self.base_path(&fs_path.await?.path).await?.write(content))
+    }
+
+    #[turbo_tasks::function]
+    async fn write_link(
+        &self,
+        fs_path: FileSystemPathVc,
+        target: LinkContentVc,
+    ) -> Result<CompletionVc> {
+        Ok(self
+            .base_path(&fs_path.await?.path)
+            .await?
+            .write_link(target))
+    }
+
+    #[turbo_tasks::function]
+    async fn metadata(&self, fs_path: FileSystemPathVc) -> Result<FileMetaVc> {
+        let path = fs_path.await?;
+        Ok(match self.read_entry(&path.path) {
+            Some(FileContent::Content(file)) => file.meta().clone().cell(),
+            Some(FileContent::NotFound) => bail!("{} not found", path.path),
+            None => self.base_path(&path.path).await?.metadata(),
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for OverlayFileSystem {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> StringVc {
+        StringVc::cell(self.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks_memory::MemoryBackend;
+    use turbo_tasks_testing::TestHarness;
+
+    use super::OverlayFileSystemVc;
+    use crate::{
+        DirectoryContent, File, FileContent, FileContentVc, FileSystem, FileSystemPathVc,
+        FileSystemVc, NullFileSystem,
+    };
+
+    async fn read_string(path: FileSystemPathVc) -> Result<Option<String>> {
+        Ok(match &*path.read().strongly_consistent().await? {
+            FileContent::Content(file) => Some(file.content().to_str()?.to_string()),
+            FileContent::NotFound => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn reads_and_invalidates_overlay_entries() -> Result<()> {
+        crate::register();
+        let harness = TestHarness::new(MemoryBackend::default());
+        let overlay = harness
+            .run(async {
+                let base: FileSystemVc = NullFileSystem.cell().into();
+                Ok(OverlayFileSystemVc::new("overlay".to_string(), base))
+            })
+            .await?;
+
+        harness
+            .run(async move {
+                let fs: FileSystemVc = overlay.into();
+                assert_eq!(read_string(fs.root().join("src/routes.js")).await?, None);
+                let content = fs.root().read_dir().strongly_consistent().await?;
+                assert!(matches!(&*content, DirectoryContent::NotFound));
+                Ok(())
+            })
+            .await?;
+
+        harness
+            .run(async move {
+                overlay
+                    .await?
+                    .set("src/routes.js", File::from("export default []").into())?;
+                Ok(())
+            })
+            .await?;
+
+        harness
+            .run(async move {
+                let fs: FileSystemVc = overlay.into();
+                assert_eq!(
+                    read_string(fs.root().join("src/routes.js"))
+                        .await?
+                        .as_deref(),
+                    Some("export default []")
+                );
+                let content = fs.root().read_dir().strongly_consistent().await?;
+                let DirectoryContent::Entries(entries) = &*content else {
+                    panic!("expected the directory to exist");
+                };
+                assert_eq!(
+                    entries.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+                    ["src"]
+                );
+                Ok(())
+            })
+            .await?;
+
+        harness
+            .run(async move {
+                overlay.await?.remove("src/routes.js")?;
+                let fs: FileSystemVc = overlay.into();
+                let content: FileContentVc = fs.root().join("src/routes.js").read();
+                assert!(matches!(
+                    &*content.strongly_consistent().await?,
+                    FileContent::NotFound
+                ));
+                Ok(())
+            })
+            .await
+    }
+}
@@ -1,8 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
 use anyhow::Result;
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match,
+};
 
-use crate::{glob::GlobVc, DirectoryContent, DirectoryEntry, FileSystemPathVc};
+use crate::{glob::GlobVc, DirectoryContent, DirectoryEntry, FileContent, FileSystemPathVc};
 
 #[turbo_tasks::value]
 #[derive(Default, Debug)]
@@ -13,6 +17,11 @@ pub struct ReadGlobResult {
 
 /// Reads matches of a glob pattern.
 ///
+/// The leading literal directories of the glob (e.g. `src/pages` of
+/// `src/pages/**/*.js`) are joined onto `directory` directly instead of being
+/// read, so only the directory they point to and its subdirectories are
+/// listed.
+///
 /// DETERMINISM: Result is in random order. Either sort result or do not depend
 /// on the order.
 #[turbo_tasks::function]
@@ -21,7 +30,50 @@ pub async fn read_glob(
     glob: GlobVc,
     include_dot_files: bool,
 ) -> Result<ReadGlobResultVc> {
-    read_glob_internal("", directory, glob, include_dot_files).await
+    let prefix = glob.await?.literal_directory_prefix();
+    if prefix.is_empty() {
+        return read_glob_internal("", directory, glob, include_dot_files, None).await;
+    }
+    let mut inner = read_glob_inner(
+        format!("{prefix}/"),
+        directory.join(&prefix),
+        glob,
+        include_dot_files,
+        None,
+    );
+    // Nested like the results of reading every directory of the prefix, with
+    // one level per segment
+    let mut dir = prefix.as_str();
+    loop {
+        let mut result = ReadGlobResult::default();
+        result.inner.insert(dir.to_string(), inner);
+        inner = ReadGlobResultVc::cell(result);
+        match dir.rsplit_once('/') {
+            Some((parent, _)) => dir = parent,
+            None => return Ok(inner),
+        }
+    }
+}
+
+/// Like [read_glob], but skips everything which is ignored by the
+/// `.gitignore` and `.ignore` files within `directory`, and the `.git`
+/// directory. Ignore files in the parent directories of `directory` are not
+/// taken into account.
+#[turbo_tasks::function]
+pub async fn read_glob_respecting_ignore_files(
+    directory: FileSystemPathVc,
+    glob: GlobVc,
+    include_dot_files: bool,
+) -> Result<ReadGlobResultVc> {
+    // The literal prefix can't be skipped, as it might contain ignore files
+    read_glob_internal(
+        "",
+        directory,
+        glob,
+        include_dot_files,
+        Some(IgnoreFilesVc::cell(Vec::new())),
+    )
+    .await
 }
 
 #[turbo_tasks::function]
@@ -30,8 +82,9 @@ async fn read_glob_inner(
     directory: FileSystemPathVc,
     glob: GlobVc,
     include_dot_files: bool,
+    ignore_files: Option<IgnoreFilesVc>,
 ) -> Result<ReadGlobResultVc> {
-    read_glob_internal(&prefix, directory, glob, include_dot_files).await
+    read_glob_internal(&prefix, directory, glob, include_dot_files, ignore_files).await
 }
 
 async fn read_glob_internal(
@@ -39,16 +92,29 @@ async fn read_glob_internal(
     directory: FileSystemPathVc,
     glob: GlobVc,
     include_dot_files: bool,
+    ignore_files: Option<IgnoreFilesVc>,
 ) -> Result<ReadGlobResultVc> {
     let dir = directory.read_dir().await?;
     let mut result = ReadGlobResult::default();
     let glob_value = glob.await?;
+    let (ignore_files, ignore_matchers) = match ignore_files {
+        Some(ignore_files) => {
+            let ignore_files = with_ignore_files_of(prefix.to_string(), directory, ignore_files);
+            (Some(ignore_files), ignore_matchers(&ignore_files.await?))
+        }
+        None => (None, Vec::new()),
+    };
     match &*dir {
         DirectoryContent::Entries(entries) => {
             for item in entries.iter() {
                 match item {
                     (segment, DirectoryEntry::Directory(path)) => {
                         let full_path = format!("{prefix}{segment}");
+                        if ignore_files.is_some()
+                            && (segment == ".git" || is_ignored(&ignore_matchers, &full_path, true))
+                        {
+                            continue;
+                        }
                         let full_path_prefix = format!("{full_path}/");
                         if glob_value.execute(&full_path) {
                             result
@@ -58,12 +124,21 @@ async fn read_glob_internal(
                         if glob_value.execute(&full_path_prefix) {
                             result.inner.insert(
                                 full_path,
-                                read_glob_inner(full_path_prefix, *path, glob, include_dot_files),
+                                read_glob_inner(
+                                    full_path_prefix,
+                                    *path,
+                                    glob,
+                                    include_dot_files,
+                                    ignore_files,
+                                ),
                             );
                         }
                     }
                     (segment, entry) => {
                         let full_path = format!("{prefix}{segment}");
+                        if is_ignored(&ignore_matchers, &full_path, false) {
+                            continue;
+                        }
                         if glob_value.execute(&full_path) {
                             result.results.insert(full_path, *entry);
                         }
@@ -75,3 +150,239 @@ async fn read_glob_internal(
     }
     Ok(ReadGlobResultVc::cell(result))
 }
+
+/// The contents of the ignore files of a traversal, from the outermost to
+/// the innermost directory.
+#[turbo_tasks::value(transparent)]
+struct IgnoreFiles(Vec<IgnoreFile>);
+
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+struct IgnoreFile {
+    /// The directory of the ignore file relative to the root of the
+    /// traversal, with a trailing `/` unless it's the root.
+    dir: String,
+    content: String,
+}
+
+/// Adds the ignore files of `directory`, which is at `prefix` in the
+/// traversal, to the `ignore_files` of its parent directories.
+#[turbo_tasks::function]
+async fn with_ignore_files_of(
+    prefix: String,
+    directory: FileSystemPathVc,
+    ignore_files: IgnoreFilesVc,
+) -> Result<IgnoreFilesVc> {
+    let mut ignore_files = ignore_files.await?.clone_value();
+    // `.ignore` files take precedence, so they are checked first
+    for name in [".gitignore", ".ignore"] {
+        if let FileContent::Content(file) = &*directory.join(name).read().await? {
+            ignore_files.push(IgnoreFile {
+                dir: prefix.clone(),
+                content: file.content().to_str()?.into_owned(),
+            });
+        }
+    }
+    Ok(IgnoreFilesVc::cell(ignore_files))
+}
+
+/// The matchers of the `ignore_files`, from the innermost to the outermost
+/// directory.
+fn ignore_matchers(ignore_files: &[IgnoreFile]) -> Vec<Gitignore> {
+    ignore_files
+        .iter()
+        .rev()
+        .filter_map(|ignore_file| {
+            // The paths are relative to the root of the traversal, so a
+            // virtual root is used for matching
+            let mut builder = GitignoreBuilder::new(Path::new("/").join(&ignore_file.dir));
+            for line in ignore_file.content.lines() {
+                // Invalid lines are skipped, like git does
+                let _ = builder.add_line(None, line);
+            }
+            builder.build().ok()
+        })
+        .collect()
+}
+
+/// Returns true when `path`, which is relative to the root of the traversal,
+/// is ignored. The innermost ignore file with a matching rule decides.
+fn is_ignored(matchers: &[Gitignore], path: &str, is_dir: bool) -> bool {
+    let path = Path::new("/").join(path);
+    for matcher in matchers {
+        if !path.starts_with(matcher.path()) {
+            continue;
+        }
+        match matcher.matched(&path, is_dir) {
+            Match::None => {}
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks_memory::MemoryBackend;
+    use turbo_tasks_testing::TestHarness;
+
+    use super::{read_glob_inner, ReadGlobResultVc};
+    use crate::{glob::GlobVc, DiskFileSystemVc, FileSystem};
+
+    async fn collect_paths(result: ReadGlobResultVc) -> Result<Vec<String>> {
+        let mut paths = Vec::new();
+        let mut queue = vec![result];
+        while let Some(result) = queue.pop() {
+            let result = result.await?;
+            paths.extend(result.results.keys().cloned());
+            queue.extend(result.inner.values().copied());
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    #[tokio::test]
+    async fn reads_globs() -> Result<()> {
+        crate::register();
+        let dir = tempfile::TempDir::new()?;
+        for path in [
+            "src/pages/index.js",
+            "src/pages/blog/post.js",
+            "src/pages/blog/post.css",
+            "src/lib/util.js",
+            "other/index.js",
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "")?;
+        }
+        let root = dir.path().to_string_lossy().to_string();
+
+        let harness = TestHarness::new(MemoryBackend::default());
+        harness
+            .run(async move {
+                let root = DiskFileSystemVc::new("test".to_string(), root).root();
+                assert_eq!(
+                    collect_paths(root.read_glob(GlobVc::new("src/pages/**/*.js"), false)).await?,
+                    ["src/pages/blog/post.js", "src/pages/index.js"]
+                );
+                assert_eq!(
+                    collect_paths(root.read_glob(GlobVc::new("**/index.js"), false)).await?,
+                    ["other/index.js", "src/pages/index.js"]
+                );
+                assert!(
+                    collect_paths(root.read_glob(GlobVc::new("missing/**"), false))
+                        .await?
+                        .is_empty()
+                );
+                Ok(())
+            })
+            .await
+    }
+
+    /// Lists the results and the inner results of every level, with their
+    /// depth.
+    async fn collect_shape(result: ReadGlobResultVc) -> Result<Vec<String>> {
+        let mut shape = Vec::new();
+        let mut queue = vec![(0, result)];
+        while let Some((depth, result)) = queue.pop() {
+            let result = result.await?;
+            shape.extend(result.results.keys().map(|key| format!("{depth} {key}")));
+            for (key, inner) in result.inner.iter() {
+                shape.push(format!("{depth} {key}/"));
+                queue.push((depth + 1, *inner));
+            }
+        }
+        shape.sort();
+        Ok(shape)
+    }
+
+    #[tokio::test]
+    async fn skips_literal_prefix() -> Result<()> {
+        crate::register();
+        let dir = tempfile::TempDir::new()?;
+        for path in [
+            "src/pages/index.js",
+            "src/pages/blog/post.js",
+            "src/lib/util.js",
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "")?;
+        }
+        let root = dir.path().to_string_lossy().to_string();
+
+        let harness = TestHarness::new(MemoryBackend::default());
+        harness
+            .run(async move {
+                let root = DiskFileSystemVc::new("test".to_string(), root).root();
+                let glob = GlobVc::new("src/pages/**/*.js");
+                let shape = collect_shape(root.read_glob(glob, false)).await?;
+                assert_eq!(
+                    shape,
+                    [
+                        "0 src/",
+                        "1 src/pages/",
+                        "2 src/pages/blog/",
+                        "2 src/pages/index.js",
+                        "3 src/pages/blog/post.js"
+                    ]
+                );
+                // Reading every directory results in the same shape
+                assert_eq!(
+                    collect_shape(read_glob_inner(String::new(), root, glob, false, None)).await?,
+                    shape
+                );
+                Ok(())
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn respects_ignore_files() -> Result<()> {
+        crate::register();
+        let dir = tempfile::TempDir::new()?;
+        for (path, content) in [
+            (".gitignore", "dist/\n*.log\n"),
+            ("src/index.js", ""),
+            ("src/debug.log", ""),
+            ("src/generated/.ignore", "*\n!keep.js\n"),
+            ("src/generated/keep.js", ""),
+            ("src/generated/drop.js", ""),
+            ("dist/index.js", ""),
+            (".git/hooks/hook.js", ""),
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, content)?;
+        }
+        let root = dir.path().to_string_lossy().to_string();
+
+        let harness = TestHarness::new(MemoryBackend::default());
+        harness
+            .run(async move {
+                let root = DiskFileSystemVc::new("test".to_string(), root).root();
+                assert_eq!(
+                    collect_paths(
+                        root.read_glob_respecting_ignore_files(GlobVc::new("**/*.*"), true)
+                    )
+                    .await?,
+                    ["src/generated/keep.js", "src/index.js"]
+                );
+                assert_eq!(
+                    collect_paths(root.read_glob(GlobVc::new("**/*.js"), false)).await?,
+                    [
+                        ".git/hooks/hook.js",
+                        "dist/index.js",
+                        "src/generated/drop.js",
+                        "src/generated/keep.js",
+                        "src/index.js"
+                    ]
+                );
+                Ok(())
+            })
+            .await
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{bail, Result};
+use auto_hash_map::AutoMap;
+use turbo_tasks::{primitives::StringVc, CompletionVc, ValueToString, ValueToStringVc};
+
+use crate::{
+    DirectoryContent, DirectoryContentVc, DirectoryEntry, FileContentVc, FileMetaVc, FileSystem,
+    FileSystemPathVc, FileSystemVc, LinkContentVc,
+};
+
+/// A wrapper [FileSystem] which forwards reads and tracking to the `inner`
+/// [FileSystem], but rejects all writes. It guarantees that a build never
+/// mutates e.g. the source tree, even when the file system is passed to
+/// plugin code.
+#[turbo_tasks::value]
+pub struct ReadonlyFileSystem {
+    inner: FileSystemVc,
+}
+
+#[turbo_tasks::value_impl]
+impl ReadonlyFileSystemVc {
+    #[turbo_tasks::function]
+    pub fn new(inner: FileSystemVc) -> Self {
+        ReadonlyFileSystem { inner }.cell()
+    }
+
+    /// Resolves the path of the `inner` [FileSystem] from a path on the
+    /// [ReadonlyFileSystem].
+    #[turbo_tasks::function]
+    async fn inner_path(self, path: FileSystemPathVc) -> Result<FileSystemPathVc> {
+        let this = self.await?;
+        Ok(this.inner.root().resolve().await?.join(&path.await?.path))
+    }
+}
+
+async fn write_error(fs: ReadonlyFileSystemVc, path: FileSystemPathVc) -> Result<CompletionVc> {
+    bail!(
+        "Writing {} is not possible on the read-only file system {}",
+        path.to_string().await?,
+        fs.await?.inner.to_string().await?
+    )
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for ReadonlyFileSystem {
+    #[turbo_tasks::function]
+    fn read(self_vc: ReadonlyFileSystemVc, path: FileSystemPathVc) -> FileContentVc {
+        self_vc.inner_path(path).read()
+    }
+
+    #[turbo_tasks::function]
+    fn read_link(self_vc: ReadonlyFileSystemVc, path: FileSystemPathVc) -> LinkContentVc {
+        self_vc.inner_path(path).read_link()
+    }
+
+    #[turbo_tasks::function]
+    async fn read_dir(
+        self_vc: ReadonlyFileSystemVc,
+        path: FileSystemPathVc,
+    ) -> Result<DirectoryContentVc> {
+        let dir_content = self_vc.inner_path(path).read_dir().await?;
+        let entries = match &*dir_content {
+            DirectoryContent::Entries(e) => e,
+            DirectoryContent::NotFound => return Ok(DirectoryContentVc::not_found()),
+        };
+
+        let mut converted_entries = AutoMap::with_capacity(entries.len());
+        for (name, entry) in entries {
+            use DirectoryEntry::*;
+
+            let entry = match *entry {
+                File(_) => File(path.join(name)),
+                Directory(_) => Directory(path.join(name)),
+                Symlink(_) => Symlink(path.join(name)),
+                Other(_) => Other(path.join(name)),
+                Error => Error,
+            };
+
+            converted_entries.insert(name.clone(), entry);
+        }
+
+        Ok(DirectoryContentVc::new(converted_entries))
+    }
+
+    #[turbo_tasks::function]
+    fn track(self_vc: ReadonlyFileSystemVc, path: FileSystemPathVc) -> CompletionVc {
+        self_vc.inner_path(path).track()
+    }
+
+    #[turbo_tasks::function]
+    async fn write(
+        self_vc: ReadonlyFileSystemVc,
+        path: FileSystemPathVc,
+        _content: FileContentVc,
+    ) -> Result<CompletionVc> {
+        write_error(self_vc, path).await
+    }
+
+    #[turbo_tasks::function]
+    async fn write_link(
+        self_vc: ReadonlyFileSystemVc,
+        path: FileSystemPathVc,
+        _target: LinkContentVc,
+    ) -> Result<CompletionVc> {
+        write_error(self_vc, path).await
+    }
+
+    #[turbo_tasks::function]
+    fn metadata(self_vc: ReadonlyFileSystemVc, path: FileSystemPathVc) -> FileMetaVc {
+        self_vc.inner_path(path).metadata()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ReadonlyFileSystem {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<StringVc> {
+        Ok(StringVc::cell(format!(
+            "read-only {}",
+            self.inner.to_string().await?
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks_memory::MemoryBackend;
+    use turbo_tasks_testing::TestHarness;
+
+    use super::ReadonlyFileSystemVc;
+    use crate::{File, FileContent, FileContentVc, FileSystem, FileSystemVc, NullFileSystem};
+
+    #[tokio::test]
+    async fn rejects_writes() -> Result<()> {
+        crate::register();
+        let harness = TestHarness::new(MemoryBackend::default());
+        harness
+            .run(async {
+                let inner: FileSystemVc = NullFileSystem.cell().into();
+                let fs: FileSystemVc = ReadonlyFileSystemVc::new(inner).into();
+                let path = fs.root().join("index.js");
+
+                let content = path.read().strongly_consistent().await?;
+                assert!(matches!(&*content, FileContent::NotFound));
+
+                let content: FileContentVc = FileContent::from(File::from("")).cell();
+                let Err(err) = path.write(content).strongly_consistent().await else {
+                    panic!("writing to a read-only file system should fail");
+                };
+                assert!(
+                    format!("{err:#}").contains("read-only"),
+                    "unexpected error: {err:#}"
+                );
+                Ok(())
+            })
+            .await
+    }
+}
@@ -0,0 +1,437 @@
+//! Records the inputs a build reads from a [FileSystem] into an [IoFixture]
+//! and serves them back later. Bugs which only occur on a user's machine can
+//! be reproduced hermetically from a recorded fixture and turned into
+//! regression tests.
+
+use std::{collections::BTreeMap, fs, path::Path, sync::Mutex};
+
+use anyhow::{anyhow, Context, Result};
+use auto_hash_map::AutoMap;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{
+    primitives::StringVc, CompletionVc, TransientInstance, ValueToString, ValueToStringVc,
+};
+
+use crate::{
+    DirectoryContent, DirectoryContentVc, DirectoryEntry, File, FileContent, FileContentVc,
+    FileMeta, FileMetaVc, FileSystem, FileSystemPathVc, FileSystemVc, LinkContent, LinkContentVc,
+};
+
+/// All inputs a build has read, keyed by their path relative to the root of
+/// the file system.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct IoFixture {
+    pub files: BTreeMap<String, RecordedFile>,
+    pub links: BTreeMap<String, LinkContent>,
+    /// The entries of directories, or `None` when the directory doesn't exist.
+    pub directories: BTreeMap<String, Option<BTreeMap<String, RecordedEntry>>>,
+    pub metadata: BTreeMap<String, FileMeta>,
+    /// The environment variables of the process.
+    pub env: BTreeMap<String, String>,
+}
+
+impl IoFixture {
+    /// Reads a fixture which has been written by [IoRecording::write].
+    pub fn read(path: &Path) -> Result<Self> {
+        let content =
+            fs::read(path).with_context(|| format!("unable to read fixture {}", path.display()))?;
+        serde_json::from_slice(&content)
+            .with_context(|| format!("unable to parse fixture {}", path.display()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedFile {
+    NotFound,
+    Text { text: String, meta: FileMeta },
+    Binary { bytes: Vec<u8>, meta: FileMeta },
+}
+
+impl RecordedFile {
+    fn new(content: &FileContent) -> Result<Self> {
+        Ok(match content {
+            FileContent::NotFound => RecordedFile::NotFound,
+            FileContent::Content(file) => {
+                let bytes = file.content().to_bytes()?.into_owned();
+                let meta = file.meta().clone();
+                match String::from_utf8(bytes) {
+                    Ok(text) => RecordedFile::Text { text, meta },
+                    Err(err) => RecordedFile::Binary {
+                        bytes: err.into_bytes(),
+                        meta,
+                    },
+                }
+            }
+        })
+    }
+
+    fn to_content(&self) -> FileContent {
+        match self {
+            RecordedFile::NotFound => FileContent::NotFound,
+            RecordedFile::Text { text, meta } => {
+                File::new(meta.clone(), text.clone().into_bytes()).into()
+            }
+            RecordedFile::Binary { bytes, meta } => File::new(meta.clone(), bytes.clone()).into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedEntry {
+    File,
+    Directory,
+    Symlink,
+    Other,
+    Error,
+}
+
+impl RecordedEntry {
+    fn to_entry(self, path: Option<FileSystemPathVc>) -> DirectoryEntry {
+        match (self, path) {
+            (RecordedEntry::File, Some(path)) => DirectoryEntry::File(path),
+            (RecordedEntry::Directory, Some(path)) => DirectoryEntry::Directory(path),
+            (RecordedEntry::Symlink, Some(path)) => DirectoryEntry::Symlink(path),
+            (RecordedEntry::Other, Some(path)) => DirectoryEntry::Other(path),
+            _ => DirectoryEntry::Error,
+        }
+    }
+}
+
+/// Collects the inputs read through [RecordingFileSystem]s and
+/// `RecordingProcessEnv`s which share it.
+#[derive(Default)]
+pub struct IoRecording {
+    fixture: Mutex<IoFixture>,
+}
+
+impl IoRecording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns everything recorded so far.
+    pub fn fixture(&self) -> IoFixture {
+        self.fixture.lock().unwrap().clone()
+    }
+
+    /// Writes everything recorded so far to `path` as JSON.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_vec_pretty(&self.fixture())?;
+        fs::write(path, content)
+            .with_context(|| format!("unable to write fixture {}", path.display()))
+    }
+
+    pub fn record_env(&self, env: impl IntoIterator<Item = (String, String)>) {
+        self.fixture.lock().unwrap().env.extend(env);
+    }
+
+    fn with_fixture(&self, f: impl FnOnce(&mut IoFixture)) {
+        f(&mut self.fixture.lock().unwrap())
+    }
+}
+
+/// A [FileSystem] which records everything read from the inner file system
+/// into an [IoRecording]. Writes are passed through.
+#[turbo_tasks::value(serialization = "none")]
+pub struct RecordingFileSystem {
+    inner: FileSystemVc,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    recording: TransientInstance<IoRecording>,
+}
+
+#[turbo_tasks::value_impl]
+impl RecordingFileSystemVc {
+    #[turbo_tasks::function]
+    pub fn new(inner: FileSystemVc, recording: TransientInstance<IoRecording>) -> Self {
+        RecordingFileSystem { inner, recording }.cell()
+    }
+}
+
+impl RecordingFileSystem {
+    async fn inner_path(&self, fs_path: FileSystemPathVc) -> Result<(String, FileSystemPathVc)> {
+        let path = fs_path.await?.path.to_string();
+        let inner_path = FileSystemPathVc::new_normalized(self.inner, path.clone());
+        Ok((path, inner_path))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for RecordingFileSystem {
+    #[turbo_tasks::function]
+    async fn read(&self, fs_path: FileSystemPathVc) -> Result<FileContentVc> {
+        let (path, inner_path) = self.inner_path(fs_path).await?;
+        let content = inner_path.read();
+        let file = RecordedFile::new(&*content.await?)?;
+        self.recording.with_fixture(|fixture| {
+            fixture.files.insert(path, file);
+        });
+        Ok(content)
+    }
+
+    #[turbo_tasks::function]
+    async fn read_link(&self, fs_path: FileSystemPathVc) -> Result<LinkContentVc> {
+        let (path, inner_path) = self.inner_path(fs_path).await?;
+        let link = inner_path.read_link();
+        let recorded = (*link.await?).clone();
+        self.recording.with_fixture(|fixture| {
+            fixture.links.insert(path, recorded);
+        });
+        Ok(link)
+    }
+
+    #[turbo_tasks::function]
+    async fn read_dir(&self, fs_path: FileSystemPathVc) -> Result<DirectoryContentVc> {
+        let (path, inner_path) = self.inner_path(fs_path).await?;
+        let fs = fs_path.await?.fs;
+        let (recorded, content) = match &*inner_path.read_dir().await? {
+            DirectoryContent::NotFound => (None, DirectoryContentVc::not_found()),
+            DirectoryContent::Entries(entries) => {
+                let mut recorded = BTreeMap::new();
+                let mut converted = AutoMap::with_capacity(entries.len());
+                for (name, entry) in entries {
+                    let (kind, entry_path) = match *entry {
+                        DirectoryEntry::File(path) => (RecordedEntry::File, Some(path)),
+                        DirectoryEntry::Directory(path) => (RecordedEntry::Directory, Some(path)),
+                        DirectoryEntry::Symlink(path) => (RecordedEntry::Symlink, Some(path)),
+                        DirectoryEntry::Other(path) => (RecordedEntry::Other, Some(path)),
+                        DirectoryEntry::Error => (RecordedEntry::Error, None),
+                    };
+                    recorded.insert(name.clone(), kind);
+                    // Entries are converted to paths of this file system, so reading them is
+                    // recorded too.
+                    let entry_path = match entry_path {
+                        Some(entry_path) => Some(FileSystemPathVc::new_normalized(
+                            fs,
+                            entry_path.await?.path.to_string(),
+                        )),
+                        None => None,
+                    };
+                    converted.insert(name.clone(), kind.to_entry(entry_path));
+                }
+                (Some(recorded), DirectoryContentVc::new(converted))
+            }
+        };
+        self.recording.with_fixture(|fixture| {
+            fixture.directories.insert(path, recorded);
+        });
+        Ok(content)
+    }
+
+    #[turbo_tasks::function]
+    async fn track(&self, fs_path: FileSystemPathVc) -> Result<CompletionVc> {
+        let (_, inner_path) = self.inner_path(fs_path).await?;
+        Ok(inner_path.track())
+    }
+
+    #[turbo_tasks::function]
+    async fn write(
+        &self,
+        fs_path: FileSystemPathVc,
+        content: FileContentVc,
+    ) -> Result<CompletionVc> {
+        let (_, inner_path) = self.inner_path(fs_path).await?;
+        Ok(inner_path.write(content))
+    }
+
+    #[turbo_tasks::function]
+    async fn write_link(
+        &self,
+        fs_path: FileSystemPathVc,
+        target: LinkContentVc,
+    ) -> Result<CompletionVc> {
+        let (_, inner_path) = self.inner_path(fs_path).await?;
+        Ok(inner_path.write_link(target))
+    }
+
+    #[turbo_tasks::function]
+    async fn metadata(&self, fs_path: FileSystemPathVc) -> Result<FileMetaVc> {
+        let (path, inner_path) = self.inner_path(fs_path).await?;
+        let meta = inner_path.metadata();
+        let recorded = (*meta.await?).clone();
+        self.recording.with_fixture(|fixture| {
+            fixture.metadata.insert(path, recorded);
+        });
+        Ok(meta)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for RecordingFileSystem {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<StringVc> {
+        Ok(StringVc::cell(format!(
+            "recording {}",
+            self.inner.to_string().await?
+        )))
+    }
+}
+
+/// A [FileSystem] which serves the inputs of an [IoFixture]. Reading
+/// anything which has not been recorded is an error, so a build which
+/// diverges from the recorded one is noticed. Writes are discarded.
+#[turbo_tasks::value(serialization = "none")]
+pub struct ReplayFileSystem {
+    name: String,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    fixture: TransientInstance<IoFixture>,
+}
+
+#[turbo_tasks::value_impl]
+impl ReplayFileSystemVc {
+    #[turbo_tasks::function]
+    pub fn new(name: String, fixture: TransientInstance<IoFixture>) -> Self {
+        ReplayFileSystem { name, fixture }.cell()
+    }
+}
+
+fn not_recorded(kind: &str, path: &str) -> anyhow::Error {
+    anyhow!("the {kind} of {path:?} has not been recorded")
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for ReplayFileSystem {
+    #[turbo_tasks::function]
+    async fn read(&self, fs_path: FileSystemPathVc) -> Result<FileContentVc> {
+        let path = &fs_path.await?.path;
+        let Some(file) = self.fixture.files.get(path.as_str()) else {
+            return Err(not_recorded("content", path));
+        };
+        Ok(file.to_content().cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn read_link(&self, fs_path: FileSystemPathVc) -> Result<LinkContentVc> {
+        let path = &fs_path.await?.path;
+        let Some(link) = self.fixture.links.get(path.as_str()) else {
+            return Err(not_recorded("link", path));
+        };
+        Ok(link.clone().cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn read_dir(&self, fs_path: FileSystemPathVc) -> Result<DirectoryContentVc> {
+        let fs_path = fs_path.await?;
+        let path = &fs_path.path;
+        let Some(directory) = self.fixture.directories.get(path.as_str()) else {
+            return Err(not_recorded("entries", path));
+        };
+        let Some(entries) = directory else {
+            return Ok(DirectoryContentVc::not_found());
+        };
+        let entries = entries
+            .iter()
+            .map(|(name, kind)| {
+                let entry_path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}/{name}")
+                };
+                let entry_path = FileSystemPathVc::new_normalized(fs_path.fs, entry_path);
+                (name.clone(), kind.to_entry(Some(entry_path)))
+            })
+            .collect();
+        Ok(DirectoryContentVc::new(entries))
+    }
+
+    #[turbo_tasks::function]
+    fn track(&self, _fs_path: FileSystemPathVc) -> CompletionVc {
+        CompletionVc::immutable()
+    }
+
+    #[turbo_tasks::function]
+    fn write(&self, _fs_path: FileSystemPathVc, _content: FileContentVc) -> CompletionVc {
+        CompletionVc::new()
+    }
+
+    #[turbo_tasks::function]
+    fn write_link(&self, _fs_path: FileSystemPathVc, _target: LinkContentVc) -> CompletionVc {
+        CompletionVc::new()
+    }
+
+    #[turbo_tasks::function]
+    async fn metadata(&self, fs_path: FileSystemPathVc) -> Result<FileMetaVc> {
+        let path = &fs_path.await?.path;
+        let Some(meta) = self.fixture.metadata.get(path.as_str()) else {
+            return Err(not_recorded("metadata", path));
+        };
+        Ok(meta.clone().cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ReplayFileSystem {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> StringVc {
+        StringVc::cell(self.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::{TransientInstance, TurboTasks};
+    use turbo_tasks_memory::MemoryBackend;
+
+    use super::{
+        IoFixture, IoRecording, RecordedEntry, RecordedFile, RecordingFileSystemVc,
+        ReplayFileSystemVc,
+    };
+    use crate::{DirectoryContent, DirectoryEntry, FileContent, FileSystemPathVc, FileSystemVc};
+
+    #[tokio::test]
+    async fn record_replayed_inputs() {
+        crate::register();
+
+        let tt = TurboTasks::new(MemoryBackend::default());
+        tt.run_once(async {
+            let mut fixture = IoFixture::default();
+            fixture.files.insert(
+                "src/index.js".to_string(),
+                RecordedFile::Text {
+                    text: "console.log(1)".to_string(),
+                    meta: Default::default(),
+                },
+            );
+            fixture
+                .files
+                .insert("src/missing.js".to_string(), RecordedFile::NotFound);
+            fixture.directories.insert(
+                "src".to_string(),
+                Some([("index.js".to_string(), RecordedEntry::File)].into()),
+            );
+            let replay: FileSystemVc = ReplayFileSystemVc::new(
+                "replay".to_string(),
+                TransientInstance::new(fixture.clone()),
+            )
+            .into();
+            let recording = TransientInstance::new(IoRecording::new());
+            let fs: FileSystemVc = RecordingFileSystemVc::new(replay, recording.clone()).into();
+
+            let src = FileSystemPathVc::new_normalized(fs, "src".to_string());
+            let DirectoryContent::Entries(entries) = &*src.read_dir().await? else {
+                panic!("src should exist");
+            };
+            assert_eq!(entries.len(), 1);
+            for entry in entries.values() {
+                let DirectoryEntry::File(path) = entry else {
+                    panic!("{entry:?} should be a file");
+                };
+                let FileContent::Content(file) = &*path.read().await? else {
+                    panic!("{entry:?} should exist");
+                };
+                assert_eq!(file.content().to_str()?, "console.log(1)");
+            }
+            let missing = FileSystemPathVc::new_normalized(fs, "src/missing.js".to_string());
+            assert!(matches!(&*missing.read().await?, FileContent::NotFound));
+            assert_eq!(recording.fixture(), fixture);
+
+            let unrecorded = FileSystemPathVc::new_normalized(replay, "other.js".to_string());
+            assert!(unrecorded.read().await.is_err());
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap()
+    }
+}
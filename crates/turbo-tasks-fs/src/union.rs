@@ -0,0 +1,218 @@
+use anyhow::{bail, Result};
+use auto_hash_map::AutoMap;
+use turbo_tasks::{primitives::StringVc, CompletionVc, ValueToString, ValueToStringVc};
+
+use crate::{
+    DirectoryContent, DirectoryContentVc, DirectoryEntry, FileContent, FileContentVc, FileMetaVc,
+    FileSystem, FileSystemEntryType, FileSystemPathVc, FileSystemVc, LinkContent, LinkContentVc,
+};
+
+/// A [FileSystem] which merges several `layers`, e.g. the project root,
+/// templates of a framework and generated files.
+///
+/// Reads resolve through the layers in order, so the first layer which
+/// contains a path wins. Directory listings contain the entries of all layers,
+/// where an entry of an earlier layer hides the entry with the same name of
+/// a later one. Writes always go to the first layer.
+#[turbo_tasks::value]
+pub struct UnionFileSystem {
+    name: String,
+    layers: Vec<FileSystemVc>,
+}
+
+#[turbo_tasks::value_impl]
+impl UnionFileSystemVc {
+    /// Creates a [UnionFileSystem] of the `layers`, ordered by precedence.
+    #[turbo_tasks::function]
+    pub fn new(name: String, layers: Vec<FileSystemVc>) -> Self {
+        UnionFileSystem { name, layers }.cell()
+    }
+}
+
+impl UnionFileSystem {
+    /// Resolves `path` on the union to the same path on each layer.
+    async fn layer_paths(&self, path: FileSystemPathVc) -> Result<Vec<FileSystemPathVc>> {
+        let path = path.await?;
+        let mut layer_paths = Vec::with_capacity(self.layers.len());
+        for layer in &self.layers {
+            layer_paths.push(layer.root().resolve().await?.join(&path.path));
+        }
+        Ok(layer_paths)
+    }
+
+    async fn first_layer_path(&self, path: FileSystemPathVc) -> Result<FileSystemPathVc> {
+        let Some(layer) = self.layers.first() else {
+            bail!("the union file system {} has no layers", self.name);
+        };
+        Ok(layer.root().resolve().await?.join(&path.await?.path))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for UnionFileSystem {
+    #[turbo_tasks::function]
+    async fn read(&self, fs_path: FileSystemPathVc) -> Result<FileContentVc> {
+        for layer_path in self.layer_paths(fs_path).await? {
+            let content = layer_path.read();
+            if let FileContent::Content(_) = &*content.await? {
+                return Ok(content);
+            }
+        }
+        Ok(FileContent::NotFound.cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn read_link(&self, fs_path: FileSystemPathVc) -> Result<LinkContentVc> {
+        for layer_path in self.layer_paths(fs_path).await? {
+            let content = layer_path.read_link();
+            if !matches!(&*content.await?, LinkContent::NotFound) {
+                return Ok(content);
+            }
+        }
+        Ok(LinkContent::NotFound.cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn read_dir(&self, fs_path: FileSystemPathVc) -> Result<DirectoryContentVc> {
+        let mut merged_entries = AutoMap::new();
+        let mut found = false;
+        for layer_path in self.layer_paths(fs_path).await? {
+            let dir_content = layer_path.read_dir().await?;
+            let DirectoryContent::Entries(entries) = &*dir_content else {
+                continue;
+            };
+            found = true;
+            for (name, entry) in entries {
+                if merged_entries.get(name).is_some() {
+                    continue;
+                }
+                use DirectoryEntry::*;
+
+                let entry = match *entry {
+                    File(_) => File(fs_path.join(name)),
+                    Directory(_) => Directory(fs_path.join(name)),
+                    Symlink(_) => Symlink(fs_path.join(name)),
+                    Other(_) => Other(fs_path.join(name)),
+                    Error => Error,
+                };
+                merged_entries.insert(name.clone(), entry);
+            }
+        }
+        if !found {
+            return Ok(DirectoryContentVc::not_found());
+        }
+        Ok(DirectoryContentVc::new(merged_entries))
+    }
+
+    #[turbo_tasks::function]
+    async fn track(&self, fs_path: FileSystemPathVc) -> Result<CompletionVc> {
+        // A change of any layer might change which layer wins
+        for layer_path in self.layer_paths(fs_path).await? {
+            layer_path.track().await?;
+        }
+        Ok(CompletionVc::new())
+    }
+
+    #[turbo_tasks::function]
+    async fn write(
+        &self,
+        fs_path: FileSystemPathVc,
+        content: FileContentVc,
+    ) -> Result<CompletionVc> {
+        Ok(self.first_layer_path(fs_path).await?.write(content))
+    }
+
+    #[turbo_tasks::function]
+    async fn write_link(
+        &self,
+        fs_path: FileSystemPathVc,
+        target: LinkContentVc,
+    ) -> Result<CompletionVc> {
+        Ok(self.first_layer_path(fs_path).await?.write_link(target))
+    }
+
+    #[turbo_tasks::function]
+    async fn metadata(&self, fs_path: FileSystemPathVc) -> Result<FileMetaVc> {
+        for layer_path in self.layer_paths(fs_path).await? {
+            if *layer_path.get_type().await? != FileSystemEntryType::NotFound {
+                return Ok(layer_path.metadata());
+            }
+        }
+        bail!("{} not found", fs_path.to_string().await?)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for UnionFileSystem {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> StringVc {
+        StringVc::cell(self.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use turbo_tasks_memory::MemoryBackend;
+    use turbo_tasks_testing::TestHarness;
+
+    use super::UnionFileSystemVc;
+    use crate::{
+        overlay::OverlayFileSystemVc, DirectoryContent, File, FileContent, FileSystem,
+        FileSystemVc, NullFileSystem,
+    };
+
+    #[tokio::test]
+    async fn resolves_through_layers() -> Result<()> {
+        crate::register();
+        let harness = TestHarness::new(MemoryBackend::default());
+        harness
+            .run(async {
+                let base: FileSystemVc = NullFileSystem.cell().into();
+                let project = OverlayFileSystemVc::new("project".to_string(), base);
+                let templates = OverlayFileSystemVc::new("templates".to_string(), base);
+                project
+                    .await?
+                    .set("pages/index.js", File::from("project").into())?;
+                templates
+                    .await?
+                    .set("pages/index.js", File::from("template").into())?;
+                templates
+                    .await?
+                    .set("pages/_app.js", File::from("template").into())?;
+
+                let fs: FileSystemVc = UnionFileSystemVc::new(
+                    "union".to_string(),
+                    vec![project.into(), templates.into()],
+                )
+                .into();
+                let pages = fs.root().join("pages");
+
+                let content = pages.join("index.js").read().await?;
+                let FileContent::Content(file) = &*content else {
+                    panic!("pages/index.js should exist");
+                };
+                assert_eq!(file.content().to_str()?, "project");
+                let content = pages.join("_app.js").read().await?;
+                let FileContent::Content(file) = &*content else {
+                    panic!("pages/_app.js should exist");
+                };
+                assert_eq!(file.content().to_str()?, "template");
+                let content = pages.join("missing.js").read().await?;
+                assert!(matches!(&*content, FileContent::NotFound));
+
+                let content = pages.read_dir().await?;
+                let DirectoryContent::Entries(entries) = &*content else {
+                    panic!("pages should exist");
+                };
+                let mut names = entries
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>();
+                names.sort();
+                assert_eq!(names, ["_app.js", "index.js"]);
+                Ok(())
+            })
+            .await
+    }
+}
@@ -58,6 +58,98 @@ pub fn unix_to_sys(path: &str) -> Cow<'_, str> {
     }
 }
 
+/// Windows doesn't accept longer paths unless they use the verbatim prefix
+/// (`\\?\`).
+pub const WINDOWS_MAX_PATH: usize = 260;
+
+/// Normalizes a Windows path, so paths which point to the same file compare
+/// equal: The verbatim prefix is removed (`\\?\C:\dir` becomes `C:\dir`
+/// and `\\?\UNC\server\share` becomes `\\server\share`), forward slashes
+/// are replaced with backslashes and the drive letter is uppercased.
+///
+/// This only operates on the string, so it works on every platform.
+pub fn normalize_windows_path(path: &str) -> Cow<'_, str> {
+    let (prefix, rest) = if let Some(rest) = path
+        .strip_prefix(r"\\?\UNC\")
+        .or_else(|| path.strip_prefix(r"\\?\unc\"))
+    {
+        (r"\\", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        ("", rest)
+    } else {
+        ("", path)
+    };
+    let has_lowercase_drive = rest
+        .as_bytes()
+        .first()
+        .map_or(false, u8::is_ascii_lowercase)
+        && rest.as_bytes().get(1) == Some(&b':');
+    if prefix.is_empty() && rest.len() == path.len() && !has_lowercase_drive && !path.contains('/')
+    {
+        return Cow::Borrowed(path);
+    }
+    let mut normalized = String::with_capacity(prefix.len() + rest.len());
+    normalized.push_str(prefix);
+    if has_lowercase_drive {
+        normalized.push(rest.as_bytes()[0].to_ascii_uppercase() as char);
+        normalized.push_str(&rest[1..].replace('/', "\\"));
+    } else {
+        normalized.push_str(&rest.replace('/', "\\"));
+    }
+    Cow::Owned(normalized)
+}
+
+/// Adds the verbatim prefix to an absolute Windows path which is too long
+/// for the Windows API, e.g. to watch it. The path must be normalized, as
+/// Windows doesn't normalize verbatim paths.
+pub fn to_long_windows_path(path: &str) -> Cow<'_, str> {
+    if path.len() < WINDOWS_MAX_PATH || path.starts_with(r"\\?\") {
+        return Cow::Borrowed(path);
+    }
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        Cow::Owned(format!(r"\\?\UNC\{unc}"))
+    } else if is_windows_absolute_drive_path(path) {
+        Cow::Owned(format!(r"\\?\{path}"))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Returns true for `C:\dir` and `C:/dir`, but false for the drive-relative
+/// `C:dir`, which is relative to the current directory of the drive.
+fn is_windows_absolute_drive_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/')
+}
+
+/// Converts an absolute Windows path (with a drive letter or a UNC share) to
+/// a /-separated path relative to `root`, which is an absolute Windows path
+/// too. Returns `None` when the path is outside of `root`, or when it's not
+/// absolute, e.g. the drive-relative `C:dir`.
+///
+/// Windows paths are case-insensitive, so the casing of `path` and `root`
+/// may differ.
+pub fn windows_path_relative_to(path: &str, root: &str) -> Option<String> {
+    let path = normalize_windows_path(path);
+    let root = normalize_windows_path(root);
+    if !(is_windows_absolute_drive_path(&path) || path.starts_with(r"\\")) {
+        return None;
+    }
+    let root = root.trim_end_matches('\\');
+    let prefix = path.get(..root.len())?;
+    if !prefix.eq_ignore_ascii_case(root) {
+        return None;
+    }
+    let rest = &path[root.len()..];
+    if !rest.is_empty() && !rest.starts_with('\\') {
+        return None;
+    }
+    normalize_path(&rest.replace('\\', "/"))
+}
+
 /// Normalizes a /-separated path into a form that contains no leading /, no
 /// double /, no "." seqment, no ".." seqment.
 ///
@@ -127,3 +219,61 @@ pub fn extract_disk_access<T>(value: IoResult<T>, path: &Path) -> Result<Option<
         Err(e) => Err(anyhow!(e).context(format!("reading file {}", path.display()))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::{normalize_windows_path, to_long_windows_path, windows_path_relative_to};
+
+    #[rstest]
+    #[case(r"C:\dir\file.js", r"C:\dir\file.js")]
+    #[case(r"c:\dir\file.js", r"C:\dir\file.js")]
+    #[case("C:/dir/file.js", r"C:\dir\file.js")]
+    #[case(r"\\?\C:\dir\file.js", r"C:\dir\file.js")]
+    #[case(r"\\?\UNC\server\share\file.js", r"\\server\share\file.js")]
+    #[case(r"\\server\share\file.js", r"\\server\share\file.js")]
+    fn normalizes_windows_paths(#[case] path: &str, #[case] expected: &str) {
+        assert_eq!(normalize_windows_path(path), expected);
+    }
+
+    #[test]
+    fn prefixes_long_windows_paths() {
+        assert_eq!(to_long_windows_path(r"C:\dir"), r"C:\dir");
+        let long_dir = "a".repeat(300);
+        assert_eq!(
+            to_long_windows_path(&format!(r"C:\{long_dir}")),
+            format!(r"\\?\C:\{long_dir}")
+        );
+        assert_eq!(
+            to_long_windows_path(&format!(r"\\server\share\{long_dir}")),
+            format!(r"\\?\UNC\server\share\{long_dir}")
+        );
+        assert_eq!(
+            to_long_windows_path(&format!(r"C:{long_dir}")),
+            format!(r"C:{long_dir}"),
+            "drive-relative paths can't be prefixed"
+        );
+    }
+
+    #[rstest]
+    #[case(r"C:\project\src\index.js", r"C:\project", Some("src/index.js"))]
+    #[case(r"c:\Project\src\index.js", r"C:\project\", Some("src/index.js"))]
+    #[case(r"\\?\C:\project\src\..\index.js", r"C:\project", Some("index.js"))]
+    #[case(
+        r"\\server\share\src\index.js",
+        r"\\?\UNC\server\share",
+        Some("src/index.js")
+    )]
+    #[case(r"C:\project", r"C:\project", Some(""))]
+    #[case(r"C:\project2\index.js", r"C:\project", None)]
+    #[case(r"D:\project\index.js", r"C:\project", None)]
+    #[case(r"C:project\index.js", r"C:\project", None)]
+    fn resolves_windows_paths_relative_to_root(
+        #[case] path: &str,
+        #[case] root: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(windows_path_relative_to(path, root).as_deref(), expected);
+    }
+}
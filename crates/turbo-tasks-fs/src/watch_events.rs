@@ -0,0 +1,302 @@
+//! Coalesces the watcher events of one batch into the net change of every
+//! path, so bursts of events like a `git checkout` or an `npm install` cause
+//! as few invalidations as possible.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use notify::DebouncedEvent;
+
+/// The net change of a path within one batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PathChange {
+    /// The path didn't exist before the batch and exists now.
+    Created,
+    /// The content or the metadata of the file at the path changed.
+    Modified,
+    /// The path existed before the batch and doesn't exist anymore.
+    Removed,
+    /// The path exists, but might have been replaced by something else, e.g.
+    /// a directory by a file, or it's unknown what happened to it.
+    Replaced,
+}
+
+impl PathChange {
+    /// Merges the `next` change of a path into this one. Returns `None` when
+    /// the changes cancel each other out. When the order of events is
+    /// ambiguous, the change which invalidates more is chosen.
+    fn merge(self, next: PathChange) -> Option<PathChange> {
+        use PathChange::*;
+
+        Some(match (self, next) {
+            // A temporary file which is gone again
+            (Created, Removed) => return None,
+            (Created, _) => Created,
+            (Modified, Modified) => Modified,
+            (Modified, Removed) | (Replaced, Removed) | (Removed, Removed) => Removed,
+            (Modified, Created) | (Modified, Replaced) => Replaced,
+            (Removed, Created) | (Removed, Modified) | (Removed, Replaced) => Replaced,
+            (Replaced, Created) | (Replaced, Modified) | (Replaced, Replaced) => Replaced,
+        })
+    }
+
+    /// Whether everything within the path needs to be invalidated.
+    fn affects_children(self) -> bool {
+        !matches!(self, PathChange::Modified)
+    }
+}
+
+/// The paths to invalidate for a batch of watcher events.
+#[derive(Default, Debug)]
+pub(crate) struct BatchedInvalidations {
+    /// Files whose content changed.
+    pub path: HashSet<PathBuf>,
+    /// Directories whose entries changed.
+    pub path_dir: HashSet<PathBuf>,
+    /// Files within these paths might have changed.
+    pub path_and_children: HashSet<PathBuf>,
+    /// Directories within these paths might have changed.
+    pub path_and_children_dir: HashSet<PathBuf>,
+    /// Paths which have been created and might need to be watched again.
+    pub new_paths: HashSet<PathBuf>,
+}
+
+/// Collects the watcher events of a batch.
+///
+/// Multiple events for the same path are merged into one change, e.g. a file
+/// which is created, written and removed again within a batch isn't
+/// invalidated at all. A rename is treated as a move, i.e. as the removal of
+/// the source and the replacement of the destination, so chains of renames
+/// like the atomic saves of editors collapse into a single change of the final
+/// path. Changes within a directory which is invalidated as a whole are
+/// dropped.
+#[derive(Default, Debug)]
+pub(crate) struct CoalescedEvents {
+    changes: HashMap<PathBuf, PathChange>,
+    /// The destinations of renames. It's unknown whether they existed before,
+    /// so the entries of their parent directories might have changed.
+    renamed: HashSet<PathBuf>,
+}
+
+impl CoalescedEvents {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn add(&mut self, event: DebouncedEvent, root: &Path) {
+        match event {
+            // A touch or a chmod doesn't change the content, but the metadata
+            DebouncedEvent::Write(path) | DebouncedEvent::Chmod(path) => {
+                self.change(path, PathChange::Modified)
+            }
+            DebouncedEvent::Create(path) => self.change(path, PathChange::Created),
+            DebouncedEvent::Remove(path) => self.change(path, PathChange::Removed),
+            DebouncedEvent::Rename(source, destination) => {
+                self.change(source, PathChange::Removed);
+                // The destination is overwritten when it exists, e.g. by the
+                // atomic save of an editor
+                self.renamed.insert(destination.clone());
+                self.change(destination, PathChange::Replaced);
+            }
+            DebouncedEvent::Error(_, Some(path)) => self.change(path, PathChange::Replaced),
+            DebouncedEvent::Rescan | DebouncedEvent::Error(_, None) => {
+                self.change(root.to_path_buf(), PathChange::Replaced)
+            }
+            DebouncedEvent::NoticeRemove(_) | DebouncedEvent::NoticeWrite(_) => {
+                // ignored
+            }
+        }
+    }
+
+    fn change(&mut self, path: PathBuf, change: PathChange) {
+        match self.changes.remove(&path) {
+            Some(previous) => {
+                if let Some(change) = previous.merge(change) {
+                    self.changes.insert(path, change);
+                }
+            }
+            None => {
+                self.changes.insert(path, change);
+            }
+        }
+    }
+
+    /// Returns the invalidations for the collected events and starts a new
+    /// batch.
+    pub fn take_invalidations(&mut self) -> BatchedInvalidations {
+        let changes = std::mem::take(&mut self.changes);
+        let renamed = std::mem::take(&mut self.renamed);
+        let is_covered = |path: &Path| {
+            path.ancestors().skip(1).any(|ancestor| {
+                changes
+                    .get(ancestor)
+                    .map_or(false, |change| change.affects_children())
+            })
+        };
+        let mut invalidations = BatchedInvalidations::default();
+        for (path, change) in &changes {
+            if matches!(change, PathChange::Created | PathChange::Replaced) {
+                invalidations.new_paths.insert(path.clone());
+            }
+            if is_covered(path) {
+                continue;
+            }
+            match change {
+                PathChange::Modified => {
+                    invalidations.path.insert(path.clone());
+                }
+                PathChange::Created | PathChange::Removed | PathChange::Replaced => {
+                    invalidations.path_and_children.insert(path.clone());
+                    invalidations.path_and_children_dir.insert(path.clone());
+                }
+            }
+            if matches!(change, PathChange::Created | PathChange::Removed)
+                || (*change == PathChange::Replaced && renamed.contains(path))
+            {
+                if let Some(parent) = path.parent() {
+                    if !changes
+                        .get(parent)
+                        .map_or(false, |change| change.affects_children())
+                    {
+                        invalidations.path_dir.insert(parent.to_path_buf());
+                    }
+                }
+            }
+        }
+        invalidations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use notify::DebouncedEvent;
+
+    use super::CoalescedEvents;
+
+    fn paths<'a>(paths: impl IntoIterator<Item = &'a PathBuf>) -> Vec<&'a Path> {
+        let mut paths = paths.into_iter().map(|p| p.as_path()).collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn coalesces_events() {
+        let root = Path::new("/project");
+        let mut events = CoalescedEvents::default();
+        for event in [
+            // Written twice
+            DebouncedEvent::Write("/project/a.js".into()),
+            DebouncedEvent::Write("/project/a.js".into()),
+            // Atomic save of an editor
+            DebouncedEvent::Create("/project/.b.js.tmp".into()),
+            DebouncedEvent::Write("/project/.b.js.tmp".into()),
+            DebouncedEvent::Rename("/project/.b.js.tmp".into(), "/project/b.js".into()),
+            // A file which only existed temporarily
+            DebouncedEvent::Create("/project/lock".into()),
+            DebouncedEvent::Remove("/project/lock".into()),
+            // A new package
+            DebouncedEvent::Create("/project/node_modules/pkg".into()),
+            DebouncedEvent::Create("/project/node_modules/pkg/index.js".into()),
+            DebouncedEvent::Write("/project/node_modules/pkg/index.js".into()),
+            DebouncedEvent::Chmod("/project/c.js".into()),
+        ] {
+            events.add(event, root);
+        }
+        let invalidations = events.take_invalidations();
+        assert!(events.is_empty());
+
+        assert_eq!(
+            paths(&invalidations.path),
+            [Path::new("/project/a.js"), Path::new("/project/c.js")]
+        );
+        assert_eq!(
+            paths(&invalidations.path_and_children),
+            [
+                Path::new("/project/b.js"),
+                Path::new("/project/node_modules/pkg")
+            ]
+        );
+        assert_eq!(
+            invalidations.path_and_children,
+            invalidations.path_and_children_dir
+        );
+        assert_eq!(
+            paths(&invalidations.path_dir),
+            [Path::new("/project"), Path::new("/project/node_modules")]
+        );
+        assert_eq!(
+            paths(&invalidations.new_paths),
+            [
+                Path::new("/project/b.js"),
+                Path::new("/project/node_modules/pkg"),
+                Path::new("/project/node_modules/pkg/index.js")
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_over_existing_files() {
+        let root = Path::new("/project");
+        let mut events = CoalescedEvents::default();
+        for event in [
+            // Atomic save of an editor, then the file is removed
+            DebouncedEvent::Create("/project/.a.js.tmp".into()),
+            DebouncedEvent::Rename("/project/.a.js.tmp".into(), "/project/a.js".into()),
+            DebouncedEvent::Remove("/project/a.js".into()),
+            // Removed, then restored by a rename
+            DebouncedEvent::Remove("/project/b.js".into()),
+            DebouncedEvent::Rename("/project/.b.js.tmp".into(), "/project/b.js".into()),
+            // Atomic save of a file in another directory, then written again
+            DebouncedEvent::Rename("/project/src/.c.js.tmp".into(), "/project/src/c.js".into()),
+            DebouncedEvent::Create("/project/src/c.js".into()),
+        ] {
+            events.add(event, root);
+        }
+        let invalidations = events.take_invalidations();
+        assert_eq!(
+            paths(&invalidations.path_and_children),
+            [
+                Path::new("/project/.b.js.tmp"),
+                Path::new("/project/a.js"),
+                Path::new("/project/b.js"),
+                Path::new("/project/src/.c.js.tmp"),
+                Path::new("/project/src/c.js")
+            ]
+        );
+        assert_eq!(
+            paths(&invalidations.path_dir),
+            [Path::new("/project"), Path::new("/project/src")]
+        );
+        // The removed file isn't watched again
+        assert_eq!(
+            paths(&invalidations.new_paths),
+            [Path::new("/project/b.js"), Path::new("/project/src/c.js")]
+        );
+    }
+
+    #[test]
+    fn merges_removal_and_recreation() {
+        let root = Path::new("/project");
+        let mut events = CoalescedEvents::default();
+        events.add(DebouncedEvent::Remove("/project/dist".into()), root);
+        events.add(DebouncedEvent::Create("/project/dist".into()), root);
+        events.add(DebouncedEvent::Create("/project/dist/main.js".into()), root);
+        let invalidations = events.take_invalidations();
+        assert_eq!(
+            paths(&invalidations.path_and_children),
+            [Path::new("/project/dist")]
+        );
+        // The entries of the parent directory didn't change
+        assert!(invalidations.path_dir.is_empty());
+
+        events.add(DebouncedEvent::Write("/project/src/a.js".into()), root);
+        events.add(DebouncedEvent::Rescan, root);
+        let invalidations = events.take_invalidations();
+        assert!(invalidations.path.is_empty());
+        assert_eq!(paths(&invalidations.path_and_children), [root]);
+    }
+}
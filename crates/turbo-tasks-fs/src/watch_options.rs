@@ -0,0 +1,213 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// How often the [WatchBackend::Poll] backend checks for changes, unless
+/// configured otherwise.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Options for
+/// [DiskFileSystem::start_watching_with_options](crate::DiskFileSystem::start_watching_with_options).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Attaches the changed paths to invalidations, see
+    /// [DiskFileSystem::start_watching_with_invalidation_reason](crate::DiskFileSystem::start_watching_with_invalidation_reason).
+    pub report_invalidation_reason: bool,
+    /// Collects changes until nothing changed for this window and applies
+    /// them as one batch, see
+    /// [DiskFileSystem::start_watching_with_debounce](crate::DiskFileSystem::start_watching_with_debounce).
+    pub debounce: Option<Duration>,
+    pub backend: WatchBackend,
+    /// Only watches the directories which have been read, as they are read,
+    /// instead of the whole root recursively. This saves file descriptors
+    /// and startup time in large repositories with many unrelated
+    /// directories. It's always the case on Linux, where the OS can't watch
+    /// recursively, and only applies to the native watcher of the OS.
+    pub lazy: bool,
+    /// Compares a hash of the content of a changed file with the content
+    /// which has been read before invalidating the readers of its content.
+    /// This costs a read of every changed file, but avoids recomputations
+    /// when a file is only touched, saved atomically by an editor or
+    /// rewritten by git with the same content. Readers of the metadata or the
+    /// link are always invalidated.
+    pub compare_content: bool,
+}
+
+/// Where the changes of a [DiskFileSystem](crate::DiskFileSystem) come from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WatchBackend {
+    /// Polls when the root is on a file system where the events of the OS
+    /// are unreliable (network file systems, Docker mounts on macOS, Windows
+    /// drives in WSL) or when the OS watcher can't be started, e.g. because
+    /// of inotify limits. Uses the OS watcher otherwise.
+    #[default]
+    Auto,
+    /// The file watching API of the OS.
+    Native,
+    /// Watchman, when it's installed. Falls back to [WatchBackend::Auto].
+    Watchman,
+    /// Compares the modification times of all files below the root in an
+    /// `interval`. This works everywhere, but costs CPU time on large
+    /// directories and only notices changes after up to `interval`.
+    Poll { interval: Duration },
+}
+
+/// Reported when the OS refuses to watch more directories (`ENOSPC` or
+/// `EMFILE` from inotify). The affected directories are polled instead, see
+/// [DiskFileSystem::watch_limit_reached](crate::DiskFileSystem::watch_limit_reached).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchLimitReached {
+    /// The first directory which couldn't be watched.
+    pub path: PathBuf,
+}
+
+impl Display for WatchLimitReached {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The limit of file watches of the OS was reached while watching {}. Changes in \
+             directories which can't be watched are detected by polling every {}s, which is \
+             slower and costs CPU time. Raise the limit, e.g. with `sudo sysctl \
+             fs.inotify.max_user_watches=524288` (add it to /etc/sysctl.conf to keep it after a \
+             reboot) and `sudo sysctl fs.inotify.max_user_instances=512`, or ignore large \
+             directories which the build doesn't depend on.",
+            self.path.display(),
+            DEFAULT_POLL_INTERVAL.as_secs()
+        )
+    }
+}
+
+/// Returns true when `err` means that the OS limit of watches or watcher
+/// instances is exhausted, rather than e.g. that the path doesn't exist.
+pub(crate) fn is_watch_limit_error(err: &notify::Error) -> bool {
+    // ENOSPC and EMFILE on Linux
+    const ENOSPC: i32 = 28;
+    const EMFILE: i32 = 24;
+    match err {
+        // notify reports ENOSPC as a generic error
+        notify::Error::Generic(message) => message.contains("limit"),
+        notify::Error::Io(err) => matches!(err.raw_os_error(), Some(ENOSPC | EMFILE)),
+        _ => false,
+    }
+}
+
+/// File system types of mounts which don't deliver (reliable) change events
+/// to the watcher of the OS.
+const POLLING_FILE_SYSTEMS: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    // Windows drives in WSL 1 and WSL 2
+    "drvfs",
+    "9p",
+    // Docker Desktop bind mounts
+    "fakeowner",
+    "fuse.grpcfuse",
+    "osxfs",
+    "fuse.osxfs",
+    "virtiofs",
+    "vboxsf",
+];
+
+/// Returns true when the file system at `path` should be polled, as its
+/// mount doesn't deliver reliable change events.
+pub(crate) fn needs_polling(path: &Path) -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+        return false;
+    };
+    let path = dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    mount_needs_polling(&mounts, &path)
+}
+
+/// Finds the mount of `path` in the `mounts` (in the format of
+/// `/proc/mounts`) and checks its file system type.
+fn mount_needs_polling(mounts: &str, path: &Path) -> bool {
+    let mount = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            let _device = fields.next()?;
+            let mount_point = unescape_mount_point(fields.next()?);
+            let fs_type = fields.next()?;
+            Some((mount_point, fs_type))
+        })
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.len());
+    match mount {
+        Some((_, fs_type)) => POLLING_FILE_SYSTEMS.contains(&fs_type),
+        None => false,
+    }
+}
+
+/// Mount points in `/proc/mounts` escape spaces, tabs, newlines and
+/// backslashes as octal sequences, e.g. `\040`.
+fn unescape_mount_point(mount_point: &str) -> String {
+    let mut result = String::with_capacity(mount_point.len());
+    let mut rest = mount_point;
+    while let Some(index) = rest.find('\\') {
+        result.push_str(&rest[..index]);
+        let escaped = rest.get(index + 1..index + 4);
+        match escaped.and_then(|escaped| u8::from_str_radix(escaped, 8).ok()) {
+            Some(byte) => {
+                result.push(byte as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{is_watch_limit_error, mount_needs_polling};
+
+    const MOUNTS: &str = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+server:/export /home/user/shared nfs4 rw,relatime 0 0
+C:\\134 /mnt/c 9p rw,noatime 0 0
+/dev/sdb1 /mnt/c/local\\040disk ext4 rw,relatime 0 0
+";
+
+    #[test]
+    fn detects_mounts_to_poll() {
+        assert!(!mount_needs_polling(MOUNTS, Path::new("/home/user/app")));
+        assert!(mount_needs_polling(
+            MOUNTS,
+            Path::new("/home/user/shared/app")
+        ));
+        assert!(mount_needs_polling(MOUNTS, Path::new("/mnt/c/Users/app")));
+        assert!(!mount_needs_polling(
+            MOUNTS,
+            Path::new("/mnt/c/local disk/app")
+        ));
+    }
+
+    #[test]
+    fn detects_watch_limit_errors() {
+        let limit = notify::Error::Generic(
+            "Can't watch (more) files, limit on the total number of inotify watches reached"
+                .to_string(),
+        );
+        assert!(is_watch_limit_error(&limit));
+        let too_many_instances = notify::Error::Io(std::io::Error::from_raw_os_error(24));
+        assert!(is_watch_limit_error(&too_many_instances));
+        assert!(!is_watch_limit_error(&notify::Error::PathNotFound));
+        let not_found = notify::Error::Io(std::io::ErrorKind::NotFound.into());
+        assert!(!is_watch_limit_error(&not_found));
+    }
+}
@@ -0,0 +1,241 @@
+//! A file watcher backed by [Watchman](https://facebook.github.io/watchman/),
+//! which scales to huge repositories where the OS watcher runs into inotify
+//! limits or has to crawl too many directories on startup.
+//!
+//! It talks to Watchman through the `watchman` command line client and
+//! translates the results of a subscription into the same [DebouncedEvent]s
+//! the built-in watcher delivers.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::mpsc::Sender,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use notify::DebouncedEvent;
+use serde::Deserialize;
+use serde_json::json;
+use turbo_tasks::spawn_thread;
+
+const SUBSCRIPTION_NAME: &str = "turbo-tasks-fs";
+
+/// Returns true when the `watchman` client is installed and can reach (or
+/// start) the Watchman service.
+pub fn is_watchman_available() -> bool {
+    Command::new("watchman")
+        .arg("version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(false, |status| status.success())
+}
+
+/// A running Watchman subscription. The subscription ends when it's dropped,
+/// which disconnects the event channel.
+pub(crate) struct WatchmanWatcher {
+    client: Child,
+}
+
+impl WatchmanWatcher {
+    /// Subscribes to all changes below `root_path` and sends them to `tx`.
+    pub(crate) fn new(root_path: &Path, tx: Sender<DebouncedEvent>) -> Result<Self> {
+        let project = watch_project(root_path)?;
+        let mut client = Command::new("watchman")
+            .args([
+                "--persistent",
+                "--json-command",
+                "--server-encoding=json",
+                "--output-encoding=json",
+                "--no-pretty",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("unable to start the watchman client")?;
+        let mut options = json!({
+            "expression": ["true"],
+            "fields": ["name", "exists", "new"],
+            "empty_on_fresh_instance": true,
+        });
+        if let Some(relative_path) = &project.relative_path {
+            options["relative_root"] = json!(relative_path);
+        }
+        let command = json!(["subscribe", project.watch, SUBSCRIPTION_NAME, options]);
+        {
+            let mut stdin = client.stdin.take().unwrap();
+            serde_json::to_writer(&mut stdin, &command)?;
+            stdin.write_all(b"\n")?;
+        }
+        let stdout = client.stdout.take().unwrap();
+        let root_path = root_path.to_path_buf();
+        spawn_thread(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let events = match serde_json::from_str(&line)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|response| events_from_response(&root_path, response))
+                {
+                    Ok(events) => events,
+                    Err(err) => vec![DebouncedEvent::Error(
+                        notify::Error::Generic(err.to_string()),
+                        None,
+                    )],
+                };
+                for event in events {
+                    if tx.send(event).is_err() {
+                        // The file system has stopped watching
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Self { client })
+    }
+}
+
+impl Drop for WatchmanWatcher {
+    fn drop(&mut self) {
+        // The reader thread stops at the end of the output
+        let _ = self.client.kill();
+        let _ = self.client.wait();
+    }
+}
+
+#[derive(Deserialize)]
+struct WatchProject {
+    watch: String,
+    relative_path: Option<String>,
+}
+
+/// Watchman watches the root of a project (e.g. the repository) once and
+/// shares it between all subscriptions within it.
+fn watch_project(root_path: &Path) -> Result<WatchProject> {
+    let output = Command::new("watchman")
+        .args(["--output-encoding=json", "--no-pretty", "watch-project"])
+        .arg(root_path)
+        .stderr(Stdio::null())
+        .output()
+        .context("unable to run watchman")?;
+    let response: WatchmanResponse = serde_json::from_slice(&output.stdout)
+        .context("unable to parse the response of watchman watch-project")?;
+    if let Some(error) = response.error {
+        bail!(
+            "watchman is unable to watch {}: {}",
+            root_path.display(),
+            error
+        );
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+#[derive(Deserialize, Debug)]
+struct WatchmanResponse {
+    error: Option<String>,
+    subscription: Option<String>,
+    #[serde(default)]
+    is_fresh_instance: bool,
+    #[serde(default)]
+    files: Vec<WatchmanFile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct WatchmanFile {
+    name: String,
+    exists: bool,
+    #[serde(default)]
+    new: bool,
+}
+
+/// Translates a response of the Watchman service into watcher events. Paths
+/// are relative to `root_path`.
+fn events_from_response(
+    root_path: &Path,
+    response: WatchmanResponse,
+) -> Result<Vec<DebouncedEvent>> {
+    if let Some(error) = response.error {
+        return Err(anyhow!("watchman error: {}", error));
+    }
+    if response.subscription.is_none() {
+        // The confirmation of the subscription or another unilateral response
+        return Ok(Vec::new());
+    }
+    if response.is_fresh_instance {
+        // Watchman has lost track of changes, e.g. because it has been
+        // restarted or had to recrawl. Anything could have changed.
+        return Ok(vec![DebouncedEvent::Rescan]);
+    }
+    Ok(response
+        .files
+        .into_iter()
+        .map(|file| {
+            let path = root_path.join(PathBuf::from(file.name));
+            match (file.exists, file.new) {
+                (false, _) => DebouncedEvent::Remove(path),
+                (true, true) => DebouncedEvent::Create(path),
+                (true, false) => DebouncedEvent::Write(path),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use notify::DebouncedEvent;
+
+    use super::events_from_response;
+
+    fn events(response: &str) -> Vec<DebouncedEvent> {
+        events_from_response(Path::new("/root"), serde_json::from_str(response).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn translates_subscription_results() {
+        let events = events(
+            r#"{
+                "subscription": "turbo-tasks-fs",
+                "clock": "c:1:2",
+                "files": [
+                    { "name": "src/index.js", "exists": true, "new": false, "type": "f" },
+                    { "name": "src/new.js", "exists": true, "new": true, "type": "f" },
+                    { "name": "old", "exists": false, "type": "d" }
+                ]
+            }"#,
+        );
+        assert!(matches!(
+            &events[..],
+            [
+                DebouncedEvent::Write(write),
+                DebouncedEvent::Create(create),
+                DebouncedEvent::Remove(remove),
+            ] if write == Path::new("/root/src/index.js")
+                && create == Path::new("/root/src/new.js")
+                && remove == Path::new("/root/old")
+        ));
+    }
+
+    #[test]
+    fn rescans_fresh_instances() {
+        let events = events(
+            r#"{ "subscription": "turbo-tasks-fs", "is_fresh_instance": true, "files": [] }"#,
+        );
+        assert!(matches!(&events[..], [DebouncedEvent::Rescan]));
+    }
+
+    #[test]
+    fn ignores_other_responses() {
+        assert!(events(r#"{ "subscribe": "turbo-tasks-fs", "clock": "c:1:2" }"#).is_empty());
+    }
+
+    #[test]
+    fn reports_errors() {
+        let response = serde_json::from_str(r#"{ "error": "unable to resolve root" }"#).unwrap();
+        assert!(events_from_response(Path::new("/root"), response).is_err());
+    }
+}
@@ -0,0 +1,6 @@
+/// Hash some content with the BLAKE3 cryptographic hash function.
+///
+/// Returns a 32-byte hash digest.
+pub fn hash_blake3(content: &[u8]) -> [u8; 32] {
+    blake3::hash(content).into()
+}
@@ -1,7 +1,10 @@
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro2::{Ident, TokenStream as TokenStream2, TokenTree};
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Field, FieldsNamed, FieldsUnnamed};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Field, Fields, FieldsNamed, FieldsUnnamed,
+    GenericParam, Type,
+};
 use turbo_tasks_macros_shared::{generate_destructuring, match_expansion};
 
 use super::FieldAttributes;
@@ -13,12 +16,32 @@ fn filter_field(field: &Field) -> bool {
 pub fn derive_trace_raw_vcs(input: TokenStream) -> TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
     let ident = &derive_input.ident;
-    let generics = &derive_input.generics;
+    let mut generics = derive_input.generics.clone();
+    // Only the traced fields which use a type parameter need to implement
+    // `TraceRawVcs`.
+    let type_params = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param.ident.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !type_params.is_empty() {
+        let where_clause = generics.make_where_clause();
+        for ty in traced_field_types(&derive_input) {
+            if uses_type_params(quote!(#ty), &type_params) {
+                where_clause
+                    .predicates
+                    .push(parse_quote! { #ty: turbo_tasks::trace::TraceRawVcs });
+            }
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let trace_items = match_expansion(&derive_input, &trace_named, &trace_unnamed, &trace_unit);
-    let generics_params = &generics.params.iter().collect::<Vec<_>>();
     quote! {
-        impl #generics turbo_tasks::trace::TraceRawVcs for #ident #generics #(where #generics_params: turbo_tasks::trace::TraceRawVcs)* {
+        impl #impl_generics turbo_tasks::trace::TraceRawVcs for #ident #ty_generics #where_clause {
             fn trace_raw_vcs(&self, __context__: &mut turbo_tasks::trace::TraceRawVcsContext) {
                 #trace_items
             }
@@ -27,6 +50,32 @@ pub fn derive_trace_raw_vcs(input: TokenStream) -> TokenStream {
     .into()
 }
 
+fn traced_field_types(derive_input: &DeriveInput) -> Vec<&Type> {
+    let fields: Vec<&Fields> = match &derive_input.data {
+        Data::Struct(data) => vec![&data.fields],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| &variant.fields)
+            .collect(),
+        Data::Union(_) => vec![],
+    };
+    fields
+        .into_iter()
+        .flat_map(|fields| fields.iter())
+        .filter(|field| filter_field(field))
+        .map(|field| &field.ty)
+        .collect()
+}
+
+fn uses_type_params(tokens: TokenStream2, type_params: &[String]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => type_params.contains(&ident.to_string()),
+        TokenTree::Group(group) => uses_type_params(group.stream(), type_params),
+        _ => false,
+    })
+}
+
 fn trace_named(_ident: &Ident, fields: &FieldsNamed) -> (TokenStream2, TokenStream2) {
     let (captures, fields_idents) = generate_destructuring(fields.named.iter(), &filter_field);
     (
@@ -2,8 +2,13 @@ use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
 use proc_macro_error::abort;
 use quote::quote;
 use syn::{
-    punctuated::Punctuated, spanned::Spanned, FnArg, Pat, PatIdent, PatType, Receiver, ReturnType,
-    Signature, Token, Type, TypePath, TypeReference,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Error, FnArg, GenericParam, Generics, Lit, LitInt, Meta, MetaNameValue, Pat,
+    PatIdent, PatType, Receiver, Result, ReturnType, Signature, Token, Type, TypePath,
+    TypeReference,
 };
 
 use crate::util::*;
@@ -15,6 +20,118 @@ pub enum SelfType<'a> {
     ValueTrait,
 }
 
+/// The arguments of the `#[turbo_tasks::function]` attribute.
+#[derive(Default)]
+pub struct FunctionArguments {
+    /// The `TaskPriority` variant of `priority = "..."`.
+    priority: Option<Ident>,
+    /// `remote` marks the function as pure and expensive, so it can be
+    /// executed by a remote executor.
+    remote: bool,
+    /// `slow_threshold_ms = ...` reports executions taking longer as slow
+    /// tasks.
+    slow_threshold_ms: Option<LitInt>,
+    /// `concurrency_limit = ...` limits the number of executions of the
+    /// function running at the same time.
+    concurrency_limit: Option<LitInt>,
+    /// `retry = ...` executes the function again up to the given number of
+    /// times when it fails with a transient error.
+    retry: Option<LitInt>,
+}
+
+impl Parse for FunctionArguments {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut result = FunctionArguments::default();
+        let punctuated: Punctuated<Meta, Token![,]> = input.parse_terminated(Meta::parse)?;
+        for meta in punctuated {
+            match (
+                meta.path()
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .as_deref()
+                    .unwrap_or_default(),
+                meta,
+            ) {
+                (
+                    "priority",
+                    Meta::NameValue(MetaNameValue {
+                        lit: Lit::Str(str), ..
+                    }),
+                ) => {
+                    let variant = match str.value().as_str() {
+                        "background" => "Background",
+                        "normal" => "Normal",
+                        "interactive" => "Interactive",
+                        _ => {
+                            return Err(Error::new_spanned(
+                                &str,
+                                "expected \"background\", \"normal\" or \"interactive\"",
+                            ))
+                        }
+                    };
+                    result.priority = Some(Ident::new(variant, str.span()));
+                }
+                ("remote", Meta::Path(_)) => {
+                    result.remote = true;
+                }
+                (
+                    "slow_threshold_ms",
+                    Meta::NameValue(MetaNameValue {
+                        lit: Lit::Int(int), ..
+                    }),
+                ) => {
+                    int.base10_parse::<u64>()?;
+                    result.slow_threshold_ms = Some(int);
+                }
+                (
+                    "concurrency_limit",
+                    Meta::NameValue(MetaNameValue {
+                        lit: Lit::Int(int), ..
+                    }),
+                ) => {
+                    if int.base10_parse::<usize>()? == 0 {
+                        return Err(Error::new_spanned(&int, "expected a limit greater than 0"));
+                    }
+                    result.concurrency_limit = Some(int);
+                }
+                (
+                    "retry",
+                    Meta::NameValue(MetaNameValue {
+                        lit: Lit::Int(int), ..
+                    }),
+                ) => {
+                    int.base10_parse::<u32>()?;
+                    result.retry = Some(int);
+                }
+                (_, meta) => {
+                    return Err(Error::new_spanned(
+                        &meta,
+                        format!(
+                            "unexpected {:?}, expected \"priority\", \"remote\", \
+                             \"slow_threshold_ms\", \"concurrency_limit\" or \"retry\"",
+                            meta
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl FunctionArguments {
+    /// Parses the arguments of a `#[turbo_tasks::function]` attribute on a
+    /// method.
+    pub fn from_attribute(attr: Option<&Attribute>) -> Self {
+        match attr {
+            Some(attr) if !attr.tokens.is_empty() => attr
+                .parse_args()
+                .unwrap_or_else(|err: Error| abort!(err.span(), "{}", err)),
+            _ => Self::default(),
+        }
+    }
+}
+
 pub fn gen_native_function_code(
     name_code: TokenStream2,
     original_function: TokenStream2,
@@ -24,6 +141,119 @@ pub fn gen_native_function_code(
     inputs: &Punctuated<FnArg, Token![,]>,
     output_type: &Type,
     self_ref_type: Option<(&Ident, SelfType<'_>)>,
+    function_args: &FunctionArguments,
+) -> (TokenStream2, Vec<TokenStream2>) {
+    let (native_function_expr, input_raw_vc_arguments) = gen_native_function_expr(
+        &name_code,
+        quote! { #name_code.to_owned() },
+        original_function,
+        async_function,
+        inputs,
+        output_type,
+        self_ref_type,
+        function_args,
+    );
+    (
+        quote! {
+            #[doc(hidden)]
+            pub(crate) static #function_ident: turbo_tasks::macro_helpers::Lazy<turbo_tasks::NativeFunction> =
+                turbo_tasks::macro_helpers::Lazy::new(|| {
+                    #native_function_expr
+                });
+
+            #[doc(hidden)]
+            pub(crate) static #function_id_ident: turbo_tasks::macro_helpers::Lazy<turbo_tasks::FunctionId> =
+                turbo_tasks::macro_helpers::Lazy::new(|| {
+                    turbo_tasks::registry::get_function_id(&#function_ident)
+                });
+        },
+        input_raw_vc_arguments,
+    )
+}
+
+/// Like [gen_native_function_code], but for a function with type parameters.
+/// Instead of a static function id, it declares a function
+/// `function_id_ident` with the same generics which returns the function id
+/// of an instantiation.
+pub fn gen_generic_native_function_code(
+    name_code: TokenStream2,
+    original_function: TokenStream2,
+    function_ident: &Ident,
+    function_id_ident: &Ident,
+    generics: &Generics,
+    async_function: bool,
+    inputs: &Punctuated<FnArg, Token![,]>,
+    output_type: &Type,
+    function_args: &FunctionArguments,
+) -> (TokenStream2, Vec<TokenStream2>) {
+    let (native_function_expr, input_raw_vc_arguments) = gen_native_function_expr(
+        &name_code,
+        quote! { __name },
+        original_function,
+        async_function,
+        inputs,
+        output_type,
+        None,
+        function_args,
+    );
+    let type_params = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    (
+        quote! {
+            #[doc(hidden)]
+            pub(crate) static #function_ident: turbo_tasks::macro_helpers::Lazy<turbo_tasks::GenericNativeFunction> =
+                turbo_tasks::macro_helpers::Lazy::new(|| {
+                    turbo_tasks::GenericNativeFunction::new(#name_code)
+                });
+
+            #[doc(hidden)]
+            pub(crate) fn #function_id_ident #impl_generics() -> turbo_tasks::FunctionId #where_clause {
+                #function_ident.get_function_id::<(#(#type_params,)*)>(
+                    &[#(std::any::type_name::<#type_params>()),*],
+                    |__name| #native_function_expr,
+                )
+            }
+        },
+        input_raw_vc_arguments,
+    )
+}
+
+/// Adds the bounds which are needed to pass values of the type parameters to
+/// a task.
+pub fn add_generic_task_input_bounds(generics: &mut Generics) {
+    for param in &generics.params {
+        match param {
+            GenericParam::Type(_) => {}
+            GenericParam::Lifetime(_) | GenericParam::Const(_) => abort!(
+                param.span(),
+                "turbo_tasks functions only support type parameters"
+            ),
+        }
+    }
+    let type_params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let where_clause = generics.make_where_clause();
+    for ident in type_params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #ident: turbo_tasks::GenericTaskInput });
+    }
+}
+
+fn gen_native_function_expr(
+    name_code: &TokenStream2,
+    name_expr: TokenStream2,
+    original_function: TokenStream2,
+    async_function: bool,
+    inputs: &Punctuated<FnArg, Token![,]>,
+    output_type: &Type,
+    self_ref_type: Option<(&Ident, SelfType<'_>)>,
+    function_args: &FunctionArguments,
 ) -> (TokenStream2, Vec<TokenStream2>) {
     let mut input_extraction = Vec::new();
     let mut input_convert = Vec::new();
@@ -172,35 +402,39 @@ pub fn gen_native_function_code(
         },
         (false, false) => quote! { Ok(#original_call_code.into()) },
     };
+    let priority_code = function_args.priority.as_ref().map(|priority| {
+        quote! { .with_priority(turbo_tasks::TaskPriority::#priority) }
+    });
+    let remote_code = function_args.remote.then(|| quote! { .with_remote() });
+    let slow_threshold_code = function_args.slow_threshold_ms.as_ref().map(|ms| {
+        quote! { .with_slow_threshold(std::time::Duration::from_millis(#ms)) }
+    });
+    let concurrency_limit_code = function_args
+        .concurrency_limit
+        .as_ref()
+        .map(|limit| quote! { .with_concurrency_limit(#limit) });
+    let retry_code = function_args.retry.as_ref().map(|retries| {
+        quote! { .with_retry(turbo_tasks::retry::RetryPolicy::new(#retries)) }
+    });
     (
         quote! {
-            #[doc(hidden)]
-            pub(crate) static #function_ident: turbo_tasks::macro_helpers::Lazy<turbo_tasks::NativeFunction> =
-                turbo_tasks::macro_helpers::Lazy::new(|| {
-                    turbo_tasks::NativeFunction::new(#name_code.to_owned(), Box::new(|inputs| {
-                        let mut __iter = inputs.iter();
-                        #(#input_extraction)*
-                        if __iter.next().is_some() {
-                            return Err(anyhow::anyhow!("{}() called with too many arguments", #name_code));
-                        }
-                        #(#input_convert)*
-                        Ok(Box::new(move || {
-                            #(#input_clone)*
-                            Box::pin(turbo_tasks::macro_helpers::tracing::Instrument::instrument(async move {
-                                #(#input_final)*
-                                let turbo_tasks_result = #original_call_code;
-                                turbo_tasks::macro_helpers::notify_scheduled_tasks();
-                                turbo_tasks_result
-                            }, turbo_tasks::macro_helpers::tracing::trace_span!(#name_code)))
-                        }))
-                    }))
-                });
-
-            #[doc(hidden)]
-            pub(crate) static #function_id_ident: turbo_tasks::macro_helpers::Lazy<turbo_tasks::FunctionId> =
-                turbo_tasks::macro_helpers::Lazy::new(|| {
-                    turbo_tasks::registry::get_function_id(&#function_ident)
-                });
+            turbo_tasks::NativeFunction::new(#name_expr, Box::new(|inputs| {
+                let mut __iter = inputs.iter();
+                #(#input_extraction)*
+                if __iter.next().is_some() {
+                    return Err(anyhow::anyhow!("{}() called with too many arguments", #name_code));
+                }
+                #(#input_convert)*
+                Ok(Box::new(move || {
+                    #(#input_clone)*
+                    Box::pin(turbo_tasks::macro_helpers::tracing::Instrument::instrument(async move {
+                        #(#input_final)*
+                        let turbo_tasks_result = #original_call_code;
+                        turbo_tasks::macro_helpers::notify_scheduled_tasks();
+                        turbo_tasks_result
+                    }, turbo_tasks::macro_helpers::tracing::trace_span!(#name_code)))
+                }))
+            }))#priority_code #remote_code #slow_threshold_code #concurrency_limit_code #retry_code
         },
         input_raw_vc_arguments,
     )
@@ -4,7 +4,10 @@ use quote::quote;
 use syn::{parse_macro_input, ItemFn};
 use turbo_tasks_macros_shared::get_function_ident;
 
-use crate::func::{gen_native_function_code, split_signature};
+use crate::func::{
+    add_generic_task_input_bounds, gen_generic_native_function_code, gen_native_function_code,
+    split_signature, FunctionArguments,
+};
 
 fn get_function_id_ident(ident: &Ident) -> Ident {
     Ident::new(
@@ -13,7 +16,12 @@ fn get_function_id_ident(ident: &Ident) -> Ident {
     )
 }
 
-pub fn function(_args: TokenStream, input: TokenStream) -> TokenStream {
+fn get_generic_function_id_ident(ident: &Ident) -> Ident {
+    Ident::new(&format!("__{}_function_id", ident), ident.span())
+}
+
+pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
+    let function_args = parse_macro_input!(args as FunctionArguments);
     let item = parse_macro_input!(input as ItemFn);
     let ItemFn {
         attrs,
@@ -27,6 +35,43 @@ pub fn function(_args: TokenStream, input: TokenStream) -> TokenStream {
     let function_id_ident = get_function_id_ident(ident);
     let inline_ident = &inline_sig.ident;
 
+    if !sig.generics.params.is_empty() {
+        let mut generics = sig.generics.clone();
+        add_generic_task_input_bounds(&mut generics);
+        let mut external_sig = external_sig;
+        external_sig.generics = generics.clone();
+        let function_id_ident = get_generic_function_id_ident(ident);
+        let (_, ty_generics, _) = generics.split_for_impl();
+        let turbofish = ty_generics.as_turbofish();
+
+        let (native_function_code, input_raw_vc_arguments) = gen_generic_native_function_code(
+            quote! { stringify!(#ident) },
+            quote! { #inline_ident #turbofish },
+            &function_ident,
+            &function_id_ident,
+            &generics,
+            sig.asyncness.is_some(),
+            &sig.inputs,
+            &output_type,
+            &function_args,
+        );
+
+        return quote! {
+            #[must_use]
+            #(#attrs)*
+            #vis #external_sig {
+                let result = turbo_tasks::dynamic_call(#function_id_ident #turbofish(), vec![#(#input_raw_vc_arguments),*]);
+                #convert_result_code
+            }
+
+            #(#attrs)*
+            #vis #inline_sig #block
+
+            #native_function_code
+        }
+        .into();
+    }
+
     let (native_function_code, input_raw_vc_arguments) = gen_native_function_code(
         quote! { stringify!(#ident) },
         quote! { #inline_ident },
@@ -36,6 +81,7 @@ pub fn function(_args: TokenStream, input: TokenStream) -> TokenStream {
         &sig.inputs,
         &output_type,
         None,
+        &function_args,
     );
 
     quote! {
@@ -11,7 +11,7 @@ use turbo_tasks_macros_shared::{
 };
 
 use crate::{
-    func::{gen_native_function_code, split_signature, SelfType},
+    func::{gen_native_function_code, split_signature, FunctionArguments, SelfType},
     util::*,
 };
 
@@ -110,6 +110,7 @@ pub fn value_impl(_args: TokenStream, input: TokenStream) -> TokenStream {
                     &sig.inputs,
                     &output_type,
                     Some((vc_ident, SelfType::Ref)),
+                    &FunctionArguments::from_attribute(function_attr),
                 );
 
                 functions.push(quote! {
@@ -195,6 +196,7 @@ pub fn value_impl(_args: TokenStream, input: TokenStream) -> TokenStream {
                     inputs,
                     &output_type,
                     Some((&ref_ident, SelfType::Value(struct_ident))),
+                    &FunctionArguments::from_attribute(function_attr),
                 );
                 let mut new_sig = sig.clone();
                 new_sig.ident = internal_function_ident;
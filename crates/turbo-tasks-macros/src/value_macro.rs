@@ -1,13 +1,14 @@
 use proc_macro::TokenStream;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro_error::abort;
 use quote::quote;
 use syn::{
     parse::{Parse, ParseStream},
-    parse_macro_input,
+    parse_macro_input, parse_quote,
     punctuated::Punctuated,
     spanned::Spanned,
-    Error, Fields, FieldsUnnamed, Item, ItemEnum, ItemStruct, Lit, LitStr, Meta, MetaNameValue,
-    Result, Token,
+    Error, Fields, FieldsUnnamed, GenericParam, Generics, Item, ItemEnum, ItemStruct, Lit, LitStr,
+    Meta, MetaNameValue, Result, Token, Type, Visibility,
 };
 use turbo_tasks_macros_shared::{get_ref_ident, get_register_value_type_ident};
 
@@ -222,9 +223,19 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         transparent,
     } = parse_macro_input!(args as ValueArguments);
 
-    let (vis, ident) = match &item {
-        Item::Enum(ItemEnum { vis, ident, .. }) => (vis, ident),
-        Item::Struct(ItemStruct { vis, ident, .. }) => (vis, ident),
+    let (vis, ident, generics) = match &item {
+        Item::Enum(ItemEnum {
+            vis,
+            ident,
+            generics,
+            ..
+        }) => (vis, ident, generics),
+        Item::Struct(ItemStruct {
+            vis,
+            ident,
+            generics,
+            ..
+        }) => (vis, ident, generics),
         _ => {
             item.span().unwrap().error("unsupported syntax").emit();
 
@@ -256,6 +267,23 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     }
 
+    if !generics.params.is_empty() {
+        return generic_value(
+            &item,
+            vis,
+            ident,
+            generics,
+            inner_type,
+            GenericValueArguments {
+                serialization_mode,
+                into_mode,
+                cell_mode,
+                manual_eq,
+            },
+        )
+        .into();
+    }
+
     let into_update_op = match into_mode {
         IntoMode::None => None,
         IntoMode::New => Some(quote! {
@@ -693,3 +721,403 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
 
     expanded.into()
 }
+
+struct GenericValueArguments {
+    serialization_mode: SerializationMode,
+    into_mode: IntoMode,
+    cell_mode: CellMode,
+    manual_eq: bool,
+}
+
+/// Expands a `#[turbo_tasks::value]` with type parameters. Every instantiation
+/// is registered as separate value type on first use. Generic values don't
+/// support `#[turbo_tasks::value_impl]` and value traits.
+fn generic_value(
+    item: &Item,
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    inner_type: Option<&Type>,
+    GenericValueArguments {
+        serialization_mode,
+        into_mode,
+        cell_mode,
+        manual_eq,
+    }: GenericValueArguments,
+) -> TokenStream2 {
+    for param in &generics.params {
+        match param {
+            GenericParam::Type(_) => {}
+            GenericParam::Lifetime(_) | GenericParam::Const(_) => abort!(
+                param.span(),
+                "turbo_tasks values only support type parameters"
+            ),
+        }
+    }
+    let params = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    let ref_ident = get_ref_ident(ident);
+    let read_ref_ident = get_read_ref_ident(ident);
+    let value_type_ident = get_value_type_ident(ident);
+
+    // The bounds which are needed to store the value in a cell.
+    let mut value_generics = generics.clone();
+    let where_clause = value_generics.make_where_clause();
+    for param in &params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #param: Send + Sync + 'static });
+    }
+    let new_value_type = match serialization_mode {
+        SerializationMode::None => quote! {
+            turbo_tasks::ValueType::new::<Self>()
+        },
+        SerializationMode::Auto | SerializationMode::Custom => {
+            where_clause.predicates.push(parse_quote! {
+                #ident<#(#params),*>: serde::Serialize + for<'de> serde::Deserialize<'de>
+            });
+            quote! {
+                turbo_tasks::ValueType::new_with_any_serialization::<Self>()
+            }
+        }
+        SerializationMode::AutoForInput | SerializationMode::CustomForInput => abort!(
+            ident.span(),
+            "generic turbo_tasks values don't support serialization for inputs"
+        ),
+    };
+    if matches!(cell_mode, CellMode::Shared) || matches!(into_mode, IntoMode::Shared) {
+        where_clause
+            .predicates
+            .push(parse_quote! { #ident<#(#params),*>: PartialEq });
+    }
+    let (impl_generics, ty_generics, where_clause) = value_generics.split_for_impl();
+    let vc_generics = quote! { <#(#params),*> };
+
+    let derive = match serialization_mode {
+        SerializationMode::Auto | SerializationMode::AutoForInput => quote! {
+            #[derive(turbo_tasks::trace::TraceRawVcs, serde::Serialize, serde::Deserialize)]
+        },
+        _ => quote! {
+            #[derive(turbo_tasks::trace::TraceRawVcs)]
+        },
+    };
+    let eq_derive = if manual_eq {
+        quote!()
+    } else {
+        quote!(
+            #[derive(PartialEq, Eq)]
+        )
+    };
+    let repr = if inner_type.is_some() {
+        quote!(#[repr(transparent)])
+    } else {
+        quote!()
+    };
+
+    let update_op = |shared: bool| {
+        if shared {
+            quote! { cell.compare_and_update_shared(content); }
+        } else {
+            quote! { cell.update_shared(content); }
+        }
+    };
+    let cell_update_op = update_op(matches!(cell_mode, CellMode::Shared));
+    let into = match into_mode {
+        IntoMode::None => quote! {},
+        IntoMode::New | IntoMode::Shared => {
+            let update_op = update_op(matches!(into_mode, IntoMode::Shared));
+            quote! {
+                impl #impl_generics From<#ident #ty_generics> for turbo_tasks::RawVc #where_clause {
+                    fn from(content: #ident #ty_generics) -> Self {
+                        let cell = turbo_tasks::macro_helpers::find_cell_by_type(
+                            <#ident #ty_generics as turbo_tasks::Typed>::get_value_type_id(),
+                        );
+                        #update_op
+                        cell.into()
+                    }
+                }
+
+                impl #impl_generics From<#ident #ty_generics> for #ref_ident #ty_generics #where_clause {
+                    fn from(content: #ident #ty_generics) -> Self {
+                        turbo_tasks::RawVc::from(content).into()
+                    }
+                }
+            }
+        }
+    };
+
+    let (cell_prefix, cell_arg_type, cell_convert_content, cell_access_content, cast, read_ref) =
+        if let Some(inner_type) = inner_type {
+            (
+                quote! { pub },
+                quote! { #inner_type },
+                quote! { let content = #ident(content); },
+                quote! { content.0 },
+                quote! { turbo_tasks::TransparentValueCast<#ident #ty_generics, #inner_type> },
+                quote! { turbo_tasks::ReadRef<#ident #vc_generics, #inner_type> },
+            )
+        } else {
+            (
+                if let IntoMode::New | IntoMode::Shared = into_mode {
+                    quote! { pub }
+                } else {
+                    quote! {}
+                },
+                quote! { #ident #ty_generics },
+                quote! {},
+                quote! { content },
+                quote! { turbo_tasks::ValueCast<#ident #ty_generics> },
+                quote! { turbo_tasks::ReadRef<#ident #vc_generics, #ident #vc_generics> },
+            )
+        };
+    let future_type = quote! { turbo_tasks::ReadRawVcFuture<#cast> };
+    let (read, strongly_consistent_read) = if let Some(inner_type) = inner_type {
+        (
+            quote! {
+                // SAFETY: Types are binary identical via #[repr(transparent)]
+                unsafe { self.node.into_transparent_read::<#ident #ty_generics, #inner_type>() }
+            },
+            quote! {
+                // SAFETY: Types are binary identical via #[repr(transparent)]
+                unsafe {
+                    self.node
+                        .into_transparent_strongly_consistent_read::<#ident #ty_generics, #inner_type>()
+                }
+            },
+        )
+    } else {
+        (
+            quote! { self.node.into_read::<#ident #ty_generics>() },
+            quote! { self.node.into_strongly_consistent_read::<#ident #ty_generics>() },
+        )
+    };
+    let strongly_consistent_doc = strongly_consistent_doccomment();
+    let doc_msg_refer_to_ident = format!(" Vc for [`{ident}`]");
+
+    quote! {
+        #derive
+        #eq_derive
+        #repr
+        #item
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Places a value in a cell of the current task.
+            ///
+            /// Cell is selected based on the value type and call order of `cell`.
+            #cell_prefix fn cell(self) -> #ref_ident #ty_generics {
+                let content = self;
+                #ref_ident::cell(#cell_access_content)
+            }
+        }
+
+        #[doc(hidden)]
+        static #value_type_ident: turbo_tasks::macro_helpers::Lazy<turbo_tasks::GenericValueType> =
+            turbo_tasks::macro_helpers::Lazy::new(turbo_tasks::GenericValueType::new);
+
+        impl #impl_generics turbo_tasks::Typed for #ident #ty_generics #where_clause {
+            type Vc = #ref_ident #ty_generics;
+
+            fn get_value_type_id() -> turbo_tasks::ValueTypeId {
+                #value_type_ident.get_value_type_id::<Self>(|| #new_value_type)
+            }
+        }
+
+        #[doc = #doc_msg_refer_to_ident]
+        ///
+        /// A reference to a value created by a turbo-tasks function, see the Vcs of
+        /// non-generic values.
+        #[must_use]
+        #vis struct #ref_ident #vc_generics {
+            node: turbo_tasks::RawVc,
+            _phantom: std::marker::PhantomData<fn() -> (#(#params,)*)>,
+        }
+
+        /// see [turbo_tasks::ReadRef]
+        #vis type #read_ref_ident #vc_generics = #read_ref;
+
+        impl #impl_generics #ref_ident #ty_generics #where_clause {
+            /// Places a value in a cell of the current task.
+            ///
+            /// Cell is selected based on the value type and call order of `cell`.
+            #cell_prefix fn cell(content: #cell_arg_type) -> Self {
+                let cell = turbo_tasks::macro_helpers::find_cell_by_type(
+                    <#ident #ty_generics as turbo_tasks::Typed>::get_value_type_id(),
+                );
+                #cell_convert_content
+                #cell_update_op
+                turbo_tasks::RawVc::from(cell).into()
+            }
+
+            /// see [turbo_tasks::RawVc::resolve]
+            pub async fn resolve(self) -> turbo_tasks::Result<Self> {
+                Ok(self.node.resolve().await?.into())
+            }
+
+            /// see [turbo_tasks::RawVc::resolve_strongly_consistent]
+            pub async fn resolve_strongly_consistent(self) -> turbo_tasks::Result<Self> {
+                Ok(self.node.resolve_strongly_consistent().await?.into())
+            }
+
+            pub async fn resolve_from(super_trait_vc: impl std::convert::Into<turbo_tasks::RawVc>) -> Result<Option<Self>, turbo_tasks::ResolveTypeError> {
+                let raw_vc: turbo_tasks::RawVc = super_trait_vc.into();
+                let raw_vc = raw_vc
+                    .resolve_value(<#ident #ty_generics as turbo_tasks::Typed>::get_value_type_id())
+                    .await?;
+                Ok(raw_vc.map(Self::from))
+            }
+
+            #strongly_consistent_doc
+            pub fn strongly_consistent(self) -> #future_type {
+                #strongly_consistent_read
+            }
+        }
+
+        impl #impl_generics turbo_tasks::ValueVc for #ref_ident #ty_generics #where_clause {
+            #[inline]
+            fn get_value_type_id() -> turbo_tasks::ValueTypeId {
+                <#ident #ty_generics as turbo_tasks::Typed>::get_value_type_id()
+            }
+
+            #[inline]
+            fn get_trait_type_ids() -> Box<dyn Iterator<Item = turbo_tasks::TraitTypeId>> {
+                Box::new(turbo_tasks::registry::get_value_type(<Self as turbo_tasks::ValueVc>::get_value_type_id()).traits_iter())
+            }
+        }
+
+        impl #impl_generics std::future::IntoFuture for #ref_ident #ty_generics #where_clause {
+            type Output = turbo_tasks::Result<#read_ref_ident #ty_generics>;
+            type IntoFuture = #future_type;
+            fn into_future(self) -> Self::IntoFuture {
+                #read
+            }
+        }
+
+        impl #impl_generics std::future::IntoFuture for &#ref_ident #ty_generics #where_clause {
+            type Output = turbo_tasks::Result<#read_ref_ident #ty_generics>;
+            type IntoFuture = #future_type;
+            fn into_future(self) -> Self::IntoFuture {
+                #read
+            }
+        }
+
+        #into
+
+        impl #vc_generics Clone for #ref_ident #vc_generics {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl #vc_generics Copy for #ref_ident #vc_generics {}
+
+        impl #vc_generics std::fmt::Debug for #ref_ident #vc_generics {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!(#ref_ident)).field("node", &self.node).finish()
+            }
+        }
+
+        impl #vc_generics PartialEq for #ref_ident #vc_generics {
+            fn eq(&self, other: &Self) -> bool {
+                self.node == other.node
+            }
+        }
+
+        impl #vc_generics Eq for #ref_ident #vc_generics {}
+
+        impl #vc_generics PartialOrd for #ref_ident #vc_generics {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl #vc_generics Ord for #ref_ident #vc_generics {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.node.cmp(&other.node)
+            }
+        }
+
+        impl #vc_generics std::hash::Hash for #ref_ident #vc_generics {
+            fn hash<__H: std::hash::Hasher>(&self, state: &mut __H) {
+                self.node.hash(state)
+            }
+        }
+
+        impl #vc_generics serde::Serialize for #ref_ident #vc_generics {
+            fn serialize<__S: serde::Serializer>(&self, serializer: __S) -> Result<__S::Ok, __S::Error> {
+                serde::Serialize::serialize(&self.node, serializer)
+            }
+        }
+
+        impl<'de, #(#params),*> serde::Deserialize<'de> for #ref_ident #vc_generics {
+            fn deserialize<__D: serde::Deserializer<'de>>(deserializer: __D) -> Result<Self, __D::Error> {
+                Ok(<turbo_tasks::RawVc as serde::Deserialize>::deserialize(deserializer)?.into())
+            }
+        }
+
+        impl #vc_generics turbo_tasks::CollectiblesSource for #ref_ident #vc_generics {
+            fn take_collectibles<__C: turbo_tasks::ValueTraitVc>(self) -> turbo_tasks::CollectiblesFuture<__C> {
+                self.node.take_collectibles()
+            }
+
+            fn peek_collectibles<__C: turbo_tasks::ValueTraitVc>(self) -> turbo_tasks::CollectiblesFuture<__C> {
+                self.node.peek_collectibles()
+            }
+        }
+
+        impl #vc_generics turbo_tasks::FromTaskInput<'_> for #ref_ident #vc_generics {
+            type Error = turbo_tasks::Error;
+
+            fn try_from(value: &turbo_tasks::TaskInput) -> Result<Self, Self::Error> {
+                Ok(Self::from(<turbo_tasks::RawVc as std::convert::TryFrom<&turbo_tasks::TaskInput>>::try_from(value)?))
+            }
+        }
+
+        impl #vc_generics From<turbo_tasks::RawVc> for #ref_ident #vc_generics {
+            fn from(node: turbo_tasks::RawVc) -> Self {
+                Self {
+                    node,
+                    _phantom: std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #vc_generics From<#ref_ident #vc_generics> for turbo_tasks::RawVc {
+            fn from(node_ref: #ref_ident #vc_generics) -> Self {
+                node_ref.node
+            }
+        }
+
+        impl #vc_generics From<&#ref_ident #vc_generics> for turbo_tasks::RawVc {
+            fn from(node_ref: &#ref_ident #vc_generics) -> Self {
+                node_ref.node
+            }
+        }
+
+        impl #vc_generics From<#ref_ident #vc_generics> for turbo_tasks::TaskInput {
+            fn from(node_ref: #ref_ident #vc_generics) -> Self {
+                node_ref.node.into()
+            }
+        }
+
+        impl #vc_generics From<&#ref_ident #vc_generics> for turbo_tasks::TaskInput {
+            fn from(node_ref: &#ref_ident #vc_generics) -> Self {
+                node_ref.node.into()
+            }
+        }
+
+        impl #vc_generics turbo_tasks::trace::TraceRawVcs for #ref_ident #vc_generics {
+            fn trace_raw_vcs(&self, context: &mut turbo_tasks::trace::TraceRawVcsContext) {
+                turbo_tasks::trace::TraceRawVcs::trace_raw_vcs(&self.node, context);
+            }
+        }
+
+        impl #vc_generics turbo_tasks::debug::ValueDebugFormat for #ref_ident #vc_generics {
+            fn value_debug_format(&self, _depth: usize) -> turbo_tasks::debug::ValueDebugFormatString {
+                turbo_tasks::debug::ValueDebugFormatString::Sync(std::any::type_name::<Self>().to_string())
+            }
+        }
+    }
+}
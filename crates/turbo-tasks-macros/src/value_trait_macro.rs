@@ -11,7 +11,7 @@ use turbo_tasks_macros_shared::{
 };
 
 use crate::{
-    func::{gen_native_function_code, split_signature, SelfType},
+    func::{gen_native_function_code, split_signature, FunctionArguments, SelfType},
     util::*,
 };
 
@@ -117,6 +117,7 @@ pub fn value_trait(args: TokenStream, input: TokenStream) -> TokenStream {
                 inputs,
                 &output_type,
                 Some((&ref_ident, SelfType::ValueTrait)),
+                &FunctionArguments::default(),
             );
 
             trait_fns.push(quote! {
@@ -294,6 +295,23 @@ pub fn value_trait(args: TokenStream, input: TokenStream) -> TokenStream {
                 #ref_ident { node: raw_vc }
             }
 
+            /// Casts to a super trait, see [turbo_tasks::FromSubTrait].
+            pub fn upcast<T: turbo_tasks::FromSubTrait<Self>>(self) -> T {
+                T::from_sub_trait(self)
+            }
+
+            /// Casts to any other trait the value implements, e.g. a sub trait or a
+            /// super trait of a super trait. Returns `None` when the value doesn't
+            /// implement it.
+            pub async fn try_cast<T: turbo_tasks::ValueTraitVc>(self) -> Result<Option<T>, turbo_tasks::ResolveTypeError> {
+                self.node.try_cast_trait().await
+            }
+
+            /// Returns true when the value implements the trait `T` too.
+            pub async fn implements<T: turbo_tasks::ValueTraitVc>(self) -> Result<bool, turbo_tasks::ResolveTypeError> {
+                self.node.implements_trait(T::get_trait_type_id()).await
+            }
+
             #strongly_consistent
         }
 
@@ -60,6 +60,14 @@ impl Cell {
         }
     }
 
+    /// Returns the content of the cell if it has a value.
+    pub fn content(&self) -> Option<&CellContent> {
+        match self {
+            Cell::Value { content, .. } => Some(content),
+            _ => None,
+        }
+    }
+
     /// Removes a task from the list of dependent tasks.
     pub fn remove_dependent_task(&mut self, task: TaskId) {
         match self {
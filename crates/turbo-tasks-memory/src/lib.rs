@@ -17,6 +17,7 @@ mod memory_backend_with_pg;
 mod output;
 mod priority_pair;
 pub mod scope;
+pub mod snapshot;
 pub mod stats;
 mod task;
 pub mod viz;
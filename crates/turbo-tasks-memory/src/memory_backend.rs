@@ -27,8 +27,10 @@ use turbo_tasks::{
     },
     event::EventListener,
     primitives::RawVcSetVc,
+    remote::RemoteExecutor,
+    task_statistics::TaskStatisticsCollector,
     util::{IdFactory, NoMoveVec},
-    CellId, RawVc, TaskId, TraitTypeId, TurboTasksBackendApi, Unused,
+    CellId, RawVc, TaskId, TaskPriority, TaskStatistics, TraitTypeId, TurboTasksBackendApi, Unused,
 };
 
 use crate::{
@@ -55,6 +57,8 @@ pub struct MemoryBackend {
     gc_queue: Option<GcQueue>,
     idle_gc_active: AtomicBool,
     scope_add_remove_priority: PriorityPair,
+    task_statistics: TaskStatisticsCollector,
+    pub(crate) remote_executor: Option<Arc<dyn RemoteExecutor>>,
 }
 
 impl Default for MemoryBackend {
@@ -64,6 +68,11 @@ impl Default for MemoryBackend {
 }
 
 impl MemoryBackend {
+    /// Creates a backend with a memory budget in bytes. When the memory usage
+    /// gets close to it, garbage collection drops the cells and tasks which
+    /// haven't been used for the longest time and are cheap to recompute.
+    /// They are recomputed when they are read again. `usize::MAX` disables
+    /// garbage collection.
     pub fn new(memory_limit: usize) -> Self {
         let memory_task_scopes = NoMoveVec::new();
         let scope_id_factory = IdFactory::new();
@@ -83,9 +92,22 @@ impl MemoryBackend {
             gc_queue: (memory_limit != usize::MAX).then(GcQueue::new),
             idle_gc_active: AtomicBool::new(false),
             scope_add_remove_priority: PriorityPair::new(),
+            task_statistics: if cfg!(feature = "lightweight_stats") {
+                TaskStatisticsCollector::counters_only()
+            } else {
+                TaskStatisticsCollector::new()
+            },
+            remote_executor: None,
         }
     }
 
+    /// Executes the tasks of functions marked with
+    /// `#[turbo_tasks::function(remote)]` with the `executor`.
+    pub fn with_remote_executor(mut self, executor: Arc<dyn RemoteExecutor>) -> Self {
+        self.remote_executor = Some(executor);
+        self
+    }
+
     fn connect_task_child(
         &self,
         parent: TaskId,
@@ -116,10 +138,23 @@ impl MemoryBackend {
         func: F,
     ) -> Result<Result<T, EventListener>> {
         self.with_task(id, |task| {
-            task.get_or_wait_output(strongly_consistent, func, note, self, turbo_tasks)
+            let result =
+                task.get_or_wait_output(strongly_consistent, func, note, self, turbo_tasks);
+            if matches!(result, Ok(Ok(_))) {
+                self.register_access(task, turbo_tasks);
+            }
+            result
         })
     }
 
+    /// Tracks when a task was used last, so that garbage collection can
+    /// collect the tasks which haven't been used for the longest time first.
+    fn register_access(&self, task: &Task, turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>) {
+        if self.gc_queue.is_some() {
+            task.register_access(turbo_tasks.program_duration_until(Instant::now()));
+        }
+    }
+
     pub fn with_all_cached_tasks(&self, mut func: impl FnMut(TaskId)) {
         for id in self.task_cache.clone().into_read_only().values() {
             func(*id);
@@ -434,6 +469,54 @@ impl Backend for MemoryBackend {
         DEPENDENCIES_TO_TRACK.scope(Default::default(), future)
     }
 
+    fn get_task_priority(&self, task: TaskId) -> TaskPriority {
+        self.with_task(task, |task| task.get_priority())
+    }
+
+    fn get_task_slow_threshold(&self, task: TaskId) -> Option<Duration> {
+        self.with_task(task, |task| task.get_slow_threshold())
+    }
+
+    fn get_task_arguments(&self, task: TaskId) -> Option<String> {
+        self.with_task(task, |task| task.get_arguments())
+    }
+
+    fn task_statistics(&self) -> TaskStatistics {
+        let tasks = self
+            .task_cache
+            .iter()
+            .map(|entry| *entry.value())
+            .collect::<Vec<_>>();
+        let dirty_tasks = tasks
+            .iter()
+            .filter(|&&task| self.with_task(task, |task| task.is_dirty()))
+            .count();
+        #[allow(unused_mut)]
+        let mut statistics = self.task_statistics.snapshot(dirty_tasks);
+        #[cfg(feature = "memory_stats")]
+        {
+            let mut memory = turbo_tasks::MemoryStatistics::default();
+            for task in tasks {
+                self.with_task(task, |task| task.add_memory_statistics(&mut memory));
+            }
+            statistics.memory = Some(memory);
+        }
+        statistics
+    }
+
+    fn get_unfinished_task_count(&self, task: TaskId) -> Option<usize> {
+        self.with_task(task, |task| task.get_unfinished_task_count(self))
+    }
+
+    fn is_vc_loaded(&self, vc: RawVc) -> bool {
+        match vc {
+            RawVc::TaskOutput(task) => self.with_task(task, |task| task.is_loaded(None)),
+            RawVc::TaskCell(task, index) => {
+                self.with_task(task, |task| task.is_loaded(Some(index)))
+            }
+        }
+    }
+
     fn try_start_task_execution(
         &self,
         task: TaskId,
@@ -453,6 +536,14 @@ impl Backend for MemoryBackend {
         })
     }
 
+    fn task_execution_cancelled(
+        &self,
+        task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) {
+        self.with_task(task, |task| task.execution_cancelled(self))
+    }
+
     fn task_execution_completed(
         &self,
         task_id: TaskId,
@@ -462,6 +553,10 @@ impl Backend for MemoryBackend {
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
     ) -> bool {
         let reexecute = self.with_task(task_id, |task| {
+            if let Some(function_id) = task.get_native_function() {
+                self.task_statistics
+                    .register_execution(function_id, duration);
+            }
             task.execution_completed(duration, instant, stateful, self, turbo_tasks)
         });
         if !reexecute {
@@ -531,7 +626,10 @@ impl Backend for MemoryBackend {
                         move || format!("reading {} {} from {}", task_id, index, reader),
                     )
                 }) {
-                    Ok(content) => Ok(Ok(content)),
+                    Ok(content) => {
+                        self.register_access(task, turbo_tasks);
+                        Ok(Ok(content))
+                    }
                     Err(RecomputingCell { listener, schedule }) => {
                         if schedule {
                             task.recompute(self, turbo_tasks);
@@ -656,8 +754,14 @@ impl Backend for MemoryBackend {
             self.lookup_and_connect_task(parent_task, &self.task_cache, &task_type, turbo_tasks)
         {
             // fast pass without creating a new task
+            if let PersistentTaskType::Native(function_id, _) = &task_type {
+                self.task_statistics.increment_cache_hits(*function_id);
+            }
             task
         } else {
+            if let PersistentTaskType::Native(function_id, _) = &task_type {
+                self.task_statistics.increment_tasks(*function_id);
+            }
             // It's important to avoid overallocating memory as this will go into the task
             // cache and stay there forever. We can to be as small as possible.
             task_type.shrink_to_fit();
@@ -700,6 +804,10 @@ impl Backend for MemoryBackend {
         self.with_task(task, |task| task.mark_as_finished(self))
     }
 
+    fn cancel_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>) {
+        self.with_task(task, |task| task.cancel(self, turbo_tasks))
+    }
+
     fn create_transient_task(
         &self,
         task_type: TransientTaskType,
@@ -65,6 +65,8 @@ enum TaskFreshness {
 struct MemoryTaskState {
     need_persist: bool,
     has_changes: bool,
+    /// The last execution depended on state outside of the task graph
+    session_dependent: bool,
     freshness: TaskFreshness,
     cells: HashMap<CellId, (TaskCell, AutoSet<TaskId, BuildNoHashHasher<TaskId>>)>,
     output: Option<Result<RawVc, SharedError>>,
@@ -81,6 +83,7 @@ impl MemoryTaskState {
             freshness,
             need_persist: Default::default(),
             has_changes: Default::default(),
+            session_dependent: Default::default(),
             cells: HashMap::default(),
             output: Default::default(),
             output_dependent: Default::default(),
@@ -276,6 +279,7 @@ impl<P: PersistedGraph> MemoryBackendWithPersistedGraph<P> {
                     children: data.children.into_iter().collect(),
                     need_persist: Default::default(),
                     has_changes: Default::default(),
+                    session_dependent: Default::default(),
                     event: Event::new(move || format!("MemoryTaskState({task})::event")),
                     event_cells: Event::new(move || {
                         format!("MemoryTaskState({task})::event_cells")
@@ -773,6 +777,7 @@ impl<P: PersistedGraph> MemoryBackendWithPersistedGraph<P> {
                                 ref mut need_persist,
                                 ref output,
                                 ref mut has_changes,
+                                session_dependent,
                                 ref children,
                                 ref dependencies,
                                 ref cells,
@@ -819,6 +824,7 @@ impl<P: PersistedGraph> MemoryBackendWithPersistedGraph<P> {
                                         let task_state =
                                             turbo_tasks::persisted_graph::PersistTaskState {
                                                 externally_active,
+                                                session_dependent,
                                             };
                                         if let Some(PersistResult {
                                             tasks_to_activate,
@@ -1071,6 +1077,7 @@ impl<P: PersistedGraph> Backend for MemoryBackendWithPersistedGraph<P> {
             println!("start {} {:?}", task, task_info.task_type);
         }
         mem_state.freshness = TaskFreshness::NeverExecuted;
+        mem_state.session_dependent = false;
         let deps = take(&mut mem_state.dependencies);
         let children = take(&mut mem_state.children);
         drop(state);
@@ -1557,6 +1564,17 @@ impl<P: PersistedGraph> Backend for MemoryBackendWithPersistedGraph<P> {
         }
     }
 
+    fn mark_own_task_as_session_dependent(
+        &self,
+        task: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackendWithPersistedGraph<P>>,
+    ) {
+        let (mut state, _) = self.mem_state_mut(task, turbo_tasks);
+        if let Some(mem_state) = state.memory.as_mut() {
+            mem_state.session_dependent = true;
+        }
+    }
+
     fn connect_task(
         &self,
         _task: TaskId,
@@ -1634,11 +1652,11 @@ impl<'a, P: PersistedGraph> PersistedGraphApi for MemoryBackendPersistedGraphApi
         }
     }
 
-    fn lookup_task_type(&self, id: TaskId) -> &PersistentTaskType {
-        let task = self.backend.tasks.get(*id).unwrap();
+    fn lookup_task_type(&self, id: TaskId) -> Option<&PersistentTaskType> {
+        let task = self.backend.tasks.get(*id)?;
         match &task.task_type {
-            TaskType::Persistent(ty) => ty,
-            _ => panic!("lookup_task_type should only be used for PersistentTaskType"),
+            TaskType::Persistent(ty) => Some(ty),
+            _ => None,
         }
     }
 }
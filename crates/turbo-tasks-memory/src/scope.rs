@@ -326,6 +326,12 @@ impl TaskScope {
         }
     }
 
+    /// The number of unfinished tasks, where every unfinished child scope
+    /// counts as one task.
+    pub fn unfinished_tasks(&self) -> usize {
+        self.unfinished_tasks.load(Ordering::Acquire).max(0) as usize
+    }
+
     pub fn has_unfinished_tasks(&self) -> Option<EventListener> {
         let state = self.state.lock();
         if state.has_unfinished_tasks {
@@ -0,0 +1,192 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::TaskId;
+
+use crate::MemoryBackend;
+
+/// A snapshot of all cached tasks of a [MemoryBackend] with hashes of their
+/// content, to find out what has been invalidated between two builds.
+///
+/// Task ids are only valid within a session, so only snapshots of the same
+/// backend can be compared.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct CacheSnapshot {
+    pub tasks: BTreeMap<usize, TaskSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TaskSnapshot {
+    pub description: String,
+    /// The hash of the output of the task, if it has been computed.
+    pub output: Option<u64>,
+    /// The hashes of the cells which have a value, by their names, e.g.
+    /// `FileContent#0`. Serializable values are hashed by their content,
+    /// other values by their identity.
+    pub cells: BTreeMap<String, u64>,
+    /// The number of times the task has been executed. Only tracked with full
+    /// task statistics.
+    pub executions: Option<u32>,
+    /// The outputs and cells read by the last execution of the task.
+    pub dependencies: BTreeSet<SnapshotDependency>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SnapshotDependency {
+    Output { task: usize },
+    Cell { task: usize, cell: String },
+}
+
+impl SnapshotDependency {
+    pub fn task(&self) -> usize {
+        match self {
+            SnapshotDependency::Output { task } | SnapshotDependency::Cell { task, .. } => *task,
+        }
+    }
+}
+
+/// The difference between two [CacheSnapshot]s.
+#[derive(Serialize, Debug, Default)]
+pub struct CacheDiff {
+    /// Tasks which are only part of the newer snapshot.
+    pub added: Vec<usize>,
+    /// Tasks which are only part of the older snapshot, e.g. because they
+    /// have been garbage collected.
+    pub removed: Vec<usize>,
+    /// Tasks which have been invalidated since the older snapshot.
+    pub invalidated: Vec<InvalidatedTask>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct InvalidatedTask {
+    pub id: usize,
+    pub description: String,
+    pub output_changed: bool,
+    pub changed_cells: Vec<String>,
+    pub reason: InvalidationReason,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InvalidationReason {
+    /// These dependencies of the task have changed.
+    Dependencies { changed: Vec<SnapshotDependency> },
+    /// None of the dependencies have changed, so the task has been
+    /// invalidated from outside, e.g. by a file watcher.
+    External,
+}
+
+impl CacheSnapshot {
+    /// Collects all cached tasks of the `backend`. Tasks which have been
+    /// unloaded from memory are skipped.
+    pub fn new(backend: &MemoryBackend) -> Self {
+        let mut snapshot = CacheSnapshot::default();
+        backend.with_all_cached_tasks(|task| {
+            backend.with_task(task, |task| task.add_to_snapshot(&mut snapshot));
+        });
+        snapshot
+    }
+
+    pub(crate) fn add_task(&mut self, id: TaskId, task: TaskSnapshot) {
+        self.tasks.insert(*id, task);
+    }
+
+    /// Compares this snapshot with a `newer` one. A task counts as
+    /// invalidated when it has been executed again, its content has changed,
+    /// or a dependency it has read has changed.
+    pub fn diff(&self, newer: &CacheSnapshot) -> CacheDiff {
+        let mut diff = CacheDiff {
+            added: newer
+                .tasks
+                .keys()
+                .filter(|id| !self.tasks.contains_key(id))
+                .copied()
+                .collect(),
+            removed: self
+                .tasks
+                .keys()
+                .filter(|id| !newer.tasks.contains_key(id))
+                .copied()
+                .collect(),
+            invalidated: Vec::new(),
+        };
+        let has_changed = |dependency: &SnapshotDependency| {
+            let (Some(old), Some(new)) = (
+                self.tasks.get(&dependency.task()),
+                newer.tasks.get(&dependency.task()),
+            ) else {
+                return false;
+            };
+            match dependency {
+                SnapshotDependency::Output { .. } => old.output != new.output,
+                SnapshotDependency::Cell { cell, .. } => old.cells.get(cell) != new.cells.get(cell),
+            }
+        };
+        for (&id, old) in self.tasks.iter() {
+            let Some(new) = newer.tasks.get(&id) else {
+                continue;
+            };
+            let output_changed = old.output != new.output;
+            let changed_cells = old
+                .cells
+                .keys()
+                .chain(new.cells.keys())
+                .filter(|cell| old.cells.get(*cell) != new.cells.get(*cell))
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            // The dependencies of the older snapshot are the ones which
+            // invalidated the task.
+            let changed_dependencies = old
+                .dependencies
+                .iter()
+                .filter(|dependency| has_changed(dependency))
+                .cloned()
+                .collect::<Vec<_>>();
+            let executed = match (old.executions, new.executions) {
+                (Some(old), Some(new)) => old != new,
+                _ => false,
+            };
+            if !executed
+                && !output_changed
+                && changed_cells.is_empty()
+                && changed_dependencies.is_empty()
+            {
+                continue;
+            }
+            diff.invalidated.push(InvalidatedTask {
+                id,
+                description: new.description.clone(),
+                output_changed,
+                changed_cells: changed_cells.into_iter().collect(),
+                reason: if changed_dependencies.is_empty() {
+                    InvalidationReason::External
+                } else {
+                    InvalidationReason::Dependencies {
+                        changed: changed_dependencies,
+                    }
+                },
+            });
+        }
+        diff
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl CacheDiff {
+    /// The invalidated tasks which haven't been invalidated by another task.
+    /// These are usually the reason for everything else being invalidated.
+    pub fn root_causes(&self) -> impl Iterator<Item = &InvalidatedTask> {
+        self.invalidated
+            .iter()
+            .filter(|task| task.reason == InvalidationReason::External)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -5,10 +5,10 @@ use std::{
     borrow::Cow,
     cell::RefCell,
     cmp::{max, Ordering, Reverse},
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fmt::{self, Debug, Display, Formatter, Write},
     future::Future,
-    hash::Hash,
+    hash::{Hash, Hasher},
     mem::{replace, take},
     pin::Pin,
     sync::Arc,
@@ -19,16 +19,21 @@ use anyhow::Result;
 use auto_hash_map::{AutoMap, AutoSet};
 use nohash_hasher::BuildNoHashHasher;
 use parking_lot::{Mutex, RwLock};
-use stats::TaskStats;
+use rustc_hash::FxHasher;
+use stats::{LastAccess, TaskStats};
 use tokio::task_local;
 use turbo_tasks::{
-    backend::{PersistentTaskType, TaskExecutionSpec},
+    backend::{CellContent, PersistentTaskType, TaskExecutionSpec},
     event::{Event, EventListener},
     get_invalidator,
     primitives::{RawVcSet, RawVcSetVc},
-    registry, CellId, Invalidator, RawVc, StatsType, TaskId, TraitTypeId, TryJoinIterExt,
-    TurboTasksBackendApi, ValueTypeId,
+    registry,
+    remote::run_remote_native,
+    CellId, FunctionId, Invalidator, RawVc, SharedReference, StatsType, TaskId, TaskPriority,
+    TraitTypeId, TryJoinIterExt, TurboTasksBackendApi, ValueTypeId,
 };
+#[cfg(feature = "memory_stats")]
+use turbo_tasks::{MemoryStatistics, RetainedMemory};
 
 use crate::{
     cell::Cell,
@@ -37,7 +42,9 @@ use crate::{
     memory_backend::Job,
     output::{Output, OutputContent},
     scope::{ScopeChildChangeEffect, TaskScopeId, TaskScopes},
+    snapshot::{CacheSnapshot, SnapshotDependency, TaskSnapshot},
     stats::{ReferenceType, StatsReferences, StatsTaskType},
+    viz::task_graph::{TaskGraph, TaskGraphCell, TaskGraphEdgeType, TaskGraphNode, TaskGraphState},
     MemoryBackend,
 };
 
@@ -185,6 +192,8 @@ pub struct Task {
     /// The mutable state of the task
     /// Unset state is equal to a Dirty task that has not been executed yet
     state: RwLock<TaskMetaState>,
+    /// When the task has been read last, see [Task::register_access]
+    last_access: LastAccess,
 }
 
 impl Debug for Task {
@@ -491,7 +500,12 @@ enum TaskStateType {
     /// Invalid execution is happening
     ///
     /// on finish this will move to Dirty or Scheduled depending on active flag
-    InProgressDirty { event: Event },
+    InProgressDirty {
+        event: Event,
+        /// The execution has been cancelled, so it will only be scheduled
+        /// again when the task is still active
+        cancelled: bool,
+    },
 }
 
 use TaskStateType::*;
@@ -512,6 +526,14 @@ use self::meta_state::{
 ///
 /// The current implementation uses a heuristic that says that the cost is
 /// linear to the number of added scoped and linear to the number of children.
+/// Hashes values with a hasher that is only stable within a session, e.g.
+/// non-serializable cell values by their identity.
+fn hash_identity(value: &impl Hash) -> u64 {
+    let mut hasher = FxHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn should_optimize_to_root_scoped(optimization_counter: usize, children_count: usize) -> bool {
     const SCOPE_OPTIMIZATION_THRESHOLD: usize = 255;
     optimization_counter * children_count > SCOPE_OPTIMIZATION_THRESHOLD
@@ -532,6 +554,7 @@ impl Task {
                 description,
                 stats_type,
             )))),
+            last_access: LastAccess::default(),
         }
     }
 
@@ -549,6 +572,7 @@ impl Task {
             state: RwLock::new(TaskMetaState::Full(Box::new(
                 TaskState::new_scheduled_in_scope(description, scope, stats_type),
             ))),
+            last_access: LastAccess::default(),
         }
     }
 
@@ -566,6 +590,7 @@ impl Task {
             state: RwLock::new(TaskMetaState::Full(Box::new(
                 TaskState::new_scheduled_in_scope(description, scope, stats_type),
             ))),
+            last_access: LastAccess::default(),
         }
     }
 
@@ -587,6 +612,7 @@ impl Task {
                 description,
                 stats_type,
             )))),
+            last_access: LastAccess::default(),
         }
     }
 
@@ -610,6 +636,7 @@ impl Task {
                 scope,
                 stats_type,
             )))),
+            last_access: LastAccess::default(),
         }
     }
 
@@ -623,6 +650,58 @@ impl Task {
         }
     }
 
+    /// Registers a read of the task output or of one of its cells, so that
+    /// garbage collection prefers tasks which haven't been used for a long
+    /// time.
+    pub(crate) fn register_access(&self, duration_since_start: Duration) {
+        self.last_access.register(duration_since_start);
+    }
+
+    pub(crate) fn get_priority(&self) -> TaskPriority {
+        if let TaskType::Persistent(ty) = &self.ty {
+            if let PersistentTaskType::Native(native_fn, _)
+            | PersistentTaskType::ResolveNative(native_fn, _) = &**ty
+            {
+                return registry::get_function(*native_fn).priority;
+            }
+        }
+        TaskPriority::Normal
+    }
+
+    pub(crate) fn get_slow_threshold(&self) -> Option<Duration> {
+        if let TaskType::Persistent(ty) = &self.ty {
+            if let PersistentTaskType::Native(native_fn, _)
+            | PersistentTaskType::ResolveNative(native_fn, _) = &**ty
+            {
+                return registry::get_function(*native_fn).slow_threshold;
+            }
+        }
+        None
+    }
+
+    pub(crate) fn get_arguments(&self) -> Option<String> {
+        if let TaskType::Persistent(ty) = &self.ty {
+            match &**ty {
+                PersistentTaskType::Native(_, inputs)
+                | PersistentTaskType::ResolveNative(_, inputs)
+                | PersistentTaskType::ResolveTrait(_, _, inputs) => {
+                    return Some(format!("{:?}", inputs));
+                }
+            }
+        }
+        None
+    }
+
+    /// The native function executed by this task, if any.
+    pub(crate) fn get_native_function(&self) -> Option<FunctionId> {
+        if let TaskType::Persistent(ty) = &self.ty {
+            if let PersistentTaskType::Native(native_fn, _) = &**ty {
+                return Some(*native_fn);
+            }
+        }
+        None
+    }
+
     pub(crate) fn get_function_name(&self) -> Option<&'static str> {
         if let TaskType::Persistent(ty) = &self.ty {
             match &**ty {
@@ -873,6 +952,17 @@ impl Task {
                 ))
             }
             TaskType::Persistent(ty) => match &**ty {
+                PersistentTaskType::Native(native_fn, inputs)
+                    if backend.remote_executor.is_some()
+                        && registry::get_function(*native_fn).remote =>
+                {
+                    drop(state);
+                    Box::pin(run_remote_native(
+                        backend.remote_executor.clone().unwrap(),
+                        *native_fn,
+                        inputs.clone(),
+                    ))
+                }
                 PersistentTaskType::Native(native_fn, inputs) => {
                     let future = if let PrepareTaskType::Native(bound_fn) = &state.prepared_type {
                         bound_fn()
@@ -984,6 +1074,91 @@ impl Task {
         };
     }
 
+    /// Discards the result of a cancelled execution. The task stays dirty and
+    /// is only scheduled again when it's still needed.
+    pub(crate) fn execution_cancelled(&self, backend: &MemoryBackend) {
+        if let TaskType::Once(_) = self.ty {
+            // once tasks can't be executed again, they store the cancellation
+            // error as result
+            return;
+        }
+        let mut state = self.full_state_mut();
+        match state.state_type {
+            InProgress {
+                ref mut event,
+                count_as_finished,
+            } => {
+                let event = event.take();
+                if count_as_finished {
+                    for scope in state.scopes.iter() {
+                        backend.with_scope(scope, |scope| {
+                            scope.increment_unfinished_tasks(backend);
+                        })
+                    }
+                }
+                state.state_type = InProgressDirty {
+                    event,
+                    cancelled: true,
+                };
+            }
+            InProgressDirty {
+                ref mut cancelled, ..
+            } => {
+                *cancelled = true;
+            }
+            Dirty { .. } | Scheduled { .. } | Done { .. } => {
+                panic!(
+                    "Task execution cancelled in unexpected state {}",
+                    Task::state_string(&state)
+                )
+            }
+        }
+    }
+
+    /// Cancels the execution of the task and of the executing tasks in its
+    /// subtree which are no longer needed, i. e. which are not in any active
+    /// scope. A once task itself is no longer needed, so it's removed from its
+    /// scope first.
+    pub(crate) fn cancel(
+        &self,
+        backend: &MemoryBackend,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) {
+        if let TaskType::Once(_) = self.ty {
+            self.remove_root_or_initial_scope(backend, turbo_tasks);
+        }
+        turbo_tasks.cancel_task_execution(self.id);
+
+        let mut visited = AutoSet::<TaskId, BuildNoHashHasher<TaskId>>::default();
+        let mut queue = Vec::new();
+        if let TaskMetaStateReadGuard::Full(state) = self.state() {
+            queue.extend(state.children.iter().copied());
+        }
+        while let Some(task_id) = queue.pop() {
+            if !visited.insert(task_id) {
+                continue;
+            }
+            backend.with_task(task_id, |task| {
+                let TaskMetaStateReadGuard::Full(state) = task.state() else {
+                    // Tasks which are not fully loaded are not executing
+                    return;
+                };
+                let active = state
+                    .scopes
+                    .iter()
+                    .any(|scope| backend.with_scope(scope, |scope| scope.state.lock().is_active()));
+                if active {
+                    // The task and its subtree are still needed
+                    return;
+                }
+                if matches!(state.state_type, InProgress { .. } | InProgressDirty { .. }) {
+                    turbo_tasks.cancel_task_execution(task_id);
+                }
+                queue.extend(state.children.iter().copied());
+            });
+        }
+    }
+
     #[must_use]
     pub(crate) fn execution_completed(
         &self,
@@ -1025,10 +1200,25 @@ impl Task {
                     }
                     event.notify(usize::MAX);
                 }
-                InProgressDirty { ref mut event } => {
+                InProgressDirty {
+                    ref mut event,
+                    cancelled,
+                } => {
                     let event = event.take();
-                    state.state_type = Scheduled { event };
-                    schedule_task = true;
+                    if !cancelled || self.scopes_dirty_or_active(false, &state.scopes, backend) {
+                        state.state_type = Scheduled { event };
+                        schedule_task = true;
+                    } else {
+                        // Nobody needs the result anymore. Tasks waiting for it will
+                        // schedule it again when reading it.
+                        let description = self.get_event_description();
+                        state.state_type = Dirty {
+                            event: Event::new(move || {
+                                format!("TaskState({})::event", description())
+                            }),
+                        };
+                        event.notify(usize::MAX);
+                    }
                 }
                 Dirty { .. } | Scheduled { .. } | Done { .. } => {
                     panic!(
@@ -1193,7 +1383,10 @@ impl Task {
                             })
                         }
                     }
-                    state.state_type = InProgressDirty { event };
+                    state.state_type = InProgressDirty {
+                        event,
+                        cancelled: false,
+                    };
                     drop(state);
                 }
             }
@@ -1884,10 +2077,53 @@ impl Task {
         }
     }
 
+    /// Returns true when the task has been invalidated and hasn't been
+    /// recomputed yet.
+    pub fn is_dirty(&self) -> bool {
+        if let TaskMetaStateReadGuard::Full(state) = self.state() {
+            matches!(
+                state.state_type,
+                TaskStateType::Dirty { .. }
+                    | TaskStateType::Scheduled { .. }
+                    | TaskStateType::InProgressDirty { .. }
+            )
+        } else {
+            false
+        }
+    }
+
+    /// The number of unfinished tasks in the root scope of this task. Returns
+    /// `None` when the task is not a root scoped task, i. e. it hasn't been
+    /// read strongly consistent.
+    pub fn get_unfinished_task_count(&self, backend: &MemoryBackend) -> Option<usize> {
+        if let TaskMetaStateReadGuard::Full(state) = self.state() {
+            if let TaskScopes::Root(root) = state.scopes {
+                return Some(backend.with_scope(root, |scope| scope.unfinished_tasks()));
+            }
+        }
+        None
+    }
+
+    /// Returns false when the task or the cell has been unloaded.
+    pub fn is_loaded(&self, cell: Option<CellId>) -> bool {
+        let TaskMetaStateReadGuard::Full(state) = self.state() else {
+            return false;
+        };
+        match cell {
+            Some(CellId { type_id, index }) => state
+                .cells
+                .get(&type_id)
+                .and_then(|cells| cells.get(index as usize))
+                .map_or(false, |cell| cell.has_value()),
+            None => true,
+        }
+    }
+
     pub fn reset_stats(&self) {
         if let TaskMetaStateWriteGuard::Full(mut state) = self.state_mut() {
             state.stats.reset();
         }
+        self.last_access.reset();
     }
 
     pub fn get_stats_info(&self, backend: &MemoryBackend) -> TaskStatsInfo {
@@ -2003,6 +2239,169 @@ impl Task {
         }
     }
 
+    pub fn add_to_graph(&self, graph: &mut TaskGraph) {
+        let description = self.get_description();
+        let TaskMetaStateReadGuard::Full(state) = self.state() else {
+            graph.add_task(
+                self.id,
+                TaskGraphNode {
+                    id: *self.id,
+                    description,
+                    state: TaskGraphState::Unloaded,
+                    cells: Vec::new(),
+                },
+            );
+            return;
+        };
+        let mut cells = Vec::new();
+        for (&type_id, list) in state.cells.iter() {
+            for (index, cell) in list.iter().enumerate() {
+                let name = CellId {
+                    type_id,
+                    index: index as u32,
+                }
+                .to_string();
+                for &dependent in cell.dependent_tasks().iter() {
+                    graph.add_edge(
+                        dependent,
+                        self.id,
+                        TaskGraphEdgeType::Cell { cell: name.clone() },
+                    );
+                }
+                cells.push(TaskGraphCell {
+                    name,
+                    has_value: cell.has_value(),
+                });
+            }
+        }
+        for &dependent in state.output.dependent_tasks.iter() {
+            graph.add_edge(dependent, self.id, TaskGraphEdgeType::Output);
+        }
+        for &child in state.children.iter() {
+            graph.add_edge(self.id, child, TaskGraphEdgeType::Child);
+        }
+        graph.add_task(
+            self.id,
+            TaskGraphNode {
+                id: *self.id,
+                description,
+                state: match state.state_type {
+                    Done { .. } => TaskGraphState::Done,
+                    Dirty { .. } => TaskGraphState::Dirty,
+                    Scheduled { .. } => TaskGraphState::Scheduled,
+                    InProgress { .. } => TaskGraphState::InProgress,
+                    InProgressDirty { .. } => TaskGraphState::InProgressDirty,
+                },
+                cells,
+            },
+        );
+    }
+
+    pub fn add_to_snapshot(&self, snapshot: &mut CacheSnapshot) {
+        let TaskMetaStateReadGuard::Full(state) = self.state() else {
+            return;
+        };
+        let mut cells = BTreeMap::new();
+        for (&type_id, list) in state.cells.iter() {
+            let value_type = registry::get_value_type(type_id);
+            for (index, cell) in list.iter().enumerate() {
+                let Some(CellContent(content)) = cell.content() else {
+                    continue;
+                };
+                let hash = content.as_ref().map_or(0, |reference| {
+                    let SharedReference(_, value) = reference;
+                    value_type
+                        .hash_content(&**value)
+                        .unwrap_or_else(|| hash_identity(reference))
+                });
+                let name = CellId {
+                    type_id,
+                    index: index as u32,
+                }
+                .to_string();
+                cells.insert(name, hash);
+            }
+        }
+        let output = match &state.output.content {
+            OutputContent::Empty => None,
+            OutputContent::Link(raw_vc) => Some(hash_identity(raw_vc)),
+            OutputContent::Error(error) => Some(hash_identity(&error.to_string())),
+            OutputContent::Panic(message) => Some(hash_identity(message)),
+        };
+        let mut dependencies = BTreeSet::new();
+        if let Done {
+            dependencies: ref deps,
+        } = state.state_type
+        {
+            for dep in deps.iter() {
+                match *dep {
+                    TaskDependency::TaskOutput(task) => {
+                        dependencies.insert(SnapshotDependency::Output { task: *task });
+                    }
+                    TaskDependency::TaskCell(task, cell) => {
+                        dependencies.insert(SnapshotDependency::Cell {
+                            task: *task,
+                            cell: cell.to_string(),
+                        });
+                    }
+                    TaskDependency::ScopeChildren(_) | TaskDependency::ScopeCollectibles(..) => {}
+                }
+            }
+        }
+        snapshot.add_task(
+            self.id,
+            TaskSnapshot {
+                description: self.get_description(),
+                output,
+                cells,
+                executions: match &state.stats {
+                    TaskStats::Essential(_) => None,
+                    TaskStats::Full(stats) => Some(stats.executions()),
+                },
+                dependencies,
+            },
+        );
+    }
+
+    /// Adds the estimated memory retained by the task and its cells. Resolve
+    /// tasks are attributed to the function they resolve the arguments for.
+    #[cfg(feature = "memory_stats")]
+    pub fn add_memory_statistics(&self, stats: &mut MemoryStatistics) {
+        let mut retained = RetainedMemory {
+            count: 1,
+            bytes: std::mem::size_of::<Task>(),
+        };
+        if let TaskMetaStateReadGuard::Full(state) = self.state() {
+            retained.bytes += std::mem::size_of::<TaskState>();
+            for (&type_id, list) in state.cells.iter() {
+                let value_type = registry::get_value_type(type_id);
+                for cell in list.iter() {
+                    let Some(CellContent(Some(SharedReference(_, value)))) = cell.content() else {
+                        continue;
+                    };
+                    let cell = RetainedMemory {
+                        count: 1,
+                        bytes: value_type.estimate_size(&**value),
+                    };
+                    retained.bytes += cell.bytes;
+                    let value_type_total = stats.value_types.entry(&value_type.name).or_default();
+                    *value_type_total = *value_type_total + cell;
+                }
+            }
+        }
+        if let TaskType::Persistent(ty) = &self.ty {
+            if let PersistentTaskType::Native(f, _) | PersistentTaskType::ResolveNative(f, _) =
+                &**ty
+            {
+                let function_total = stats
+                    .functions
+                    .entry(&registry::get_function(*f).name)
+                    .or_default();
+                *function_total = *function_total + retained;
+            }
+        }
+    }
+
     fn state_string(state: &TaskState) -> String {
         let mut state_str = match state.state_type {
             Scheduled { .. } => "scheduled".to_string(),
@@ -2178,7 +2577,7 @@ impl Task {
             }
             Scheduled { ref event }
             | InProgress { ref event, .. }
-            | InProgressDirty { ref event } => {
+            | InProgressDirty { ref event, .. } => {
                 let listener = event.listen_with_note(note);
                 drop(state);
                 Ok(Err(listener))
@@ -2447,9 +2846,12 @@ impl Task {
                 let last_duration = state.stats.last_duration();
                 let compute_duration = last_duration.into();
 
+                // Tasks which are still read are used, even when they haven't been
+                // executed for a long time.
                 let age = to_exp_u8(
-                    (now_relative_to_start
-                        .saturating_sub(state.stats.last_execution_relative_to_start()))
+                    (now_relative_to_start.saturating_sub(
+                        state.stats.last_used_relative_to_start(&self.last_access),
+                    ))
                     .as_secs(),
                 );
 
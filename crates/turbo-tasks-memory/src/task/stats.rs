@@ -1,4 +1,7 @@
-use std::time::Duration;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
 
 use turbo_tasks::{small_duration::SmallDuration, StatsType};
 
@@ -11,8 +14,12 @@ pub enum TaskStats {
 }
 
 impl TaskStats {
-    /// Creates a new [`TaskStats`].
+    /// Creates a new [`TaskStats`]. Full stats are not allocated with the
+    /// `lightweight_stats` feature.
     pub fn new(stats_type: StatsType) -> Self {
+        if cfg!(feature = "lightweight_stats") {
+            return Self::Essential(TaskStatsEssential::default());
+        }
         match stats_type {
             turbo_tasks::StatsType::Essential => Self::Essential(TaskStatsEssential::default()),
             turbo_tasks::StatsType::Full => Self::Full(Box::default()),
@@ -41,6 +48,7 @@ impl TaskStats {
             Self::Full(stats) => {
                 stats.total_duration += duration;
                 stats.last_duration = duration;
+                stats.last_execution_relative_to_start = duration_since_start.into();
             }
             Self::Essential(stats) => {
                 stats.last_duration = duration.into();
@@ -56,6 +64,7 @@ impl TaskStats {
                 stats.executions = 0;
                 stats.total_duration = Duration::ZERO;
                 stats.last_duration = Duration::ZERO;
+                stats.last_execution_relative_to_start = SmallDuration::MIN;
             }
             Self::Essential(stats) => {
                 stats.last_duration = SmallDuration::MIN;
@@ -80,6 +89,35 @@ impl TaskStats {
             Self::Essential(stats) => stats.last_execution_relative_to_start(),
         }
     }
+
+    /// Returns the last time the task was executed or read relative to the
+    /// start of the program.
+    pub fn last_used_relative_to_start(&self, last_access: &LastAccess) -> Duration {
+        self.last_execution_relative_to_start()
+            .max(last_access.relative_to_start())
+    }
+}
+
+/// The last read of the output or of one of the cells of a task relative to
+/// the start of the program, with a precision of 1 millisecond. Reads are
+/// frequent, so it's updated without the lock of the task state.
+#[derive(Debug, Default)]
+pub struct LastAccess(AtomicU64);
+
+impl LastAccess {
+    pub fn register(&self, duration_since_start: Duration) {
+        // Concurrent reads might register their accesses out of order.
+        self.0
+            .fetch_max(duration_since_start.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+
+    pub fn relative_to_start(&self) -> Duration {
+        Duration::from_millis(self.0.load(Ordering::Relaxed))
+    }
 }
 
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
@@ -99,7 +137,6 @@ impl TaskStatsEssential {
 
     /// Returns the last execution of the task relative to the start of the
     /// program.
-    #[allow(dead_code)] // NOTE(alexkirsz) This will be useful for GC.
     pub fn last_execution_relative_to_start(&self) -> Duration {
         self.last_execution_relative_to_start.into()
     }
@@ -136,8 +173,53 @@ impl TaskStatsFull {
 
     /// Returns the last execution of the task relative to the start of the
     /// program.
-    #[allow(dead_code)] // NOTE(alexkirsz) This will be useful for GC.
     pub fn last_execution_relative_to_start(&self) -> Duration {
         self.last_execution_relative_to_start.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use turbo_tasks::StatsType;
+
+    use super::{LastAccess, TaskStats};
+
+    #[test]
+    fn last_used_includes_accesses() {
+        for stats_type in [StatsType::Essential, StatsType::Full] {
+            let mut stats = TaskStats::new(stats_type);
+            let last_access = LastAccess::default();
+            stats.register_execution(Duration::from_millis(5), Duration::from_secs(1));
+            assert_eq!(
+                stats.last_used_relative_to_start(&last_access),
+                Duration::from_secs(1)
+            );
+
+            // Reading a task which was executed long ago keeps it in use.
+            last_access.register(Duration::from_secs(60));
+            // A read which registers its access late doesn't go back in time.
+            last_access.register(Duration::from_secs(59));
+            assert_eq!(
+                stats.last_execution_relative_to_start(),
+                Duration::from_secs(1)
+            );
+            assert_eq!(
+                stats.last_used_relative_to_start(&last_access),
+                Duration::from_secs(60)
+            );
+
+            // An execution after the last read counts as a use too.
+            stats.register_execution(Duration::from_millis(5), Duration::from_secs(120));
+            assert_eq!(
+                stats.last_used_relative_to_start(&last_access),
+                Duration::from_secs(120)
+            );
+
+            stats.reset();
+            last_access.reset();
+            assert!(stats.last_used_relative_to_start(&last_access) < Duration::from_secs(1));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod graph;
 pub mod table;
+pub mod task_graph;
 
 use std::{
     cmp::max,
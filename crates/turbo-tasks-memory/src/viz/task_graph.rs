@@ -0,0 +1,168 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use serde::Serialize;
+use turbo_tasks::TaskId;
+
+use super::escape_in_template_str;
+use crate::MemoryBackend;
+
+/// A snapshot of all tasks of a [MemoryBackend] with their cells and the
+/// edges between them, to inspect cache invalidation visually. Edges can
+/// reference root tasks, which are not cached and therefore not part of
+/// `tasks`.
+#[derive(Serialize, Debug, Default)]
+pub struct TaskGraph {
+    pub tasks: Vec<TaskGraphNode>,
+    pub edges: Vec<TaskGraphEdge>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TaskGraphNode {
+    pub id: usize,
+    pub description: String,
+    pub state: TaskGraphState,
+    pub cells: Vec<TaskGraphCell>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskGraphState {
+    Done,
+    Dirty,
+    Scheduled,
+    InProgress,
+    InProgressDirty,
+    /// The task state has been unloaded from memory.
+    Unloaded,
+}
+
+impl TaskGraphState {
+    /// Returns true when the task needs to be executed (again).
+    pub fn is_dirty(&self) -> bool {
+        matches!(
+            self,
+            TaskGraphState::Dirty | TaskGraphState::Scheduled | TaskGraphState::InProgressDirty
+        )
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            TaskGraphState::Done => "#ffffff",
+            TaskGraphState::Dirty => "#ff8080",
+            TaskGraphState::Scheduled => "#ffc080",
+            TaskGraphState::InProgress => "#80c0ff",
+            TaskGraphState::InProgressDirty => "#c080ff",
+            TaskGraphState::Unloaded => "#e0e0e0",
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct TaskGraphCell {
+    /// The value type and index of the cell, e.g. `FileContent#0`.
+    pub name: String,
+    pub has_value: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct TaskGraphEdge {
+    pub from: usize,
+    pub to: usize,
+    #[serde(flatten)]
+    pub ty: TaskGraphEdgeType,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskGraphEdgeType {
+    /// `to` has been called by `from`.
+    Child,
+    /// `from` has read the output of `to`.
+    Output,
+    /// `from` has read a cell of `to`.
+    Cell { cell: String },
+}
+
+impl TaskGraph {
+    /// Collects all cached tasks of the `backend`.
+    pub fn new(backend: &MemoryBackend) -> Self {
+        let mut graph = TaskGraph::default();
+        backend.with_all_cached_tasks(|task| {
+            backend.with_task(task, |task| task.add_to_graph(&mut graph));
+        });
+        graph.tasks.sort_by_key(|node| node.id);
+        graph
+    }
+
+    pub(crate) fn add_task(&mut self, id: TaskId, node: TaskGraphNode) {
+        debug_assert_eq!(*id, node.id);
+        self.tasks.push(node);
+    }
+
+    pub(crate) fn add_edge(&mut self, from: TaskId, to: TaskId, ty: TaskGraphEdgeType) {
+        self.edges.push(TaskGraphEdge {
+            from: *from,
+            to: *to,
+            ty,
+        });
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Formats the graph in the GraphViz DOT language. Dirty tasks are
+    /// highlighted, dependency edges are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut output = "digraph {\nrankdir=LR\nnode [shape=box, style=filled]\n\n".to_string();
+        for task in self.tasks.iter() {
+            let mut label = format!(
+                "{}\\n{:?}",
+                escape_in_template_str(&task.description),
+                task.state
+            );
+            for cell in task.cells.iter() {
+                write!(
+                    label,
+                    "\\n{}{}",
+                    escape_in_template_str(&cell.name),
+                    if cell.has_value { "" } else { " (empty)" }
+                )
+                .unwrap();
+            }
+            writeln!(
+                output,
+                "task_{} [label=\"{}\", fillcolor=\"{}\"]",
+                task.id,
+                label,
+                task.state.color()
+            )
+            .unwrap();
+        }
+        output += "\n";
+        for edge in self.edges.iter() {
+            match &edge.ty {
+                TaskGraphEdgeType::Child => {
+                    writeln!(output, "task_{} -> task_{}", edge.from, edge.to).unwrap()
+                }
+                TaskGraphEdgeType::Output => writeln!(
+                    output,
+                    "task_{} -> task_{} [style=dashed]",
+                    edge.from, edge.to
+                )
+                .unwrap(),
+                TaskGraphEdgeType::Cell { cell } => writeln!(
+                    output,
+                    "task_{} -> task_{} [style=dashed, label=\"{}\"]",
+                    edge.from,
+                    edge.to,
+                    escape_in_template_str(cell)
+                )
+                .unwrap(),
+            }
+        }
+        output += "}\n";
+        output
+    }
+}
@@ -0,0 +1,114 @@
+#![feature(min_specialization)]
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use turbo_tasks::{get_invalidator, Invalidator, TurboTasks};
+use turbo_tasks_memory::{
+    snapshot::{CacheSnapshot, InvalidationReason, SnapshotDependency},
+    MemoryBackend,
+};
+use turbo_tasks_testing::register;
+
+register!();
+
+static COUNTER: Mutex<(u32, Option<Invalidator>)> = Mutex::new((1, None));
+
+#[tokio::test]
+async fn cache_snapshot_diff() {
+    lazy_static::initialize(&REGISTER);
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let build = || async {
+        assert!(*is_small(counter()).strongly_consistent().await?);
+        double(counter()).strongly_consistent().await?;
+        Ok(())
+    };
+    tt.run_once(build()).await.unwrap();
+    let before = CacheSnapshot::new(tt.backend());
+
+    let unchanged = before.diff(&CacheSnapshot::new(tt.backend()));
+    assert!(unchanged.invalidated.is_empty());
+    assert!(unchanged.added.is_empty() && unchanged.removed.is_empty());
+
+    {
+        let mut counter = COUNTER.lock().unwrap();
+        counter.0 += 1;
+        counter.1.take().unwrap().invalidate();
+    }
+    tt.run_once(build()).await.unwrap();
+    let after = CacheSnapshot::new(tt.backend());
+
+    let find = |name: &str| {
+        *after
+            .tasks
+            .iter()
+            .find(|(_, task)| task.description.split_once("] ").map(|(_, n)| n) == Some(name))
+            .unwrap_or_else(|| panic!("{name} is missing in {after:#?}"))
+            .0
+    };
+    let counter_id = find("counter");
+    let double_id = find("double");
+    let is_small_id = find("is_small");
+
+    let diff = before.diff(&after);
+    let invalidated = |id: usize| {
+        diff.invalidated
+            .iter()
+            .find(|task| task.id == id)
+            .unwrap_or_else(|| panic!("{id} is not invalidated in {diff:#?}"))
+    };
+    let counter = invalidated(counter_id);
+    assert_eq!(counter.reason, InvalidationReason::External);
+    assert_eq!(counter.changed_cells.len(), 1);
+
+    let double = invalidated(double_id);
+    assert_eq!(double.changed_cells.len(), 1);
+    let InvalidationReason::Dependencies { changed } = &double.reason else {
+        panic!("double should be invalidated by counter: {diff:#?}");
+    };
+    assert!(changed
+        .iter()
+        .all(|dependency| dependency.task() == counter_id));
+    assert!(changed
+        .iter()
+        .any(|dependency| matches!(dependency, SnapshotDependency::Cell { .. })));
+
+    // is_small has been executed again, but its result didn't change
+    let is_small = invalidated(is_small_id);
+    assert!(is_small.changed_cells.is_empty());
+    assert!(!is_small.output_changed);
+
+    assert_eq!(
+        diff.root_causes().map(|task| task.id).collect::<Vec<_>>(),
+        vec![counter_id]
+    );
+    let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+    assert!(json["invalidated"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|task| task["reason"]["type"] == "external"));
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::value(transparent)]
+struct Bool(bool);
+
+#[turbo_tasks::function]
+fn counter() -> NumberVc {
+    let mut counter = COUNTER.lock().unwrap();
+    counter.1 = Some(get_invalidator());
+    NumberVc::cell(counter.0)
+}
+
+#[turbo_tasks::function]
+async fn double(number: NumberVc) -> Result<NumberVc> {
+    Ok(NumberVc::cell(*number.await? * 2))
+}
+
+#[turbo_tasks::function]
+async fn is_small(number: NumberVc) -> Result<BoolVc> {
+    Ok(BoolVc::cell(*number.await? < 10))
+}
@@ -0,0 +1,42 @@
+#![feature(min_specialization)]
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::Result;
+use turbo_tasks::{cancellation_token, Cancelled, CompletionVc, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+#[tokio::test]
+async fn dropping_run_once_cancels_subtree() {
+    lazy_static::initialize(&REGISTER);
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let run = tt.run_once(async {
+        wait_for_cancellation().await?;
+        Ok(())
+    });
+    assert!(tokio::time::timeout(Duration::from_millis(100), run)
+        .await
+        .is_err());
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !CANCELLED.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the task should have been cancelled");
+}
+
+#[turbo_tasks::function]
+async fn wait_for_cancellation() -> Result<CompletionVc> {
+    cancellation_token().cancelled().await;
+    CANCELLED.store(true, Ordering::SeqCst);
+    Err(Cancelled.into())
+}
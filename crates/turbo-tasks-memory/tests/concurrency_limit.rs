@@ -0,0 +1,36 @@
+#![feature(min_specialization)]
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use anyhow::Result;
+use turbo_tasks::TryJoinIterExt;
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+static MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn concurrency_limit() {
+    run! {
+        let results = (0..8).map(limited).try_join().await?;
+        assert_eq!(results.iter().map(|r| **r).sum::<u32>(), 28);
+        assert_eq!(MAX_RUNNING.load(Ordering::SeqCst), 2);
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function(concurrency_limit = 2)]
+async fn limited(value: u32) -> Result<NumberVc> {
+    let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+    MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    RUNNING.fetch_sub(1, Ordering::SeqCst);
+    Ok(NumberVc::cell(value))
+}
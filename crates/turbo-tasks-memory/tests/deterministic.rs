@@ -0,0 +1,44 @@
+#![feature(min_specialization)]
+
+use std::sync::Mutex;
+
+use turbo_tasks::{CompletionVc, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+static EXECUTIONS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+async fn execution_order(seed: Option<u64>) -> Vec<u32> {
+    lazy_static::initialize(&REGISTER);
+    EXECUTIONS.lock().unwrap().clear();
+    let tt = TurboTasks::new_deterministic(MemoryBackend::default(), seed);
+    tt.run_once(async {
+        // All tasks are scheduled before any of them is awaited.
+        let completions = (0..20).map(record).collect::<Vec<_>>();
+        for completion in completions {
+            completion.await?;
+        }
+        Ok(())
+    })
+    .await
+    .unwrap();
+    EXECUTIONS.lock().unwrap().clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deterministic_execution_order() {
+    assert_eq!(execution_order(None).await, (0..20).collect::<Vec<_>>());
+
+    let order = execution_order(Some(42)).await;
+    assert_ne!(order, (0..20).collect::<Vec<_>>());
+    assert_eq!(execution_order(Some(42)).await, order);
+    assert_ne!(execution_order(Some(7)).await, order);
+}
+
+#[turbo_tasks::function]
+fn record(value: u32) -> CompletionVc {
+    EXECUTIONS.lock().unwrap().push(value);
+    CompletionVc::new()
+}
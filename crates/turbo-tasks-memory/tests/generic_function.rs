@@ -0,0 +1,38 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::{primitives::StringVc, ValueVc};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn generic_function() {
+    run! {
+        let a = AVc::cell(1);
+        let b = BVc::cell(2);
+        assert!(type_name_of(a).await?.ends_with("AVc"));
+        assert!(type_name_of(b).await?.ends_with("BVc"));
+        assert_eq!(*wrap(a).await?, 1);
+        assert_eq!(wrap(a).resolve().await?, wrap(a).resolve().await?);
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct A(u32);
+
+#[turbo_tasks::value(transparent)]
+struct B(u32);
+
+#[turbo_tasks::function]
+fn type_name_of<T: ValueVc>(_value: T) -> StringVc {
+    StringVc::cell(std::any::type_name::<T>().to_string())
+}
+
+#[turbo_tasks::function]
+async fn wrap<T>(value: T) -> Result<T>
+where
+    T: ValueVc,
+{
+    Ok(value)
+}
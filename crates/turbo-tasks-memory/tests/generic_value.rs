@@ -0,0 +1,50 @@
+#![feature(min_specialization)]
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use turbo_tasks::{ForeignVc, SharedReference, Typed};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn generic_value() {
+    run! {
+        let numbers = PairVc::cell(Pair { first: 1u32, second: 2 });
+        assert_eq!(*sum(numbers).await?, 3);
+
+        let strings = PairVc::cell(Pair { first: "a".to_string(), second: "b".to_string() });
+        assert_eq!(strings.await?.first, "a");
+        assert_ne!(Pair::<u32>::get_value_type_id(), Pair::<String>::get_value_type_id());
+
+        let duration = ForeignVc::cell(Duration::from_secs(1));
+        assert_eq!(*duration.await?, Duration::from_secs(1));
+    }
+}
+
+#[tokio::test]
+async fn generic_values_are_not_serializable() {
+    run! {
+        // The instantiation isn't registered in a fresh process before it's
+        // used, so the value couldn't be deserialized.
+        let pair = Pair { first: 1u32, second: 2 };
+        let value = SharedReference(Some(Pair::<u32>::get_value_type_id()), Arc::new(pair));
+        assert!(!value.is_serializable());
+    }
+}
+
+#[turbo_tasks::value]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+#[turbo_tasks::value(transparent)]
+struct Wrapped<T>(T);
+
+#[turbo_tasks::function]
+async fn sum(pair: PairVc<u32>) -> Result<WrappedVc<u32>> {
+    let pair = pair.await?;
+    Ok(WrappedVc::cell(pair.first + pair.second))
+}
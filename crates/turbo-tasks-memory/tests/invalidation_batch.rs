@@ -0,0 +1,64 @@
+#![feature(min_specialization)]
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use turbo_tasks::{get_invalidator, InvalidationBatch, Invalidator};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn invalidation_batch() {
+    run! {
+        let a = CounterVc::cell(Counter { value: Mutex::new((0, None))});
+        let b = CounterVc::cell(Counter { value: Mutex::new((0, None))});
+
+        assert_eq!(*sum(a, b).strongly_consistent().await?, 0);
+
+        let mut batch = InvalidationBatch::new();
+        a.await?.incr(&mut batch);
+        b.await?.incr(&mut batch);
+        assert_eq!(batch.len(), 2);
+        batch.apply();
+
+        assert_eq!(*sum(a, b).strongly_consistent().await?, 2);
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct CounterValue(usize);
+
+#[turbo_tasks::value(serialization = "none", cell = "new", eq = "manual")]
+struct Counter {
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    value: Mutex<(usize, Option<Invalidator>)>,
+}
+
+impl Counter {
+    fn incr(&self, batch: &mut InvalidationBatch) {
+        let mut lock = self.value.lock().unwrap();
+        lock.0 += 1;
+        if let Some(i) = lock.1.take() {
+            batch.add(i);
+        }
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl CounterVc {
+    #[turbo_tasks::function]
+    async fn get_value(self) -> Result<CounterValueVc> {
+        let this = self.await?;
+        let mut lock = this.value.lock().unwrap();
+        lock.1 = Some(get_invalidator());
+        Ok(CounterValueVc::cell(lock.0))
+    }
+}
+
+#[turbo_tasks::function]
+async fn sum(a: CounterVc, b: CounterVc) -> Result<CounterValueVc> {
+    Ok(CounterValueVc::cell(
+        *a.get_value().await? + *b.get_value().await?,
+    ))
+}
@@ -0,0 +1,49 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::{with_priority, TaskPriority};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn declared_priority() {
+    assert_eq!(BACKGROUND_VALUE_FUNCTION.priority, TaskPriority::Background);
+    assert_eq!(NORMAL_VALUE_FUNCTION.priority, TaskPriority::Normal);
+    run! {
+        assert_eq!(*background_value().await?, 1);
+        assert_eq!(*ValueVc::cell(2).doubled().await?, 4);
+    }
+}
+
+#[tokio::test]
+async fn interactive_work() {
+    with_priority(TaskPriority::Interactive, async {
+        run! {
+            assert_eq!(*background_value().await?, 1);
+            assert_eq!(*normal_value().await?, 2);
+        }
+    })
+    .await
+}
+
+#[turbo_tasks::value(transparent)]
+struct Value(u32);
+
+#[turbo_tasks::function(priority = "background")]
+fn background_value() -> ValueVc {
+    ValueVc::cell(1)
+}
+
+#[turbo_tasks::function]
+fn normal_value() -> ValueVc {
+    ValueVc::cell(2)
+}
+
+#[turbo_tasks::value_impl]
+impl ValueVc {
+    #[turbo_tasks::function(priority = "interactive")]
+    async fn doubled(self) -> Result<ValueVc> {
+        Ok(ValueVc::cell(*self.await? * 2))
+    }
+}
@@ -0,0 +1,45 @@
+#![feature(min_specialization)]
+
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::Notify;
+use turbo_tasks::ReadTimeoutError;
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+static RELEASE: Notify = Notify::const_new();
+
+#[tokio::test]
+async fn read_with_timeout_and_progress() {
+    run! {
+        let error = slow()
+            .strongly_consistent()
+            .with_timeout(Duration::from_millis(50))
+            .await
+            .unwrap_err()
+            .downcast::<ReadTimeoutError>()?;
+        assert!(error.progress.unfinished_tasks.unwrap() > 0);
+
+        let mut reports = Vec::new();
+        let value = slow()
+            .strongly_consistent()
+            .with_progress(None, Duration::from_millis(10), |progress| {
+                reports.push(progress);
+                RELEASE.notify_one();
+            })
+            .await?;
+        assert_eq!(*value, 42);
+        assert!(!reports.is_empty());
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function]
+async fn slow() -> Result<NumberVc> {
+    RELEASE.notified().await;
+    Ok(NumberVc::cell(42))
+}
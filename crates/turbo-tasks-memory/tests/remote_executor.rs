@@ -0,0 +1,81 @@
+#![feature(min_specialization)]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use anyhow::{Context, Result};
+use turbo_tasks::{
+    remote::{
+        CachedRemoteExecutor, RemoteExecutionFuture, RemoteExecutor, RemoteTask, RemoteTaskInput,
+    },
+    SharedReference, TurboTasks,
+};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+static LOCAL_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+static REMOTE_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn remote_execution() {
+    lazy_static::initialize(&REGISTER);
+    let executor = Arc::new(CachedRemoteExecutor::new(TestExecutor));
+    let tt = TurboTasks::new(MemoryBackend::default().with_remote_executor(executor.clone()));
+    tt.run_once(async {
+        assert_eq!(*double(NumberVc::cell(2)).await?, 4);
+        // A different cell with an equal value is served from the cache.
+        assert_eq!(*double(NumberVc::cell(2)).await?, 4);
+        assert_eq!(*double(NumberVc::cell(3)).await?, 6);
+        // The executor declines this function, so it's executed locally.
+        assert_eq!(*triple(NumberVc::cell(2)).await?, 6);
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(REMOTE_EXECUTIONS.load(Ordering::SeqCst), 2);
+    assert_eq!(LOCAL_EXECUTIONS.load(Ordering::SeqCst), 1);
+    assert_eq!(executor.len(), 2);
+}
+
+/// Executes `double` after sending the task through a serialization round
+/// trip, like a remote worker would receive it.
+struct TestExecutor;
+
+impl RemoteExecutor for TestExecutor {
+    fn execute<'a>(&'a self, task: &'a RemoteTask) -> RemoteExecutionFuture<'a> {
+        Box::pin(async move {
+            if !task.function.ends_with("double") {
+                return Ok(None);
+            }
+            REMOTE_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+            let task: RemoteTask = serde_json::from_str(&serde_json::to_string(task)?)?;
+            let [RemoteTaskInput::Cell(input)] = &task.inputs[..] else {
+                panic!("unexpected inputs {:?}", task.inputs);
+            };
+            let input = input.clone().downcast::<Number>().context("not a number")?;
+            Ok(Some(SharedReference(
+                Some(*NUMBER_VALUE_TYPE_ID),
+                Arc::new(Number(input.0 * 2)),
+            )))
+        })
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function(remote)]
+async fn double(number: NumberVc) -> Result<NumberVc> {
+    LOCAL_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+    Ok(NumberVc::cell(*number.await? * 2))
+}
+
+#[turbo_tasks::function(remote)]
+async fn triple(number: NumberVc) -> Result<NumberVc> {
+    LOCAL_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+    Ok(NumberVc::cell(*number.await? * 3))
+}
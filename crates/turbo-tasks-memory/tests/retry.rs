@@ -0,0 +1,60 @@
+#![feature(min_specialization)]
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Result};
+use turbo_tasks::retry::transient_error;
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+static FLAKY_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+static BROKEN_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn transient_errors_are_retried() {
+    run! {
+        assert!(flaky().await.is_err());
+        let value = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Ok(value) = flaky().strongly_consistent().await {
+                    return *value;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await?;
+        assert_eq!(value, 42);
+        assert_eq!(FLAKY_EXECUTIONS.load(Ordering::SeqCst), 3);
+    }
+}
+
+#[tokio::test]
+async fn other_errors_are_not_retried() {
+    run! {
+        assert!(broken().await.is_err());
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(broken().await.is_err());
+        assert_eq!(BROKEN_EXECUTIONS.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function(retry = 3)]
+async fn flaky() -> Result<NumberVc> {
+    if FLAKY_EXECUTIONS.fetch_add(1, Ordering::SeqCst) < 2 {
+        return Err(transient_error(anyhow!("resource busy")));
+    }
+    Ok(NumberVc::cell(42))
+}
+
+#[turbo_tasks::function(retry = 3)]
+async fn broken() -> Result<NumberVc> {
+    BROKEN_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+    bail!("not found")
+}
@@ -0,0 +1,72 @@
+#![feature(min_specialization)]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use turbo_tasks::{primitives::StringVc, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn slow_tasks_are_reported() {
+    lazy_static::initialize(&REGISTER);
+    let log = Log::default();
+    let writer = log.clone();
+    let _guard = tracing::subscriber::set_default(
+        tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish(),
+    );
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async {
+        assert_eq!(&*slow(StringVc::cell("input".to_string())).await?, "input");
+        assert_eq!(&*fast(StringVc::cell("input".to_string())).await?, "input");
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let log = log.contents();
+    assert!(log.contains("slow is still executing after"), "{log}");
+    assert!(log.contains("with arguments"), "{log}");
+    assert!(!log.contains("fast is still executing"), "{log}");
+}
+
+#[turbo_tasks::function(slow_threshold_ms = 10)]
+async fn slow(input: StringVc) -> Result<StringVc> {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    Ok(input)
+}
+
+#[turbo_tasks::function(slow_threshold_ms = 10000)]
+async fn fast(input: StringVc) -> Result<StringVc> {
+    Ok(input)
+}
+
+#[derive(Clone, Default)]
+struct Log(Arc<Mutex<Vec<u8>>>);
+
+impl Log {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for Log {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
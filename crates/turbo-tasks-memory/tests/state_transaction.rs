@@ -0,0 +1,66 @@
+#![feature(min_specialization)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+use turbo_tasks::{State, StateTransaction};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+static EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn state_transaction() {
+    run! {
+        let range = RangeVc::new(1, 3);
+        assert_eq!(*range.width().strongly_consistent().await?, 2);
+        assert_eq!(EXECUTIONS.load(Ordering::SeqCst), 1);
+
+        let this = range.await?;
+        let mut transaction = StateTransaction::new();
+        transaction.set(&this.start, 10);
+        transaction.set(&this.end, 15);
+        // Unchanged states don't invalidate anything.
+        transaction.set(&this.unused, 0);
+        assert_eq!(transaction.len(), 3);
+        transaction.commit();
+
+        assert_eq!(*range.width().strongly_consistent().await?, 5);
+        // Both changes are applied by a single re-execution.
+        assert_eq!(EXECUTIONS.load(Ordering::SeqCst), 2);
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Width(u32);
+
+#[turbo_tasks::value(serialization = "none", cell = "new", eq = "manual")]
+struct Range {
+    start: State<u32>,
+    end: State<u32>,
+    unused: State<u32>,
+}
+
+#[turbo_tasks::value_impl]
+impl RangeVc {
+    #[turbo_tasks::function]
+    fn new(start: u32, end: u32) -> Self {
+        Range {
+            start: State::new(start),
+            end: State::new(end),
+            unused: State::new(0),
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn width(self) -> Result<WidthVc> {
+        EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+        let this = self.await?;
+        let start = *this.start.get();
+        let end = *this.end.get();
+        this.unused.get();
+        Ok(WidthVc::cell(end - start))
+    }
+}
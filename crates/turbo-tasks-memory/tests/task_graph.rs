@@ -0,0 +1,67 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::TurboTasks;
+use turbo_tasks_memory::{
+    viz::task_graph::{TaskGraph, TaskGraphEdgeType, TaskGraphState},
+    MemoryBackend,
+};
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn task_graph() {
+    lazy_static::initialize(&REGISTER);
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async {
+        assert_eq!(*double(number()).await?, 84);
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let graph = TaskGraph::new(tt.backend());
+    let find = |name: &str| {
+        graph
+            .tasks
+            .iter()
+            .find(|task| task.description.split_once("] ").map(|(_, n)| n) == Some(name))
+            .unwrap_or_else(|| panic!("{name} is missing in {graph:#?}"))
+    };
+    let number = find("number");
+    let double = find("double");
+    assert_eq!(number.state, TaskGraphState::Done);
+    assert!(!double.state.is_dirty());
+    assert_eq!(number.cells.len(), 1);
+    assert!(number.cells[0].name.ends_with("Number#0"));
+    assert!(number.cells[0].has_value);
+    assert!(graph.edges.iter().any(|edge| edge.from == double.id
+        && edge.to == number.id
+        && matches!(edge.ty, TaskGraphEdgeType::Cell { .. })));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph {"));
+    assert!(dot.contains(&format!("task_{} -> task_{}", double.id, number.id)));
+
+    let json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+    assert_eq!(json["tasks"].as_array().unwrap().len(), graph.tasks.len());
+    assert!(json["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|edge| edge["type"] == "cell"));
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function]
+fn number() -> NumberVc {
+    NumberVc::cell(42)
+}
+
+#[turbo_tasks::function]
+async fn double(number: NumberVc) -> Result<NumberVc> {
+    Ok(NumberVc::cell(*number.await? * 2))
+}
@@ -0,0 +1,83 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::TurboTasks;
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn counts_tasks_cache_hits_and_executions() {
+    lazy_static::initialize(&REGISTER);
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async {
+        assert_eq!(*double(1).await?, 2);
+        assert_eq!(*double(1).await?, 2);
+        assert_eq!(*double(2).await?, 4);
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let stats = tt.task_statistics();
+    #[cfg(not(feature = "lightweight_stats"))]
+    {
+        let double_stats = stats.functions[&*DOUBLE_FUNCTION.name];
+        assert_eq!(double_stats.tasks, 2);
+        assert_eq!(double_stats.cache_hits, 1);
+        assert_eq!(double_stats.executions, 2);
+        assert_eq!(stats.total, double_stats);
+    }
+    #[cfg(feature = "lightweight_stats")]
+    {
+        assert!(stats.functions.is_empty());
+        assert_eq!(stats.total.tasks, 2);
+        assert_eq!(stats.total.executions, 2);
+    }
+    assert_eq!(stats.total.cache_hit_ratio(), 1.0 / 3.0);
+    assert_eq!(stats.dirty_tasks, 0);
+}
+
+#[tokio::test]
+async fn estimates_retained_memory() {
+    lazy_static::initialize(&REGISTER);
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async {
+        repeat(1000).await?;
+        repeat(2000).await?;
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let stats = tt.task_statistics();
+    #[cfg(feature = "memory_stats")]
+    {
+        let memory = stats.memory.unwrap();
+        let text = memory.value_types[&*TEXT_VALUE_TYPE.name];
+        assert_eq!(text.count, 2);
+        assert!(text.bytes >= 3000);
+        let repeat = memory.functions[&*REPEAT_FUNCTION.name];
+        assert_eq!(repeat.count, 2);
+        assert!(repeat.bytes > text.bytes);
+    }
+    #[cfg(not(feature = "memory_stats"))]
+    assert!(stats.memory.is_none());
+}
+
+#[turbo_tasks::value(transparent)]
+struct Value(u32);
+
+#[turbo_tasks::value(transparent)]
+struct Text(String);
+
+#[turbo_tasks::function]
+fn double(value: u32) -> ValueVc {
+    ValueVc::cell(value * 2)
+}
+
+#[turbo_tasks::function]
+fn repeat(count: usize) -> TextVc {
+    TextVc::cell("x".repeat(count))
+}
@@ -0,0 +1,96 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{assert_snapshot, register, TestHarness, TestInputVc};
+
+register!();
+
+#[tokio::test]
+async fn counts_executions_after_input_change() {
+    lazy_static::initialize(&REGISTER);
+    let harness = TestHarness::new(MemoryBackend::default());
+    let input = harness
+        .run(async { Ok(TestInputVc::new(1u32)) })
+        .await
+        .unwrap();
+
+    let (result, executions) = harness
+        .run_counting_executions(async move {
+            Ok(*is_small(double(input)).strongly_consistent().await?)
+        })
+        .await
+        .unwrap();
+    assert!(result);
+    assert_eq!(executions.functions(), vec!["double", "is_small"]);
+
+    let (_, executions) = harness
+        .run_counting_executions(async move {
+            Ok(*is_small(double(input)).strongly_consistent().await?)
+        })
+        .await
+        .unwrap();
+    assert!(executions.is_empty());
+
+    harness
+        .run(async move {
+            input.await?.set(2);
+            Ok(())
+        })
+        .await
+        .unwrap();
+    let (result, executions) = harness
+        .run_counting_executions(async move {
+            Ok(*is_small(double(input)).strongly_consistent().await?)
+        })
+        .await
+        .unwrap();
+    assert!(result);
+    assert_eq!(executions.count("double"), 1);
+    // The result of double has changed, so is_small is executed again.
+    assert_eq!(executions.count("is_small"), 1);
+
+    harness
+        .run(async move {
+            input.await?.set(2);
+            Ok(())
+        })
+        .await
+        .unwrap();
+    let (_, executions) = harness
+        .run_counting_executions(async move {
+            Ok(*is_small(double(input)).strongly_consistent().await?)
+        })
+        .await
+        .unwrap();
+    assert_eq!(executions.count("double"), 1);
+    // double returns the same value, so is_small isn't invalidated.
+    assert_eq!(executions.count("is_small"), 0);
+
+    harness
+        .run(async move {
+            assert_snapshot(
+                double(input),
+                concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/double.snap"),
+            )
+            .await
+        })
+        .await
+        .unwrap();
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::value(transparent)]
+struct Bool(bool);
+
+#[turbo_tasks::function]
+async fn double(input: TestInputVc<u32>) -> Result<NumberVc> {
+    Ok(NumberVc::cell(input.await?.get() * 2))
+}
+
+#[turbo_tasks::function]
+async fn is_small(number: NumberVc) -> Result<BoolVc> {
+    Ok(BoolVc::cell(*number.await? < 10))
+}
@@ -0,0 +1,103 @@
+#![feature(min_specialization)]
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    Layer,
+};
+use turbo_tasks::{primitives::U32Vc, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn executions_are_traced() {
+    lazy_static::initialize(&REGISTER);
+    let spans = Spans::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async {
+        assert_eq!(*outer(1).await?, 2);
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let executions = spans.executions();
+    let find = |name: &str| {
+        executions
+            .iter()
+            .find(|fields| fields["name"].contains(name))
+            .unwrap_or_else(|| panic!("no execution of {name} in {executions:?}"))
+    };
+    let outer = find("outer");
+    let inner = find("inner");
+    // The execution of inner is scheduled by the execution of outer.
+    assert_eq!(inner["parent_task_id"], outer["task_id"]);
+    assert!(outer.contains_key("parent_task_id"), "{outer:?}");
+    for fields in [outer, inner] {
+        let duration: u64 = fields["duration_us"].parse().unwrap();
+        assert!(duration < 60_000_000, "{fields:?}");
+    }
+}
+
+#[turbo_tasks::function]
+async fn outer(value: u32) -> Result<U32Vc> {
+    Ok(inner(value))
+}
+
+#[turbo_tasks::function]
+fn inner(value: u32) -> U32Vc {
+    U32Vc::cell(value * 2)
+}
+
+/// Records the fields of all `execution` spans.
+#[derive(Clone, Default)]
+struct Spans(Arc<Mutex<HashMap<Id, HashMap<String, String>>>>);
+
+impl Spans {
+    fn executions(&self) -> Vec<HashMap<String, String>> {
+        self.0.lock().unwrap().values().cloned().collect()
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Spans {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "execution" {
+            return;
+        }
+        let mut fields = HashMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        self.0.lock().unwrap().insert(id.clone(), fields);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        if let Some(fields) = self.0.lock().unwrap().get_mut(id) {
+            values.record(&mut FieldVisitor(fields));
+        }
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
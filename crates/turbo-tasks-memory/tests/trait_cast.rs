@@ -0,0 +1,82 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn trait_cast() {
+    run! {
+        let dog: LoudVc = DogVc::cell(Dog).into();
+
+        // Upcasts to a direct super trait are checked at compile time.
+        let greeter: GreeterVc = dog.upcast();
+        assert_eq!(*greeter.greet().await?, "woof");
+
+        // Sub traits and unrelated traits are checked at runtime.
+        let named = greeter.try_cast::<NamedVc>().await?.unwrap();
+        assert_eq!(*named.name().await?, "dog");
+        let loud = named.try_cast::<LoudVc>().await?.unwrap();
+        assert_eq!(*loud.shout().await?, "WOOF");
+        assert!(named.implements::<GreeterVc>().await?);
+
+        let cat: NamedVc = CatVc::cell(Cat).into();
+        assert!(cat.try_cast::<GreeterVc>().await?.is_none());
+        assert!(!cat.implements::<LoudVc>().await?);
+    }
+}
+
+#[turbo_tasks::value_trait]
+trait Named {
+    fn name(&self) -> StringVc;
+}
+
+#[turbo_tasks::value_trait]
+trait Greeter: Named {
+    fn greet(&self) -> StringVc;
+}
+
+#[turbo_tasks::value_trait]
+trait Loud: Greeter + Named {
+    fn shout(&self) -> StringVc;
+}
+
+#[turbo_tasks::value]
+struct Dog;
+
+#[turbo_tasks::value_impl]
+impl Named for Dog {
+    #[turbo_tasks::function]
+    fn name(&self) -> StringVc {
+        StringVc::cell("dog".to_string())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Greeter for Dog {
+    #[turbo_tasks::function]
+    fn greet(&self) -> StringVc {
+        StringVc::cell("woof".to_string())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Loud for Dog {
+    #[turbo_tasks::function]
+    async fn shout(self_vc: DogVc) -> Result<StringVc> {
+        Ok(StringVc::cell(self_vc.greet().await?.to_uppercase()))
+    }
+}
+
+#[turbo_tasks::value]
+struct Cat;
+
+#[turbo_tasks::value_impl]
+impl Named for Cat {
+    #[turbo_tasks::function]
+    fn name(&self) -> StringVc {
+        StringVc::cell("cat".to_string())
+    }
+}
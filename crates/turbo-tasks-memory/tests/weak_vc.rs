@@ -0,0 +1,41 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::{trace::TraceRawVcs, WeakVc};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn weak_vc() {
+    run! {
+        let number = NumberVc::cell(42);
+        let registry = registry(number).await?;
+        // Only the strong reference is reported to the garbage collection.
+        assert_eq!(registry.get_raw_vcs().len(), 1);
+
+        let weak = registry.weak[0];
+        assert_eq!(weak, WeakVc::new(number));
+        let number = weak.upgrade().expect("the cell is still loaded");
+        assert_eq!(*number.await?, 42);
+        assert_eq!(*weak.upgrade_or_recompute().await?, 42);
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::value]
+struct Registry {
+    strong: Vec<NumberVc>,
+    weak: Vec<WeakVc<NumberVc>>,
+}
+
+#[turbo_tasks::function]
+fn registry(number: NumberVc) -> RegistryVc {
+    Registry {
+        strong: vec![number],
+        weak: vec![WeakVc::new(number)],
+    }
+    .cell()
+}
@@ -0,0 +1,96 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use anyhow::Result;
+use turbo_tasks::{backend::Backend, trace::TraceRawVcs, TurboTasks};
+
+/// A [TurboTasks] instance for tests which need a real backend, e.g. to check
+/// which tasks are executed again after a [TestInput](crate::TestInput) has
+/// changed.
+///
+/// ```ignore
+/// let harness = TestHarness::new(MemoryBackend::default());
+/// let input = harness.run(async { Ok(TestInputVc::new(1)) }).await?;
+/// harness.run(async move { double(input).strongly_consistent().await }).await?;
+///
+/// harness.run(async move { Ok(input.await?.set(2)) }).await?;
+/// let (_, executions) = harness
+///     .run_counting_executions(async move { double(input).strongly_consistent().await })
+///     .await?;
+/// assert_eq!(executions.count("double"), 1);
+/// ```
+pub struct TestHarness<B: Backend + 'static> {
+    turbo_tasks: Arc<TurboTasks<B>>,
+}
+
+impl<B: Backend + 'static> TestHarness<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            turbo_tasks: TurboTasks::new(backend),
+        }
+    }
+
+    pub fn turbo_tasks(&self) -> &Arc<TurboTasks<B>> {
+        &self.turbo_tasks
+    }
+
+    /// Runs `future` to completion in a new root task. Reads should be
+    /// strongly consistent, so all tasks they depend on have finished when
+    /// this returns.
+    pub async fn run<T: TraceRawVcs + Send + 'static>(
+        &self,
+        future: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<T> {
+        self.turbo_tasks.run_once(future).await
+    }
+
+    /// Like [TestHarness::run], but also returns which functions have been
+    /// executed while running. It relies on the per function
+    /// [TaskStatistics](turbo_tasks::TaskStatistics) of the backend.
+    pub async fn run_counting_executions<T: TraceRawVcs + Send + 'static>(
+        &self,
+        future: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<(T, Executions)> {
+        let before = self.turbo_tasks.task_statistics();
+        let result = self.run(future).await?;
+        let after = self.turbo_tasks.task_statistics();
+        let counts = after
+            .functions
+            .iter()
+            .filter_map(|(&name, stats)| {
+                let previous = before
+                    .functions
+                    .get(name)
+                    .map_or(0, |stats| stats.executions);
+                let count = stats.executions - previous;
+                (count > 0).then_some((name, count))
+            })
+            .collect();
+        Ok((result, Executions { counts }))
+    }
+}
+
+/// The number of executions per function during a
+/// [TestHarness::run_counting_executions].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Executions {
+    counts: HashMap<&'static str, usize>,
+}
+
+impl Executions {
+    /// The number of executions of the function with the `name`, e.g.
+    /// `double` or `FooVc::bar` for methods.
+    pub fn count(&self, name: &str) -> usize {
+        self.counts.get(name).copied().unwrap_or_default()
+    }
+
+    /// The names of all functions which have been executed, sorted.
+    pub fn functions(&self) -> Vec<&'static str> {
+        let mut functions = self.counts.keys().copied().collect::<Vec<_>>();
+        functions.sort_unstable();
+        functions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Mutex;
+
+use auto_hash_map::AutoSet;
+use turbo_tasks::{get_invalidator, InvalidationBatch, Invalidator};
+
+/// An input of a test which can be changed between runs to check which tasks
+/// are invalidated.
+///
+/// ```ignore
+/// #[turbo_tasks::function]
+/// async fn double(input: TestInputVc<u32>) -> Result<NumberVc> {
+///     Ok(NumberVc::cell(input.await?.get() * 2))
+/// }
+/// ```
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new")]
+pub struct TestInput<T> {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    state: Mutex<TestInputState<T>>,
+}
+
+struct TestInputState<T> {
+    value: T,
+    readers: AutoSet<Invalidator>,
+}
+
+impl<T: Clone + Send + Sync + 'static> TestInputVc<T> {
+    pub fn new(value: T) -> Self {
+        Self::cell(TestInput {
+            state: Mutex::new(TestInputState {
+                value,
+                readers: AutoSet::new(),
+            }),
+        })
+    }
+}
+
+impl<T: Clone> TestInput<T> {
+    /// Returns the current value. The task calling it is invalidated when the
+    /// value is changed.
+    pub fn get(&self) -> T {
+        let mut state = self.state.lock().unwrap();
+        state.readers.insert(get_invalidator());
+        state.value.clone()
+    }
+
+    /// Changes the value and invalidates all tasks which have read it.
+    pub fn set(&self, value: T) {
+        let readers = {
+            let mut state = self.state.lock().unwrap();
+            state.value = value;
+            std::mem::take(&mut state.readers)
+        };
+        let mut batch = InvalidationBatch::new();
+        for reader in readers {
+            batch.add(reader);
+        }
+        batch.apply();
+    }
+}
@@ -1,7 +1,12 @@
 //! Testing utilities and macros for turbo-tasks and applications based on it.
 
+#![feature(min_specialization)]
+
+pub mod harness;
+mod input;
 mod macros;
 pub mod retry;
+pub mod snapshot;
 
 use std::{
     borrow::Cow,
@@ -15,6 +20,9 @@ use std::{
 use anyhow::{anyhow, Result};
 use auto_hash_map::AutoSet;
 use futures::FutureExt;
+pub use harness::{Executions, TestHarness};
+pub use input::{TestInput, TestInputVc};
+pub use snapshot::assert_snapshot;
 use turbo_tasks::{
     backend::CellContent,
     event::{Event, EventListener},
@@ -135,6 +143,22 @@ impl TurboTasksApi for VcStorage {
         unreachable!()
     }
 
+    fn get_unfinished_task_count(&self, _task: TaskId) -> Option<usize> {
+        None
+    }
+
+    fn is_vc_loaded(&self, _vc: RawVc) -> bool {
+        true
+    }
+
+    fn invalidate_batch(
+        &self,
+        _tasks: Vec<TaskId>,
+        _reasons: Vec<turbo_tasks::util::StaticOrArc<dyn turbo_tasks::InvalidationReason>>,
+    ) {
+        unreachable!()
+    }
+
     fn notify_scheduled_tasks(&self) {
         // ignore
     }
@@ -236,10 +260,18 @@ impl TurboTasksApi for VcStorage {
         // no-op
     }
 
+    fn cancel_task(&self, _task: TaskId) {
+        // no-op
+    }
+
     fn mark_own_task_as_finished(&self, _task: TaskId) {
         // no-op
     }
 
+    fn mark_own_task_as_session_dependent(&self, _task: TaskId) {
+        // no-op
+    }
+
     fn detached(
         &self,
         _f: std::pin::Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
@@ -260,3 +292,10 @@ impl VcStorage {
         )
     }
 }
+
+/// Registers the value types of this crate. It's not named `register` to not
+/// conflict with the [register!] macro, which calls it.
+pub fn register_values() {
+    turbo_tasks::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
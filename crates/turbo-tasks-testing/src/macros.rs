@@ -1,9 +1,13 @@
+/// Registers the functions and values of the test, after the ones of the
+/// crates it depends on, e.g. `register!(turbopack_core::register)`.
 #[macro_export]
 macro_rules! register {
-    () => {
+    ($($register:path),* $(,)?) => {
         lazy_static::lazy_static! {
             static ref REGISTER: () = {
                 turbo_tasks::register();
+                $crate::register_values();
+                $($register();)*
                 include!(concat!(env!("OUT_DIR"), "/register_test_", module_path!(), ".rs"));
             };
         }
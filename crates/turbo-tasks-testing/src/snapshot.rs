@@ -0,0 +1,48 @@
+use std::{env, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use turbo_tasks::debug::ValueDebug;
+
+// Updates the existing snapshots with the actual values of this run.
+// e.g. `UPDATE=1 cargo test -p turbo-tasks-memory -- test_my_pattern`
+static UPDATE: Lazy<bool> = Lazy::new(|| env::var("UPDATE").unwrap_or_default() == "1");
+
+/// Compares the debug representation of the cell content of `value` with the
+/// snapshot stored in `path`. A missing snapshot is created.
+///
+/// ```ignore
+/// assert_snapshot(
+///     double(input),
+///     concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/double.snap"),
+/// )
+/// .await?;
+/// ```
+pub async fn assert_snapshot(value: impl ValueDebug, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let actual = value.dbg().await?.as_str().to_string();
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => Some(expected),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            return Err(err).with_context(|| format!("unable to read {}", path.display()));
+        }
+    };
+    if expected.as_deref() == Some(&actual) {
+        return Ok(());
+    }
+    if expected.is_none() || *UPDATE {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &actual).with_context(|| format!("unable to write {}", path.display()))?;
+        return Ok(());
+    }
+    bail!(
+        "snapshot {} doesn't match, run with UPDATE=1 to update it\n--- expected\n{}\n--- \
+         actual\n{}",
+        path.display(),
+        expected.unwrap_or_default(),
+        actual
+    );
+}
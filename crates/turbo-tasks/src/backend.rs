@@ -11,12 +11,14 @@ use std::{
 
 use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
+use turbo_tasks_hash::hash_xxh3_hash64;
 
 pub use crate::id::BackendJobId;
 use crate::{
-    event::EventListener, manager::TurboTasksBackendApi, primitives::RawVcSetVc, raw_vc::CellId,
-    registry, task_input::SharedReference, FunctionId, RawVc, ReadRef, TaskId, TaskIdProvider,
-    TaskInput, TraitRef, TraitTypeId, ValueTraitVc,
+    event::EventListener, manager::TurboTasksBackendApi, primitives::RawVcSetVc,
+    priority::TaskPriority, raw_vc::CellId, registry, task_input::SharedReference,
+    task_statistics::TaskStatistics, FunctionId, RawVc, ReadRef, TaskId, TaskIdProvider, TaskInput,
+    TraitRef, TraitTypeId, ValueTraitVc,
 };
 
 pub enum TaskType {
@@ -123,6 +125,20 @@ impl PersistentTaskType {
             }
         }
     }
+
+    /// A hash of the task type which is equal across sessions and processes,
+    /// unlike the [TaskId] of the task. Functions and traits are hashed by
+    /// their global name and inputs by their serialized value, so it can be
+    /// used as key in a persistent store.
+    ///
+    /// Task ids referenced by the inputs are session-local too. They are
+    /// hashed as mapped by [with_task_id_mapping](crate::with_task_id_mapping),
+    /// which should map them to ids of the persistent store.
+    ///
+    /// Fails when an input is not serializable.
+    pub fn stable_hash(&self) -> Result<u64> {
+        Ok(hash_xxh3_hash64(serde_json::to_vec(self)?))
+    }
 }
 
 pub struct TaskExecutionSpec {
@@ -187,6 +203,17 @@ impl CellContent {
     }
 }
 
+/// The backend stores the task graph and schedules the execution of tasks.
+/// [TurboTasks](crate::TurboTasks) calls into it for every task operation.
+///
+/// Task ids, cell ids and the ids of functions and value types are only valid
+/// within a session. A backend which keeps tasks beyond a session (e.g. in a
+/// key-value store like SQLite, RocksDB or LMDB) has to store them by
+/// [PersistentTaskType::stable_hash] and serialize cells with the global
+/// names of their value types, which is what the `Serialize` implementations
+/// of [PersistentTaskType] and [CellContent] do. See
+/// [PersistedGraph](crate::persisted_graph::PersistedGraph) for a layer which
+/// takes care of the persistent part on top of an in-memory backend.
 pub trait Backend: Sync + Send {
     #[allow(unused_variables)]
     fn initialize(&mut self, task_id_provider: &dyn TaskIdProvider) {}
@@ -206,6 +233,42 @@ pub trait Backend: Sync + Send {
 
     fn get_task_description(&self, task: TaskId) -> String;
 
+    /// The priority declared by the function of the task. The manager
+    /// combines it with the priority of the scheduling context.
+    fn get_task_priority(&self, _task: TaskId) -> TaskPriority {
+        TaskPriority::Normal
+    }
+
+    /// Executions of the task taking longer are reported as slow tasks.
+    /// `None` uses the threshold of the manager.
+    fn get_task_slow_threshold(&self, _task: TaskId) -> Option<Duration> {
+        None
+    }
+
+    /// A readable representation of the arguments of the task, used to report
+    /// slow tasks.
+    fn get_task_arguments(&self, _task: TaskId) -> Option<String> {
+        None
+    }
+
+    /// Statistics about the tasks of this backend. Backends which don't
+    /// collect statistics return empty statistics.
+    fn task_statistics(&self) -> TaskStatistics {
+        TaskStatistics::default()
+    }
+
+    /// The number of tasks a strongly consistent read of the task is still
+    /// waiting for. Returns `None` when the backend doesn't track it.
+    fn get_unfinished_task_count(&self, _task: TaskId) -> Option<usize> {
+        None
+    }
+
+    /// Returns false when the task output or cell has been unloaded by the
+    /// garbage collection, so reading it would recompute it.
+    fn is_vc_loaded(&self, _vc: RawVc) -> bool {
+        true
+    }
+
     type ExecutionScopeFuture<T: Future<Output = Result<()>> + Send + 'static>: Future<Output = Result<()>>
         + Send
         + 'static;
@@ -229,6 +292,18 @@ pub trait Backend: Sync + Send {
         turbo_tasks: &dyn TurboTasksBackendApi<Self>,
     );
 
+    /// Called before [Backend::task_execution_result] when the execution has
+    /// been cancelled. The result of a cancelled execution is incomplete, so
+    /// backends should discard it and keep the task dirty. By default the
+    /// result is stored like any other result.
+    fn task_execution_cancelled(
+        &self,
+        _task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) {
+        // Do nothing by default
+    }
+
     fn task_execution_completed(
         &self,
         task: TaskId,
@@ -346,6 +421,24 @@ pub trait Backend: Sync + Send {
         // Do nothing by default
     }
 
+    fn mark_own_task_as_session_dependent(
+        &self,
+        _task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) {
+        // Do nothing by default
+    }
+
+    /// Cancels the execution of `task` and of the tasks in its subtree whose
+    /// results are no longer needed. By default only the task itself is
+    /// cancelled.
+    fn cancel_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>)
+    where
+        Self: Sized + 'static,
+    {
+        turbo_tasks.cancel_task_execution(task);
+    }
+
     fn create_transient_task(
         &self,
         task_type: TransientTaskType,
@@ -0,0 +1,98 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::Result;
+
+use crate::event::Event;
+
+/// A token to cooperatively cancel a task execution. The manager cancels it
+/// when the result of the execution is no longer needed. Long running tasks
+/// can check it between steps and stop early with a [Cancelled] error.
+#[derive(Clone)]
+pub struct CancellationToken {
+    inner: Arc<CancellationTokenInner>,
+}
+
+struct CancellationTokenInner {
+    cancelled: AtomicBool,
+    event: Event,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(CancellationTokenInner {
+                cancelled: AtomicBool::new(false),
+                event: Event::new(|| "CancellationToken::event".to_string()),
+            }),
+        }
+    }
+
+    /// Cancels the token and wakes up everyone waiting for it. Cancelling is
+    /// idempotent.
+    pub fn cancel(&self) {
+        if !self.inner.cancelled.swap(true, Ordering::AcqRel) {
+            self.inner.event.notify(usize::MAX);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Returns an error when the token has been cancelled. Use it with `?` to
+    /// stop an execution early.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Cancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Resolves once the token has been cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            if self.is_cancelled() {
+                return;
+            }
+            let listener = self.inner.event.listen();
+            // The token might have been cancelled before we started listening.
+            if self.is_cancelled() {
+                return;
+            }
+            listener.await;
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// The error of a task execution that stopped because it has been cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "task execution has been cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
@@ -0,0 +1,155 @@
+use std::{
+    future::Future,
+    mem::take,
+    panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use tokio::sync::Notify;
+
+type SpawnedFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Executes spawned futures one at a time on a single tokio task. Futures
+/// which have been woken up are polled in rounds, either in the order they
+/// have been spawned or in an order shuffled by a seed. Given the same inputs
+/// this makes the order of task executions reproducible, which helps to debug
+/// ordering dependent bugs.
+pub(crate) struct DeterministicExecutor {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    spawned: Mutex<Vec<SpawnedFuture>>,
+    notify: Notify,
+    driver_started: AtomicBool,
+    seed: Option<u64>,
+}
+
+struct Slot {
+    future: SpawnedFuture,
+    waker: Arc<SlotWaker>,
+}
+
+struct SlotWaker {
+    woken: AtomicBool,
+    inner: Weak<Inner>,
+}
+
+impl Wake for SlotWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+        if let Some(inner) = self.inner.upgrade() {
+            inner.notify.notify_one();
+        }
+    }
+}
+
+impl DeterministicExecutor {
+    /// Creates an executor which polls futures in the order they have been
+    /// spawned, or in a shuffled order when a `seed` is provided.
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                spawned: Mutex::new(Vec::new()),
+                notify: Notify::new(),
+                driver_started: AtomicBool::new(false),
+                seed,
+            }),
+        }
+    }
+
+    pub fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        self.inner.spawned.lock().unwrap().push(Box::pin(future));
+        self.inner.notify.notify_one();
+        if !self.inner.driver_started.swap(true, Ordering::AcqRel) {
+            tokio::spawn(drive(self.inner.clone()));
+        }
+    }
+}
+
+impl Drop for DeterministicExecutor {
+    fn drop(&mut self) {
+        // Wake up the driver so it can stop once all futures are done.
+        self.inner.notify.notify_one();
+    }
+}
+
+async fn drive(inner: Arc<Inner>) {
+    let mut slots: Vec<Slot> = Vec::new();
+    let mut rng = inner.seed.map(SplitMix64);
+    loop {
+        for future in take(&mut *inner.spawned.lock().unwrap()) {
+            slots.push(Slot {
+                future,
+                waker: Arc::new(SlotWaker {
+                    woken: AtomicBool::new(true),
+                    inner: Arc::downgrade(&inner),
+                }),
+            });
+        }
+        let mut woken = slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.waker.woken.swap(false, Ordering::AcqRel))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if woken.is_empty() {
+            if slots.is_empty() && Arc::strong_count(&inner) == 1 {
+                // The executor has been dropped and there is nothing left to do.
+                return;
+            }
+            inner.notify.notified().await;
+            continue;
+        }
+        if let Some(rng) = &mut rng {
+            rng.shuffle(&mut woken);
+        }
+        let mut done = vec![false; slots.len()];
+        for index in woken {
+            let slot = &mut slots[index];
+            let waker = Waker::from(slot.waker.clone());
+            let mut cx = Context::from_waker(&waker);
+            // A panic only affects the future that panicked, like in a tokio task.
+            if !matches!(
+                catch_unwind(AssertUnwindSafe(|| slot.future.as_mut().poll(&mut cx))),
+                Ok(Poll::Pending)
+            ) {
+                done[index] = true;
+            }
+        }
+        let mut done = done.into_iter();
+        slots.retain(|_| !done.next().unwrap_or(false));
+        // Give other tokio tasks, e.g. the ones waiting for results, a chance to run.
+        tokio::task::yield_now().await;
+    }
+}
+
+/// A small seeded random number generator, see
+/// <https://prng.di.unimi.it/splitmix64.c>.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
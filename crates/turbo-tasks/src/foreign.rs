@@ -0,0 +1,11 @@
+use crate::{self as turbo_tasks};
+
+/// Stores a value of a type from another crate in a cell, e.g.
+/// `ForeignVc::<IndexMap<String, u32>>::cell(map)`. Use it for types which
+/// can't be annotated with `#[turbo_tasks::value]`, instead of writing a
+/// transparent newtype for every one of them.
+///
+/// The value is not traced for Vcs, not serialized and never compared, so
+/// every `cell` invalidates the readers.
+#[turbo_tasks::value(transparent, serialization = "none", eq = "manual", cell = "new")]
+pub struct Foreign<T>(#[turbo_tasks(trace_ignore)] T);
@@ -59,12 +59,28 @@ impl Debug for TaskId {
 }
 
 macro_rules! make_serializable {
-    ($ty:ty, $get_global_name:path, $get_id:path, $visitor_name:ident) => {
+    (
+        $ty:ty,
+        $get_global_name:path,
+        $get_id:path,
+        $visitor_name:ident
+        $(, $is_generic_instance:path)?
+    ) => {
         impl Serialize for $ty {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             where
                 S: serde::Serializer,
             {
+                // Instantiations of generics are registered on first use, so
+                // their names can't be resolved when deserializing.
+                $(
+                    if $is_generic_instance(*self) {
+                        return Err(serde::ser::Error::custom(format!(
+                            "{} is an instantiation of a generic and is not serializable",
+                            $get_global_name(*self)
+                        )));
+                    }
+                )?
                 serializer.serialize_str($get_global_name(*self))
             }
         }
@@ -110,13 +126,15 @@ make_serializable!(
     FunctionId,
     registry::get_function_global_name,
     registry::get_function_id_by_global_name,
-    FunctionIdVisitor
+    FunctionIdVisitor,
+    registry::is_generic_function_instance
 );
 make_serializable!(
     ValueTypeId,
     registry::get_value_type_global_name,
     registry::get_value_type_id_by_global_name,
-    ValueTypeVisitor
+    ValueTypeVisitor,
+    registry::is_generic_value_type_instance
 );
 make_serializable!(
     TraitTypeId,
@@ -0,0 +1,220 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::Arc,
+};
+
+use dashmap::DashSet;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::trace::{TraceRawVcs, TraceRawVcsContext};
+
+static STRINGS: Lazy<DashSet<Arc<str>>> = Lazy::new(DashSet::new);
+
+/// An immutable string which is stored only once per process. Strings like
+/// paths and module specifiers are part of many task inputs and cells, so
+/// sharing them saves memory.
+///
+/// As equal strings share the same allocation, comparing and hashing only
+/// looks at the pointer. Strings are removed from the pool when the last
+/// reference is dropped.
+#[derive(Clone)]
+pub struct InternedString(Arc<str>);
+
+impl InternedString {
+    pub fn new(s: &str) -> Self {
+        if let Some(existing) = STRINGS.get(s) {
+            return Self(existing.clone());
+        }
+        let arc: Arc<str> = Arc::from(s);
+        // Another thread might have interned the same string in the meantime.
+        if STRINGS.insert(arc.clone()) {
+            Self(arc)
+        } else {
+            Self::new(s)
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The number of distinct strings which are currently interned.
+    pub fn pool_size() -> usize {
+        STRINGS.len()
+    }
+}
+
+impl Drop for InternedString {
+    fn drop(&mut self) {
+        // The pool and this instance hold the last two references. The pool
+        // entry is locked while checking, so no new reference can be handed
+        // out in between.
+        if Arc::strong_count(&self.0) == 2 {
+            STRINGS.remove_if(&*self.0, |arc| Arc::strong_count(arc) == 2);
+        }
+    }
+}
+
+impl Deref for InternedString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for InternedString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for InternedString {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for InternedString {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for InternedString {}
+
+impl PartialEq<str> for InternedString {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for InternedString {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for InternedString {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl PartialOrd for InternedString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InternedString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if Arc::ptr_eq(&self.0, &other.0) {
+            Ordering::Equal
+        } else {
+            self.0.cmp(&other.0)
+        }
+    }
+}
+
+impl Hash for InternedString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state)
+    }
+}
+
+impl Debug for InternedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+impl Display for InternedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for InternedString {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl From<String> for InternedString {
+    fn from(s: String) -> Self {
+        Self::new(&s)
+    }
+}
+
+impl From<InternedString> for String {
+    fn from(s: InternedString) -> Self {
+        s.as_str().to_string()
+    }
+}
+
+impl Default for InternedString {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Serialize for InternedString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(Self::new(&s))
+    }
+}
+
+impl TraceRawVcs for InternedString {
+    fn trace_raw_vcs(&self, _context: &mut TraceRawVcsContext) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InternedString;
+
+    #[test]
+    fn shares_equal_strings() {
+        let a = InternedString::new("interned_string::shares_equal_strings");
+        let b = InternedString::from("interned_string::shares_equal_strings".to_string());
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert_ne!(a, InternedString::new("interned_string::other"));
+        assert_eq!(a, "interned_string::shares_equal_strings");
+    }
+
+    #[test]
+    fn removes_unused_strings() {
+        let s = InternedString::new("interned_string::removes_unused_strings");
+        let ptr = s.as_str().as_ptr();
+        let clone = s.clone();
+        drop(s);
+        assert_eq!(clone.as_str().as_ptr(), ptr);
+        drop(clone);
+        let new = InternedString::new("interned_string::removes_unused_strings");
+        assert!(super::STRINGS.contains(new.as_str()));
+        drop(new);
+        assert!(!super::STRINGS.contains("interned_string::removes_unused_strings"));
+    }
+
+    #[test]
+    fn serializes_as_string() {
+        let s = InternedString::new("interned_string::serializes_as_string");
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(json, "\"interned_string::serializes_as_string\"");
+        let de: InternedString = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, s);
+    }
+}
@@ -34,15 +34,19 @@
 #![feature(never_type)]
 
 pub mod backend;
+mod cancellation;
 mod collectibles;
 mod completion;
 pub mod debug;
+mod deterministic;
 mod display;
 pub mod duration_span;
 pub mod event;
+pub mod foreign;
 pub mod graph;
 mod id;
 mod id_factory;
+mod interned_string;
 mod invalidation;
 mod join_iter_ext;
 #[doc(hidden)]
@@ -55,53 +59,64 @@ mod nothing;
 mod once_map;
 pub mod persisted_graph;
 pub mod primitives;
+mod priority;
 mod raw_vc;
 mod read_ref;
 pub mod registry;
+pub mod remote;
+pub mod retry;
 pub mod small_duration;
 mod state;
 mod task_input;
+pub mod task_statistics;
 mod timed_future;
 pub mod trace;
 mod trait_ref;
 pub mod util;
 mod value;
 mod value_type;
+mod weak_vc;
 
 pub use anyhow::{Error, Result};
+pub use cancellation::{CancellationToken, Cancelled};
 pub use collectibles::CollectiblesSource;
 pub use completion::{Completion, CompletionVc, CompletionsVc};
 pub use display::{ValueToString, ValueToStringVc};
+pub use foreign::{Foreign, ForeignVc};
 pub use id::{
     with_task_id_mapping, without_task_id_mapping, FunctionId, IdMapping, TaskId, TraitTypeId,
     ValueTypeId,
 };
+pub use interned_string::InternedString;
 pub use invalidation::{
     DynamicEqHash, InvalidationReason, InvalidationReasonKind, InvalidationReasonSet,
 };
 pub use join_iter_ext::{JoinIterExt, TryJoinIterExt};
 pub use manager::{
-    dynamic_call, emit, get_invalidator, mark_finished, mark_stateful, run_once,
-    run_once_with_reason, spawn_blocking, spawn_thread, trait_call, turbo_tasks, Invalidator,
-    StatsType, TaskIdProvider, TurboTasks, TurboTasksApi, TurboTasksBackendApi, TurboTasksCallApi,
-    Unused, UpdateInfo,
+    cancellation_token, dynamic_call, emit, get_invalidator, mark_finished, mark_session_dependent,
+    mark_stateful, run_once, run_once_with_reason, spawn_blocking, spawn_thread, trait_call,
+    turbo_tasks, with_priority, InvalidationBatch, Invalidator, StatsType, TaskIdProvider,
+    TurboTasks, TurboTasksApi, TurboTasksBackendApi, TurboTasksCallApi, Unused, UpdateInfo,
 };
-pub use native_function::{NativeFunction, NativeFunctionVc};
+pub use native_function::{GenericNativeFunction, NativeFunction, NativeFunctionVc};
 pub use nothing::{Nothing, NothingVc};
+pub use priority::TaskPriority;
 pub use raw_vc::{
-    CellId, CollectiblesFuture, RawVc, ReadRawVcFuture, ResolveTypeError, TraitCast,
-    TransparentValueCast, ValueCast,
+    CellId, CollectiblesFuture, RawVc, ReadProgress, ReadRawVcFuture, ReadTimeoutError,
+    ResolveTypeError, TraitCast, TransparentValueCast, ValueCast,
 };
 pub use read_ref::ReadRef;
-pub use state::State;
-pub use task_input::{FromTaskInput, SharedReference, SharedValue, TaskInput};
+pub use state::{State, StateTransaction};
+pub use task_input::{FromTaskInput, GenericTaskInput, SharedReference, SharedValue, TaskInput};
+pub use task_statistics::{FunctionStatistics, MemoryStatistics, RetainedMemory, TaskStatistics};
 pub use trait_ref::{IntoTraitRef, TraitRef};
 pub use turbo_tasks_macros::{function, value, value_impl, value_trait, TaskInput};
 pub use value::{TransientInstance, TransientValue, Value};
 pub use value_type::{
-    FromSubTrait, IntoSuperTrait, TraitMethod, TraitType, Typed, TypedForInput, ValueTraitVc,
-    ValueType, ValueVc,
+    FromSubTrait, GenericValueType, IntoSuperTrait, TraitMethod, TraitType, Typed, TypedForInput,
+    ValueTraitVc, ValueType, ValueVc,
 };
+pub use weak_vc::WeakVc;
 
 pub mod test_helpers {
     pub use super::manager::{current_task_for_testing, with_turbo_tasks_for_testing};
@@ -21,18 +21,22 @@ use futures::FutureExt;
 use nohash_hasher::BuildNoHashHasher;
 use serde::{de::Visitor, Deserialize, Serialize};
 use tokio::{runtime::Handle, select, task_local};
-use tracing::{instrument, trace_span, Instrument, Level};
+use tracing::{field::Empty, info_span, instrument, trace_span, warn, Instrument, Level, Span};
 
 use crate::{
     backend::{Backend, CellContent, PersistentTaskType, TransientTaskType},
+    cancellation::{CancellationToken, Cancelled},
+    deterministic::DeterministicExecutor,
     event::{Event, EventListener},
     id::{BackendJobId, FunctionId, TraitTypeId},
     id_factory::IdFactory,
     invalidation::InvalidationReasonSet,
     primitives::RawVcSetVc,
+    priority::TaskPriority,
     raw_vc::{CellId, RawVc},
     registry,
     task_input::{SharedReference, TaskInput},
+    task_statistics::TaskStatistics,
     timed_future::{self, TimedFuture},
     trace::TraceRawVcs,
     util::{FormatDuration, StaticOrArc},
@@ -67,6 +71,13 @@ pub trait TurboTasksCallApi: Sync + Send {
 pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
     fn invalidate(&self, task: TaskId);
     fn invalidate_with_reason(&self, task: TaskId, reason: StaticOrArc<dyn InvalidationReason>);
+    /// Invalidates all tasks at once. Executions of the invalidated tasks only
+    /// start after all of them have been invalidated.
+    fn invalidate_batch(
+        &self,
+        tasks: Vec<TaskId>,
+        reasons: Vec<StaticOrArc<dyn InvalidationReason>>,
+    );
 
     /// Eagerly notifies all tasks that were scheduled for notifications via
     /// `schedule_notify_tasks_set()`
@@ -78,6 +89,14 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
         strongly_consistent: bool,
     ) -> Result<Result<RawVc, EventListener>>;
 
+    /// The number of tasks a strongly consistent read of the task is still
+    /// waiting for, if known.
+    fn get_unfinished_task_count(&self, task: TaskId) -> Option<usize>;
+
+    /// Returns false when the task output or cell has been unloaded, see
+    /// [WeakVc](crate::WeakVc).
+    fn is_vc_loaded(&self, vc: RawVc) -> bool;
+
     /// INVALIDATION: Be careful with this, it will not track dependencies, so
     /// using it could break cache invalidation.
     fn try_read_task_output_untracked(
@@ -117,9 +136,15 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
     fn read_own_task_cell(&self, task: TaskId, index: CellId) -> Result<CellContent>;
     fn update_own_task_cell(&self, task: TaskId, index: CellId, content: CellContent);
     fn mark_own_task_as_finished(&self, task: TaskId);
+    fn mark_own_task_as_session_dependent(&self, task: TaskId);
 
     fn connect_task(&self, task: TaskId);
 
+    /// Cancels the execution of a task and of the tasks in its subtree whose
+    /// results are no longer needed. Cancellation is cooperative: executions
+    /// stop early when they check their [CancellationToken].
+    fn cancel_task(&self, task: TaskId);
+
     /// Wraps the given future in the current task.
     fn detached(
         &self,
@@ -191,6 +216,9 @@ pub trait TurboTasksBackendApi<B: Backend + 'static>:
     fn pin(&self) -> Arc<dyn TurboTasksBackendApi<B>>;
 
     fn schedule(&self, task: TaskId);
+    /// Cancels the [CancellationToken] of the current execution of the task,
+    /// if it's executing.
+    fn cancel_task_execution(&self, task: TaskId);
     fn schedule_backend_background_job(&self, id: BackendJobId);
     fn schedule_backend_foreground_job(&self, id: BackendJobId);
 
@@ -266,6 +294,19 @@ pub struct TurboTasks<B: Backend + 'static> {
     currently_scheduled_foreground_jobs: AtomicUsize,
     currently_scheduled_background_jobs: AtomicUsize,
     scheduled_tasks: AtomicUsize,
+    /// The number of executions per priority which are waiting to start.
+    pending_executions: [AtomicUsize; TaskPriority::COUNT],
+    priority_event: Event,
+    /// The number of invalidation batches which are currently applied.
+    /// Executions scheduled meanwhile are deferred until all batches are
+    /// applied.
+    invalidation_batches: AtomicUsize,
+    deferred_executions: Mutex<Vec<TaskId>>,
+    /// Executions taking longer are reported as slow tasks, unless their
+    /// function has its own threshold.
+    slow_task_threshold: Mutex<Option<Duration>>,
+    /// The cancellation tokens of the tasks which are currently executing.
+    cancellation_tokens: Mutex<HashMap<TaskId, CancellationToken, BuildNoHashHasher<TaskId>>>,
     start: Mutex<Option<Instant>>,
     aggregated_update: Mutex<(Option<(Duration, usize)>, InvalidationReasonSet)>,
     event: Event,
@@ -276,6 +317,8 @@ pub struct TurboTasks<B: Backend + 'static> {
     // locking overhead.
     enable_full_stats: AtomicBool,
     program_start: Instant,
+    /// Executes all spawned work in a reproducible order when set.
+    deterministic_executor: Option<DeterministicExecutor>,
 }
 
 #[derive(Default)]
@@ -287,6 +330,9 @@ struct CurrentTaskState {
 
     // true, if the current task has state in cells
     stateful: bool,
+
+    /// Cancelled when the result of the execution is no longer needed
+    cancellation_token: CancellationToken,
 }
 
 // TODO implement our own thread pool and make these thread locals instead
@@ -299,6 +345,9 @@ task_local! {
     static CURRENT_TASK_ID: TaskId;
 
     static CURRENT_TASK_STATE: RefCell<CurrentTaskState>;
+
+    /// The priority of the current execution or of the work it's part of
+    static CURRENT_PRIORITY: TaskPriority;
 }
 
 impl<B: Backend + 'static> TurboTasks<B> {
@@ -307,7 +356,23 @@ impl<B: Backend + 'static> TurboTasks<B> {
     // that should be safe as long tasks can't outlife turbo task
     // so we probably want to make sure that all tasks are joined
     // when trying to drop turbo tasks
-    pub fn new(mut backend: B) -> Arc<Self> {
+    pub fn new(backend: B) -> Arc<Self> {
+        Self::new_with_executor(backend, None)
+    }
+
+    /// Creates a [TurboTasks] which executes tasks one at a time in a
+    /// reproducible order, to debug ordering dependent bugs. Without a `seed`
+    /// tasks are executed in the order they have been scheduled, with a
+    /// `seed` in an order shuffled by it. This is much slower and is only
+    /// intended for tests.
+    pub fn new_deterministic(backend: B, seed: Option<u64>) -> Arc<Self> {
+        Self::new_with_executor(backend, Some(DeterministicExecutor::new(seed)))
+    }
+
+    fn new_with_executor(
+        mut backend: B,
+        deterministic_executor: Option<DeterministicExecutor>,
+    ) -> Arc<Self> {
         let task_id_factory = IdFactory::new();
         backend.initialize(&task_id_factory);
         let this = Arc::new_cyclic(|this| Self {
@@ -319,6 +384,12 @@ impl<B: Backend + 'static> TurboTasks<B> {
             currently_scheduled_background_jobs: AtomicUsize::new(0),
             currently_scheduled_foreground_jobs: AtomicUsize::new(0),
             scheduled_tasks: AtomicUsize::new(0),
+            pending_executions: Default::default(),
+            priority_event: Event::new(|| "TurboTasks::priority_event".to_string()),
+            invalidation_batches: AtomicUsize::new(0),
+            deferred_executions: Default::default(),
+            slow_task_threshold: Default::default(),
+            cancellation_tokens: Default::default(),
             start: Default::default(),
             aggregated_update: Default::default(),
             event: Event::new(|| "TurboTasks::event".to_string()),
@@ -327,6 +398,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
             event_background: Event::new(|| "TurboTasks::event_background".to_string()),
             enable_full_stats: AtomicBool::new(false),
             program_start: Instant::now(),
+            deterministic_executor,
         });
         this.backend.startup(&*this);
         this
@@ -372,15 +444,17 @@ impl<B: Backend + 'static> TurboTasks<B> {
     ) -> Result<T> {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let task_id = self.spawn_once_task(async move {
-            let result = future.await?;
+            let result = until_cancelled(future).await?;
             tx.send(result)
                 .map_err(|_| anyhow!("unable to send result"))?;
             Ok(CompletionVc::new().into())
         });
+        let guard = CancelTaskOnDrop::new(self, task_id);
         // INVALIDATION: A Once task will never invalidate, therefore we don't need to
         // track a dependency
         let raw_result = read_task_output_untracked(self, task_id, false).await?;
         raw_result.into_read_untracked::<Completion>(self).await?;
+        guard.disarm();
 
         Ok(rx.await?)
     }
@@ -440,14 +514,29 @@ impl<B: Backend + 'static> TurboTasks<B> {
 
     #[track_caller]
     pub(crate) fn schedule(&self, task_id: TaskId) {
+        if self.invalidation_batches.load(Ordering::Acquire) > 0 {
+            let mut deferred_executions = self.deferred_executions.lock().unwrap();
+            if self.invalidation_batches.load(Ordering::Acquire) > 0 {
+                deferred_executions.push(task_id);
+                return;
+            }
+        }
         self.begin_primary_job();
         self.scheduled_tasks.fetch_add(1, Ordering::AcqRel);
 
         #[cfg(feature = "tokio_tracing")]
         let description = self.backend.get_task_description(task_id);
 
+        let priority = self
+            .backend
+            .get_task_priority(task_id)
+            .in_context(CURRENT_PRIORITY.try_with(|priority| *priority).ok());
+        self.pending_executions[priority.index()].fetch_add(1, Ordering::AcqRel);
+        let parent_task = CURRENT_TASK_ID.try_with(|id| *id).ok();
+
         let this = self.pin();
         let future = async move {
+            this.wait_for_priority(priority).await;
             #[allow(clippy::blocks_in_if_conditions)]
             while CURRENT_TASK_STATE
                 .scope(Default::default(), async {
@@ -455,15 +544,40 @@ impl<B: Backend + 'static> TurboTasks<B> {
                         return false;
                     }
 
+                    let cancellation_token =
+                        CURRENT_TASK_STATE.with(|cell| cell.borrow().cancellation_token.clone());
+                    this.cancellation_tokens
+                        .lock()
+                        .unwrap()
+                        .insert(task_id, cancellation_token.clone());
+
                     // Setup thread locals
                     let execution_future = CELL_COUNTERS.scope(Default::default(), async {
                         let execution = this.backend.try_start_task_execution(task_id, &*this)?;
-                        Some(
-                            TimedFuture::new(AssertUnwindSafe(execution.future).catch_unwind())
-                                .await,
-                        )
+                        let future =
+                            TimedFuture::new(AssertUnwindSafe(execution.future).catch_unwind());
+                        let slow_threshold = this
+                            .backend
+                            .get_task_slow_threshold(task_id)
+                            .or(*this.slow_task_threshold.lock().unwrap());
+                        Some(match slow_threshold {
+                            Some(threshold) => {
+                                tokio::pin!(future);
+                                select! {
+                                    result = &mut future => result,
+                                    _ = tokio::time::sleep(threshold) => {
+                                        this.report_slow_task(task_id, threshold);
+                                        future.await
+                                    }
+                                }
+                            }
+                            None => future.await,
+                        })
                     });
-                    if let Some((result, duration, instant)) = execution_future.await {
+                    let execution_result = execution_future.await;
+                    this.cancellation_tokens.lock().unwrap().remove(&task_id);
+                    if let Some((result, duration, instant)) = execution_result {
+                        Span::current().record("duration_us", duration.as_micros() as u64);
                         if cfg!(feature = "log_function_stats") && duration.as_millis() > 1000 {
                             println!(
                                 "{} took {}",
@@ -478,6 +592,9 @@ impl<B: Backend + 'static> TurboTasks<B> {
                                 Err(_) => None,
                             },
                         });
+                        if cancellation_token.is_cancelled() {
+                            this.backend.task_execution_cancelled(task_id, &*this);
+                        }
                         this.backend.task_execution_result(task_id, result, &*this);
                         let stateful = this.finish_current_task_state();
                         let reexecute = this
@@ -491,6 +608,15 @@ impl<B: Backend + 'static> TurboTasks<B> {
                     }
                     true
                 })
+                // Executions scheduled by this execution are spawned in its span, so the spans
+                // follow the parent relationship in the task graph.
+                .instrument(info_span!(
+                    "execution",
+                    name = %this.backend.get_task_description(task_id),
+                    task_id = *task_id,
+                    parent_task_id = parent_task.map(|id| *id),
+                    duration_us = Empty,
+                ))
                 .await
             {}
             this.finish_primary_job();
@@ -500,10 +626,20 @@ impl<B: Backend + 'static> TurboTasks<B> {
         let future = TURBO_TASKS
             .scope(
                 self.pin(),
-                CURRENT_TASK_ID.scope(task_id, self.backend.execution_scope(task_id, future)),
+                CURRENT_TASK_ID.scope(
+                    task_id,
+                    CURRENT_PRIORITY.scope(priority, self.backend.execution_scope(task_id, future)),
+                ),
             )
             .in_current_span();
 
+        if let Some(executor) = &self.deterministic_executor {
+            executor.spawn(async move {
+                let _ = future.await;
+            });
+            return;
+        }
+
         #[cfg(feature = "tokio_tracing")]
         tokio::task::Builder::new()
             .name(&description)
@@ -513,6 +649,36 @@ impl<B: Backend + 'static> TurboTasks<B> {
         tokio::task::spawn(future);
     }
 
+    /// Spawns a future on the tokio runtime, or on the deterministic executor
+    /// when enabled.
+    fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        if let Some(executor) = &self.deterministic_executor {
+            executor.spawn(future);
+        } else {
+            tokio::spawn(future);
+        }
+    }
+
+    /// Waits until no execution with a higher priority is waiting to start
+    /// anymore.
+    async fn wait_for_priority(&self, priority: TaskPriority) {
+        let higher_pending = || {
+            self.pending_executions[priority.index() + 1..]
+                .iter()
+                .any(|count| count.load(Ordering::Acquire) > 0)
+        };
+        while higher_pending() {
+            let listener = self.priority_event.listen();
+            if !higher_pending() {
+                break;
+            }
+            listener.await;
+        }
+        if self.pending_executions[priority.index()].fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.priority_event.notify(usize::MAX);
+        }
+    }
+
     fn begin_primary_job(&self) {
         if self
             .currently_scheduled_tasks
@@ -741,7 +907,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
         let this = self.pin();
         self.currently_scheduled_background_jobs
             .fetch_add(1, Ordering::AcqRel);
-        tokio::spawn(
+        self.spawn(
             TURBO_TASKS
                 .scope(this.clone(), async move {
                     while this.currently_scheduled_tasks.load(Ordering::Acquire) != 0 {
@@ -778,7 +944,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
     ) {
         let this = self.pin();
         this.begin_foreground_job();
-        tokio::spawn(
+        self.spawn(
             TURBO_TASKS
                 .scope(this.clone(), async move {
                     if !this.stopped.load(Ordering::Acquire) {
@@ -795,6 +961,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
             let CurrentTaskState {
                 tasks_to_notify,
                 stateful,
+                ..
             } = &mut *cell.borrow_mut();
             let tasks = take(tasks_to_notify);
             if !tasks.is_empty() {
@@ -808,6 +975,37 @@ impl<B: Backend + 'static> TurboTasks<B> {
     pub fn backend(&self) -> &B {
         &self.backend
     }
+
+    /// Reports executions taking longer than `threshold` as slow tasks, with
+    /// the arguments of the task. Functions can override it with
+    /// `#[turbo_tasks::function(slow_threshold_ms = ...)]`.
+    pub fn set_slow_task_threshold(&self, threshold: Option<Duration>) {
+        *self.slow_task_threshold.lock().unwrap() = threshold;
+    }
+
+    fn report_slow_task(&self, task_id: TaskId, threshold: Duration) {
+        let description = self.backend.get_task_description(task_id);
+        match self.backend.get_task_arguments(task_id) {
+            Some(arguments) => warn!(
+                "{} is still executing after {} with arguments {}",
+                description,
+                FormatDuration(threshold),
+                arguments
+            ),
+            None => warn!(
+                "{} is still executing after {}",
+                description,
+                FormatDuration(threshold)
+            ),
+        }
+    }
+
+    /// Returns statistics about the tasks per function, e.g. to report the
+    /// number of executions and cache hits of a build or to detect excessive
+    /// invalidation.
+    pub fn task_statistics(&self) -> TaskStatistics {
+        self.backend.task_statistics()
+    }
 }
 
 impl<B: Backend + 'static> TurboTasksCallApi for TurboTasks<B> {
@@ -832,7 +1030,7 @@ impl<B: Backend + 'static> TurboTasksCallApi for TurboTasks<B> {
         future: Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
     ) -> TaskId {
         self.spawn_once_task(async move {
-            future.await?;
+            until_cancelled(future).await?;
             Ok(CompletionVc::new().into())
         })
     }
@@ -848,7 +1046,7 @@ impl<B: Backend + 'static> TurboTasksCallApi for TurboTasks<B> {
             reason_set.insert(reason);
         }
         self.spawn_once_task(async move {
-            future.await?;
+            until_cancelled(future).await?;
             Ok(CompletionVc::new().into())
         })
     }
@@ -883,6 +1081,36 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         self.backend.invalidate_task(task, self);
     }
 
+    fn invalidate_batch(
+        &self,
+        tasks: Vec<TaskId>,
+        reasons: Vec<StaticOrArc<dyn InvalidationReason>>,
+    ) {
+        if !reasons.is_empty() {
+            let (_, reason_set) = &mut *self.aggregated_update.lock().unwrap();
+            for reason in reasons {
+                reason_set.insert(reason);
+            }
+        }
+        // Keeps the batch a single update, even when no execution is scheduled until
+        // the end of the batch.
+        self.begin_primary_job();
+        self.invalidation_batches.fetch_add(1, Ordering::AcqRel);
+        self.backend.invalidate_tasks(tasks, self);
+        let deferred_executions = {
+            let mut deferred_executions = self.deferred_executions.lock().unwrap();
+            if self.invalidation_batches.fetch_sub(1, Ordering::AcqRel) == 1 {
+                take(&mut *deferred_executions)
+            } else {
+                Vec::new()
+            }
+        };
+        for task in deferred_executions {
+            self.schedule(task);
+        }
+        self.finish_primary_job();
+    }
+
     fn notify_scheduled_tasks(&self) {
         let _ = CURRENT_TASK_STATE.try_with(|cell| {
             let CurrentTaskState {
@@ -909,6 +1137,14 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         )
     }
 
+    fn get_unfinished_task_count(&self, task: TaskId) -> Option<usize> {
+        self.backend.get_unfinished_task_count(task)
+    }
+
+    fn is_vc_loaded(&self, vc: RawVc) -> bool {
+        self.backend.is_vc_loaded(vc)
+    }
+
     fn try_read_task_output_untracked(
         &self,
         task: TaskId,
@@ -991,6 +1227,10 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         self.backend.update_task_cell(task, index, content, self);
     }
 
+    fn cancel_task(&self, task: TaskId) {
+        self.backend.cancel_task(task, self);
+    }
+
     fn connect_task(&self, task: TaskId) {
         self.backend
             .connect_task(task, current_task("connecting task"), self);
@@ -1000,6 +1240,10 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         self.backend.mark_own_task_as_finished(task, self);
     }
 
+    fn mark_own_task_as_session_dependent(&self, task: TaskId) {
+        self.backend.mark_own_task_as_session_dependent(task, self);
+    }
+
     fn detached(
         &self,
         f: Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
@@ -1110,6 +1354,12 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
         self.schedule(task)
     }
 
+    fn cancel_task_execution(&self, task: TaskId) {
+        if let Some(token) = self.cancellation_tokens.lock().unwrap().get(&task) {
+            token.cancel();
+        }
+    }
+
     fn stats_type(&self) -> StatsType {
         match self.enable_full_stats.load(Ordering::Acquire) {
             true => StatsType::Full,
@@ -1140,7 +1390,7 @@ impl<B: Backend + 'static> TaskIdProvider for TurboTasks<B> {
     }
 }
 
-fn current_task(from: &str) -> TaskId {
+pub(crate) fn current_task(from: &str) -> TaskId {
     match CURRENT_TASK_ID.try_with(|id| *id) {
         Ok(id) => id,
         Err(_) => panic!(
@@ -1212,6 +1462,75 @@ impl Invalidator {
     }
 }
 
+/// Collects [Invalidator]s to invalidate them at once. File watchers use it
+/// to apply bulk changes, e.g. from a `git checkout`, without recomputing
+/// tasks with a partially applied change.
+#[derive(Default)]
+pub struct InvalidationBatch {
+    invalidators: Vec<(Invalidator, Option<StaticOrArc<dyn InvalidationReason>>)>,
+}
+
+impl InvalidationBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invalidators.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.invalidators.len()
+    }
+
+    pub fn add(&mut self, invalidator: Invalidator) {
+        self.invalidators.push((invalidator, None));
+    }
+
+    pub fn add_with_reason<T: InvalidationReason>(&mut self, invalidator: Invalidator, reason: T) {
+        self.invalidators.push((
+            invalidator,
+            Some((Arc::new(reason) as Arc<dyn InvalidationReason>).into()),
+        ));
+    }
+
+    pub fn add_with_static_reason<T: InvalidationReason>(
+        &mut self,
+        invalidator: Invalidator,
+        reason: &'static T,
+    ) {
+        self.invalidators.push((
+            invalidator,
+            Some((reason as &'static dyn InvalidationReason).into()),
+        ));
+    }
+
+    /// Invalidates all collected tasks. Executions of the invalidated tasks
+    /// only start after the whole batch has been applied.
+    pub fn apply(self) {
+        let mut invalidators = self.invalidators;
+        while let Some((first, _)) = invalidators.first() {
+            let turbo_tasks = first.turbo_tasks.clone();
+            let handle = first.handle.clone();
+            let (same, other) =
+                invalidators
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|(invalidator, _)| {
+                        invalidator.turbo_tasks.ptr_eq(&turbo_tasks)
+                    });
+            invalidators = other;
+            let _guard = handle.enter();
+            if let Some(turbo_tasks) = turbo_tasks.upgrade() {
+                let (tasks, reasons) = same
+                    .into_iter()
+                    .map(|(invalidator, reason)| (invalidator.task, reason))
+                    .unzip::<_, _, Vec<_>, Vec<_>>();
+                turbo_tasks.invalidate_batch(tasks, reasons.into_iter().flatten().collect());
+            }
+        }
+    }
+}
+
 impl TraceRawVcs for Invalidator {
     fn trace_raw_vcs(&self, _context: &mut crate::trace::TraceRawVcsContext) {
         // nothing here
@@ -1269,10 +1588,12 @@ pub async fn run_once<T: Send + 'static>(
         Ok(())
     }));
 
+    let guard = CancelTaskOnDrop::new(&*tt, task_id);
     // INVALIDATION: A Once task will never invalidate, therefore we don't need to
     // track a dependency
     let raw_result = read_task_output_untracked(&*tt, task_id, false).await?;
     raw_result.into_read_untracked::<Completion>(&*tt).await?;
+    guard.disarm();
 
     Ok(rx.await?)
 }
@@ -1294,14 +1615,53 @@ pub async fn run_once_with_reason<T: Send + 'static>(
         }),
     );
 
+    let guard = CancelTaskOnDrop::new(&*tt, task_id);
     // INVALIDATION: A Once task will never invalidate, therefore we don't need to
     // track a dependency
     let raw_result = read_task_output_untracked(&*tt, task_id, false).await?;
     raw_result.into_read_untracked::<Completion>(&*tt).await?;
+    guard.disarm();
 
     Ok(rx.await?)
 }
 
+/// Cancels a once task when the future waiting for its result is dropped
+/// before it's done, e.g. when a request has been aborted.
+struct CancelTaskOnDrop<'a> {
+    turbo_tasks: &'a dyn TurboTasksApi,
+    task: Option<TaskId>,
+}
+
+impl<'a> CancelTaskOnDrop<'a> {
+    fn new(turbo_tasks: &'a dyn TurboTasksApi, task: TaskId) -> Self {
+        Self {
+            turbo_tasks,
+            task: Some(task),
+        }
+    }
+
+    fn disarm(mut self) {
+        self.task = None;
+    }
+}
+
+impl Drop for CancelTaskOnDrop<'_> {
+    fn drop(&mut self) {
+        if let Some(task) = self.task {
+            self.turbo_tasks.cancel_task(task);
+        }
+    }
+}
+
+/// Runs the future of a once task until the task is cancelled.
+async fn until_cancelled<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    let token = cancellation_token();
+    select! {
+        result = future => result,
+        _ = token.cancelled() => Err(Cancelled.into()),
+    }
+}
+
 /// see [TurboTasks] `dynamic_call`
 pub fn dynamic_call(func: FunctionId, inputs: Vec<TaskInput>) -> RawVc {
     with_turbo_tasks(|tt| tt.dynamic_call(func, inputs))
@@ -1352,8 +1712,10 @@ pub fn current_task_for_testing() -> TaskId {
 }
 
 /// Get an [Invalidator] that can be used to invalidate the current [Task]
-/// based on external events.
+/// based on external events. Since the task can be invalidated from outside,
+/// it's also marked as session dependent.
 pub fn get_invalidator() -> Invalidator {
+    mark_session_dependent();
     let handle = Handle::current();
     Invalidator {
         task: current_task("turbo_tasks::get_invalidator()"),
@@ -1370,6 +1732,29 @@ pub fn mark_finished() {
     });
 }
 
+/// Marks the current task as session dependent. Its result depends on state
+/// outside of the task graph (e.g. the file system), so a persisted result
+/// can't be trusted in a later session and needs to be recomputed.
+pub fn mark_session_dependent() {
+    with_turbo_tasks(|tt| {
+        tt.mark_own_task_as_session_dependent(current_task("turbo_tasks::mark_session_dependent()"))
+    });
+}
+
+/// Runs `future` with the given [TaskPriority]. Tasks scheduled by it are
+/// executed with that priority, unless their function declares a different
+/// one.
+pub async fn with_priority<T>(priority: TaskPriority, future: impl Future<Output = T>) -> T {
+    CURRENT_PRIORITY.scope(priority, future).await
+}
+
+/// Returns the [CancellationToken] of the current task execution. It's
+/// cancelled when the result of the execution is no longer needed, e.g.
+/// because the request waiting for it has been aborted.
+pub fn cancellation_token() -> CancellationToken {
+    CURRENT_TASK_STATE.with(|cell| cell.borrow().cancellation_token.clone())
+}
+
 /// Marks the current task as stateful. This prevents the tasks from being
 /// dropped without persisting the state.
 pub fn mark_stateful() {
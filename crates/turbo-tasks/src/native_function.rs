@@ -1,16 +1,30 @@
 use std::{
+    any::TypeId,
     fmt::Debug,
     future::Future,
     hash::Hash,
     pin::Pin,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 use anyhow::{Context, Result};
+use dashmap::DashMap;
+use once_cell::sync::OnceCell;
+use tokio::sync::Semaphore;
 
 use crate::{
-    self as turbo_tasks, registry::register_function, task_input::TaskInput, util::SharedError,
-    RawVc,
+    self as turbo_tasks,
+    priority::TaskPriority,
+    registry,
+    registry::register_function,
+    retry::{RetryPolicy, RetryState},
+    task_input::TaskInput,
+    util::SharedError,
+    FunctionId, RawVc,
 };
 
 type NativeTaskFuture = Pin<Box<dyn Future<Output = Result<RawVc>> + Send>>;
@@ -28,6 +42,24 @@ pub struct NativeFunction {
     /// handles the task execution.
     #[turbo_tasks(debug_ignore, trace_ignore)]
     pub bind_fn: BoundNativeTaskFn,
+    /// The scheduling priority declared by the function.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    pub priority: TaskPriority,
+    /// The function is pure and expensive enough to be executed by a
+    /// [RemoteExecutor](crate::remote::RemoteExecutor).
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    pub remote: bool,
+    /// Executions taking longer are reported as slow tasks. Overrides the
+    /// threshold of the [TurboTasks](crate::TurboTasks) instance.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    pub slow_threshold: Option<Duration>,
+    /// Limits the number of executions of the function which run at the
+    /// same time. Executions of other functions are not affected.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    pub concurrency_limit: Option<Arc<Semaphore>>,
+    /// Retries executions which fail with a transient error.
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    retry: Option<RetryState>,
     // TODO move to Task
     /// A counter that tracks total executions of that function
     #[turbo_tasks(debug_ignore, trace_ignore)]
@@ -49,17 +81,72 @@ impl NativeFunction {
         Self {
             name,
             bind_fn,
+            priority: TaskPriority::Normal,
+            remote: false,
+            slow_threshold: None,
+            concurrency_limit: None,
+            retry: None,
             executed_count: AtomicUsize::new(0),
         }
     }
 
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_remote(mut self) -> Self {
+        self.remote = true;
+        self
+    }
+
+    pub fn with_slow_threshold(mut self, slow_threshold: Duration) -> Self {
+        self.slow_threshold = Some(slow_threshold);
+        self
+    }
+
+    /// Allows at most `limit` executions of the function at the same time,
+    /// e. g. to limit the number of spawned processes. An execution which
+    /// waits for another execution of the same function while holding a
+    /// permit can deadlock, so the function must not call itself.
+    pub fn with_concurrency_limit(mut self, limit: usize) -> Self {
+        self.concurrency_limit = Some(Arc::new(Semaphore::new(limit)));
+        self
+    }
+
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(RetryState::new(policy));
+        self
+    }
+
+    pub fn retry_policy(&self) -> Option<RetryPolicy> {
+        self.retry.as_ref().map(|retry| retry.policy)
+    }
+
     /// Creates a functor for execution from a fixed set of inputs.
     pub fn bind(&'static self, inputs: &Vec<TaskInput>) -> NativeTaskFn {
         match (self.bind_fn)(inputs)
             .with_context(|| format!("Error during argument binding of {}", self.name))
         {
             Ok(native_fn) => Box::new(move || {
-                let r = native_fn();
+                let r = match &self.concurrency_limit {
+                    Some(semaphore) => {
+                        let future = native_fn();
+                        Box::pin(async move {
+                            let _permit = semaphore.acquire().await?;
+                            future.await
+                        })
+                    }
+                    None => native_fn(),
+                };
+                let r = match &self.retry {
+                    Some(retry) => Box::pin(async move {
+                        let result = r.await;
+                        retry.handle_result(&result);
+                        result
+                    }),
+                    None => r,
+                };
                 if cfg!(feature = "log_function_stats") {
                     let count = self.executed_count.fetch_add(1, Ordering::Relaxed);
                     if count > 0 && count % 100000 == 0 {
@@ -113,3 +200,65 @@ impl Ord for &'static NativeFunction {
         )
     }
 }
+
+/// A generic `#[turbo_tasks::function]`. Every instantiation is registered as
+/// separate [NativeFunction] on first use, so the type arguments are part of
+/// the identity of its tasks. Tasks of instantiations are not persisted, as
+/// they can't be deserialized before the instantiation is used again.
+pub struct GenericNativeFunction {
+    /// A readable name of the function that is used to reporting purposes.
+    pub name: &'static str,
+    global_name: OnceCell<String>,
+    instances: DashMap<TypeId, FunctionId>,
+}
+
+impl GenericNativeFunction {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            global_name: OnceCell::new(),
+            instances: DashMap::new(),
+        }
+    }
+
+    pub fn register(&'static self, global_name: &str) {
+        let _ = self.global_name.set(global_name.to_string());
+    }
+
+    /// Returns the function id of the instantiation with the type arguments
+    /// `T`, which is a tuple of all type arguments. `create` creates the
+    /// [NativeFunction] of the instantiation from its name.
+    pub fn get_function_id<T: 'static>(
+        &'static self,
+        type_names: &[&str],
+        create: impl FnOnce(String) -> NativeFunction,
+    ) -> FunctionId {
+        let type_id = TypeId::of::<T>();
+        if let Some(id) = self.instances.get(&type_id) {
+            return *id;
+        }
+        *self.instances.entry(type_id).or_insert_with(|| {
+            let type_arguments = type_names.join(", ");
+            let global_name = self
+                .global_name
+                .get()
+                .unwrap_or_else(|| panic!("Use of unregistered generic function {}", self.name));
+            let function: &'static NativeFunction = Box::leak(Box::new(create(format!(
+                "{}<{}>",
+                self.name, type_arguments
+            ))));
+            registry::register_generic_function_instance(
+                &format!("{global_name}<{type_arguments}>"),
+                function,
+            )
+        })
+    }
+}
+
+impl Debug for GenericNativeFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GenericNativeFunction")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,3 +1,19 @@
+//! A [PersistedGraph] stores the task graph beyond a session, so unchanged
+//! tasks don't need to be recomputed after a restart. It's used by an
+//! in-memory backend for all tasks which are not in memory yet.
+//!
+//! An implementation on top of a key-value store needs to take care of:
+//!
+//! * [TaskId]s, which are only valid within a session. They are assigned by
+//!   [PersistedGraphApi::get_or_create_task_type] and have to be mapped to ids
+//!   of the store, e.g. with
+//!   [with_task_id_mapping](crate::with_task_id_mapping) while serializing.
+//!   Tasks can be looked up by [PersistentTaskType::stable_hash].
+//! * Cells, which are serialized with the global name of their value type.
+//!   Values which are not serializable are replaced by
+//!   [TaskCell::NeedComputation] with [TaskData::make_serializable], so reading
+//!   them recomputes the task in a later session.
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
@@ -25,6 +41,20 @@ pub struct TaskData {
     pub cells: Vec<(CellId, TaskCell)>,
     pub output: RawVc,
 }
+
+impl TaskData {
+    /// Replaces the content of cells which can't be serialized with
+    /// [TaskCell::NeedComputation], so the task can be persisted anyway.
+    pub fn make_serializable(&mut self) {
+        for (_, cell) in self.cells.iter_mut() {
+            if let TaskCell::Content(CellContent(Some(content))) = cell {
+                if !content.is_serializable() {
+                    *cell = TaskCell::NeedComputation;
+                }
+            }
+        }
+    }
+}
 pub struct ReadTaskState {
     pub clean: bool,
     pub keeps_external_active: bool,
@@ -32,6 +62,9 @@ pub struct ReadTaskState {
 
 pub struct PersistTaskState {
     pub externally_active: bool,
+    /// The task depends on state outside of the task graph, so it has to be
+    /// recomputed in a later session.
+    pub session_dependent: bool,
 }
 
 /*
@@ -199,7 +232,8 @@ pub trait PersistedGraph: Sync + Send {
 pub trait PersistedGraphApi {
     fn get_or_create_task_type(&self, ty: PersistentTaskType) -> TaskId;
 
-    fn lookup_task_type(&self, id: TaskId) -> &PersistentTaskType;
+    /// Returns the task type of a task, or `None` for transient tasks.
+    fn lookup_task_type(&self, id: TaskId) -> Option<&PersistentTaskType>;
 }
 
 /*
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// A scheduling hint for task executions. When executions of different
+/// priorities are waiting to start, the ones with a higher priority start
+/// first. Lower priority executions wait until no higher priority execution
+/// is waiting to start anymore.
+///
+/// A function can declare its priority with
+/// `#[turbo_tasks::function(priority = "background")]`. The priority can
+/// also be set for a piece of work with [with_priority]:
+/// - [TaskPriority::Normal] functions inherit the priority of the execution
+///   that schedules them.
+/// - [TaskPriority::Interactive] work promotes everything it schedules, as it's
+///   waiting for the results.
+///
+/// [with_priority]: crate::with_priority
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum TaskPriority {
+    /// Work which is not needed right now, e.g. prefetching other entries.
+    Background,
+    #[default]
+    Normal,
+    /// Latency critical work, e.g. an HMR update or the page which is
+    /// currently requested.
+    Interactive,
+}
+
+impl TaskPriority {
+    pub(crate) const COUNT: usize = 3;
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The priority of an execution of a function with this declared priority
+    /// when it's scheduled in a context with the given priority.
+    pub(crate) fn in_context(self, context: Option<TaskPriority>) -> TaskPriority {
+        match (self, context) {
+            (_, Some(TaskPriority::Interactive)) => TaskPriority::Interactive,
+            (TaskPriority::Normal, Some(context)) => context,
+            (declared, _) => declared,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TaskPriority::*;
+
+    #[test]
+    fn test_in_context() {
+        assert_eq!(Normal.in_context(None), Normal);
+        assert_eq!(Normal.in_context(Some(Background)), Background);
+        assert_eq!(Background.in_context(Some(Normal)), Background);
+        assert_eq!(Background.in_context(Some(Interactive)), Interactive);
+        assert_eq!(Interactive.in_context(Some(Background)), Interactive);
+    }
+}
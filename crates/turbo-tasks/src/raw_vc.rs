@@ -7,12 +7,14 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::Poll,
+    time::Duration,
 };
 
 use anyhow::{anyhow, Result};
 use auto_hash_map::AutoSet;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::time::{interval_at, sleep_until, Instant};
 
 use crate::{
     backend::CellContent,
@@ -40,6 +42,33 @@ pub enum ResolveTypeError {
     ReadError { source: anyhow::Error },
 }
 
+/// The progress of a read, reported by [ReadRawVcFuture::with_progress].
+#[derive(Debug, Clone, Copy)]
+pub struct ReadProgress {
+    /// The time since the read has started.
+    pub elapsed: Duration,
+    /// The number of tasks a strongly consistent read is still waiting for,
+    /// if the backend tracks it.
+    pub unfinished_tasks: Option<usize>,
+}
+
+impl Display for ReadProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.elapsed)?;
+        if let Some(unfinished_tasks) = self.unfinished_tasks {
+            write!(f, " with {} unfinished tasks", unfinished_tasks)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("reading {vc} timed out after {progress}")]
+pub struct ReadTimeoutError {
+    pub vc: RawVc,
+    pub progress: ReadProgress,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CellId {
     pub type_id: ValueTypeId,
@@ -209,6 +238,21 @@ impl RawVc {
         }
     }
 
+    /// Returns true when the value of the Vc implements the trait. A Vc isn't
+    /// limited to the trait it has been cast to, so any trait can be queried.
+    pub async fn implements_trait(self, trait_type: TraitTypeId) -> Result<bool, ResolveTypeError> {
+        Ok(self.resolve_trait(trait_type).await?.is_some())
+    }
+
+    /// Casts the Vc to any trait its value implements, or returns `None` when
+    /// the value doesn't implement it.
+    pub async fn try_cast_trait<T: ValueTraitVc>(self) -> Result<Option<T>, ResolveTypeError> {
+        Ok(self
+            .resolve_trait(T::get_trait_type_id())
+            .await?
+            .map(T::from))
+    }
+
     pub async fn resolve_value(
         self,
         value_type: ValueTypeId,
@@ -474,6 +518,59 @@ where
     }
 }
 
+impl<C> ReadRawVcFuture<C>
+where
+    C: Cast,
+{
+    /// Waits at most `timeout` for the read. Fails with a [ReadTimeoutError]
+    /// when the read hasn't finished in time, e. g. because a strongly
+    /// consistent read is still waiting for the graph to settle.
+    pub async fn with_timeout(self, timeout: Duration) -> Result<C::Output> {
+        self.with_progress(Some(timeout), timeout, |_| {}).await
+    }
+
+    /// Calls `on_progress` every `interval` while the read is pending. Fails
+    /// with a [ReadTimeoutError] when a `timeout` is given and the read
+    /// hasn't finished in time.
+    pub async fn with_progress(
+        self,
+        timeout: Option<Duration>,
+        interval: Duration,
+        mut on_progress: impl FnMut(ReadProgress),
+    ) -> Result<C::Output> {
+        let vc = self.current;
+        let turbo_tasks = self.turbo_tasks.clone();
+        let progress = |start: Instant| ReadProgress {
+            elapsed: start.elapsed(),
+            unfinished_tasks: turbo_tasks.get_unfinished_task_count(vc.get_task_id()),
+        };
+        let start = Instant::now();
+        let mut ticks = interval_at(start + interval, interval);
+        let deadline = async {
+            match timeout {
+                Some(timeout) => sleep_until(start + timeout).await,
+                None => futures::future::pending().await,
+            }
+        };
+        let read = self;
+        tokio::pin!(deadline);
+        tokio::pin!(read);
+        loop {
+            tokio::select! {
+                result = &mut read => return result,
+                _ = &mut deadline => {
+                    return Err(ReadTimeoutError {
+                        vc,
+                        progress: progress(start),
+                    }
+                    .into());
+                }
+                _ = ticks.tick() => on_progress(progress(start)),
+            }
+        }
+    }
+}
+
 impl<C> Future for ReadRawVcFuture<C>
 where
     C: Cast,
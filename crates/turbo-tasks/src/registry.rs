@@ -1,6 +1,6 @@
 use std::{fmt::Debug, hash::Hash, ops::Deref};
 
-use dashmap::{mapref::entry::Entry, DashMap};
+use dashmap::{mapref::entry::Entry, DashMap, DashSet};
 use once_cell::sync::Lazy;
 
 use crate::{
@@ -16,12 +16,21 @@ static FUNCTIONS_BY_VALUE: Lazy<DashMap<&'static NativeFunction, FunctionId>> =
     Lazy::new(DashMap::new);
 static FUNCTIONS: Lazy<NoMoveVec<(&'static NativeFunction, String)>> = Lazy::new(NoMoveVec::new);
 
+/// Instantiations of generic functions. They are registered on first use, so
+/// they might not be registered yet when a value referencing them is
+/// deserialized in another process, and can't be persisted.
+static GENERIC_FUNCTION_INSTANCES: Lazy<DashSet<FunctionId>> = Lazy::new(DashSet::new);
+
 static VALUE_TYPE_ID_FACTORY: IdFactory<ValueTypeId> = IdFactory::new();
 static VALUE_TYPES_BY_NAME: Lazy<DashMap<String, ValueTypeId>> = Lazy::new(DashMap::new);
 static VALUE_TYPES_BY_VALUE: Lazy<DashMap<&'static ValueType, ValueTypeId>> =
     Lazy::new(DashMap::new);
 static VALUE_TYPES: Lazy<NoMoveVec<(&'static ValueType, String)>> = Lazy::new(NoMoveVec::new);
 
+/// Instantiations of generic value types, which can't be persisted for the
+/// same reason as [GENERIC_FUNCTION_INSTANCES].
+static GENERIC_VALUE_TYPE_INSTANCES: Lazy<DashSet<ValueTypeId>> = Lazy::new(DashSet::new);
+
 static TRAIT_TYPE_ID_FACTORY: IdFactory<TraitTypeId> = IdFactory::new();
 static TRAIT_TYPES_BY_NAME: Lazy<DashMap<String, TraitTypeId>> = Lazy::new(DashMap::new);
 static TRAIT_TYPES_BY_VALUE: Lazy<DashMap<&'static TraitType, TraitTypeId>> =
@@ -76,6 +85,22 @@ pub fn register_function(global_name: &str, func: &'static NativeFunction) {
     )
 }
 
+/// Registers an instantiation of a generic function, see
+/// [GenericNativeFunction](crate::GenericNativeFunction).
+pub fn register_generic_function_instance(
+    global_name: &str,
+    func: &'static NativeFunction,
+) -> FunctionId {
+    register_function(global_name, func);
+    let id = get_function_id(func);
+    GENERIC_FUNCTION_INSTANCES.insert(id);
+    id
+}
+
+pub fn is_generic_function_instance(id: FunctionId) -> bool {
+    GENERIC_FUNCTION_INSTANCES.contains(&id)
+}
+
 pub fn get_function_id(func: &'static NativeFunction) -> FunctionId {
     get_thing_id(func, &FUNCTIONS_BY_VALUE)
 }
@@ -103,6 +128,22 @@ pub fn register_value_type(global_name: &str, ty: &'static ValueType) {
     )
 }
 
+/// Registers an instantiation of a generic value type, see
+/// [GenericValueType](crate::GenericValueType).
+pub fn register_generic_value_type_instance(
+    global_name: &str,
+    ty: &'static ValueType,
+) -> ValueTypeId {
+    register_value_type(global_name, ty);
+    let id = get_value_type_id(ty);
+    GENERIC_VALUE_TYPE_INSTANCES.insert(id);
+    id
+}
+
+pub fn is_generic_value_type_instance(id: ValueTypeId) -> bool {
+    GENERIC_VALUE_TYPE_INSTANCES.contains(&id)
+}
+
 pub fn get_value_type_id(func: &'static ValueType) -> ValueTypeId {
     get_thing_id(func, &VALUE_TYPES_BY_VALUE)
 }
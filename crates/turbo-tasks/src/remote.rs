@@ -0,0 +1,152 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use anyhow::{bail, Result};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    macro_helpers::find_cell_by_type, manager::read_task_cell, registry, turbo_tasks, FunctionId,
+    RawVc, SharedReference, TaskInput,
+};
+
+/// A task execution which is sent to a remote worker. Inputs which point to
+/// cells are replaced by the content of these cells, so the task can be
+/// executed without access to the local task graph.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RemoteTask {
+    /// The global name of the function, see
+    /// [registry::get_function_global_name].
+    pub function: String,
+    pub inputs: Vec<RemoteTaskInput>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum RemoteTaskInput {
+    /// An input which doesn't reference the local task graph.
+    Value(TaskInput),
+    /// The content of a cell that was passed as input. Vcs inside of the
+    /// value can't be read by a remote worker.
+    Cell(SharedReference),
+}
+
+pub type RemoteExecutionFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<SharedReference>>> + Send + 'a>>;
+
+/// Executes tasks of functions that are marked with
+/// `#[turbo_tasks::function(remote)]` on remote workers. These functions have
+/// to be pure and their output must not contain Vcs.
+pub trait RemoteExecutor: Send + Sync {
+    /// Executes the task and returns the content of its output cell.
+    ///
+    /// Returns `Ok(None)` when the task can't be executed remotely (e. g. no
+    /// worker is available or the inputs are not serializable). The task is
+    /// executed locally in that case. Errors are reported as errors of the
+    /// task.
+    fn execute<'a>(&'a self, task: &'a RemoteTask) -> RemoteExecutionFuture<'a>;
+}
+
+/// Caches the results of a [RemoteExecutor] by the serialized task, so tasks
+/// with equal inputs are only sent once. Cached results also keep the output
+/// cell unchanged when the task is re-executed with equal inputs.
+pub struct CachedRemoteExecutor<E: RemoteExecutor> {
+    executor: E,
+    cache: DashMap<String, SharedReference>,
+}
+
+impl<E: RemoteExecutor> CachedRemoteExecutor<E> {
+    pub fn new(executor: E) -> Self {
+        Self {
+            executor,
+            cache: DashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.cache.clear()
+    }
+}
+
+impl<E: RemoteExecutor> RemoteExecutor for CachedRemoteExecutor<E> {
+    fn execute<'a>(&'a self, task: &'a RemoteTask) -> RemoteExecutionFuture<'a> {
+        Box::pin(async move {
+            let Ok(key) = serde_json::to_string(task) else {
+                return Ok(None);
+            };
+            if let Some(output) = self.cache.get(&key) {
+                return Ok(Some(output.clone()));
+            }
+            let output = self.executor.execute(task).await?;
+            if let Some(output) = &output {
+                self.cache.insert(key, output.clone());
+            }
+            Ok(output)
+        })
+    }
+}
+
+/// Returns true when the input can be sent to a remote worker as it is.
+fn is_transferable(input: &TaskInput) -> bool {
+    match input {
+        TaskInput::TaskOutput(_) | TaskInput::TaskCell(..) | TaskInput::TransientSharedValue(_) => {
+            false
+        }
+        TaskInput::List(list) => list.iter().all(is_transferable),
+        _ => true,
+    }
+}
+
+async fn to_remote_input(input: &TaskInput) -> Result<Option<RemoteTaskInput>> {
+    Ok(match input {
+        &TaskInput::TaskCell(task, index) => {
+            let content = read_task_cell(&*turbo_tasks(), task, index).await?;
+            match content.0 {
+                Some(content @ SharedReference(Some(_), _)) => Some(RemoteTaskInput::Cell(content)),
+                _ => None,
+            }
+        }
+        input if is_transferable(input) => Some(RemoteTaskInput::Value(input.clone())),
+        _ => None,
+    })
+}
+
+/// Executes a native function with the `executor`. Falls back to a local
+/// execution when the task can't be executed remotely.
+pub async fn run_remote_native(
+    executor: Arc<dyn RemoteExecutor>,
+    fn_id: FunctionId,
+    inputs: Vec<TaskInput>,
+) -> Result<RawVc> {
+    let mut remote_inputs = Vec::with_capacity(inputs.len());
+    for input in inputs.iter() {
+        match to_remote_input(input).await? {
+            Some(input) => remote_inputs.push(input),
+            None => return registry::get_function(fn_id).bind(&inputs)().await,
+        }
+    }
+    let task = RemoteTask {
+        function: registry::get_function_global_name(fn_id).to_string(),
+        inputs: remote_inputs,
+    };
+    match executor.execute(&task).await? {
+        Some(output) => {
+            let Some(type_id) = output.0 else {
+                bail!(
+                    "remote execution of {} returned an untyped value",
+                    task.function
+                );
+            };
+            let cell = find_cell_by_type(type_id);
+            cell.update_shared_reference(output);
+            Ok(cell.into())
+        }
+        None => registry::get_function(fn_id).bind(&inputs)().await,
+    }
+}
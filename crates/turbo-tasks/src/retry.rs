@@ -0,0 +1,182 @@
+use std::{
+    error::Error as StdError,
+    fmt::{self, Debug, Display},
+    time::Duration,
+};
+
+use anyhow::{Error, Result};
+use dashmap::DashMap;
+use nohash_hasher::BuildNoHashHasher;
+
+use crate::{get_invalidator, manager::current_task, util::SharedError, TaskId};
+
+/// An error which is expected to go away when the task is executed again,
+/// e.g. a timeout or a file which is temporarily locked. Created with
+/// [transient_error].
+pub struct TransientError {
+    inner: Error,
+}
+
+impl Debug for TransientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+impl Display for TransientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
+impl StdError for TransientError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.inner.source()
+    }
+}
+
+/// Marks the error as transient. Functions with a [RetryPolicy] are executed
+/// again when they fail with a transient error.
+pub fn transient_error(error: impl Into<Error>) -> Error {
+    Error::new(TransientError {
+        inner: error.into(),
+    })
+}
+
+/// Returns true when the error has been marked as transient by the task
+/// which returned it. Errors of other tasks which have been read by the task
+/// are not considered, as they are retried by their own task.
+pub fn is_transient_error(error: &Error) -> bool {
+    for error in error.chain() {
+        if error.is::<TransientError>() {
+            return true;
+        }
+        if error.is::<SharedError>() {
+            return false;
+        }
+    }
+    false
+}
+
+/// Executes a task again after an exponential backoff when it fails with a
+/// [transient error](transient_error), e.g. with
+/// `#[turbo_tasks::function(retry = 3)]`.
+///
+/// Until then, the error is returned to readers of the task. The attempts
+/// are counted per task and reset when the task succeeds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of retries after the first failed execution.
+    pub max_retries: u32,
+    /// The backoff before the first retry. It's doubled for every further
+    /// retry.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// The backoff before the `retry`th retry, starting with 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << retry.saturating_sub(1).min(31))
+            .min(self.max_backoff)
+    }
+}
+
+/// The [RetryPolicy] of a function with the retries of its tasks.
+pub(crate) struct RetryState {
+    pub(crate) policy: RetryPolicy,
+    retries: DashMap<TaskId, u32, BuildNoHashHasher<TaskId>>,
+}
+
+impl RetryState {
+    pub(crate) fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            retries: DashMap::default(),
+        }
+    }
+
+    /// Schedules a retry of the current task when the result is a transient
+    /// error. It has to be called in the context of the task execution.
+    pub(crate) fn handle_result<T>(&self, result: &Result<T>) {
+        let task = current_task("retry");
+        match result {
+            Err(error) if is_transient_error(error) => {
+                let retry = {
+                    let mut retries = self.retries.entry(task).or_default();
+                    *retries += 1;
+                    *retries
+                };
+                if retry > self.policy.max_retries {
+                    // Give up until the task is invalidated by something else.
+                    self.retries.remove(&task);
+                    return;
+                }
+                let backoff = self.policy.backoff(retry);
+                let invalidator = get_invalidator();
+                tokio::spawn(async move {
+                    tokio::time::sleep(backoff).await;
+                    invalidator.invalidate();
+                });
+            }
+            _ => {
+                self.retries.remove(&task);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::anyhow;
+
+    use super::{is_transient_error, transient_error, RetryPolicy};
+    use crate::util::SharedError;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(100), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_is_transient_error() {
+        let error = transient_error(anyhow!("busy")).context("reading file");
+        assert!(is_transient_error(&error));
+        assert_eq!(format!("{:#}", error), "reading file: busy");
+        assert!(!is_transient_error(&anyhow!("not found")));
+        let read_error = anyhow::Error::new(SharedError::new(transient_error(anyhow!("busy"))));
+        assert!(!is_transient_error(&read_error));
+    }
+}
@@ -8,7 +8,7 @@ use auto_hash_map::AutoSet;
 use parking_lot::{Mutex, MutexGuard};
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{get_invalidator, mark_stateful, trace::TraceRawVcs, Invalidator};
+use crate::{get_invalidator, mark_stateful, trace::TraceRawVcs, InvalidationBatch, Invalidator};
 
 pub struct State<T> {
     inner: Mutex<StateInner<T>>,
@@ -174,3 +174,137 @@ impl<'a, T> Drop for StateRef<'a, T> {
         }
     }
 }
+
+/// Updates multiple [State]s at once. The new values are written while all
+/// states are locked, and the dependent tasks are only invalidated after all
+/// of them have been written. So no task can observe a part of the updates
+/// without being invalidated by the rest of them.
+///
+/// ```ignore
+/// let mut transaction = StateTransaction::new();
+/// transaction.set(&self.start, start);
+/// transaction.set(&self.end, end);
+/// transaction.commit();
+/// ```
+#[derive(Default)]
+pub struct StateTransaction<'a> {
+    updates: Vec<Box<dyn StagedUpdate<'a> + Send + 'a>>,
+}
+
+impl<'a> StateTransaction<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    /// Sets the state when the `value` is different from the current value.
+    pub fn set<T: PartialEq + Send + 'a>(&mut self, state: &'a State<T>, value: T) {
+        self.update_conditionally(state, move |current| {
+            if *current == value {
+                return false;
+            }
+            *current = value;
+            true
+        });
+    }
+
+    /// Sets the state without comparing it with the old value.
+    pub fn set_unconditionally<T: Send + 'a>(&mut self, state: &'a State<T>, value: T) {
+        self.update_conditionally(state, move |current| {
+            *current = value;
+            true
+        });
+    }
+
+    /// Updates the state with the `update` function, see
+    /// [State::update_conditionally].
+    ///
+    /// Panics on commit when a state is updated more than once in the same
+    /// transaction.
+    pub fn update_conditionally<T: Send + 'a>(
+        &mut self,
+        state: &'a State<T>,
+        update: impl FnOnce(&mut T) -> bool + Send + 'a,
+    ) {
+        self.updates.push(Box::new(StateUpdate { state, update }));
+    }
+
+    /// Applies all updates and invalidates the tasks depending on the
+    /// modified states.
+    pub fn commit(self) {
+        let mut updates = self.updates;
+        // Locking in a consistent order avoids deadlocks with concurrent
+        // transactions.
+        updates.sort_by_key(|update| update.state_address());
+        for pair in updates.windows(2) {
+            assert!(
+                pair[0].state_address() != pair[1].state_address(),
+                "a State can only be updated once per transaction"
+            );
+        }
+        let mut locked = updates
+            .into_iter()
+            .map(|update| update.lock())
+            .collect::<Vec<_>>();
+        let mut batch = InvalidationBatch::new();
+        for update in locked.iter_mut() {
+            update.apply(&mut batch);
+        }
+        // Invalidate the dependent tasks only after all locks are released, as
+        // they might read the states again.
+        drop(locked);
+        batch.apply();
+    }
+}
+
+trait StagedUpdate<'a> {
+    fn state_address(&self) -> usize;
+    fn lock(self: Box<Self>) -> Box<dyn LockedUpdate + 'a>;
+}
+
+trait LockedUpdate {
+    /// Applies the update. The lock is held until the value is dropped.
+    fn apply(&mut self, batch: &mut InvalidationBatch);
+}
+
+struct StateUpdate<'a, T, F> {
+    state: &'a State<T>,
+    update: F,
+}
+
+impl<'a, T: 'a, F: FnOnce(&mut T) -> bool + 'a> StagedUpdate<'a> for StateUpdate<'a, T, F> {
+    fn state_address(&self) -> usize {
+        self.state as *const State<T> as usize
+    }
+
+    fn lock(self: Box<Self>) -> Box<dyn LockedUpdate + 'a> {
+        Box::new(LockedStateUpdate {
+            inner: self.state.inner.lock(),
+            update: Some(self.update),
+        })
+    }
+}
+
+struct LockedStateUpdate<'a, T, F> {
+    inner: MutexGuard<'a, StateInner<T>>,
+    update: Option<F>,
+}
+
+impl<'a, T, F: FnOnce(&mut T) -> bool> LockedUpdate for LockedStateUpdate<'a, T, F> {
+    fn apply(&mut self, batch: &mut InvalidationBatch) {
+        if let Some(update) = self.update.take() {
+            if update(&mut self.inner.value) {
+                for invalidator in take(&mut self.inner.invalidators) {
+                    batch.add(invalidator);
+                }
+            }
+        }
+    }
+}
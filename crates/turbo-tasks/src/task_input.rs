@@ -19,7 +19,7 @@ use crate::{
     registry, turbo_tasks,
     value::{TransientInstance, TransientValue, Value},
     value_type::TypedForInput,
-    CellId, RawVc, TaskId, TraitType, Typed, ValueTypeId,
+    CellId, InternedString, RawVc, TaskId, TraitType, Typed, ValueTypeId,
 };
 
 #[derive(Clone)]
@@ -32,6 +32,20 @@ impl SharedReference {
             Err(_) => None,
         }
     }
+
+    /// Returns true when the value can be serialized, e.g. to be stored by a
+    /// [PersistedGraph](crate::persisted_graph::PersistedGraph).
+    pub fn is_serializable(&self) -> bool {
+        match self.0 {
+            Some(ty) => {
+                !registry::is_generic_value_type_instance(ty)
+                    && registry::get_value_type(ty)
+                        .any_as_serializable(&self.1)
+                        .is_some()
+            }
+            None => false,
+        }
+    }
 }
 
 impl Hash for SharedReference {
@@ -82,7 +96,7 @@ impl Serialize for SharedReference {
             let value_type = registry::get_value_type(*ty);
             if let Some(serializable) = value_type.any_as_serializable(arc) {
                 let mut t = serializer.serialize_tuple(2)?;
-                t.serialize_element(registry::get_value_type_global_name(*ty))?;
+                t.serialize_element(ty)?;
                 t.serialize_element(serializable)?;
                 t.end()
             } else {
@@ -252,7 +266,7 @@ impl Serialize for SharedValue {
             let value_type = registry::get_value_type(*ty);
             if let Some(serializable) = value_type.magic_as_serializable(arc) {
                 let mut t = serializer.serialize_tuple(2)?;
-                t.serialize_element(registry::get_value_type_global_name(*ty))?;
+                t.serialize_element(ty)?;
                 t.serialize_element(serializable)?;
                 t.end()
             } else {
@@ -327,7 +341,7 @@ pub enum TaskInput {
     TaskOutput(TaskId),
     TaskCell(TaskId, CellId),
     List(Vec<TaskInput>),
-    String(String),
+    String(InternedString),
     Bool(bool),
     Usize(usize),
     I8(i8),
@@ -483,6 +497,24 @@ where
     fn try_from(value: &'a TaskInput) -> Result<Self, Self::Error>;
 }
 
+/// A type argument of a generic `#[turbo_tasks::function]`. Values of it are
+/// stored in and restored from the task inputs. It's implemented for all Vc
+/// types.
+pub trait GenericTaskInput:
+    Into<TaskInput> + for<'a> FromTaskInput<'a, Error = anyhow::Error> + Clone + Send + Sync + 'static
+{
+}
+
+impl<T> GenericTaskInput for T where
+    T: Into<TaskInput>
+        + for<'a> FromTaskInput<'a, Error = anyhow::Error>
+        + Clone
+        + Send
+        + Sync
+        + 'static
+{
+}
+
 impl From<RawVc> for TaskInput {
     fn from(raw_vc: RawVc) -> Self {
         match raw_vc {
@@ -536,13 +568,19 @@ impl Display for TaskInput {
 
 impl From<String> for TaskInput {
     fn from(s: String) -> Self {
-        TaskInput::String(s)
+        TaskInput::String(s.into())
     }
 }
 
 impl From<&str> for TaskInput {
     fn from(s: &str) -> Self {
-        TaskInput::String(s.to_string())
+        TaskInput::String(s.into())
+    }
+}
+
+impl From<InternedString> for TaskInput {
+    fn from(s: InternedString) -> Self {
+        TaskInput::String(s)
     }
 }
 
@@ -674,6 +712,17 @@ impl FromTaskInput<'_> for String {
     }
 }
 
+impl FromTaskInput<'_> for InternedString {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &TaskInput) -> Result<Self, Self::Error> {
+        match value {
+            TaskInput::String(str) => Ok(str.clone()),
+            _ => Err(anyhow!("invalid task input type, expected string")),
+        }
+    }
+}
+
 impl<'a> FromTaskInput<'a> for &'a str {
     type Error = anyhow::Error;
 
@@ -0,0 +1,261 @@
+use std::{
+    collections::HashMap,
+    ops::Add,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use dashmap::DashMap;
+use nohash_hasher::BuildNoHashHasher;
+use serde::Serialize;
+
+use crate::{registry, FunctionId};
+
+/// A snapshot of the statistics of all tasks, see
+/// [TurboTasks::task_statistics].
+///
+/// [TurboTasks::task_statistics]: crate::TurboTasks::task_statistics
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskStatistics {
+    /// The statistics per function, keyed by the function name.
+    pub functions: HashMap<&'static str, FunctionStatistics>,
+    /// The number of tasks which have been invalidated and haven't been
+    /// recomputed yet.
+    pub dirty_tasks: usize,
+    /// The statistics of all functions summed up. `functions` is empty when
+    /// only these totals have been collected, see
+    /// [TaskStatisticsCollector::counters_only].
+    pub total: FunctionStatistics,
+    /// The estimated memory retained by the tasks, when the backend collects
+    /// it. It's expensive to compute, so backends usually only collect it
+    /// behind a feature flag.
+    pub memory: Option<MemoryStatistics>,
+}
+
+/// The estimated memory retained by tasks and their cells.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct MemoryStatistics {
+    /// The tasks per function with their cells, keyed by the function name.
+    pub functions: HashMap<&'static str, RetainedMemory>,
+    /// The cells per value type, keyed by the value type name.
+    pub value_types: HashMap<&'static str, RetainedMemory>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RetainedMemory {
+    /// The number of tasks or cells.
+    pub count: usize,
+    /// The estimated number of bytes, see [ValueType::estimate_size].
+    ///
+    /// [ValueType::estimate_size]: crate::ValueType::estimate_size
+    pub bytes: usize,
+}
+
+impl Add for RetainedMemory {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            count: self.count + other.count,
+            bytes: self.bytes + other.bytes,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FunctionStatistics {
+    /// The number of tasks created for calls of the function.
+    pub tasks: usize,
+    /// The number of calls of the function which reused an existing task.
+    pub cache_hits: usize,
+    /// The number of executions of tasks of the function, including
+    /// re-executions after invalidation.
+    pub executions: usize,
+    /// The time spent in all executions.
+    pub total_duration: Duration,
+}
+
+impl Add for FunctionStatistics {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            tasks: self.tasks + other.tasks,
+            cache_hits: self.cache_hits + other.cache_hits,
+            executions: self.executions + other.executions,
+            total_duration: self.total_duration + other.total_duration,
+        }
+    }
+}
+
+impl FunctionStatistics {
+    /// The share of calls which reused an existing task, between 0 and 1.
+    pub fn cache_hit_ratio(&self) -> f64 {
+        let calls = self.tasks + self.cache_hits;
+        if calls == 0 {
+            0.0
+        } else {
+            self.cache_hits as f64 / calls as f64
+        }
+    }
+
+    /// The average time spent in an execution.
+    pub fn average_duration(&self) -> Duration {
+        if self.executions == 0 {
+            Duration::ZERO
+        } else {
+            self.total_duration / self.executions as u32
+        }
+    }
+}
+
+#[derive(Default)]
+struct FunctionCounters {
+    tasks: AtomicUsize,
+    cache_hits: AtomicUsize,
+    executions: AtomicUsize,
+    total_duration_nanos: AtomicU64,
+}
+
+/// Collects [TaskStatistics] for a backend.
+#[derive(Default)]
+pub struct TaskStatisticsCollector {
+    /// Only `totals` are collected when set.
+    counters_only: bool,
+    totals: FunctionCounters,
+    functions: DashMap<FunctionId, FunctionCounters, BuildNoHashHasher<FunctionId>>,
+}
+
+impl TaskStatisticsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a collector which only counts tasks, cache hits and executions
+    /// over all functions. It doesn't track time or keep per function
+    /// counters, so it's cheap enough to be enabled in production builds.
+    pub fn counters_only() -> Self {
+        Self {
+            counters_only: true,
+            ..Default::default()
+        }
+    }
+
+    fn with_counters(&self, function_id: FunctionId, func: impl FnOnce(&FunctionCounters)) {
+        if self.counters_only {
+            func(&self.totals);
+        } else if let Some(counters) = self.functions.get(&function_id) {
+            func(&counters);
+        } else {
+            func(&self.functions.entry(function_id).or_default());
+        }
+    }
+
+    pub fn increment_tasks(&self, function_id: FunctionId) {
+        self.with_counters(function_id, |counters| {
+            counters.tasks.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    pub fn increment_cache_hits(&self, function_id: FunctionId) {
+        self.with_counters(function_id, |counters| {
+            counters.cache_hits.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    pub fn register_execution(&self, function_id: FunctionId, duration: Duration) {
+        self.with_counters(function_id, |counters| {
+            counters.executions.fetch_add(1, Ordering::Relaxed);
+            if !self.counters_only {
+                counters
+                    .total_duration_nanos
+                    .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Takes a snapshot of the collected statistics.
+    pub fn snapshot(&self, dirty_tasks: usize) -> TaskStatistics {
+        let mut functions: HashMap<&'static str, FunctionStatistics> = HashMap::new();
+        let mut total = self.totals.snapshot();
+        for entry in self.functions.iter() {
+            let stats = entry.value().snapshot();
+            total = total + stats;
+            // Different functions might share the same name.
+            let function_total = functions
+                .entry(&registry::get_function(*entry.key()).name)
+                .or_default();
+            *function_total = *function_total + stats;
+        }
+        TaskStatistics {
+            functions,
+            dirty_tasks,
+            total,
+            memory: None,
+        }
+    }
+}
+
+impl FunctionCounters {
+    fn snapshot(&self) -> FunctionStatistics {
+        FunctionStatistics {
+            tasks: self.tasks.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            executions: self.executions.load(Ordering::Relaxed),
+            total_duration: Duration::from_nanos(self.total_duration_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{FunctionStatistics, TaskStatisticsCollector};
+    use crate::FunctionId;
+
+    #[test]
+    fn test_average_duration() {
+        let stats = FunctionStatistics {
+            executions: 4,
+            total_duration: Duration::from_millis(10),
+            ..Default::default()
+        };
+        assert_eq!(stats.average_duration(), Duration::from_micros(2500));
+        assert_eq!(
+            FunctionStatistics::default().average_duration(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_cache_hit_ratio() {
+        let stats = FunctionStatistics {
+            tasks: 1,
+            cache_hits: 3,
+            ..Default::default()
+        };
+        assert_eq!(stats.cache_hit_ratio(), 0.75);
+        assert_eq!(FunctionStatistics::default().cache_hit_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_counters_only() {
+        let collector = TaskStatisticsCollector::counters_only();
+        let function_id = FunctionId::from(1);
+        collector.increment_tasks(function_id);
+        collector.increment_cache_hits(function_id);
+        collector.register_execution(function_id, Duration::from_millis(10));
+        let stats = collector.snapshot(0);
+        assert!(stats.functions.is_empty());
+        assert_eq!(
+            stats.total,
+            FunctionStatistics {
+                tasks: 1,
+                cache_hits: 1,
+                executions: 1,
+                total_duration: Duration::ZERO,
+            }
+        );
+    }
+}
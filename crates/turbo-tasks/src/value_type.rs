@@ -1,19 +1,22 @@
 use std::{
-    any::{type_name, Any},
+    any::{type_name, Any, TypeId},
     borrow::Cow,
     fmt::{self, Debug, Display, Formatter},
     hash::Hash,
+    io,
     sync::Arc,
 };
 
 use auto_hash_map::{AutoMap, AutoSet};
+use dashmap::DashMap;
 use nohash_hasher::BuildNoHashHasher;
 use serde::{Deserialize, Serialize};
+use turbo_tasks_hash::{DeterministicHasher, Xxh3Hash64Hasher};
 
 use crate::{
     id::{FunctionId, TraitTypeId},
     magic_any::{AnyDeserializeSeed, MagicAny, MagicAnyDeserializeSeed},
-    registry::{register_trait_type, register_value_type},
+    registry::{self, register_trait_type, register_value_type},
     CollectiblesSource, RawVc, ValueTypeId,
 };
 
@@ -115,6 +118,9 @@ pub struct ValueType {
     /// List of trait methods available
     pub trait_methods: AutoMap<(TraitTypeId, Cow<'static, str>), FunctionId>,
 
+    /// The size of the value itself, without any heap allocations it owns.
+    size: usize,
+
     /// Functors for serialization
     magic_serialization: Option<(MagicSerializationFn, MagicAnyDeserializeSeed)>,
     any_serialization: Option<(AnySerializationFn, AnyDeserializeSeed)>,
@@ -168,6 +174,33 @@ pub fn any_as_serialize<T: Any + Serialize + Send + Sync + 'static>(
     );
 }
 
+/// Counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct HashWriter(Xxh3Hash64Hasher);
+
+impl io::Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_bytes(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 impl ValueType {
     /// This is internally used by `#[turbo_tasks::value]`
     pub fn new<T>() -> Self {
@@ -175,6 +208,7 @@ impl ValueType {
             name: std::any::type_name::<T>().to_string(),
             traits: AutoSet::default(),
             trait_methods: AutoMap::new(),
+            size: std::mem::size_of::<T>(),
             magic_serialization: None,
             any_serialization: None,
         }
@@ -188,6 +222,7 @@ impl ValueType {
             name: std::any::type_name::<T>().to_string(),
             traits: AutoSet::default(),
             trait_methods: AutoMap::new(),
+            size: std::mem::size_of::<T>(),
             magic_serialization: Some((
                 <dyn MagicAny>::as_serialize::<T>,
                 MagicAnyDeserializeSeed::new::<T>(),
@@ -204,11 +239,35 @@ impl ValueType {
             name: std::any::type_name::<T>().to_string(),
             traits: AutoSet::default(),
             trait_methods: AutoMap::new(),
+            size: std::mem::size_of::<T>(),
             magic_serialization: None,
             any_serialization: Some((any_as_serialize::<T>, AnyDeserializeSeed::new::<T>())),
         }
     }
 
+    /// Estimates the memory retained by a value of this type. Heap
+    /// allocations owned by the value are approximated by the length of its
+    /// JSON serialization, so they are only counted for serializable types.
+    pub fn estimate_size(&self, value: &(dyn Any + Send + Sync)) -> usize {
+        let mut size = self.size;
+        if let Some((as_serialize, _)) = self.any_serialization {
+            let mut counter = ByteCounter(0);
+            if serde_json::to_writer(&mut counter, as_serialize(value)).is_ok() {
+                size += counter.0;
+            }
+        }
+        size
+    }
+
+    /// Hashes the JSON serialization of a value of this type. Returns `None`
+    /// when the type isn't serializable.
+    pub fn hash_content(&self, value: &(dyn Any + Send + Sync)) -> Option<u64> {
+        let (as_serialize, _) = self.any_serialization?;
+        let mut writer = HashWriter(Xxh3Hash64Hasher::new());
+        serde_json::to_writer(&mut writer, as_serialize(value)).ok()?;
+        Some(writer.0.finish())
+    }
+
     pub fn magic_as_serializable<'a>(
         &self,
         arc: &'a Arc<dyn MagicAny>,
@@ -275,6 +334,34 @@ impl ValueType {
     }
 }
 
+/// A generic `#[turbo_tasks::value]`. Every instantiation is registered as
+/// separate [ValueType] on first use, with its type name as global name.
+/// Values of instantiations are not persisted, as they can't be deserialized
+/// before the instantiation is used again.
+#[derive(Default)]
+pub struct GenericValueType {
+    instances: DashMap<TypeId, ValueTypeId>,
+}
+
+impl GenericValueType {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value type id of the instantiation `T`. `create` creates
+    /// the [ValueType] when `T` is used for the first time.
+    pub fn get_value_type_id<T: 'static>(&self, create: impl FnOnce() -> ValueType) -> ValueTypeId {
+        let type_id = TypeId::of::<T>();
+        if let Some(id) = self.instances.get(&type_id) {
+            return *id;
+        }
+        *self.instances.entry(type_id).or_insert_with(|| {
+            let value_type: &'static ValueType = Box::leak(Box::new(create()));
+            registry::register_generic_value_type_instance(type_name::<T>(), value_type)
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct TraitType {
     pub name: String,
@@ -0,0 +1,90 @@
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    debug::{ValueDebugFormat, ValueDebugFormatString},
+    manager::with_turbo_tasks,
+    trace::{TraceRawVcs, TraceRawVcsContext},
+    RawVc,
+};
+
+/// A reference to a Vc (`XxxVc`) which doesn't keep the referenced task output
+/// or cell alive. The garbage collection can unload it while it's still
+/// referenced, e. g. by a cache or a registry of all assets seen so far.
+///
+/// Use [WeakVc::upgrade] to get the Vc back, as long as it's still loaded.
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WeakVc<T> {
+    node: RawVc,
+    #[serde(skip)]
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Into<RawVc>> WeakVc<T> {
+    pub fn new(vc: T) -> Self {
+        Self {
+            node: vc.into(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: From<RawVc>> WeakVc<T> {
+    /// Returns the Vc when the task output or cell it references is still
+    /// loaded, or `None` when it has been unloaded. Reading an unloaded Vc
+    /// would recompute it.
+    pub fn upgrade(&self) -> Option<T> {
+        with_turbo_tasks(|tt| tt.is_vc_loaded(self.node)).then(|| self.node.into())
+    }
+
+    /// Returns the Vc regardless of whether it's still loaded.
+    pub fn upgrade_or_recompute(&self) -> T {
+        self.node.into()
+    }
+}
+
+impl<T> Clone for WeakVc<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for WeakVc<T> {}
+
+impl<T> PartialEq for WeakVc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl<T> Eq for WeakVc<T> {}
+
+impl<T> Hash for WeakVc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.node.hash(state);
+    }
+}
+
+impl<T> Debug for WeakVc<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WeakVc").field(&self.node).finish()
+    }
+}
+
+impl<T> ValueDebugFormat for WeakVc<T> {
+    fn value_debug_format(&self, _depth: usize) -> ValueDebugFormatString {
+        ValueDebugFormatString::Sync(format!("{:?}", self))
+    }
+}
+
+/// The referenced Vc is not reported, so it's not kept alive by the value
+/// containing the [WeakVc].
+impl<T> TraceRawVcs for WeakVc<T> {
+    fn trace_raw_vcs(&self, _context: &mut TraceRawVcsContext) {}
+}
@@ -10,15 +10,19 @@ use turbopack_core::{
     asset::{Asset, AssetVc, AssetsVc},
     chunk::{
         Chunk, ChunkVc, ChunkableModule, ChunkingContext, ChunkingContextVc, ChunksVc,
-        EvaluatableAssetsVc,
+        ContentHashing, ContentHashingVc, EvaluatableAssetsVc,
     },
     environment::EnvironmentVc,
     ident::AssetIdentVc,
 };
 use turbopack_css::chunk::CssChunkVc;
-use turbopack_ecmascript::chunk::{
-    EcmascriptChunkPlaceableVc, EcmascriptChunkVc, EcmascriptChunkingContext,
-    EcmascriptChunkingContextVc,
+use turbopack_ecmascript::{
+    chunk::{
+        EcmascriptChunkPlaceableVc, EcmascriptChunkVc, EcmascriptChunkingContext,
+        EcmascriptChunkingContextVc,
+    },
+    export_usage::{ExportUsageVc, OptionExportUsageVc},
+    minify::{MinifyOptions, OptionMinifyOptionsVc},
 };
 use turbopack_ecmascript_runtime::RuntimeType;
 
@@ -37,11 +41,29 @@ impl BuildChunkingContextBuilder {
         self
     }
 
+    pub fn content_hashing(mut self, content_hashing: ContentHashing) -> Self {
+        self.context.content_hashing = content_hashing;
+        self
+    }
+
     pub fn layer(mut self, layer: impl Into<String>) -> Self {
         self.context.layer = Some(layer.into());
         self
     }
 
+    /// Omits the exports of ES modules which are unused according to
+    /// `export_usage` from the chunks.
+    pub fn export_usage(mut self, export_usage: ExportUsageVc) -> Self {
+        self.context.export_usage = Some(export_usage);
+        self
+    }
+
+    /// Minifies the code of EcmaScript chunk items with the given `options`.
+    pub fn minify(mut self, options: MinifyOptions) -> Self {
+        self.context.minify = Some(options);
+        self
+    }
+
     /// Builds the chunking context.
     pub fn build(self) -> BuildChunkingContextVc {
         BuildChunkingContextVc::new(Value::new(self.context))
@@ -67,6 +89,13 @@ pub struct BuildChunkingContext {
     environment: EnvironmentVc,
     /// The kind of runtime to include in the output.
     runtime_type: RuntimeType,
+    /// How content hashes in static asset paths are computed.
+    content_hashing: ContentHashing,
+    /// The used exports of ES modules, see
+    /// [BuildChunkingContextBuilder::export_usage].
+    export_usage: Option<ExportUsageVc>,
+    /// How to minify the code of EcmaScript chunk items, if at all.
+    minify: Option<MinifyOptions>,
 }
 
 impl BuildChunkingContextVc {
@@ -87,6 +116,9 @@ impl BuildChunkingContextVc {
                 layer: None,
                 environment,
                 runtime_type: Default::default(),
+                content_hashing: Default::default(),
+                export_usage: None,
+                minify: None,
             },
         }
     }
@@ -244,16 +276,17 @@ impl ChunkingContext for BuildChunkingContext {
             Some(ext) => format!(
                 "{basename}.{content_hash}.{ext}",
                 basename = &basename[..basename.len() - ext.len() - 1],
-                content_hash = &content_hash[..8]
-            ),
-            None => format!(
-                "{basename}.{content_hash}",
-                content_hash = &content_hash[..8]
             ),
+            None => format!("{basename}.{content_hash}"),
         };
         Ok(self.asset_root_path.join(&asset_path))
     }
 
+    #[turbo_tasks::function]
+    fn content_hashing(&self) -> ContentHashingVc {
+        self.content_hashing.cell()
+    }
+
     #[turbo_tasks::function]
     fn layer(&self) -> StringVc {
         StringVc::cell(self.layer.clone().unwrap_or_default())
@@ -297,7 +330,17 @@ impl ChunkingContext for BuildChunkingContext {
 }
 
 #[turbo_tasks::value_impl]
-impl EcmascriptChunkingContext for BuildChunkingContext {}
+impl EcmascriptChunkingContext for BuildChunkingContext {
+    #[turbo_tasks::function]
+    fn export_usage(&self) -> OptionExportUsageVc {
+        OptionExportUsageVc::cell(self.export_usage)
+    }
+
+    #[turbo_tasks::function]
+    fn minify(&self) -> OptionMinifyOptionsVc {
+        OptionMinifyOptionsVc::cell(self.minify)
+    }
+}
 
 async fn get_parallel_chunks<I>(entries: I) -> Result<impl Iterator<Item = ChunkVc>>
 where
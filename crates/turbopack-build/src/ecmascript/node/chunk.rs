@@ -3,7 +3,7 @@ use indexmap::IndexSet;
 use turbo_tasks::{primitives::StringVc, ValueToString, ValueToStringVc};
 use turbopack_core::{
     asset::{Asset, AssetContentVc, AssetVc},
-    chunk::ChunkingContext,
+    chunk::{ChunkingContext, OutputChunk, OutputChunkRuntimeInfo, OutputChunkRuntimeInfoVc},
     ident::AssetIdentVc,
     introspect::{Introspectable, IntrospectableChildrenVc, IntrospectableVc},
     reference::AssetReferencesVc,
@@ -44,6 +44,18 @@ impl ValueToString for EcmascriptBuildNodeChunk {
     }
 }
 
+#[turbo_tasks::value_impl]
+impl OutputChunk for EcmascriptBuildNodeChunk {
+    #[turbo_tasks::function]
+    fn runtime_info(&self) -> OutputChunkRuntimeInfoVc {
+        OutputChunkRuntimeInfo {
+            included_ids: Some(self.chunk.entry_ids()),
+            ..Default::default()
+        }
+        .cell()
+    }
+}
+
 #[turbo_tasks::function]
 fn modifier() -> StringVc {
     StringVc::cell("ecmascript build node chunk".to_string())
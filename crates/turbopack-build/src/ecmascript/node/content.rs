@@ -9,7 +9,10 @@ use turbopack_core::{
     code_builder::{CodeBuilder, CodeVc},
     source_map::{GenerateSourceMap, GenerateSourceMapVc, OptionSourceMapVc},
 };
-use turbopack_ecmascript::{chunk::EcmascriptChunkContentVc, utils::StringifyJs};
+use turbopack_ecmascript::{
+    chunk::{AsyncModuleInfoVc, EcmascriptChunkContentVc},
+    utils::StringifyJs,
+};
 
 use super::chunk::EcmascriptBuildNodeChunkVc;
 use crate::BuildChunkingContextVc;
@@ -57,13 +60,16 @@ impl EcmascriptBuildNodeChunkContentVc {
 
         let content = this.content.await?;
         let availability_info = Value::new(content.availability_info);
+        let async_module_info = AsyncModuleInfoVc::new(this.content);
         for (id, item_code) in content
             .chunk_items
             .iter()
             .map(|chunk_item| async move {
                 Ok((
                     chunk_item.id().await?,
-                    chunk_item.code(availability_info).await?,
+                    chunk_item
+                        .code(availability_info, async_module_info)
+                        .await?,
                 ))
             })
             .try_join()
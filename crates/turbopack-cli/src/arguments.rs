@@ -19,6 +19,13 @@ impl Arguments {
             Arguments::Dev(args) => args.common.dir.as_deref(),
         }
     }
+
+    /// The file to write a Chrome trace to. see [CommonArguments]::trace_chrome
+    pub fn trace_chrome(&self) -> Option<&Path> {
+        match self {
+            Arguments::Dev(args) => args.common.trace_chrome.as_deref(),
+        }
+    }
 }
 
 #[derive(Debug, Args)]
@@ -54,6 +61,11 @@ pub struct CommonArguments {
     /// MB.
     #[clap(long)]
     pub memory_limit: Option<usize>,
+
+    /// Write a Chrome trace of all task executions to the provided file. It
+    /// can be opened in chrome://tracing or https://ui.perfetto.dev.
+    #[clap(long, value_parser)]
+    pub trace_chrome: Option<PathBuf>,
 }
 
 #[derive(Debug, Args)]
@@ -82,6 +94,11 @@ pub struct DevArguments {
     #[clap(long)]
     pub no_open: bool,
 
+    /// Verify that the packages installed in node_modules match the lockfile
+    /// and warn about any differences.
+    #[clap(long)]
+    pub check_lockfile: bool,
+
     // ==
     // = Inherited options from next-dev, need revisit later.
     // ==
@@ -22,9 +22,10 @@ use turbo_tasks_malloc::TurboMalloc;
 use turbo_tasks_memory::MemoryBackend;
 use turbopack::evaluate_context::node_build_environment;
 use turbopack_cli_utils::issue::{ConsoleUiVc, LogOptions};
+pub use turbopack_core::issue::IssueReporterProvider;
 use turbopack_core::{
     environment::ServerAddr,
-    issue::{IssueReporterVc, IssueSeverity},
+    issue::IssueSeverity,
     resolve::{parse::RequestVc, pattern::QueryMapVc},
     server_fs::ServerFileSystemVc,
 };
@@ -39,6 +40,7 @@ use turbopack_dev_server::{
     DevServer, DevServerBuilder,
 };
 use turbopack_env::dotenv::load_env;
+use turbopack_lockfile_drift::check_lockfile_drift;
 use turbopack_node::execution_context::ExecutionContextVc;
 
 use self::web_entry_source::create_web_entry_source;
@@ -59,6 +61,7 @@ pub struct TurbopackDevServerBuilder {
     root_dir: String,
     entry_requests: Vec<EntryRequest>,
     eager_compile: bool,
+    check_lockfile: bool,
     hostname: Option<IpAddr>,
     issue_reporter: Option<Box<dyn IssueReporterProvider>>,
     port: Option<u16>,
@@ -81,6 +84,7 @@ impl TurbopackDevServerBuilder {
             root_dir,
             entry_requests: vec![],
             eager_compile: false,
+            check_lockfile: false,
             hostname: None,
             issue_reporter: None,
             port: None,
@@ -104,6 +108,11 @@ impl TurbopackDevServerBuilder {
         self
     }
 
+    pub fn check_lockfile(mut self, check_lockfile: bool) -> TurbopackDevServerBuilder {
+        self.check_lockfile = check_lockfile;
+        self
+    }
+
     pub fn hostname(mut self, hostname: IpAddr) -> TurbopackDevServerBuilder {
         self.hostname = Some(hostname);
         self
@@ -197,6 +206,7 @@ impl TurbopackDevServerBuilder {
         let project_dir = self.project_dir;
         let root_dir = self.root_dir;
         let eager_compile = self.eager_compile;
+        let check_lockfile = self.check_lockfile;
         let show_all = self.show_all;
         let log_detail = self.log_detail;
         let browserslist_query = self.browserslist_query;
@@ -220,6 +230,7 @@ impl TurbopackDevServerBuilder {
                 project_dir.clone(),
                 entry_requests.clone().into(),
                 eager_compile,
+                check_lockfile,
                 turbo_tasks.clone().into(),
                 browserslist_query.clone(),
             )
@@ -251,6 +262,7 @@ async fn source(
     project_dir: String,
     entry_requests: TransientInstance<Vec<EntryRequest>>,
     eager_compile: bool,
+    check_lockfile: bool,
     turbo_tasks: TransientInstance<TurboTasks<MemoryBackend>>,
     browserslist_query: String,
 ) -> Result<ContentSourceVc> {
@@ -263,6 +275,10 @@ async fn source(
         .replace(MAIN_SEPARATOR, "/");
     let project_path = fs.root().join(&project_relative);
 
+    if check_lockfile {
+        check_lockfile_drift(project_path).await?;
+    }
+
     let env = load_env(project_path);
     let build_output_root = output_fs.root().join(".turbopack/build");
 
@@ -380,6 +396,7 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
     let mut server = TurbopackDevServerBuilder::new(tt, dir, root_dir)
         .entry_request(EntryRequest::Relative("src/index".into()))
         .eager_compile(args.eager_compile)
+        .check_lockfile(args.check_lockfile)
         .hostname(args.hostname)
         .port(args.port)
         .log_detail(args.common.log_detail)
@@ -524,16 +541,3 @@ fn profile_timeout<T>(
 ) -> impl Future<Output = T> {
     future
 }
-
-pub trait IssueReporterProvider: Send + Sync + 'static {
-    fn get_issue_reporter(&self) -> IssueReporterVc;
-}
-
-impl<T> IssueReporterProvider for T
-where
-    T: Fn() -> IssueReporterVc + Send + Sync + Clone + 'static,
-{
-    fn get_issue_reporter(&self) -> IssueReporterVc {
-        self()
-    }
-}
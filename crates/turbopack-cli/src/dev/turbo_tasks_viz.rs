@@ -1,12 +1,13 @@
 use std::{sync::Arc, time::Duration};
 
 use anyhow::{bail, Result};
-use mime::TEXT_HTML_UTF_8;
+use mime::{APPLICATION_JSON, TEXT_HTML_UTF_8, TEXT_PLAIN_UTF_8};
 use turbo_tasks::{get_invalidator, TurboTasks, TurboTasksBackendApi, Value};
 use turbo_tasks_fs::File;
 use turbo_tasks_memory::{
     stats::{ReferenceType, Stats},
-    viz, MemoryBackend,
+    viz::{self, task_graph::TaskGraph},
+    MemoryBackend,
 };
 use turbopack_core::asset::AssetContentVc;
 use turbopack_dev_server::source::{
@@ -50,6 +51,21 @@ impl ContentSource for TurboTasksSource {
                 RouteType::Exact,
                 self_vc.into(),
             ),
+            RouteTreeVc::new_route(
+                vec![BaseSegment::Static("task-graph".to_string())],
+                RouteType::Exact,
+                self_vc.into(),
+            ),
+            RouteTreeVc::new_route(
+                vec![BaseSegment::Static("task-graph.dot".to_string())],
+                RouteType::Exact,
+                self_vc.into(),
+            ),
+            RouteTreeVc::new_route(
+                vec![BaseSegment::Static("task-graph.json".to_string())],
+                RouteType::Exact,
+                self_vc.into(),
+            ),
             RouteTreeVc::new_route(
                 vec![BaseSegment::Static("reset".to_string())],
                 RouteType::Exact,
@@ -86,7 +102,12 @@ impl GetContentSourceContent for TurboTasksSource {
                 invalidator.invalidate();
             }
         });
-        let html = match path {
+        let content_type = match path {
+            "task-graph.dot" => TEXT_PLAIN_UTF_8,
+            "task-graph.json" => APPLICATION_JSON,
+            _ => TEXT_HTML_UTF_8,
+        };
+        let content = match path {
             "graph" => {
                 let mut stats = Stats::new();
                 let b = tt.backend();
@@ -129,6 +150,9 @@ impl GetContentSourceContent for TurboTasksSource {
                 let table = viz::table::create_table(tree, tt.stats_type());
                 viz::table::wrap_html(&table)
             }
+            "task-graph" => viz::graph::wrap_html(&TaskGraph::new(tt.backend()).to_dot()),
+            "task-graph.dot" => TaskGraph::new(tt.backend()).to_dot(),
+            "task-graph.json" => TaskGraph::new(tt.backend()).to_json()?,
             "reset" => {
                 let b = tt.backend();
                 b.with_all_cached_tasks(|task| {
@@ -139,7 +163,7 @@ impl GetContentSourceContent for TurboTasksSource {
             _ => bail!("Unknown path: {}", path),
         };
         Ok(ContentSourceContentVc::static_content(
-            AssetContentVc::from(File::from(html).with_content_type(TEXT_HTML_UTF_8)).into(),
+            AssetContentVc::from(File::from(content).with_content_type(content_type)).into(),
         ))
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use anyhow::{anyhow, Result};
 use turbo_tasks::{TryJoinIterExt, Value};
@@ -17,7 +17,11 @@ use turbopack::{
 };
 use turbopack_cli_utils::runtime_entry::{RuntimeEntriesVc, RuntimeEntry};
 use turbopack_core::{
-    chunk::{ChunkableModuleVc, ChunkingContextVc},
+    asset::{Asset, AssetVc, AssetsSetVc},
+    chunk::{
+        preload_graph::{EntryChunkGroupsVc, PreloadManifestAssetVc},
+        ChunkableModule, ChunkableModuleVc, ChunkingContext, ChunkingContextVc,
+    },
     compile_time_defines,
     compile_time_info::{CompileTimeDefinesVc, CompileTimeInfo, CompileTimeInfoVc},
     context::AssetContextVc,
@@ -37,8 +41,7 @@ use turbopack_dev_server::{
 };
 use turbopack_ecmascript_plugins::transform::{
     emotion::{EmotionTransformConfig, EmotionTransformer},
-    styled_components::{StyledComponentsTransformConfig, StyledComponentsTransformer},
-    styled_jsx::StyledJsxTransformer,
+    styled_components::StyledComponentsTransformConfigVc,
 };
 use turbopack_node::execution_context::ExecutionContextVc;
 
@@ -123,22 +126,19 @@ async fn get_client_module_options_context(
 
     let custom_ecma_transform_plugins = Some(CustomEcmascriptTransformPluginsVc::cell(
         CustomEcmascriptTransformPlugins {
-            source_transforms: vec![
-                TransformPluginVc::cell(Box::new(
-                    EmotionTransformer::new(&EmotionTransformConfig::default())
-                        .expect("Should be able to create emotion transformer"),
-                )),
-                TransformPluginVc::cell(Box::new(StyledComponentsTransformer::new(
-                    &StyledComponentsTransformConfig::default(),
-                ))),
-                TransformPluginVc::cell(Box::new(StyledJsxTransformer::new())),
-            ],
+            source_transforms: vec![TransformPluginVc::cell(Box::new(
+                EmotionTransformer::new(&EmotionTransformConfig::default())
+                    .expect("Should be able to create emotion transformer"),
+            ))],
             output_transforms: vec![],
         },
     ));
 
     let module_options_context = ModuleOptionsContext {
         enable_jsx,
+        enable_styled_jsx: true,
+        enable_styled_components: Some(StyledComponentsTransformConfigVc::default()),
+        enable_import_meta_glob: true,
         enable_postcss_transform: Some(Default::default()),
         enable_typescript_transform: Some(Default::default()),
         rules: vec![(
@@ -181,6 +181,7 @@ pub fn client_defines() -> CompileTimeDefinesVc {
     compile_time_defines!(
         process.turbopack = true,
         process.env.NODE_ENV = "development",
+        typeof window = "object",
     )
     .cell()
 }
@@ -305,12 +306,39 @@ pub async fn create_web_entry_source(
         .try_join()
         .await?;
 
-    let entry_asset = DevHtmlAssetVc::new(server_root.join("index.html"), entries).into();
+    // Frameworks can read the chunks each entry might load from the preload
+    // manifest to preload them upfront.
+    let project_path_ref = project_path.await?;
+    let mut entry_chunk_groups = BTreeMap::new();
+    for (chunkable, chunking_context, runtime_entries) in &entries {
+        let Some(name) = project_path_ref
+            .get_path_to(&*chunkable.ident().path().await?)
+            .map(|name| name.to_string())
+        else {
+            continue;
+        };
+        let chunk = chunkable.as_root_chunk(*chunking_context);
+        let chunk_group = if let Some(runtime_entries) = runtime_entries {
+            chunking_context.evaluated_chunk_group(chunk, *runtime_entries)
+        } else {
+            chunking_context.chunk_group(chunk)
+        };
+        entry_chunk_groups.insert(name, chunk_group);
+    }
+    let preload_manifest: AssetVc = PreloadManifestAssetVc::new(
+        server_root.join("preload-manifest.json"),
+        server_root,
+        EntryChunkGroupsVc::cell(entry_chunk_groups),
+    )
+    .into();
+
+    let entry_asset: AssetVc = DevHtmlAssetVc::new(server_root.join("index.html"), entries).into();
 
+    let root_assets = AssetsSetVc::cell([entry_asset, preload_manifest].into_iter().collect());
     let graph = if eager_compile {
-        AssetGraphContentSourceVc::new_eager(server_root, entry_asset)
+        AssetGraphContentSourceVc::new_eager_multiple(server_root, root_assets)
     } else {
-        AssetGraphContentSourceVc::new_lazy(server_root, entry_asset)
+        AssetGraphContentSourceVc::new_lazy_multiple(server_root, root_assets)
     }
     .into();
     Ok(graph)
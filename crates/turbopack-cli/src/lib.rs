@@ -9,5 +9,6 @@ pub fn register() {
     turbopack::register();
     turbopack_dev::register();
     turbopack_ecmascript_plugins::register();
+    turbopack_lockfile_drift::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
 }
@@ -5,6 +5,7 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 use clap::Parser;
+use tracing_chrome::{ChromeLayerBuilder, TraceStyle};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
 use turbopack_cli::{arguments::Arguments, register};
 use turbopack_cli_utils::{
@@ -25,8 +26,24 @@ fn main() {
     let args = Arguments::parse();
 
     let trace = std::env::var("TURBOPACK_TRACING").ok();
+    let trace_chrome = args.trace_chrome();
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .on_thread_stop(|| {
+            TurboMalloc::thread_stop();
+        })
+        .build()
+        .unwrap();
+
+    let _guard = if trace.is_some() || trace_chrome.is_some() {
+        // The exit guard needs to be created within the runtime.
+        let _enter = runtime.enter();
+
+        let raw_trace = trace.is_some();
+        // A Chrome trace includes the task executions by default.
+        let mut trace = trace.unwrap_or_else(|| "turbo_tasks=info".to_string());
 
-    let _guard = if let Some(mut trace) = trace {
         // Trace presets
         match trace.as_str() {
             "overview" => {
@@ -45,19 +62,37 @@ fn main() {
 
         let subscriber = subscriber.with(EnvFilter::builder().parse(trace).unwrap());
 
-        let internal_dir = args
-            .dir()
-            .unwrap_or_else(|| Path::new("."))
-            .join(".turbopack");
-        std::fs::create_dir_all(&internal_dir)
-            .context("Unable to create .turbopack directory")
-            .unwrap();
-        let trace_file = internal_dir.join("trace.log");
-        let trace_writer = std::fs::File::create(trace_file).unwrap();
-        let (trace_writer, guard) = TraceWriter::new(trace_writer);
-        let subscriber = subscriber.with(RawTraceLayer::new(trace_writer));
+        let (raw_trace_layer, raw_trace_guard) = if raw_trace {
+            let internal_dir = args
+                .dir()
+                .unwrap_or_else(|| Path::new("."))
+                .join(".turbopack");
+            std::fs::create_dir_all(&internal_dir)
+                .context("Unable to create .turbopack directory")
+                .unwrap();
+            let trace_file = internal_dir.join("trace.log");
+            let trace_writer = std::fs::File::create(trace_file).unwrap();
+            let (trace_writer, guard) = TraceWriter::new(trace_writer);
+            (Some(RawTraceLayer::new(trace_writer)), Some(guard))
+        } else {
+            (None, None)
+        };
+        let subscriber = subscriber.with(raw_trace_layer);
+
+        let (chrome_layer, chrome_guard) = match trace_chrome {
+            Some(trace_chrome) => {
+                let (layer, guard) = ChromeLayerBuilder::new()
+                    .file(trace_chrome)
+                    .include_args(true)
+                    .trace_style(TraceStyle::Async)
+                    .build();
+                (Some(layer), Some(guard))
+            }
+            None => (None, None),
+        };
+        let subscriber = subscriber.with(chrome_layer);
 
-        let guard = ExitGuard::new(guard).unwrap();
+        let guard = ExitGuard::new((raw_trace_guard, chrome_guard)).unwrap();
 
         subscriber.init();
 
@@ -66,15 +101,7 @@ fn main() {
         None
     };
 
-    tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .on_thread_stop(|| {
-            TurboMalloc::thread_stop();
-        })
-        .build()
-        .unwrap()
-        .block_on(main_inner(args))
-        .unwrap();
+    runtime.block_on(main_inner(args)).unwrap();
 }
 
 async fn main_inner(args: Arguments) -> Result<()> {
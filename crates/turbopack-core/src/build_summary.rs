@@ -0,0 +1,119 @@
+//! A machine readable summary of a build. CI can store it after every build to
+//! keep track of the duration, caching, output size and issues of builds over
+//! time.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use anyhow::Result;
+use serde::Serialize;
+use turbo_tasks::TaskStatistics;
+use turbo_tasks_fs::FileContent;
+
+use crate::{
+    asset::{Asset, AssetContent, AssetVc},
+    issue::{CapturedIssues, Issue, IssueSeverity},
+    reference::all_assets,
+};
+
+/// The assets of an entry of a build and their total size.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EntrySummary {
+    /// The path of the entry.
+    pub path: String,
+    /// The number of assets the entry references, including itself.
+    pub assets: usize,
+    /// The total size of the content of the assets in bytes.
+    pub size: u64,
+}
+
+/// Summarizes `entry` and all the assets it references.
+#[turbo_tasks::function]
+pub async fn entry_summary(entry: AssetVc) -> Result<EntrySummaryVc> {
+    let assets = all_assets(entry).await?;
+    let mut size = 0;
+    for asset in assets.iter() {
+        if let AssetContent::File(file) = &*asset.content().await? {
+            if let FileContent::Content(file) = &*file.await? {
+                size += file.content().len() as u64;
+            }
+        }
+    }
+    Ok(EntrySummary {
+        path: entry.ident().path().await?.path.to_string(),
+        assets: assets.len(),
+        size,
+    }
+    .cell())
+}
+
+/// The duration of a phase of a build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseSummary {
+    pub name: String,
+    pub duration_ms: u64,
+}
+
+/// How many tasks were executed and how many calls reused a cached task.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskCacheSummary {
+    pub tasks: usize,
+    pub cache_hits: usize,
+    pub executions: usize,
+    /// The share of calls which reused an existing task, between 0 and 1.
+    pub cache_hit_rate: f64,
+    pub dirty_tasks: usize,
+}
+
+/// Combines the phase durations, task cache statistics, per entry output sizes
+/// and issue counts of a build into a single JSON document.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildSummary {
+    pub phases: Vec<PhaseSummary>,
+    pub tasks: TaskCacheSummary,
+    pub entries: Vec<EntrySummary>,
+    /// The number of issues per severity. Severities without issues are
+    /// omitted.
+    pub issues: BTreeMap<&'static str, usize>,
+}
+
+impl BuildSummary {
+    pub fn add_phase(&mut self, name: impl Into<String>, duration: Duration) {
+        self.phases.push(PhaseSummary {
+            name: name.into(),
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+
+    pub fn set_task_statistics(&mut self, statistics: &TaskStatistics) {
+        let total = &statistics.total;
+        self.tasks = TaskCacheSummary {
+            tasks: total.tasks,
+            cache_hits: total.cache_hits,
+            executions: total.executions,
+            cache_hit_rate: total.cache_hit_ratio(),
+            dirty_tasks: statistics.dirty_tasks,
+        };
+    }
+
+    pub fn add_entry(&mut self, entry: EntrySummary) {
+        self.entries.push(entry);
+    }
+
+    /// Counts the `issues` by their severity.
+    pub async fn add_issues(&mut self, issues: &CapturedIssues) -> Result<()> {
+        for issue in issues.iter() {
+            let severity: IssueSeverity = *issue.severity().await?;
+            *self.issues.entry(severity.as_str()).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -4,7 +4,7 @@ use anyhow::Result;
 use turbo_tasks::primitives::{BoolVc, StringVc};
 use turbo_tasks_fs::FileSystemPathVc;
 
-use super::{ChunkVc, EvaluatableAssetsVc};
+use super::{ChunkVc, ContentHashing, ContentHashingVc, EvaluatableAssetsVc};
 use crate::{
     asset::{AssetVc, AssetsVc},
     environment::EnvironmentVc,
@@ -33,12 +33,21 @@ pub trait ChunkingContext {
 
     fn can_be_in_same_chunk(&self, asset_a: AssetVc, asset_b: AssetVc) -> BoolVc;
 
+    /// Returns the path of a static asset. `content_hash` has been computed
+    /// according to [ChunkingContext::content_hashing] already.
     fn asset_path(
         &self,
         content_hash: &str,
         original_asset_ident: AssetIdentVc,
     ) -> FileSystemPathVc;
 
+    /// How content hashes passed to [ChunkingContext::asset_path] are
+    /// computed. This doesn't affect [ChunkingContext::chunk_path], which
+    /// doesn't use content hashes.
+    fn content_hashing(&self) -> ContentHashingVc {
+        ContentHashing::default().cell()
+    }
+
     fn is_hot_module_replacement_enabled(&self) -> BoolVc {
         BoolVc::cell(false)
     }
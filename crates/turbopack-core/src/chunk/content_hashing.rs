@@ -0,0 +1,66 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::trace::TraceRawVcs;
+use turbo_tasks_fs::rope::Rope;
+use turbo_tasks_hash::{encode_hex, encode_hex_string, hash_blake3, hash_sha256, hash_xxh3_hash64};
+
+/// The hash function used to compute content hashes of output assets.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    TraceRawVcs,
+    Default,
+)]
+pub enum HashAlgorithm {
+    /// A fast, non-cryptographic hash.
+    #[default]
+    Xxh3Hash64,
+    Blake3,
+    /// Required by some deploy targets for integrity validation.
+    Sha256,
+}
+
+/// How content hashes, which are used in the paths of output assets for long
+/// term caching, are computed.
+///
+/// This only applies to static assets. The paths of chunks are derived from
+/// the idents of their entries instead of their content, since the content of
+/// a chunk contains the paths of the chunks it loads.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct ContentHashing {
+    pub algorithm: HashAlgorithm,
+    /// The number of hex characters of the hash which are used. Hashes which
+    /// are shorter than this are used in full.
+    pub length: usize,
+}
+
+impl Default for ContentHashing {
+    fn default() -> Self {
+        ContentHashing {
+            algorithm: HashAlgorithm::Xxh3Hash64,
+            length: 8,
+        }
+    }
+}
+
+impl ContentHashing {
+    /// Computes the hex encoded and truncated content hash of `content`.
+    pub fn hash(&self, content: &Rope) -> Result<String> {
+        let mut hash = match self.algorithm {
+            HashAlgorithm::Xxh3Hash64 => encode_hex(hash_xxh3_hash64(content)),
+            HashAlgorithm::Blake3 => encode_hex_string(&hash_blake3(&content.to_bytes()?)),
+            HashAlgorithm::Sha256 => encode_hex_string(&hash_sha256(&content.to_bytes()?)),
+        };
+        hash.truncate(self.length);
+        Ok(hash)
+    }
+}
@@ -2,10 +2,12 @@ pub mod availability_info;
 pub mod available_assets;
 pub(crate) mod chunking_context;
 pub(crate) mod containment_tree;
+pub(crate) mod content_hashing;
 pub(crate) mod data;
 pub(crate) mod evaluate;
 pub mod optimize;
 pub(crate) mod passthrough_asset;
+pub mod preload_graph;
 
 use std::{
     collections::HashSet,
@@ -31,6 +33,7 @@ use turbo_tasks_hash::DeterministicHash;
 use self::availability_info::AvailabilityInfo;
 pub use self::{
     chunking_context::{ChunkingContext, ChunkingContextVc},
+    content_hashing::{ContentHashing, ContentHashingVc, HashAlgorithm},
     data::{ChunkData, ChunkDataOption, ChunkDataOptionVc, ChunkDataVc, ChunksData, ChunksDataVc},
     evaluate::{EvaluatableAsset, EvaluatableAssetVc, EvaluatableAssets, EvaluatableAssetsVc},
     passthrough_asset::{PassthroughAsset, PassthroughAssetVc},
@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use serde::Serialize;
+use turbo_tasks_fs::{File, FileSystemPath, FileSystemPathVc};
+
+use crate::{
+    asset::{Asset, AssetContentVc, AssetVc, AssetsVc},
+    chunk::OutputChunkVc,
+    ident::AssetIdentVc,
+    reference::all_referenced_assets,
+};
+
+/// The chunks an entry needs, split into the chunks which are loaded
+/// synchronously with the entry and the chunks which might be loaded lazily
+/// later on, e.g. by dynamic `import()`s.
+///
+/// This allows SSR frameworks to emit `<link rel="modulepreload">` tags for
+/// all chunks of a page upfront instead of discovering them at runtime.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default, Clone)]
+pub struct PreloadGraph {
+    /// Paths of the chunks loaded with the entry, relative to the output root.
+    pub sync: Vec<String>,
+    /// Paths of the chunks which are loaded asynchronously by the entry or by
+    /// one of its lazy chunks, relative to the output root.
+    pub lazy: Vec<String>,
+}
+
+#[turbo_tasks::value_impl]
+impl PreloadGraphVc {
+    /// Computes the preload graph for an entry from its chunk group, as
+    /// returned by [ChunkingContext::chunk_group] or
+    /// [ChunkingContext::evaluated_chunk_group].
+    ///
+    /// [ChunkingContext::chunk_group]: crate::chunk::ChunkingContext::chunk_group
+    /// [ChunkingContext::evaluated_chunk_group]: crate::chunk::ChunkingContext::evaluated_chunk_group
+    #[turbo_tasks::function]
+    pub async fn new(output_root: FileSystemPathVc, chunk_group: AssetsVc) -> Result<Self> {
+        let output_root = output_root.await?;
+        let chunk_group = chunk_group.await?;
+
+        let mut visited: HashSet<AssetVc> = HashSet::new();
+        let mut queue = VecDeque::with_capacity(chunk_group.len());
+        let mut sync = Vec::new();
+        for chunk in chunk_group.iter() {
+            let chunk = chunk.resolve().await?;
+            if visited.insert(chunk) {
+                queue.push_back(chunk);
+                sync.push(chunk);
+            }
+        }
+
+        // Everything reachable from the synchronous chunks, which is not part
+        // of the chunk group itself, is loaded lazily. Async chunk groups are
+        // only reachable through the manifest chunks of their loaders, so all
+        // output assets are followed, but only output chunks end up in the
+        // graph. Static assets and source maps are left out.
+        let mut lazy = Vec::new();
+        while let Some(asset) = queue.pop_front() {
+            for referenced in all_referenced_assets(asset).await?.iter() {
+                let referenced = referenced.resolve().await?;
+                if !visited.insert(referenced) {
+                    continue;
+                }
+                if !referenced.ident().path().await?.is_inside(&output_root) {
+                    continue;
+                }
+                queue.push_back(referenced);
+                if OutputChunkVc::resolve_from(referenced).await?.is_some() {
+                    lazy.push(referenced);
+                }
+            }
+        }
+
+        Ok(PreloadGraph {
+            sync: chunk_paths(&output_root, sync).await?,
+            lazy: chunk_paths(&output_root, lazy).await?,
+        }
+        .cell())
+    }
+}
+
+async fn chunk_paths(output_root: &FileSystemPath, chunks: Vec<AssetVc>) -> Result<Vec<String>> {
+    let mut paths = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let path = chunk.ident().path().await?;
+        if let Some(path) = output_root.get_path_to(&path) {
+            paths.push(path.to_string());
+        }
+    }
+    Ok(paths)
+}
+
+/// A map from entry names to their chunk groups.
+#[turbo_tasks::value(transparent)]
+pub struct EntryChunkGroups(BTreeMap<String, AssetsVc>);
+
+/// A JSON manifest containing the [PreloadGraph] of every entry, keyed by the
+/// entry name.
+#[turbo_tasks::value]
+pub struct PreloadManifestAsset {
+    path: FileSystemPathVc,
+    output_root: FileSystemPathVc,
+    entries: EntryChunkGroupsVc,
+}
+
+#[turbo_tasks::value_impl]
+impl PreloadManifestAssetVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        path: FileSystemPathVc,
+        output_root: FileSystemPathVc,
+        entries: EntryChunkGroupsVc,
+    ) -> Self {
+        PreloadManifestAsset {
+            path,
+            output_root,
+            entries,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for PreloadManifestAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        AssetIdentVc::from_path(self.path)
+    }
+
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<AssetContentVc> {
+        let mut manifest = BTreeMap::new();
+        for (name, chunk_group) in self.entries.await?.iter() {
+            let graph = PreloadGraphVc::new(self.output_root, *chunk_group).await?;
+            manifest.insert(name.clone(), graph.clone_value());
+        }
+        let manifest = PreloadManifest { entries: manifest };
+        Ok(File::from(serde_json::to_string_pretty(&manifest)?).into())
+    }
+}
+
+#[derive(Serialize)]
+struct PreloadManifest {
+    entries: BTreeMap<String, PreloadGraph>,
+}
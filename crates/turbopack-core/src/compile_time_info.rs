@@ -7,6 +7,22 @@ use crate::environment::EnvironmentVc;
 // TODO stringify split map collect could be optimized with a marco
 #[macro_export]
 macro_rules! definable_name_map_internal {
+    ($map:ident, typeof $($name:ident).+ = $value:expr) => {
+        $map.insert(
+            $crate::definable_name_map_internal!(typeof $($name).+).into(),
+            $value.into()
+        );
+    };
+    ($map:ident, typeof $($name:ident).+ = $value:expr,) => {
+        $map.insert(
+            $crate::definable_name_map_internal!(typeof $($name).+).into(),
+            $value.into()
+        );
+    };
+    ($map:ident, typeof $($name:ident).+ = $value:expr, $($more:tt)+) => {
+        $crate::definable_name_map_internal!($map, typeof $($name).+ = $value);
+        $crate::definable_name_map_internal!($map, $($more)+);
+    };
     ($map:ident, .. $value:expr) => {
         for (key, value) in $value {
             $map.insert(
@@ -35,6 +51,9 @@ macro_rules! definable_name_map_internal {
         $crate::definable_name_map_internal!($map, .. $value);
         $crate::definable_name_map_internal!($map, $($more)+);
     };
+    (typeof $($name:ident).+) => {
+        $crate::definable_name_map_internal!($($name).+, ["typeof".to_string()])
+    };
     ($name:ident) => {
         [stringify!($name).to_string()]
     };
@@ -96,6 +115,9 @@ impl From<&str> for CompileTimeDefineValue {
     }
 }
 
+/// Values which replace references to free variables and their members, e.g.
+/// `process.env.NODE_ENV`, at compile time. A name starting with a `typeof`
+/// segment replaces `typeof` expressions of the remaining name instead.
 #[turbo_tasks::value(transparent)]
 pub struct CompileTimeDefines(pub IndexMap<Vec<String>, CompileTimeDefineValue>);
 
@@ -665,8 +665,21 @@ pub struct PlainIssueProcessingPathItem {
     pub description: StringReadRef,
 }
 
+/// Receives the issues collected from a root operation and presents them to
+/// the user.
+///
+/// Embedders (GUIs, language servers, CI wrappers) can implement this trait to
+/// render diagnostics natively instead of scraping terminal output. Use
+/// [`CapturedIssues::get_plain_issues`] or [`IssueVc::into_plain`] to obtain
+/// resolved snapshots of each issue, including severity, context, source
+/// location and the content of the source asset to build code frames from.
 #[turbo_tasks::value_trait]
 pub trait IssueReporter {
+    /// Reports `issues` that were collected from `source`. The same `source`
+    /// may be reported multiple times as it is recomputed, so implementations
+    /// are expected to deduplicate by `source` if needed.
+    ///
+    /// Returns true when a fatal issue was reported.
     fn report_issues(
         &self,
         issues: TransientInstance<ReadRef<CapturedIssues>>,
@@ -674,6 +687,21 @@ pub trait IssueReporter {
     ) -> BoolVc;
 }
 
+/// Creates an [`IssueReporterVc`] on demand. This is passed at startup by
+/// embedders that want to replace the default console reporter.
+pub trait IssueReporterProvider: Send + Sync + 'static {
+    fn get_issue_reporter(&self) -> IssueReporterVc;
+}
+
+impl<T> IssueReporterProvider for T
+where
+    T: Fn() -> IssueReporterVc + Send + Sync + Clone + 'static,
+{
+    fn get_issue_reporter(&self) -> IssueReporterVc {
+        self()
+    }
+}
+
 #[async_trait]
 pub trait IssueContextExt
 where
@@ -8,7 +8,7 @@ use super::{Issue, IssueVc};
 use crate::{
     error::PrettyPrintError,
     issue::{IssueSeverityVc, OptionIssueSourceVc},
-    resolve::{options::ResolveOptionsVc, parse::RequestVc},
+    resolve::{options::ResolveOptionsVc, parse::RequestVc, suggestions::resolve_suggestions},
 };
 
 #[turbo_tasks::value(shared)]
@@ -49,10 +49,23 @@ impl Issue for ResolvingIssue {
 
     #[turbo_tasks::function]
     async fn description(&self) -> Result<StringVc> {
-        Ok(StringVc::cell(format!(
+        let mut description = format!(
             "unable to resolve {module_name}",
             module_name = self.request.to_string().await?
-        )))
+        );
+        if self.error_message.is_none() {
+            let suggestions =
+                resolve_suggestions(self.context, self.request, self.resolve_options).await?;
+            if let Some((last, rest)) = suggestions.split_last() {
+                description.push_str(". Did you mean ");
+                if !rest.is_empty() {
+                    let rest = rest.iter().map(|s| format!("'{s}'")).collect::<Vec<_>>();
+                    write!(description, "{} or ", rest.join(", "))?;
+                }
+                write!(description, "'{last}'?")?;
+            }
+        }
+        Ok(StringVc::cell(description))
     }
 
     #[turbo_tasks::function]
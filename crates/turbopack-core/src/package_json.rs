@@ -5,7 +5,7 @@ use serde_json::Value as JsonValue;
 use turbo_tasks::{debug::ValueDebugFormat, primitives::StringVc, trace::TraceRawVcs};
 use turbo_tasks_fs::{FileContent, FileJsonContent, FileJsonContentReadRef, FileSystemPathVc};
 
-use super::issue::{Issue, IssueVc};
+use super::issue::{Issue, IssueSeverityVc, IssueVc};
 
 /// PackageJson wraps the parsed JSON content of a `package.json` file. The
 /// wrapper is necessary so that we can reference the [FileJsonContent]'s inner
@@ -83,3 +83,40 @@ impl Issue for PackageJsonIssue {
         StringVc::cell(self.error_message.clone())
     }
 }
+
+/// A malformed entry of the "exports" field of a `package.json`. Depending on
+/// the problem, the entry is ignored or interpreted leniently.
+#[turbo_tasks::value(shared)]
+pub struct MalformedExportsIssue {
+    pub path: FileSystemPathVc,
+    pub severity: IssueSeverityVc,
+    pub message: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for MalformedExportsIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        self.severity
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("Malformed \"exports\" field in package.json".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("resolve".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::cell(self.message.clone())
+    }
+}
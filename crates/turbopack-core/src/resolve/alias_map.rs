@@ -18,9 +18,9 @@ use turbo_tasks::{
 
 /// A map of [`AliasPattern`]s to the [`Template`]s they resolve to.
 ///
-/// If a pattern has a wildcard character (*) within it, it will capture any
-/// number of characters, including path separators. The result of the capture
-/// will then be passed to the template.
+/// If a pattern has wildcard characters (*) within it, each of them will
+/// capture any number of characters, including path separators. The results
+/// of the captures will then be passed to the template, in order.
 ///
 /// If the pattern does not have a wildcard character, it will only match the
 /// exact string, and return the template as-is.
@@ -434,16 +434,10 @@ where
                     }
                     AliasKey::Wildcard { suffix } => {
                         let remaining = &self.request[prefix.len()..];
-                        if
-                        // The suffix is longer than what remains of the request.
-                        suffix.len() > remaining.len()
-                            // Not a suffix match.
-                            || !remaining.ends_with(suffix)
-                        {
+                        let Some(captures) = match_wildcards(remaining, suffix) else {
                             continue;
-                        }
-                        let capture = &remaining[..remaining.len() - suffix.len()];
-                        let output = template.replace(capture);
+                        };
+                        let output = template.replace(&captures);
                         return Some(AliasMatch::Replaced(output));
                     }
                 }
@@ -456,12 +450,53 @@ where
     }
 }
 
+/// Matches `remaining` against a wildcard followed by `suffix`, which itself
+/// might contain more wildcards. Returns the captures of all wildcards.
+///
+/// Wildcards within the suffix capture as few characters as possible, while
+/// the part after the last wildcard must match the end of `remaining`.
+fn match_wildcards<'a>(remaining: &'a str, suffix: &str) -> Option<Vec<&'a str>> {
+    let mut segments = suffix.split('*');
+    let last = segments.next_back().unwrap_or_default();
+    if last.len() > remaining.len() || !remaining.ends_with(last) {
+        return None;
+    }
+    let mut rest = &remaining[..remaining.len() - last.len()];
+    let mut captures = Vec::new();
+    for segment in segments {
+        let index = rest.find(segment)?;
+        captures.push(&rest[..index]);
+        rest = &rest[index + segment.len()..];
+    }
+    captures.push(rest);
+    Some(captures)
+}
+
+/// Replaces the wildcards (*) in `template` with the `captures`, in order.
+/// Wildcards beyond the number of captures are replaced with the last
+/// capture, so a template with many wildcards can reuse a single capture.
+pub fn replace_wildcards(template: &str, captures: &[&str]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut last_capture = "";
+    for (index, part) in template.split('*').enumerate() {
+        if index > 0 {
+            if let Some(capture) = captures.get(index - 1) {
+                last_capture = capture;
+            }
+            output.push_str(last_capture);
+        }
+        output.push_str(part);
+    }
+    output
+}
+
 /// An alias pattern.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum AliasPattern {
     /// Will match an exact string.
     Exact(String),
-    /// Will match a pattern with a single wildcard.
+    /// Will match a pattern with one or more wildcards. The prefix ends at
+    /// the first wildcard, the suffix might contain more wildcards.
     Wildcard { prefix: String, suffix: String },
 }
 
@@ -646,8 +681,9 @@ pub trait AliasTemplate {
     where
         Self: 'a;
 
-    /// Replaces `capture` within `self`.
-    fn replace<'a>(&'a self, capture: &'a str) -> Self::Output<'a>;
+    /// Replaces the wildcards within `self` with the `captures` of the
+    /// matched pattern, in order.
+    fn replace<'a>(&'a self, captures: &[&'a str]) -> Self::Output<'a>;
 }
 
 #[cfg(test)]
@@ -690,13 +726,9 @@ mod test {
     impl<'a> AliasTemplate for &'a str {
         type Output<'b> = Cow<'a, str> where Self: 'b;
 
-        fn replace(&self, capture: &str) -> Self::Output<'a> {
-            if let Some(index) = self.find('*') {
-                let mut output = String::with_capacity(self.len() - 1 + capture.len());
-                output.push_str(&self[..index]);
-                output.push_str(capture);
-                output.push_str(&self[index + 1..]);
-                Cow::Owned(output)
+        fn replace(&self, captures: &[&str]) -> Self::Output<'a> {
+            if self.contains('*') {
+                Cow::Owned(super::replace_wildcards(self, captures))
             } else {
                 Cow::Borrowed(*self)
             }
@@ -833,8 +865,14 @@ mod test {
         map.insert(AliasPattern::parse("*"), "foo*foo");
         map.insert(AliasPattern::parse("**"), "bar*foo");
 
-        assert_alias_matches!(map, "", replaced_owned("foofoo"));
-        assert_alias_matches!(map, "bar", replaced_owned("foobarfoo"));
+        // The first wildcard of "**" captures as few characters as possible.
+        assert_alias_matches!(map, "", replaced_owned("barfoo"), replaced_owned("foofoo"));
+        assert_alias_matches!(
+            map,
+            "bar",
+            replaced_owned("barfoo"),
+            replaced_owned("foobarfoo")
+        );
         assert_alias_matches!(
             map,
             "*",
@@ -844,8 +882,36 @@ mod test {
         assert_alias_matches!(
             map,
             "**",
-            replaced_owned("bar*foo"),
+            replaced_owned("barfoo"),
             replaced_owned("foo**foo")
         );
     }
+
+    #[test]
+    fn test_multiple_wildcards() {
+        let mut map = AliasMap::new();
+        map.insert(AliasPattern::parse("@app/*/impl/*"), "./src/*/impl/*.ts");
+        map.insert(AliasPattern::parse("@lib/*/*"), "./lib/*");
+
+        assert_alias_matches!(
+            map,
+            "@app/foo/impl/bar",
+            replaced_owned("./src/foo/impl/bar.ts")
+        );
+        assert_alias_matches!(
+            map,
+            "@app/foo/impl/bar/impl/baz",
+            replaced_owned("./src/foo/impl/bar/impl/baz.ts")
+        );
+        assert_alias_matches!(map, "@app/foo/bar");
+        // Templates with fewer wildcards only use the first captures.
+        assert_alias_matches!(map, "@lib/foo/bar", replaced_owned("./lib/foo"));
+    }
+
+    #[test]
+    fn test_replace_wildcards() {
+        assert_eq!(super::replace_wildcards("a*b*c", &["1", "2"]), "a1b2c");
+        assert_eq!(super::replace_wildcards("a*b*c", &["1"]), "a1b1c");
+        assert_eq!(super::replace_wildcards("abc", &["1"]), "abc");
+    }
 }
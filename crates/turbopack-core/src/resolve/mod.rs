@@ -6,20 +6,24 @@ use std::{
 };
 
 use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use indexmap::IndexSet;
 use serde_json::Value as JsonValue;
 use turbo_tasks::{
-    primitives::{BoolVc, StringVc, StringsVc},
+    primitives::{BoolVc, OptionStringVc, StringVc, StringsVc},
     TryJoinIterExt, Value, ValueToString, ValueToStringVc,
 };
 use turbo_tasks_fs::{
-    util::{normalize_path, normalize_request},
-    FileSystemEntryType, FileSystemPathVc, RealPathResult,
+    util::{normalize_path, normalize_request, windows_path_relative_to},
+    DiskFileSystemVc, File, FileSystemEntryType, FileSystemPathVc, RealPathResult,
 };
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
 
 use self::{
     options::{
-        resolve_modules_options, ImportMapResult, ResolveInPackage, ResolveIntoPackage,
-        ResolveModules, ResolveModulesOptionsVc, ResolveOptionsVc,
+        import_mapping_to_result, resolve_modules_options, EnforceExtension, ExternalType,
+        ImportMapResult, ResolveInPackage, ResolveIntoPackage, ResolveModules,
+        ResolveModulesOptionsVc, ResolveOptionsVc, TypesOnlyResolve,
     },
     parse::{Request, RequestVc},
     pattern::QueryMapVc,
@@ -29,7 +33,10 @@ use crate::{
     asset::{Asset, AssetOptionVc, AssetVc, AssetsVc},
     file_source::FileSourceVc,
     issue::resolve::{ResolvingIssue, ResolvingIssueVc},
-    package_json::{read_package_json, PackageJsonIssue, PackageJsonIssueVc},
+    package_json::{
+        read_package_json, MalformedExportsIssue, MalformedExportsIssueVc, PackageJsonIssue,
+        PackageJsonIssueVc,
+    },
     reference::{AssetReference, AssetReferenceVc},
     reference_type::ReferenceType,
     resolve::{
@@ -37,6 +44,8 @@ use crate::{
         pattern::{read_matches, Pattern, PatternMatch, PatternVc},
         plugin::ResolvePlugin,
     },
+    source::SourcesVc,
+    virtual_source::VirtualSourceVc,
 };
 
 mod alias_map;
@@ -47,6 +56,10 @@ pub mod parse;
 pub mod pattern;
 pub mod plugin;
 pub(crate) mod remap;
+pub mod suggestions;
+mod types_versions;
+pub mod web_import_map;
+pub mod workspaces;
 
 pub use alias_map::{
     AliasMap, AliasMapIntoIter, AliasMapLookupIterator, AliasMatch, AliasPattern, AliasTemplate,
@@ -60,7 +73,7 @@ use crate::issue::{IssueSeverity, IssueSeverityVc, OptionIssueSourceVc};
 pub enum PrimaryResolveResult {
     Asset(AssetVc),
     OriginalReferenceExternal,
-    OriginalReferenceTypeExternal(String),
+    OriginalReferenceTypeExternal(String, ExternalType),
     Ignore,
     Empty,
     Custom(u8),
@@ -332,6 +345,28 @@ impl ResolveResultVc {
                 .collect(),
         ))
     }
+
+    /// Returns the files which were consulted during resolving and affect the
+    /// result, e.g. package.json files and followed symlinks. Embedders can
+    /// use them to implement their own invalidation or cache keys.
+    #[turbo_tasks::function]
+    pub async fn affecting_sources(self) -> Result<SourcesVc> {
+        let this = self.await?;
+        let mut paths = IndexSet::new();
+        for reference in this.references.iter() {
+            if let Some(reference) =
+                AffectingResolvingAssetReferenceVc::resolve_from(*reference).await?
+            {
+                paths.insert(reference.await?.path.resolve().await?);
+            }
+        }
+        Ok(SourcesVc::cell(
+            paths
+                .into_iter()
+                .map(|path| FileSourceVc::new(path).into())
+                .collect(),
+        ))
+    }
 }
 
 #[turbo_tasks::value(transparent)]
@@ -400,8 +435,23 @@ async fn exports_field(package_json_path: FileSystemPathVc) -> Result<ExportsFie
     let Some(exports) = package_json.get("exports") else {
         return Ok(ExportsFieldResult::None.cell());
     };
-    match exports.try_into() {
-        Ok(exports) => Ok(ExportsFieldResult::Some(exports).cell()),
+    match ExportsField::try_from(exports) {
+        Ok(exports) => {
+            for diagnostic in exports.diagnostics() {
+                let issue: MalformedExportsIssueVc = MalformedExportsIssue {
+                    path: package_json_path,
+                    severity: if diagnostic.ignored {
+                        IssueSeverity::Error.cell()
+                    } else {
+                        IssueSeverity::Warning.cell()
+                    },
+                    message: diagnostic.message.clone(),
+                }
+                .into();
+                issue.as_issue().emit();
+            }
+            Ok(ExportsFieldResult::Some(exports).cell())
+        }
         Err(err) => {
             let issue: PackageJsonIssueVc = PackageJsonIssue {
                 path: package_json_path,
@@ -547,9 +597,27 @@ async fn find_package(
                     packages.push(package_dir.resolve().await?);
                 }
             }
+            ResolveModules::Workspaces(workspaces) => {
+                if let Some(package_dir) = workspaces.await?.get(&package_name) {
+                    packages.push(*package_dir);
+                }
+            }
+            ResolveModules::Fallback(_) => {
+                // handled below, after all other locations were checked
+            }
             ResolveModules::Registry(_, _) => todo!(),
         }
     }
+    if packages.is_empty() {
+        for resolve_modules in &options.modules {
+            if let ResolveModules::Fallback(context) = resolve_modules {
+                let package_dir = context.join(&package_name);
+                if dir_exists(package_dir, &mut references).await?.is_some() {
+                    packages.push(package_dir.resolve().await?);
+                }
+            }
+        }
+    }
     Ok(FindPackageResultVc::cell(FindPackageResult {
         packages,
         references,
@@ -648,9 +716,122 @@ pub async fn resolve(
 ) -> Result<ResolveResultVc> {
     let raw_result = resolve_internal(context, request, options);
     let result = handle_resolve_plugins(context, request, options, raw_result);
+    let result = handle_types_only_result(context, request, options, result);
+    let result = add_affecting_paths(options, result);
     Ok(result)
 }
 
+#[turbo_tasks::function]
+async fn add_affecting_paths(
+    options: ResolveOptionsVc,
+    result: ResolveResultVc,
+) -> Result<ResolveResultVc> {
+    let affecting_paths = &options.await?.affecting_paths;
+    if affecting_paths.is_empty() {
+        return Ok(result);
+    }
+    Ok(result.with_references(
+        affecting_paths
+            .iter()
+            .map(|path| AffectingResolvingAssetReferenceVc::new(*path).into())
+            .collect(),
+    ))
+}
+
+/// File extensions of files which only contain type declarations and no
+/// runtime code.
+const TYPES_ONLY_EXTENSIONS: [&str; 3] = [".d.ts", ".d.mts", ".d.cts"];
+
+/// Reports results resolving to types-only files and replaces them with a
+/// runtime stand-in when configured. Such results commonly come from importing
+/// `@types/*` packages at runtime and would otherwise produce a module which
+/// throws when it's evaluated.
+#[turbo_tasks::function]
+async fn handle_types_only_result(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    options: ResolveOptionsVc,
+    result: ResolveResultVc,
+) -> Result<ResolveResultVc> {
+    let options_value = options.await?;
+    let Some(types_only) = &options_value.types_only else {
+        return Ok(result);
+    };
+
+    let mut changed = false;
+    let result_value = result.await?;
+
+    let mut new_primary = Vec::new();
+    let mut new_references = Vec::new();
+
+    for primary in result_value.primary.iter() {
+        if let PrimaryResolveResult::Asset(asset) = primary {
+            let path = asset.ident().path();
+            let path_value = path.await?;
+            if TYPES_ONLY_EXTENSIONS
+                .iter()
+                .any(|ext| path_value.path.ends_with(ext))
+            {
+                let mut error_message = format!(
+                    "The request resolved to {}, which only contains type declarations and no \
+                     runtime code. Types-only packages (like @types/*) can only be imported with \
+                     `import type`.",
+                    path.to_string().await?
+                );
+                if let TypesOnlyResolve::StandIn(mapping) = types_only {
+                    let stand_in = import_mapping_to_result(*mapping, context, request).await?;
+                    if let Some(stand_in) =
+                        resolve_import_map_result(&stand_in, context, context, request, options)
+                            .await?
+                    {
+                        error_message.push_str(" A runtime stand-in is used instead.");
+                        let stand_in = stand_in.await?;
+                        changed = true;
+                        new_primary.extend(stand_in.primary.iter().cloned());
+                        new_references.extend(stand_in.references.iter().copied());
+                        emit_types_only_issue(context, request, options, error_message);
+                        continue;
+                    }
+                }
+                emit_types_only_issue(context, request, options, error_message);
+            }
+        }
+        new_primary.push(primary.clone());
+    }
+
+    if !changed {
+        return Ok(result);
+    }
+
+    let mut references = result_value.references.clone();
+    references.append(&mut new_references);
+
+    Ok(ResolveResult {
+        primary: new_primary,
+        references,
+    }
+    .cell())
+}
+
+fn emit_types_only_issue(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    options: ResolveOptionsVc,
+    error_message: String,
+) {
+    let issue: ResolvingIssueVc = ResolvingIssue {
+        severity: IssueSeverity::Warning.cell(),
+        request_type: "types-only module".to_string(),
+        request,
+        context,
+        resolve_options: options,
+        error_message: Some(error_message),
+        source: OptionIssueSourceVc::none(),
+    }
+    .into();
+    issue.as_issue().emit();
+}
+
 #[turbo_tasks::function]
 async fn handle_resolve_plugins(
     context: FileSystemPathVc,
@@ -745,6 +926,13 @@ async fn resolve_internal(
         }
     }
 
+    // Apply externals if provided
+    if let Some(externals) = &options_value.externals {
+        if let Some(result) = *externals.lookup(request).await? {
+            return Ok(result);
+        }
+    }
+
     let request_value = request.await?;
     let result = match &*request_value {
         Request::Dynamic => ResolveResult::unresolveable().into(),
@@ -803,6 +991,20 @@ async fn resolve_internal(
                 ));
             }
 
+            if let (Some(enforce_extension), Pattern::Constant(path)) =
+                (options_value.enforce_extension, path)
+            {
+                check_enforced_extension(
+                    context,
+                    request,
+                    options,
+                    path,
+                    enforce_extension,
+                    &results,
+                )
+                .await?;
+            }
+
             merge_results(results)
         }
         Request::Module {
@@ -833,14 +1035,34 @@ async fn resolve_internal(
 
             resolve_internal(context.root(), relative, options)
         }
-        Request::Windows { path: _ } => {
+        Request::Windows { path } => {
+            // Absolute Windows paths (including UNC and verbatim paths) are
+            // resolved when they point into the root of the file system.
+            if let Pattern::Constant(path) = path {
+                if let Some(disk_fs) = DiskFileSystemVc::resolve_from(context.fs()).await? {
+                    if let Some(relative_path) =
+                        windows_path_relative_to(path, &disk_fs.await?.root)
+                    {
+                        let relative = RequestVc::relative(
+                            Value::new(format!("./{relative_path}").into()),
+                            true,
+                        );
+                        return Ok(resolve_internal(context.root(), relative, options));
+                    }
+                }
+            }
+
             let issue: ResolvingIssueVc = ResolvingIssue {
                 severity: IssueSeverity::Error.cell(),
-                request_type: "windows import: not implemented yet".to_string(),
+                request_type: "windows import".to_string(),
                 request,
                 context,
                 resolve_options: options,
-                error_message: Some("windows imports are not implemented yet".to_string()),
+                error_message: Some(
+                    "windows imports are only supported for absolute paths within the root of the \
+                     project"
+                        .to_string(),
+                ),
                 source: OptionIssueSourceVc::none(),
             }
             .into();
@@ -877,8 +1099,17 @@ async fn resolve_internal(
             remainder,
         } => ResolveResult::primary(PrimaryResolveResult::OriginalReferenceTypeExternal(
             format!("{}{}", protocol, remainder),
+            ExternalType::CommonJs,
         ))
         .into(),
+        Request::Builtin { protocol, name } => {
+            resolve_builtin(context, request, options, protocol, name).await?
+        }
+        Request::DataUri {
+            media_type,
+            base64,
+            data,
+        } => resolve_data_uri(context, request, options, media_type, *base64, data).await?,
         Request::Unknown { path } => {
             let issue: ResolvingIssueVc = ResolvingIssue {
                 severity: IssueSeverity::Error.cell(),
@@ -968,11 +1199,72 @@ async fn resolve_into_folder(
                     );
                 }
             }
+            ResolveIntoPackage::TypesVersions(typescript_version) => {
+                if let Some(result) =
+                    resolve_types_versions(package_path, options, typescript_version, None).await?
+                {
+                    return Ok(result);
+                }
+            }
         }
     }
     Ok(ResolveResult::unresolveable().into())
 }
 
+/// Resolves a `subpath` of a package through its `typesVersions` field. When
+/// `subpath` is `None`, the main type declarations of the package are
+/// resolved. Returns `None` when the field doesn't apply to the request.
+async fn resolve_types_versions(
+    package_path: FileSystemPathVc,
+    options: ResolveOptionsVc,
+    typescript_version: &str,
+    subpath: Option<&str>,
+) -> Result<Option<ResolveResultVc>> {
+    let package_json_path = package_path.join("package.json");
+    let read = read_package_json(package_json_path).await?;
+    let Some(package_json) = &*read else {
+        return Ok(None);
+    };
+    // TypeScript ignores typesVersions when an exports field exists.
+    if !package_json["exports"].is_null() {
+        return Ok(None);
+    }
+    let Some(paths) = types_versions::types_versions_paths(package_json, typescript_version) else {
+        return Ok(None);
+    };
+    let path = match subpath {
+        Some(subpath) => subpath.to_string(),
+        None => {
+            let main = package_json["types"]
+                .as_str()
+                .or_else(|| package_json["typings"].as_str())
+                .unwrap_or("index.d.ts");
+            let Some(main) = normalize_path(main) else {
+                return Ok(None);
+            };
+            main
+        }
+    };
+    let Some(targets) = types_versions::map_types_versions_path(paths, &path) else {
+        return Ok(None);
+    };
+    let results = targets
+        .iter()
+        .filter_map(|target| normalize_path(target))
+        .map(|target| {
+            let request = RequestVc::relative(Value::new(format!("./{target}").into()), false);
+            resolve_internal(package_path, request, options)
+        })
+        .collect();
+    let result = ResolveResultVc::select_first(results).await?;
+    if result.is_unresolveable() {
+        return Ok(None);
+    }
+    let mut result = result.clone_value();
+    result.add_reference(AffectingResolvingAssetReferenceVc::new(package_json_path).into());
+    Ok(Some(result.cell()))
+}
+
 async fn resolve_module_request(
     context: FileSystemPathVc,
     options: ResolveOptionsVc,
@@ -990,7 +1282,7 @@ async fn resolve_module_request(
                 {
                     let read = read_package_json(*package_json_path).await?;
                     if let Some(package_json) = &*read {
-                        if let Some(field_value) = package_json[field].as_object() {
+                        if let Some(field_value) = alias_field_value(package_json, field) {
                             let package_path = package_json_path.parent();
                             let full_pattern =
                                 Pattern::concat([module.to_string().into(), path.clone()]);
@@ -1040,7 +1332,7 @@ async fn resolve_module_request(
     // resolve packages. A request to "foo/bar" might resolve to either
     // "[baseUrl]/foo/bar" or "[baseUrl]/node_modules/foo/bar", and we'll need to
     // try both.
-    for package_path in &result.packages {
+    'packages: for package_path in &result.packages {
         if is_match {
             results.push(resolve_into_folder(*package_path, options).await?);
         }
@@ -1079,6 +1371,23 @@ async fn resolve_module_request(
                             break;
                         }
                     }
+                    ResolveIntoPackage::TypesVersions(typescript_version) => {
+                        let subpath = path.clone().into_string();
+                        if let Some(subpath) = subpath.as_deref().and_then(|s| s.strip_prefix('/'))
+                        {
+                            if let Some(result) = resolve_types_versions(
+                                *package_path,
+                                options,
+                                typescript_version,
+                                Some(subpath),
+                            )
+                            .await?
+                            {
+                                results.push(result);
+                                continue 'packages;
+                            }
+                        }
+                    }
                 }
             }
             let mut new_pat = path.clone();
@@ -1111,6 +1420,29 @@ async fn resolve_import_map_result(
                 && context.resolve().await? == original_context
             {
                 None
+            } else if let Some(chain) = &*find_alias_cycle(
+                context,
+                request,
+                original_context,
+                original_request,
+                options,
+            )
+            .await?
+            {
+                let issue: ResolvingIssueVc = ResolvingIssue {
+                    severity: IssueSeverity::Error.cell(),
+                    request_type: "aliased request".to_string(),
+                    request: original_request,
+                    context: original_context,
+                    resolve_options: options,
+                    error_message: Some(format!(
+                        "The import map contains a cycle of aliases: {chain}"
+                    )),
+                    source: OptionIssueSourceVc::none(),
+                }
+                .into();
+                issue.as_issue().emit();
+                None
             } else {
                 Some(resolve_internal(context, request, options))
             }
@@ -1137,6 +1469,136 @@ async fn resolve_import_map_result(
     })
 }
 
+/// Reports relative requests which violate the [EnforceExtension] policy.
+/// `results` are the results for the request as written, followed by the
+/// results for every extension of the options.
+async fn check_enforced_extension(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    options: ResolveOptionsVc,
+    path: &str,
+    enforce_extension: EnforceExtension,
+    results: &[ResolveResultVc],
+) -> Result<()> {
+    let Some((exact, with_extensions)) = results.split_first() else {
+        return Ok(());
+    };
+    let options_value = options.await?;
+    let error_message = match enforce_extension {
+        EnforceExtension::Required => {
+            if !*exact.is_unresolveable().await? {
+                return Ok(());
+            }
+            let mut found = None;
+            for (extension, result) in options_value.extensions.iter().zip(with_extensions) {
+                if !*result.is_unresolveable().await? {
+                    found = Some(extension);
+                    break;
+                }
+            }
+            let Some(extension) = found else {
+                return Ok(());
+            };
+            format!(
+                "Relative requests must include the file extension. Use \"{path}{extension}\" \
+                 instead."
+            )
+        }
+        EnforceExtension::Forbidden => {
+            let Some(extension) = options_value
+                .extensions
+                .iter()
+                .find(|extension| path.ends_with(extension.as_str()))
+            else {
+                return Ok(());
+            };
+            if *exact.is_unresolveable().await? {
+                return Ok(());
+            }
+            format!(
+                "Relative requests must not include the file extension. Use \"{}\" instead.",
+                &path[..path.len() - extension.len()]
+            )
+        }
+    };
+    let issue: ResolvingIssueVc = ResolvingIssue {
+        severity: IssueSeverity::Error.cell(),
+        request_type: "relative request".to_string(),
+        request,
+        context,
+        resolve_options: options,
+        error_message: Some(error_message),
+        source: OptionIssueSourceVc::none(),
+    }
+    .into();
+    issue.as_issue().emit();
+    Ok(())
+}
+
+/// Follows the aliases of the import maps, starting at `request`, and returns
+/// the chain of requests when they lead back to `original_request`. Resolving
+/// such an alias would never finish.
+///
+/// This is a function, so the aliases are only followed once for every
+/// aliased request, and not again on every alias hop of every resolve.
+#[turbo_tasks::function]
+async fn find_alias_cycle(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    original_context: FileSystemPathVc,
+    original_request: RequestVc,
+    options: ResolveOptionsVc,
+) -> Result<OptionStringVc> {
+    let options = options.await?;
+    let import_maps = [options.import_map, options.fallback_import_map]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if import_maps.is_empty() {
+        return Ok(OptionStringVc::cell(None));
+    }
+
+    let mut visited = HashSet::new();
+    // Each entry holds the chain of requests which led to it.
+    let mut stack = vec![(
+        context.resolve().await?,
+        request.resolve().await?,
+        vec![original_request, request],
+    )];
+    while let Some((context, request, chain)) = stack.pop() {
+        if !visited.insert((context, request)) {
+            continue;
+        }
+        for import_map in import_maps.iter() {
+            let mut results = vec![import_map.lookup(context, request).await?.clone_value()];
+            while let Some(result) = results.pop() {
+                match result {
+                    ImportMapResult::Alias(alias, alias_context) => {
+                        let alias = alias.resolve().await?;
+                        let alias_context = alias_context.unwrap_or(context).resolve().await?;
+                        let mut chain = chain.clone();
+                        chain.push(alias);
+                        if alias == original_request && alias_context == original_context {
+                            let mut requests = Vec::with_capacity(chain.len());
+                            for request in chain {
+                                requests.push(request.to_string().await?.clone_value());
+                            }
+                            return Ok(OptionStringVc::cell(Some(requests.join(" -> "))));
+                        }
+                        // An alias to itself is not followed during resolving.
+                        if alias != request || alias_context != context {
+                            stack.push((alias_context, alias, chain));
+                        }
+                    }
+                    ImportMapResult::Alternatives(list) => results.extend(list),
+                    ImportMapResult::Result(_) | ImportMapResult::NoEntry => {}
+                }
+            }
+        }
+    }
+    Ok(OptionStringVc::cell(None))
+}
+
 fn resolve_import_map_result_boxed<'a>(
     result: &'a ImportMapResult,
     context: FileSystemPathVc,
@@ -1150,6 +1612,116 @@ fn resolve_import_map_result_boxed<'a>(
     })
 }
 
+/// Resolves a builtin request like `node:path`. Without polyfills, builtins
+/// are left to the runtime as externals.
+async fn resolve_builtin(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    options: ResolveOptionsVc,
+    protocol: &str,
+    name: &str,
+) -> Result<ResolveResultVc> {
+    let Some(polyfills) = options.await?.builtin_polyfills else {
+        return Ok(
+            ResolveResult::primary(PrimaryResolveResult::OriginalReferenceTypeExternal(
+                format!("{protocol}{name}"),
+                ExternalType::CommonJs,
+            ))
+            .into(),
+        );
+    };
+
+    let polyfill_request = RequestVc::parse_string(name.to_string());
+    let result = polyfills.lookup(context, polyfill_request).await?;
+    if let Some(result) =
+        resolve_import_map_result(&result, context, context, request, options).await?
+    {
+        return Ok(result);
+    }
+
+    let issue: ResolvingIssueVc = ResolvingIssue {
+        severity: IssueSeverity::Error.cell(),
+        request_type: format!("builtin `{protocol}{name}`"),
+        request,
+        context,
+        resolve_options: options,
+        error_message: Some(format!(
+            "The builtin module `{protocol}{name}` is not available in this environment and no \
+             polyfill is configured for `{name}`."
+        )),
+        source: OptionIssueSourceVc::none(),
+    }
+    .into();
+    issue.as_issue().emit();
+    Ok(ResolveResult::unresolveable().into())
+}
+
+/// Decodes a `data:` URI into a virtual source. The file extension of the
+/// virtual source is derived from the media type, so module rules apply to it
+/// the same way as they do for real files.
+async fn resolve_data_uri(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    options: ResolveOptionsVc,
+    media_type: &str,
+    base64: bool,
+    data: &str,
+) -> Result<ResolveResultVc> {
+    let content = if base64 {
+        STANDARD
+            .decode(data)
+            .map_err(|err| anyhow!("invalid base64 data: {err}"))
+    } else {
+        Ok(urlencoding::decode_binary(data.as_bytes()).into_owned())
+    };
+    let content = match content {
+        Ok(content) => content,
+        Err(err) => {
+            let issue: ResolvingIssueVc = ResolvingIssue {
+                severity: IssueSeverity::Error.cell(),
+                request_type: "data uri".to_string(),
+                request,
+                context,
+                resolve_options: options,
+                error_message: Some(err.to_string()),
+                source: OptionIssueSourceVc::none(),
+            }
+            .into();
+            issue.as_issue().emit();
+            return Ok(ResolveResult::unresolveable().into());
+        }
+    };
+
+    let extension = match media_type {
+        "text/javascript" | "application/javascript" | "text/ecmascript" => ".mjs",
+        "application/typescript" | "text/typescript" => ".mts",
+        "application/json" => ".json",
+        "text/css" => ".css",
+        "image/svg+xml" => ".svg",
+        "image/png" => ".png",
+        "image/jpeg" => ".jpg",
+        "image/gif" => ".gif",
+        "image/webp" => ".webp",
+        _ => "",
+    };
+    let hash = encode_hex(hash_xxh3_hash64(&content));
+    let path = context.join(&format!("__data_uri__/{hash}{extension}"));
+    let source = VirtualSourceVc::new(path, File::from(content).into());
+    Ok(ResolveResult::asset(source.into()).into())
+}
+
+/// Looks up an alias field in a package.json. The field is given as a path of
+/// keys to support nested fields.
+fn alias_field_value<'a>(
+    package_json: &'a JsonValue,
+    field: &[String],
+) -> Option<&'a serde_json::Map<String, JsonValue>> {
+    field
+        .iter()
+        .try_fold(package_json, |value, key| value.get(key))?
+        .as_object()
+}
+
 async fn resolve_alias_field_result(
     result: &JsonValue,
     refs: Vec<AssetReferenceVc>,
@@ -1157,7 +1729,7 @@ async fn resolve_alias_field_result(
     resolve_options: ResolveOptionsVc,
     issue_context: FileSystemPathVc,
     issue_request: &str,
-    field_name: &str,
+    field: &[String],
 ) -> Result<ResolveResultVc> {
     if result.as_bool() == Some(false) {
         return Ok(
@@ -1175,7 +1747,7 @@ async fn resolve_alias_field_result(
     let issue: ResolvingIssueVc = ResolvingIssue {
         severity: IssueSeverity::Error.cell(),
         context: issue_context,
-        request_type: format!("alias field ({field_name})"),
+        request_type: format!("alias field ({})", field.join(".")),
         request: RequestVc::parse(Value::new(Pattern::Constant(issue_request.to_string()))),
         resolve_options,
         error_message: Some(format!("invalid alias field value: {}", result)),
@@ -1206,7 +1778,7 @@ async fn resolved(
                 {
                     let read = read_package_json(*package_json_path).await?;
                     if let Some(package_json) = &*read {
-                        if let Some(field_value) = package_json[field].as_object() {
+                        if let Some(field_value) = alias_field_value(package_json, field) {
                             let package_path = package_json_path.parent();
                             if let Some(rel_path) =
                                 package_path.await?.get_relative_path_to(&*fs_path.await?)
@@ -1289,13 +1861,22 @@ fn handle_exports_imports_field(
     }
     {
         let mut duplicates_set = HashSet::new();
-        results.retain(|item| duplicates_set.insert(*item));
+        results.retain(|item| duplicates_set.insert(item.clone()));
     }
     let mut resolved_results = Vec::new();
-    for path in results {
-        if let Some(path) = normalize_path(path) {
-            let request = RequestVc::relative(Value::new(format!("./{}", path).into()), false);
-            resolved_results.push(resolve_internal(package_path, request, options));
+    for fallbacks in results {
+        let mut fallback_results = Vec::new();
+        for path in fallbacks {
+            if let Some(path) = normalize_path(path) {
+                let request = RequestVc::relative(Value::new(format!("./{}", path).into()), false);
+                fallback_results.push(resolve_internal(package_path, request, options));
+            }
+        }
+        // The first target of an array of alternatives which resolves wins.
+        match fallback_results.len() {
+            0 => {}
+            1 => resolved_results.extend(fallback_results),
+            _ => resolved_results.push(ResolveResultVc::select_first(fallback_results)),
         }
     }
     // other options do not apply anymore when an exports field exist
@@ -1,18 +1,25 @@
 use std::{collections::BTreeMap, future::Future, pin::Pin};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use mime::Mime;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    debug::ValueDebugFormat, primitives::StringVc, trace::TraceRawVcs, TryJoinIterExt, Value,
-    ValueToString, ValueToStringVc,
+    debug::ValueDebugFormat,
+    primitives::{RegexVc, StringVc},
+    trace::TraceRawVcs,
+    TryJoinIterExt, Value, ValueToString, ValueToStringVc,
 };
-use turbo_tasks_fs::{glob::GlobVc, FileSystemPathVc};
+use turbo_tasks_fs::{glob::GlobVc, FileSystemPath, FileSystemPathVc};
 
 use super::{
-    alias_map::{AliasMap, AliasTemplate},
-    AliasPattern, PrimaryResolveResult, ResolveResult, ResolveResultVc,
+    alias_map::{replace_wildcards, AliasMap, AliasTemplate},
+    AliasPattern, PrimaryResolveResult, ResolveResult, ResolveResultOptionVc, ResolveResultVc,
+};
+use crate::resolve::{
+    parse::{Request, RequestVc},
+    plugin::ResolvePluginVc,
+    workspaces::WorkspacePackagesVc,
 };
-use crate::resolve::{parse::RequestVc, plugin::ResolvePluginVc};
 
 #[turbo_tasks::value(shared)]
 #[derive(Hash, Debug)]
@@ -28,6 +35,14 @@ pub enum ResolveModules {
     Nested(FileSystemPathVc, Vec<String>),
     /// look into that directory
     Path(FileSystemPathVc),
+    /// look into that directory, but only when no package was found in any of
+    /// the other locations. The directory may live outside of the project
+    /// root (e.g. a global node_modules or a vendored directory), as long as
+    /// its filesystem is watched to invalidate when files in it change.
+    Fallback(FileSystemPathVc),
+    /// look up packages by name in the workspace packages of a monorepo, which
+    /// resolves them to their source directories directly
+    Workspaces(WorkspacePackagesVc),
     /// lookup versions based on lockfile in the registry filesystem
     /// registry filesystem is assumed to have structure like
     /// @scope/module/version/<path-in-package>
@@ -69,6 +84,12 @@ pub enum ResolveIntoPackage {
     /// [module]: https://esbuild.github.io/api/#main-fields
     /// [browser]: https://esbuild.github.io/api/#main-fields
     MainField(String),
+    /// Using the [typesVersions] field to redirect type declarations for the
+    /// given TypeScript version. Only applies to packages without an exports
+    /// field.
+    ///
+    /// [typesVersions]: https://www.typescriptlang.org/docs/handbook/declaration-files/publishing.html#version-selection-with-typesversions
+    TypesVersions(String),
     /// Default behavior of using the index.js file at the root of the package.
     Default(String),
 }
@@ -76,8 +97,10 @@ pub enum ResolveIntoPackage {
 // The different ways to resolve a request withing a package
 #[derive(TraceRawVcs, Hash, PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub enum ResolveInPackage {
-    /// Using a alias field which allows to map requests
-    AliasField(String),
+    /// Using a alias field which allows to map requests. The field is given as
+    /// a path of keys into package.json, e.g. `["browser"]` or
+    /// `["react-native"]`, and can be nested.
+    AliasField(Vec<String>),
     /// Using the [imports] field.
     ///
     /// [imports]: https://nodejs.org/api/packages.html#imports
@@ -87,10 +110,105 @@ pub enum ResolveInPackage {
     },
 }
 
+/// How an external module is consumed at runtime.
+#[turbo_tasks::value(shared)]
+#[derive(Hash, Debug, Copy, Clone)]
+pub enum ExternalType {
+    /// Loaded with `require()`.
+    CommonJs,
+    /// Loaded as an ECMAScript module, i.e. ESM interop is applied even when
+    /// it is required from CommonJS.
+    EcmaScriptModule,
+    /// Read from a global variable with the external's name, e.g. `React`.
+    Global,
+    /// Loaded from a URL at runtime with a native dynamic `import()`, e.g. a
+    /// module served by a CDN.
+    Url,
+}
+
+/// Matches the requests that should be treated as externals.
+#[derive(
+    TraceRawVcs, Hash, PartialEq, Eq, Clone, Debug, Serialize, Deserialize, ValueDebugFormat,
+)]
+pub enum ExternalMatcher {
+    /// Matches the full request (e.g. `@aws-sdk/client-s3`) against a glob.
+    Glob(GlobVc),
+    /// Matches the full request against a regular expression. Created with
+    /// [ExternalMatcher::regex].
+    Regex(RegexVc),
+}
+
+impl ExternalMatcher {
+    /// Compiles `regex` into a matcher for the full request.
+    pub fn regex(regex: &str) -> Result<Self> {
+        let regex = regex::Regex::new(regex)
+            .with_context(|| format!("invalid externals regex: {regex}"))?;
+        Ok(ExternalMatcher::Regex(RegexVc::cell(regex)))
+    }
+
+    async fn matches(&self, request: &str) -> Result<bool> {
+        Ok(match self {
+            ExternalMatcher::Glob(glob) => glob.await?.execute(request),
+            ExternalMatcher::Regex(regex) => regex.await?.is_match(request),
+        })
+    }
+}
+
+/// A rule which marks all requests matching `matcher` as external.
+#[derive(
+    TraceRawVcs, Hash, PartialEq, Eq, Clone, Debug, Serialize, Deserialize, ValueDebugFormat,
+)]
+pub struct ExternalRule {
+    pub matcher: ExternalMatcher,
+    /// The request to use at runtime (or the name of the global variable). When
+    /// not set, the original request is used.
+    pub name: Option<String>,
+    pub ty: ExternalType,
+}
+
+/// A list of rules which make package requests resolve to externals, without
+/// the need to add an [ImportMap] entry for every package.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Default)]
+pub struct Externals {
+    pub rules: Vec<ExternalRule>,
+}
+
+#[turbo_tasks::value_impl]
+impl ExternalsVc {
+    /// Returns the external result for the first rule matching `request`, if
+    /// any. Only module requests are considered.
+    #[turbo_tasks::function]
+    pub async fn lookup(self, request: RequestVc) -> Result<ResolveResultOptionVc> {
+        let this = self.await?;
+        let request = request.await?;
+        if !matches!(&*request, Request::Module { .. }) {
+            return Ok(ResolveResultOptionVc::none());
+        }
+        let Some(request) = request.request() else {
+            return Ok(ResolveResultOptionVc::none());
+        };
+        for rule in this.rules.iter() {
+            if rule.matcher.matches(&request).await? {
+                let name = rule.name.clone().unwrap_or_else(|| request.clone());
+                return Ok(ResolveResultOptionVc::some(
+                    ResolveResult::primary(PrimaryResolveResult::OriginalReferenceTypeExternal(
+                        name, rule.ty,
+                    ))
+                    .cell(),
+                ));
+            }
+        }
+        Ok(ResolveResultOptionVc::none())
+    }
+}
+
 #[turbo_tasks::value(shared)]
 #[derive(Clone)]
 pub enum ImportMapping {
-    External(Option<String>),
+    /// An external module, which is loaded at runtime as described by the
+    /// [ExternalType]. When no name is given, the original request is used.
+    External(Option<String>, ExternalType),
     /// An already resolved result that will be returned directly.
     Direct(ResolveResultVc),
     /// A request alias that will be resolved first, and fall back to resolving
@@ -125,19 +243,17 @@ impl ImportMapping {
 impl AliasTemplate for ImportMappingVc {
     type Output<'a> = Pin<Box<dyn Future<Output = Result<ImportMappingVc>> + Send + 'a>>;
 
-    fn replace<'a>(&'a self, capture: &'a str) -> Self::Output<'a> {
+    fn replace<'a>(&'a self, captures: &[&'a str]) -> Self::Output<'a> {
+        let captures = captures.to_vec();
         Box::pin(async move {
             let this = &*self.await?;
             Ok(match this {
-                ImportMapping::External(name) => {
-                    if let Some(name) = name {
-                        ImportMapping::External(Some(name.clone().replace('*', capture)))
-                    } else {
-                        ImportMapping::External(None)
-                    }
-                }
+                ImportMapping::External(name, ty) => ImportMapping::External(
+                    name.as_ref().map(|name| replace_wildcards(name, &captures)),
+                    *ty,
+                ),
                 ImportMapping::PrimaryAlternative(name, context) => {
-                    ImportMapping::PrimaryAlternative(name.clone().replace('*', capture), *context)
+                    ImportMapping::PrimaryAlternative(replace_wildcards(name, &captures), *context)
                 }
                 ImportMapping::Direct(_) | ImportMapping::Ignore | ImportMapping::Empty => {
                     this.clone()
@@ -145,12 +261,13 @@ impl AliasTemplate for ImportMappingVc {
                 ImportMapping::Alternatives(alternatives) => ImportMapping::Alternatives(
                     alternatives
                         .iter()
-                        .map(|mapping| mapping.replace(capture))
+                        .map(|mapping| mapping.replace(&captures))
                         .try_join()
                         .await?,
                 ),
                 ImportMapping::Dynamic(replacement) => {
-                    (*replacement.replace(capture).await?).clone()
+                    let captures = captures.iter().map(|capture| capture.to_string()).collect();
+                    (*replacement.replace(captures).await?).clone()
                 }
             }
             .cell())
@@ -162,12 +279,18 @@ impl AliasTemplate for ImportMappingVc {
 #[derive(Clone, Default)]
 pub struct ImportMap {
     map: AliasMap<ImportMappingVc>,
+    /// Aliases which only apply to requests from within a directory. They take
+    /// precedence over the other aliases, and the innermost scope wins.
+    scopes: Vec<(FileSystemPathVc, AliasMap<ImportMappingVc>)>,
 }
 
 impl ImportMap {
     /// Creates a new import map.
     pub fn new(map: AliasMap<ImportMappingVc>) -> ImportMap {
-        Self { map }
+        Self {
+            map,
+            scopes: Vec::new(),
+        }
     }
 
     /// Creates a new empty import map.
@@ -177,8 +300,21 @@ impl ImportMap {
 
     /// Extends the import map with another import map.
     pub fn extend(&mut self, other: &ImportMap) {
-        let Self { map } = other.clone();
+        let Self { map, scopes } = other.clone();
         self.map.extend(map);
+        self.scopes.extend(scopes);
+    }
+
+    /// Returns the patterns of all aliases in the import map, excluding scoped
+    /// aliases.
+    pub fn patterns(&self) -> impl Iterator<Item = AliasPattern> + '_ {
+        (&self.map).into_iter().map(|(pattern, _)| pattern)
+    }
+
+    /// Inserts aliases which only apply to requests from within the `scope`
+    /// directory.
+    pub fn insert_scope(&mut self, scope: FileSystemPathVc, map: AliasMap<ImportMappingVc>) {
+        self.scopes.push((scope, map));
     }
 
     /// Inserts an alias into the import map.
@@ -247,10 +383,69 @@ impl ImportMapVc {
     }
 }
 
+/// Matches resolved files independent of their location.
+#[derive(
+    TraceRawVcs, Hash, PartialEq, Eq, Clone, Debug, Serialize, Deserialize, ValueDebugFormat,
+)]
+pub enum ResolvedMatcher {
+    /// Matches every file within the package with the given name, i.e. within
+    /// a `node_modules/<name>` directory.
+    Package(String),
+    /// Matches every file with the given content type, e.g. `image/png`, or a
+    /// whole group of content types, e.g. `image/*`. The content type is
+    /// guessed from the extension, without reading the file. Native Node.js
+    /// addons (`.node`) have the content type `application/node`.
+    ContentType(String),
+}
+
+impl ResolvedMatcher {
+    fn matches(&self, path: &FileSystemPath) -> bool {
+        match self {
+            ResolvedMatcher::Package(name) => package_name_of(&path.path) == Some(name.as_str()),
+            ResolvedMatcher::ContentType(content_type) => {
+                let actual = guess_content_type(path);
+                match content_type.strip_suffix("/*") {
+                    Some(ty) => actual.type_() == ty,
+                    None => actual.essence_str() == content_type,
+                }
+            }
+        }
+    }
+}
+
+/// Returns the name of the package the path is in, according to the last
+/// `node_modules` directory in the path.
+fn package_name_of(path: &str) -> Option<&str> {
+    let index = path
+        .match_indices("node_modules/")
+        .map(|(index, _)| index)
+        .filter(|index| *index == 0 || path[..*index].ends_with('/'))
+        .last()?;
+    let rest = &path[index + "node_modules/".len()..];
+    let end = if rest.starts_with('@') {
+        let scope_end = rest.find('/')?;
+        scope_end + 1 + rest[scope_end + 1..].find('/')?
+    } else {
+        rest.find('/')?
+    };
+    Some(&rest[..end])
+}
+
+fn guess_content_type(path: &FileSystemPath) -> Mime {
+    match path.extension() {
+        Some("node") => "application/node".parse().unwrap(),
+        Some(extension) => mime_guess::from_ext(extension).first_or_octet_stream(),
+        None => mime::APPLICATION_OCTET_STREAM,
+    }
+}
+
 #[turbo_tasks::value(shared)]
 #[derive(Clone, Default)]
 pub struct ResolvedMap {
     pub by_glob: Vec<(FileSystemPathVc, GlobVc, ImportMappingVc)>,
+    /// Mappings for resolved files which match independent of their location.
+    /// These are checked after the glob mappings.
+    pub by_matcher: Vec<(ResolvedMatcher, ImportMappingVc)>,
 }
 
 #[turbo_tasks::value(shared)]
@@ -262,23 +457,30 @@ pub enum ImportMapResult {
     NoEntry,
 }
 
-async fn import_mapping_to_result(
+pub(super) async fn import_mapping_to_result(
     mapping: ImportMappingVc,
     context: FileSystemPathVc,
     request: RequestVc,
 ) -> Result<ImportMapResult> {
     Ok(match &*mapping.await? {
         ImportMapping::Direct(result) => ImportMapResult::Result(*result),
-        ImportMapping::External(name) => ImportMapResult::Result(
-            ResolveResult::primary_with_references(
-                name.as_ref().map_or_else(
-                    || PrimaryResolveResult::OriginalReferenceExternal,
-                    |req| PrimaryResolveResult::OriginalReferenceTypeExternal(req.to_string()),
-                ),
-                Vec::new(),
+        ImportMapping::External(name, ty) => {
+            let primary = match (name, ty) {
+                (None, ExternalType::CommonJs) => PrimaryResolveResult::OriginalReferenceExternal,
+                (Some(name), _) => {
+                    PrimaryResolveResult::OriginalReferenceTypeExternal(name.clone(), *ty)
+                }
+                (None, _) => match request.await?.request() {
+                    Some(request) => {
+                        PrimaryResolveResult::OriginalReferenceTypeExternal(request, *ty)
+                    }
+                    None => PrimaryResolveResult::OriginalReferenceExternal,
+                },
+            };
+            ImportMapResult::Result(
+                ResolveResult::primary_with_references(primary, Vec::new()).into(),
             )
-            .into(),
-        ),
+        }
         ImportMapping::Ignore => {
             ImportMapResult::Result(ResolveResult::primary(PrimaryResolveResult::Ignore).into())
         }
@@ -360,6 +562,30 @@ impl ImportMapVc {
         let this = self.await?;
         // TODO lookup pattern
         if let Some(request_string) = request.await?.request() {
+            if !this.scopes.is_empty() {
+                let context_value = context.await?;
+                let mut scopes = Vec::new();
+                for (scope, map) in this.scopes.iter() {
+                    let scope = scope.await?;
+                    if context_value.is_inside(&scope) || *context_value == *scope {
+                        scopes.push((scope.path.len(), map));
+                    }
+                }
+                // The innermost scope wins. The sort is stable, so the first
+                // of multiple equal scopes wins.
+                scopes.sort_by(|(a, _), (b, _)| b.cmp(a));
+                for (_, map) in scopes {
+                    if let Some(result) = map.lookup(&request_string).next() {
+                        return Ok(import_mapping_to_result(
+                            result.try_join_into_self().await?.into_owned(),
+                            context,
+                            request,
+                        )
+                        .await?
+                        .into());
+                    }
+                }
+            }
             if let Some(result) = this.map.lookup(&request_string).next() {
                 return Ok(import_mapping_to_result(
                     result.try_join_into_self().await?.into_owned(),
@@ -395,10 +621,44 @@ impl ResolvedMapVc {
                 }
             }
         }
+        for (matcher, mapping) in this.by_matcher.iter() {
+            if matcher.matches(&resolved) {
+                return Ok(import_mapping_to_result(*mapping, context, request)
+                    .await?
+                    .into());
+            }
+        }
         Ok(ImportMapResult::NoEntry.into())
     }
 }
 
+/// A policy for extensions in relative requests. Violations are reported as
+/// issues, but don't change the result of resolving.
+#[derive(
+    TraceRawVcs, Hash, PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, ValueDebugFormat,
+)]
+pub enum EnforceExtension {
+    /// Relative requests must include the extension, e.g. `./button.js`
+    /// instead of `./button`.
+    Required,
+    /// Relative requests must omit any of the resolve extensions, e.g.
+    /// `./button` instead of `./button.js`.
+    Forbidden,
+}
+
+/// What to do when a request resolves to a types-only file (e.g. `.d.ts`),
+/// which has no runtime code.
+#[derive(
+    TraceRawVcs, Hash, PartialEq, Eq, Clone, Debug, Serialize, Deserialize, ValueDebugFormat,
+)]
+pub enum TypesOnlyResolve {
+    /// Keep the result, but emit a warning.
+    Warn,
+    /// Emit a warning and replace the result with the given mapping, e.g. an
+    /// empty module.
+    StandIn(ImportMappingVc),
+}
+
 #[turbo_tasks::value(shared)]
 #[derive(Clone, Debug, Default)]
 pub struct ResolveOptions {
@@ -413,7 +673,22 @@ pub struct ResolveOptions {
     pub import_map: Option<ImportMapVc>,
     /// An import map to use when a request is otherwise unresolveable.
     pub fallback_import_map: Option<ImportMapVc>,
+    /// Rules to mark packages as externals. Applied after the import map.
+    pub externals: Option<ExternalsVc>,
+    /// An import map for builtin requests with a protocol like `node:path`,
+    /// which is looked up with the name of the builtin (e.g. `path`). When
+    /// set, builtins are expected to be polyfilled and a builtin without an
+    /// entry is an error. Otherwise builtins are treated as externals.
+    pub builtin_polyfills: Option<ImportMapVc>,
+    /// How to handle requests resolving to types-only files. `None` accepts
+    /// them silently, which is what resolving of types wants.
+    pub types_only: Option<TypesOnlyResolve>,
+    /// Whether relative requests must include or omit their extension.
+    pub enforce_extension: Option<EnforceExtension>,
     pub resolved_map: Option<ResolvedMapVc>,
+    /// Files these options were derived from, e.g. tsconfig.json files. They
+    /// are reported as affecting every result.
+    pub affecting_paths: Vec<FileSystemPathVc>,
     pub plugins: Vec<ResolvePluginVc>,
     pub placeholder_for_future_extensions: (),
 }
@@ -455,6 +730,43 @@ impl ResolveOptionsVc {
         );
         Ok(resolve_options.into())
     }
+
+    /// Returns a new [ResolveOptionsVc] with the aliases of `extension` added
+    /// to its import map. Embedders can use this to layer their own aliases
+    /// on top of the options of a context.
+    #[turbo_tasks::function]
+    pub fn with_import_map_extension(self, extension: ImportMapVc) -> Self {
+        self.with_extended_import_map(extension)
+    }
+
+    /// Returns a new [ResolveOptionsVc] with an alias added to its import map.
+    /// The `pattern` may contain wildcards, see [AliasPattern::parse].
+    #[turbo_tasks::function]
+    pub fn with_alias(self, pattern: &str, mapping: ImportMappingVc) -> Self {
+        let mut import_map = ImportMap::empty();
+        import_map.insert_alias(AliasPattern::parse(pattern), mapping);
+        self.with_extended_import_map(import_map.cell())
+    }
+
+    /// Returns a new [ResolveOptionsVc] which tries the given extensions
+    /// instead of the current ones.
+    #[turbo_tasks::function]
+    pub async fn with_extensions(self, extensions: Vec<String>) -> Result<Self> {
+        let mut resolve_options = self.await?.clone_value();
+        resolve_options.extensions = extensions;
+        Ok(resolve_options.into())
+    }
+
+    /// Returns a new [ResolveOptionsVc] which looks up modules in the given
+    /// directory before all other module locations.
+    #[turbo_tasks::function]
+    pub async fn with_modules_path(self, path: FileSystemPathVc) -> Result<Self> {
+        let mut resolve_options = self.await?.clone_value();
+        resolve_options
+            .modules
+            .insert(0, ResolveModules::Path(path));
+        Ok(resolve_options.into())
+    }
 }
 
 #[turbo_tasks::value(shared)]
@@ -473,6 +785,6 @@ pub async fn resolve_modules_options(options: ResolveOptionsVc) -> Result<Resolv
 
 #[turbo_tasks::value_trait]
 pub trait ImportMappingReplacement {
-    fn replace(&self, capture: &str) -> ImportMappingVc;
+    fn replace(&self, captures: Vec<String>) -> ImportMappingVc;
     fn result(&self, context: FileSystemPathVc, request: RequestVc) -> ImportMapResultVc;
 }
@@ -36,6 +36,21 @@ pub enum Request {
         protocol: String,
         remainder: String,
     },
+    /// A request to a builtin module with a protocol, e.g. `node:path`.
+    Builtin {
+        protocol: String,
+        name: String,
+    },
+    /// A `data:` URI, e.g. `data:text/javascript,export default 1`.
+    DataUri {
+        /// The media type without parameters, e.g. `text/javascript`.
+        media_type: String,
+        /// Whether the data is base64 encoded. Otherwise it's percent
+        /// encoded.
+        base64: bool,
+        /// The still encoded data.
+        data: String,
+    },
     Unknown {
         path: Pattern,
     },
@@ -75,6 +90,15 @@ impl Request {
                 protocol,
                 remainder,
             } => format!("{protocol}{remainder}"),
+            Request::Builtin { protocol, name } => format!("{protocol}{name}"),
+            Request::DataUri {
+                media_type,
+                base64,
+                data,
+            } => format!(
+                "data:{media_type}{},{data}",
+                if *base64 { ";base64" } else { "" }
+            ),
             Request::Unknown {
                 path: Pattern::Constant(path),
             } => path.to_string(),
@@ -102,6 +126,9 @@ impl Request {
                     lazy_static! {
                         static ref WINDOWS_PATH: Regex =
                             Regex::new(r"^([A-Za-z]:\\|\\\\)").unwrap();
+                        static ref BUILTIN: Regex = Regex::new(r"^(node:)(.+)$").unwrap();
+                        static ref DATA_URI: Regex =
+                            Regex::new(r"(?s)^data:([^,]*),(.*)$").unwrap();
                         static ref URI_PATH: Regex = Regex::new(r"^([^/\\]+:)(.+)").unwrap();
                         static ref MODULE_PATH: Regex =
                             Regex::new(r"^((?:@[^/]+/)?[^/]+)([^?]*)(.*)").unwrap();
@@ -109,9 +136,33 @@ impl Request {
                     if WINDOWS_PATH.is_match(r) {
                         return Request::Windows { path: request };
                     }
+                    if let Some(caps) = BUILTIN.captures(r) {
+                        if let (Some(protocol), Some(name)) = (caps.get(1), caps.get(2)) {
+                            return Request::Builtin {
+                                protocol: protocol.as_str().to_string(),
+                                name: name.as_str().to_string(),
+                            };
+                        }
+                    }
+                    if let Some(caps) = DATA_URI.captures(r) {
+                        if let (Some(params), Some(data)) = (caps.get(1), caps.get(2)) {
+                            let mut params = params.as_str().split(';');
+                            let media_type = params.next().unwrap_or_default().trim();
+                            let base64 = params.any(|param| param.trim() == "base64");
+                            return Request::DataUri {
+                                media_type: if media_type.is_empty() {
+                                    // Default as specified by RFC 2397
+                                    "text/plain".to_string()
+                                } else {
+                                    media_type.to_ascii_lowercase()
+                                },
+                                base64,
+                                data: data.as_str().to_string(),
+                            };
+                        }
+                    }
                     if let Some(caps) = URI_PATH.captures(r) {
                         if let (Some(protocol), Some(remainder)) = (caps.get(1), caps.get(2)) {
-                            // TODO data uri
                             return Request::Uri {
                                 protocol: protocol.as_str().to_string(),
                                 remainder: remainder.as_str().to_string(),
@@ -164,7 +215,7 @@ impl Request {
                         Request::PackageInternal { path } => {
                             path.extend(iter);
                         }
-                        Request::Uri { .. } => {
+                        Request::Uri { .. } | Request::Builtin { .. } | Request::DataUri { .. } => {
                             result = Request::Dynamic;
                         }
                         Request::Unknown { path } => {
@@ -235,6 +286,8 @@ impl RequestVc {
             | Request::Windows { .. }
             | Request::Relative { .. }
             | Request::Uri { .. }
+            | Request::Builtin { .. }
+            | Request::DataUri { .. }
             | Request::Dynamic => self,
             Request::Module {
                 module,
@@ -312,6 +365,8 @@ impl ValueToString for Request {
                 protocol,
                 remainder,
             } => format!("uri \"{protocol}\" \"{remainder}\""),
+            Request::Builtin { protocol, name } => format!("builtin \"{protocol}{name}\""),
+            Request::DataUri { media_type, .. } => format!("data uri \"{media_type}\""),
             Request::Unknown { path } => format!("unknown {path}"),
             Request::Dynamic => "dynamic".to_string(),
             Request::Alternatives { requests } => {
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::{
-    alias_map::{AliasMap, AliasMapIter, AliasPattern, AliasTemplate},
+    alias_map::{replace_wildcards, AliasMap, AliasMapIter, AliasPattern, AliasTemplate},
     options::ConditionValue,
 };
 
@@ -61,19 +61,19 @@ pub enum SubpathValue {
 impl AliasTemplate for SubpathValue {
     type Output<'a> = Result<Self> where Self: 'a;
 
-    fn replace(&self, capture: &str) -> Result<Self> {
+    fn replace(&self, captures: &[&str]) -> Result<Self> {
         Ok(match self {
             SubpathValue::Alternatives(list) => SubpathValue::Alternatives(
                 list.iter()
-                    .map(|value| value.replace(capture))
+                    .map(|value| value.replace(captures))
                     .collect::<Result<Vec<_>>>()?,
             ),
             SubpathValue::Conditional(list) => SubpathValue::Conditional(
                 list.iter()
-                    .map(|(condition, value)| Ok((condition.clone(), value.replace(capture)?)))
+                    .map(|(condition, value)| Ok((condition.clone(), value.replace(captures)?)))
                     .collect::<Result<Vec<_>>>()?,
             ),
-            SubpathValue::Result(value) => SubpathValue::Result(value.replace('*', capture)),
+            SubpathValue::Result(value) => SubpathValue::Result(replace_wildcards(value, captures)),
             SubpathValue::Excluded => SubpathValue::Excluded,
         })
     }
@@ -89,26 +89,47 @@ impl SubpathValue {
     /// uses the `conditions` to skip or enter conditional results.
     /// The state of conditions is stored within `condition_overrides`, which is
     /// also exposed to the consumer.
+    ///
+    /// Every item of `target` is a list of fallbacks, of which the first one
+    /// that resolves should be used. These come from arrays of alternatives.
     pub fn add_results<'a>(
         &'a self,
         conditions: &BTreeMap<String, ConditionValue>,
         unspecified_condition: &ConditionValue,
         condition_overrides: &mut HashMap<&'a str, ConditionValue>,
-        target: &mut Vec<&'a str>,
+        target: &mut Vec<Vec<&'a str>>,
     ) -> bool {
         match self {
             SubpathValue::Alternatives(list) => {
+                let mut matched = false;
+                let mut fallbacks = Vec::new();
                 for value in list {
-                    if value.add_results(
+                    let mut results = Vec::new();
+                    matched |= value.add_results(
                         conditions,
                         unspecified_condition,
                         condition_overrides,
-                        target,
-                    ) {
-                        return true;
+                        &mut results,
+                    );
+                    if results.len() <= 1 {
+                        fallbacks.extend(results.into_iter().flatten());
+                    } else {
+                        // Results for unknown conditions are all needed, so they can't be
+                        // fallbacks of each other.
+                        if !fallbacks.is_empty() {
+                            target.push(std::mem::take(&mut fallbacks));
+                        }
+                        target.extend(results);
+                    }
+                    // An excluded alternative prevents all following ones.
+                    if matches!(value, SubpathValue::Excluded) {
+                        break;
                     }
                 }
-                false
+                if !fallbacks.is_empty() {
+                    target.push(fallbacks);
+                }
+                matched
             }
             SubpathValue::Conditional(list) => {
                 for (condition, value) in list {
@@ -150,7 +171,7 @@ impl SubpathValue {
                 false
             }
             SubpathValue::Result(r) => {
-                target.push(r);
+                target.push(vec![r]);
                 true
             }
             SubpathValue::Excluded => true,
@@ -217,9 +238,73 @@ impl<'a> Iterator for ResultsIterMut<'a> {
     }
 }
 
+/// A problem with an entry of an "exports" field.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
+pub struct ExportsFieldDiagnostic {
+    pub message: String,
+    /// Whether the entry was ignored. Otherwise it's interpreted leniently.
+    pub ignored: bool,
+}
+
 /// Content of an "exports" field in a package.json
 #[derive(PartialEq, Eq, Serialize, Deserialize)]
-pub struct ExportsField(AliasMap<SubpathValue>);
+pub struct ExportsField {
+    map: AliasMap<SubpathValue>,
+    diagnostics: Vec<ExportsFieldDiagnostic>,
+}
+
+impl ExportsField {
+    /// Problems with entries which were found while parsing the field.
+    pub fn diagnostics(&self) -> &[ExportsFieldDiagnostic] {
+        &self.diagnostics
+    }
+}
+
+/// Reports export targets which don't start with "./", which Node.js rejects,
+/// and conditions which follow a "default" condition, which are never used.
+/// Both are still interpreted, the targets relative to the package.
+fn check_exports_entry(
+    key: &str,
+    value: &SubpathValue,
+    diagnostics: &mut Vec<ExportsFieldDiagnostic>,
+) {
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            SubpathValue::Alternatives(list) => stack.extend(list.iter()),
+            SubpathValue::Conditional(list) => {
+                if let Some(index) = list
+                    .iter()
+                    .position(|(condition, _)| condition == "default")
+                {
+                    for (condition, _) in &list[index + 1..] {
+                        diagnostics.push(ExportsFieldDiagnostic {
+                            message: format!(
+                                "condition \"{condition}\" for key \"{key}\" follows the \
+                                 \"default\" condition and is never used: \"default\" must be the \
+                                 last condition"
+                            ),
+                            ignored: false,
+                        });
+                    }
+                }
+                stack.extend(list.iter().map(|(_, value)| value))
+            }
+            SubpathValue::Result(target) => {
+                if !target.starts_with("./") {
+                    diagnostics.push(ExportsFieldDiagnostic {
+                        message: format!(
+                            "invalid exports field target \"{target}\" for key \"{key}\": targets \
+                             must start with \"./\""
+                        ),
+                        ignored: false,
+                    });
+                }
+            }
+            SubpathValue::Excluded => {}
+        }
+    }
+}
 
 impl TryFrom<&Value> for ExportsField {
     type Error = anyhow::Error;
@@ -227,6 +312,7 @@ impl TryFrom<&Value> for ExportsField {
     fn try_from(value: &Value) -> Result<Self> {
         // The "exports" field can be an object, a string, or an array of strings.
         // https://nodejs.org/api/packages.html#exports
+        let mut diagnostics = Vec::new();
         let map = match value {
             Value::Object(object) => {
                 let mut map = AliasMap::new();
@@ -236,78 +322,103 @@ impl TryFrom<&Value> for ExportsField {
 
                 for (key, value) in object.iter() {
                     // NOTE: Node.js does not allow conditional and non-conditional keys
-                    // to be mixed at the top-level, but we do.
+                    // to be mixed at the top-level, but we do and report it.
                     if key != "." && !key.starts_with("./") {
                         conditions.push((key, value));
                         continue;
                     }
 
-                    let mut value = SubpathValue::try_new(value, ExportImport::Export)?;
-
-                    let pattern = if is_folder_shorthand(key) {
-                        expand_folder_shorthand(key, &mut value)?
-                    } else {
-                        AliasPattern::parse(key)
-                    };
-
-                    map.insert(pattern, value);
+                    // Malformed entries are ignored, so that the remaining entries of the
+                    // field can still be used.
+                    let entry =
+                        SubpathValue::try_new(value, ExportImport::Export).and_then(|mut value| {
+                            let pattern = if is_folder_shorthand(key) {
+                                expand_folder_shorthand(key, &mut value)?
+                            } else {
+                                AliasPattern::parse(key)
+                            };
+                            Ok((pattern, value))
+                        });
+                    match entry {
+                        Ok((pattern, value)) => {
+                            check_exports_entry(key, &value, &mut diagnostics);
+                            map.insert(pattern, value);
+                        }
+                        Err(err) => diagnostics.push(ExportsFieldDiagnostic {
+                            message: format!("ignored exports field entry \"{key}\": {err}"),
+                            ignored: true,
+                        }),
+                    }
                 }
 
                 if !conditions.is_empty() {
-                    map.insert(
-                        AliasPattern::Exact(".".to_string()),
-                        SubpathValue::Conditional(
-                            conditions
-                                .into_iter()
-                                .map(|(key, value)| {
-                                    Ok((
-                                        key.to_string(),
-                                        SubpathValue::try_new(value, ExportImport::Export)?,
-                                    ))
-                                })
-                                .collect::<Result<Vec<_>>>()?,
-                        ),
-                    );
+                    if object.len() > conditions.len() {
+                        diagnostics.push(ExportsFieldDiagnostic {
+                            message: format!(
+                                "conditions ({}) are mixed with subpaths at the top-level of the \
+                                 exports field: the conditions are applied to the \".\" subpath",
+                                conditions
+                                    .iter()
+                                    .map(|(key, _)| format!("\"{key}\""))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                            ignored: false,
+                        });
+                    }
+                    let mut list = Vec::with_capacity(conditions.len());
+                    for (key, value) in conditions {
+                        match SubpathValue::try_new(value, ExportImport::Export) {
+                            Ok(value) => list.push((key.to_string(), value)),
+                            Err(err) => diagnostics.push(ExportsFieldDiagnostic {
+                                message: format!(
+                                    "ignored exports field condition \"{key}\": {err}"
+                                ),
+                                ignored: true,
+                            }),
+                        }
+                    }
+                    let value = SubpathValue::Conditional(list);
+                    check_exports_entry(".", &value, &mut diagnostics);
+                    map.insert(AliasPattern::Exact(".".to_string()), value);
                 }
 
                 map
             }
             Value::String(string) => {
                 let mut map = AliasMap::new();
-                map.insert(
-                    AliasPattern::exact("."),
-                    SubpathValue::Result(string.to_string()),
-                );
+                let value = SubpathValue::Result(string.to_string());
+                check_exports_entry(".", &value, &mut diagnostics);
+                map.insert(AliasPattern::exact("."), value);
                 map
             }
             Value::Array(array) => {
                 let mut map = AliasMap::new();
-                map.insert(
-                    AliasPattern::exact("."),
-                    // This allows for more complex patterns than the spec allows, since we accept
-                    // the following:
-                    // [{ "node": "./node.js", "default": "./index.js" }, "./index.js"]
-                    SubpathValue::Alternatives(
-                        array
-                            .iter()
-                            .map(|value| SubpathValue::try_new(value, ExportImport::Export))
-                            .collect::<Result<Vec<_>>>()?,
-                    ),
+                // This allows for more complex patterns than the spec allows, since we accept
+                // the following:
+                // [{ "node": "./node.js", "default": "./index.js" }, "./index.js"]
+                let value = SubpathValue::Alternatives(
+                    array
+                        .iter()
+                        .map(|value| SubpathValue::try_new(value, ExportImport::Export))
+                        .collect::<Result<Vec<_>>>()?,
                 );
+                check_exports_entry(".", &value, &mut diagnostics);
+                map.insert(AliasPattern::exact("."), value);
                 map
             }
             _ => {
                 bail!("\"exports\" field must be an object or a string");
             }
         };
-        Ok(Self(map))
+        Ok(Self { map, diagnostics })
     }
 }
 
 impl Deref for ExportsField {
     type Target = AliasMap<SubpathValue>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.map
     }
 }
 
@@ -424,3 +535,84 @@ impl<'a> IntoIterator for &'a ResolveAliasMap {
         (&self.0).into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ExportsField, ExportsFieldDiagnostic};
+
+    fn diagnostics(exports: serde_json::Value) -> Vec<ExportsFieldDiagnostic> {
+        ExportsField::try_from(&exports)
+            .unwrap()
+            .diagnostics()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_valid_exports() {
+        assert_eq!(
+            diagnostics(json!({
+                ".": { "import": "./index.mjs", "default": "./index.js" },
+                "./feature": ["./feature.js", { "node": "./feature-node.js" }],
+            })),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_subpath_nested_in_condition() {
+        let diagnostics = diagnostics(json!({
+            "./a": { "import": { "./nested": "./a.mjs" } },
+            "./b": "./b.js",
+        }));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].ignored);
+        assert!(diagnostics[0].message.contains("\"./a\""));
+        assert!(diagnostics[0].message.contains("\"./nested\""));
+    }
+
+    #[test]
+    fn test_subpath_nested_in_top_level_condition() {
+        let exports = json!({
+            "import": { "./nested": "./a.mjs" },
+            "require": "./a.js",
+        });
+        let field = ExportsField::try_from(&exports).unwrap();
+        let diagnostics = field.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].ignored);
+        assert!(diagnostics[0].message.contains("condition \"import\""));
+        // The remaining condition can still be used.
+        assert_eq!((&*field).into_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_mixed_conditions_and_subpaths() {
+        let diagnostics = diagnostics(json!({
+            "./a": "./a.js",
+            "import": "./index.mjs",
+        }));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].ignored);
+        assert!(diagnostics[0].message.contains("\"import\""));
+    }
+
+    #[test]
+    fn test_conditions_after_default() {
+        let diagnostics = diagnostics(json!({
+            ".": { "default": "./index.js", "import": "./index.mjs" },
+        }));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].ignored);
+        assert!(diagnostics[0].message.contains("condition \"import\""));
+    }
+
+    #[test]
+    fn test_targets_without_dot_slash() {
+        let diagnostics = diagnostics(json!({ ".": "index.js" }));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].ignored);
+        assert!(diagnostics[0].message.contains("\"index.js\""));
+    }
+}
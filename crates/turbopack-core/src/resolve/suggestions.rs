@@ -0,0 +1,281 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use turbo_tasks::primitives::StringsVc;
+use turbo_tasks_fs::{DirectoryContent, DirectoryEntry, FileSystemEntryType, FileSystemPathVc};
+
+use super::{
+    alias_map::AliasPattern,
+    options::{ResolveModules, ResolveOptionsVc},
+    parse::{Request, RequestVc},
+    pattern::Pattern,
+};
+
+/// The maximum number of suggestions reported for a failed request.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The maximum number of directories which are listed to suggest packages for
+/// a failed module request. Every `node_modules` directory up to the root
+/// would be a candidate otherwise, which is a lot of I/O for a single failed
+/// request.
+const MAX_MODULE_DIRECTORIES: usize = 8;
+
+/// Computes requests which are close to a `request` which failed to resolve,
+/// e.g. `./Button` for `./Buttn`.
+///
+/// Relative requests are compared against the files in the requested
+/// directory, module requests against the installed packages and the aliases
+/// of the import map. `context` is the path resolving has started from, which
+/// might be a file or a directory.
+#[turbo_tasks::function]
+pub async fn resolve_suggestions(
+    context: FileSystemPathVc,
+    request: RequestVc,
+    options: ResolveOptionsVc,
+) -> Result<StringsVc> {
+    let context = match *context.get_type().await? {
+        FileSystemEntryType::File => context.parent(),
+        _ => context,
+    };
+    let suggestions = match &*request.await? {
+        Request::Relative {
+            path: Pattern::Constant(path),
+            ..
+        } => relative_suggestions(context, path, options).await?,
+        Request::Module {
+            module,
+            path: Pattern::Constant(path),
+            ..
+        } => {
+            let mut suggestions = module_suggestions(context, module, options).await?;
+            for suggestion in suggestions.iter_mut() {
+                suggestion.push_str(path);
+            }
+            suggestions
+        }
+        _ => Vec::new(),
+    };
+    Ok(StringsVc::cell(suggestions))
+}
+
+async fn relative_suggestions(
+    context: FileSystemPathVc,
+    path: &str,
+    options: ResolveOptionsVc,
+) -> Result<Vec<String>> {
+    let (dir, name) = match path.rfind('/') {
+        Some(index) => (&path[..=index], &path[index + 1..]),
+        None => ("", path),
+    };
+    if name.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(dir_path) = &*context.try_join(dir).await? else {
+        return Ok(Vec::new());
+    };
+    let DirectoryContent::Entries(entries) = &*dir_path.read_dir().await? else {
+        return Ok(Vec::new());
+    };
+    let options = options.await?;
+
+    let mut candidates = BTreeSet::new();
+    for (entry_name, entry) in entries.iter() {
+        match entry {
+            DirectoryEntry::File(_) | DirectoryEntry::Symlink(_) => {
+                candidates.insert(entry_name.clone());
+                // Requests usually omit the extension, so files are also
+                // suggested without it.
+                for extension in options.extensions.iter() {
+                    if let Some(stem) = entry_name.strip_suffix(extension.as_str()) {
+                        candidates.insert(stem.to_string());
+                    }
+                }
+            }
+            DirectoryEntry::Directory(_) => {
+                candidates.insert(entry_name.clone());
+            }
+            DirectoryEntry::Other(_) | DirectoryEntry::Error => {}
+        }
+    }
+
+    Ok(closest_matches(name, candidates.iter().map(|s| s.as_str()))
+        .into_iter()
+        .map(|candidate| format!("{dir}{candidate}"))
+        .collect())
+}
+
+async fn module_suggestions(
+    context: FileSystemPathVc,
+    module: &str,
+    options: ResolveOptionsVc,
+) -> Result<Vec<String>> {
+    let options = options.await?;
+    let mut candidates = BTreeSet::new();
+    // Scoped packages are only listed for scoped requests, as that needs
+    // another read for every scope.
+    let scoped = module.starts_with('@');
+    let mut directories = Vec::new();
+
+    for resolve_modules in options.modules.iter() {
+        match resolve_modules {
+            ResolveModules::Nested(root, names) => {
+                let root = root.await?;
+                let mut context = context;
+                let mut context_value = context.await?;
+                while context_value.is_inside(&root) {
+                    for name in names.iter() {
+                        directories.push(context.join(name));
+                    }
+                    context = context.parent().resolve().await?;
+                    let new_context_value = context.await?;
+                    if *new_context_value == *context_value {
+                        break;
+                    }
+                    context_value = new_context_value;
+                }
+            }
+            ResolveModules::Path(dir) | ResolveModules::Fallback(dir) => {
+                directories.push(*dir);
+            }
+            ResolveModules::Workspaces(workspaces) => {
+                candidates.extend(workspaces.await?.keys().cloned());
+            }
+            ResolveModules::Registry(..) => {}
+        }
+    }
+
+    for dir in directories.into_iter().take(MAX_MODULE_DIRECTORIES) {
+        add_package_names(dir, scoped, &mut candidates).await?;
+    }
+
+    for import_map in [options.import_map, options.fallback_import_map]
+        .into_iter()
+        .flatten()
+    {
+        for pattern in import_map.await?.patterns() {
+            match pattern {
+                AliasPattern::Exact(alias) => {
+                    candidates.insert(alias);
+                }
+                AliasPattern::Wildcard { prefix, .. } => {
+                    if let Some(alias) = prefix.strip_suffix('/') {
+                        candidates.insert(alias.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(
+        closest_matches(module, candidates.iter().map(|s| s.as_str()))
+            .into_iter()
+            .map(|candidate| candidate.to_string())
+            .collect(),
+    )
+}
+
+/// Adds the names of the packages in a `node_modules`-like directory,
+/// including scoped packages when `scoped` is set.
+async fn add_package_names(
+    dir: FileSystemPathVc,
+    scoped: bool,
+    names: &mut BTreeSet<String>,
+) -> Result<()> {
+    let DirectoryContent::Entries(entries) = &*dir.read_dir().await? else {
+        return Ok(());
+    };
+    for (name, entry) in entries.iter() {
+        let (DirectoryEntry::Directory(path) | DirectoryEntry::Symlink(path)) = entry else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if name.starts_with('@') {
+            if !scoped {
+                continue;
+            }
+            if let DirectoryContent::Entries(scoped) = &*path.read_dir().await? {
+                for (scoped_name, _) in scoped.iter() {
+                    names.insert(format!("{name}/{scoped_name}"));
+                }
+            }
+        } else {
+            names.insert(name.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the candidates which are within a small edit distance of `name`,
+/// closest first.
+fn closest_matches<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut matches = candidates
+        .filter(|candidate| *candidate != name)
+        .filter_map(|candidate| {
+            let distance = edit_distance(name, candidate);
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// The Levenshtein distance between `a` and `b`. Differences in case count
+/// as half an edit each, rounded down, so wrongly cased requests are always
+/// suggested.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // Costs are doubled to represent the half edits of case differences.
+    let mut previous = (0..=b.len()).map(|i| i * 2).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = (i + 1) * 2;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = if a_char == b_char {
+                0
+            } else if a_char.to_lowercase().eq(b_char.to_lowercase()) {
+                1
+            } else {
+                2
+            };
+            current[j + 1] = (previous[j] + substitution)
+                .min(previous[j + 1] + 2)
+                .min(current[j] + 2);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()] / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest_matches, edit_distance};
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("Buttn", "Button"), 1);
+        assert_eq!(edit_distance("react", "raect"), 2);
+        assert_eq!(edit_distance("button", "Button"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest_matches() {
+        let candidates = ["Button", "Buttons", "Input", "button.css"];
+        assert_eq!(
+            closest_matches("Buttn", candidates.into_iter()),
+            vec!["Button"]
+        );
+        assert_eq!(
+            closest_matches("react", ["preact", "react-dom", "vue"].into_iter()),
+            vec!["preact"]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use serde_json::{Map, Value as JsonValue};
+
+use super::alias_map::replace_wildcards;
+
+/// Selects the path mappings of the [typesVersions] field of a package.json
+/// which apply to `typescript_version`. Like TypeScript, the first entry whose
+/// version range matches wins.
+///
+/// [typesVersions]: https://www.typescriptlang.org/docs/handbook/declaration-files/publishing.html#version-selection-with-typesversions
+pub(super) fn types_versions_paths<'a>(
+    package_json: &'a JsonValue,
+    typescript_version: &str,
+) -> Option<&'a Map<String, JsonValue>> {
+    let version = parse_version(typescript_version)?;
+    package_json["typesVersions"]
+        .as_object()?
+        .iter()
+        .find(|(range, _)| range_matches(range, version))
+        .and_then(|(_, paths)| paths.as_object())
+}
+
+/// Maps a `path` within a package through the path mappings of a
+/// `typesVersions` entry. Returns the targets in order of preference, or
+/// `None` when no mapping matches.
+///
+/// Mappings may contain a single `*` wildcard. When multiple mappings match,
+/// the one with the longest prefix before the wildcard wins, and exact
+/// mappings win over wildcards.
+pub(super) fn map_types_versions_path(
+    paths: &Map<String, JsonValue>,
+    path: &str,
+) -> Option<Vec<String>> {
+    let mut best: Option<(usize, &str, &JsonValue)> = None;
+    for (pattern, targets) in paths.iter() {
+        let (prefix_len, capture) = match pattern.split_once('*') {
+            None if pattern == path => (usize::MAX, ""),
+            None => continue,
+            Some((prefix, suffix)) => {
+                if path.len() < prefix.len() + suffix.len()
+                    || !path.starts_with(prefix)
+                    || !path.ends_with(suffix)
+                {
+                    continue;
+                }
+                (prefix.len(), &path[prefix.len()..path.len() - suffix.len()])
+            }
+        };
+        if best.map_or(true, |(best_len, ..)| prefix_len > best_len) {
+            best = Some((prefix_len, capture, targets));
+        }
+    }
+    let (_, capture, targets) = best?;
+    let targets = match targets {
+        JsonValue::String(target) => vec![target.as_str()],
+        JsonValue::Array(targets) => targets.iter().filter_map(|t| t.as_str()).collect(),
+        _ => return None,
+    };
+    Some(
+        targets
+            .into_iter()
+            .map(|target| replace_wildcards(target, &[capture]))
+            .collect(),
+    )
+}
+
+type Version = (u64, u64, u64);
+
+fn parse_version(version: &str) -> Option<Version> {
+    let version = version.trim().trim_start_matches('v');
+    // Prerelease and build metadata don't affect the selection.
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    Some((major, minor, patch))
+}
+
+/// Checks a version against a semver range as used in `typesVersions`, e.g.
+/// `>=4.2`, `<4.0` or `*`. Comparators separated by whitespace must all
+/// match, alternatives separated by `||` must match at least once.
+fn range_matches(range: &str, version: Version) -> bool {
+    range.split("||").any(|comparators| {
+        comparators
+            .split_whitespace()
+            .all(|comparator| comparator_matches(comparator, version))
+    })
+}
+
+fn comparator_matches(comparator: &str, version: Version) -> bool {
+    if comparator == "*" {
+        return true;
+    }
+    let (op, bound) = [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|bound| (op, bound)))
+        .unwrap_or(("=", comparator));
+    let Some(bound) = parse_version(bound) else {
+        return false;
+    };
+    match op {
+        ">=" => version >= bound,
+        "<=" => version <= bound,
+        ">" => version > bound,
+        "<" => version < bound,
+        _ => version == bound,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{map_types_versions_path, types_versions_paths};
+
+    #[test]
+    fn test_types_versions_paths() {
+        let package_json = json!({
+            "typesVersions": {
+                ">=4.2": { "*": ["ts4.2/*"] },
+                "<4.0": { "*": ["ts3/*"] },
+                "*": { "*": ["legacy/*"] }
+            }
+        });
+        let select = |version| {
+            types_versions_paths(&package_json, version)
+                .and_then(|paths| map_types_versions_path(paths, "index.d.ts"))
+        };
+        assert_eq!(select("5.0.4"), Some(vec!["ts4.2/index.d.ts".to_string()]));
+        assert_eq!(select("3.9.7"), Some(vec!["ts3/index.d.ts".to_string()]));
+        assert_eq!(select("4.1.0"), Some(vec!["legacy/index.d.ts".to_string()]));
+        assert_eq!(select("not a version"), None);
+    }
+
+    #[test]
+    fn test_map_types_versions_path() {
+        let paths = json!({
+            "*": ["types/*", "fallback/*"],
+            "sub/*": ["types/sub/*.d.ts"],
+            "exact": ["types/exact.d.ts"]
+        });
+        let paths = paths.as_object().unwrap();
+        assert_eq!(
+            map_types_versions_path(paths, "sub/a"),
+            Some(vec!["types/sub/a.d.ts".to_string()])
+        );
+        assert_eq!(
+            map_types_versions_path(paths, "exact"),
+            Some(vec!["types/exact.d.ts".to_string()])
+        );
+        assert_eq!(
+            map_types_versions_path(paths, "other"),
+            Some(vec![
+                "types/other".to_string(),
+                "fallback/other".to_string()
+            ])
+        );
+        let paths = json!({ "lib/*": ["types/*"] });
+        assert_eq!(
+            map_types_versions_path(paths.as_object().unwrap(), "other"),
+            None
+        );
+    }
+}
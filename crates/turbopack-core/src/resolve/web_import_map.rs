@@ -0,0 +1,190 @@
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_fs::{FileJsonContent, FileSystemPathVc};
+
+use super::{
+    alias_map::{AliasMap, AliasPattern},
+    options::{ExternalType, ImportMap, ImportMapVc, ImportMapping, ImportMappingVc},
+};
+use crate::issue::{Issue, IssueVc};
+
+/// Reads a [web import map] (e.g. an import_map.json) and converts it into an
+/// [ImportMap], so the same file can be used by the browser and the bundler.
+///
+/// ```json
+/// {
+///   "imports": {
+///     "react": "https://esm.sh/react",
+///     "lodash/": "./vendor/lodash/"
+///   },
+///   "scopes": {
+///     "/legacy/": { "react": "./vendor/react-17.js" }
+///   }
+/// }
+/// ```
+///
+/// Relative and absolute URLs are resolved from the directory of the import
+/// map, which is expected to be served at the root. `http(s)` URLs become
+/// [ExternalType::Url] externals. Keys ending with `/` map every request
+/// starting with them. Scoped imports only apply to requests from within the
+/// scope directory and take precedence over the top-level imports.
+///
+/// [web import map]: https://html.spec.whatwg.org/multipage/webappapis.html#import-maps
+#[turbo_tasks::function]
+pub async fn read_web_import_map(path: FileSystemPathVc) -> Result<ImportMapVc> {
+    let mut import_map = ImportMap::empty();
+    let content = path.read_json().await?;
+    let json = match &*content {
+        FileJsonContent::Content(json) => json,
+        FileJsonContent::NotFound => return Ok(import_map.cell()),
+        FileJsonContent::Unparseable(e) => {
+            emit_invalid_import_map(path, format!("invalid JSON: {e}"));
+            return Ok(import_map.cell());
+        }
+    };
+    let Some(json) = json.as_object() else {
+        emit_invalid_import_map(path, "the import map must be an object".to_string());
+        return Ok(import_map.cell());
+    };
+    let base = path.parent().resolve().await?;
+
+    if let Some(imports) = json.get("imports") {
+        if let Some(map) = specifier_map(path, base, imports, "imports") {
+            import_map = ImportMap::new(map);
+        }
+    }
+
+    if let Some(scopes) = json.get("scopes") {
+        let Some(scopes) = scopes.as_object() else {
+            emit_invalid_import_map(path, "\"scopes\" must be an object".to_string());
+            return Ok(import_map.cell());
+        };
+        for (scope, imports) in scopes {
+            let scope_path = match local_path(scope) {
+                Some(scope_path) => *base.try_join(scope_path.trim_end_matches('/')).await?,
+                None => None,
+            };
+            let Some(scope_path) = scope_path else {
+                emit_invalid_import_map(
+                    path,
+                    format!("the scope \"{scope}\" must be a path within the project"),
+                );
+                continue;
+            };
+            if let Some(map) = specifier_map(path, base, imports, scope) {
+                import_map.insert_scope(scope_path, map);
+            }
+        }
+    }
+
+    Ok(import_map.cell())
+}
+
+/// Converts a specifier map (the value of `imports` or of a scope) into an
+/// [AliasMap]. Invalid entries are skipped with an issue.
+fn specifier_map(
+    path: FileSystemPathVc,
+    base: FileSystemPathVc,
+    imports: &JsonValue,
+    name: &str,
+) -> Option<AliasMap<ImportMappingVc>> {
+    let Some(imports) = imports.as_object() else {
+        emit_invalid_import_map(path, format!("\"{name}\" must be an object"));
+        return None;
+    };
+    let mut map = AliasMap::new();
+    for (specifier, target) in imports {
+        match specifier_mapping(base, specifier, target) {
+            Ok((pattern, mapping)) => {
+                map.insert(pattern, mapping.cell());
+            }
+            Err(message) => {
+                emit_invalid_import_map(path, format!("the import \"{specifier}\" {message}"))
+            }
+        }
+    }
+    Some(map)
+}
+
+fn specifier_mapping(
+    base: FileSystemPathVc,
+    specifier: &str,
+    target: &JsonValue,
+) -> Result<(AliasPattern, ImportMapping), &'static str> {
+    if local_path(specifier).is_some() || is_url(specifier) {
+        return Err("must be a bare specifier, URL-like specifiers are not supported");
+    }
+    let Some(target) = target.as_str() else {
+        return Err("must map to a string");
+    };
+    let is_prefix = specifier.ends_with('/');
+    if is_prefix && !target.ends_with('/') {
+        return Err("must map to a URL ending with \"/\"");
+    }
+    let target = if is_prefix {
+        format!("{target}*")
+    } else {
+        target.to_string()
+    };
+    let mapping = if is_url(&target) {
+        ImportMapping::External(Some(target), ExternalType::Url)
+    } else if let Some(target) = local_path(&target) {
+        ImportMapping::PrimaryAlternative(format!("./{target}"), Some(base))
+    } else {
+        return Err("must map to a URL or a path starting with \"/\", \"./\" or \"../\"");
+    };
+    let pattern = if is_prefix {
+        AliasPattern::wildcard(specifier, "")
+    } else {
+        AliasPattern::exact(specifier)
+    };
+    Ok((pattern, mapping))
+}
+
+/// Returns the path relative to the import map for relative and absolute
+/// URLs.
+fn local_path(url: &str) -> Option<&str> {
+    if url.starts_with("./") || url.starts_with("../") {
+        Some(url.strip_prefix("./").unwrap_or(url))
+    } else {
+        url.strip_prefix('/')
+    }
+}
+
+fn is_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+fn emit_invalid_import_map(path: FileSystemPathVc, message: String) {
+    WebImportMapIssue { path, message }.cell().as_issue().emit();
+}
+
+#[turbo_tasks::value(shared)]
+pub struct WebImportMapIssue {
+    pub path: FileSystemPathVc,
+    pub message: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for WebImportMapIssue {
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("Invalid import map".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("resolve".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::cell(self.message.clone())
+    }
+}
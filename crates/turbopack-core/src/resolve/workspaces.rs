@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+use turbo_tasks::{primitives::StringVc, TryJoinIterExt};
+use turbo_tasks_fs::{
+    glob::GlobVc, DirectoryContent, DirectoryEntry, FileContent, FileSystemPathVc,
+};
+
+use crate::{
+    issue::{Issue, IssueVc},
+    package_json::read_package_json,
+};
+
+/// The packages of a monorepo, keyed by their name.
+#[turbo_tasks::value(transparent)]
+pub struct WorkspacePackages(BTreeMap<String, FileSystemPathVc>);
+
+#[derive(Deserialize)]
+struct PnpmWorkspace {
+    #[serde(default)]
+    packages: Vec<String>,
+}
+
+/// Finds the workspace packages of the monorepo at `root`.
+///
+/// The packages are defined by the globs in the `workspaces` field of the root
+/// package.json (either as a list or as `{ "packages": [...] }`), or in the
+/// `packages` field of a pnpm-workspace.yaml. Globs starting with `!` exclude
+/// packages.
+///
+/// Since the result depends on the files which were read, it's invalidated
+/// whenever packages are added, removed or renamed.
+#[turbo_tasks::function]
+pub async fn workspace_packages(root: FileSystemPathVc) -> Result<WorkspacePackagesVc> {
+    let mut globs = Vec::new();
+    if let Some(package_json) = &*read_package_json(root.join("package.json")).await? {
+        let workspaces = &package_json["workspaces"];
+        let workspaces = workspaces
+            .as_array()
+            .or_else(|| workspaces["packages"].as_array());
+        if let Some(workspaces) = workspaces {
+            globs.extend(
+                workspaces
+                    .iter()
+                    .filter_map(|glob| glob.as_str().map(|glob| glob.to_string())),
+            );
+        }
+    }
+    let pnpm_workspace_path = root.join("pnpm-workspace.yaml");
+    if let FileContent::Content(file) = &*pnpm_workspace_path.read().await? {
+        match serde_yaml::from_slice::<PnpmWorkspace>(&file.content().to_bytes()?) {
+            Ok(workspace) => globs.extend(workspace.packages),
+            Err(err) => {
+                WorkspaceIssue {
+                    path: pnpm_workspace_path,
+                    error_message: err.to_string(),
+                }
+                .cell()
+                .as_issue()
+                .emit();
+            }
+        }
+    }
+
+    let (excluded, included): (Vec<_>, Vec<_>) =
+        globs.into_iter().partition(|glob| glob.starts_with('!'));
+    if included.is_empty() {
+        return Ok(WorkspacePackagesVc::cell(BTreeMap::new()));
+    }
+    let excluded = excluded
+        .iter()
+        .map(|glob| GlobVc::new(normalize_glob(&glob[1..])))
+        .collect::<Vec<_>>();
+
+    let mut package_dirs = Vec::new();
+    for glob in included {
+        let glob = normalize_glob(&glob);
+        // The root package itself can't be imported as a workspace package.
+        if glob.is_empty() || glob == "." {
+            continue;
+        }
+        let glob = GlobVc::new(&format!("{glob}/package.json"));
+        let prefix = glob.await?.literal_directory_prefix();
+        let dirs = if prefix.is_empty() {
+            find_package_dirs(String::new(), root, glob)
+        } else {
+            find_package_dirs(format!("{prefix}/"), root.join(&prefix), glob)
+        };
+        package_dirs.extend(dirs.await?.iter().cloned());
+    }
+
+    // Directories are read in any order, but the first package wins when
+    // names are duplicated.
+    package_dirs.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut packages = BTreeMap::new();
+    'packages: for (relative_path, dir) in package_dirs {
+        for glob in excluded.iter() {
+            if glob.await?.execute(&relative_path) {
+                continue 'packages;
+            }
+        }
+        if let Some(package_json) = &*read_package_json(dir.join("package.json")).await? {
+            if let Some(name) = package_json["name"].as_str() {
+                packages.entry(name.to_string()).or_insert(dir);
+            }
+        }
+    }
+    Ok(WorkspacePackagesVc::cell(packages))
+}
+
+fn normalize_glob(glob: &str) -> &str {
+    let glob = glob.strip_prefix("./").unwrap_or(glob);
+    glob.strip_suffix('/').unwrap_or(glob)
+}
+
+/// Directories containing a package.json, with their path relative to the
+/// workspace root.
+#[turbo_tasks::value(transparent)]
+struct PackageDirs(Vec<(String, FileSystemPathVc)>);
+
+/// Finds the directories of the package.json files in `dir`, which is at
+/// `prefix` relative to the workspace root, matched by `glob`. Unlike
+/// [FileSystemPathVc::read_glob], this doesn't descend into `node_modules`,
+/// which never contains workspace packages but would be traversed completely
+/// for globs like `packages/**`.
+#[turbo_tasks::function]
+async fn find_package_dirs(
+    prefix: String,
+    dir: FileSystemPathVc,
+    glob: GlobVc,
+) -> Result<PackageDirsVc> {
+    let DirectoryContent::Entries(entries) = &*dir.read_dir().await? else {
+        return Ok(PackageDirsVc::cell(Vec::new()));
+    };
+    let glob_value = glob.await?;
+    let mut package_dirs = Vec::new();
+    let mut inner = Vec::new();
+    for (segment, entry) in entries.iter() {
+        let path = format!("{prefix}{segment}");
+        match entry {
+            DirectoryEntry::File(_) | DirectoryEntry::Symlink(_)
+                if segment == "package.json" && glob_value.execute(&path) =>
+            {
+                package_dirs.push((prefix.trim_end_matches('/').to_string(), dir));
+            }
+            DirectoryEntry::Directory(child) if segment != "node_modules" => {
+                let child_prefix = format!("{path}/");
+                if glob_value.execute(&child_prefix) {
+                    inner.push(find_package_dirs(child_prefix, *child, glob));
+                }
+            }
+            _ => {}
+        }
+    }
+    for dirs in inner.into_iter().try_join().await? {
+        package_dirs.extend(dirs.iter().cloned());
+    }
+    Ok(PackageDirsVc::cell(package_dirs))
+}
+
+#[turbo_tasks::value(shared)]
+pub struct WorkspaceIssue {
+    pub path: FileSystemPathVc,
+    pub error_message: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for WorkspaceIssue {
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("Error parsing pnpm-workspace.yaml file".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("parse".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::cell(self.error_message.clone())
+    }
+}
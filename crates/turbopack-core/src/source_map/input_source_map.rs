@@ -0,0 +1,78 @@
+use std::ops::Deref;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sourcemap::DecodedMap;
+use turbo_tasks::ValueToString;
+use turbo_tasks_fs::{FileContent, FileSystemPathVc};
+
+use super::{CrateMapWrapper, OptionSourceMapVc, SourceMap};
+use crate::SOURCE_MAP_ROOT_NAME;
+
+/// Loads the source map which the file at `origin` refers to with a
+/// `sourceMappingURL` comment, i.e. the input source map of transpiled or
+/// generated code. `url` is either a path relative to `origin` or a `data:`
+/// URL.
+///
+/// The relative sources of the map are rewritten to the same format as the
+/// sources of the source maps generated by turbopack, so the maps can be
+/// chained. Returns `None` when the source map doesn't exist or is invalid.
+#[turbo_tasks::function]
+pub async fn input_source_map(origin: FileSystemPathVc, url: String) -> Result<OptionSourceMapVc> {
+    let (map, map_dir) = if url.starts_with("data:") {
+        let map = decode_data_url(&url).and_then(|data| decode(sourcemap::decode_slice(&data)));
+        (map, origin.parent())
+    } else {
+        let Some(path) = *origin.parent().try_join(&url).await? else {
+            return Ok(OptionSourceMapVc::cell(None));
+        };
+        let map = match &*path.read().await? {
+            FileContent::Content(file) => {
+                decode(sourcemap::decode_slice(&file.content().to_bytes()?))
+            }
+            FileContent::NotFound => None,
+        };
+        (map, path.parent())
+    };
+    let Some(mut map) = map else {
+        return Ok(OptionSourceMapVc::cell(None));
+    };
+
+    let sources = map.sources().map(String::from).collect::<Vec<_>>();
+    for (index, source) in sources.iter().enumerate() {
+        // Absolute paths and urls, e.g. `webpack://` sources, are kept as they are
+        if source.starts_with('/') || source.contains(':') {
+            continue;
+        }
+        if let Some(path) = *map_dir.try_join(source).await? {
+            let source = format!("/{SOURCE_MAP_ROOT_NAME}/{}", path.to_string().await?);
+            map.0.set_source(index as u32, &source);
+        }
+    }
+    map.0.set_source_root(None::<String>);
+
+    Ok(OptionSourceMapVc::cell(Some(
+        SourceMap::new_regular(map.0).cell(),
+    )))
+}
+
+/// Decodes the content of a `data:` URL, which is either base64 or percent
+/// encoded.
+fn decode_data_url(url: &str) -> Option<Vec<u8>> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    if header.split(';').any(|parameter| parameter == "base64") {
+        STANDARD.decode(data).ok()
+    } else {
+        Some(urlencoding::decode_binary(data.as_bytes()).into_owned())
+    }
+}
+
+/// Flattens a decoded source map into a regular one.
+fn decode(map: sourcemap::Result<DecodedMap>) -> Option<CrateMapWrapper> {
+    let map = match map.ok()? {
+        DecodedMap::Regular(map) => map,
+        DecodedMap::Index(index) => index.flatten().ok()?,
+        DecodedMap::Hermes(map) => map.deref().clone(),
+    };
+    Some(CrateMapWrapper(map))
+}
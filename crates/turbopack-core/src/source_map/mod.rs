@@ -9,8 +9,10 @@ use turbo_tasks_fs::rope::{Rope, RopeBuilder, RopeVc};
 
 use crate::source_pos::SourcePos;
 
+pub(crate) mod input_source_map;
 pub(crate) mod source_map_asset;
 
+pub use input_source_map::input_source_map;
 pub use source_map_asset::{SourceMapAssetReference, SourceMapAssetReferenceVc};
 
 /// Allows callers to generate source maps.
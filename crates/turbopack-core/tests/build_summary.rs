@@ -0,0 +1,179 @@
+#![feature(min_specialization)]
+
+use std::time::Duration;
+
+use anyhow::Result;
+use turbo_tasks::{primitives::StringVc, FunctionStatistics, TaskStatistics};
+use turbo_tasks_fs::{File, FileSystem, FileSystemPathVc, VirtualFileSystemVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc},
+    build_summary::{entry_summary, BuildSummary},
+    ident::AssetIdentVc,
+    issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
+    reference::{AssetReferencesVc, SingleAssetReferenceVc},
+};
+
+register!(turbopack_core::register);
+
+#[turbo_tasks::value]
+struct TestAsset {
+    path: FileSystemPathVc,
+    content: String,
+    references: Vec<AssetVc>,
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for TestAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        AssetIdentVc::from_path(self.path)
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        File::from(self.content.clone()).into()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        AssetReferencesVc::cell(
+            self.references
+                .iter()
+                .map(|asset| {
+                    SingleAssetReferenceVc::new(*asset, StringVc::cell("test".to_string())).into()
+                })
+                .collect(),
+        )
+    }
+}
+
+fn asset(path: FileSystemPathVc, content: &str, references: Vec<AssetVc>) -> AssetVc {
+    TestAssetVc::cell(TestAsset {
+        path,
+        content: content.to_string(),
+        references,
+    })
+    .into()
+}
+
+#[turbo_tasks::value]
+struct TestIssue {
+    context: FileSystemPathVc,
+    severity: IssueSeverity,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for TestIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        self.severity.into()
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("test issue".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::empty()
+    }
+}
+
+/// Emits two warnings and an error and returns a dummy value.
+#[turbo_tasks::function]
+fn emit_issues(context: FileSystemPathVc) -> StringVc {
+    for severity in [
+        IssueSeverity::Warning,
+        IssueSeverity::Error,
+        IssueSeverity::Warning,
+    ] {
+        TestIssueVc::cell(TestIssue { context, severity })
+            .as_issue()
+            .emit();
+    }
+    StringVc::cell("done".to_string())
+}
+
+#[tokio::test]
+async fn summarizes_entries() {
+    run! {
+        let root = VirtualFileSystemVc::new().root();
+        let shared = asset(root.join("shared.js"), "shared", vec![]);
+        let a = asset(
+            root.join("a.js"),
+            "entry a",
+            vec![shared, asset(root.join("a.txt"), "text", vec![])],
+        );
+        let b = asset(root.join("b.js"), "b", vec![shared]);
+
+        let a = entry_summary(a).await?;
+        assert_eq!(a.path, "a.js");
+        assert_eq!(a.assets, 3);
+        assert_eq!(a.size, 17);
+        let b = entry_summary(b).await?;
+        assert_eq!(b.path, "b.js");
+        assert_eq!(b.assets, 2);
+        assert_eq!(b.size, 7);
+    }
+}
+
+#[tokio::test]
+async fn summary_json() {
+    run! {
+        let root = VirtualFileSystemVc::new().root();
+        let mut summary = BuildSummary::default();
+        summary.add_phase("compilation", Duration::from_millis(1500));
+        summary.add_phase("emit", Duration::from_micros(2500));
+        summary.set_task_statistics(&TaskStatistics {
+            total: FunctionStatistics {
+                tasks: 3,
+                cache_hits: 1,
+                executions: 4,
+                total_duration: Duration::ZERO,
+            },
+            dirty_tasks: 2,
+            ..Default::default()
+        });
+        summary.add_entry(
+            entry_summary(asset(root.join("index.js"), "index", vec![]))
+                .await?
+                .clone_value(),
+        );
+
+        let output = emit_issues(root.join("index.js"));
+        let issues = IssueVc::peek_issues_with_path(output)
+            .await?
+            .strongly_consistent()
+            .await?;
+        summary.add_issues(&issues).await?;
+
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json()?)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "phases": [
+                    { "name": "compilation", "durationMs": 1500 },
+                    { "name": "emit", "durationMs": 2 },
+                ],
+                "tasks": {
+                    "tasks": 3,
+                    "cacheHits": 1,
+                    "executions": 4,
+                    "cacheHitRate": 0.25,
+                    "dirtyTasks": 2,
+                },
+                "entries": [
+                    { "path": "index.js", "assets": 1, "size": 5 },
+                ],
+                "issues": { "error": 1, "warning": 2 },
+            })
+        );
+    }
+}
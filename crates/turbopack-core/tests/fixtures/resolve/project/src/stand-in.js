@@ -0,0 +1 @@
+export const standIn = true;
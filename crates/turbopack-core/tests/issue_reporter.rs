@@ -0,0 +1,138 @@
+#![feature(min_specialization)]
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use turbo_tasks::{
+    primitives::{BoolVc, StringVc},
+    RawVc, ReadRef, TransientInstance, TransientValue,
+};
+use turbo_tasks_fs::{FileSystem, FileSystemPathVc, VirtualFileSystemVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::issue::{
+    CapturedIssues, Issue, IssueReporter, IssueReporterProvider, IssueReporterVc, IssueSeverity,
+    IssueSeverityVc, IssueVc,
+};
+
+register!(turbopack_core::register);
+
+#[turbo_tasks::value(shared)]
+struct TestIssue {
+    context: FileSystemPathVc,
+    severity: IssueSeverity,
+    title: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for TestIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        self.severity.into()
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell(self.title.clone())
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::empty()
+    }
+}
+
+/// Emits a fatal issue, or a warning and an error, and returns a dummy value.
+#[turbo_tasks::function]
+fn emit_issues(context: FileSystemPathVc, fatal: bool) -> StringVc {
+    let severities = if fatal {
+        vec![IssueSeverity::Fatal]
+    } else {
+        vec![IssueSeverity::Warning, IssueSeverity::Error]
+    };
+    for (i, severity) in severities.into_iter().enumerate() {
+        TestIssueVc::cell(TestIssue {
+            context,
+            severity,
+            title: format!("issue {i}"),
+        })
+        .as_issue()
+        .emit();
+    }
+    StringVc::cell("done".to_string())
+}
+
+/// Records the titles of all reported issues.
+#[turbo_tasks::value(shared, serialization = "none", eq = "manual")]
+struct CollectingReporter {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    reported: Arc<Mutex<Vec<String>>>,
+}
+
+impl PartialEq for CollectingReporter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.reported, &other.reported)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl IssueReporter for CollectingReporter {
+    #[turbo_tasks::function]
+    async fn report_issues(
+        &self,
+        issues: TransientInstance<ReadRef<CapturedIssues>>,
+        _source: TransientValue<RawVc>,
+    ) -> Result<BoolVc> {
+        let mut has_fatal = false;
+        for issue in issues.get_plain_issues().await? {
+            has_fatal |= issue.severity <= IssueSeverity::Fatal;
+            self.reported.lock().unwrap().push(issue.title.clone());
+        }
+        Ok(BoolVc::cell(has_fatal))
+    }
+}
+
+async fn report_with(provider: &dyn IssueReporterProvider, fatal: bool) -> Result<bool> {
+    let context = VirtualFileSystemVc::new().root().join("index.js");
+    let output = emit_issues(context, fatal);
+    let source = TransientValue::new(output.into());
+    let issues = IssueVc::peek_issues_with_path(output)
+        .await?
+        .strongly_consistent()
+        .await?;
+    Ok(*provider
+        .get_issue_reporter()
+        .report_issues(TransientInstance::new(issues), source)
+        .await?)
+}
+
+#[tokio::test]
+async fn custom_issue_reporter() {
+    run! {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let provider: Box<dyn IssueReporterProvider> = Box::new({
+            let reported = reported.clone();
+            move || {
+                CollectingReporterVc::cell(CollectingReporter {
+                    reported: reported.clone(),
+                })
+                .into()
+            }
+        });
+
+        let has_fatal = report_with(&*provider, false).await?;
+        assert!(!has_fatal);
+        let mut titles = reported.lock().unwrap().clone();
+        titles.sort();
+        assert_eq!(titles, ["issue 0", "issue 1"]);
+
+        reported.lock().unwrap().clear();
+        let has_fatal = report_with(&*provider, true).await?;
+        assert!(has_fatal);
+        assert_eq!(*reported.lock().unwrap(), ["issue 0"]);
+    }
+}
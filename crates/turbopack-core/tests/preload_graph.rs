@@ -0,0 +1,166 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_fs::{File, FileContent, FileSystem, FileSystemPathVc, VirtualFileSystemVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc, AssetsVc},
+    chunk::{
+        preload_graph::{EntryChunkGroupsVc, PreloadGraphVc, PreloadManifestAssetVc},
+        OutputChunk, OutputChunkRuntimeInfo, OutputChunkRuntimeInfoVc, OutputChunkVc,
+    },
+    ident::AssetIdentVc,
+    reference::{AssetReferencesVc, SingleAssetReferenceVc},
+};
+
+register!(turbopack_core::register);
+
+/// An output asset which references other assets, e.g. a chunk loader
+/// manifest or a source map.
+#[turbo_tasks::value]
+struct TestAsset {
+    path: FileSystemPathVc,
+    references: Vec<AssetVc>,
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for TestAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        AssetIdentVc::from_path(self.path)
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        File::from("").into()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        references(&self.references)
+    }
+}
+
+#[turbo_tasks::value]
+struct TestChunk {
+    path: FileSystemPathVc,
+    references: Vec<AssetVc>,
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for TestChunk {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        AssetIdentVc::from_path(self.path)
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        File::from("").into()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        references(&self.references)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputChunk for TestChunk {
+    #[turbo_tasks::function]
+    fn runtime_info(&self) -> OutputChunkRuntimeInfoVc {
+        OutputChunkRuntimeInfo::default().cell()
+    }
+}
+
+fn references(assets: &[AssetVc]) -> AssetReferencesVc {
+    AssetReferencesVc::cell(
+        assets
+            .iter()
+            .map(|asset| {
+                SingleAssetReferenceVc::new(*asset, StringVc::cell("test".to_string())).into()
+            })
+            .collect(),
+    )
+}
+
+fn chunk(path: FileSystemPathVc, references: Vec<AssetVc>) -> AssetVc {
+    OutputChunkVc::from(TestChunk { path, references }.cell()).into()
+}
+
+fn asset(path: FileSystemPathVc, references: Vec<AssetVc>) -> AssetVc {
+    TestAsset { path, references }.cell().into()
+}
+
+/// An entry chunk group with an async chunk group behind a loader manifest.
+fn entry_chunk_group(root: FileSystemPathVc) -> AssetsVc {
+    let output_root = root.join("output");
+    let nested = chunk(
+        output_root.join("nested.js"),
+        vec![asset(output_root.join("nested.js.map"), vec![])],
+    );
+    let lazy = chunk(
+        output_root.join("lazy.js"),
+        vec![
+            asset(output_root.join("lazy.js.map"), vec![]),
+            asset(output_root.join("nested-manifest.js"), vec![nested]),
+        ],
+    );
+    let shared = chunk(output_root.join("shared.js"), vec![]);
+    let entry = chunk(
+        output_root.join("entry.js"),
+        vec![
+            shared,
+            asset(output_root.join("manifest.js"), vec![lazy]),
+            asset(
+                root.join("static/logo.svg"),
+                vec![chunk(output_root.join("unrelated.js"), vec![])],
+            ),
+        ],
+    );
+    AssetsVc::cell(vec![entry, shared])
+}
+
+#[tokio::test]
+async fn lazy_chunks_behind_manifests() {
+    run! {
+        let root = VirtualFileSystemVc::new().root();
+        let graph = PreloadGraphVc::new(root.join("output"), entry_chunk_group(root)).await?;
+        assert_eq!(graph.sync, vec!["entry.js", "shared.js"]);
+        assert_eq!(graph.lazy, vec!["lazy.js", "nested.js"]);
+    }
+}
+
+#[tokio::test]
+async fn preload_manifest() {
+    run! {
+        let root = VirtualFileSystemVc::new().root();
+        let output_root = root.join("output");
+        let manifest = PreloadManifestAssetVc::new(
+            output_root.join("preload-manifest.json"),
+            output_root,
+            EntryChunkGroupsVc::cell(
+                [("index.js".to_string(), entry_chunk_group(root))]
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+        let content = manifest.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            panic!("the preload manifest has no content");
+        };
+        let manifest: serde_json::Value = serde_json::from_str(&file.content().to_str()?)?;
+        assert_eq!(
+            manifest,
+            serde_json::json!({
+                "entries": {
+                    "index.js": {
+                        "sync": ["entry.js", "shared.js"],
+                        "lazy": ["lazy.js", "nested.js"],
+                    },
+                },
+            })
+        );
+    }
+}
@@ -0,0 +1,353 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_fs::{glob::GlobVc, DiskFileSystemVc, FileContent, FileSystem, FileSystemPathVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::{
+    asset::Asset,
+    file_source::FileSourceVc,
+    issue::{Issue, IssueVc},
+    resolve::{
+        options::{
+            EnforceExtension, ExternalMatcher, ExternalRule, ExternalType, Externals, ImportMap,
+            ImportMapping, ResolveInPackage, ResolveIntoPackage, ResolveModules, ResolveOptions,
+            ResolveOptionsVc, TypesOnlyResolve,
+        },
+        parse::RequestVc,
+        resolve, PrimaryResolveResult, ResolveResult, ResolveResultVc,
+    },
+};
+
+register!(turbopack_core::register);
+
+fn fixtures() -> FileSystemPathVc {
+    DiskFileSystemVc::new(
+        "fixtures".to_string(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/resolve").to_string(),
+    )
+    .root()
+}
+
+fn options(fixtures: FileSystemPathVc) -> ResolveOptions {
+    ResolveOptions {
+        extensions: vec![".js".to_string()],
+        modules: vec![
+            ResolveModules::Nested(fixtures, vec!["node_modules".to_string()]),
+            ResolveModules::Fallback(fixtures.join("global/node_modules")),
+        ],
+        into_package: vec![
+            ResolveIntoPackage::MainField("main".to_string()),
+            ResolveIntoPackage::Default("index".to_string()),
+        ],
+        ..Default::default()
+    }
+}
+
+/// Resolves `request` from the `project/src` directory of the fixtures and
+/// returns the paths of the resolved assets.
+async fn resolve_paths(options: ResolveOptionsVc, request: &str) -> Result<Vec<String>> {
+    let result = resolve_request(options, request);
+    paths(result).await
+}
+
+fn resolve_request(options: ResolveOptionsVc, request: &str) -> ResolveResultVc {
+    resolve(
+        fixtures().join("project/src"),
+        RequestVc::parse_string(request.to_string()),
+        options,
+    )
+}
+
+async fn paths(result: ResolveResultVc) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for asset in result.primary_assets().await?.iter() {
+        paths.push(asset.ident().path().await?.path.to_string());
+    }
+    Ok(paths)
+}
+
+/// Returns the details of the issues which were emitted while resolving.
+async fn issue_details(result: ResolveResultVc) -> Result<Vec<String>> {
+    let issues = IssueVc::peek_issues_with_path(result).await?.await?;
+    let mut details = Vec::new();
+    for issue in issues.iter() {
+        details.push(issue.detail().await?.clone_value());
+    }
+    Ok(details)
+}
+
+#[tokio::test]
+async fn alias_fields() {
+    run! {
+        let fixtures = fixtures();
+        let react_native = ResolveOptions {
+            in_package: vec![ResolveInPackage::AliasField(vec![
+                "react-native".to_string()
+            ])],
+            ..options(fixtures)
+        }
+        .cell();
+        assert_eq!(
+            resolve_paths(react_native, "lodash").await?,
+            ["project/src/lodash-native.js"]
+        );
+
+        let nested = ResolveOptions {
+            in_package: vec![ResolveInPackage::AliasField(vec![
+                "custom".to_string(),
+                "aliases".to_string(),
+            ])],
+            ..options(fixtures)
+        }
+        .cell();
+        assert_eq!(
+            resolve_paths(nested, "lodash").await?,
+            ["project/src/lodash-custom.js"]
+        );
+
+        let without = options(fixtures).cell();
+        assert_eq!(
+            resolve_paths(without, "lodash").await?,
+            Vec::<String>::new()
+        );
+    }
+}
+
+#[tokio::test]
+async fn fallback_modules() {
+    run! {
+        let options = options(fixtures()).cell();
+        assert_eq!(
+            resolve_paths(options, "global-pkg").await?,
+            ["global/node_modules/global-pkg/index.js"]
+        );
+        // Packages in the nested node_modules take precedence.
+        assert_eq!(
+            resolve_paths(options, "local-pkg").await?,
+            ["project/node_modules/local-pkg/index.js"]
+        );
+    }
+}
+
+#[tokio::test]
+async fn types_only_warning() {
+    run! {
+        let options = ResolveOptions {
+            types_only: Some(TypesOnlyResolve::Warn),
+            ..options(fixtures())
+        }
+        .cell();
+        let result = resolve_request(options, "types-only");
+        assert_eq!(
+            paths(result).await?,
+            ["project/node_modules/types-only/index.d.ts"]
+        );
+        let details = issue_details(result).await?;
+        assert_eq!(details.len(), 1);
+        assert!(details[0].contains("only contains type declarations"));
+    }
+}
+
+#[tokio::test]
+async fn types_only_stand_in() {
+    run! {
+        let fixtures = fixtures();
+        let stand_in = ResolveResult::asset(
+            FileSourceVc::new(fixtures.join("project/src/stand-in.js")).into(),
+        )
+        .cell();
+        let options = ResolveOptions {
+            types_only: Some(TypesOnlyResolve::StandIn(
+                ImportMapping::Direct(stand_in).cell(),
+            )),
+            ..options(fixtures)
+        }
+        .cell();
+        let result = resolve_request(options, "types-only");
+        assert_eq!(paths(result).await?, ["project/src/stand-in.js"]);
+        let details = issue_details(result).await?;
+        assert_eq!(details.len(), 1);
+        assert!(details[0].contains("A runtime stand-in is used instead."));
+    }
+}
+
+#[tokio::test]
+async fn data_uri() {
+    run! {
+        let options = options(fixtures()).cell();
+        let result = resolve_request(options, "data:text/javascript,export%20default%201");
+        let assets = result.primary_assets().await?;
+        assert_eq!(assets.len(), 1);
+        let path = assets[0].ident().path().await?;
+        assert!(path.path.starts_with("project/src/__data_uri__/"));
+        assert!(path.path.ends_with(".mjs"));
+        let content = assets[0].content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            panic!("the data uri has no content");
+        };
+        assert_eq!(file.content().to_str()?, "export default 1");
+
+        let result = resolve_request(options, "data:application/json;base64,eyJhIjoxfQ==");
+        let assets = result.primary_assets().await?;
+        assert_eq!(assets.len(), 1);
+        assert!(assets[0].ident().path().await?.path.ends_with(".json"));
+        let content = assets[0].content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            panic!("the data uri has no content");
+        };
+        assert_eq!(file.content().to_str()?, "{\"a\":1}");
+    }
+}
+
+#[tokio::test]
+async fn enforce_extension_required() {
+    run! {
+        let options = ResolveOptions {
+            enforce_extension: Some(EnforceExtension::Required),
+            ..options(fixtures())
+        }
+        .cell();
+
+        let result = resolve_request(options, "./button");
+        // Violations don't change the result.
+        assert_eq!(paths(result).await?, ["project/src/button.js"]);
+        let details = issue_details(result).await?;
+        assert_eq!(details.len(), 1);
+        assert!(details[0].contains("Use \"./button.js\" instead."));
+
+        let result = resolve_request(options, "./button.js");
+        assert_eq!(paths(result).await?, ["project/src/button.js"]);
+        assert_eq!(issue_details(result).await?, Vec::<String>::new());
+    }
+}
+
+#[tokio::test]
+async fn enforce_extension_forbidden() {
+    run! {
+        let options = ResolveOptions {
+            enforce_extension: Some(EnforceExtension::Forbidden),
+            ..options(fixtures())
+        }
+        .cell();
+
+        let result = resolve_request(options, "./button.js");
+        assert_eq!(paths(result).await?, ["project/src/button.js"]);
+        let details = issue_details(result).await?;
+        assert_eq!(details.len(), 1);
+        assert!(details[0].contains("Use \"./button\" instead."));
+
+        let result = resolve_request(options, "./button");
+        assert_eq!(paths(result).await?, ["project/src/button.js"]);
+        assert_eq!(issue_details(result).await?, Vec::<String>::new());
+    }
+}
+
+#[tokio::test]
+async fn builtins() {
+    run! {
+        // Without polyfills, builtins are left to the runtime.
+        let result = resolve_request(options(fixtures()).cell(), "node:path");
+        assert_eq!(
+            result.await?.primary,
+            [PrimaryResolveResult::OriginalReferenceTypeExternal(
+                "node:path".to_string(),
+                ExternalType::CommonJs
+            )]
+        );
+
+        let mut polyfills = ImportMap::default();
+        polyfills.insert_exact_alias(
+            "path",
+            ImportMapping::PrimaryAlternative("./button.js".to_string(), None).cell(),
+        );
+        let options = ResolveOptions {
+            builtin_polyfills: Some(polyfills.cell()),
+            ..options(fixtures())
+        }
+        .cell();
+        let result = resolve_request(options, "node:path");
+        assert_eq!(paths(result).await?, ["project/src/button.js"]);
+
+        // Builtins without polyfill are errors once polyfills are configured.
+        let result = resolve_request(options, "node:fs");
+        assert!(result.await?.is_unresolveable());
+        assert_eq!(issue_details(result).await?.len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn externals() {
+    run! {
+        let options = ResolveOptions {
+            externals: Some(
+                Externals {
+                    rules: vec![
+                        ExternalRule {
+                            matcher: ExternalMatcher::Glob(GlobVc::new("@aws-sdk/*")),
+                            name: None,
+                            ty: ExternalType::CommonJs,
+                        },
+                        ExternalRule {
+                            matcher: ExternalMatcher::regex("^react(-dom)?$")?,
+                            name: Some("React".to_string()),
+                            ty: ExternalType::Global,
+                        },
+                        ExternalRule {
+                            matcher: ExternalMatcher::regex("^local-")?,
+                            name: None,
+                            ty: ExternalType::EcmaScriptModule,
+                        },
+                    ],
+                }
+                .cell(),
+            ),
+            ..options(fixtures())
+        }
+        .cell();
+
+        let result = resolve_request(options, "@aws-sdk/client-s3");
+        assert_eq!(
+            result.await?.primary,
+            [PrimaryResolveResult::OriginalReferenceTypeExternal(
+                "@aws-sdk/client-s3".to_string(),
+                ExternalType::CommonJs
+            )]
+        );
+
+        let result = resolve_request(options, "react-dom");
+        assert_eq!(
+            result.await?.primary,
+            [PrimaryResolveResult::OriginalReferenceTypeExternal(
+                "React".to_string(),
+                ExternalType::Global
+            )]
+        );
+
+        // The regex is matched against the full request, not just the package
+        // name.
+        let result = resolve_request(options, "react-dom/client");
+        assert!(result.await?.is_unresolveable());
+
+        // Packages are externals even when they could be resolved.
+        let result = resolve_request(options, "local-pkg");
+        assert_eq!(
+            result.await?.primary,
+            [PrimaryResolveResult::OriginalReferenceTypeExternal(
+                "local-pkg".to_string(),
+                ExternalType::EcmaScriptModule
+            )]
+        );
+
+        // Only module requests are externals.
+        assert_eq!(
+            resolve_paths(options, "./button.js").await?,
+            ["project/src/button.js"]
+        );
+    }
+}
+
+#[test]
+fn invalid_externals_regex() {
+    let err = ExternalMatcher::regex("(").unwrap_err();
+    assert_eq!(err.to_string(), "invalid externals regex: (");
+}
@@ -0,0 +1,176 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_fs::{FileSystem, FileSystemPathVc, VirtualFileSystemVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::resolve::{
+    options::{
+        ImportMap, ImportMapResult, ImportMapping, ImportMappingVc, ResolveModules, ResolveOptions,
+        ResolveOptionsVc, ResolvedMap, ResolvedMatcher,
+    },
+    parse::RequestVc,
+};
+
+register!(turbopack_core::register);
+
+fn alias(request: &str) -> ImportMappingVc {
+    ImportMapping::PrimaryAlternative(request.to_string(), None).cell()
+}
+
+/// Looks up `request` in the import map of `options` and returns the request
+/// it's aliased to.
+async fn lookup_alias(
+    options: ResolveOptionsVc,
+    context: FileSystemPathVc,
+    request: &str,
+) -> Result<Option<String>> {
+    let Some(import_map) = options.await?.import_map else {
+        return Ok(None);
+    };
+    let result = import_map
+        .lookup(context, RequestVc::parse_string(request.to_string()))
+        .await?;
+    Ok(match &*result {
+        ImportMapResult::Alias(request, _) => request.await?.request(),
+        _ => None,
+    })
+}
+
+#[tokio::test]
+async fn with_alias() {
+    run! {
+        let context = VirtualFileSystemVc::new().root();
+        let options = ResolveOptions::default()
+            .cell()
+            .with_alias("react", alias("preact/compat"))
+            .with_alias("lodash/*", alias("lodash-es/*"));
+        assert_eq!(
+            lookup_alias(options, context, "react").await?.as_deref(),
+            Some("preact/compat")
+        );
+        assert_eq!(
+            lookup_alias(options, context, "lodash/get")
+                .await?
+                .as_deref(),
+            Some("lodash-es/get")
+        );
+        assert_eq!(lookup_alias(options, context, "vue").await?, None);
+    }
+}
+
+#[tokio::test]
+async fn with_import_map_extension() {
+    run! {
+        let context = VirtualFileSystemVc::new().root();
+        let mut import_map = ImportMap::empty();
+        import_map.insert_exact_alias("a", alias("b"));
+        let options = ResolveOptions {
+            import_map: Some(import_map.cell()),
+            ..Default::default()
+        }
+        .cell();
+
+        let mut extension = ImportMap::empty();
+        extension.insert_exact_alias("c", alias("d"));
+        let extended = options.with_import_map_extension(extension.cell());
+
+        assert_eq!(
+            lookup_alias(extended, context, "a").await?.as_deref(),
+            Some("b")
+        );
+        assert_eq!(
+            lookup_alias(extended, context, "c").await?.as_deref(),
+            Some("d")
+        );
+        // The original options are left untouched.
+        assert_eq!(lookup_alias(options, context, "c").await?, None);
+    }
+}
+
+#[tokio::test]
+async fn with_extensions() {
+    run! {
+        let options = ResolveOptions {
+            extensions: vec![".js".to_string()],
+            ..Default::default()
+        }
+        .cell();
+        let derived = options.with_extensions(vec![".ts".to_string(), ".tsx".to_string()]);
+        assert_eq!(derived.await?.extensions, [".ts", ".tsx"]);
+        assert_eq!(options.await?.extensions, [".js"]);
+    }
+}
+
+#[tokio::test]
+async fn with_modules_path() {
+    run! {
+        let root = VirtualFileSystemVc::new().root();
+        let node_modules = root.join("node_modules").resolve().await?;
+        let vendor = root.join("vendor").resolve().await?;
+        let options = ResolveOptions {
+            modules: vec![ResolveModules::Path(node_modules)],
+            ..Default::default()
+        }
+        .cell();
+        let derived = options.with_modules_path(vendor);
+        assert_eq!(
+            derived.await?.modules,
+            [
+                ResolveModules::Path(vendor),
+                ResolveModules::Path(node_modules)
+            ]
+        );
+    }
+}
+
+#[tokio::test]
+async fn resolved_map_matchers() {
+    run! {
+        let root = VirtualFileSystemVc::new().root();
+        let resolved_map = ResolvedMap {
+            by_glob: Vec::new(),
+            by_matcher: vec![
+                (
+                    ResolvedMatcher::Package("@scope/native".to_string()),
+                    ImportMapping::Ignore.cell(),
+                ),
+                (
+                    ResolvedMatcher::Package("sharp".to_string()),
+                    ImportMapping::Ignore.cell(),
+                ),
+                (
+                    ResolvedMatcher::ContentType("application/node".to_string()),
+                    ImportMapping::Ignore.cell(),
+                ),
+                (
+                    ResolvedMatcher::ContentType("image/*".to_string()),
+                    ImportMapping::Ignore.cell(),
+                ),
+            ],
+        }
+        .cell();
+        let matches = |path: &'static str| async move {
+            let result = resolved_map
+                .lookup(
+                    root.join(path),
+                    root,
+                    RequestVc::parse_string("x".to_string()),
+                )
+                .await?;
+            anyhow::Ok(!matches!(&*result, ImportMapResult::NoEntry))
+        };
+
+        assert!(matches("node_modules/sharp/lib/index.js").await?);
+        assert!(matches("node_modules/a/node_modules/sharp/index.js").await?);
+        assert!(matches("node_modules/@scope/native/index.js").await?);
+        assert!(!matches("node_modules/sharp-cli/index.js").await?);
+        assert!(!matches("node_modules/@scope/other/index.js").await?);
+        assert!(!matches("src/sharp/index.js").await?);
+
+        assert!(matches("build/Release/addon.node").await?);
+        assert!(matches("assets/logo.png").await?);
+        assert!(matches("assets/logo.svg").await?);
+        assert!(!matches("src/index.js").await?);
+        assert!(!matches("README").await?);
+    }
+}
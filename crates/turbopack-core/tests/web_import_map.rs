@@ -0,0 +1,122 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemPathVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::{
+    asset::Asset,
+    issue::{Issue, IssueVc},
+    resolve::{
+        options::{ExternalType, ImportMapVc, ResolveOptions, ResolveOptionsVc},
+        parse::RequestVc,
+        resolve,
+        web_import_map::read_web_import_map,
+        PrimaryResolveResult, ResolveResultVc,
+    },
+};
+
+register!(turbopack_core::register);
+
+fn fixtures() -> FileSystemPathVc {
+    DiskFileSystemVc::new(
+        "fixtures".to_string(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/web_import_map").to_string(),
+    )
+    .root()
+}
+
+fn options(import_map: ImportMapVc) -> ResolveOptionsVc {
+    ResolveOptions {
+        extensions: vec![".js".to_string()],
+        import_map: Some(import_map),
+        ..Default::default()
+    }
+    .cell()
+}
+
+fn resolve_request(context: FileSystemPathVc, import_map: &str, request: &str) -> ResolveResultVc {
+    resolve(
+        context,
+        RequestVc::parse_string(request.to_string()),
+        options(read_web_import_map(fixtures().join(import_map))),
+    )
+}
+
+async fn paths(result: ResolveResultVc) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for asset in result.primary_assets().await?.iter() {
+        paths.push(asset.ident().path().await?.path.to_string());
+    }
+    Ok(paths)
+}
+
+#[tokio::test]
+async fn imports() {
+    run! {
+        let src = fixtures().join("src");
+
+        let result = resolve_request(src, "import_map.json", "react");
+        assert_eq!(
+            result.await?.primary,
+            [PrimaryResolveResult::OriginalReferenceTypeExternal(
+                "https://esm.sh/react@18".to_string(),
+                ExternalType::Url
+            )]
+        );
+
+        let result = resolve_request(src, "import_map.json", "lodash/get.js");
+        assert_eq!(paths(result).await?, ["vendor/lodash/get.js"]);
+
+        let result = resolve_request(src, "import_map.json", "utils");
+        assert_eq!(paths(result).await?, ["src/utils.js"]);
+    }
+}
+
+#[tokio::test]
+async fn scopes() {
+    run! {
+        let legacy = fixtures().join("legacy");
+
+        // The scope takes precedence over the top-level imports.
+        let result = resolve_request(legacy, "import_map.json", "react");
+        assert_eq!(paths(result).await?, ["vendor/react-17.js"]);
+
+        // Imports which are not in the scope fall back to the top-level ones.
+        let result = resolve_request(legacy, "import_map.json", "lodash/get.js");
+        assert_eq!(paths(result).await?, ["vendor/lodash/get.js"]);
+
+        // Scopes don't apply outside of their directory.
+        let result = resolve_request(fixtures().join("src"), "import_map.json", "react");
+        assert!(paths(result).await?.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn invalid_entries() {
+    run! {
+        let import_map = read_web_import_map(fixtures().join("invalid.json"));
+        let issues = IssueVc::peek_issues_with_path(import_map).await?.await?;
+        let mut descriptions = Vec::new();
+        for issue in issues.iter() {
+            descriptions.push(issue.description().await?.clone_value());
+        }
+        descriptions.sort();
+        assert_eq!(
+            descriptions,
+            [
+                "the import \"./local\" must be a bare specifier, URL-like specifiers are not \
+                 supported",
+                "the import \"number\" must map to a string",
+                "the scope \"https://example.com/\" must be a path within the project",
+            ]
+        );
+
+        // Valid entries are still applied.
+        let result = resolve(
+            fixtures().join("src"),
+            RequestVc::parse_string("utils".to_string()),
+            options(import_map),
+        );
+        assert_eq!(paths(result).await?, ["src/utils.js"]);
+    }
+}
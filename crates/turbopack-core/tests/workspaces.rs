@@ -0,0 +1,92 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemPathVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::{
+    asset::Asset,
+    resolve::{
+        options::{ResolveIntoPackage, ResolveModules, ResolveOptions},
+        parse::RequestVc,
+        resolve,
+        workspaces::workspace_packages,
+    },
+};
+
+register!(turbopack_core::register);
+
+fn fixtures() -> FileSystemPathVc {
+    DiskFileSystemVc::new(
+        "fixtures".to_string(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/workspaces").to_string(),
+    )
+    .root()
+}
+
+/// Returns the names of the workspace packages at `root` and the paths of
+/// their directories.
+async fn packages(root: FileSystemPathVc) -> Result<Vec<(String, String)>> {
+    let mut packages = Vec::new();
+    for (name, dir) in workspace_packages(root).await?.iter() {
+        packages.push((name.clone(), dir.await?.path.to_string()));
+    }
+    Ok(packages)
+}
+
+#[tokio::test]
+async fn package_json_workspaces() {
+    run! {
+        // Packages in node_modules and excluded packages are not part of the
+        // workspace.
+        assert_eq!(
+            packages(fixtures().join("npm")).await?,
+            [
+                ("a".to_string(), "npm/packages/a".to_string()),
+                ("b".to_string(), "npm/packages/nested/b".to_string()),
+                ("web".to_string(), "npm/apps/web".to_string()),
+            ]
+        );
+    }
+}
+
+#[tokio::test]
+async fn pnpm_workspaces() {
+    run! {
+        assert_eq!(
+            packages(fixtures().join("pnpm")).await?,
+            [("@scope/d".to_string(), "pnpm/libs/d".to_string())]
+        );
+    }
+}
+
+#[tokio::test]
+async fn resolves_workspace_packages() {
+    run! {
+        let root = fixtures().join("npm");
+        let options = ResolveOptions {
+            extensions: vec![".js".to_string()],
+            modules: vec![ResolveModules::Workspaces(workspace_packages(root))],
+            into_package: vec![ResolveIntoPackage::MainField("main".to_string())],
+            ..Default::default()
+        }
+        .cell();
+        let context = root.join("apps/web");
+        for (request, expected) in [
+            ("a", Some("npm/packages/a/index.js")),
+            ("b/index.js", Some("npm/packages/nested/b/index.js")),
+            ("c", None),
+        ] {
+            let result = resolve(
+                context,
+                RequestVc::parse_string(request.to_string()),
+                options,
+            );
+            let assets = result.primary_assets().await?;
+            let path = match assets.first() {
+                Some(asset) => Some(asset.ident().path().await?.path.to_string()),
+                None => None,
+            };
+            assert_eq!(path.as_deref(), expected, "resolving {request}");
+        }
+    }
+}
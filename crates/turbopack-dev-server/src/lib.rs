@@ -31,8 +31,8 @@ use socket2::{Domain, Protocol, Socket, Type};
 use tokio::task::JoinHandle;
 use tracing::{event, info_span, Instrument, Level, Span};
 use turbo_tasks::{
-    run_once_with_reason, trace::TraceRawVcs, util::FormatDuration, CollectiblesSource, RawVc,
-    TransientInstance, TransientValue, TurboTasksApi,
+    run_once_with_reason, trace::TraceRawVcs, util::FormatDuration, with_priority,
+    CollectiblesSource, RawVc, TaskPriority, TransientInstance, TransientValue, TurboTasksApi,
 };
 use turbopack_core::{
     error::PrettyPrintError,
@@ -193,7 +193,9 @@ impl DevServerBuilder {
                             method: request.method().clone(),
                             uri: request.uri().clone(),
                         };
-                        run_once_with_reason(tt.clone(), reason, async move {
+                        // The request is waiting for the result, so it's scheduled ahead of
+                        // other work.
+                        let run = run_once_with_reason(tt.clone(), reason, async move {
                             let issue_reporter = get_issue_reporter();
 
                             if hyper_tungstenite::is_upgrade_request(&request) {
@@ -270,8 +272,8 @@ impl DevServerBuilder {
                                 ));
                             }
                             Ok(response)
-                        })
-                        .await
+                        });
+                        with_priority(TaskPriority::Interactive, run).await
                     };
                     async move {
                         match future.await {
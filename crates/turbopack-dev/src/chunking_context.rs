@@ -10,7 +10,7 @@ use turbopack_core::{
     asset::{Asset, AssetVc, AssetsVc},
     chunk::{
         Chunk, ChunkVc, ChunkableModule, ChunkingContext, ChunkingContextVc, ChunksVc,
-        EvaluatableAssetsVc,
+        ContentHashing, ContentHashingVc, EvaluatableAssetsVc,
     },
     environment::EnvironmentVc,
     ident::AssetIdentVc,
@@ -66,6 +66,11 @@ impl DevChunkingContextBuilder {
         self
     }
 
+    pub fn content_hashing(mut self, content_hashing: ContentHashing) -> Self {
+        self.context.content_hashing = content_hashing;
+        self
+    }
+
     pub fn build(self) -> DevChunkingContextVc {
         DevChunkingContextVc::new(Value::new(self.context))
     }
@@ -103,6 +108,8 @@ pub struct DevChunkingContext {
     environment: EnvironmentVc,
     /// The kind of runtime to include in the output.
     runtime_type: RuntimeType,
+    /// How content hashes in static asset paths are computed.
+    content_hashing: ContentHashing,
 }
 
 impl DevChunkingContextVc {
@@ -126,6 +133,7 @@ impl DevChunkingContextVc {
                 enable_hot_module_replacement: false,
                 environment,
                 runtime_type: Default::default(),
+                content_hashing: Default::default(),
             },
         }
     }
@@ -256,16 +264,17 @@ impl ChunkingContext for DevChunkingContext {
             Some(ext) => format!(
                 "{basename}.{content_hash}.{ext}",
                 basename = &basename[..basename.len() - ext.len() - 1],
-                content_hash = &content_hash[..8]
-            ),
-            None => format!(
-                "{basename}.{content_hash}",
-                content_hash = &content_hash[..8]
             ),
+            None => format!("{basename}.{content_hash}"),
         };
         Ok(self.asset_root_path.join(&asset_path))
     }
 
+    #[turbo_tasks::function]
+    fn content_hashing(&self) -> ContentHashingVc {
+        self.content_hashing.cell()
+    }
+
     #[turbo_tasks::function]
     fn is_hot_module_replacement_enabled(&self) -> BoolVc {
         BoolVc::cell(self.enable_hot_module_replacement)
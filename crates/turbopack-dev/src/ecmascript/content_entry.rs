@@ -14,7 +14,7 @@ use turbopack_core::{
     issue::{code_gen::CodeGenerationIssue, IssueSeverity},
 };
 use turbopack_ecmascript::chunk::{
-    EcmascriptChunkContentVc, EcmascriptChunkItem, EcmascriptChunkItemVc,
+    AsyncModuleInfoVc, EcmascriptChunkContentVc, EcmascriptChunkItem, EcmascriptChunkItemVc,
 };
 
 /// A chunk item's content entry.
@@ -34,9 +34,10 @@ impl EcmascriptDevChunkContentEntry {
     pub async fn new(
         chunk_item: EcmascriptChunkItemVc,
         availability_info: AvailabilityInfo,
+        async_module_info: AsyncModuleInfoVc,
     ) -> Result<Self> {
         let code = chunk_item
-            .code(Value::new(availability_info))
+            .code(Value::new(availability_info), async_module_info)
             .resolve()
             .await?;
         Ok(EcmascriptDevChunkContentEntry {
@@ -57,6 +58,7 @@ impl EcmascriptDevChunkContentEntriesVc {
     pub async fn new(
         chunk_content: EcmascriptChunkContentVc,
     ) -> Result<EcmascriptDevChunkContentEntriesVc> {
+        let async_module_info = AsyncModuleInfoVc::new(chunk_content);
         let chunk_content = chunk_content.await?;
         let availability_info = chunk_content.availability_info;
 
@@ -67,7 +69,12 @@ impl EcmascriptDevChunkContentEntriesVc {
                 async move {
                     Ok((
                         chunk_item.id().await?,
-                        EcmascriptDevChunkContentEntry::new(*chunk_item, availability_info).await?,
+                        EcmascriptDevChunkContentEntry::new(
+                            *chunk_item,
+                            availability_info,
+                            async_module_info,
+                        )
+                        .await?,
                     ))
                 }
                 .instrument(info_span!(
@@ -2,8 +2,15 @@ use anyhow::Result;
 use async_trait::async_trait;
 use swc_core::ecma::ast::Program;
 use turbo_tasks::primitives::StringVc;
-use turbo_tasks_fs::FileSystemPathVc;
-use turbopack_core::issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc};
+use turbo_tasks_fs::{FileContent, FileSystemPathVc};
+use turbopack_core::{
+    asset::Asset,
+    issue::{
+        resolve::{ResolvingIssue, ResolvingIssueVc},
+        Issue, IssueSeverity, IssueSeverityVc, IssueVc, OptionIssueSourceVc,
+    },
+    resolve::{node::node_cjs_resolve_options, parse::RequestVc, resolve},
+};
 use turbopack_ecmascript::{CustomTransformer, TransformContext};
 
 /// A wrapper around an SWC's ecma transform wasm plugin module bytes, allowing
@@ -52,6 +59,47 @@ impl SwcPluginModule {
     }
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct OptionSwcPluginModule(Option<SwcPluginModuleVc>);
+
+#[turbo_tasks::value_impl]
+impl SwcPluginModuleVc {
+    /// Resolves the SWC plugin `name` from `context` and compiles it. `name` is
+    /// either the name of a package whose main entry is the Wasm binary, like
+    /// in `jsc.experimental.plugins` of `.swcrc`, or a path to the Wasm binary.
+    #[turbo_tasks::function]
+    pub async fn resolve(context: FileSystemPathVc, name: &str) -> Result<OptionSwcPluginModuleVc> {
+        let request = RequestVc::parse_string(name.to_string());
+        let resolve_options = node_cjs_resolve_options(context.root());
+        let asset = *resolve(context, request, resolve_options)
+            .first_asset()
+            .await?;
+        let content = match asset {
+            Some(asset) => Some(asset.content().file_content().await?),
+            None => None,
+        };
+        let Some(FileContent::Content(file)) = content.as_deref() else {
+            let issue: ResolvingIssueVc = ResolvingIssue {
+                severity: IssueSeverity::Error.cell(),
+                request_type: "swc plugin".to_string(),
+                request,
+                context,
+                resolve_options,
+                error_message: Some(format!("unable to load the SWC plugin \"{name}\"")),
+                source: OptionIssueSourceVc::none(),
+            }
+            .into();
+            issue.as_issue().emit();
+            return Ok(OptionSwcPluginModuleVc::cell(None));
+        };
+
+        let plugin_bytes = file.content().to_bytes()?.into_owned();
+        Ok(OptionSwcPluginModuleVc::cell(Some(
+            SwcPluginModule::new(name, plugin_bytes).into(),
+        )))
+    }
+}
+
 #[turbo_tasks::value(shared)]
 struct UnsupportedSwcEcmaTransformPluginsIssue {
     pub context: FileSystemPathVc,
@@ -105,10 +153,11 @@ impl SwcEcmaTransformPluginsTransformer {
     }
 
     // [TODO] Due to WEB-1102 putting this module itself behind compile time feature
-    // doesn't work. Instead allow to instantiate dummy instance.
+    // doesn't work. Instead allow to instantiate dummy instance, which reports an
+    // issue instead of running the plugins.
     #[cfg(not(feature = "swc_ecma_transform_plugin"))]
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
+    pub fn new(plugins: Vec<(SwcPluginModuleVc, serde_json::Value)>) -> Self {
+        let _ = plugins;
         Self {}
     }
 }
@@ -0,0 +1,68 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemPathVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::issue::{Issue, IssueSeverity, IssueVc};
+use turbopack_ecmascript_plugins::transform::swc_ecma_transform_plugins::{
+    OptionSwcPluginModuleVc, SwcPluginModuleVc,
+};
+
+register!(turbopack_ecmascript_plugins::register);
+
+fn fixtures() -> FileSystemPathVc {
+    DiskFileSystemVc::new(
+        "fixtures".to_string(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/swc_plugins").to_string(),
+    )
+    .root()
+}
+
+/// Returns the severity, title and detail of the issues which were emitted
+/// while resolving the plugin.
+async fn issues(plugin: OptionSwcPluginModuleVc) -> Result<Vec<(IssueSeverity, String, String)>> {
+    let issues = IssueVc::peek_issues_with_path(plugin).await?.await?;
+    let mut result = Vec::new();
+    for issue in issues.iter() {
+        result.push((
+            *issue.severity().await?,
+            issue.title().await?.clone_value(),
+            issue.detail().await?.clone_value(),
+        ));
+    }
+    Ok(result)
+}
+
+#[tokio::test]
+async fn missing_package() {
+    run! {
+        let plugin = SwcPluginModuleVc::resolve(fixtures(), "swc-plugin-missing");
+        assert!(plugin.await?.is_none());
+
+        let issues = issues(plugin).await?;
+        assert_eq!(issues.len(), 1);
+        let (severity, title, detail) = &issues[0];
+        assert_eq!(*severity, IssueSeverity::Error);
+        assert_eq!(title, "Error resolving swc plugin");
+        assert!(
+            detail.contains("unable to load the SWC plugin \"swc-plugin-missing\""),
+            "{detail}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn missing_file() {
+    run! {
+        let plugin = SwcPluginModuleVc::resolve(fixtures(), "./missing.wasm");
+        assert!(plugin.await?.is_none());
+
+        let issues = issues(plugin).await?;
+        assert_eq!(issues.len(), 1);
+        let (_, _, detail) = &issues[0];
+        assert!(
+            detail.contains("unable to load the SWC plugin \"./missing.wasm\""),
+            "{detail}"
+        );
+    }
+}
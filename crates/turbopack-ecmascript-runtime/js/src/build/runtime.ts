@@ -34,6 +34,7 @@ interface TurbopackNodeBuildContext {
   e: Module["exports"];
   r: CommonJsRequire;
   x: ExternalRequire;
+  P: ResolveAbsolutePath;
   f: RequireContextFactory;
   i: EsmImport;
   s: EsmExport;
@@ -43,6 +44,8 @@ interface TurbopackNodeBuildContext {
   m: Module;
   c: ModuleCache;
   l: LoadChunk;
+  w: LoadWebAssembly;
+  a: AsyncModule;
   g: typeof globalThis;
   __dirname: string;
 }
@@ -53,6 +56,7 @@ type ModuleFactory = (
 ) => undefined;
 
 const path = require("path");
+const fs = require("fs/promises");
 const relativePathToRuntimeRoot = path.relative(RUNTIME_PUBLIC_PATH, ".");
 const RUNTIME_ROOT = path.resolve(__filename, relativePathToRuntimeRoot);
 
@@ -130,6 +134,26 @@ function loadChunkAsync(source: SourceInfo, chunkPath: string): Promise<void> {
   });
 }
 
+/**
+ * Returns an absolute path to the given module path, which is relative to the
+ * output directory. Without a module path, the output directory is returned.
+ */
+function resolveAbsolutePath(modulePath?: string): string {
+  if (modulePath) {
+    return path.join(RUNTIME_ROOT, modulePath);
+  }
+  return RUNTIME_ROOT;
+}
+
+async function loadWebAssembly(
+  wasmChunkPath: ChunkPath,
+  imports: WebAssemblyImports
+): Promise<Exports> {
+  const buffer = await fs.readFile(path.resolve(RUNTIME_ROOT, wasmChunkPath));
+  const { instance } = await WebAssembly.instantiate(buffer, imports);
+  return instance.exports;
+}
+
 function instantiateModule(id: ModuleId, source: SourceInfo): Module {
   const moduleFactory = moduleFactories[id];
   if (typeof moduleFactory !== "function") {
@@ -179,6 +203,7 @@ function instantiateModule(id: ModuleId, source: SourceInfo): Module {
       e: module.exports,
       r: commonJsRequire.bind(null, module),
       x: externalRequire,
+      P: resolveAbsolutePath,
       f: requireContext.bind(null, module),
       i: esmImport.bind(null, module),
       s: esm.bind(null, module.exports),
@@ -188,6 +213,8 @@ function instantiateModule(id: ModuleId, source: SourceInfo): Module {
       m: module,
       c: moduleCache,
       l: loadChunkAsync.bind(null, { type: SourceType.Parent, parentId: id }),
+      w: loadWebAssembly,
+      a: asyncModule.bind(null, module),
       g: globalThis,
       __dirname: module.id.replace(/(^|\/)[\/]+$/, ""),
     });
@@ -198,8 +225,9 @@ function instantiateModule(id: ModuleId, source: SourceInfo): Module {
 
   module.loaded = true;
   if (module.namespaceObject && module.exports !== module.namespaceObject) {
-    // in case of a circular dependency: cjs1 -> esm2 -> cjs1
-    interopEsm(module.exports, module.namespaceObject);
+    // in case of a circular dependency: cjs1 -> esm2 -> cjs1, the exports
+    // which have been added while loading are still missing
+    interopCommonJs(module, module.namespaceObject);
   }
 
   return module;
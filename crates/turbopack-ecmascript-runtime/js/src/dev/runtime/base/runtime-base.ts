@@ -45,6 +45,8 @@ interface TurbopackDevBaseContext {
   m: Module;
   c: ModuleCache;
   l: LoadChunk;
+  w: LoadWebAssembly;
+  a: AsyncModule;
   g: typeof globalThis;
   k: RefreshContext;
   __dirname: string;
@@ -110,6 +112,11 @@ type SourceInfo =
 interface RuntimeBackend {
   registerChunk: (chunkPath: ChunkPath, params?: DevRuntimeParams) => void;
   loadChunk: (chunkPath: ChunkPath, source: SourceInfo) => Promise<void>;
+  loadWebAssembly: (
+    wasmChunkPath: ChunkPath,
+    source: SourceInfo,
+    imports: WebAssemblyImports
+  ) => Promise<Exports>;
   reloadChunk?: (chunkPath: ChunkPath) => Promise<void>;
   unloadChunk?: (chunkPath: ChunkPath) => void;
 
@@ -272,6 +279,18 @@ async function loadChunkPath(
   }
 }
 
+/**
+ * Instantiates the WebAssembly module at `wasmChunkPath` with the given
+ * imports, and returns its exports.
+ */
+function loadWebAssembly(
+  source: SourceInfo,
+  wasmChunkPath: ChunkPath,
+  imports: WebAssemblyImports
+): Promise<Exports> {
+  return BACKEND.loadWebAssembly(wasmChunkPath, source, imports);
+}
+
 function instantiateModule(id: ModuleId, source: SourceInfo): Module {
   const moduleFactory = moduleFactories[id];
   if (typeof moduleFactory !== "function") {
@@ -337,13 +356,18 @@ function instantiateModule(id: ModuleId, source: SourceInfo): Module {
           r: commonJsRequire.bind(null, module),
           f: requireContext.bind(null, module),
           i: esmImport.bind(null, module),
-          s: esmExport.bind(null, module),
+          s: esmExport.bind(null, module, module.exports),
           j: dynamicExport.bind(null, module),
           v: exportValue.bind(null, module),
           n: exportNamespace.bind(null, module),
           m: module,
           c: moduleCache,
           l: loadChunk.bind(null, { type: SourceType.Parent, parentId: id }),
+          w: loadWebAssembly.bind(null, {
+            type: SourceType.Parent,
+            parentId: id,
+          }),
+          a: asyncModule.bind(null, module),
           g: globalThis,
           k: refresh,
           __dirname: module.id.replace(/(^|\/)\/+$/, ""),
@@ -357,8 +381,9 @@ function instantiateModule(id: ModuleId, source: SourceInfo): Module {
 
   module.loaded = true;
   if (module.namespaceObject && module.exports !== module.namespaceObject) {
-    // in case of a circular dependency: cjs1 -> esm2 -> cjs1
-    interopEsm(module.exports, module.namespaceObject);
+    // in case of a circular dependency: cjs1 -> esm2 -> cjs1, the exports
+    // which have been added while loading are still missing
+    interopCommonJs(module, module.namespaceObject);
   }
 
   return module;
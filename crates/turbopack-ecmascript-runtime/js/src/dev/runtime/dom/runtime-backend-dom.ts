@@ -14,6 +14,11 @@ type ChunkResolver = {
   promise: Promise<void>;
 };
 
+/**
+ * Only available when the runtime is loaded in a web worker.
+ */
+declare var importScripts: ((...urls: string[]) => void) | undefined;
+
 let BACKEND: RuntimeBackend;
 
 function augmentContext(context: TurbopackDevBaseContext): TurbopackDevContext {
@@ -61,6 +66,12 @@ function commonJsRequireContext(
       return doLoadChunk(chunkPath, source);
     },
 
+    async loadWebAssembly(wasmChunkPath, _source, imports) {
+      const req = fetch(`/${getChunkRelativeUrl(wasmChunkPath)}`);
+      const { instance } = await WebAssembly.instantiateStreaming(req, imports);
+      return instance.exports;
+    },
+
     unloadChunk(chunkPath) {
       deleteResolver(chunkPath);
 
@@ -198,6 +209,22 @@ function commonJsRequireContext(
 
     const chunkUrl = `/${getChunkRelativeUrl(chunkPath)}`;
 
+    if (typeof importScripts === "function") {
+      // We're running in a web worker, which has no DOM to add the chunks to.
+      if (chunkPath.endsWith(".css")) {
+        // CSS has no effect in a worker.
+        resolver.resolve();
+      } else if (chunkPath.endsWith(".js")) {
+        // `importScripts` evaluates the chunk synchronously, which registers
+        // itself in `registerChunk`.
+        importScripts(chunkUrl);
+      } else {
+        throw new Error(`can't infer type of chunk from path ${chunkPath}`);
+      }
+
+      return resolver.promise;
+    }
+
     if (chunkPath.endsWith(".css")) {
       const link = document.createElement("link");
       link.rel = "stylesheet";
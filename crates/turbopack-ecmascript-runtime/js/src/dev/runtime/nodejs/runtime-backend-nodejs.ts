@@ -16,6 +16,7 @@ type ExternalRequire = (id: ModuleId) => Exports | EsmNamespaceObject;
 
 interface TurbopackDevContext {
   x: ExternalRequire;
+  P: ResolveAbsolutePath;
 }
 
 function commonJsRequireContext(
@@ -59,9 +60,31 @@ externalRequire.resolve = (
   return require.resolve(id, options);
 };
 
+/**
+ * The output directory, which is only known once the chunk containing the
+ * runtime has been registered.
+ */
+let OUTPUT_ROOT: string | undefined;
+
+/**
+ * Returns an absolute path to the given module path, which is relative to the
+ * output directory. Without a module path, the output directory is returned.
+ */
+function resolveAbsolutePath(modulePath?: string): string {
+  if (OUTPUT_ROOT == null) {
+    throw new Error("the output directory is not known yet");
+  }
+  const path = require("path");
+  if (modulePath) {
+    return path.join(OUTPUT_ROOT, modulePath);
+  }
+  return OUTPUT_ROOT;
+}
+
 function augmentContext(context: TurbopackDevBaseContext): TurbopackDevContext {
   const nodejsContext = context as TurbopackDevContext;
   nodejsContext.x = externalRequire;
+  nodejsContext.P = resolveAbsolutePath;
   return nodejsContext;
 }
 
@@ -74,6 +97,16 @@ let BACKEND: RuntimeBackend;
         return;
       }
 
+      // The runtime is part of this chunk, so the output directory can be
+      // derived from its path.
+      if (OUTPUT_ROOT == null) {
+        const path = require("path");
+        OUTPUT_ROOT = path.resolve(
+          __dirname,
+          path.relative(path.dirname(chunkPath), ".")
+        );
+      }
+
       if (params.runtimeModuleIds.length > 0) {
         for (const otherChunkData of params.otherChunks) {
           loadChunk(getChunkPath(otherChunkData), {
@@ -92,18 +125,23 @@ let BACKEND: RuntimeBackend;
       loadChunk(chunkPath, source);
     },
 
+    async loadWebAssembly(wasmChunkPath, source, imports) {
+      const fs = require("fs/promises");
+      const buffer = await fs.readFile(resolveChunkPath(wasmChunkPath, source));
+      const { instance } = await WebAssembly.instantiate(buffer, imports);
+      return instance.exports;
+    },
+
     restart: () => {
       throw new Error("restart not implemented for the Node.js backend");
     },
   };
 
-  function loadChunk(chunkPath: ChunkPath, source: SourceInfo) {
-    if (!chunkPath.endsWith(".js")) {
-      // We only support loading JS chunks in Node.js.
-      // This branch can be hit when trying to load a CSS chunk.
-      return;
-    }
-
+  /**
+   * Resolves the path of a chunk, relative to the chunk the runtime is loaded
+   * from.
+   */
+  function resolveChunkPath(chunkPath: ChunkPath, source: SourceInfo) {
     let fromChunkPath = undefined;
     switch (source.type) {
       case SourceType.Runtime:
@@ -116,12 +154,22 @@ let BACKEND: RuntimeBackend;
         break;
     }
 
-    // We'll only mark the chunk as loaded once the script has been executed,
-    // which happens in `registerChunk`. Hence the absence of `resolve()`.
     const path = require("path");
-    const resolved = require.resolve(
+    return require.resolve(
       "./" + path.relative(path.dirname(fromChunkPath), chunkPath)
     );
+  }
+
+  function loadChunk(chunkPath: ChunkPath, source: SourceInfo) {
+    if (!chunkPath.endsWith(".js")) {
+      // We only support loading JS chunks in Node.js.
+      // This branch can be hit when trying to load a CSS chunk.
+      return;
+    }
+
+    // We'll only mark the chunk as loaded once the script has been executed,
+    // which happens in `registerChunk`. Hence the absence of `resolve()`.
+    const resolved = resolveChunkPath(chunkPath, source);
     delete require.cache[resolved];
     require(resolved);
   }
@@ -63,6 +63,10 @@ function commonJsRequireContext(
       throw new Error("chunk loading is not supported");
     },
 
+    loadWebAssembly(wasmChunkPath, source, imports) {
+      throw new Error("loading WebAssembly is not supported");
+    },
+
     restart: () => {
       throw new Error("restart is not supported");
     },
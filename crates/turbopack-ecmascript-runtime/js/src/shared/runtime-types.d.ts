@@ -29,7 +29,14 @@ type EsmImport = (
 type EsmExport = (exportGetters: Record<string, () => any>) => void;
 type ExportValue = (value: any) => void;
 
+type ResolveAbsolutePath = (modulePath?: string) => string;
+
 type LoadChunk = (chunkPath: ChunkPath) => Promise<any> | undefined;
+type WebAssemblyImports = Record<string, Record<string, any>>;
+type LoadWebAssembly = (
+  wasmChunkPath: ChunkPath,
+  imports: WebAssemblyImports
+) => Promise<Exports>;
 
 type ModuleCache = Record<ModuleId, Module>;
 type ModuleFactories = Record<ModuleId, ModuleFactory>;
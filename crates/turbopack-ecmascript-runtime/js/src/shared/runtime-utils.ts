@@ -78,9 +78,18 @@ function esm(exports: Exports, getters: Record<string, () => any>) {
 
 /**
  * Makes the module an ESM with exports
+ *
+ * The getters are added to the `exports` object the module has been
+ * instantiated with. For async modules, `module.exports` is the module's
+ * promise by the time the exports are defined.
  */
-function esmExport(module: Module, getters: Record<string, () => any>) {
-  esm((module.namespaceObject = module.exports), getters);
+function esmExport(
+  module: Module,
+  exports: Exports,
+  getters: Record<string, () => any>
+) {
+  module.namespaceObject = module.exports;
+  esm(exports, getters);
 }
 
 /**
@@ -141,6 +150,26 @@ const getProto: (obj: any) => any = Object.getPrototypeOf
 /** Prototypes that are not expanded for exports */
 const LEAF_PROTOTYPES = [null, getProto({}), getProto([]), getProto(getProto)];
 
+/**
+ * Returns the names of the own and inherited properties of `raw`, which are
+ * exported to ESM. This includes non-enumerable properties, e.g. getters of
+ * classes.
+ */
+function getExportNames(raw: Exports): string[] {
+  const names: string[] = [];
+  for (
+    let current = raw;
+    (typeof current === "object" || typeof current === "function") &&
+    !LEAF_PROTOTYPES.includes(current);
+    current = getProto(current)
+  ) {
+    for (const key of Object.getOwnPropertyNames(current)) {
+      if (!names.includes(key)) names.push(key);
+    }
+  }
+  return names;
+}
+
 /**
  * @param allowExportDefault
  *   * `false`: will have the raw module as default export
@@ -152,15 +181,8 @@ function interopEsm(
   allowExportDefault?: boolean
 ) {
   const getters: { [s: string]: () => any } = Object.create(null);
-  for (
-    let current = raw;
-    (typeof current === "object" || typeof current === "function") &&
-    !LEAF_PROTOTYPES.includes(current);
-    current = getProto(current)
-  ) {
-    for (const key of Object.getOwnPropertyNames(current)) {
-      getters[key] = createGetter(raw, key);
-    }
+  for (const key of getExportNames(raw)) {
+    getters[key] = createGetter(raw, key);
   }
   if (!(allowExportDefault && "default" in getters)) {
     getters["default"] = () => raw;
@@ -168,13 +190,38 @@ function interopEsm(
   esm(ns, getters);
 }
 
+/**
+ * Adds the exports of the CommonJS `module` to its namespace object `ns`.
+ *
+ * Like in Node.js and webpack, the default export is `module.exports`, unless
+ * the module is marked with `__esModule` and has a `default` property.
+ *
+ * The getters read `module.exports` on every access, so they follow later
+ * reassignments of `module.exports` and changes of `__esModule`. These happen
+ * when the module is imported while it's still loading, i.e. in a cycle.
+ * Properties which are added to the exports after the module has been loaded
+ * are only visible on the default export.
+ */
+function interopCommonJs(module: Module, ns: EsmNamespaceObject) {
+  const getters: { [s: string]: () => any } = Object.create(null);
+  for (const key of getExportNames(module.exports)) {
+    getters[key] = () => module.exports[key];
+  }
+  getters["default"] = () => {
+    const raw = module.exports;
+    return raw != null && raw.__esModule && "default" in raw
+      ? raw.default
+      : raw;
+  };
+  esm(ns, getters);
+}
+
 function esmImport(sourceModule: Module, id: ModuleId): EsmNamespaceObject {
   const module = getOrInstantiateModuleFromParent(id, sourceModule);
   if (module.error) throw module.error;
   if (module.namespaceObject) return module.namespaceObject;
-  const raw = module.exports;
   const ns = (module.namespaceObject = {});
-  interopEsm(raw, ns, raw.__esModule);
+  interopCommonJs(module, ns);
   return ns;
 }
 
@@ -221,6 +268,194 @@ function requireContext(
   return requireContext;
 }
 
+const turbopackQueues = Symbol("turbopack queues");
+const turbopackExports = Symbol("turbopack exports");
+const turbopackError = Symbol("turbopack error");
+
+enum QueueStatus {
+  /**
+   * The body of the module is still executing synchronously, up to its first
+   * `await`.
+   */
+  Unknown = -1,
+  Unresolved = 0,
+  Resolved = 1,
+}
+
+type AsyncQueueFn = (() => void) & { queueCount: number };
+type AsyncQueue = AsyncQueueFn[] & { status: QueueStatus };
+
+type AsyncModuleExt = {
+  [turbopackQueues]: (fn: (queue: AsyncQueue) => void) => void;
+  [turbopackExports]: Exports;
+  [turbopackError]?: any;
+};
+
+type AsyncModulePromise<T = Exports> = Promise<T> & AsyncModuleExt;
+
+type Dep = Exports | AsyncModulePromise | Promise<Exports>;
+
+type HandleAsyncDependencies = (
+  deps: Dep[]
+) => Exports[] | Promise<() => Exports[]>;
+type AsyncModuleResult = (err?: any) => void;
+type AsyncModule = (
+  body: (
+    handleAsyncDependencies: HandleAsyncDependencies,
+    asyncResult: AsyncModuleResult
+  ) => void,
+  hasAwait: boolean
+) => void;
+
+function createPromise<T>() {
+  let resolve: (value: T | PromiseLike<T>) => void;
+  let reject: (reason?: any) => void;
+
+  const promise = new Promise<T>((res, rej) => {
+    reject = rej;
+    resolve = res;
+  });
+
+  return {
+    promise,
+    resolve: resolve!,
+    reject: reject!,
+  };
+}
+
+function resolveQueue(queue?: AsyncQueue) {
+  if (queue && queue.status !== QueueStatus.Resolved) {
+    queue.status = QueueStatus.Resolved;
+    queue.forEach((fn) => fn.queueCount--);
+    queue.forEach((fn) => (fn.queueCount-- ? fn.queueCount++ : fn()));
+  }
+}
+
+/**
+ * Brings the dependencies of an async module into a common shape: async
+ * modules are kept as they are, other promises are awaited and everything
+ * else is a synchronously available namespace.
+ */
+function wrapDeps(deps: Dep[]): AsyncModuleExt[] {
+  return deps.map((dep) => {
+    if (dep !== null && typeof dep === "object") {
+      if (turbopackQueues in dep) return dep as AsyncModuleExt;
+      if (typeof dep.then === "function") {
+        const queue: AsyncQueue = Object.assign([], {
+          status: QueueStatus.Unresolved,
+        });
+
+        const obj: AsyncModuleExt = {
+          [turbopackExports]: {},
+          [turbopackQueues]: (fn: (queue: AsyncQueue) => void) => fn(queue),
+        };
+
+        dep.then(
+          (res: any) => {
+            obj[turbopackExports] = res;
+            resolveQueue(queue);
+          },
+          (err: any) => {
+            obj[turbopackError] = err;
+            resolveQueue(queue);
+          }
+        );
+
+        return obj;
+      }
+    }
+
+    return {
+      [turbopackExports]: dep,
+      [turbopackQueues]: () => {},
+    };
+  });
+}
+
+/**
+ * Turns the module into an async module, whose exports are only available
+ * once the returned promise of its body has settled.
+ *
+ * `module.exports` is replaced with a promise which resolves to the exports of
+ * the module, like in webpack. Importers of an async module are async modules
+ * themselves, which wait for their async dependencies with
+ * `handleAsyncDependencies`. Dependencies which are still evaluating in a
+ * cycle are not waited for, to avoid deadlocks.
+ */
+function asyncModule(
+  module: Module,
+  body: (
+    handleAsyncDependencies: HandleAsyncDependencies,
+    asyncResult: AsyncModuleResult
+  ) => void,
+  hasAwait: boolean
+) {
+  const queue: AsyncQueue | undefined = hasAwait
+    ? Object.assign([], { status: QueueStatus.Unknown })
+    : undefined;
+
+  const depQueues: Set<AsyncQueue> = new Set();
+
+  const { resolve, reject, promise: rawPromise } = createPromise<Exports>();
+
+  const promise: AsyncModulePromise = Object.assign(rawPromise, {
+    [turbopackExports]: module.exports,
+    [turbopackQueues]: (fn: (queue: AsyncQueue) => void) => {
+      queue && fn(queue);
+      depQueues.forEach(fn);
+      promise["catch"](() => {});
+    },
+  });
+
+  module.exports = module.namespaceObject = promise;
+
+  function handleAsyncDependencies(deps: Dep[]) {
+    const currentDeps = wrapDeps(deps);
+
+    const getResult = () =>
+      currentDeps.map((d) => {
+        if (d[turbopackError]) throw d[turbopackError];
+        return d[turbopackExports];
+      });
+
+    const { promise, resolve } = createPromise<() => Exports[]>();
+
+    const fn: AsyncQueueFn = Object.assign(() => resolve(getResult), {
+      queueCount: 0,
+    });
+
+    function fnQueue(q: AsyncQueue) {
+      if (q !== queue && !depQueues.has(q)) {
+        depQueues.add(q);
+        if (q && q.status === QueueStatus.Unresolved) {
+          fn.queueCount++;
+          q.push(fn);
+        }
+      }
+    }
+
+    currentDeps.map((dep) => dep[turbopackQueues](fnQueue));
+
+    return fn.queueCount ? promise : getResult();
+  }
+
+  function asyncResult(err?: any) {
+    if (err) {
+      reject((promise[turbopackError] = err));
+    } else {
+      resolve(promise[turbopackExports]);
+    }
+
+    resolveQueue(queue);
+  }
+
+  body(handleAsyncDependencies, asyncResult);
+
+  if (queue && queue.status === QueueStatus.Unknown) {
+    queue.status = QueueStatus.Unresolved;
+  }
+}
+
 /**
  * Returns the path of a chunk defined by its data.
  */
@@ -14,7 +14,7 @@ use swc_core::{
 };
 
 use super::{ConstantNumber, ConstantValue, ImportMap, JsValue, ObjectPart, WellKnownFunctionKind};
-use crate::{analyzer::is_unresolved, utils::unparen};
+use crate::{analyzer::is_unresolved, utils::unparen, worker::WorkerType};
 
 #[derive(Debug, Clone, Default)]
 pub struct EffectsBlock {
@@ -170,6 +170,16 @@ pub enum Effect {
         span: Span,
         in_try: bool,
     },
+    /// A reference to `new URL(..., import.meta.url)` which is the script of a
+    /// worker, e.g. `new Worker(new URL(..., import.meta.url))`.
+    Worker {
+        input: JsValue,
+        ty: WorkerType,
+        /// The ast path to the expression which creates the worker.
+        ast_path: Vec<AstParentKind>,
+        span: Span,
+        in_try: bool,
+    },
 }
 
 impl Effect {
@@ -206,7 +216,7 @@ impl Effect {
             }
             Effect::ImportedBinding { .. } => {}
             Effect::ImportMeta { .. } => {}
-            Effect::Url { input, .. } => {
+            Effect::Url { input, .. } | Effect::Worker { input, .. } => {
                 input.normalize();
             }
         }
@@ -665,6 +675,62 @@ impl Analyzer<'_> {
         self.effects.push(effect);
     }
 
+    /// Checks if the `new URL(...)` expression at `ast_path` is the script of a
+    /// worker, i.e. the first argument of `new Worker(...)`,
+    /// `new SharedWorker(...)` or `worklet.addModule(...)`. Returns the type
+    /// of the worker and the ast path to the expression which creates it.
+    fn worker_script(
+        &self,
+        ast_path: &AstNodePath<AstParentNodeRef<'_>>,
+    ) -> Option<(WorkerType, Vec<AstParentKind>)> {
+        let [.., parent, AstParentNodeRef::ExprOrSpread(_, ExprOrSpreadField::Expr), AstParentNodeRef::Expr(_, ExprField::New)] =
+            &ast_path[..]
+        else {
+            return None;
+        };
+        let ty = match parent {
+            AstParentNodeRef::NewExpr(
+                NewExpr {
+                    callee: box Expr::Ident(callee),
+                    ..
+                },
+                NewExprField::Args(0),
+            ) if is_unresolved(callee, self.eval_context.unresolved_mark) => match &*callee.sym {
+                "Worker" => WorkerType::Worker,
+                "SharedWorker" => WorkerType::SharedWorker,
+                _ => return None,
+            },
+            AstParentNodeRef::CallExpr(
+                CallExpr {
+                    callee:
+                        Callee::Expr(box Expr::Member(MemberExpr {
+                            obj: box worklet,
+                            prop: MemberProp::Ident(method),
+                            ..
+                        })),
+                    ..
+                },
+                CallExprField::Args(0),
+            ) if &*method.sym == "addModule" => {
+                let name = match worklet {
+                    Expr::Ident(ident) => &ident.sym,
+                    Expr::Member(MemberExpr {
+                        prop: MemberProp::Ident(ident),
+                        ..
+                    }) => &ident.sym,
+                    _ => return None,
+                };
+                if !name.ends_with("Worklet") {
+                    return None;
+                }
+                WorkerType::Worklet
+            }
+            _ => return None,
+        };
+        let worker_ast_path = &ast_path[..ast_path.len() - 3];
+        Some((ty, worker_ast_path.iter().map(|n| n.kind()).collect()))
+    }
+
     fn check_iife<'ast: 'r, 'r>(
         &mut self,
         n: &'ast CallExpr,
@@ -1147,12 +1213,25 @@ impl VisitAstPath for Analyzer<'_> {
                         }) = &*args[1].expr
                         {
                             if &*prop.sym == "url" {
-                                self.add_effect(Effect::Url {
-                                    input: self.eval_context.eval(&args[0].expr),
-                                    ast_path: as_parent_path(ast_path),
-                                    span: new_expr.span(),
-                                    in_try: is_in_try(ast_path),
-                                });
+                                let input = self.eval_context.eval(&args[0].expr);
+                                let span = new_expr.span();
+                                let in_try = is_in_try(ast_path);
+                                if let Some((ty, worker_ast_path)) = self.worker_script(ast_path) {
+                                    self.add_effect(Effect::Worker {
+                                        input,
+                                        ty,
+                                        ast_path: worker_ast_path,
+                                        span,
+                                        in_try,
+                                    });
+                                } else {
+                                    self.add_effect(Effect::Url {
+                                        input,
+                                        ast_path: as_parent_path(ast_path),
+                                        span,
+                                        in_try,
+                                    });
+                                }
                             }
                         }
                     }
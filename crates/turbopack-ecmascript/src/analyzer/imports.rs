@@ -97,6 +97,10 @@ pub(crate) struct ImportMap {
     /// Ordered list of imported symbols
     references: IndexSet<ImportMapReference>,
 
+    /// Indices in references of import declarations without any specifiers,
+    /// which are only imported for their side effects.
+    side_effect_imports: IndexSet<usize>,
+
     /// True, when the module has exports
     has_exports: bool,
 }
@@ -160,6 +164,37 @@ impl ImportMap {
         self.reexports.iter().map(|(i, r)| (*i, r))
     }
 
+    /// The symbols which are imported by `import` declarations, by index in
+    /// references. Namespace imports have no symbol, as they can access all
+    /// exports. Reexports are not included.
+    pub fn imported_symbols(&self) -> impl Iterator<Item = (usize, Option<&JsWord>)> {
+        self.imports
+            .values()
+            .map(|(i, symbol)| (*i, Some(symbol)))
+            .chain(self.namespace_imports.values().map(|i| (*i, None)))
+    }
+
+    /// The indices of all references to the same module as the reference at
+    /// `index`, including `index` itself.
+    pub fn module_references(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let reference = &self.references[index];
+        self.references
+            .iter()
+            .enumerate()
+            .filter(move |(_, r)| {
+                r.module_path == reference.module_path && r.annotations == reference.annotations
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// Returns true when the reference at `index` only evaluates the module
+    /// and is not imported for its side effects alone, i.e. it can be dropped
+    /// together with the bindings imported from the module.
+    pub fn is_droppable_module_evaluation(&self, index: usize) -> bool {
+        self.references[index].imported_symbol == ImportedSymbol::ModuleEvaluation
+            && !self.side_effect_imports.contains(&index)
+    }
+
     /// Analyze ES import
     pub(super) fn analyze(m: &Program) -> Self {
         let mut data = ImportMap::default();
@@ -245,11 +280,14 @@ impl Visit for Analyzer<'_> {
     fn visit_import_decl(&mut self, import: &ImportDecl) {
         let annotations = take(&mut self.current_annotations);
 
-        self.ensure_reference(
+        let i = self.ensure_reference(
             import.src.value.clone(),
             ImportedSymbol::ModuleEvaluation,
             annotations.clone(),
         );
+        if import.specifiers.is_empty() {
+            self.data.side_effect_imports.insert(i);
+        }
 
         for s in &import.specifiers {
             let symbol = get_import_symbol_from_import(s);
@@ -374,11 +412,8 @@ fn get_import_symbol_from_import(specifier: &ImportSpecifier) -> ImportedSymbol
 
 fn get_import_symbol_from_export(specifier: &ExportSpecifier) -> ImportedSymbol {
     match specifier {
-        ExportSpecifier::Named(ExportNamedSpecifier { orig, exported, .. }) => {
-            ImportedSymbol::Symbol(match exported {
-                Some(exported) => orig_name(exported),
-                _ => orig_name(orig),
-            })
+        ExportSpecifier::Named(ExportNamedSpecifier { orig, .. }) => {
+            ImportedSymbol::Symbol(orig_name(orig))
         }
         ExportSpecifier::Default(..) => ImportedSymbol::Symbol(js_word!("default")),
         ExportSpecifier::Namespace(..) => ImportedSymbol::Namespace,
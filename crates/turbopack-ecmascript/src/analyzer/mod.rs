@@ -3030,9 +3030,8 @@ fn regex_from_js(pattern: &str, flags: &str) -> Result<Regex> {
 /// Parse the arguments passed to a require.context invocation, validate them
 /// and convert them to the appropriate rust values.
 pub fn parse_require_context(args: &Vec<JsValue>) -> Result<RequireContextOptions> {
-    if !(1..=3).contains(&args.len()) {
-        // https://linear.app/vercel/issue/WEB-910/add-support-for-requirecontexts-mode-argument
-        bail!("require.context() only supports 1-3 arguments (mode is not supported)");
+    if !(1..=4).contains(&args.len()) {
+        bail!("require.context() only supports 1-4 arguments");
     }
 
     let Some(dir) = args[0].as_str().map(|s| s.to_string()) else {
@@ -3061,11 +3060,25 @@ pub fn parse_require_context(args: &Vec<JsValue>) -> Result<RequireContextOption
     } else {
         // https://webpack.js.org/api/module-methods/#requirecontext
         // > optional, default /^\.\/.*$/, any file
-        static DEFAULT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\\./.*$").unwrap());
+        static DEFAULT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\./.*$").unwrap());
 
         DEFAULT_REGEX.clone()
     };
 
+    if let Some(mode) = args.get(3) {
+        // Only the synchronous mode is supported, where all modules of the
+        // context are part of the same chunk and required synchronously.
+        match mode.as_str() {
+            Some("sync") => {}
+            Some(mode) => {
+                bail!("require.context(..., ..., ..., mode) doesn't support mode {mode:?}")
+            }
+            None => {
+                bail!("require.context(..., ..., ..., mode) requires mode to be a constant string")
+            }
+        }
+    }
+
     Ok(RequireContextOptions {
         dir,
         include_subdirs,
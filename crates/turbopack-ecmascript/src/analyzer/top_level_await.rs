@@ -27,6 +27,14 @@ impl Visit for TopLevelAwaitVisitor {
         self.has_top_level_await = true;
     }
 
+    fn visit_for_of_stmt(&mut self, n: &ForOfStmt) {
+        if n.is_await {
+            self.has_top_level_await = true;
+        } else {
+            n.visit_children_with(self);
+        }
+    }
+
     // prevent non top level items from visiting their children
     noop_visit_type!();
     noop!(visit_arrow_expr, ArrowExpr);
@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use indexmap::IndexSet;
+use turbo_tasks::TryJoinIterExt;
+use turbopack_core::{
+    asset::Asset,
+    chunk::{ChunkItem, ChunkableModuleReference},
+    reference::AssetReferencesVc,
+};
+
+use super::{
+    content::EcmascriptChunkContentVc,
+    placeable::{
+        EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceablesVc,
+    },
+};
+use crate::references::esm::{base::ReferencedAsset, EsmAssetReferenceVc};
+
+/// The async modules in the module graph of a chunk.
+///
+/// A module is async when it is async itself (see
+/// [EcmascriptChunkPlaceable::is_self_async]), or when it statically imports
+/// an async module. Importers of async modules need to wait for them before
+/// they can be evaluated themselves.
+#[turbo_tasks::value(transparent)]
+pub struct AsyncModuleInfo(IndexSet<EcmascriptChunkPlaceableVc>);
+
+#[turbo_tasks::value_impl]
+impl AsyncModuleInfoVc {
+    /// Computes the async modules in the module graph of the chunk items of
+    /// `chunk_content`, including modules which are only available from
+    /// parent chunks.
+    #[turbo_tasks::function]
+    pub async fn new(chunk_content: EcmascriptChunkContentVc) -> Result<Self> {
+        let chunk_content = chunk_content.await?;
+
+        let mut queue = chunk_content
+            .chunk_items
+            .iter()
+            .map(|chunk_item| esm_dependencies(chunk_item.references()))
+            .try_join()
+            .await?
+            .iter()
+            .flat_map(|dependencies| dependencies.iter().copied())
+            .collect::<Vec<_>>();
+
+        // The module graph is traversed once, the async modules are then found
+        // by following the edges backwards from the self-async modules. This
+        // also handles cycles.
+        let mut visited = HashSet::new();
+        let mut importers: HashMap<_, Vec<_>> = HashMap::new();
+        let mut self_async = Vec::new();
+        while !queue.is_empty() {
+            let placeables = queue
+                .drain(..)
+                .filter(|placeable| visited.insert(*placeable))
+                .collect::<Vec<_>>();
+            let results = placeables
+                .iter()
+                .map(|placeable| async move {
+                    Ok((
+                        *placeable.is_self_async().await?,
+                        esm_dependencies(placeable.references()).await?,
+                    ))
+                })
+                .try_join()
+                .await?;
+            for (placeable, (is_self_async, dependencies)) in placeables.into_iter().zip(results) {
+                if is_self_async {
+                    self_async.push(placeable);
+                }
+                for &dependency in dependencies.iter() {
+                    importers.entry(dependency).or_default().push(placeable);
+                    queue.push(dependency);
+                }
+            }
+        }
+
+        let mut async_modules = IndexSet::new();
+        let mut queue = self_async;
+        while let Some(placeable) = queue.pop() {
+            if async_modules.insert(placeable) {
+                if let Some(importers) = importers.get(&placeable) {
+                    queue.extend(importers.iter().copied());
+                }
+            }
+        }
+
+        Ok(AsyncModuleInfoVc::cell(async_modules))
+    }
+}
+
+/// Returns the modules which are statically imported by the given references,
+/// i.e. the modules which have to be evaluated before the importing module.
+#[turbo_tasks::function]
+pub(crate) async fn esm_dependencies(
+    references: AssetReferencesVc,
+) -> Result<EcmascriptChunkPlaceablesVc> {
+    let mut dependencies = Vec::new();
+    for reference in references.await?.iter() {
+        let Some(reference) = EsmAssetReferenceVc::resolve_from(reference).await? else {
+            continue;
+        };
+        // only chunked references are imported
+        if reference.chunking_type().await?.is_none() {
+            continue;
+        }
+        if let ReferencedAsset::Some(placeable) = &*reference.get_referenced_asset().await? {
+            dependencies.push(*placeable);
+        }
+    }
+    Ok(EcmascriptChunkPlaceablesVc::cell(dependencies))
+}
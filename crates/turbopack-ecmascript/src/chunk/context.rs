@@ -3,6 +3,7 @@ use turbo_tasks::{primitives::BoolVc, ValueToString};
 use turbopack_core::chunk::{ChunkItem, ChunkingContext, ChunkingContextVc, ModuleId, ModuleIdVc};
 
 use super::item::EcmascriptChunkItemVc;
+use crate::{export_usage::OptionExportUsageVc, minify::OptionMinifyOptionsVc};
 
 /// [`EcmascriptChunkingContext`] must be implemented by [`ChunkingContext`]
 /// implementors that want to operate on [`EcmascriptChunk`]s.
@@ -14,6 +15,18 @@ pub trait EcmascriptChunkingContext: ChunkingContext {
         BoolVc::cell(false)
     }
 
+    /// The usage of exports which is used to omit unused exports from chunk
+    /// items, see [crate::export_usage]. All exports are kept when `None`.
+    fn export_usage(&self) -> OptionExportUsageVc {
+        OptionExportUsageVc::cell(None)
+    }
+
+    /// The options to minify the code of chunk items with, see
+    /// [crate::minify]. The code isn't minified when `None`.
+    fn minify(&self) -> OptionMinifyOptionsVc {
+        OptionMinifyOptionsVc::cell(None)
+    }
+
     async fn chunk_item_id(&self, chunk_item: EcmascriptChunkItemVc) -> Result<ModuleIdVc> {
         let layer = self.layer();
         let mut ident = chunk_item.asset_ident();
@@ -17,8 +17,8 @@ use turbopack_core::{
 };
 
 use super::{
-    context::EcmascriptChunkingContextVc, placeable::EcmascriptChunkPlaceableVc,
-    EcmascriptChunkPlaceable, EcmascriptChunkingContext,
+    async_module_info::AsyncModuleInfoVc, context::EcmascriptChunkingContextVc,
+    placeable::EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceable, EcmascriptChunkingContext,
 };
 use crate::{
     manifest::{chunk_asset::ManifestChunkAssetVc, loader_item::ManifestLoaderItemVc},
@@ -53,12 +53,15 @@ impl EcmascriptChunkItemContentVc {
                 EcmascriptChunkItemOptions {
                     refresh,
                     externals,
+                    absolute_paths: externals,
+                    async_module: content.async_module.clone(),
                     ..Default::default()
                 }
             } else {
                 EcmascriptChunkItemOptions {
                     refresh,
                     externals,
+                    absolute_paths: externals,
                     // These things are not available in ESM
                     module: true,
                     exports: true,
@@ -91,6 +94,9 @@ impl EcmascriptChunkItemContentVc {
         if this.options.externals {
             args.push("x: __turbopack_external_require__");
         }
+        if this.options.absolute_paths {
+            args.push("P: __turbopack_resolve_absolute_path__");
+        }
         if this.options.refresh {
             args.push("k: __turbopack_refresh__");
         }
@@ -100,6 +106,12 @@ impl EcmascriptChunkItemContentVc {
         if this.options.exports {
             args.push("e: exports");
         }
+        if this.options.wasm {
+            args.push("w: __turbopack_wasm__");
+        }
+        if this.options.async_module.is_some() {
+            args.push("a: __turbopack_async_module__");
+        }
         let mut code = CodeBuilder::default();
         let args = FormatIter(|| args.iter().copied().intersperse(", "));
         if this.options.this {
@@ -107,9 +119,23 @@ impl EcmascriptChunkItemContentVc {
         } else {
             write!(code, "(({{ {} }}) => (() => {{\n\n", args,)?;
         }
+        if this.options.async_module.is_some() {
+            code += "__turbopack_async_module__(async (__turbopack_handle_async_dependencies__, \
+                     __turbopack_async_result__) => { try {\n";
+        }
 
         let source_map = this.source_map.map(|sm| sm.as_generate_source_map());
         code.push_source(&this.inner_code, source_map);
+
+        if let Some(opts) = &this.options.async_module {
+            write!(
+                code,
+                "\n__turbopack_async_result__();\n}} catch(e) {{ __turbopack_async_result__(e); \
+                 }} }}, {});",
+                opts.has_top_level_await
+            )?;
+        }
+
         if this.options.this {
             code += "\n}.call(this) })";
         } else {
@@ -133,10 +159,26 @@ pub struct EcmascriptChunkItemOptions {
     /// Whether this chunk item's module factory should include a
     /// `__turbopack_external_require__` argument.
     pub externals: bool,
+    /// Whether this chunk item's module factory should include a
+    /// `__turbopack_resolve_absolute_path__` argument.
+    pub absolute_paths: bool,
+    /// Whether this chunk item's module factory should include a
+    /// `__turbopack_wasm__` argument.
+    pub wasm: bool,
+    /// Whether this chunk item is an async module, which is evaluated in an
+    /// async function wrapped with `__turbopack_async_module__`.
+    pub async_module: Option<AsyncModuleOptions>,
     pub this: bool,
     pub placeholder_for_future_extensions: (),
 }
 
+#[derive(PartialEq, Eq, Default, Debug, Clone, Serialize, Deserialize, TraceRawVcs)]
+pub struct AsyncModuleOptions {
+    /// Whether the module awaits something itself, as opposed to only waiting
+    /// for its async dependencies.
+    pub has_top_level_await: bool,
+}
+
 #[turbo_tasks::value_trait]
 pub trait EcmascriptChunkItem: ChunkItem {
     fn content(&self) -> EcmascriptChunkItemContentVc;
@@ -146,6 +188,13 @@ pub trait EcmascriptChunkItem: ChunkItem {
     ) -> EcmascriptChunkItemContentVc {
         self.content()
     }
+    fn content_with_async_module_info(
+        &self,
+        availability_info: Value<AvailabilityInfo>,
+        _async_module_info: AsyncModuleInfoVc,
+    ) -> EcmascriptChunkItemContentVc {
+        self.content_with_availability_info(availability_info)
+    }
     fn chunking_context(&self) -> EcmascriptChunkingContextVc;
 }
 
@@ -159,10 +208,14 @@ impl EcmascriptChunkItemVc {
 
     /// Generates the module factory for this chunk item.
     #[turbo_tasks::function]
-    pub async fn code(self, availability_info: Value<AvailabilityInfo>) -> Result<CodeVc> {
+    pub async fn code(
+        self,
+        availability_info: Value<AvailabilityInfo>,
+        async_module_info: AsyncModuleInfoVc,
+    ) -> Result<CodeVc> {
         Ok(
             match self
-                .content_with_availability_info(availability_info)
+                .content_with_async_module_info(availability_info, async_module_info)
                 .module_factory()
                 .resolve()
                 .await
@@ -1,3 +1,4 @@
+pub(crate) mod async_module_info;
 pub(crate) mod content;
 pub(crate) mod context;
 pub(crate) mod data;
@@ -29,12 +30,13 @@ use turbopack_core::{
 
 use self::content::ecmascript_chunk_content;
 pub use self::{
+    async_module_info::{AsyncModuleInfo, AsyncModuleInfoVc},
     content::{EcmascriptChunkContent, EcmascriptChunkContentVc},
     context::{EcmascriptChunkingContext, EcmascriptChunkingContextVc},
     data::EcmascriptChunkData,
     item::{
-        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
-        EcmascriptChunkItemOptions, EcmascriptChunkItemVc,
+        AsyncModuleOptions, EcmascriptChunkItem, EcmascriptChunkItemContent,
+        EcmascriptChunkItemContentVc, EcmascriptChunkItemOptions, EcmascriptChunkItemVc,
     },
     placeable::{
         EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceables,
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use turbo_tasks::primitives::BoolVc;
 use turbopack_core::{
     asset::{Asset, AssetVc},
     chunk::{ChunkableModule, ChunkableModuleVc},
@@ -12,6 +13,12 @@ use crate::references::esm::EsmExportsVc;
 pub trait EcmascriptChunkPlaceable: ChunkableModule + Module + Asset {
     fn as_chunk_item(&self, context: EcmascriptChunkingContextVc) -> EcmascriptChunkItemVc;
     fn get_exports(&self) -> EcmascriptExportsVc;
+    /// Whether the module is async on its own, e.g. because it needs to be
+    /// instantiated asynchronously. Modules which import async modules are
+    /// async as well, see [super::AsyncModuleInfo].
+    fn is_self_async(&self) -> BoolVc {
+        BoolVc::cell(false)
+    }
 }
 
 #[turbo_tasks::value(transparent)]
@@ -0,0 +1,288 @@
+//! Export usage analysis, which finds the exports of ES modules that are
+//! never imported, so they can be dropped from the emitted chunks.
+//!
+//! The analysis starts at some entries, whose exports are all used, and
+//! follows the references of the modules:
+//!
+//! * `import { a } from "./x"` uses `a` of `./x`.
+//! * Namespace imports (`import * as x from "./x"`), dynamic `import()`,
+//!   `require()` and any other kind of reference use all exports, as they can
+//!   be accessed dynamically.
+//! * Reexports (`export { a } from "./x"`, `export * from "./x"`) only use the
+//!   exports of `./x` which are used on the reexporting module.
+//!
+//! The result is applied by an [EcmascriptChunkingContext] which returns it
+//! from [EcmascriptChunkingContext::export_usage]. The getters of unused
+//! exports are omitted from the generated code, so the minifier can remove
+//! their declarations when nothing else uses them.
+//!
+//! [EcmascriptChunkingContext]: crate::chunk::EcmascriptChunkingContext
+//! [EcmascriptChunkingContext::export_usage]: crate::chunk::EcmascriptChunkingContext::export_usage
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::Result;
+use turbopack_core::{
+    asset::{Asset, AssetVc},
+    reference::AssetReference,
+    resolve::PrimaryResolveResult,
+};
+
+use crate::{
+    chunk::{
+        EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceablesVc,
+        EcmascriptExports,
+    },
+    references::esm::{base::ReferencedAsset, export::EsmExport, EsmAssetReferenceVc},
+    EcmascriptModuleAssetVc,
+};
+
+/// An export of a referenced module which is used by the importing module.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub(crate) struct ImportedExport {
+    pub reference: EsmAssetReferenceVc,
+    /// The name of the export, or `None` when all exports are used.
+    pub export: Option<String>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub(crate) struct ImportedExports(Vec<ImportedExport>);
+
+/// The exports of a module which are used.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub enum UsedExports {
+    All,
+    Exports(BTreeSet<String>),
+}
+
+impl Default for UsedExports {
+    fn default() -> Self {
+        UsedExports::Exports(BTreeSet::new())
+    }
+}
+
+impl UsedExports {
+    pub fn is_used(&self, export: &str) -> bool {
+        match self {
+            UsedExports::All => true,
+            UsedExports::Exports(exports) => exports.contains(export),
+        }
+    }
+
+    /// Marks `export` as used, or all exports when it's `None`. Returns true
+    /// when that changed anything.
+    fn add(&mut self, export: Option<&str>) -> bool {
+        match (self, export) {
+            (UsedExports::All, _) => false,
+            (UsedExports::Exports(exports), Some(export)) => {
+                if exports.contains(export) {
+                    false
+                } else {
+                    exports.insert(export.to_string());
+                    true
+                }
+            }
+            (this, None) => {
+                *this = UsedExports::All;
+                true
+            }
+        }
+    }
+}
+
+/// The used exports of all modules which are reachable from some entries.
+#[turbo_tasks::value]
+pub struct ExportUsage {
+    modules: HashMap<EcmascriptChunkPlaceableVc, UsedExports>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionExportUsage(Option<ExportUsageVc>);
+
+#[turbo_tasks::value_impl]
+impl ExportUsageVc {
+    /// Analyzes which exports of the modules reachable from `entries` are
+    /// used. All exports of the `entries` themselves are used.
+    #[turbo_tasks::function]
+    pub async fn new(entries: EcmascriptChunkPlaceablesVc) -> Result<Self> {
+        let mut analysis = UsageAnalysis::default();
+        for entry in entries.await?.iter() {
+            let entry = entry.resolve().await?;
+            analysis.mark(entry, None);
+            analysis.visit_queue.push(entry.into());
+        }
+        loop {
+            if let Some(asset) = analysis.visit_queue.pop() {
+                if analysis.visited.insert(asset) {
+                    analysis.visit(asset).await?;
+                }
+            } else if let Some(module) = analysis.propagate_queue.pop() {
+                analysis.propagate(module).await?;
+            } else {
+                break;
+            }
+        }
+        Ok(ExportUsage {
+            modules: analysis.usage,
+        }
+        .cell())
+    }
+
+    /// The used exports of `module`. All exports are considered as used when
+    /// the module hasn't been reached by the analysis.
+    #[turbo_tasks::function]
+    pub async fn used_exports(self, module: EcmascriptChunkPlaceableVc) -> Result<UsedExportsVc> {
+        let module = module.resolve().await?;
+        Ok(self
+            .await?
+            .modules
+            .get(&module)
+            .cloned()
+            .unwrap_or(UsedExports::All)
+            .cell())
+    }
+}
+
+#[derive(Default)]
+struct UsageAnalysis {
+    usage: HashMap<EcmascriptChunkPlaceableVc, UsedExports>,
+    visited: HashSet<AssetVc>,
+    visit_queue: Vec<AssetVc>,
+    /// Modules whose usage changed, so it needs to be propagated to the
+    /// modules they reexport from.
+    propagate_queue: Vec<EcmascriptChunkPlaceableVc>,
+}
+
+impl UsageAnalysis {
+    fn mark(&mut self, module: EcmascriptChunkPlaceableVc, export: Option<&str>) {
+        if self.usage.entry(module).or_default().add(export) {
+            self.propagate_queue.push(module);
+        }
+    }
+
+    /// Marks the exports used by the references of `asset` and queues the
+    /// referenced assets.
+    async fn visit(&mut self, asset: AssetVc) -> Result<()> {
+        let mut imported_exports = HashMap::<EsmAssetReferenceVc, Vec<Option<String>>>::new();
+        let mut reexport_references = HashSet::new();
+        if let Some(module) = EcmascriptModuleAssetVc::resolve_from(asset).await? {
+            let analysis = module.failsafe_analyze().await?;
+            for ImportedExport { reference, export } in analysis.imported_exports.await?.iter() {
+                imported_exports
+                    .entry(reference.resolve().await?)
+                    .or_default()
+                    .push(export.clone());
+            }
+            if let EcmascriptExports::EsmExports(exports) = &*analysis.exports.await? {
+                let exports = exports.await?;
+                for export in exports.exports.values() {
+                    if let EsmExport::ImportedBinding(reference, _)
+                    | EsmExport::ImportedNamespace(reference) = export
+                    {
+                        reexport_references.insert(reference.resolve().await?);
+                    }
+                }
+                for reference in exports.star_exports.iter() {
+                    reexport_references.insert(reference.resolve().await?);
+                }
+            }
+        }
+
+        for reference in asset.references().await?.iter() {
+            let reference = reference.resolve().await?;
+            if let Some(esm_reference) = EsmAssetReferenceVc::resolve_from(reference).await? {
+                let ReferencedAsset::Some(module) = &*esm_reference.get_referenced_asset().await?
+                else {
+                    continue;
+                };
+                let module = module.resolve().await?;
+                self.visit_queue.push(module.into());
+                match imported_exports.get(&esm_reference) {
+                    Some(exports) => {
+                        for export in exports {
+                            self.mark(module, export.as_deref());
+                        }
+                    }
+                    // Reexports are marked when the reexporting module is used
+                    None if reexport_references.contains(&esm_reference) => {
+                        self.usage.entry(module).or_default();
+                    }
+                    None => self.mark(module, None),
+                }
+                continue;
+            }
+            for result in reference.resolve_reference().await?.primary.iter() {
+                if let PrimaryResolveResult::Asset(asset) = *result {
+                    let asset = asset.resolve().await?;
+                    if let Some(module) = EcmascriptChunkPlaceableVc::resolve_from(asset).await? {
+                        self.mark(module, None);
+                    }
+                    self.visit_queue.push(asset);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Marks the exports which `module` reexports from other modules as used,
+    /// when they are used on `module`.
+    async fn propagate(&mut self, module: EcmascriptChunkPlaceableVc) -> Result<()> {
+        let EcmascriptExports::EsmExports(exports) = &*module.get_exports().await? else {
+            return Ok(());
+        };
+        let exports = exports.await?;
+        let used = self.usage.get(&module).cloned().unwrap_or_default();
+        for (name, export) in exports.exports.iter() {
+            if !used.is_used(name) {
+                continue;
+            }
+            let (reference, export) = match export {
+                EsmExport::ImportedBinding(reference, export) => (reference, Some(export.as_str())),
+                EsmExport::ImportedNamespace(reference) => (reference, None),
+                EsmExport::LocalBinding(_) | EsmExport::Error => continue,
+            };
+            if let ReferencedAsset::Some(target) = &*reference.get_referenced_asset().await? {
+                self.mark(target.resolve().await?, export);
+            }
+        }
+        for reference in exports.star_exports.iter() {
+            let ReferencedAsset::Some(target) = &*reference.get_referenced_asset().await? else {
+                continue;
+            };
+            let target = target.resolve().await?;
+            match &used {
+                UsedExports::All => self.mark(target, None),
+                UsedExports::Exports(used) => {
+                    // `export *` doesn't reexport the default export
+                    for name in used {
+                        if name != "default" && !exports.exports.contains_key(name) {
+                            self.mark(target, Some(name));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UsedExports;
+
+    #[test]
+    fn adds_used_exports() {
+        let mut used = UsedExports::default();
+        assert!(!used.is_used("a"));
+        assert!(used.add(Some("a")));
+        assert!(!used.add(Some("a")));
+        assert!(used.is_used("a"));
+        assert!(!used.is_used("b"));
+        assert!(used.add(None));
+        assert!(used.is_used("b"));
+        assert!(!used.add(Some("c")));
+        assert!(!used.add(None));
+    }
+}
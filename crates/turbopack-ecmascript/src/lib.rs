@@ -11,8 +11,10 @@ pub mod chunk;
 pub mod chunk_group_files_asset;
 pub mod code_gen;
 mod errors;
+pub mod export_usage;
 pub mod magic_identifier;
 pub(crate) mod manifest;
+pub mod minify;
 pub mod parse;
 mod path_visitor;
 pub(crate) mod references;
@@ -25,21 +27,25 @@ pub mod tree_shake;
 pub mod typescript;
 pub mod utils;
 pub mod webpack;
+pub(crate) mod worker;
 
 use anyhow::Result;
 use chunk::{
     EcmascriptChunkItem, EcmascriptChunkItemVc, EcmascriptChunkPlaceablesVc, EcmascriptChunkVc,
-    EcmascriptChunkingContextVc,
+    EcmascriptChunkingContext, EcmascriptChunkingContextVc,
 };
 use code_gen::CodeGenerateableVc;
 use parse::{parse, ParseResult};
 pub use parse::{ParseResultSourceMap, ParseResultSourceMapVc};
 use path_visitor::ApplyVisitors;
 use references::AnalyzeEcmascriptModuleResult;
-pub use references::TURBOPACK_HELPER;
+pub use references::{
+    esm::{EsmExport, EsmExports, EsmExportsVc},
+    TURBOPACK_HELPER,
+};
 pub use static_code::{StaticEcmascriptCode, StaticEcmascriptCodeVc};
 use swc_core::{
-    common::GLOBALS,
+    common::{comments::Comments, GLOBALS},
     ecma::{
         codegen::{text_writer::JsWriter, Emitter},
         visit::{VisitMutWith, VisitMutWithPath},
@@ -51,7 +57,9 @@ pub use transform::{
     TransformPluginVc, UnsupportedServerActionIssue,
 };
 use turbo_tasks::{
-    primitives::StringVc, trace::TraceRawVcs, RawVc, ReadRef, TryJoinIterExt, Value, ValueToString,
+    primitives::{BoolVc, StringVc},
+    trace::TraceRawVcs,
+    RawVc, ReadRef, TryJoinIterExt, Value, ValueToString,
 };
 use turbo_tasks_fs::{rope::Rope, FileSystemPathVc};
 use turbopack_core::{
@@ -77,7 +85,8 @@ use turbopack_core::{
 pub use self::references::AnalyzeEcmascriptModuleResultVc;
 use self::{
     chunk::{
-        placeable::EcmascriptExportsReadRef, EcmascriptChunkItemContentVc, EcmascriptExportsVc,
+        placeable::EcmascriptExportsReadRef, AsyncModuleInfoVc, AsyncModuleOptions,
+        EcmascriptChunkItemContentVc, EcmascriptExportsVc,
     },
     code_gen::{
         CodeGen, CodeGenerateableWithAvailabilityInfo, CodeGenerateableWithAvailabilityInfoVc,
@@ -89,7 +98,13 @@ use self::{
 use crate::{
     chunk::{EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc},
     code_gen::CodeGenerateable,
-    references::analyze_ecmascript_module,
+    export_usage::{UsedExports, UsedExportsVc},
+    minify::MinifyOptions,
+    references::{
+        analyze_ecmascript_module,
+        async_module::{async_dependency_handler, async_dependency_idents},
+        esm::EsmExportsVc,
+    },
     transform::remove_shebang,
 };
 
@@ -333,6 +348,7 @@ impl EcmascriptModuleAssetVc {
                 code_generation: result_value.code_generation,
                 has_top_level_await: *has_top_level_await,
                 successful: false,
+                imported_exports: result_value.imported_exports,
             }
             .cell());
         }
@@ -365,6 +381,7 @@ impl EcmascriptModuleAssetVc {
         self,
         chunking_context: EcmascriptChunkingContextVc,
         availability_info: Value<AvailabilityInfo>,
+        async_module_info: Option<AsyncModuleInfoVc>,
     ) -> Result<EcmascriptModuleContentVc> {
         let this = self.await?;
         if *self.analyze().needs_availability_info().await? {
@@ -375,12 +392,19 @@ impl EcmascriptModuleAssetVc {
 
         let parsed = parse(this.source, Value::new(this.ty), this.transforms);
 
+        let used_exports = match *chunking_context.export_usage().await? {
+            Some(export_usage) => export_usage.used_exports(self.into()),
+            None => UsedExports::All.cell(),
+        };
+
         Ok(EcmascriptModuleContentVc::new(
             parsed,
             self.ident(),
             chunking_context,
             self.analyze(),
             availability_info,
+            used_exports,
+            async_module_info,
         ))
     }
 }
@@ -450,6 +474,13 @@ impl EcmascriptChunkPlaceable for EcmascriptModuleAsset {
     async fn get_exports(self_vc: EcmascriptModuleAssetVc) -> Result<EcmascriptExportsVc> {
         Ok(self_vc.failsafe_analyze().await?.exports)
     }
+
+    #[turbo_tasks::function]
+    async fn is_self_async(self_vc: EcmascriptModuleAssetVc) -> Result<BoolVc> {
+        Ok(BoolVc::cell(
+            self_vc.failsafe_analyze().await?.has_top_level_await,
+        ))
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -520,7 +551,22 @@ impl EcmascriptChunkItem for ModuleChunkItem {
         availability_info: Value<AvailabilityInfo>,
     ) -> Result<EcmascriptChunkItemContentVc> {
         let this = self_vc.await?;
-        let content = this.module.module_content(this.context, availability_info);
+        let content = this
+            .module
+            .module_content(this.context, availability_info, None);
+        Ok(EcmascriptChunkItemContentVc::new(content, this.context))
+    }
+
+    #[turbo_tasks::function]
+    async fn content_with_async_module_info(
+        self_vc: ModuleChunkItemVc,
+        availability_info: Value<AvailabilityInfo>,
+        async_module_info: AsyncModuleInfoVc,
+    ) -> Result<EcmascriptChunkItemContentVc> {
+        let this = self_vc.await?;
+        let content =
+            this.module
+                .module_content(this.context, availability_info, Some(async_module_info));
         Ok(EcmascriptChunkItemContentVc::new(content, this.context))
     }
 }
@@ -531,6 +577,7 @@ pub struct EcmascriptModuleContent {
     pub inner_code: Rope,
     pub source_map: Option<ParseResultSourceMapVc>,
     pub is_esm: bool,
+    pub async_module: Option<AsyncModuleOptions>,
 }
 
 #[turbo_tasks::value_impl]
@@ -543,12 +590,16 @@ impl EcmascriptModuleContentVc {
         context: EcmascriptChunkingContextVc,
         analyzed: AnalyzeEcmascriptModuleResultVc,
         availability_info: Value<AvailabilityInfo>,
+        used_exports: UsedExportsVc,
+        async_module_info: Option<AsyncModuleInfoVc>,
     ) -> Result<Self> {
         let AnalyzeEcmascriptModuleResult {
             references,
             code_generation,
+            has_top_level_await,
             ..
         } = &*analyzed.await?;
+        let minify = *context.minify().await?;
 
         let mut code_gens = Vec::new();
         for r in references.await?.iter() {
@@ -562,13 +613,32 @@ impl EcmascriptModuleContentVc {
         for c in code_generation.await?.iter() {
             match c {
                 CodeGen::CodeGenerateable(c) => {
-                    code_gens.push(c.code_generation(context));
+                    if let Some(exports) = EsmExportsVc::resolve_from(c).await? {
+                        code_gens
+                            .push(exports.code_generation_for_used_exports(context, used_exports));
+                    } else {
+                        code_gens.push(c.code_generation(context));
+                    }
                 }
                 CodeGen::CodeGenerateableWithAvailabilityInfo(c) => {
                     code_gens.push(c.code_generation(context, availability_info));
                 }
             }
         }
+        // The async dependencies are awaited after all imports and exports have
+        // been hoisted, so this needs to be the last code generation.
+        let mut async_module = has_top_level_await.then(|| AsyncModuleOptions {
+            has_top_level_await: true,
+        });
+        if let Some(async_module_info) = async_module_info {
+            let idents = async_dependency_idents(*references, async_module_info).await?;
+            if !idents.is_empty() {
+                code_gens.push(async_dependency_handler(idents));
+                async_module.get_or_insert(AsyncModuleOptions {
+                    has_top_level_await: false,
+                });
+            }
+        }
         // need to keep that around to allow references into that
         let code_gens = code_gens.into_iter().try_join().await?;
         let code_gens = code_gens.iter().map(|cg| &**cg).collect::<Vec<_>>();
@@ -585,13 +655,14 @@ impl EcmascriptModuleContentVc {
             }
         }
 
-        gen_content_with_visitors(parsed, ident, visitors, root_visitors).await
+        gen_content_with_visitors(parsed, ident, visitors, root_visitors, minify, async_module)
+            .await
     }
 
     /// Creates a new [`EcmascriptModuleContentVc`] without an analysis pass.
     #[turbo_tasks::function]
     pub async fn new_without_analysis(parsed: ParseResultVc, ident: AssetIdentVc) -> Result<Self> {
-        gen_content_with_visitors(parsed, ident, Vec::new(), Vec::new()).await
+        gen_content_with_visitors(parsed, ident, Vec::new(), Vec::new(), None, None).await
     }
 }
 
@@ -603,18 +674,22 @@ async fn gen_content_with_visitors(
         &dyn VisitorFactory,
     )>,
     root_visitors: Vec<&dyn VisitorFactory>,
+    minify: Option<MinifyOptions>,
+    async_module: Option<AsyncModuleOptions>,
 ) -> Result<EcmascriptModuleContentVc> {
     let parsed = parsed.await?;
 
     if let ParseResult::Ok {
         program,
+        comments,
         source_map,
         globals,
         eval_context,
-        ..
+        input_source_map,
     } = &*parsed
     {
         let mut program = program.clone();
+        let comments = minify.map(|_| minify::copy_comments(comments));
 
         GLOBALS.set(globals, || {
             if !visitors.is_empty() {
@@ -632,6 +707,10 @@ async fn gen_content_with_visitors(
             // we need to remove any shebang before bundling as it's only valid as the first
             // line in a js file (not in a chunk item wrapped in the runtime)
             remove_shebang(&mut program);
+
+            if let (Some(options), Some(comments)) = (minify, &comments) {
+                minify::minify(&mut program, source_map.clone(), comments, options);
+            }
         });
 
         let mut bytes: Vec<u8> = vec![];
@@ -642,21 +721,25 @@ async fn gen_content_with_visitors(
 
         let mut emitter = Emitter {
             cfg: swc_core::ecma::codegen::Config {
+                minify: minify.is_some(),
                 ..Default::default()
             },
             cm: source_map.clone(),
-            comments: None,
+            comments: comments.as_ref().map(|c| c as &dyn Comments),
             wr: JsWriter::new(source_map.clone(), "\n", &mut bytes, Some(&mut srcmap)),
         };
 
         emitter.emit_program(&program)?;
 
-        let srcmap = ParseResultSourceMap::new(source_map.clone(), srcmap).cell();
+        let srcmap = ParseResultSourceMap::new(source_map.clone(), srcmap)
+            .with_input_source_map(*input_source_map)
+            .cell();
 
         Ok(EcmascriptModuleContent {
             inner_code: bytes.into(),
             source_map: Some(srcmap),
             is_esm: eval_context.is_esm(),
+            async_module,
         }
         .cell())
     } else {
@@ -669,6 +752,7 @@ async fn gen_content_with_visitors(
             .into(),
             source_map: None,
             is_esm: false,
+            async_module: None,
         }
         .cell())
     }
@@ -0,0 +1,155 @@
+//! Minification of the code of chunk items with swc's minifier.
+
+use std::sync::Arc;
+
+use swc_core::{
+    base::SwcComments,
+    common::{comments::Comment, util::take::Take, BytePos, Mark, SourceMap, Span, SyntaxContext},
+    ecma::{
+        ast::{Module, Program},
+        minifier::{
+            optimize,
+            option::{
+                CompressOptions, ExtraOptions, MangleOptions, MinifyOptions as SwcMinifyOptions,
+                TopLevelOptions,
+            },
+        },
+        transforms::base::{fixer::fixer, hygiene::hygiene, resolver},
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+
+/// Options for the minification of chunk items.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct MinifyOptions {
+    /// Applies optimizations like dead code elimination, constant folding and
+    /// inlining.
+    pub compress: bool,
+    /// The number of times the compressor runs. More passes can compress
+    /// further, but take longer.
+    pub passes: usize,
+    /// Shortens the names of local variables.
+    pub mangle: bool,
+    /// Keeps the names of functions, e.g. when `Function.prototype.name` is
+    /// relied on.
+    pub keep_fn_names: bool,
+    /// Keeps the names of classes.
+    pub keep_class_names: bool,
+    /// Keeps license comments, i.e. comments which start with `/*!` or
+    /// contain `@license` or `@preserve`. All other comments are removed.
+    pub preserve_license_comments: bool,
+}
+
+impl Default for MinifyOptions {
+    fn default() -> Self {
+        MinifyOptions {
+            compress: true,
+            passes: 2,
+            mangle: true,
+            keep_fn_names: false,
+            keep_class_names: false,
+            preserve_license_comments: true,
+        }
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionMinifyOptions(Option<MinifyOptions>);
+
+impl MinifyOptions {
+    fn to_swc(self) -> SwcMinifyOptions {
+        // The code of a chunk item is wrapped in a function by the runtime, so its
+        // top level declarations are local and can be removed and renamed.
+        SwcMinifyOptions {
+            compress: self.compress.then(|| CompressOptions {
+                passes: self.passes,
+                keep_fnames: self.keep_fn_names,
+                keep_classnames: self.keep_class_names,
+                top_level: Some(TopLevelOptions { functions: true }),
+                ..Default::default()
+            }),
+            mangle: self.mangle.then(|| MangleOptions {
+                props: None,
+                top_level: Some(true),
+                keep_class_names: self.keep_class_names,
+                keep_fn_names: self.keep_fn_names,
+                keep_private_props: false,
+                ie8: false,
+                safari10: false,
+                reserved: Vec::new(),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+fn is_license_comment(comment: &Comment) -> bool {
+    comment.text.starts_with('!')
+        || comment.text.contains("@license")
+        || comment.text.contains("@preserve")
+}
+
+/// Copies the `comments` of a parsed module, as the minifier takes some of
+/// them, e.g. `/*#__PURE__*/` annotations.
+pub(crate) fn copy_comments(comments: &SwcComments) -> SwcComments {
+    let copy = SwcComments::default();
+    for entry in comments.leading.iter() {
+        copy.leading.insert(*entry.key(), entry.value().clone());
+    }
+    for entry in comments.trailing.iter() {
+        copy.trailing.insert(*entry.key(), entry.value().clone());
+    }
+    copy
+}
+
+/// Minifies a `program` whose identifiers have been made unique by the
+/// hygiene pass. This needs to be called within the same `GLOBALS` as the
+/// emitting of the program.
+///
+/// Afterwards, `comments` only contains the comments which should be emitted.
+pub(crate) fn minify(
+    program: &mut Program,
+    source_map: Arc<SourceMap>,
+    comments: &SwcComments,
+    options: MinifyOptions,
+) {
+    // The marks of the syntax contexts don't belong to the current globals, but
+    // the names are unique after the hygiene pass, so the scopes can be resolved
+    // again.
+    program.visit_mut_with(&mut ResetSyntaxContexts);
+    let unresolved_mark = Mark::new();
+    let top_level_mark = Mark::new();
+    program.visit_mut_with(&mut resolver(unresolved_mark, top_level_mark, false));
+
+    *program = optimize(
+        std::mem::replace(program, Program::Module(Module::dummy())),
+        source_map,
+        Some(comments),
+        None,
+        &options.to_swc(),
+        &ExtraOptions {
+            unresolved_mark,
+            top_level_mark,
+        },
+    );
+    if !options.mangle {
+        program.visit_mut_with(&mut hygiene());
+    }
+    program.visit_mut_with(&mut fixer(Some(comments)));
+
+    let retain = |_: &BytePos, comments: &mut Vec<Comment>| {
+        comments.retain(|comment| options.preserve_license_comments && is_license_comment(comment));
+        !comments.is_empty()
+    };
+    comments.leading.retain(retain);
+    comments.trailing.retain(retain);
+}
+
+struct ResetSyntaxContexts;
+
+impl VisitMut for ResetSyntaxContexts {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        span.ctxt = SyntaxContext::empty();
+    }
+}
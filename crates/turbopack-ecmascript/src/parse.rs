@@ -1,6 +1,8 @@
 use std::{future::Future, sync::Arc};
 
 use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use swc_core::{
     base::SwcComments,
     common::{
@@ -31,7 +33,9 @@ use turbopack_core::{
     error::PrettyPrintError,
     issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
     source::SourceVc,
-    source_map::{GenerateSourceMap, GenerateSourceMapVc, OptionSourceMapVc},
+    source_map::{
+        input_source_map, GenerateSourceMap, GenerateSourceMapVc, OptionSourceMapVc, SourceMap,
+    },
     SOURCE_MAP_ROOT_NAME,
 };
 use turbopack_swc_utils::emitter::IssueEmitter;
@@ -57,6 +61,9 @@ pub enum ParseResult {
         globals: Arc<Globals>,
         #[turbo_tasks(debug_ignore, trace_ignore)]
         source_map: Arc<swc_core::common::SourceMap>,
+        /// The source map referenced by a `sourceMappingURL` comment, which is
+        /// chained with the source map of the generated code.
+        input_source_map: OptionSourceMapVc,
     },
     Unparseable,
     NotFound,
@@ -83,11 +90,16 @@ pub struct ParseResultSourceMap {
     /// SourceMap.
     #[turbo_tasks(debug_ignore, trace_ignore)]
     mappings: Vec<(BytePos, LineCol)>,
+
+    /// The source map of the parsed source itself, if it has one.
+    input_source_map: Option<OptionSourceMapVc>,
 }
 
 impl PartialEq for ParseResultSourceMap {
     fn eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self.source_map, &other.source_map) && self.mappings == other.mappings
+        Arc::ptr_eq(&self.source_map, &other.source_map)
+            && self.mappings == other.mappings
+            && self.input_source_map == other.input_source_map
     }
 }
 
@@ -99,22 +111,42 @@ impl ParseResultSourceMap {
         ParseResultSourceMap {
             source_map,
             mappings,
+            input_source_map: None,
         }
     }
+
+    /// Maps the generated code through `input_source_map` back to the
+    /// original sources.
+    pub fn with_input_source_map(mut self, input_source_map: OptionSourceMapVc) -> Self {
+        self.input_source_map = Some(input_source_map);
+        self
+    }
 }
 
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for ParseResultSourceMap {
     #[turbo_tasks::function]
-    fn generate_source_map(&self) -> OptionSourceMapVc {
+    async fn generate_source_map(&self) -> Result<OptionSourceMapVc> {
+        let input_source_map = match self.input_source_map {
+            Some(input_source_map) => *input_source_map.await?,
+            None => None,
+        };
+        let input_source_map = match input_source_map {
+            Some(input_source_map) => Some(input_source_map.await?),
+            None => None,
+        };
+        let original = match input_source_map.as_deref() {
+            Some(SourceMap::Regular(map)) => Some(&****map),
+            _ => None,
+        };
         let map = self.source_map.build_source_map_with_config(
             &self.mappings,
-            None,
+            original,
             InlineSourcesContentConfig {},
         );
-        OptionSourceMapVc::cell(Some(
-            turbopack_core::source_map::SourceMap::new_regular(map).cell(),
-        ))
+        Ok(OptionSourceMapVc::cell(Some(
+            SourceMap::new_regular(map).cell(),
+        )))
     }
 }
 
@@ -183,6 +215,10 @@ async fn parse_internal(
             FileContent::Content(file) => match file.content().to_str() {
                 Ok(string) => {
                     let transforms = &*transforms.await?;
+                    let input_source_map = match source_mapping_url(&string) {
+                        Some(url) => input_source_map(fs_path_vc, url.to_string()),
+                        None => OptionSourceMapVc::cell(None),
+                    };
                     match parse_content(
                         string.into_owned(),
                         fs_path_vc,
@@ -192,6 +228,7 @@ async fn parse_internal(
                         source,
                         ty,
                         transforms,
+                        input_source_map,
                     )
                     .await
                     {
@@ -229,6 +266,7 @@ async fn parse_content(
     source: SourceVc,
     ty: EcmascriptModuleAssetType,
     transforms: &[EcmascriptInputTransform],
+    input_source_map: OptionSourceMapVc,
 ) -> Result<ParseResultVc> {
     let source_map: Arc<swc_core::common::SourceMap> = Default::default();
     let handler = Handler::with_emitter(
@@ -359,6 +397,7 @@ async fn parse_content(
                 // borrowed
                 globals: Arc::new(Globals::new()),
                 source_map,
+                input_source_map,
             })
         },
     )
@@ -373,6 +412,18 @@ async fn parse_content(
     Ok(result.cell())
 }
 
+/// Finds the url of the source map in the last `//# sourceMappingURL=`
+/// comment of `source`.
+fn source_mapping_url(source: &str) -> Option<&str> {
+    static SOURCE_MAPPING_URL: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?m)^[ \t]*//[#@][ \t]*sourceMappingURL=(\S+)[ \t]*$").unwrap());
+    SOURCE_MAPPING_URL
+        .captures_iter(source)
+        .last()
+        .and_then(|captures| captures.get(1))
+        .map(|url| url.as_str())
+}
+
 #[turbo_tasks::function]
 async fn hash_ident(ident: StringVc) -> Result<U64Vc> {
     let ident = &*ident.await?;
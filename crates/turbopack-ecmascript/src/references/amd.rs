@@ -225,6 +225,7 @@ fn transform_amd_factory(
         .map(|element| match element {
             ResolvedElement::PatternMapping(pm, req) => match &**pm {
                 PatternMapping::Invalid => quote_expr!("undefined"),
+                pm if pm.global_external().is_some() => Box::new(pm.global_external().unwrap()),
                 pm => {
                     let arg = if let Some(req) = req {
                         pm.apply(req.as_str().into())
@@ -257,7 +258,7 @@ fn transform_amd_factory(
             });
             *callee = Callee::Expr(quote_expr!(
                 "($f1, r = typeof $f2 !== \"function\" ? $f3 : $call_f) => r !== undefined && \
-                 __turbopack_export_value(r)",
+                 __turbopack_export_value__(r)",
                 f1 = f.clone(),
                 f2 = f.clone(),
                 f3 = f,
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use indexmap::IndexSet;
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::ast::{
+        ArrayLit, ArrayPat, AssignExpr, AssignOp, AwaitExpr, CallExpr, Callee, CondExpr, Expr,
+        ExprStmt, Ident, MemberExpr, MemberProp, Pat, PatOrExpr, Program, Stmt,
+    },
+    quote,
+};
+use turbopack_core::reference::AssetReferencesVc;
+
+use super::esm::base::ReferencedAsset;
+use crate::{
+    chunk::{async_module_info::esm_dependencies, AsyncModuleInfoVc},
+    code_gen::{CodeGeneration, CodeGenerationVc},
+    create_visitor,
+    references::esm::base::insert_hoisted_stmt,
+};
+
+/// Returns the identifiers of the imported modules which are async, according
+/// to `async_module_info`.
+pub(crate) async fn async_dependency_idents(
+    references: AssetReferencesVc,
+    async_module_info: AsyncModuleInfoVc,
+) -> Result<IndexSet<String>> {
+    let async_modules = async_module_info.await?;
+    let mut idents = IndexSet::new();
+    for placeable in esm_dependencies(references).await?.iter() {
+        if async_modules.contains(placeable) {
+            idents.insert(ReferencedAsset::get_ident_from_placeable(placeable).await?);
+        }
+    }
+    Ok(idents)
+}
+
+/// Waits for the async dependencies of a module after they have been imported,
+/// and replaces the imported promises with the exports of the modules.
+pub(crate) fn async_dependency_handler(idents: IndexSet<String>) -> CodeGenerationVc {
+    let visitor = create_visitor!(visit_mut_program(program: &mut Program) {
+        add_async_dependency_handler(program, &idents);
+    });
+
+    CodeGeneration {
+        visitors: vec![visitor],
+    }
+    .cell()
+}
+
+fn add_async_dependency_handler(program: &mut Program, idents: &IndexSet<String>) {
+    let idents = idents
+        .iter()
+        .map(|ident| Ident::new(ident.clone().into(), DUMMY_SP))
+        .collect::<Vec<_>>();
+
+    let stmt = quote!(
+        "var __turbopack_async_dependencies__ = __turbopack_handle_async_dependencies__($deps);"
+            as Stmt,
+        deps: Expr = Expr::Array(ArrayLit {
+            span: DUMMY_SP,
+            elems: idents
+                .iter()
+                .map(|ident| Some(Expr::Ident(ident.clone()).into()))
+                .collect(),
+        })
+    );
+    insert_hoisted_stmt(program, stmt);
+
+    // [a, b] = __turbopack_async_dependencies__.then
+    //     ? (await __turbopack_async_dependencies__)()
+    //     : __turbopack_async_dependencies__;
+    let dependencies = || {
+        Box::new(Expr::Ident(Ident::new(
+            "__turbopack_async_dependencies__".into(),
+            DUMMY_SP,
+        )))
+    };
+    let stmt = Stmt::Expr(ExprStmt {
+        span: DUMMY_SP,
+        expr: Box::new(Expr::Assign(AssignExpr {
+            span: DUMMY_SP,
+            op: AssignOp::Assign,
+            left: PatOrExpr::Pat(Box::new(Pat::Array(ArrayPat {
+                span: DUMMY_SP,
+                elems: idents
+                    .into_iter()
+                    .map(|ident| Some(Pat::Ident(ident.into())))
+                    .collect(),
+                optional: false,
+                type_ann: None,
+            }))),
+            right: Box::new(Expr::Cond(CondExpr {
+                span: DUMMY_SP,
+                test: Box::new(Expr::Member(MemberExpr {
+                    span: DUMMY_SP,
+                    obj: dependencies(),
+                    prop: MemberProp::Ident(Ident::new("then".into(), DUMMY_SP)),
+                })),
+                cons: Box::new(Expr::Call(CallExpr {
+                    span: DUMMY_SP,
+                    callee: Callee::Expr(Box::new(Expr::Await(AwaitExpr {
+                        span: DUMMY_SP,
+                        arg: dependencies(),
+                    }))),
+                    args: vec![],
+                    type_args: None,
+                })),
+                alt: dependencies(),
+            })),
+        })),
+    });
+    insert_hoisted_stmt(program, stmt);
+}
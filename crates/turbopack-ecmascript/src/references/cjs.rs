@@ -6,9 +6,14 @@ use swc_core::{
 use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
 use turbopack_core::{
     chunk::{ChunkableModuleReference, ChunkableModuleReferenceVc},
-    issue::{IssueSourceVc, OptionIssueSourceVc},
+    ident::AssetIdentVc,
+    issue::{analyze::AnalyzeIssue, IssueSeverity, IssueSourceVc, OptionIssueSourceVc},
     reference::{AssetReference, AssetReferenceVc},
-    resolve::{origin::ResolveOriginVc, parse::RequestVc, ResolveResultVc},
+    resolve::{
+        origin::{ResolveOrigin, ResolveOriginVc},
+        parse::RequestVc,
+        ResolveResultVc,
+    },
 };
 
 use super::pattern_mapping::{PatternMapping, PatternMappingVc, ResolveType::Cjs};
@@ -16,7 +21,10 @@ use crate::{
     chunk::EcmascriptChunkingContextVc,
     code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
     create_visitor,
-    references::{util::throw_module_not_found_expr, AstPathVc},
+    references::{
+        util::{throw_module_not_found_expr, throw_sync_url_external_expr},
+        AstPathVc,
+    },
     resolve::{cjs_resolve, try_to_severity},
 };
 
@@ -172,6 +180,35 @@ impl CodeGenerateable for CjsRequireAssetReference {
                 }));
             }
             _ => {
+                if let Some(global) = pm.global_external() {
+                    visitors.push(create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
+                        *expr = global.clone();
+                    }));
+                    return Ok(CodeGeneration { visitors }.into());
+                }
+                if pm.is_url_external() {
+                    let request_string = self.request.to_string().await?;
+                    AnalyzeIssue {
+                        code: None,
+                        category: StringVc::cell("analyze".to_string()),
+                        message: StringVc::cell(format!(
+                            "{request_string} is an external loaded from a URL, which can't be \
+                             required synchronously.\nUse `import` or `import()` instead."
+                        )),
+                        source_ident: AssetIdentVc::from_path(self.origin.origin_path()),
+                        severity: IssueSeverity::Error.into(),
+                        source: Some(self.issue_source),
+                        title: StringVc::cell("URL external required".to_string()),
+                    }
+                    .cell()
+                    .as_issue()
+                    .emit();
+                    visitors.push(create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
+                        *expr = throw_sync_url_external_expr(&request_string);
+                    }));
+                    return Ok(CodeGeneration { visitors }.into());
+                }
+                let esm_external = pm.is_esm_external();
                 visitors.push(
                     create_visitor!(exact path, visit_mut_call_expr(call_expr: &mut CallExpr) {
                         call_expr.callee = Callee::Expr(
@@ -189,6 +226,9 @@ impl CodeGenerateable for CjsRequireAssetReference {
                             _ => pm.create(),
                         };
                         call_expr.args.push(ExprOrSpread { spread: None, expr: Box::new(expr) });
+                        if esm_external {
+                            call_expr.args.push(ExprOrSpread { spread: None, expr: Box::new(true.into()) });
+                        }
                     }),
                 );
             }
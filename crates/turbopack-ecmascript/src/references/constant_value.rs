@@ -1,5 +1,8 @@
 use anyhow::Result;
-use swc_core::quote;
+use swc_core::{
+    ecma::ast::{Expr, UnaryExpr, UnaryOp},
+    quote,
+};
 use turbo_tasks::Value;
 use turbopack_core::compile_time_info::CompileTimeDefineValue;
 
@@ -37,11 +40,7 @@ impl CodeGenerateable for ConstantValue {
         let value = self.value.clone();
         let visitors = [
             create_visitor!(exact &self.path.await?, visit_mut_expr(expr: &mut Expr) {
-                *expr = match value {
-                    CompileTimeDefineValue::Bool(true) => quote!("(\"TURBOPACK compile-time value\", true)" as Expr),
-                    CompileTimeDefineValue::Bool(false) => quote!("(\"TURBOPACK compile-time value\", false)" as Expr),
-                    CompileTimeDefineValue::String(ref s) => quote!("(\"TURBOPACK compile-time value\", $e)" as Expr, e: Expr = s.to_string().into()),
-                };
+                *expr = constant_value_expr(&value);
             }),
         ]
         .into();
@@ -49,3 +48,59 @@ impl CodeGenerateable for ConstantValue {
         Ok(CodeGeneration { visitors }.cell())
     }
 }
+
+/// Replaces a `typeof` expression with the compile-time value defined for the
+/// type of its operand.
+#[turbo_tasks::value]
+pub struct ConstantTypeOf {
+    value: CompileTimeDefineValue,
+    path: AstPathVc,
+}
+
+#[turbo_tasks::value_impl]
+impl ConstantTypeOfVc {
+    #[turbo_tasks::function]
+    pub fn new(value: Value<CompileTimeDefineValue>, path: AstPathVc) -> Self {
+        Self::cell(ConstantTypeOf {
+            value: value.into_value(),
+            path,
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for ConstantTypeOf {
+    #[turbo_tasks::function]
+    async fn code_generation(
+        &self,
+        _context: EcmascriptChunkingContextVc,
+    ) -> Result<CodeGenerationVc> {
+        let value = self.value.clone();
+        let visitors = [
+            create_visitor!(exact &self.path.await?, visit_mut_expr(expr: &mut Expr) {
+                // The path only points to the operand's parent expression, other unary
+                // operators must be left untouched.
+                if let Expr::Unary(UnaryExpr { op: UnaryOp::TypeOf, .. }) = expr {
+                    *expr = constant_value_expr(&value);
+                }
+            }),
+        ]
+        .into();
+
+        Ok(CodeGeneration { visitors }.cell())
+    }
+}
+
+fn constant_value_expr(value: &CompileTimeDefineValue) -> Expr {
+    match value {
+        CompileTimeDefineValue::Bool(true) => {
+            quote!("(\"TURBOPACK compile-time value\", true)" as Expr)
+        }
+        CompileTimeDefineValue::Bool(false) => {
+            quote!("(\"TURBOPACK compile-time value\", false)" as Expr)
+        }
+        CompileTimeDefineValue::String(s) => {
+            quote!("(\"TURBOPACK compile-time value\", $e)" as Expr, e: Expr = s.to_string().into())
+        }
+    }
+}
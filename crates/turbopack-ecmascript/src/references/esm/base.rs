@@ -2,7 +2,10 @@ use anyhow::{anyhow, bail, Result};
 use lazy_static::lazy_static;
 use swc_core::{
     common::DUMMY_SP,
-    ecma::ast::{Expr, ExprStmt, Ident, Lit, Module, ModuleItem, Program, Script, Stmt},
+    ecma::ast::{
+        AwaitExpr, CallExpr, Callee, Expr, ExprOrSpread, ExprStmt, Ident, Import, Lit, Module,
+        ModuleItem, Program, Script, Stmt,
+    },
     quote,
 };
 use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
@@ -16,8 +19,8 @@ use turbopack_core::{
     reference::{AssetReference, AssetReferenceVc},
     reference_type::EcmaScriptModulesReferenceSubType,
     resolve::{
-        origin::ResolveOriginVc, parse::RequestVc, ModulePartVc, PrimaryResolveResult,
-        ResolveResultVc,
+        options::ExternalType, origin::ResolveOriginVc, parse::RequestVc, ModulePartVc,
+        PrimaryResolveResult, ResolveResultVc,
     },
 };
 
@@ -26,14 +29,17 @@ use crate::{
     chunk::{EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkingContextVc},
     code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
     create_visitor, magic_identifier,
-    references::util::{request_to_string, throw_module_not_found_expr},
+    references::util::{
+        global_external_expr, global_external_namespace_expr, request_to_string,
+        throw_module_not_found_expr,
+    },
     resolve::esm_resolve,
 };
 
 #[turbo_tasks::value]
 pub enum ReferencedAsset {
     Some(EcmascriptChunkPlaceableVc),
-    OriginalReferenceTypeExternal(String),
+    OriginalReferenceTypeExternal(String, ExternalType),
     None,
 }
 
@@ -41,7 +47,7 @@ impl ReferencedAsset {
     pub async fn get_ident(&self) -> Result<Option<String>> {
         Ok(match self {
             ReferencedAsset::Some(asset) => Some(Self::get_ident_from_placeable(asset).await?),
-            ReferencedAsset::OriginalReferenceTypeExternal(request) => {
+            ReferencedAsset::OriginalReferenceTypeExternal(request, _) => {
                 Some(magic_identifier::mangle(&format!("external {}", request)))
             }
             ReferencedAsset::None => None,
@@ -70,15 +76,21 @@ impl ReferencedAssetVc {
             match result {
                 PrimaryResolveResult::OriginalReferenceExternal => {
                     if let Some(request) = request.await?.request() {
-                        return Ok(ReferencedAsset::OriginalReferenceTypeExternal(request).cell());
+                        return Ok(ReferencedAsset::OriginalReferenceTypeExternal(
+                            request,
+                            ExternalType::CommonJs,
+                        )
+                        .cell());
                     } else {
                         return Ok(ReferencedAssetVc::cell(ReferencedAsset::None));
                     }
                 }
-                PrimaryResolveResult::OriginalReferenceTypeExternal(request) => {
-                    return Ok(
-                        ReferencedAsset::OriginalReferenceTypeExternal(request.clone()).cell(),
-                    );
+                PrimaryResolveResult::OriginalReferenceTypeExternal(request, ty) => {
+                    return Ok(ReferencedAsset::OriginalReferenceTypeExternal(
+                        request.clone(),
+                        *ty,
+                    )
+                    .cell());
                 }
                 PrimaryResolveResult::Asset(asset) => {
                     if let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(asset).await?
@@ -117,7 +129,7 @@ impl EsmAssetReference {
 #[turbo_tasks::value_impl]
 impl EsmAssetReferenceVc {
     #[turbo_tasks::function]
-    pub(super) async fn get_referenced_asset(self) -> Result<ReferencedAssetVc> {
+    pub(crate) async fn get_referenced_asset(self) -> Result<ReferencedAssetVc> {
         let this = self.await?;
 
         Ok(ReferencedAssetVc::from_resolve_result(
@@ -240,7 +252,56 @@ impl CodeGenerateable for EsmAssetReference {
                             insert_hoisted_stmt(program, stmt);
                         }));
                     }
-                    ReferencedAsset::OriginalReferenceTypeExternal(request) => {
+                    ReferencedAsset::OriginalReferenceTypeExternal(name, ExternalType::Global) => {
+                        let namespace = global_external_namespace_expr(global_external_expr(name));
+                        visitors.push(create_visitor!(visit_mut_program(program: &mut Program) {
+                            let stmt = quote!(
+                                "var $name = $namespace;" as Stmt,
+                                name = Ident::new(ident.clone().into(), DUMMY_SP),
+                                namespace: Expr = namespace.clone()
+                            );
+                            insert_hoisted_stmt(program, stmt);
+                        }));
+                    }
+                    ReferencedAsset::OriginalReferenceTypeExternal(
+                        request,
+                        ty @ (ExternalType::Url | ExternalType::EcmaScriptModule),
+                    ) => {
+                        if matches!(ty, ExternalType::EcmaScriptModule)
+                            && !*context.environment().node_externals().await?
+                        {
+                            bail!(
+                                "the chunking context does not support Node.js external modules \
+                                 (request: {})",
+                                request
+                            );
+                        }
+                        // URLs and ES modules can only be loaded asynchronously with a native
+                        // `import()`. Modules importing them are async modules, so the import
+                        // can be awaited at the top level.
+                        let request = request.clone();
+                        visitors.push(create_visitor!(visit_mut_program(program: &mut Program) {
+                            let import = Expr::Call(CallExpr {
+                                span: DUMMY_SP,
+                                callee: Callee::Import(Import { span: DUMMY_SP }),
+                                args: vec![ExprOrSpread {
+                                    spread: None,
+                                    expr: Box::new(Expr::Lit(request.clone().into())),
+                                }],
+                                type_args: None,
+                            });
+                            let stmt = quote!(
+                                "var $name = $import;" as Stmt,
+                                name = Ident::new(ident.clone().into(), DUMMY_SP),
+                                import: Expr = Expr::Await(AwaitExpr {
+                                    span: DUMMY_SP,
+                                    arg: Box::new(import),
+                                })
+                            );
+                            insert_hoisted_stmt(program, stmt);
+                        }));
+                    }
+                    ReferencedAsset::OriginalReferenceTypeExternal(request, _) => {
                         if !*context.environment().node_externals().await? {
                             bail!(
                                 "the chunking context does not support Node.js external modules \
@@ -250,7 +311,6 @@ impl CodeGenerateable for EsmAssetReference {
                         }
                         let request = request.clone();
                         visitors.push(create_visitor!(visit_mut_program(program: &mut Program) {
-                            // TODO Technically this should insert a ESM external, but we don't support that yet
                             let stmt = quote!(
                                 "var $name = __turbopack_external_require__($id, true);" as Stmt,
                                 name = Ident::new(ident.clone().into(), DUMMY_SP),
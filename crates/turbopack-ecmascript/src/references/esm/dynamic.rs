@@ -23,7 +23,7 @@ use crate::{
         CodeGeneration, CodeGenerationVc,
     },
     create_visitor,
-    references::AstPathVc,
+    references::{util::global_external_namespace_expr, AstPathVc},
     resolve::{esm_resolve, try_to_severity},
 };
 
@@ -167,7 +167,15 @@ impl CodeGenerateableWithAvailabilityInfo for EsmAsyncAssetReference {
                         Some(ExprOrSpread { expr, spread: None }) => pm.apply(*expr),
                         _ => pm.create(),
                     };
-                    if pm.is_internal_import() {
+                    if let Some(global) = pm.global_external() {
+                        call_expr.callee = Callee::Expr(quote_expr!("Promise.resolve"));
+                        call_expr.args = vec![
+                            ExprOrSpread {
+                                spread: None,
+                                expr: Box::new(global_external_namespace_expr(global)),
+                            }
+                        ];
+                    } else if pm.is_internal_import() {
                         call_expr.callee = Callee::Expr(quote_expr!(
                             "Promise.resolve().then",
                         ));
@@ -27,6 +27,7 @@ use crate::{
     },
     code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
     create_visitor,
+    export_usage::{UsedExports, UsedExportsVc},
     references::esm::base::insert_hoisted_stmt,
 };
 
@@ -141,11 +142,26 @@ pub struct EsmExports {
 #[turbo_tasks::value_impl]
 impl CodeGenerateable for EsmExports {
     #[turbo_tasks::function]
-    async fn code_generation(
+    fn code_generation(
         self_vc: EsmExportsVc,
+        context: EcmascriptChunkingContextVc,
+    ) -> CodeGenerationVc {
+        self_vc.code_generation_for_used_exports(context, UsedExports::All.cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EsmExportsVc {
+    /// Like [CodeGenerateable::code_generation], but omits the exports which
+    /// aren't `used_exports`, see [crate::export_usage].
+    #[turbo_tasks::function]
+    pub async fn code_generation_for_used_exports(
+        self,
         _context: EcmascriptChunkingContextVc,
+        used_exports: UsedExportsVc,
     ) -> Result<CodeGenerationVc> {
-        let this = self_vc.await?;
+        let this = self.await?;
+        let used_exports = used_exports.await?;
         let mut visitors = Vec::new();
 
         let mut all_exports: BTreeMap<Cow<str>, Cow<EsmExport>> = this
@@ -180,6 +196,9 @@ impl CodeGenerateable for EsmExports {
             }
         }
         for (exported, local) in all_exports.into_iter() {
+            if !used_exports.is_used(&exported) {
+                continue;
+            }
             let expr = match local.as_ref() {
                 EsmExport::Error => Some(quote!(
                     "(() => { throw new Error(\"Failed binding. See build errors!\"); })" as Expr,
@@ -11,7 +11,7 @@ pub use self::{
     base::{EsmAssetReference, EsmAssetReferenceVc},
     binding::{EsmBinding, EsmBindingVc},
     dynamic::{EsmAsyncAssetReference, EsmAsyncAssetReferenceVc},
-    export::{EsmExports, EsmExportsVc},
+    export::{EsmExport, EsmExports, EsmExportsVc},
     meta::{ImportMetaBinding, ImportMetaBindingVc, ImportMetaRef, ImportMetaRefVc},
     module_item::{EsmModuleItem, EsmModuleItemVc},
     url::{UrlAssetReference, UrlAssetReferenceVc},
@@ -121,38 +121,7 @@ impl CodeGenerateable for UrlAssetReference {
 
         let referenced_asset = self_vc.get_referenced_asset().await?;
 
-        // For rendering environments (CSR and SSR), we rewrite the `import.meta.url` to
-        // be a location.origin because it allows us to access files from the root of
-        // the dev server. It's important that this be rewritten for SSR as well, so
-        // that the client's hydration matches exactly.
-        //
-        // In a non-rendering env, the `import.meta.url` is already the correct `file://` URL
-        // to load files.
-        let rewrite = match &*this.rendering.await? {
-            Rendering::None => {
-                CodeGenerationIssue {
-                    severity: IssueSeverity::Error.into(),
-                    title: StringVc::cell(
-                        "new URL(…) not implemented for this environment".to_string(),
-                    ),
-                    message: StringVc::cell(
-                        "new URL(…) is only currently supported for rendering environments like \
-                         Client-Side or Server-Side Rendering."
-                            .to_string(),
-                    ),
-                    path: this.origin.origin_path(),
-                }
-                .cell()
-                .as_issue()
-                .emit();
-                None
-            }
-            Rendering::Client => Some(quote!("location.origin" as Expr)),
-            Rendering::Server(server_addr) => {
-                let location = server_addr.await?.to_string()?;
-                Some(location.into())
-            }
-        };
+        let rewrite = url_rewrite(this.rendering, this.origin).await?;
 
         let ast_path = this.ast_path.await?;
 
@@ -166,22 +135,29 @@ impl CodeGenerateable for UrlAssetReference {
                     create_visitor!(ast_path, visit_mut_expr(new_expr: &mut Expr) {
                         if let Expr::New(NewExpr { args: Some(args), .. }) = new_expr {
                             if let Some(ExprOrSpread { box expr, spread: None }) = args.get_mut(0) {
-                                *expr = quote!(
-                                    "__turbopack_require__($id)" as Expr,
-                                    id: Expr = module_id_to_lit(&id),
-                                );
+                                *expr = match &rewrite {
+                                    // The absolute file URL makes the base irrelevant.
+                                    Some(UrlRewrite::OutputFile) => quote!(
+                                        "__turbopack_external_require__(\"url\").pathToFileURL(__turbopack_resolve_absolute_path__(__turbopack_require__($id)))" as Expr,
+                                        id: Expr = module_id_to_lit(&id),
+                                    ),
+                                    _ => quote!(
+                                        "__turbopack_require__($id)" as Expr,
+                                        id: Expr = module_id_to_lit(&id),
+                                    ),
+                                };
                             }
 
-                            if let Some(rewrite) = &rewrite {
+                            if let Some(UrlRewrite::Base(base)) = &rewrite {
                                 if let Some(ExprOrSpread { box expr, spread: None }) = args.get_mut(1) {
-                                    *expr = rewrite.clone();
+                                    *expr = base.clone();
                                 }
                             }
                         }
                     }),
                 );
             }
-            ReferencedAsset::OriginalReferenceTypeExternal(request) => {
+            ReferencedAsset::OriginalReferenceTypeExternal(request, _) => {
                 let request = request.to_string();
                 visitors.push(
                     create_visitor!(ast_path, visit_mut_expr(new_expr: &mut Expr) {
@@ -190,9 +166,9 @@ impl CodeGenerateable for UrlAssetReference {
                                 *expr = request.as_str().into()
                             }
 
-                            if let Some(rewrite) = &rewrite {
+                            if let Some(UrlRewrite::Base(base)) = &rewrite {
                                 if let Some(ExprOrSpread { box expr, spread: None }) = args.get_mut(1) {
-                                    *expr = rewrite.clone();
+                                    *expr = base.clone();
                                 }
                             }
                         }
@@ -205,3 +181,63 @@ impl CodeGenerateable for UrlAssetReference {
         Ok(CodeGeneration { visitors }.into())
     }
 }
+
+/// How a `new URL(..., import.meta.url)` expression is rewritten to point to
+/// the emitted asset.
+pub(crate) enum UrlRewrite {
+    /// The `import.meta.url` base is replaced with the given expression, which
+    /// is the origin the assets are served from.
+    Base(Expr),
+    /// The URL is the `file://` URL of the asset in the output directory,
+    /// which is only known at runtime. This is used when assets are not
+    /// served, like in Node.js builds.
+    OutputFile,
+}
+
+/// Returns how a `new URL(..., import.meta.url)` expression is rewritten in
+/// the given rendering environment, or `None` when `import.meta.url` should be
+/// kept.
+pub(crate) async fn url_rewrite(
+    rendering: RenderingVc,
+    origin: ResolveOriginVc,
+) -> Result<Option<UrlRewrite>> {
+    // For rendering environments (CSR and SSR), we rewrite the `import.meta.url` to
+    // be a location.origin because it allows us to access files from the root of
+    // the dev server. It's important that this be rewritten for SSR as well, so
+    // that the client's hydration matches exactly.
+    //
+    // In a non-rendering env, the `import.meta.url` is already the correct `file://` URL
+    // to load files.
+    //
+    // Server environments without a server address don't serve the assets, so
+    // they are loaded from the output directory instead.
+    Ok(match &*rendering.await? {
+        Rendering::None => {
+            CodeGenerationIssue {
+                severity: IssueSeverity::Error.into(),
+                title: StringVc::cell(
+                    "new URL(…) not implemented for this environment".to_string(),
+                ),
+                message: StringVc::cell(
+                    "new URL(…) is only currently supported for rendering environments like \
+                     Client-Side or Server-Side Rendering."
+                        .to_string(),
+                ),
+                path: origin.origin_path(),
+            }
+            .cell()
+            .as_issue()
+            .emit();
+            None
+        }
+        Rendering::Client => Some(UrlRewrite::Base(quote!("location.origin" as Expr))),
+        Rendering::Server(server_addr) => {
+            let server_addr = server_addr.await?;
+            if server_addr.port().is_some() {
+                Some(UrlRewrite::Base(server_addr.to_string()?.into()))
+            } else {
+                Some(UrlRewrite::OutputFile)
+            }
+        }
+    })
+}
@@ -1,4 +1,5 @@
 pub mod amd;
+pub(crate) mod async_module;
 pub mod cjs;
 pub mod constant_condition;
 pub mod constant_value;
@@ -7,14 +8,16 @@ pub mod node;
 pub mod pattern_mapping;
 pub mod raw;
 pub mod require_context;
+pub mod side_effects;
 pub mod type_issue;
 pub mod typescript;
 pub mod unreachable;
 pub mod util;
+pub mod worker;
 
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     future::Future,
     mem::take,
     pin::Pin,
@@ -23,7 +26,7 @@ use std::{
 
 use anyhow::Result;
 use constant_condition::{ConstantConditionValue, ConstantConditionVc};
-use constant_value::ConstantValueVc;
+use constant_value::{ConstantTypeOfVc, ConstantValueVc};
 use indexmap::IndexSet;
 use lazy_static::lazy_static;
 use parking_lot::Mutex;
@@ -39,7 +42,7 @@ use swc_core::{
     ecma::{
         ast::*,
         visit::{
-            fields::{AssignExprField, ExprField, PatField, PatOrExprField},
+            fields::{AssignExprField, ExprField, PatField, PatOrExprField, UnaryExprField},
             AstParentKind, AstParentNodeRef, VisitAstPath, VisitWithPath,
         },
     },
@@ -58,6 +61,7 @@ use turbopack_core::{
     reference_type::{CommonJsReferenceSubType, ReferenceType},
     resolve::{
         find_context_file,
+        options::ExternalType,
         origin::{PlainResolveOriginVc, ResolveOrigin, ResolveOriginVc},
         package_json,
         parse::RequestVc,
@@ -76,14 +80,16 @@ use self::{
     },
     cjs::CjsAssetReferenceVc,
     esm::{
-        export::EsmExport, EsmAssetReferenceVc, EsmAsyncAssetReferenceVc, EsmExports,
-        EsmModuleItemVc, ImportMetaBindingVc, ImportMetaRefVc, UrlAssetReferenceVc,
+        base::ReferencedAsset, export::EsmExport, EsmAssetReferenceVc, EsmAsyncAssetReferenceVc,
+        EsmExports, EsmModuleItemVc, ImportMetaBindingVc, ImportMetaRefVc, UrlAssetReferenceVc,
     },
     node::{DirAssetReferenceVc, PackageJsonReferenceVc},
     raw::FileSourceReferenceVc,
+    side_effects::{follow_side_effect_free_reexport, is_side_effect_free_reference},
     typescript::{
         TsConfigReferenceVc, TsReferencePathAssetReferenceVc, TsReferenceTypeAssetReferenceVc,
     },
+    worker::WorkerAssetReferenceVc,
 };
 use super::{
     analyzer::{
@@ -117,6 +123,7 @@ use crate::{
     code_gen::{
         CodeGen, CodeGenerateableVc, CodeGenerateableWithAvailabilityInfoVc, CodeGenerateablesVc,
     },
+    export_usage::{ImportedExport, ImportedExportsVc},
     magic_identifier,
     references::{
         cjs::{
@@ -140,6 +147,9 @@ pub struct AnalyzeEcmascriptModuleResult {
     pub has_top_level_await: bool,
     /// `true` when the analysis was successful.
     pub successful: bool,
+    /// The exports of other modules which are used by this module, see
+    /// [crate::export_usage].
+    pub(crate) imported_exports: ImportedExportsVc,
 }
 
 #[turbo_tasks::value_impl]
@@ -176,6 +186,7 @@ pub(crate) struct AnalyzeEcmascriptModuleResultBuilder {
     exports: EcmascriptExports,
     has_top_level_await: bool,
     successful: bool,
+    imported_exports: Vec<ImportedExport>,
 }
 
 impl AnalyzeEcmascriptModuleResultBuilder {
@@ -186,6 +197,7 @@ impl AnalyzeEcmascriptModuleResultBuilder {
             exports: EcmascriptExports::None,
             has_top_level_await: false,
             successful: false,
+            imported_exports: Vec::new(),
         }
     }
 
@@ -197,6 +209,15 @@ impl AnalyzeEcmascriptModuleResultBuilder {
         self.references.insert(reference.into());
     }
 
+    /// Removes an ESM reference which turned out to be unused, together with
+    /// the exports which are imported through it.
+    pub fn remove_esm_reference(&mut self, reference: EsmAssetReferenceVc) {
+        self.references
+            .shift_remove(&AssetReferenceVc::from(reference));
+        self.imported_exports
+            .retain(|imported_export| imported_export.reference != reference);
+    }
+
     /// Adds a codegen to the analysis result.
     pub fn add_code_gen<C>(&mut self, code_gen: C)
     where
@@ -218,6 +239,13 @@ impl AnalyzeEcmascriptModuleResultBuilder {
             ));
     }
 
+    /// Adds an export of the module referenced by `reference` which is used,
+    /// or all exports when `export` is `None`.
+    pub fn add_imported_export(&mut self, reference: EsmAssetReferenceVc, export: Option<String>) {
+        self.imported_exports
+            .push(ImportedExport { reference, export });
+    }
+
     /// Sets the analysis result ES export.
     pub fn set_exports(&mut self, exports: EcmascriptExports) {
         self.exports = exports;
@@ -257,6 +285,7 @@ impl AnalyzeEcmascriptModuleResultBuilder {
                 exports: self.exports.into(),
                 has_top_level_await: self.has_top_level_await,
                 successful: self.successful,
+                imported_exports: ImportedExportsVc::cell(self.imported_exports),
             },
         ))
     }
@@ -295,6 +324,9 @@ struct AnalysisState<'a> {
     // the object allocation.
     first_import_meta: bool,
     import_parts: bool,
+    /// Whether all AMD `define()` calls could be analyzed, or `None` when there
+    /// is no `define()` call.
+    amd_define_supported: Mutex<Option<bool>>,
 }
 
 impl<'a> AnalysisState<'a> {
@@ -318,6 +350,10 @@ where
     HANDLER.set(handler, || GLOBALS.set(globals, f))
 }
 
+/// The maximum number of reexports which are followed for a named import from
+/// a side effect free module.
+const MAX_REEXPORT_DEPTH: usize = 16;
+
 #[turbo_tasks::function]
 pub(crate) async fn analyze_ecmascript_module(
     source: SourceVc,
@@ -452,11 +488,9 @@ pub(crate) async fn analyze_ecmascript_module(
         }),
     );
 
-    let has_top_level_await =
+    let mut has_top_level_await =
         set_handler_and_globals(&handler, globals, || has_top_level_await(program));
 
-    analysis.set_top_level_await(has_top_level_await);
-
     let mut var_graph =
         set_handler_and_globals(&handler, globals, || create_graph(program, eval_context));
 
@@ -488,6 +522,54 @@ pub(crate) async fn analyze_ecmascript_module(
         analysis.add_reference(*r);
     }
 
+    // URL and ESM externals are loaded with an awaited `import()`, which makes
+    // the module an async module.
+    for r in import_references.iter() {
+        if matches!(
+            &*r.get_referenced_asset().await?,
+            ReferencedAsset::OriginalReferenceTypeExternal(
+                _,
+                ExternalType::Url | ExternalType::EcmaScriptModule
+            )
+        ) {
+            has_top_level_await = true;
+        }
+    }
+    analysis.set_top_level_await(has_top_level_await);
+
+    // Named imports which a side effect free module only reexports are
+    // imported from the module declaring them instead when they are used, so
+    // the reexporting module can be dropped when nothing else is imported from
+    // it.
+    let mut reexported_imports = HashMap::new();
+    if !options.import_parts {
+        for (i, symbol) in eval_context.imports.imported_symbols() {
+            let Some(symbol) = symbol else {
+                continue;
+            };
+            let mut binding = None;
+            let (mut reference, mut export) = (import_references[i], symbol.to_string());
+            for _ in 0..MAX_REEXPORT_DEPTH {
+                let next = follow_side_effect_free_reexport(reference, export.clone()).await?;
+                let Some((next_reference, next_export)) = &*next else {
+                    break;
+                };
+                (reference, export) = (*next_reference, next_export.clone());
+                binding = Some((reference, export.clone()));
+            }
+            if let Some(binding) = binding {
+                reexported_imports.insert(i, binding);
+            }
+        }
+    }
+
+    let mut used_reexported_imports = HashSet::new();
+    for (i, symbol) in eval_context.imports.imported_symbols() {
+        if !reexported_imports.contains_key(&i) {
+            analysis.add_imported_export(import_references[i], symbol.map(|s| s.to_string()));
+        }
+    }
+
     let (webpack_runtime, webpack_entry, webpack_chunks, esm_exports, esm_star_exports) =
         set_handler_and_globals(&handler, globals, || {
             // TODO migrate to effects
@@ -570,6 +652,17 @@ pub(crate) async fn analyze_ecmascript_module(
         }
     }
 
+    let exported_references = esm_exports
+        .values()
+        .filter_map(|export| match export {
+            EsmExport::ImportedBinding(reference, _) | EsmExport::ImportedNamespace(reference) => {
+                Some(*reference)
+            }
+            EsmExport::LocalBinding(_) => None,
+        })
+        .chain(esm_star_exports.iter().copied())
+        .collect::<HashSet<_>>();
+
     let exports = if !esm_exports.is_empty() || !esm_star_exports.is_empty() {
         if matches!(specified_type, SpecifiedModuleType::CommonJs) {
             SpecifiedModuleTypeIssue {
@@ -638,6 +731,12 @@ pub(crate) async fn analyze_ecmascript_module(
 
     let effects = take(&mut var_graph.effects);
 
+    // Uses of imported bindings in branches which are eliminated are counted,
+    // so imports which are only used in these branches can be dropped.
+    let mut imported_binding_uses = HashMap::new();
+    count_imported_bindings(&effects, &mut imported_binding_uses);
+    let mut eliminated_imported_binding_uses = HashMap::new();
+
     let mut analysis_state = AnalysisState {
         handler: &handler,
         source,
@@ -647,7 +746,11 @@ pub(crate) async fn analyze_ecmascript_module(
         fun_args_values: Mutex::new(HashMap::<u32, Vec<JsValue>>::new()),
         first_import_meta: true,
         import_parts: options.import_parts,
+        amd_define_supported: Mutex::new(None),
     };
+    // The paths of the `typeof define` and `define.amd` checks of UMD wrappers,
+    // together with whether it's a `typeof` check.
+    let mut amd_checks = Vec::new();
 
     enum Action {
         Effect(Effect),
@@ -690,6 +793,10 @@ pub(crate) async fn analyze_ecmascript_module(
 
                 macro_rules! inactive {
                     ($block:ident) => {
+                        count_imported_bindings(
+                            &$block.effects,
+                            &mut eliminated_imported_binding_uses,
+                        );
                         analysis.add_code_gen(UnreachableVc::new(AstPathVc::cell(
                             $block.ast_path.to_vec(),
                         )));
@@ -881,6 +988,11 @@ pub(crate) async fn analyze_ecmascript_module(
                 span: _,
                 in_try: _,
             } => {
+                if matches!(&var, JsValue::FreeVar(name) if &**name == "define") {
+                    if let Some(path) = typeof_path(&ast_path) {
+                        amd_checks.push((true, path.to_vec()));
+                    }
+                }
                 handle_free_var(&ast_path, var, &analysis_state, &mut analysis).await?;
             }
             Effect::Member {
@@ -893,6 +1005,11 @@ pub(crate) async fn analyze_ecmascript_module(
                 let obj = analysis_state.link_value(obj, in_try).await?;
                 let prop = analysis_state.link_value(prop, in_try).await?;
 
+                if obj == JsValue::WellKnownFunction(WellKnownFunctionKind::Define)
+                    && prop.as_str() == Some("amd")
+                {
+                    amd_checks.push((false, ast_path.clone()));
+                }
                 handle_member(&ast_path, obj, prop, &analysis_state, &mut analysis).await?;
             }
             Effect::ImportedBinding {
@@ -908,6 +1025,18 @@ pub(crate) async fn analyze_ecmascript_module(
                             *r,
                             AstPathVc::cell(ast_path),
                         ))
+                    } else if let Some((reference, export)) =
+                        reexported_imports.get(&esm_reference_index)
+                    {
+                        if used_reexported_imports.insert(esm_reference_index) {
+                            analysis.add_reference(*reference);
+                            analysis.add_imported_export(*reference, Some(export.clone()));
+                        }
+                        analysis.add_code_gen(EsmBindingVc::new(
+                            *reference,
+                            Some(export.clone()),
+                            AstPathVc::cell(ast_path),
+                        ));
                     } else {
                         analysis.add_code_gen(EsmBindingVc::new(
                             *r,
@@ -958,6 +1087,101 @@ pub(crate) async fn analyze_ecmascript_module(
                     in_try,
                 ));
             }
+            Effect::Worker {
+                input,
+                ty,
+                ast_path,
+                span,
+                in_try,
+            } => {
+                let pat = js_value_to_pattern(&input);
+                if !pat.has_constant_parts() {
+                    handler.span_warn_with_code(
+                        span,
+                        &format!(
+                            "new URL({input}, import.meta.url) of a {} is very dynamic",
+                            ty.description()
+                        ),
+                        DiagnosticId::Lint(
+                            errors::failed_to_analyse::ecmascript::NEW_URL_IMPORT_META.to_string(),
+                        ),
+                    )
+                }
+                analysis.add_reference(WorkerAssetReferenceVc::new(
+                    origin,
+                    RequestVc::parse(Value::new(pat)),
+                    Value::new(ty),
+                    compile_time_info.environment().rendering(),
+                    AstPathVc::cell(ast_path),
+                    IssueSourceVc::from_byte_offset(
+                        source.into(),
+                        span.lo.to_usize(),
+                        span.hi.to_usize(),
+                    ),
+                    in_try,
+                ));
+            }
+        }
+    }
+
+    // UMD wrappers only call `define()` when they detect an AMD loader, so
+    // these checks are replaced to let them use the AMD branch, whose factory
+    // result becomes the exports. This is only safe when all `define()` calls
+    // have been replaced.
+    if *analysis_state.amd_define_supported.get_mut() == Some(true) {
+        for (is_typeof, path) in amd_checks {
+            if is_typeof {
+                analysis.add_code_gen(ConstantTypeOfVc::new(
+                    Value::new("function".to_string().into()),
+                    AstPathVc::cell(path),
+                ));
+            } else {
+                analysis.add_code_gen(ConstantValueVc::new(
+                    Value::new(true.into()),
+                    AstPathVc::cell(path),
+                ));
+            }
+        }
+    }
+
+    // Imports are dropped when all of the bindings imported from a module are
+    // only used in eliminated branches and the module is not imported for its
+    // side effects otherwise. Imports of side effect free modules are dropped
+    // whenever none of their bindings are used, including bindings which are
+    // imported from the reexported module instead.
+    let imports = &eval_context.imports;
+    let eliminated_imports = eliminated_imported_binding_uses
+        .iter()
+        .filter(|(index, uses)| imported_binding_uses.get(index) == Some(uses))
+        .map(|(index, _)| *index)
+        .collect::<HashSet<_>>();
+    let is_unused = |i: &usize| {
+        reexported_imports.contains_key(i)
+            || imported_binding_uses.get(i) == eliminated_imported_binding_uses.get(i)
+    };
+    let mut visited = HashSet::new();
+    for index in 0..import_references.len() {
+        if visited.contains(&index) {
+            continue;
+        }
+        let module_references = imports.module_references(index).collect::<Vec<_>>();
+        visited.extend(module_references.iter().copied());
+        let droppable = (module_references
+            .iter()
+            .any(|i| eliminated_imports.contains(i))
+            && module_references.iter().all(|&i| {
+                eliminated_imports.contains(&i) || imports.is_droppable_module_evaluation(i)
+            }))
+            || (module_references.iter().all(is_unused)
+                && *is_side_effect_free_reference(import_references[index]).await?);
+        if !droppable {
+            continue;
+        }
+        for i in module_references {
+            let reference = import_references[i];
+            if !exported_references.contains(&reference) {
+                analysis.remove_esm_reference(reference);
+            }
         }
     }
 
@@ -966,6 +1190,42 @@ pub(crate) async fn analyze_ecmascript_module(
     analysis.build().await
 }
 
+/// Counts the uses of imported bindings in `effects`, including nested
+/// effects, by index of the import reference.
+fn count_imported_bindings(effects: &[Effect], uses: &mut HashMap<usize, usize>) {
+    for effect in effects {
+        match effect {
+            Effect::ImportedBinding {
+                esm_reference_index,
+                ..
+            } => {
+                *uses.entry(*esm_reference_index).or_default() += 1;
+            }
+            Effect::Conditional { kind, .. } => match &**kind {
+                ConditionalKind::If { then } => count_imported_bindings(&then.effects, uses),
+                ConditionalKind::IfElse { then, r#else }
+                | ConditionalKind::Ternary { then, r#else } => {
+                    count_imported_bindings(&then.effects, uses);
+                    count_imported_bindings(&r#else.effects, uses);
+                }
+                ConditionalKind::And { expr }
+                | ConditionalKind::Or { expr }
+                | ConditionalKind::NullishCoalescing { expr } => {
+                    count_imported_bindings(&expr.effects, uses)
+                }
+            },
+            Effect::Call { args, .. } | Effect::MemberCall { args, .. } => {
+                for arg in args {
+                    if let EffectArg::Closure(_, block) = arg {
+                        count_imported_bindings(&block.effects, uses);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 fn handle_call_boxed<'a, G: Fn(Vec<Effect>) + Send + Sync + 'a>(
     ast_path: &'a [AstParentKind],
     span: Span,
@@ -1110,7 +1370,7 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
             )
         }
         JsValue::WellKnownFunction(WellKnownFunctionKind::Define) => {
-            analyze_amd_define(
+            let supported = analyze_amd_define(
                 source,
                 analysis,
                 origin,
@@ -1120,6 +1380,8 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
                 linked_args(args).await?,
                 in_try,
             );
+            let mut amd_define_supported = state.amd_define_supported.lock();
+            *amd_define_supported = Some(amd_define_supported.unwrap_or(true) && supported);
         }
 
         JsValue::WellKnownFunction(WellKnownFunctionKind::RequireResolve) => {
@@ -1646,6 +1908,16 @@ async fn handle_member(
             }
         }
     }
+    if handle_compile_time_define(
+        ast_path,
+        &JsValue::member(Box::new(obj.clone()), Box::new(prop.clone())),
+        state,
+        analysis,
+    )
+    .await?
+    {
+        return Ok(());
+    }
     match (obj, prop) {
         (JsValue::WellKnownFunction(WellKnownFunctionKind::Require), JsValue::Constant(s))
             if s.as_str() == Some("cache") =>
@@ -1685,10 +1957,70 @@ async fn handle_free_var(
             }
         }
     }
+    handle_compile_time_define(ast_path, &var, state, analysis).await?;
 
     Ok(())
 }
 
+/// Replaces a reference to a compile-time define, or a `typeof` expression of
+/// such a reference, with the defined value. Defines of `typeof` expressions
+/// are named with a leading `typeof` segment, e.g. `typeof window`.
+async fn handle_compile_time_define(
+    ast_path: &[AstParentKind],
+    value: &JsValue,
+    state: &AnalysisState<'_>,
+    analysis: &mut AnalyzeEcmascriptModuleResultBuilder,
+) -> Result<bool> {
+    let Some(def_name_len) = value.get_defineable_name_len() else {
+        return Ok(false);
+    };
+    let typeof_path = typeof_path(ast_path);
+    let compile_time_info = state.compile_time_info.await?;
+    let defines = compile_time_info.defines.await?;
+    for (name, define) in defines.iter() {
+        let (is_typeof, name) = match name.split_first() {
+            Some((first, rest)) if first == "typeof" => (true, rest),
+            _ => (false, &name[..]),
+        };
+        if name.len() != def_name_len
+            || !value
+                .iter_defineable_name_rev()
+                .eq(name.iter().map(Cow::Borrowed).rev())
+        {
+            continue;
+        }
+        if !is_typeof {
+            return handle_free_var_reference(
+                ast_path,
+                &FreeVarReference::Value(define.clone()),
+                state,
+                analysis,
+            )
+            .await;
+        }
+        if let Some(typeof_path) = typeof_path {
+            analysis.add_code_gen(ConstantTypeOfVc::new(
+                Value::new(define.clone()),
+                AstPathVc::cell(typeof_path.to_vec()),
+            ));
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Returns the path to the `typeof` expression when `ast_path` points to its
+/// operand.
+fn typeof_path(ast_path: &[AstParentKind]) -> Option<&[AstParentKind]> {
+    match ast_path {
+        [path @ .., AstParentKind::UnaryExpr(UnaryExprField::Arg), AstParentKind::Expr(_)] => {
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
 async fn handle_free_var_reference(
     ast_path: &[AstParentKind],
     value: &FreeVarReference,
@@ -1738,6 +2070,7 @@ async fn handle_free_var_reference(
             .resolve()
             .await?;
             analysis.add_reference(esm_reference);
+            analysis.add_imported_export(esm_reference, export.clone());
             analysis.add_code_gen(EsmBindingVc::new(
                 esm_reference,
                 export.clone(),
@@ -1761,7 +2094,7 @@ fn analyze_amd_define(
     ast_path: &[AstParentKind],
     args: Vec<JsValue>,
     in_try: bool,
-) {
+) -> bool {
     match &args[..] {
         [JsValue::Constant(id), JsValue::Array { items: deps, .. }, _] if id.as_str().is_some() => {
             analyze_amd_define_with_deps(
@@ -1774,13 +2107,11 @@ fn analyze_amd_define(
                 id.as_str(),
                 deps,
                 in_try,
-            );
-        }
-        [JsValue::Array { items: deps, .. }, _] => {
-            analyze_amd_define_with_deps(
-                source, analysis, origin, handler, span, ast_path, None, deps, in_try,
-            );
+            )
         }
+        [JsValue::Array { items: deps, .. }, _] => analyze_amd_define_with_deps(
+            source, analysis, origin, handler, span, ast_path, None, deps, in_try,
+        ),
         [JsValue::Constant(id), JsValue::Function(..)] if id.as_str().is_some() => {
             analysis.add_code_gen(AmdDefineWithDependenciesCodeGenVc::new(
                 vec![
@@ -1794,6 +2125,7 @@ fn analyze_amd_define(
                 issue_source(source, span),
                 in_try,
             ));
+            true
         }
         [JsValue::Constant(id), _] if id.as_str().is_some() => {
             analysis.add_code_gen(AmdDefineWithDependenciesCodeGenVc::new(
@@ -1808,6 +2140,7 @@ fn analyze_amd_define(
                 issue_source(source, span),
                 in_try,
             ));
+            true
         }
         [JsValue::Function(..)] => {
             analysis.add_code_gen(AmdDefineWithDependenciesCodeGenVc::new(
@@ -1822,6 +2155,7 @@ fn analyze_amd_define(
                 issue_source(source, span),
                 in_try,
             ));
+            true
         }
         [JsValue::Object { .. }] => {
             analysis.add_code_gen(AmdDefineWithDependenciesCodeGenVc::new(
@@ -1832,6 +2166,7 @@ fn analyze_amd_define(
                 issue_source(source, span),
                 in_try,
             ));
+            true
         }
         [_] => {
             analysis.add_code_gen(AmdDefineWithDependenciesCodeGenVc::new(
@@ -1846,6 +2181,7 @@ fn analyze_amd_define(
                 issue_source(source, span),
                 in_try,
             ));
+            true
         }
         _ => {
             handler.span_err_with_code(
@@ -1853,6 +2189,7 @@ fn analyze_amd_define(
                 "unsupported AMD define() form",
                 DiagnosticId::Error(errors::failed_to_analyse::ecmascript::AMD_DEFINE.to_string()),
             );
+            false
         }
     }
 }
@@ -1867,7 +2204,8 @@ fn analyze_amd_define_with_deps(
     id: Option<&str>,
     deps: &[JsValue],
     in_try: bool,
-) {
+) -> bool {
+    let mut supported = true;
     let mut requests = Vec::new();
     for dep in deps {
         if let Some(dep) = dep.as_str() {
@@ -1908,6 +2246,7 @@ fn analyze_amd_define_with_deps(
                 "unsupported AMD define() dependency element form",
                 DiagnosticId::Error(errors::failed_to_analyse::ecmascript::AMD_DEFINE.to_string()),
             );
+            supported = false;
         }
     }
 
@@ -1927,6 +2266,7 @@ fn analyze_amd_define_with_deps(
         issue_source(source, span),
         in_try,
     ));
+    supported
 }
 
 /// Used to generate the "root" path to a __filename/__dirname/import.meta.url
@@ -2644,6 +2984,18 @@ fn detect_dynamic_export(p: &Program) -> DetectedDynamicExportType {
                 self.found = true;
             }
         }
+        fn visit_call_expr(&mut self, n: &CallExpr) {
+            // AMD `define()` calls export the factory result, which is accessed
+            // like CommonJS exports.
+            if let Callee::Expr(box Expr::Ident(i)) = &n.callee {
+                if &*i.sym == "define" {
+                    self.cjs = true;
+                    self.found = true;
+                    return;
+                }
+            }
+            n.visit_children_with(self);
+        }
         fn visit_expr(&mut self, n: &Expr) {
             if self.found {
                 return;
@@ -13,13 +13,14 @@ use turbopack_core::{
     },
     issue::{code_gen::CodeGenerationIssue, IssueSeverity},
     resolve::{
+        options::ExternalType,
         origin::{ResolveOrigin, ResolveOriginVc},
         parse::RequestVc,
         PrimaryResolveResult, ResolveResultVc,
     },
 };
 
-use super::util::{request_to_string, throw_module_not_found_expr};
+use super::util::{global_external_expr, request_to_string, throw_module_not_found_expr};
 use crate::{chunk::EcmascriptChunkItemVc, utils::module_id_to_lit};
 
 /// A mapping from a request pattern (e.g. "./module", `./images/${name}.png`)
@@ -59,7 +60,7 @@ pub(crate) enum PatternMapping {
     /// Original reference
     OriginalReferenceExternal,
     /// Original reference with different request
-    OriginalReferenceTypeExternal(String),
+    OriginalReferenceTypeExternal(String, ExternalType),
 }
 
 #[derive(PartialOrd, Ord, Hash, Debug, Copy, Clone)]
@@ -79,7 +80,36 @@ impl PatternMapping {
             | PatternMapping::SingleLoader(_)
             | PatternMapping::Map(_) => true,
             PatternMapping::OriginalReferenceExternal
-            | PatternMapping::OriginalReferenceTypeExternal(_) => false,
+            | PatternMapping::OriginalReferenceTypeExternal(..) => false,
+        }
+    }
+
+    /// Whether an external should be loaded with ESM interop applied, even
+    /// when it is required from CommonJS.
+    pub fn is_esm_external(&self) -> bool {
+        matches!(
+            self,
+            PatternMapping::OriginalReferenceTypeExternal(_, ExternalType::EcmaScriptModule)
+        )
+    }
+
+    /// Whether an external is loaded from a URL, which is only possible with a
+    /// native dynamic `import()`.
+    pub fn is_url_external(&self) -> bool {
+        matches!(
+            self,
+            PatternMapping::OriginalReferenceTypeExternal(_, ExternalType::Url)
+        )
+    }
+
+    /// Returns the expression reading the global variable when this is an
+    /// external which is consumed as a global.
+    pub fn global_external(&self) -> Option<Expr> {
+        match self {
+            PatternMapping::OriginalReferenceTypeExternal(name, ExternalType::Global) => {
+                Some(global_external_expr(name))
+            }
+            _ => None,
         }
     }
 
@@ -102,7 +132,7 @@ impl PatternMapping {
             PatternMapping::OriginalReferenceExternal => {
                 todo!("emit an error for this case: apply need to be used");
             }
-            PatternMapping::OriginalReferenceTypeExternal(s) => {
+            PatternMapping::OriginalReferenceTypeExternal(s, _) => {
                 Expr::Lit(Lit::Str(s.as_str().into()))
             }
         }
@@ -142,8 +172,8 @@ impl PatternMappingVc {
             Some(PrimaryResolveResult::OriginalReferenceExternal) => {
                 return Ok(PatternMapping::OriginalReferenceExternal.cell())
             }
-            Some(PrimaryResolveResult::OriginalReferenceTypeExternal(s)) => {
-                return Ok(PatternMapping::OriginalReferenceTypeExternal(s.clone()).cell())
+            Some(PrimaryResolveResult::OriginalReferenceTypeExternal(s, ty)) => {
+                return Ok(PatternMapping::OriginalReferenceTypeExternal(s.clone(), *ty).cell())
             }
             Some(PrimaryResolveResult::Ignore) => return Ok(PatternMapping::Ignored.cell()),
             _ => {
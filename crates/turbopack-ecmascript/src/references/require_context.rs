@@ -43,7 +43,6 @@ use crate::{
         EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkVc,
         EcmascriptChunkingContextVc, EcmascriptExports, EcmascriptExportsVc,
     },
-    chunk_group_files_asset::ChunkGroupFilesAssetVc,
     code_gen::{CodeGenerateable, CodeGeneration, CodeGenerationVc},
     create_visitor,
     references::{
@@ -386,7 +385,7 @@ impl Module for RequireContextAsset {}
 impl ChunkableModule for RequireContextAsset {
     #[turbo_tasks::function]
     fn as_chunk(
-        self_vc: ChunkGroupFilesAssetVc,
+        self_vc: RequireContextAssetVc,
         context: ChunkingContextVc,
         availability_info: Value<AvailabilityInfo>,
     ) -> ChunkVc {
@@ -0,0 +1,134 @@
+//! Support for the `sideEffects` field of package.json.
+//!
+//! Modules of packages with `"sideEffects": false`, or which don't match any of
+//! the globs in `"sideEffects": ["*.css"]`, are assumed to not have side
+//! effects when they are evaluated. Imports of such modules can be dropped when
+//! nothing which is imported from them is used, and named imports which the
+//! module only reexports can be imported from the reexported module directly.
+
+use std::borrow::Cow;
+
+use anyhow::Result;
+use turbo_tasks::{primitives::BoolVc, Value};
+use turbo_tasks_fs::{glob::Glob, FileJsonContent, FileSystemPathVc};
+use turbopack_core::{
+    asset::Asset,
+    resolve::{
+        find_context_file, origin::ResolveOrigin, package_json, parse::RequestVc,
+        FindContextFileResult,
+    },
+};
+
+use super::esm::{base::ReferencedAsset, EsmAssetReferenceVc};
+use crate::{analyzer::imports::Reexport, parse::ParseResult, EcmascriptModuleAssetVc};
+
+/// Returns true when the package.json closest to `path` marks the module as
+/// side effect free.
+#[turbo_tasks::function]
+pub async fn is_marked_as_side_effect_free(path: FileSystemPathVc) -> Result<BoolVc> {
+    let FindContextFileResult::Found(package_json, _) =
+        *find_context_file(path.parent(), package_json()).await?
+    else {
+        return Ok(BoolVc::cell(false));
+    };
+    let FileJsonContent::Content(content) = &*package_json.read_json().await? else {
+        return Ok(BoolVc::cell(false));
+    };
+    let side_effect_free = match content.get("sideEffects") {
+        Some(serde_json::Value::Bool(side_effects)) => !side_effects,
+        Some(serde_json::Value::Array(globs)) => {
+            let package_dir = package_json.parent().await?;
+            let path = path.await?;
+            match package_dir.get_path_to(&path) {
+                Some(path) => !globs.iter().any(|glob| match glob.as_str() {
+                    Some(glob) => side_effects_glob_matches(glob, path),
+                    // Be conservative about what we don't understand
+                    None => true,
+                }),
+                None => false,
+            }
+        }
+        _ => false,
+    };
+    Ok(BoolVc::cell(side_effect_free))
+}
+
+/// Matches a glob of the `sideEffects` field against the path of a module
+/// relative to its package. Like in webpack, globs without a `/` match files
+/// in any directory.
+fn side_effects_glob_matches(glob: &str, path: &str) -> bool {
+    let glob = glob.strip_prefix("./").unwrap_or(glob);
+    let glob = if glob.contains('/') {
+        Cow::Borrowed(glob)
+    } else {
+        Cow::Owned(format!("**/{glob}"))
+    };
+    match Glob::try_from(&*glob) {
+        Ok(glob) => glob.execute(path),
+        Err(_) => true,
+    }
+}
+
+/// Returns true when the module referenced by `reference` is marked as side
+/// effect free.
+#[turbo_tasks::function]
+pub(crate) async fn is_side_effect_free_reference(
+    reference: EsmAssetReferenceVc,
+) -> Result<BoolVc> {
+    let ReferencedAsset::Some(placeable) = &*reference.get_referenced_asset().await? else {
+        return Ok(BoolVc::cell(false));
+    };
+    Ok(is_marked_as_side_effect_free(placeable.ident().path()))
+}
+
+/// A reference to a module and the name of an export of it.
+#[turbo_tasks::value(transparent)]
+pub(crate) struct OptionReexportedBinding(Option<(EsmAssetReferenceVc, String)>);
+
+/// Follows a named reexport (`export { a } from "./a"`) of `export` in the
+/// side effect free module referenced by `reference`, returning a reference to
+/// the module the export is reexported from and its name in that module.
+///
+/// Only the parsed module is looked at, not its analysis, so modules which
+/// import each other can follow their reexports without a cycle.
+#[turbo_tasks::function]
+pub(crate) async fn follow_side_effect_free_reexport(
+    reference: EsmAssetReferenceVc,
+    export: String,
+) -> Result<OptionReexportedBindingVc> {
+    let ReferencedAsset::Some(placeable) = &*reference.get_referenced_asset().await? else {
+        return Ok(OptionReexportedBindingVc::cell(None));
+    };
+    let Some(module) = EcmascriptModuleAssetVc::resolve_from(placeable).await? else {
+        return Ok(OptionReexportedBindingVc::cell(None));
+    };
+    if !*is_marked_as_side_effect_free(module.ident().path()).await? {
+        return Ok(OptionReexportedBindingVc::cell(None));
+    }
+    let ParseResult::Ok { eval_context, .. } = &*module.parse().await? else {
+        return Ok(OptionReexportedBindingVc::cell(None));
+    };
+    let imports = &eval_context.imports;
+    for (index, reexport) in imports.reexports() {
+        let Reexport::Named { imported, exported } = reexport else {
+            continue;
+        };
+        if &**exported != export {
+            continue;
+        }
+        let Some(r) = imports.references().nth(index) else {
+            continue;
+        };
+        let reference = EsmAssetReferenceVc::new(
+            module.as_resolve_origin(),
+            RequestVc::parse(Value::new(r.module_path.to_string().into())),
+            Value::new(r.annotations.clone()),
+            None,
+        );
+        return Ok(OptionReexportedBindingVc::cell(Some((
+            reference.resolve().await?,
+            imported.to_string(),
+        ))));
+    }
+    Ok(OptionReexportedBindingVc::cell(None))
+}
@@ -14,6 +14,35 @@ pub fn throw_module_not_found_expr(request: &str) -> Expr {
     )
 }
 
+/// Creates an expression that reads the global variable `name`, which is used
+/// for externals that are consumed as globals.
+pub fn global_external_expr(name: &str) -> Expr {
+    quote!(
+        "globalThis[$name]" as Expr,
+        name: Expr = name.into()
+    )
+}
+
+/// Creates the namespace object of a global external, which has the
+/// properties of the `global` as named exports and the `global` itself as the
+/// default export.
+pub fn global_external_namespace_expr(global: Expr) -> Expr {
+    quote!(
+        "Object.assign(Object.create(null), $global, { default: $global })" as Expr,
+        global: Expr = global
+    )
+}
+
+/// Creates an expression that throws when a URL external is loaded
+/// synchronously, since URLs can only be loaded with a dynamic `import()`.
+pub fn throw_sync_url_external_expr(url: &str) -> Expr {
+    let message = format!("Cannot load '{url}' synchronously, use import() instead");
+    quote!(
+        "(() => { throw new Error($message); })()" as Expr,
+        message: Expr = message.into()
+    )
+}
+
 #[turbo_tasks::function]
 pub async fn request_to_string(request: RequestVc) -> Result<StringVc> {
     Ok(StringVc::cell(
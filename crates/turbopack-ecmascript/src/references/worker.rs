@@ -0,0 +1,186 @@
+use anyhow::Result;
+use swc_core::{
+    ecma::ast::{CallExpr, Expr, ExprOrSpread, NewExpr, Prop, PropName, PropOrSpread},
+    quote,
+};
+use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
+use turbopack_core::{
+    chunk::{
+        ChunkableModuleReference, ChunkableModuleReferenceVc, ChunkingType, ChunkingTypeOptionVc,
+        EvaluatableAssetVc,
+    },
+    environment::RenderingVc,
+    issue::{IssueSourceVc, OptionIssueSourceVc},
+    reference::{AssetReference, AssetReferenceVc},
+    reference_type::EcmaScriptModulesReferenceSubType,
+    resolve::{origin::ResolveOriginVc, parse::RequestVc, ResolveResultVc},
+};
+
+use super::esm::{
+    base::{ReferencedAsset, ReferencedAssetVc},
+    url::{url_rewrite, UrlRewrite},
+};
+use crate::{
+    chunk::{EcmascriptChunkPlaceable, EcmascriptChunkingContextVc},
+    code_gen::{CodeGenerateable, CodeGenerateableVc, CodeGeneration, CodeGenerationVc},
+    create_visitor,
+    references::AstPathVc,
+    resolve::{esm_resolve, try_to_severity},
+    utils::module_id_to_lit,
+    worker::{module::WorkerModuleVc, WorkerType},
+};
+
+/// Worker Asset References are injected during code analysis when we find a
+/// (staticly analyzable) `new Worker(new URL("path", import.meta.url))` or
+/// one of its variants for shared workers and worklets.
+///
+/// The entry of the worker is placed in its own chunk group, and the URL is
+/// rewritten to the script which loads this chunk group.
+#[turbo_tasks::value]
+pub struct WorkerAssetReference {
+    origin: ResolveOriginVc,
+    request: RequestVc,
+    ty: WorkerType,
+    rendering: RenderingVc,
+    ast_path: AstPathVc,
+    issue_source: IssueSourceVc,
+    in_try: bool,
+}
+
+#[turbo_tasks::value_impl]
+impl WorkerAssetReferenceVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: ResolveOriginVc,
+        request: RequestVc,
+        ty: Value<WorkerType>,
+        rendering: RenderingVc,
+        ast_path: AstPathVc,
+        issue_source: IssueSourceVc,
+        in_try: bool,
+    ) -> Self {
+        WorkerAssetReference {
+            origin,
+            request,
+            ty: ty.into_value(),
+            rendering,
+            ast_path,
+            issue_source,
+            in_try,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AssetReference for WorkerAssetReference {
+    #[turbo_tasks::function]
+    async fn resolve_reference(&self) -> Result<ResolveResultVc> {
+        let ty = self.ty;
+        Ok(esm_resolve(
+            self.origin,
+            self.request,
+            Value::new(EcmaScriptModulesReferenceSubType::Undefined),
+            OptionIssueSourceVc::some(self.issue_source),
+            try_to_severity(self.in_try),
+        )
+        .await?
+        .map(
+            |asset| async move {
+                Ok(match EvaluatableAssetVc::resolve_from(asset).await? {
+                    Some(entry) => WorkerModuleVc::new(entry, Value::new(ty)).into(),
+                    None => asset,
+                })
+            },
+            |r| async move { Ok(r) },
+        )
+        .await?
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for WorkerAssetReference {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<StringVc> {
+        Ok(StringVc::cell(format!(
+            "{} {}",
+            self.ty.description(),
+            self.request.to_string().await?,
+        )))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for WorkerAssetReference {
+    #[turbo_tasks::function]
+    fn chunking_type(&self) -> ChunkingTypeOptionVc {
+        ChunkingTypeOptionVc::cell(Some(ChunkingType::PlacedOrParallel))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl CodeGenerateable for WorkerAssetReference {
+    #[turbo_tasks::function]
+    async fn code_generation(
+        self_vc: WorkerAssetReferenceVc,
+        context: EcmascriptChunkingContextVc,
+    ) -> Result<CodeGenerationVc> {
+        let this = self_vc.await?;
+        let mut visitors = vec![];
+
+        let referenced_asset =
+            ReferencedAssetVc::from_resolve_result(self_vc.resolve_reference(), this.request)
+                .await?;
+
+        if let ReferencedAsset::Some(asset) = &*referenced_asset {
+            let rewrite = url_rewrite(this.rendering, this.origin).await?;
+            let id = asset.as_chunk_item(context).id().await?;
+            let ast_path = this.ast_path.await?;
+
+            visitors.push(
+                create_visitor!(ast_path, visit_mut_expr(expr: &mut Expr) {
+                    let args = match expr {
+                        Expr::New(NewExpr { args: Some(args), .. }) => args,
+                        Expr::Call(CallExpr { args, .. }) => args,
+                        _ => return,
+                    };
+
+                    // We rewrite the arguments of the `new URL()` to point to the worker script,
+                    // which is exported by the chunk item of the worker.
+                    if let Some(ExprOrSpread { expr: box Expr::New(NewExpr { args: Some(url_args), .. }), spread: None }) = args.get_mut(0) {
+                        if let Some(ExprOrSpread { box expr, spread: None }) = url_args.get_mut(0) {
+                            *expr = quote!(
+                                "__turbopack_require__($id)" as Expr,
+                                id: Expr = module_id_to_lit(&id),
+                            );
+                        }
+
+                        // Workers are only created in browsers, where the worker script is
+                        // served.
+                        if let Some(UrlRewrite::Base(base)) = &rewrite {
+                            if let Some(ExprOrSpread { box expr, spread: None }) = url_args.get_mut(1) {
+                                *expr = base.clone();
+                            }
+                        }
+                    }
+
+                    // The worker script loads the chunks with `importScripts`, which is not
+                    // available in module workers.
+                    if let Some(ExprOrSpread { expr: box Expr::Object(options), spread: None }) = args.get_mut(1) {
+                        options.props.retain(|prop| {
+                            !matches!(
+                                prop,
+                                PropOrSpread::Prop(box Prop::KeyValue(kv))
+                                    if matches!(&kv.key, PropName::Ident(ident) if &*ident.sym == "type")
+                                        || matches!(&kv.key, PropName::Str(str) if &*str.value == "type")
+                            )
+                        });
+                    }
+                }),
+            );
+        }
+
+        Ok(CodeGeneration { visitors }.into())
+    }
+}
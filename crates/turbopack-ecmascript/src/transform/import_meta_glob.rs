@@ -0,0 +1,401 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use swc_core::{
+    common::{util::take::Take, DUMMY_SP},
+    ecma::{
+        ast::{
+            CallExpr, Callee, Expr, ExprOrSpread, Ident, ImportDecl, ImportDefaultSpecifier,
+            ImportNamedSpecifier, ImportSpecifier, ImportStarAsSpecifier, KeyValueProp, Lit,
+            MemberExpr, MemberProp, MetaPropExpr, MetaPropKind, Module, ModuleDecl,
+            ModuleExportName, ModuleItem, ObjectLit, Program, Prop, PropName, PropOrSpread, Script,
+        },
+        visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+    },
+    quote,
+};
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_fs::{glob::GlobVc, DirectoryEntry, FileSystemPathVc};
+use turbopack_core::issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc};
+
+use super::TransformContext;
+
+/// A `import.meta.glob(patterns, options)` call with constant arguments.
+struct GlobCall {
+    patterns: Vec<String>,
+    eager: bool,
+    import: Option<String>,
+}
+
+/// Replaces `import.meta.glob` calls with an object which maps every matched
+/// file (relative to the importing module) to its module.
+///
+/// In the default lazy mode the values are thunks calling `import()`. With
+/// `eager: true` the matched files are imported statically and the values are
+/// the module namespaces. `import: "name"` selects a single export instead.
+pub(super) async fn import_meta_glob(
+    program: &mut Program,
+    ctx: &TransformContext<'_>,
+) -> Result<()> {
+    let mut collector = GlobCallCollector::default();
+    program.visit_with(&mut collector);
+    if collector.calls.is_empty() {
+        return Ok(());
+    }
+
+    let origin_dir = ctx.file_path.parent();
+    let origin_dir_value = origin_dir.await?;
+    let mut replacements = Vec::with_capacity(collector.calls.len());
+    for call in collector.calls {
+        let call = match call {
+            Ok(call) => call,
+            Err(message) => {
+                emit_issue(ctx, message);
+                replacements.push(None);
+                continue;
+            }
+        };
+
+        let mut included = Vec::new();
+        let mut excluded = Vec::new();
+        let mut unsupported = None;
+        for pattern in call.patterns.iter() {
+            let (negative, pattern) = match pattern.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern.as_str()),
+            };
+            let Some((dir, glob)) = split_pattern(origin_dir, pattern) else {
+                unsupported = Some(pattern.to_string());
+                break;
+            };
+            let glob = GlobVc::new(glob);
+            if negative {
+                excluded.push((dir.await?, glob.await?));
+            } else {
+                included.push(dir.read_glob(glob, false));
+            }
+        }
+        if let Some(pattern) = unsupported {
+            emit_issue(
+                ctx,
+                format!(
+                    "import.meta.glob(\"{pattern}\") is not supported, patterns must start with \
+                     \"./\" or \"../\""
+                ),
+            );
+            replacements.push(None);
+            continue;
+        }
+
+        let mut keys = BTreeSet::new();
+        while let Some(result) = included.pop() {
+            let result = result.await?;
+            included.extend(result.inner.values().copied());
+            'files: for entry in result.results.values() {
+                let &DirectoryEntry::File(path) = entry else {
+                    continue;
+                };
+                let path = path.await?;
+                if path.path == ctx.file_path_str {
+                    continue;
+                }
+                for (dir, glob) in excluded.iter() {
+                    if let Some(relative) = dir.get_path_to(&path) {
+                        if glob.execute(relative) {
+                            continue 'files;
+                        }
+                    }
+                }
+                if let Some(key) = origin_dir_value.get_relative_path_to(&path) {
+                    keys.insert(key);
+                }
+            }
+        }
+
+        replacements.push(Some((call, keys)));
+    }
+
+    let mut replacer = GlobCallReplacer {
+        replacements: replacements.into_iter(),
+        imports: Vec::new(),
+        ctx,
+    };
+    program.visit_mut_with(&mut replacer);
+
+    let imports = replacer.imports;
+    if !imports.is_empty() {
+        if let Program::Script(Script {
+            span,
+            body,
+            shebang,
+        }) = program
+        {
+            *program = Program::Module(Module {
+                span: *span,
+                body: body.take().into_iter().map(ModuleItem::Stmt).collect(),
+                shebang: shebang.take(),
+            });
+        }
+        if let Program::Module(module) = program {
+            module.body.splice(0..0, imports);
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits a relative glob pattern into the directory it's relative to and the
+/// remaining glob, e.g. `../pages/**/*.js` into the parent directory and
+/// `pages/**/*.js`.
+fn split_pattern(origin_dir: FileSystemPathVc, pattern: &str) -> Option<(FileSystemPathVc, &str)> {
+    if !pattern.starts_with("./") && !pattern.starts_with("../") {
+        return None;
+    }
+    let mut dir = origin_dir;
+    let mut rest = pattern;
+    loop {
+        if let Some(stripped) = rest.strip_prefix("./") {
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("../") {
+            dir = dir.parent();
+            rest = stripped;
+        } else {
+            return Some((dir, rest));
+        }
+    }
+}
+
+fn is_import_meta_glob(call: &CallExpr) -> bool {
+    matches!(
+        &call.callee,
+        Callee::Expr(box Expr::Member(MemberExpr {
+            obj: box Expr::MetaProp(MetaPropExpr {
+                kind: MetaPropKind::ImportMeta,
+                ..
+            }),
+            prop: MemberProp::Ident(Ident { sym, .. }),
+            ..
+        })) if &**sym == "glob"
+    )
+}
+
+fn parse_glob_call(call: &CallExpr) -> Result<GlobCall, String> {
+    let (patterns, options) = match &call.args[..] {
+        [patterns] => (patterns, None),
+        [patterns, options] => (patterns, Some(options)),
+        _ => return Err("import.meta.glob() expects one or two arguments".to_string()),
+    };
+    if patterns.spread.is_some() || options.map_or(false, |options| options.spread.is_some()) {
+        return Err("import.meta.glob() doesn't support spread arguments".to_string());
+    }
+
+    let patterns = match &*patterns.expr {
+        Expr::Lit(Lit::Str(pattern)) => vec![pattern.value.to_string()],
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .map(|elem| match elem {
+                Some(ExprOrSpread {
+                    spread: None,
+                    expr: box Expr::Lit(Lit::Str(pattern)),
+                }) => Ok(pattern.value.to_string()),
+                _ => Err("import.meta.glob() patterns must be string literals".to_string()),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => {
+            return Err(
+                "import.meta.glob() expects a string literal or an array of string literals"
+                    .to_string(),
+            )
+        }
+    };
+
+    let mut glob_call = GlobCall {
+        patterns,
+        eager: false,
+        import: None,
+    };
+    let Some(options) = options else {
+        return Ok(glob_call);
+    };
+    let Expr::Object(ObjectLit { props, .. }) = &*options.expr else {
+        return Err("import.meta.glob() options must be an object literal".to_string());
+    };
+    for prop in props {
+        let PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp { key, value })) = prop else {
+            return Err("import.meta.glob() options must be constant properties".to_string());
+        };
+        let key = match key {
+            PropName::Ident(ident) => &*ident.sym,
+            PropName::Str(str) => &*str.value,
+            _ => return Err("import.meta.glob() options must have constant keys".to_string()),
+        };
+        match (key, &**value) {
+            ("eager", Expr::Lit(Lit::Bool(eager))) => glob_call.eager = eager.value,
+            ("import", Expr::Lit(Lit::Str(import))) => {
+                glob_call.import = Some(import.value.to_string())
+            }
+            ("eager" | "import", _) => {
+                return Err(format!(
+                    "import.meta.glob() option \"{key}\" must be a constant value"
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "import.meta.glob() option \"{key}\" is not supported"
+                ))
+            }
+        }
+    }
+    Ok(glob_call)
+}
+
+fn emit_issue(ctx: &TransformContext<'_>, message: String) {
+    ImportMetaGlobIssue {
+        context: ctx.file_path,
+        message: StringVc::cell(message),
+    }
+    .cell()
+    .as_issue()
+    .emit();
+}
+
+/// Collects all `import.meta.glob` calls in the order they are visited.
+#[derive(Default)]
+struct GlobCallCollector {
+    calls: Vec<Result<GlobCall, String>>,
+}
+
+impl Visit for GlobCallCollector {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Call(call) if is_import_meta_glob(call) => {
+                self.calls.push(parse_glob_call(call));
+            }
+            _ => expr.visit_children_with(self),
+        }
+    }
+}
+
+/// Replaces the `import.meta.glob` calls visited by [GlobCallCollector], in the
+/// same order, with the object of matched modules.
+struct GlobCallReplacer<'a, 'b, I> {
+    replacements: I,
+    imports: Vec<ModuleItem>,
+    ctx: &'a TransformContext<'b>,
+}
+
+impl<'a, 'b, I> GlobCallReplacer<'a, 'b, I> {
+    fn import_binding(&mut self, request: &str, import: Option<&str>) -> Ident {
+        let local = Ident::new(
+            format!("__turbopack_import_meta_glob_{}__", self.imports.len()).into(),
+            DUMMY_SP.apply_mark(self.ctx.top_level_mark),
+        );
+        let specifier = match import {
+            None => ImportSpecifier::Namespace(ImportStarAsSpecifier {
+                span: DUMMY_SP,
+                local: local.clone(),
+            }),
+            Some("default") => ImportSpecifier::Default(ImportDefaultSpecifier {
+                span: DUMMY_SP,
+                local: local.clone(),
+            }),
+            Some(import) => ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local: local.clone(),
+                imported: Some(ModuleExportName::Str(import.into())),
+                is_type_only: false,
+            }),
+        };
+        self.imports
+            .push(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                span: DUMMY_SP,
+                specifiers: vec![specifier],
+                src: Box::new(request.into()),
+                type_only: false,
+                asserts: None,
+            })));
+        local
+    }
+}
+
+impl<'a, 'b, I> VisitMut for GlobCallReplacer<'a, 'b, I>
+where
+    I: Iterator<Item = Option<(GlobCall, BTreeSet<String>)>>,
+{
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        let Expr::Call(call) = expr else {
+            expr.visit_mut_children_with(self);
+            return;
+        };
+        if !is_import_meta_glob(call) {
+            expr.visit_mut_children_with(self);
+            return;
+        }
+        let Some(Some((call, keys))) = self.replacements.next() else {
+            return;
+        };
+
+        let props = keys
+            .into_iter()
+            .map(|key| {
+                let value = if call.eager {
+                    Expr::Ident(self.import_binding(&key, call.import.as_deref()))
+                } else if let Some(import) = &call.import {
+                    quote!(
+                        "() => import($request).then((m) => m[$import])" as Expr,
+                        request: Expr = key.clone().into(),
+                        import: Expr = import.clone().into()
+                    )
+                } else {
+                    quote!(
+                        "() => import($request)" as Expr,
+                        request: Expr = key.clone().into()
+                    )
+                };
+                PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                    key: PropName::Str(key.into()),
+                    value: Box::new(value),
+                })))
+            })
+            .collect();
+        *expr = Expr::Object(ObjectLit {
+            span: DUMMY_SP,
+            props,
+        });
+    }
+}
+
+#[turbo_tasks::value(shared)]
+pub struct ImportMetaGlobIssue {
+    pub context: FileSystemPathVc,
+    pub message: StringVc,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for ImportMetaGlobIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("unsupported".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("Unable to process import.meta.glob".into())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        self.message
+    }
+}
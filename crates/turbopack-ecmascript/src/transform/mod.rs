@@ -1,3 +1,5 @@
+mod import_meta_glob;
+
 use std::{fmt::Debug, hash::Hash, sync::Arc};
 
 use anyhow::Result;
@@ -22,10 +24,14 @@ use turbopack_core::{
     issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
 };
 
+use self::import_meta_glob::import_meta_glob;
+
 #[turbo_tasks::value(serialization = "auto_for_input")]
 #[derive(Debug, Clone, PartialOrd, Ord, Hash)]
 pub enum EcmascriptInputTransform {
     CommonJs,
+    /// Replaces `import.meta.glob(...)` with the matched modules.
+    ImportMetaGlob,
     Plugin(TransformPluginVc),
     PresetEnv(EnvironmentVc),
     React {
@@ -43,6 +49,8 @@ pub enum EcmascriptInputTransform {
     TypeScript {
         #[serde(default)]
         use_define_for_class_fields: bool,
+        #[serde(default)]
+        treat_const_enum_as_enum: bool,
     },
     Decorators {
         #[serde(default)]
@@ -238,31 +246,54 @@ impl EcmascriptInputTransform {
             EcmascriptInputTransform::TypeScript {
                 // TODO(WEB-1213)
                 use_define_for_class_fields: _use_define_for_class_fields,
+                treat_const_enum_as_enum,
             } => {
-                use swc_core::ecma::transforms::typescript::strip_with_config;
-                let config = Default::default();
+                use swc_core::ecma::transforms::typescript::{
+                    strip_with_config, Config, TsEnumConfig,
+                };
+                // Namespaces are always emitted, `const enum`s are inlined unless they
+                // are treated as regular enums
+                let config = Config {
+                    ts_enum_config: TsEnumConfig {
+                        treat_const_enum_as_enum: *treat_const_enum_as_enum,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
                 program.visit_mut_with(&mut strip_with_config(config, top_level_mark));
             }
             EcmascriptInputTransform::Decorators {
                 is_legacy,
-                is_ecma: _,
+                is_ecma,
                 emit_decorators_metadata,
                 // TODO(WEB-1213)
                 use_define_for_class_fields: _use_define_for_class_fields,
             } => {
-                use swc_core::ecma::transforms::proposal::decorators::{decorators, Config};
-                let config = Config {
-                    legacy: *is_legacy,
-                    emit_metadata: *emit_decorators_metadata,
-                    ..Default::default()
+                use swc_core::ecma::transforms::proposal::{
+                    decorator_2022_03::decorator_2022_03,
+                    decorators::{decorators, Config},
                 };
 
                 let p = std::mem::replace(program, Program::Module(Module::dummy()));
-                *program = p.fold_with(&mut chain!(
-                    decorators(config),
-                    inject_helpers(unresolved_mark)
-                ));
+                *program = if *is_ecma {
+                    // The standard decorators proposal, as implemented by TypeScript 5
+                    p.fold_with(&mut chain!(
+                        decorator_2022_03(),
+                        inject_helpers(unresolved_mark)
+                    ))
+                } else {
+                    let config = Config {
+                        legacy: *is_legacy,
+                        emit_metadata: *emit_decorators_metadata,
+                        ..Default::default()
+                    };
+                    p.fold_with(&mut chain!(
+                        decorators(config),
+                        inject_helpers(unresolved_mark)
+                    ))
+                };
             }
+            EcmascriptInputTransform::ImportMetaGlob => import_meta_glob(program, ctx).await?,
             EcmascriptInputTransform::Plugin(transform) => {
                 transform.await?.transform(program, ctx).await?
             }
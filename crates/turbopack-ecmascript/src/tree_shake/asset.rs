@@ -1,5 +1,8 @@
 use anyhow::{bail, Context, Result};
-use turbo_tasks::{primitives::StringVc, Value};
+use turbo_tasks::{
+    primitives::{BoolVc, StringVc},
+    Value,
+};
 use turbopack_core::{
     asset::{Asset, AssetContentVc, AssetVc},
     chunk::{
@@ -127,6 +130,11 @@ impl EcmascriptChunkPlaceable for EcmascriptModulePartAsset {
     async fn get_exports(self_vc: EcmascriptModuleAssetVc) -> Result<EcmascriptExportsVc> {
         Ok(self_vc.analyze().await?.exports)
     }
+
+    #[turbo_tasks::function]
+    async fn is_self_async(self_vc: EcmascriptModulePartAssetVc) -> Result<BoolVc> {
+        Ok(BoolVc::cell(self_vc.analyze().await?.has_top_level_await))
+    }
 }
 
 #[turbo_tasks::value_impl]
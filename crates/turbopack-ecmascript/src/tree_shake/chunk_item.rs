@@ -10,9 +10,10 @@ use turbopack_core::{
 use super::{asset::EcmascriptModulePartAssetVc, part_of_module, split_module};
 use crate::{
     chunk::{
-        EcmascriptChunkItem, EcmascriptChunkItemContentVc, EcmascriptChunkItemVc,
-        EcmascriptChunkingContextVc,
+        AsyncModuleInfoVc, EcmascriptChunkItem, EcmascriptChunkItemContentVc,
+        EcmascriptChunkItemVc, EcmascriptChunkingContextVc,
     },
+    export_usage::UsedExports,
     EcmascriptModuleContentVc,
 };
 
@@ -33,11 +34,37 @@ impl EcmascriptChunkItem for EcmascriptModulePartChunkItem {
     }
 
     #[turbo_tasks::function]
-    async fn content_with_availability_info(
+    fn content_with_availability_info(
         self_vc: EcmascriptModulePartChunkItemVc,
         availability_info: Value<AvailabilityInfo>,
+    ) -> EcmascriptChunkItemContentVc {
+        self_vc.module_content(availability_info, None)
+    }
+
+    #[turbo_tasks::function]
+    fn content_with_async_module_info(
+        self_vc: EcmascriptModulePartChunkItemVc,
+        availability_info: Value<AvailabilityInfo>,
+        async_module_info: AsyncModuleInfoVc,
+    ) -> EcmascriptChunkItemContentVc {
+        self_vc.module_content(availability_info, Some(async_module_info))
+    }
+
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> EcmascriptChunkingContextVc {
+        self.context
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptModulePartChunkItemVc {
+    #[turbo_tasks::function]
+    async fn module_content(
+        self,
+        availability_info: Value<AvailabilityInfo>,
+        async_module_info: Option<AsyncModuleInfoVc>,
     ) -> Result<EcmascriptChunkItemContentVc> {
-        let this = self_vc.await?;
+        let this = self.await?;
         let availability_info = if *this.module.analyze().needs_availability_info().await? {
             availability_info
         } else {
@@ -54,15 +81,12 @@ impl EcmascriptChunkItem for EcmascriptModulePartChunkItem {
             this.context,
             this.module.analyze(),
             availability_info,
+            UsedExports::All.cell(),
+            async_module_info,
         );
 
         Ok(EcmascriptChunkItemContentVc::new(content, this.context))
     }
-
-    #[turbo_tasks::function]
-    fn chunking_context(&self) -> EcmascriptChunkingContextVc {
-        self.context
-    }
 }
 
 #[turbo_tasks::value_impl]
@@ -314,7 +314,7 @@ pub(super) async fn split(path: FileSystemPathVc, parsed: ParseResultVc) -> Resu
             eval_context,
             source_map,
             globals,
-            ..
+            input_source_map,
         } => {
             let (mut dep_graph, items) = Analyzer::analyze(module);
 
@@ -338,6 +338,7 @@ pub(super) async fn split(path: FileSystemPathVc, parsed: ParseResultVc) -> Resu
                         comments: comments.clone(),
                         source_map: source_map.clone(),
                         eval_context,
+                        input_source_map: *input_source_map,
                     })
                 })
                 .collect();
@@ -32,6 +32,10 @@ use turbopack_core::{
     source::{option_asset_to_source, OptionSourceVc, SourceVc},
 };
 
+/// The TypeScript version used to select the `typesVersions` entry of a
+/// package.
+const TYPESCRIPT_VERSION: &str = "5.0.0";
+
 #[turbo_tasks::value(shared)]
 pub struct TsConfigIssue {
     pub severity: IssueSeverityVc,
@@ -201,6 +205,9 @@ pub async fn read_from_tsconfigs<T>(
 pub struct TsConfigResolveOptions {
     base_url: Option<FileSystemPathVc>,
     import_map: Option<ImportMapVc>,
+    /// The tsconfig.json files the options were read from, including extended
+    /// configs.
+    tsconfig_paths: Vec<FileSystemPathVc>,
 }
 
 impl Default for TsConfigResolveOptionsVc {
@@ -287,9 +294,15 @@ pub async fn tsconfig_resolve_options(
         None
     };
 
+    let tsconfig_paths = configs
+        .iter()
+        .map(|(_, source)| source.ident().path())
+        .collect();
+
     Ok(TsConfigResolveOptions {
         base_url,
         import_map,
+        tsconfig_paths,
     }
     .cell())
 }
@@ -324,6 +337,9 @@ pub async fn apply_tsconfig_resolve_options(
                 .unwrap_or(tsconfig_import_map),
         );
     }
+    resolve_options
+        .affecting_paths
+        .extend(tsconfig_resolve_options.tsconfig_paths.iter().copied());
     Ok(resolve_options.cell())
 }
 
@@ -414,6 +430,8 @@ async fn apply_typescript_types_options(
 ) -> Result<ResolveOptionsVc> {
     let mut resolve_options = resolve_options.await?.clone_value();
     resolve_options.extensions = vec![".tsx".to_string(), ".ts".to_string(), ".d.ts".to_string()];
+    resolve_options.types_only = None;
+    resolve_options.enforce_extension = None;
     resolve_options.into_package = resolve_options
         .into_package
         .drain(..)
@@ -433,6 +451,11 @@ async fn apply_typescript_types_options(
             }
         })
         .collect();
+    resolve_options
+        .into_package
+        .push(ResolveIntoPackage::TypesVersions(
+            TYPESCRIPT_VERSION.to_string(),
+        ));
     resolve_options
         .into_package
         .push(ResolveIntoPackage::MainField("types".to_string()));
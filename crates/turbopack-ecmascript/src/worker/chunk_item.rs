@@ -0,0 +1,82 @@
+use anyhow::Result;
+use turbo_tasks::{primitives::StringVc, Value, ValueToString};
+use turbopack_core::{
+    asset::Asset,
+    chunk::{ChunkItem, ChunkItemVc},
+    ident::AssetIdentVc,
+    reference::{AssetReferencesVc, SingleAssetReferenceVc},
+};
+
+use super::{module::WorkerModuleVc, script::WorkerScriptVc};
+use crate::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
+        EcmascriptChunkItemVc, EcmascriptChunkingContextVc,
+    },
+    utils::StringifyJs,
+};
+
+/// Exports the URL of the worker script, which is served from the root of the
+/// output file system, like static assets.
+#[turbo_tasks::value(shared)]
+pub(super) struct WorkerChunkItem {
+    pub context: EcmascriptChunkingContextVc,
+    pub module: WorkerModuleVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WorkerChunkItemVc {
+    #[turbo_tasks::function]
+    async fn script(self) -> Result<WorkerScriptVc> {
+        let this = self.await?;
+        let module = this.module.await?;
+        Ok(WorkerScriptVc::new(
+            module.entry,
+            this.context.into(),
+            Value::new(module.ty),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for WorkerChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> EcmascriptChunkingContextVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    async fn content(self_vc: WorkerChunkItemVc) -> Result<EcmascriptChunkItemContentVc> {
+        let script_path = self_vc.script().ident().path().await?;
+        Ok(EcmascriptChunkItemContent {
+            inner_code: format!(
+                "__turbopack_export_value__({path});",
+                path = StringifyJs(&format_args!("/{}", &*script_path))
+            )
+            .into(),
+            ..Default::default()
+        }
+        .into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for WorkerChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> AssetIdentVc {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self_vc: WorkerChunkItemVc) -> Result<AssetReferencesVc> {
+        let script = self_vc.script();
+        Ok(AssetReferencesVc::cell(vec![SingleAssetReferenceVc::new(
+            script.into(),
+            StringVc::cell(format!(
+                "worker script {}",
+                script.ident().to_string().await?
+            )),
+        )
+        .into()]))
+    }
+}
@@ -0,0 +1,33 @@
+pub(crate) mod chunk_item;
+pub(crate) mod module;
+pub(crate) mod script;
+
+/// The kind of a worker whose script is referenced with
+/// `new URL(..., import.meta.url)`.
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum WorkerType {
+    /// `new Worker(new URL(...))`
+    Worker,
+    /// `new SharedWorker(new URL(...))`
+    SharedWorker,
+    /// `worklet.addModule(new URL(...))`, e.g. `CSS.paintWorklet` or
+    /// `audioContext.audioWorklet`.
+    Worklet,
+}
+
+impl WorkerType {
+    /// Worklets can only load ES modules, while workers are created as
+    /// classic workers which load the chunks with `importScripts`.
+    pub fn is_module(&self) -> bool {
+        matches!(self, WorkerType::Worklet)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            WorkerType::Worker => "worker",
+            WorkerType::SharedWorker => "shared worker",
+            WorkerType::Worklet => "worklet",
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::{bail, Result};
+use turbo_tasks::{primitives::StringVc, Value};
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc},
+    chunk::{
+        availability_info::AvailabilityInfo, ChunkVc, ChunkableModule, ChunkableModuleVc,
+        ChunkingContextVc, EvaluatableAssetVc,
+    },
+    ident::AssetIdentVc,
+    module::{Module, ModuleVc},
+    reference::{AssetReferencesVc, SingleAssetReferenceVc},
+};
+
+use super::{chunk_item::WorkerChunkItem, WorkerType};
+use crate::chunk::{
+    EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkVc,
+    EcmascriptChunkingContextVc, EcmascriptExports, EcmascriptExportsVc,
+};
+
+#[turbo_tasks::function]
+fn modifier(ty: Value<WorkerType>) -> StringVc {
+    StringVc::cell(ty.description().to_string())
+}
+
+#[turbo_tasks::function]
+fn entry_reference_description() -> StringVc {
+    StringVc::cell("worker entry".to_string())
+}
+
+/// Placed into the chunk of the module which creates the worker, in place of
+/// the worker's entry. It exports the URL of the worker script, which loads
+/// the separate chunk group of the entry and evaluates it.
+#[turbo_tasks::value(shared)]
+pub struct WorkerModule {
+    pub entry: EvaluatableAssetVc,
+    pub ty: WorkerType,
+}
+
+#[turbo_tasks::value_impl]
+impl WorkerModuleVc {
+    #[turbo_tasks::function]
+    pub fn new(entry: EvaluatableAssetVc, ty: Value<WorkerType>) -> Self {
+        Self::cell(WorkerModule {
+            entry,
+            ty: ty.into_value(),
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WorkerModule {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        self.entry
+            .ident()
+            .with_modifier(modifier(Value::new(self.ty)))
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> Result<AssetContentVc> {
+        bail!("WorkerModule is only placed into chunks and has no content of its own")
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> AssetReferencesVc {
+        AssetReferencesVc::cell(vec![SingleAssetReferenceVc::new(
+            self.entry.into(),
+            entry_reference_description(),
+        )
+        .into()])
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Module for WorkerModule {}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModule for WorkerModule {
+    #[turbo_tasks::function]
+    fn as_chunk(
+        self_vc: WorkerModuleVc,
+        context: ChunkingContextVc,
+        availability_info: Value<AvailabilityInfo>,
+    ) -> ChunkVc {
+        EcmascriptChunkVc::new(context, self_vc.into(), availability_info).into()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for WorkerModule {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self_vc: WorkerModuleVc,
+        context: EcmascriptChunkingContextVc,
+    ) -> EcmascriptChunkItemVc {
+        WorkerChunkItem {
+            context,
+            module: self_vc,
+        }
+        .cell()
+        .into()
+    }
+
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> EcmascriptExportsVc {
+        EcmascriptExports::Value.cell()
+    }
+}
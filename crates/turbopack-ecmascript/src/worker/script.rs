@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use turbo_tasks::{primitives::StringVc, TryJoinIterExt, Value};
+use turbo_tasks_fs::File;
+use turbopack_core::{
+    asset::{Asset, AssetContentVc, AssetVc, AssetsVc},
+    chunk::{
+        ChunkableModule, ChunkingContext, ChunkingContextVc, EvaluatableAssetVc,
+        EvaluatableAssetsVc,
+    },
+    ident::AssetIdentVc,
+    reference::{AssetReferencesVc, SingleAssetReferenceVc},
+};
+
+use super::WorkerType;
+use crate::utils::StringifyJs;
+
+#[turbo_tasks::function]
+fn modifier(ty: Value<WorkerType>) -> StringVc {
+    StringVc::cell(format!("{} script", ty.description()))
+}
+
+#[turbo_tasks::function]
+fn chunk_reference_description() -> StringVc {
+    StringVc::cell("worker chunk".to_string())
+}
+
+/// The script which a worker is created with. It loads all chunks of the
+/// evaluated chunk group of the worker's entry, in order, which evaluates the
+/// entry once the chunk containing the runtime has been loaded.
+///
+/// Chunks are loaded with `importScripts` in classic workers and with static
+/// `import` statements in worklets, which only support ES modules.
+#[turbo_tasks::value(shared)]
+pub struct WorkerScript {
+    pub entry: EvaluatableAssetVc,
+    pub chunking_context: ChunkingContextVc,
+    pub ty: WorkerType,
+}
+
+#[turbo_tasks::value_impl]
+impl WorkerScriptVc {
+    #[turbo_tasks::function]
+    pub fn new(
+        entry: EvaluatableAssetVc,
+        chunking_context: ChunkingContextVc,
+        ty: Value<WorkerType>,
+    ) -> Self {
+        Self::cell(WorkerScript {
+            entry,
+            chunking_context,
+            ty: ty.into_value(),
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn chunks(self) -> Result<AssetsVc> {
+        let this = self.await?;
+        Ok(this.chunking_context.evaluated_chunk_group(
+            this.entry.as_root_chunk(this.chunking_context),
+            EvaluatableAssetsVc::one(this.entry),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WorkerScript {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        AssetIdentVc::from_path(
+            self.chunking_context.chunk_path(
+                self.entry
+                    .ident()
+                    .with_modifier(modifier(Value::new(self.ty))),
+                ".js",
+            ),
+        )
+    }
+
+    #[turbo_tasks::function]
+    async fn content(self_vc: WorkerScriptVc) -> Result<AssetContentVc> {
+        let this = self_vc.await?;
+        let script_dir = self_vc.ident().path().parent().await?;
+        let chunk_paths = self_vc
+            .chunks()
+            .await?
+            .iter()
+            .map(|chunk| chunk.ident().path())
+            .try_join()
+            .await?;
+        let chunk_urls = chunk_paths
+            .iter()
+            .filter(|path| path.path.ends_with(".js"))
+            .filter_map(|path| script_dir.get_relative_path_to(path))
+            .collect::<Vec<_>>();
+
+        let mut code = String::new();
+        if this.ty.is_module() {
+            for url in &chunk_urls {
+                writeln!(code, "import {};", StringifyJs(url))?;
+            }
+        } else {
+            writeln!(code, "importScripts(")?;
+            for url in &chunk_urls {
+                writeln!(code, "    {},", StringifyJs(url))?;
+            }
+            writeln!(code, ");")?;
+        }
+
+        Ok(File::from(code).into())
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self_vc: WorkerScriptVc) -> Result<AssetReferencesVc> {
+        Ok(AssetReferencesVc::cell(
+            self_vc
+                .chunks()
+                .await?
+                .iter()
+                .copied()
+                .map(|chunk| {
+                    SingleAssetReferenceVc::new(chunk, chunk_reference_description()).into()
+                })
+                .collect(),
+        ))
+    }
+}
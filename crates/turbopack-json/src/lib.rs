@@ -3,11 +3,13 @@
 //! JSON assets are parsed to ensure they contain valid JSON.
 //!
 //! When imported from ES modules, they produce a module that exports the
-//! JSON value as an object.
+//! JSON value as an object. The top-level keys of JSON objects are available
+//! as named exports, and keys which are never imported are dropped when the
+//! chunking context tracks the usage of exports.
 
 #![feature(min_specialization)]
 
-use std::fmt::Write;
+use std::{collections::BTreeMap, fmt::Write};
 
 use anyhow::{bail, Error, Result};
 use turbo_tasks::{primitives::StringVc, Value, ValueToString};
@@ -23,10 +25,15 @@ use turbopack_core::{
     reference::AssetReferencesVc,
     source::SourceVc,
 };
-use turbopack_ecmascript::chunk::{
-    EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
-    EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkVc,
-    EcmascriptChunkingContextVc, EcmascriptExports, EcmascriptExportsVc,
+use turbopack_ecmascript::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkItemContentVc,
+        EcmascriptChunkItemVc, EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc,
+        EcmascriptChunkVc, EcmascriptChunkingContext, EcmascriptChunkingContextVc,
+        EcmascriptExports, EcmascriptExportsVc,
+    },
+    export_usage::UsedExports,
+    EsmExport, EsmExports,
 };
 
 #[turbo_tasks::function]
@@ -95,8 +102,29 @@ impl EcmascriptChunkPlaceable for JsonModuleAsset {
     }
 
     #[turbo_tasks::function]
-    fn get_exports(&self) -> EcmascriptExportsVc {
-        EcmascriptExports::Value.cell()
+    async fn get_exports(&self) -> Result<EcmascriptExportsVc> {
+        // Invalid JSON is reported when generating the code of the module.
+        let FileJsonContent::Content(serde_json::Value::Object(object)) =
+            &*self.source.content().parse_json().await?
+        else {
+            return Ok(EcmascriptExports::Value.cell());
+        };
+        let exports = object
+            .keys()
+            .map(|key| (key.clone(), EsmExport::LocalBinding(key.clone())))
+            .chain([(
+                "default".to_string(),
+                EsmExport::LocalBinding("default".to_string()),
+            )])
+            .collect::<BTreeMap<_, _>>();
+        Ok(EcmascriptExports::EsmExports(
+            EsmExports {
+                exports,
+                star_exports: Vec::new(),
+            }
+            .cell(),
+        )
+        .cell())
     }
 }
 
@@ -134,6 +162,29 @@ impl EcmascriptChunkItem for JsonChunkItem {
         let data = content.parse_json().await?;
         match &*data {
             FileJsonContent::Content(data) => {
+                let used_exports = match *self.context.export_usage().await? {
+                    Some(export_usage) => export_usage
+                        .used_exports(self.module.into())
+                        .await?
+                        .clone_value(),
+                    None => UsedExports::All,
+                };
+                // The whole value is needed for the default export, otherwise
+                // only the keys which are imported by name are kept.
+                let data = match (data, used_exports) {
+                    (serde_json::Value::Object(object), UsedExports::Exports(used))
+                        if !used.contains("default") =>
+                    {
+                        serde_json::Value::Object(
+                            object
+                                .iter()
+                                .filter(|(key, _)| used.contains(*key))
+                                .map(|(key, value)| (key.clone(), value.clone()))
+                                .collect(),
+                        )
+                    }
+                    _ => data.clone(),
+                };
                 let js_str_content = serde_json::to_string(&data.to_string())?;
                 let inner_code =
                     format!("__turbopack_export_value__(JSON.parse({js_str_content}));");
@@ -0,0 +1,209 @@
+#![feature(min_specialization)]
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use turbo_tasks::{primitives::StringVc, CompletionVc};
+use turbo_tasks_fs::{FileContent, FileSystemPathVc};
+use turbopack_core::{
+    issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
+    package_json::read_package_json,
+};
+use turborepo_lockfiles::{Lockfile, NpmLockfile, PnpmLockfile, Yarn1Lockfile};
+
+pub fn register() {
+    turbo_tasks::register();
+    turbo_tasks_fs::register();
+    turbopack_core::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
+
+/// The dependency fields of a package.json which are compared against the
+/// lockfile.
+const DEPENDENCY_FIELDS: [&str; 3] = ["dependencies", "devDependencies", "optionalDependencies"];
+
+/// A package which is installed in node_modules in a different state than
+/// the lockfile describes.
+#[derive(Debug)]
+enum LockfileDrift {
+    /// The package is locked, but not installed at all.
+    Missing { name: String, locked: String },
+    /// The package is installed with a different version than locked.
+    VersionMismatch {
+        name: String,
+        locked: String,
+        installed: String,
+    },
+}
+
+/// Verifies that the packages installed in `node_modules` match the lockfile
+/// of the project at `project_path`. Supports npm (`package-lock.json`), yarn
+/// v1 (`yarn.lock`) and pnpm (`pnpm-lock.yaml`) lockfiles.
+///
+/// Every mismatch is reported as a warning, which is much more helpful than
+/// the resolving errors it would otherwise lead to in the middle of a build.
+#[turbo_tasks::function]
+pub async fn check_lockfile_drift(project_path: FileSystemPathVc) -> Result<CompletionVc> {
+    let package_json = read_package_json(project_path.join("package.json")).await?;
+    let Some(package_json) = &*package_json else {
+        return Ok(CompletionVc::new());
+    };
+
+    let Some((lockfile_path, lockfile)) = read_lockfile(project_path).await? else {
+        return Ok(CompletionVc::new());
+    };
+    let (lockfile, workspace_path) = match lockfile {
+        Ok(lockfile) => lockfile,
+        Err(err) => {
+            LockfileDriftIssue {
+                path: lockfile_path,
+                title: "Unable to parse lockfile".to_string(),
+                description: err.to_string(),
+            }
+            .cell()
+            .as_issue()
+            .emit();
+            return Ok(CompletionVc::new());
+        }
+    };
+
+    let mut drifts = Vec::new();
+    for field in DEPENDENCY_FIELDS {
+        let Some(dependencies) = package_json[field].as_object() else {
+            continue;
+        };
+        for (name, specifier) in dependencies {
+            let Some(specifier) = specifier.as_str() else {
+                continue;
+            };
+            // Packages which are not in the lockfile at all (e.g. workspace
+            // packages) can't be checked.
+            let Ok(Some(locked)) = lockfile.resolve_package(workspace_path, name, specifier) else {
+                continue;
+            };
+            let installed_path = project_path
+                .join("node_modules")
+                .join(name)
+                .join("package.json");
+            match &*read_package_json(installed_path).await? {
+                // Optional dependencies might be skipped on purpose, e.g. when
+                // they don't support the current platform.
+                None if field == "optionalDependencies" => {}
+                None => drifts.push(LockfileDrift::Missing {
+                    name: name.clone(),
+                    locked: locked.version,
+                }),
+                Some(installed) => {
+                    let installed = installed["version"].as_str().unwrap_or_default();
+                    if installed != locked.version {
+                        drifts.push(LockfileDrift::VersionMismatch {
+                            name: name.clone(),
+                            locked: locked.version,
+                            installed: installed.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    if !drifts.is_empty() {
+        let mut description = "The installed packages in node_modules don't match the lockfile. \
+                               Run your package manager's install command to fix this.\n"
+            .to_string();
+        for drift in drifts.iter() {
+            match drift {
+                LockfileDrift::Missing { name, locked } => {
+                    writeln!(description, "  - {name}@{locked} is not installed")?;
+                }
+                LockfileDrift::VersionMismatch {
+                    name,
+                    locked,
+                    installed,
+                } => {
+                    writeln!(
+                        description,
+                        "  - {name} is installed as {installed}, but locked to {locked}"
+                    )?;
+                }
+            }
+        }
+        LockfileDriftIssue {
+            path: lockfile_path,
+            title: format!(
+                "{} package(s) in node_modules differ from the lockfile",
+                drifts.len()
+            ),
+            description,
+        }
+        .cell()
+        .as_issue()
+        .emit();
+    }
+
+    Ok(CompletionVc::new())
+}
+
+type ParsedLockfile = Result<(Box<dyn Lockfile + Send + Sync>, &'static str)>;
+
+/// Finds and parses the lockfile in `project_path`. Returns the lockfile
+/// together with the workspace path of the project's root package in it.
+async fn read_lockfile(
+    project_path: FileSystemPathVc,
+) -> Result<Option<(FileSystemPathVc, ParsedLockfile)>> {
+    for name in ["package-lock.json", "pnpm-lock.yaml", "yarn.lock"] {
+        let path = project_path.join(name);
+        let FileContent::Content(file) = &*path.read().await? else {
+            continue;
+        };
+        let content = file.content().to_bytes()?;
+        let lockfile: ParsedLockfile = match name {
+            "package-lock.json" => NpmLockfile::load(&content)
+                .map(|l| (Box::new(l) as Box<dyn Lockfile + Send + Sync>, ""))
+                .map_err(Into::into),
+            "pnpm-lock.yaml" => PnpmLockfile::from_bytes(&content)
+                .map(|l| (Box::new(l) as Box<dyn Lockfile + Send + Sync>, "."))
+                .map_err(Into::into),
+            _ => Yarn1Lockfile::from_bytes(&content)
+                .map(|l| (Box::new(l) as Box<dyn Lockfile + Send + Sync>, ""))
+                .map_err(Into::into),
+        };
+        return Ok(Some((path, lockfile)));
+    }
+    Ok(None)
+}
+
+#[turbo_tasks::value(shared)]
+struct LockfileDriftIssue {
+    path: FileSystemPathVc,
+    title: String,
+    description: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for LockfileDriftIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        IssueSeverity::Warning.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("lockfile".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell(self.title.clone())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::cell(self.description.clone())
+    }
+}
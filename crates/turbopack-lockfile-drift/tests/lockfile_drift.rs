@@ -0,0 +1,84 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemPathVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::issue::{Issue, IssueSeverity, IssueVc};
+use turbopack_lockfile_drift::check_lockfile_drift;
+
+register!(turbopack_lockfile_drift::register);
+
+fn fixture(name: &str) -> FileSystemPathVc {
+    DiskFileSystemVc::new(
+        "fixtures".to_string(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures").to_string(),
+    )
+    .root()
+    .join(name)
+}
+
+/// Returns the severity, title and description of the issues which were
+/// emitted while checking the project.
+async fn check(project: &str) -> Result<Vec<(IssueSeverity, String, String)>> {
+    let completion = check_lockfile_drift(fixture(project));
+    let issues = IssueVc::peek_issues_with_path(completion).await?.await?;
+    let mut result = Vec::new();
+    for issue in issues.iter() {
+        result.push((
+            *issue.severity().await?,
+            issue.title().await?.clone_value(),
+            issue.description().await?.clone_value(),
+        ));
+    }
+    Ok(result)
+}
+
+#[tokio::test]
+async fn drift() {
+    run! {
+        let issues = check("drift").await?;
+        assert_eq!(issues.len(), 1);
+        let (severity, title, description) = &issues[0];
+        assert_eq!(*severity, IssueSeverity::Warning);
+        assert_eq!(
+            title,
+            "2 package(s) in node_modules differ from the lockfile"
+        );
+        assert!(
+            description.contains("  - missing@3.0.0 is not installed\n"),
+            "{description}"
+        );
+        assert!(
+            description.contains("  - outdated is installed as 2.0.0, but locked to 2.1.0\n"),
+            "{description}"
+        );
+        assert!(!description.contains("in-sync"), "{description}");
+        // Optional dependencies which aren't installed are fine.
+        assert!(!description.contains("optional"), "{description}");
+    }
+}
+
+#[tokio::test]
+async fn in_sync() {
+    run! {
+        assert_eq!(check("in-sync").await?, []);
+    }
+}
+
+#[tokio::test]
+async fn unsupported_lockfile() {
+    run! {
+        let issues = check("unsupported-lockfile").await?;
+        assert_eq!(issues.len(), 1);
+        let (severity, title, _) = &issues[0];
+        assert_eq!(*severity, IssueSeverity::Warning);
+        assert_eq!(title, "Unable to parse lockfile");
+    }
+}
+
+#[tokio::test]
+async fn no_lockfile() {
+    run! {
+        assert_eq!(check("no-lockfile").await?, []);
+    }
+}
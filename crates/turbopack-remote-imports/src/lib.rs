@@ -0,0 +1,373 @@
+#![feature(min_specialization)]
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use turbo_tasks::{
+    primitives::{OptionStringVc, StringVc},
+    CollectiblesSource, CompletionVc,
+};
+use turbo_tasks_fetch::fetch;
+use turbo_tasks_fs::{File, FileContent, FileJsonContent, FileSystemPathVc};
+use turbo_tasks_hash::{encode_hex_string, hash_sha256};
+use turbopack_core::{
+    file_source::FileSourceVc,
+    issue::{Issue, IssueSeverity, IssueSeverityVc, IssueVc},
+    resolve::{
+        options::{
+            ImportMap, ImportMapResult, ImportMapResultVc, ImportMapVc, ImportMapping,
+            ImportMappingReplacement, ImportMappingReplacementVc, ImportMappingVc,
+        },
+        parse::{Request, RequestVc},
+        AliasMap, AliasPattern, ResolveResult,
+    },
+};
+use url::Url;
+
+pub fn register() {
+    turbo_tasks::register();
+    turbo_tasks_fs::register();
+    turbo_tasks_fetch::register();
+    turbopack_core::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
+
+/// Resolves `http://` and `https://` requests by downloading the modules into
+/// a cache directory. The downloaded modules are treated as regular source
+/// files afterwards. Relative and absolute requests within downloaded modules
+/// are resolved against the url of the module.
+///
+/// The integrity of every remote module is recorded in a lockfile, which maps
+/// urls to the sha256 hash of their content. Locked modules are read from the
+/// cache without network access and modules which changed on the remote are
+/// reported as errors. Urls which are not locked yet are added to the
+/// lockfile by [RemoteImportsVc::update_lockfile].
+#[turbo_tasks::value(shared)]
+pub struct RemoteImports {
+    /// The directory where downloaded modules are stored. The directory
+    /// structure mirrors the urls of the modules, e.g.
+    /// `https://esm.sh/react@18/index.js` is stored at
+    /// `https/esm.sh/react@18/index.js`.
+    pub cache_dir: FileSystemPathVc,
+    /// The lockfile with the integrity hashes of all remote modules.
+    pub lockfile: FileSystemPathVc,
+}
+
+#[turbo_tasks::value_impl]
+impl RemoteImportsVc {
+    #[turbo_tasks::function]
+    pub fn new(cache_dir: FileSystemPathVc, lockfile: FileSystemPathVc) -> Self {
+        RemoteImports {
+            cache_dir,
+            lockfile,
+        }
+        .cell()
+    }
+
+    /// An import map which resolves `http://` and `https://` requests, and
+    /// relative and absolute requests within downloaded modules. Pass it as
+    /// `import_map` of the resolve options, or extend another import map with
+    /// it.
+    #[turbo_tasks::function]
+    pub async fn import_map(self) -> Result<ImportMapVc> {
+        let mapping = ImportMapping::Dynamic(self.into()).cell();
+        let mut import_map = ImportMap::empty();
+        for protocol in ["https://", "http://"] {
+            import_map.insert_wildcard_alias(protocol, mapping);
+        }
+        let mut relative = AliasMap::new();
+        for prefix in ["./", "../", "/"] {
+            relative.insert(AliasPattern::wildcard(prefix, ""), mapping);
+        }
+        import_map.insert_scope(self.await?.cache_dir, relative);
+        Ok(import_map.cell())
+    }
+}
+
+impl RemoteImportsVc {
+    /// Adds the integrities of the remote modules which were downloaded while
+    /// computing `source` to the lockfile. The lockfile is written once for
+    /// all of them, so call this with the root of the compilation, e.g. after
+    /// emitting the output.
+    pub async fn update_lockfile<T: CollectiblesSource>(self, source: T) -> Result<()> {
+        let locks = source
+            .peek_collectibles::<RemoteModuleLockVc>()
+            .strongly_consistent()
+            .await?;
+        let mut integrities = BTreeMap::new();
+        for lock in locks.iter() {
+            let lock = lock.entry().await?;
+            integrities.insert(lock.url.clone(), lock.integrity.clone());
+        }
+        if integrities.is_empty() {
+            return Ok(());
+        }
+        write_lockfile(self.await?.lockfile, RemoteIntegritiesVc::cell(integrities)).await?;
+        Ok(())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ImportMappingReplacement for RemoteImports {
+    #[turbo_tasks::function]
+    fn replace(self_vc: RemoteImportsVc, _captures: Vec<String>) -> ImportMappingVc {
+        ImportMapping::Dynamic(self_vc.into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn result(
+        &self,
+        context: FileSystemPathVc,
+        request: RequestVc,
+    ) -> Result<ImportMapResultVc> {
+        let request = request.await?;
+        let url = match &*request {
+            Request::Uri {
+                protocol,
+                remainder,
+            } if protocol == "https:" || protocol == "http:" => format!("{protocol}{remainder}"),
+            Request::Relative { .. } | Request::ServerRelative { .. } => {
+                let (Some(base), Some(request)) = (
+                    &*base_url(self.cache_dir, context).await?,
+                    request.request(),
+                ) else {
+                    return Ok(ImportMapResult::NoEntry.into());
+                };
+                match Url::parse(base).and_then(|base| base.join(&request)) {
+                    Ok(url) => url.to_string(),
+                    Err(_) => return Ok(ImportMapResult::NoEntry.into()),
+                }
+            }
+            _ => return Ok(ImportMapResult::NoEntry.into()),
+        };
+        let result = match *remote_module(self.cache_dir, self.lockfile, url).await? {
+            Some(path) => ResolveResult::asset(FileSourceVc::new(path).into()),
+            None => ResolveResult::unresolveable(),
+        };
+        Ok(ImportMapResult::Result(result.into()).into())
+    }
+}
+
+/// Returns the url of the directory `context` in the cache mirrors, with a
+/// trailing slash, or None when `context` isn't within the cache.
+#[turbo_tasks::function]
+async fn base_url(
+    cache_dir: FileSystemPathVc,
+    context: FileSystemPathVc,
+) -> Result<OptionStringVc> {
+    let cache_dir = cache_dir.await?;
+    let context = context.await?;
+    let Some(path) = cache_dir.get_path_to(&context) else {
+        return Ok(OptionStringVc::cell(None));
+    };
+    let Some((scheme, rest)) = path.split_once('/') else {
+        return Ok(OptionStringVc::cell(None));
+    };
+    if scheme != "https" && scheme != "http" {
+        return Ok(OptionStringVc::cell(None));
+    }
+    Ok(OptionStringVc::cell(Some(format!("{scheme}://{rest}/"))))
+}
+
+#[turbo_tasks::value(transparent)]
+struct RemoteIntegrities(BTreeMap<String, String>);
+
+/// Reads the integrities from the lockfile. All remote modules share this
+/// task.
+#[turbo_tasks::function]
+async fn read_lockfile(lockfile: FileSystemPathVc) -> Result<RemoteIntegritiesVc> {
+    let mut locked = BTreeMap::new();
+    if let FileJsonContent::Content(json) = &*lockfile.read_json().await? {
+        if let Some(entries) = json.as_object() {
+            for (url, integrity) in entries {
+                if let Some(integrity) = integrity.as_str() {
+                    locked.insert(url.clone(), integrity.to_string());
+                }
+            }
+        }
+    }
+    Ok(RemoteIntegritiesVc::cell(locked))
+}
+
+/// Adds `integrities` to the lockfile, keeping the entries which are already
+/// locked.
+#[turbo_tasks::function]
+async fn write_lockfile(
+    lockfile: FileSystemPathVc,
+    integrities: RemoteIntegritiesVc,
+) -> Result<CompletionVc> {
+    let mut locked = read_lockfile(lockfile).await?.clone_value();
+    let len = locked.len();
+    for (url, integrity) in integrities.await?.iter() {
+        locked
+            .entry(url.clone())
+            .or_insert_with(|| integrity.clone());
+    }
+    if locked.len() != len {
+        let content = serde_json::to_string_pretty(&locked)?;
+        lockfile
+            .write(FileContent::Content(File::from(content)).cell())
+            .await?;
+    }
+    Ok(CompletionVc::new())
+}
+
+/// The integrity of a remote module which isn't locked yet. It's collected
+/// from the compilation to update the lockfile.
+#[turbo_tasks::value_trait]
+trait RemoteModuleLock {
+    fn entry(&self) -> RemoteModuleLockEntryVc;
+}
+
+#[turbo_tasks::value(shared)]
+struct RemoteModuleLockEntry {
+    url: String,
+    integrity: String,
+}
+
+#[turbo_tasks::value_impl]
+impl RemoteModuleLock for RemoteModuleLockEntry {
+    #[turbo_tasks::function]
+    fn entry(self_vc: RemoteModuleLockEntryVc) -> RemoteModuleLockEntryVc {
+        self_vc
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct OptionRemoteModulePath(Option<FileSystemPathVc>);
+
+/// Returns the path of the cached copy of the remote module at `url`,
+/// downloading it first when necessary.
+#[turbo_tasks::function]
+async fn remote_module(
+    cache_dir: FileSystemPathVc,
+    lockfile: FileSystemPathVc,
+    url: String,
+) -> Result<OptionRemoteModulePathVc> {
+    let locked = read_lockfile(lockfile).await?;
+    let Some(cached) = cache_path(cache_dir, &url) else {
+        return Ok(OptionRemoteModulePathVc::cell(None));
+    };
+
+    if let Some(integrity) = locked.get(&url) {
+        if let FileContent::Content(file) = &*cached.read().await? {
+            if content_integrity(&file.content().to_bytes()?) == *integrity {
+                return Ok(OptionRemoteModulePathVc::cell(Some(cached)));
+            }
+        }
+    }
+
+    let response = &*fetch(StringVc::cell(url.clone()), OptionStringVc::cell(None)).await?;
+    let response = match response {
+        Ok(response) => response.await?,
+        Err(err) => {
+            err.to_issue(IssueSeverity::Error.into(), lockfile)
+                .as_issue()
+                .emit();
+            return Ok(OptionRemoteModulePathVc::cell(None));
+        }
+    };
+    let body = &response.body.await?.0;
+    let integrity = content_integrity(body);
+
+    match locked.get(&url) {
+        Some(expected) if *expected != integrity => {
+            RemoteModuleIssue {
+                lockfile,
+                title: format!("Integrity check failed for {url}"),
+                description: format!(
+                    "The content of {url} doesn't match the lockfile (expected {expected}, got \
+                     {integrity}). Remove the entry from the lockfile if the change is expected."
+                ),
+            }
+            .cell()
+            .as_issue()
+            .emit();
+            return Ok(OptionRemoteModulePathVc::cell(None));
+        }
+        Some(_) => {}
+        None => {
+            turbo_tasks::emit(
+                RemoteModuleLockEntry {
+                    url: url.clone(),
+                    integrity,
+                }
+                .cell()
+                .as_remote_module_lock(),
+            );
+        }
+    }
+
+    cached
+        .write(FileContent::Content(File::from(body.clone())).cell())
+        .await?;
+    Ok(OptionRemoteModulePathVc::cell(Some(cached)))
+}
+
+fn content_integrity(content: &[u8]) -> String {
+    format!("sha256-{}", encode_hex_string(&hash_sha256(content)))
+}
+
+/// Returns the path in the cache mirroring `url`, or None for urls which
+/// can't be stored.
+fn cache_path(cache_dir: FileSystemPathVc, url: &str) -> Option<FileSystemPathVc> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    let host = match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    };
+    let (dir, file_name) = url.path().rsplit_once('/')?;
+    let mut file_name = if file_name.is_empty() {
+        "index".to_string()
+    } else {
+        file_name.to_string()
+    };
+    // Different queries can return different modules, e.g. for different
+    // targets on a CDN.
+    if let Some(query) = url.query() {
+        file_name.push('~');
+        file_name.push_str(&encode_hex_string(&hash_sha256(query.as_bytes()))[..8]);
+    }
+    // Module rules apply to cached modules the same way as to local files, so
+    // they need an extension.
+    if !file_name.contains('.') {
+        file_name.push_str(".js");
+    }
+    Some(cache_dir.join(&format!("{}/{host}{dir}/{file_name}", url.scheme())))
+}
+
+#[turbo_tasks::value(shared)]
+struct RemoteModuleIssue {
+    lockfile: FileSystemPathVc,
+    title: String,
+    description: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for RemoteModuleIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> IssueSeverityVc {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("resolve".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell(self.title.clone())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.lockfile
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::cell(self.description.clone())
+    }
+}
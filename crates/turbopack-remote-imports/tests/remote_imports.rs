@@ -0,0 +1,190 @@
+#![feature(min_specialization)]
+
+use std::path::Path;
+
+use anyhow::Result;
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemPathVc};
+use turbo_tasks_testing::{register, run};
+use turbopack_core::{
+    asset::Asset,
+    issue::{Issue, IssueVc},
+    resolve::{options::ResolveOptions, parse::RequestVc, resolve, ResolveResultVc},
+};
+use turbopack_remote_imports::RemoteImportsVc;
+
+register!(turbopack_remote_imports::register);
+
+fn project(dir: &Path) -> FileSystemPathVc {
+    DiskFileSystemVc::new("project".to_string(), dir.to_string_lossy().to_string()).root()
+}
+
+fn remote_imports(project: FileSystemPathVc) -> RemoteImportsVc {
+    RemoteImportsVc::new(project.join(".remote"), project.join("remote.lock.json"))
+}
+
+fn resolve_request(
+    remote: RemoteImportsVc,
+    context: FileSystemPathVc,
+    request: &str,
+) -> ResolveResultVc {
+    let options = ResolveOptions {
+        import_map: Some(remote.import_map()),
+        ..Default::default()
+    }
+    .cell();
+    resolve(
+        context,
+        RequestVc::parse_string(request.to_string()),
+        options,
+    )
+}
+
+async fn paths(result: ResolveResultVc) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for asset in result.primary_assets().await?.iter() {
+        paths.push(asset.ident().path().await?.path.to_string());
+    }
+    Ok(paths)
+}
+
+/// Returns the titles of the issues which were emitted while resolving.
+async fn issue_titles(result: ResolveResultVc) -> Result<Vec<String>> {
+    let issues = IssueVc::peek_issues_with_path(result).await?.await?;
+    let mut titles = Vec::new();
+    for issue in issues.iter() {
+        titles.push(issue.title().await?.clone_value());
+    }
+    Ok(titles)
+}
+
+fn read_lockfile(dir: &Path) -> serde_json::Value {
+    let lockfile = std::fs::read_to_string(dir.join("remote.lock.json")).unwrap();
+    serde_json::from_str(&lockfile).unwrap()
+}
+
+#[tokio::test]
+async fn downloads_and_locks() {
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.path("/lib/a.js");
+        then.status(200).body("export const a = 1;");
+    });
+    let url = server.url("/lib/a.js");
+    let cached = format!(".remote/http/{}/lib/a.js", server.address());
+    let dir = tempfile::tempdir().unwrap();
+
+    let (root, request, expected) = (dir.path().to_path_buf(), url.clone(), cached.clone());
+    run! {
+        // Moves the values into the task
+        let (root, request, expected) = (root, request, expected);
+        let project = project(&root);
+        let remote = remote_imports(project);
+        let result = resolve_request(remote, project, &request);
+        assert_eq!(paths(result).await?, [expected]);
+        remote.update_lockfile(result).await?;
+    }
+    mock.assert_hits(1);
+
+    assert_eq!(
+        read_lockfile(dir.path()),
+        serde_json::json!({
+            url.clone(): "sha256-683314ed22112e8dea8095c8c6173afa2c61279f5fe07968ebe0e21fff16871d"
+        })
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join(&cached)).unwrap(),
+        "export const a = 1;"
+    );
+
+    // Locked modules are read from the cache without network access.
+    let (root, request) = (dir.path().to_path_buf(), url.clone());
+    run! {
+        // Moves the values into the task
+        let (root, request) = (root, request);
+        let project = project(&root);
+        let result = resolve_request(remote_imports(project), project, &request);
+        assert_eq!(paths(result).await?, [cached]);
+    }
+    mock.assert_hits(1);
+}
+
+#[tokio::test]
+async fn nested_imports() {
+    let server = httpmock::MockServer::start();
+    let b = server.mock(|when, then| {
+        when.path("/lib/b.js");
+        then.status(200).body("export const b = 1;");
+    });
+    let c = server.mock(|when, then| {
+        when.path("/c.js");
+        then.status(200).body("export const c = 1;");
+    });
+    let host = server.address().to_string();
+    let dir = tempfile::tempdir().unwrap();
+
+    let root = dir.path().to_path_buf();
+    run! {
+        // Moves the values into the task
+        let (root, host) = (root, host);
+        let project = project(&root);
+        let remote = remote_imports(project);
+        // The directory of a downloaded `http://{host}/lib/a.js`.
+        let context = project.join(&format!(".remote/http/{host}/lib"));
+
+        let relative = resolve_request(remote, context, "./b.js");
+        assert_eq!(
+            paths(relative).await?,
+            [format!(".remote/http/{host}/lib/b.js")]
+        );
+        let absolute = resolve_request(remote, context, "/c.js");
+        assert_eq!(
+            paths(absolute).await?,
+            [format!(".remote/http/{host}/c.js")]
+        );
+
+        // Relative requests outside of the cache are resolved as usual.
+        let local = resolve_request(remote, project.join("src"), "./b.js");
+        assert_eq!(paths(local).await?, Vec::<String>::new());
+    }
+    b.assert_hits(1);
+    c.assert_hits(1);
+}
+
+#[tokio::test]
+async fn lockfile_mismatch() {
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.path("/a.js");
+        then.status(200).body("export const a = 2;");
+    });
+    let url = server.url("/a.js");
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = serde_json::json!({
+        url.clone(): "sha256-683314ed22112e8dea8095c8c6173afa2c61279f5fe07968ebe0e21fff16871d"
+    });
+    std::fs::write(
+        dir.path().join("remote.lock.json"),
+        serde_json::to_string(&lockfile).unwrap(),
+    )
+    .unwrap();
+
+    let (root, request) = (dir.path().to_path_buf(), url.clone());
+    run! {
+        // Moves the values into the task
+        let (root, request) = (root, request);
+        let project = project(&root);
+        let remote = remote_imports(project);
+        let result = resolve_request(remote, project, &request);
+        assert_eq!(paths(result).await?, Vec::<String>::new());
+        assert_eq!(
+            issue_titles(result).await?,
+            [format!("Integrity check failed for {request}")]
+        );
+        remote.update_lockfile(result).await?;
+    }
+    mock.assert_hits(1);
+
+    // The locked integrity is kept and the module isn't cached.
+    assert_eq!(read_lockfile(dir.path()), lockfile);
+    assert!(!dir.path().join(".remote").exists());
+}
@@ -144,19 +144,17 @@ impl Asset for StaticAsset {
     #[turbo_tasks::function]
     async fn ident(&self) -> Result<AssetIdentVc> {
         let content = self.source.content();
+        let content_hashing = self.context.content_hashing().await?;
         let content_hash = if let AssetContent::File(file) = &*content.await? {
             if let FileContent::Content(file) = &*file.await? {
-                turbo_tasks_hash::hash_xxh3_hash64(file.content())
+                content_hashing.hash(file.content())?
             } else {
                 return Err(anyhow!("StaticAsset::path: not found"));
             }
         } else {
             return Err(anyhow!("StaticAsset::path: unsupported file content"));
         };
-        let content_hash_b16 = turbo_tasks_hash::encode_hex(content_hash);
-        let asset_path = self
-            .context
-            .asset_path(&content_hash_b16, self.source.ident());
+        let asset_path = self.context.asset_path(&content_hash, self.source.ident());
         Ok(AssetIdentVc::from_path(asset_path))
     }
 
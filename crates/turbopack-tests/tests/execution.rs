@@ -4,10 +4,12 @@ mod util;
 
 use std::{
     collections::HashMap,
+    fs,
     path::{Path, PathBuf},
 };
 
 use anyhow::{Context, Result};
+use serde::Deserialize;
 use turbo_tasks::{debug::ValueDebug, CompletionVc, NothingVc, TryJoinIterExt, TurboTasks, Value};
 use turbo_tasks_bytes::stream::SingleValue;
 use turbo_tasks_env::CommandLineProcessEnvVc;
@@ -18,7 +20,9 @@ use turbo_tasks_fs::{
 use turbo_tasks_memory::MemoryBackend;
 use turbopack::{
     condition::ContextCondition,
-    module_options::{ModuleOptionsContext, TypescriptTransformOptionsVc},
+    module_options::{
+        DecoratorsKind, DecoratorsOptions, ModuleOptionsContext, TypescriptTransformOptions,
+    },
     resolve_options_context::ResolveOptionsContext,
     transition::TransitionsByNameVc,
     ModuleAssetContextVc,
@@ -57,6 +61,18 @@ struct JsResult {
     jest_result: JestRunResult,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionOptions {
+    /// Emits TypeScript `const enum`s like regular enums.
+    #[serde(default)]
+    treat_const_enum_as_enum: bool,
+    /// The decorators transform to use. When not set, it's read from the
+    /// tsconfig.json of the test.
+    #[serde(default)]
+    decorators: Option<DecoratorsKind>,
+}
+
 enum IssueSnapshotMode {
     Snapshots,
     NoSnapshots,
@@ -173,6 +189,12 @@ async fn run_test(resource: &str) -> Result<RunTestResultVc> {
         resource_path.to_str().unwrap()
     );
 
+    let options_file = fs::read_to_string(resource_path.join("options.json"));
+    let options: ExecutionOptions = match options_file {
+        Err(_) => ExecutionOptions::default(),
+        Ok(options_str) => parse_json_with_source_context(&options_str).unwrap(),
+    };
+
     let root_fs = DiskFileSystemVc::new("workspace".to_string(), REPO_ROOT.clone());
     let project_fs = DiskFileSystemVc::new("project".to_string(), REPO_ROOT.clone());
     let project_root = project_fs.root();
@@ -208,7 +230,21 @@ async fn run_test(resource: &str) -> Result<RunTestResultVc> {
         TransitionsByNameVc::cell(HashMap::new()),
         compile_time_info,
         ModuleOptionsContext {
-            enable_typescript_transform: Some(TypescriptTransformOptionsVc::default()),
+            enable_typescript_transform: Some(
+                TypescriptTransformOptions {
+                    treat_const_enum_as_enum: options.treat_const_enum_as_enum,
+                    ..Default::default()
+                }
+                .cell(),
+            ),
+            decorators: options.decorators.map(|decorators_kind| {
+                DecoratorsOptions {
+                    decorators_kind: Some(decorators_kind),
+                    ..Default::default()
+                }
+                .cell()
+            }),
+            enable_import_meta_glob: true,
             preset_env_versions: Some(env),
             rules: vec![(
                 ContextCondition::InDirectory("node_modules".to_string()),
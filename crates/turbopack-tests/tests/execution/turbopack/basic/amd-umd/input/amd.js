@@ -0,0 +1,3 @@
+define(["./dep.js"], function (dep) {
+  return { value: dep.value * 2 };
+});
@@ -0,0 +1,16 @@
+import amd from "./amd.js";
+import { named } from "./amd-exports.js";
+import umd from "./umd.js";
+
+it("should use the factory result of AMD define() as exports", () => {
+  expect(amd.value).toBe(42);
+});
+
+it("should pass exports to AMD factories", () => {
+  expect(named).toBe("named");
+});
+
+it("should use the AMD branch of UMD wrappers", () => {
+  expect(umd.value).toBe(22);
+  expect(globalThis.umdGlobal).toBeUndefined();
+});
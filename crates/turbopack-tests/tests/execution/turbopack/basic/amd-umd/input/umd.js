@@ -0,0 +1,9 @@
+(function (root, factory) {
+  if (typeof define === "function" && define.amd) {
+    define(["./dep.js"], factory);
+  } else {
+    root.umdGlobal = factory(root.dep);
+  }
+})(this, function (dep) {
+  return { value: dep.value + 1 };
+});
@@ -0,0 +1,3 @@
+require("./cycle-b.js");
+
+module.exports = { value: "a" };
@@ -0,0 +1,9 @@
+import a, { value } from "./cycle-a.js";
+
+export function getA() {
+  return a;
+}
+
+export function getAValue() {
+  return value;
+}
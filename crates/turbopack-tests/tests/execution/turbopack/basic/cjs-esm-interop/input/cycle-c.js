@@ -0,0 +1,4 @@
+require("./cycle-d.js");
+
+Object.defineProperty(exports, "__esModule", { value: true });
+exports.default = "c";
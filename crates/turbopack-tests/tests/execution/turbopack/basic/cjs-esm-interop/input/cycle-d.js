@@ -0,0 +1,5 @@
+import c from "./cycle-c.js";
+
+export function getC() {
+  return c;
+}
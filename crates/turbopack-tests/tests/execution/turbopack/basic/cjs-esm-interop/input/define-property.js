@@ -0,0 +1,10 @@
+let count = 0;
+
+Object.defineProperty(exports, "count", {
+  enumerable: true,
+  get: () => count,
+});
+
+exports.increment = () => {
+  count++;
+};
@@ -0,0 +1,33 @@
+// The CommonJS modules of the cycles need to be loaded first
+import "./cycle-a.js";
+import "./cycle-c.js";
+import flagged, { named as flaggedNamed } from "./es-module-flag.js";
+import fn, { named as fnNamed } from "./reassigned.js";
+import { count, increment } from "./define-property.js";
+import { getA, getAValue } from "./cycle-b.js";
+import { getC } from "./cycle-d.js";
+
+it("should use the default property of modules marked with __esModule", () => {
+  expect(flagged).toBe("default");
+  expect(flaggedNamed).toBe("named");
+});
+
+it("should use module.exports as default export", () => {
+  expect(fn()).toBe("fn");
+  expect(fnNamed).toBe("named");
+});
+
+it("should keep live bindings of getters defined on exports", () => {
+  expect(count).toBe(0);
+  increment();
+  expect(count).toBe(1);
+});
+
+it("should follow reassignments of module.exports in a cycle", () => {
+  expect(getA()).toMatchObject({ value: "a" });
+  expect(getAValue()).toBe("a");
+});
+
+it("should follow __esModule flags which are set in a cycle", () => {
+  expect(getC()).toBe("c");
+});
@@ -0,0 +1,4 @@
+module.exports = function fn() {
+  return "fn";
+};
+module.exports.named = "named";
@@ -0,0 +1,9 @@
+export const enum Direction {
+  Up = 1,
+  Down,
+}
+
+export const enum Label {
+  Yes = "yes",
+  No = "no",
+}
@@ -0,0 +1,16 @@
+import { Direction, Label } from "./enums.ts";
+
+it("should import const enums from another module", () => {
+  expect(Direction.Up).toBe(1);
+  expect(Direction.Down).toBe(2);
+  expect(Label.Yes).toBe("yes");
+});
+
+it("should emit the reverse mapping of numeric const enums", () => {
+  expect(Direction[2]).toBe("Down");
+});
+
+it("should access imported const enums from TypeScript at runtime", async () => {
+  const { down } = await import("./use.ts");
+  expect(down).toBe(2);
+});
@@ -0,0 +1,3 @@
+import { Direction } from "./enums";
+
+export const down: number = Direction.Down;
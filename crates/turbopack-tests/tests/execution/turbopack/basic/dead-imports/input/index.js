@@ -0,0 +1,21 @@
+it("should drop imports which are only used in eliminated branches", async () => {
+  const { log, value } = await import("./module.js");
+  expect(value()).toEqual(["both"]);
+  expect(log).not.toContain("prod-only");
+});
+
+it("should keep imports which are also used in live code", async () => {
+  const { log } = await import("./module.js");
+  expect(log).toContain("both");
+});
+
+it("should keep imports for side effects", async () => {
+  const { log } = await import("./module.js");
+  expect(log).toContain("side-effect");
+});
+
+it("should keep imports which are reexported", async () => {
+  const { log, reexported } = await import("./module.js");
+  expect(reexported).toBe("reexported");
+  expect(log).toContain("reexported");
+});
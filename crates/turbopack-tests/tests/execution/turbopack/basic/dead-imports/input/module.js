@@ -0,0 +1,15 @@
+import { log } from "./log.js";
+import { prodOnly } from "./prod-only.js";
+import { both } from "./both.js";
+import "./side-effect.js";
+import { sideEffect } from "./side-effect.js";
+import { reexported } from "./reexported.js";
+
+export function value() {
+  if (process.env.NODE_ENV === "production") {
+    return [prodOnly, both, sideEffect, reexported];
+  }
+  return [both];
+}
+
+export { log, reexported };
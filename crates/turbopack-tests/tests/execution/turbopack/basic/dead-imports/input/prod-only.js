@@ -0,0 +1,5 @@
+import { log } from "./log.js";
+
+log.push("prod-only");
+
+export const prodOnly = "prod-only";
@@ -0,0 +1,5 @@
+import { log } from "./log.js";
+
+log.push("reexported");
+
+export const reexported = "reexported";
@@ -0,0 +1,5 @@
+import { log } from "./log.js";
+
+log.push("side-effect");
+
+export const sideEffect = "side-effect";
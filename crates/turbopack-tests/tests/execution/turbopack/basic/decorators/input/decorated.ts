@@ -0,0 +1,18 @@
+function sealed(constructor: Function) {
+  constructor.prototype.sealed = true;
+}
+
+function double(target: any, key: string, descriptor: PropertyDescriptor) {
+  const original = descriptor.value;
+  descriptor.value = function (value: number) {
+    return original.call(this, value) * 2;
+  };
+}
+
+@sealed
+export class Calculator {
+  @double
+  identity(value: number): number {
+    return value;
+  }
+}
@@ -0,0 +1,9 @@
+import { Calculator } from "./decorated.ts";
+
+it("should apply legacy class decorators", () => {
+  expect(Calculator.prototype.sealed).toBe(true);
+});
+
+it("should apply legacy method decorators", () => {
+  expect(new Calculator().identity(21)).toBe(42);
+});
@@ -0,0 +1,32 @@
+it("should lazily import the matched modules", async () => {
+  const modules = import.meta.glob("./pages/**/*.js");
+  expect(Object.keys(modules)).toEqual([
+    "./pages/a.js",
+    "./pages/b.js",
+    "./pages/nested/c.js",
+  ]);
+  const a = await modules["./pages/a.js"]();
+  expect(a.default).toBe("a");
+  expect(a.name).toBe("page a");
+});
+
+it("should eagerly import the matched modules", () => {
+  const modules = import.meta.glob("./pages/*.js", { eager: true });
+  expect(Object.keys(modules)).toEqual(["./pages/a.js", "./pages/b.js"]);
+  expect(modules["./pages/b.js"].default).toBe("b");
+});
+
+it("should import a single export", async () => {
+  const eager = import.meta.glob("./pages/**/*.js", {
+    eager: true,
+    import: "name",
+  });
+  expect(eager["./pages/nested/c.js"]).toBe("page c");
+  const lazy = import.meta.glob("./pages/*.js", { import: "default" });
+  expect(await lazy["./pages/a.js"]()).toBe("a");
+});
+
+it("should support negative patterns", () => {
+  const modules = import.meta.glob(["./pages/**/*.js", "!./pages/nested/**"]);
+  expect(Object.keys(modules)).toEqual(["./pages/a.js", "./pages/b.js"]);
+});
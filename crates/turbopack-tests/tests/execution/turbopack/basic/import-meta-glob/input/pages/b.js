@@ -0,0 +1,2 @@
+export default "b";
+export const name = "page b";
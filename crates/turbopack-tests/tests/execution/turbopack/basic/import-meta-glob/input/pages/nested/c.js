@@ -0,0 +1,2 @@
+export default "c";
+export const name = "page c";
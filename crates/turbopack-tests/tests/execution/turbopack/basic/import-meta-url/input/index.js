@@ -0,0 +1,13 @@
+import { readFileSync } from "fs";
+import { fileURLToPath } from "url";
+
+it("should resolve new URL(..., import.meta.url) to a file URL of the emitted asset", () => {
+  const url = new URL("./file.txt", import.meta.url);
+  expect(url.protocol).toBe("file:");
+  expect(fileURLToPath(url)).not.toContain("/input/");
+});
+
+it("should read an asset referenced with new URL(..., import.meta.url)", () => {
+  const url = new URL("./file.txt", import.meta.url);
+  expect(readFileSync(url, "utf-8")).toBe("Hello from an asset\n");
+});
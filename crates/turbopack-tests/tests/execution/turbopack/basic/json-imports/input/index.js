@@ -0,0 +1,22 @@
+import data from "./data.json";
+import { name, nested } from "./data.json";
+import * as namespace from "./data.json";
+
+it("should import the value as the default export", () => {
+  expect(data).toEqual({
+    name: "data",
+    nested: { value: 42 },
+    list: [1, 2, 3],
+  });
+});
+
+it("should import top-level keys as named exports", () => {
+  expect(name).toBe("data");
+  expect(nested).toEqual({ value: 42 });
+});
+
+it("should import top-level keys through a namespace", () => {
+  expect(namespace.name).toBe("data");
+  expect(namespace.list).toEqual([1, 2, 3]);
+  expect(namespace.default).toBe(data);
+});
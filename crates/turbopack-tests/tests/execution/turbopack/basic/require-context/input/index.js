@@ -0,0 +1,26 @@
+it("should list the modules of the context", () => {
+  const context = require.context("./context", true, /\.js$/);
+  expect(context.keys()).toEqual(["./a.js", "./b.js", "./nested/c.js"]);
+});
+
+it("should require modules of the context", () => {
+  const context = require.context("./context", true, /\.js$/);
+  expect(context("./a.js")).toBe("a");
+  expect(context("./nested/c.js")).toBe("c");
+  expect(() => context("./missing.js")).toThrow();
+});
+
+it("should not include subdirectories when not recursive", () => {
+  const context = require.context("./context", false, /\.js$/);
+  expect(context.keys()).toEqual(["./a.js", "./b.js"]);
+});
+
+it("should use the default filter", () => {
+  const context = require.context("./context", false);
+  expect(context.keys()).toEqual(["./a.js", "./b.js"]);
+});
+
+it("should support the sync mode", () => {
+  const context = require.context("./context", false, /\.js$/, "sync");
+  expect(context("./b.js")).toBe("b");
+});
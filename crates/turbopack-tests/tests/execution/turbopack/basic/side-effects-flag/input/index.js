@@ -0,0 +1,15 @@
+it("should import reexported bindings from the declaring module", async () => {
+  const { log, value } = await import("./reexport.js");
+  expect(value).toBe("a");
+  expect(log).toContain("a");
+  expect(log).not.toContain("lib");
+  expect(log).not.toContain("b");
+});
+
+it("should drop imports of side effect free modules", async () => {
+  const { log } = await import("./side-effects.js");
+  expect(log).not.toContain("b");
+  expect(log).not.toContain("unused");
+  expect(log).not.toContain("other");
+  expect(log).toContain("polyfill");
+});
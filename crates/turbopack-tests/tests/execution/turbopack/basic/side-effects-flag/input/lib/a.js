@@ -0,0 +1,5 @@
+import { log } from "../log.js";
+
+log.push("a");
+
+export const a = "a";
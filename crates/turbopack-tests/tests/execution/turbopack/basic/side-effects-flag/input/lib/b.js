@@ -0,0 +1,5 @@
+import { log } from "../log.js";
+
+log.push("b");
+
+export const b = "b";
@@ -0,0 +1,6 @@
+import { log } from "../log.js";
+
+log.push("lib");
+
+export { a } from "./a.js";
+export { b } from "./b.js";
@@ -0,0 +1,6 @@
+import { log } from "./log.js";
+import { a } from "./lib";
+
+export const value = a;
+
+export { log };
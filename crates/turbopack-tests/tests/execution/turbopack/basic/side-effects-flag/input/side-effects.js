@@ -0,0 +1,7 @@
+import { log } from "./log.js";
+import { b } from "./lib";
+import "./lib/unused.js";
+import "./lib-globs/other.js";
+import "./lib-globs/polyfills/polyfill.js";
+
+export { log };
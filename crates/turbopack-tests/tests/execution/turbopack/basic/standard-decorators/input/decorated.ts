@@ -0,0 +1,25 @@
+export const log: string[] = [];
+
+function logged(value: Function, context: ClassDecoratorContext) {
+  context.addInitializer(() => {
+    log.push(`defined ${String(context.name)}`);
+  });
+}
+
+function double(
+  value: (this: unknown, n: number) => number,
+  context: ClassMethodDecoratorContext
+) {
+  log.push(`decorated ${String(context.name)} (${context.kind})`);
+  return function (this: unknown, n: number) {
+    return value.call(this, n) * 2;
+  };
+}
+
+@logged
+export class Calculator {
+  @double
+  identity(value: number): number {
+    return value;
+  }
+}
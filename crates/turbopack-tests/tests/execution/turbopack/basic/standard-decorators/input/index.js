@@ -0,0 +1,9 @@
+import { Calculator, log } from "./decorated.ts";
+
+it("should apply standard method decorators", () => {
+  expect(new Calculator().identity(21)).toBe(42);
+});
+
+it("should pass the decorator context", () => {
+  expect(log).toEqual(["decorated identity (method)", "defined Calculator"]);
+});
@@ -0,0 +1,11 @@
+async function* values() {
+  yield 1;
+  yield 2;
+}
+
+let sum = 0;
+for await (const value of values()) {
+  sum += value;
+}
+
+export { sum };
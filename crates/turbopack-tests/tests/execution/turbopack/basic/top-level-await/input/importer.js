@@ -0,0 +1,6 @@
+import { log } from "./log.js";
+import { value } from "./tla.js";
+
+log.push("importer");
+
+export { log, value };
@@ -0,0 +1,10 @@
+it("should evaluate importers after their async dependencies", async () => {
+  const { log, value } = await import("./importer.js");
+  expect(value).toBe(42);
+  expect(log).toEqual(["tla start", "tla end", "importer"]);
+});
+
+it("should support for await at the top level", async () => {
+  const { sum } = await import("./for-await.js");
+  expect(sum).toBe(3);
+});
@@ -0,0 +1,7 @@
+import { log } from "./log.js";
+
+log.push("tla start");
+await new Promise((resolve) => setTimeout(resolve, 10));
+log.push("tla end");
+
+export const value = 42;
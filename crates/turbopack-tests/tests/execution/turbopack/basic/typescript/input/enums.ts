@@ -0,0 +1,13 @@
+export const enum Direction {
+  Up = 1,
+  Down,
+}
+
+export enum Color {
+  Red = "red",
+  Green = "green",
+}
+
+export function down(): number {
+  return Direction.Down;
+}
@@ -0,0 +1,16 @@
+import { Color, down } from "./enums.ts";
+import { Shapes } from "./namespaces.ts";
+
+it("should inline const enums", () => {
+  expect(down()).toBe(2);
+});
+
+it("should emit regular enums", () => {
+  expect(Color.Red).toBe("red");
+  expect(Color.Green).toBe("green");
+});
+
+it("should emit namespaces", () => {
+  expect(Shapes.sides).toBe(4);
+  expect(Shapes.Square.area(3)).toBe(9);
+});
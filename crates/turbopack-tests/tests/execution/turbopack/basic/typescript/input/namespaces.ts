@@ -0,0 +1,9 @@
+export namespace Shapes {
+  export const sides = 4;
+
+  export namespace Square {
+    export function area(size: number): number {
+      return size * size;
+    }
+  }
+}
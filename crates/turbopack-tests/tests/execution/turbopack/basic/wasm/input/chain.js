@@ -0,0 +1,6 @@
+import { log } from "./log.js";
+import { result } from "./compute.js";
+
+log.push("chain");
+
+export { log, result };
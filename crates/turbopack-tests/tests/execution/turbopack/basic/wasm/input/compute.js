@@ -0,0 +1,6 @@
+import { log } from "./log.js";
+import { compute } from "./compute.wasm";
+
+log.push("compute");
+
+export const result = compute(1);
@@ -0,0 +1,11 @@
+it("should call JavaScript imports from a WebAssembly module", async () => {
+  const { compute } = await import("./compute.wasm");
+  expect(compute(1)).toBe(22);
+  expect(compute(5)).toBe(30);
+});
+
+it("should evaluate a chain of async dependencies in order", async () => {
+  const { log, result } = await import("./chain.js");
+  expect(result).toBe(22);
+  expect(log).toEqual(["offset start", "offset end", "compute", "chain"]);
+});
@@ -0,0 +1,9 @@
+import { log } from "./log.js";
+
+log.push("offset start");
+const offset = await new Promise((resolve) => setTimeout(() => resolve(10), 10));
+log.push("offset end");
+
+export function addOffset(value) {
+  return value + offset;
+}
@@ -3,7 +3,7 @@
 mod util;
 
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fs,
     path::{Path, PathBuf},
 };
@@ -14,13 +14,18 @@ use serde::Deserialize;
 use turbo_tasks::{debug::ValueDebug, NothingVc, TryJoinIterExt, TurboTasks, Value, ValueToString};
 use turbo_tasks_env::DotenvProcessEnvVc;
 use turbo_tasks_fs::{
-    json::parse_json_with_source_context, util::sys_to_unix, DiskFileSystemVc, FileSystem,
-    FileSystemPathReadRef, FileSystemPathVc,
+    glob::GlobVc, json::parse_json_with_source_context, util::sys_to_unix, DiskFileSystemVc,
+    FileSystem, FileSystemPathReadRef, FileSystemPathVc,
 };
 use turbo_tasks_memory::MemoryBackend;
 use turbopack::{
     condition::ContextCondition,
-    ecmascript::{EcmascriptModuleAssetVc, TransformPluginVc},
+    ecmascript::{
+        chunk::{EcmascriptChunkPlaceableVc, EcmascriptChunkPlaceablesVc},
+        export_usage::ExportUsageVc,
+        minify::MinifyOptions,
+        EcmascriptModuleAssetVc, TransformPluginVc,
+    },
     module_options::{
         CustomEcmascriptTransformPlugins, CustomEcmascriptTransformPluginsVc, JsxTransformOptions,
         JsxTransformOptionsVc, ModuleOptionsContext,
@@ -33,8 +38,8 @@ use turbopack_build::BuildChunkingContextVc;
 use turbopack_core::{
     asset::{Asset, AssetVc, AssetsVc},
     chunk::{
-        ChunkableModule, ChunkableModuleVc, ChunkingContext, ChunkingContextVc, EvaluatableAssetVc,
-        EvaluatableAssetsVc,
+        ChunkableModule, ChunkableModuleVc, ChunkingContext, ChunkingContextVc, ContentHashing,
+        EvaluatableAssetVc, EvaluatableAssetsVc,
     },
     compile_time_defines,
     compile_time_info::CompileTimeInfo,
@@ -44,6 +49,7 @@ use turbopack_core::{
     issue::IssueVc,
     reference::all_referenced_assets,
     reference_type::{EntryReferenceSubType, ReferenceType},
+    resolve::options::{ExternalMatcher, ExternalRule, ExternalType, Externals},
     source::SourceVc,
 };
 use turbopack_dev::DevChunkingContextVc;
@@ -83,6 +89,22 @@ struct SnapshotOptions {
     runtime_type: RuntimeType,
     #[serde(default)]
     environment: Environment,
+    /// Omits unused exports of ES modules. Only applies to the build runtime.
+    #[serde(default)]
+    tree_shaking: bool,
+    /// Minifies the chunk items. Only applies to the build runtime.
+    #[serde(default)]
+    minify: bool,
+    /// How the content hashes of static assets are computed. Only applies to
+    /// the build runtime.
+    #[serde(default)]
+    content_hashing: Option<ContentHashing>,
+    /// The path of a web import map, relative to the test directory.
+    #[serde(default)]
+    web_import_map: Option<String>,
+    /// Marks the requests matching the globs as externals of the given type.
+    #[serde(default)]
+    externals: BTreeMap<String, ExternalType>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -107,6 +129,11 @@ impl Default for SnapshotOptions {
             runtime: Default::default(),
             runtime_type: default_runtime_type(),
             environment: Default::default(),
+            tree_shaking: false,
+            minify: false,
+            content_hashing: None,
+            web_import_map: None,
+            externals: BTreeMap::new(),
         }
     }
 }
@@ -224,6 +251,7 @@ async fn run_test(resource: &str) -> Result<FileSystemPathVc> {
                 DEFINED_VALUE = "value",
                 DEFINED_TRUE = true,
                 A.VERY.LONG.DEFINED.VALUE = "value",
+                typeof window = "object",
             )
             .cell(),
         )
@@ -271,6 +299,24 @@ async fn run_test(resource: &str) -> Result<FileSystemPathVc> {
             enable_react: true,
             enable_node_modules: Some(project_root),
             custom_conditions: vec!["development".to_string()],
+            web_import_map: options
+                .web_import_map
+                .as_ref()
+                .map(|web_import_map| path.join(web_import_map)),
+            externals: (!options.externals.is_empty()).then(|| {
+                Externals {
+                    rules: options
+                        .externals
+                        .iter()
+                        .map(|(glob, ty)| ExternalRule {
+                            matcher: ExternalMatcher::Glob(GlobVc::new(glob)),
+                            name: None,
+                            ty: *ty,
+                        })
+                        .collect(),
+                }
+                .cell()
+            }),
             rules: vec![(
                 ContextCondition::InDirectory("node_modules".to_string()),
                 ResolveOptionsContext {
@@ -293,6 +339,11 @@ async fn run_test(resource: &str) -> Result<FileSystemPathVc> {
     let chunk_root_path = path.join("output");
     let static_root_path = path.join("static");
 
+    let entry_module = context.process(
+        FileSourceVc::new(entry_asset).into(),
+        Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined)),
+    );
+
     let chunking_context: ChunkingContextVc = match options.runtime {
         Runtime::Dev => DevChunkingContextVc::builder(
             project_root,
@@ -304,16 +355,30 @@ async fn run_test(resource: &str) -> Result<FileSystemPathVc> {
         .runtime_type(options.runtime_type)
         .build()
         .into(),
-        Runtime::Build => BuildChunkingContextVc::builder(
-            project_root,
-            path,
-            chunk_root_path,
-            static_root_path,
-            env,
-        )
-        .runtime_type(options.runtime_type)
-        .build()
-        .into(),
+        Runtime::Build => {
+            let mut builder = BuildChunkingContextVc::builder(
+                project_root,
+                path,
+                chunk_root_path,
+                static_root_path,
+                env,
+            )
+            .runtime_type(options.runtime_type);
+            if options.tree_shaking {
+                if let Some(entry) = EcmascriptChunkPlaceableVc::resolve_from(entry_module).await? {
+                    builder = builder.export_usage(ExportUsageVc::new(
+                        EcmascriptChunkPlaceablesVc::cell(vec![entry]),
+                    ));
+                }
+            }
+            if options.minify {
+                builder = builder.minify(MinifyOptions::default());
+            }
+            if let Some(content_hashing) = options.content_hashing {
+                builder = builder.content_hashing(content_hashing);
+            }
+            builder.build().into()
+        }
     };
 
     let expected_paths = expected(chunk_root_path)
@@ -322,11 +387,6 @@ async fn run_test(resource: &str) -> Result<FileSystemPathVc> {
         .copied()
         .collect();
 
-    let entry_module = context.process(
-        FileSourceVc::new(entry_asset).into(),
-        Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined)),
-    );
-
     let chunks =
         if let Some(ecmascript) = EcmascriptModuleAssetVc::resolve_from(entry_module).await? {
             // TODO: Load runtime entries from snapshots
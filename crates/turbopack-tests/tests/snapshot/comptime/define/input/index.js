@@ -20,7 +20,6 @@ if (process.env.NODE_ENV === 'production') {
 
 var p = process;
 
-// TODO: replacement is not implemented yet
 console.log(A.VERY.LONG.DEFINED.VALUE);
 console.log(DEFINED_VALUE);
 console.log(p.env.NODE_ENV);
@@ -35,3 +34,13 @@ p.env.NODE_ENV == 'production' ? console.log('production') : console.log('develo
 // TODO short-circuit is not implemented yet
 p.env.NODE_ENV != 'production' && console.log('development');
 p.env.NODE_ENV == 'production' && console.log('production');
+
+if (typeof window === 'undefined') {
+  console.log('server');
+}
+
+if (typeof window !== 'undefined') {
+  console.log('client');
+}
+
+console.log(typeof window);
@@ -18,15 +18,22 @@ if ("TURBOPACK compile-time falsy", 0) {
     "TURBOPACK unreachable";
 }
 var p = process;
-console.log(A.VERY.LONG.DEFINED.VALUE);
-console.log(DEFINED_VALUE);
-console.log(p.env.NODE_ENV);
+console.log(("TURBOPACK compile-time value", "value"));
+console.log(("TURBOPACK compile-time value", "value"));
+console.log(("TURBOPACK compile-time value", "development"));
 if ("TURBOPACK compile-time falsy", 0) {
     "TURBOPACK unreachable";
 }
-p.env.NODE_ENV == 'production' ? console.log('production') : console.log('development');
-p.env.NODE_ENV != 'production' && console.log('development');
-p.env.NODE_ENV == 'production' && console.log('production');
+("TURBOPACK compile-time value", "development") == 'production' ? console.log('production') : console.log('development');
+("TURBOPACK compile-time value", "development") != 'production' && console.log('development');
+("TURBOPACK compile-time value", "development") == 'production' && console.log('production');
+if (("TURBOPACK compile-time value", "object") === 'undefined') {
+    console.log('server');
+}
+if (("TURBOPACK compile-time value", "object") !== 'undefined') {
+    console.log('client');
+}
+console.log(("TURBOPACK compile-time value", "object"));
 
 }.call(this) }),
 }]);
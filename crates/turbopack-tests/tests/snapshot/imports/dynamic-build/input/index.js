@@ -0,0 +1 @@
+import("./vercel.mjs").then(console.log);
@@ -0,0 +1,14 @@
+module.exports = {
+
+"[project]/crates/turbopack-tests/tests/snapshot/imports/dynamic-build/input/vercel.mjs (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, x: __turbopack_external_require__, P: __turbopack_resolve_absolute_path__ }) => (() => {
+
+__turbopack_esm__({
+    "default": ()=>__TURBOPACK__default__export__
+});
+const __TURBOPACK__default__export__ = "turbopack";
+
+})()),
+
+};
+
+//# sourceMappingURL=79fb1_turbopack-tests_tests_snapshot_imports_dynamic-build_input_vercel_mjs_8fefc5._.js.map
\ No newline at end of file
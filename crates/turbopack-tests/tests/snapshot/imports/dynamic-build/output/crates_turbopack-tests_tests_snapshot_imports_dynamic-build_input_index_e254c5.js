@@ -0,0 +1,24 @@
+module.exports = {
+
+"[project]/crates/turbopack-tests/tests/snapshot/imports/dynamic-build/input/vercel.mjs (ecmascript, manifest chunk, loader)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_export_value__((__turbopack_import__) => {
+    return Promise.all(["output/79fb1_turbopack-tests_tests_snapshot_imports_dynamic-build_input_vercel_mjs_c455cf._.js"].map((chunk) => __turbopack_load__(chunk))).then(() => {
+        return __turbopack_require__("[project]/crates/turbopack-tests/tests/snapshot/imports/dynamic-build/input/vercel.mjs (ecmascript, manifest chunk)");
+    }).then((chunks) => {
+        return Promise.all(chunks.map((chunk) => __turbopack_load__(chunk)));
+    }).then(() => {
+        return __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/imports/dynamic-build/input/vercel.mjs (ecmascript)");
+    });
+});
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/imports/dynamic-build/input/index.js (ecmascript)": (function({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, x: __turbopack_external_require__, P: __turbopack_resolve_absolute_path__, m: module, e: exports }) { !function() {
+
+__turbopack_require__("[project]/crates/turbopack-tests/tests/snapshot/imports/dynamic-build/input/vercel.mjs (ecmascript, manifest chunk, loader)")(__turbopack_import__).then(console.log);
+
+}.call(this) }),
+
+};
+
+//# sourceMappingURL=crates_turbopack-tests_tests_snapshot_imports_dynamic-build_input_index_e254c5.js.map
\ No newline at end of file
@@ -0,0 +1,7 @@
+import cjs from "cjs-external";
+import { named } from "esm-external";
+import global, { render } from "global-external";
+
+console.log(cjs, named, global, render);
+
+import("global-external").then((namespace) => console.log(namespace.default));
@@ -0,0 +1,2 @@
+import img from "./vercel.svg";
+console.log(img);
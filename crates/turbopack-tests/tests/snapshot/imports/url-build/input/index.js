@@ -0,0 +1,5 @@
+import { readFileSync } from "fs";
+
+const url = new URL("./file.txt", import.meta.url);
+
+console.log(readFileSync(url, "utf-8"));
@@ -0,0 +1,5 @@
+import React, { useState } from "react";
+import { format } from "utils/format.js";
+import { legacy } from "./legacy/index.js";
+
+console.log(React, useState, format, legacy);
@@ -0,0 +1,10 @@
+/*! index.js | MIT License */
+import { greet } from "./lib";
+
+// This comment is removed
+function shout(message) {
+  const uppercased = message.toUpperCase();
+  return uppercased + "!";
+}
+
+console.log(shout(greet("world")));
@@ -0,0 +1,13 @@
+/**
+ * @license lib.js
+ * Licensed under the MIT license.
+ */
+
+export function greet(name) {
+  const greeting = "Hello, " + name;
+  return greeting;
+}
+
+export function unused() {
+  return "unused";
+}
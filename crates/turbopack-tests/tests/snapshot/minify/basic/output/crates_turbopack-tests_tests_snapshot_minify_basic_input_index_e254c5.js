@@ -0,0 +1,17 @@
+module.exports = {
+
+"[project]/crates/turbopack-tests/tests/snapshot/minify/basic/input/lib.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+/**
+ * @license lib.js
+ * Licensed under the MIT license.
+ */__turbopack_esm__({greet:()=>e});function e(e){return"Hello, "+e;}
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/minify/basic/input/index.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+/*! index.js | MIT License */console.log(function(t){let e=t.toUpperCase();return e+"!";}(__turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/minify/basic/input/lib.js (ecmascript)").greet("world")));
+})()),
+
+};
+
+//# sourceMappingURL=crates_turbopack-tests_tests_snapshot_minify_basic_input_index_e254c5.js.map
\ No newline at end of file
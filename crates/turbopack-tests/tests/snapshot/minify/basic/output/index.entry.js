@@ -0,0 +1,5 @@
+const CHUNK_PUBLIC_PATH = "output/index.entry.js";
+const runtime = require("./[turbopack]_runtime.js");
+runtime.loadChunk("output/crates_turbopack-tests_tests_snapshot_minify_basic_input_index_e254c5.js");
+runtime.getOrInstantiateRuntimeModule("[project]/crates/turbopack-tests/tests/snapshot/minify/basic/input/index.js (ecmascript)", CHUNK_PUBLIC_PATH);
+module.exports = runtime.getOrInstantiateRuntimeModule("[project]/crates/turbopack-tests/tests/snapshot/minify/basic/input/index.js (ecmascript)", CHUNK_PUBLIC_PATH).exports;
\ No newline at end of file
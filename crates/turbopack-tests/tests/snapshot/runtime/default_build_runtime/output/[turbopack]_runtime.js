@@ -71,25 +71,42 @@ const LEAF_PROTOTYPES = [
     getProto([]),
     getProto(getProto)
 ];
-function interopEsm(raw, ns, allowExportDefault) {
-    const getters = Object.create(null);
+function getExportNames(raw) {
+    const names = [];
     for(let current = raw; (typeof current === "object" || typeof current === "function") && !LEAF_PROTOTYPES.includes(current); current = getProto(current)){
         for (const key of Object.getOwnPropertyNames(current)){
-            getters[key] = createGetter(raw, key);
+            if (!names.includes(key)) names.push(key);
         }
     }
+    return names;
+}
+function interopEsm(raw, ns, allowExportDefault) {
+    const getters = Object.create(null);
+    for (const key of getExportNames(raw)){
+        getters[key] = createGetter(raw, key);
+    }
     if (!(allowExportDefault && "default" in getters)) {
         getters["default"] = ()=>raw;
     }
     esm(ns, getters);
 }
+function interopCommonJs(module, ns) {
+    const getters = Object.create(null);
+    for (const key of getExportNames(module.exports)){
+        getters[key] = ()=>module.exports[key];
+    }
+    getters["default"] = ()=>{
+        const raw = module.exports;
+        return raw != null && raw.__esModule && "default" in raw ? raw.default : raw;
+    };
+    esm(ns, getters);
+}
 function esmImport(sourceModule, id) {
     const module = getOrInstantiateModuleFromParent(id, sourceModule);
     if (module.error) throw module.error;
     if (module.namespaceObject) return module.namespaceObject;
-    const raw = module.exports;
     const ns = module.namespaceObject = {};
-    interopEsm(raw, ns, raw.__esModule);
+    interopCommonJs(module, ns);
     return ns;
 }
 function commonJsRequire(sourceModule, id) {
@@ -238,7 +255,7 @@ function instantiateModule(id, source) {
     }
     module1.loaded = true;
     if (module1.namespaceObject && module1.exports !== module1.namespaceObject) {
-        interopEsm(module1.exports, module1.namespaceObject);
+        interopCommonJs(module1, module1.namespaceObject);
     }
     return module1;
 }
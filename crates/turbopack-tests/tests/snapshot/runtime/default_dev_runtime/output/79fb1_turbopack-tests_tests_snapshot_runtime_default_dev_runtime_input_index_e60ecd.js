@@ -81,25 +81,42 @@ const LEAF_PROTOTYPES = [
     getProto([]),
     getProto(getProto)
 ];
-function interopEsm(raw, ns, allowExportDefault) {
-    const getters = Object.create(null);
+function getExportNames(raw) {
+    const names = [];
     for(let current = raw; (typeof current === "object" || typeof current === "function") && !LEAF_PROTOTYPES.includes(current); current = getProto(current)){
         for (const key of Object.getOwnPropertyNames(current)){
-            getters[key] = createGetter(raw, key);
+            if (!names.includes(key)) names.push(key);
         }
     }
+    return names;
+}
+function interopEsm(raw, ns, allowExportDefault) {
+    const getters = Object.create(null);
+    for (const key of getExportNames(raw)){
+        getters[key] = createGetter(raw, key);
+    }
     if (!(allowExportDefault && "default" in getters)) {
         getters["default"] = ()=>raw;
     }
     esm(ns, getters);
 }
+function interopCommonJs(module, ns) {
+    const getters = Object.create(null);
+    for (const key of getExportNames(module.exports)){
+        getters[key] = ()=>module.exports[key];
+    }
+    getters["default"] = ()=>{
+        const raw = module.exports;
+        return raw != null && raw.__esModule && "default" in raw ? raw.default : raw;
+    };
+    esm(ns, getters);
+}
 function esmImport(sourceModule, id) {
     const module = getOrInstantiateModuleFromParent(id, sourceModule);
     if (module.error) throw module.error;
     if (module.namespaceObject) return module.namespaceObject;
-    const raw = module.exports;
     const ns = module.namespaceObject = {};
-    interopEsm(raw, ns, raw.__esModule);
+    interopCommonJs(module, ns);
     return ns;
 }
 function commonJsRequire(sourceModule, id) {
@@ -300,7 +317,7 @@ function instantiateModule(id, source) {
     }
     module.loaded = true;
     if (module.namespaceObject && module.exports !== module.namespaceObject) {
-        interopEsm(module.exports, module.namespaceObject);
+        interopCommonJs(module, module.namespaceObject);
     }
     return module;
 }
@@ -934,6 +951,7 @@ globalThis.TURBOPACK_CHUNK_LISTS = {
         registerChunkList(globalThis.TURBOPACK_CHUNK_UPDATE_LISTENERS, chunkList);
     }
 };
+;
 let BACKEND;
 function augmentContext(context1) {
     return context1;
@@ -1049,6 +1067,16 @@ function commonJsRequireContext(entry1, sourceModule1) {
             return resolver1.promise;
         }
         const chunkUrl1 = `/${getChunkRelativeUrl(chunkPath1)}`;
+        if (typeof importScripts === "function") {
+            if (chunkPath1.endsWith(".css")) {
+                resolver1.resolve();
+            } else if (chunkPath1.endsWith(".js")) {
+                importScripts(chunkUrl1);
+            } else {
+                throw new Error(`can't infer type of chunk from path ${chunkPath1}`);
+            }
+            return resolver1.promise;
+        }
         if (chunkPath1.endsWith(".css")) {
             const link1 = document.createElement("link");
             link1.rel = "stylesheet";
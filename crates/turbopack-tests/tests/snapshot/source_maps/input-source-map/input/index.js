@@ -0,0 +1,4 @@
+import { add } from "./lib";
+import { greet } from "./inline";
+
+console.log(add(1, 2), greet("world"));
@@ -0,0 +1,4 @@
+export function greet(name) {
+  return "Hello, " + name;
+}
+//# sourceMappingURL=data:application/json;charset=utf-8;base64,eyJ2ZXJzaW9uIjogMywgImZpbGUiOiAiaW5saW5lLmpzIiwgInNvdXJjZXMiOiBbImlubGluZS5jb2ZmZWUiXSwgInNvdXJjZXNDb250ZW50IjogWyJleHBvcnQgZ3JlZXQgPSAobmFtZSkgLT5cbiAgXCJIZWxsbywgI3tuYW1lfVwiXG4iXSwgIm5hbWVzIjogW10sICJtYXBwaW5ncyI6ICJBQUFBO0FBQ0E7QUFEQSJ9
@@ -0,0 +1,6 @@
+(globalThis.TURBOPACK = globalThis.TURBOPACK || []).push([
+    "output/79fb1_turbopack-tests_tests_snapshot_source_maps_input-source-map_input_index_3b2482.js",
+    {},
+    {"otherChunks":[{"path":"output/79fb1_turbopack-tests_tests_snapshot_source_maps_input-source-map_input_index_b53fce.js","included":["[project]/crates/turbopack-tests/tests/snapshot/source_maps/input-source-map/input/index.js (ecmascript)"]}],"runtimeModuleIds":["[project]/crates/turbopack-tests/tests/snapshot/source_maps/input-source-map/input/index.js (ecmascript)"]}
+]);
+// Dummy runtime
\ No newline at end of file
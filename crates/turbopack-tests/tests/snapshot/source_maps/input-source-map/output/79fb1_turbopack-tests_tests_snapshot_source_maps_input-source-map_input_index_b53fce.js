@@ -0,0 +1,35 @@
+(globalThis.TURBOPACK = globalThis.TURBOPACK || []).push(["output/79fb1_turbopack-tests_tests_snapshot_source_maps_input-source-map_input_index_b53fce.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/source_maps/input-source-map/input/inline.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, k: __turbopack_refresh__ }) => (() => {
+
+__turbopack_esm__({
+    "greet": ()=>greet
+});
+function greet(name) {
+    return "Hello, " + name;
+}
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/source_maps/input-source-map/input/lib.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, k: __turbopack_refresh__ }) => (() => {
+
+__turbopack_esm__({
+    "add": ()=>add
+});
+function add(a, b) {
+    return a + b;
+}
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/source_maps/input-source-map/input/index.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, k: __turbopack_refresh__ }) => (() => {
+
+var __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$source_maps$2f$input$2d$source$2d$map$2f$input$2f$lib$2e$js__$28$ecmascript$29$__ = __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/source_maps/input-source-map/input/lib.js (ecmascript)");
+var __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$source_maps$2f$input$2d$source$2d$map$2f$input$2f$inline$2e$js__$28$ecmascript$29$__ = __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/source_maps/input-source-map/input/inline.js (ecmascript)");
+"__TURBOPACK__ecmascript__hoisting__location__";
+;
+;
+console.log(__TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$source_maps$2f$input$2d$source$2d$map$2f$input$2f$lib$2e$js__$28$ecmascript$29$__["add"](1, 2), __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$source_maps$2f$input$2d$source$2d$map$2f$input$2f$inline$2e$js__$28$ecmascript$29$__["greet"]("world"));
+
+})()),
+}]);
+
+//# sourceMappingURL=79fb1_turbopack-tests_tests_snapshot_source_maps_input-source-map_input_index_b53fce.js.map
\ No newline at end of file
@@ -0,0 +1,3 @@
+import { used } from "./data.json";
+
+console.log(used);
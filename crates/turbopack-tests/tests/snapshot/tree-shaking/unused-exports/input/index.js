@@ -0,0 +1,5 @@
+import { used, reexported } from "./lib";
+import * as namespace from "./namespace";
+
+console.log(used, reexported, namespace);
+import("./dynamic").then((dynamic) => console.log(dynamic));
@@ -0,0 +1,5 @@
+export { renamed as reexported } from "./reexported";
+export * from "./star";
+
+export const used = "used";
+export const unused = "unused";
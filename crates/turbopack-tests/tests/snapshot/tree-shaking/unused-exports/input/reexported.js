@@ -0,0 +1,2 @@
+export const renamed = "renamed";
+export const unusedReexport = "unused";
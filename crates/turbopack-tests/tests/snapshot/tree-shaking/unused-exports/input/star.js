@@ -0,0 +1 @@
+export const unusedStar = "unused";
@@ -0,0 +1,11 @@
+module.exports = {
+
+"[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/dynamic.js (ecmascript, manifest chunk)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_export_value__([
+  "output/79fb1_turbopack-tests_tests_snapshot_tree-shaking_unused-exports_input_dynamic_8c0ec0.js"
+]);
+
+})()),
+
+};
\ No newline at end of file
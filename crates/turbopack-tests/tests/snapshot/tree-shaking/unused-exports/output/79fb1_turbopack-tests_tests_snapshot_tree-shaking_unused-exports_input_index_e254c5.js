@@ -0,0 +1,70 @@
+module.exports = {
+
+"[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/dynamic.js (ecmascript, manifest chunk, loader)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_export_value__((__turbopack_import__) => {
+    return Promise.all(["output/79fb1_turbopack-tests_tests_snapshot_tree-shaking_unused-exports_input_dynamic_2f053e.js"].map((chunk) => __turbopack_load__(chunk))).then(() => {
+        return __turbopack_require__("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/dynamic.js (ecmascript, manifest chunk)");
+    }).then((chunks) => {
+        return Promise.all(chunks.map((chunk) => __turbopack_load__(chunk)));
+    }).then(() => {
+        return __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/dynamic.js (ecmascript)");
+    });
+});
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/namespace.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({
+    "a": ()=>a,
+    "b": ()=>b
+});
+const a = "a";
+const b = "b";
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/star.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({});
+const unusedStar = "unused";
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/reexported.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({
+    "renamed": ()=>renamed
+});
+const renamed = "renamed";
+const unusedReexport = "unused";
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/lib.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_esm__({
+    "reexported": ()=>__TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$reexported$2e$js__$28$ecmascript$29$__["renamed"],
+    "used": ()=>used
+});
+var __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$reexported$2e$js__$28$ecmascript$29$__ = __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/reexported.js (ecmascript)");
+var __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$star$2e$js__$28$ecmascript$29$__ = __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/star.js (ecmascript)");
+"__TURBOPACK__ecmascript__hoisting__location__";
+;
+;
+const used = "used";
+const unused = "unused";
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/index.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+var __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$lib$2e$js__$28$ecmascript$29$__ = __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/lib.js (ecmascript)");
+var __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$namespace$2e$js__$28$ecmascript$29$__ = __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/namespace.js (ecmascript)");
+"__TURBOPACK__ecmascript__hoisting__location__";
+;
+;
+console.log(__TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$lib$2e$js__$28$ecmascript$29$__["used"], __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$lib$2e$js__$28$ecmascript$29$__["reexported"], __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$tree$2d$shaking$2f$unused$2d$exports$2f$input$2f$namespace$2e$js__$28$ecmascript$29$__);
+__turbopack_require__("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/dynamic.js (ecmascript, manifest chunk, loader)")(__turbopack_import__).then((dynamic)=>console.log(dynamic));
+
+})()),
+
+};
+
+//# sourceMappingURL=79fb1_turbopack-tests_tests_snapshot_tree-shaking_unused-exports_input_index_e254c5.js.map
\ No newline at end of file
@@ -0,0 +1,2 @@
+const RUNTIME_PUBLIC_PATH = "output/[turbopack]_runtime.js";
+// Dummy runtime
\ No newline at end of file
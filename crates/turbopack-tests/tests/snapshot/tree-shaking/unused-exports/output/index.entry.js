@@ -0,0 +1,5 @@
+const CHUNK_PUBLIC_PATH = "output/index.entry.js";
+const runtime = require("./[turbopack]_runtime.js");
+runtime.loadChunk("output/79fb1_turbopack-tests_tests_snapshot_tree-shaking_unused-exports_input_index_e254c5.js");
+runtime.getOrInstantiateRuntimeModule("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/index.js (ecmascript)", CHUNK_PUBLIC_PATH);
+module.exports = runtime.getOrInstantiateRuntimeModule("[project]/crates/turbopack-tests/tests/snapshot/tree-shaking/unused-exports/input/index.js (ecmascript)", CHUNK_PUBLIC_PATH).exports;
\ No newline at end of file
@@ -0,0 +1,3 @@
+const App = () => <div>Hello from Preact</div>;
+
+console.log(<App />);
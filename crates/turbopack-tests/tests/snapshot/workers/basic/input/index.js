@@ -0,0 +1,10 @@
+const worker = new Worker(new URL("./worker.js", import.meta.url), {
+  type: "module",
+  name: "greeter",
+});
+worker.postMessage("turbopack");
+
+const sharedWorker = new SharedWorker(new URL("./worker.js", import.meta.url));
+sharedWorker.port.start();
+
+CSS.paintWorklet.addModule(new URL("./worklet.js", import.meta.url));
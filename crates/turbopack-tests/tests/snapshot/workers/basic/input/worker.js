@@ -0,0 +1,5 @@
+import { greet } from "./greet";
+
+self.onmessage = (event) => {
+  self.postMessage(greet(event.data));
+};
@@ -0,0 +1,8 @@
+registerPaint(
+  "checkerboard",
+  class {
+    paint(ctx, size) {
+      ctx.fillRect(0, 0, size.width / 2, size.height / 2);
+    }
+  }
+);
@@ -0,0 +1,32 @@
+(globalThis.TURBOPACK = globalThis.TURBOPACK || []).push(["output/crates_turbopack-tests_tests_snapshot_workers_basic_input_index_b53fce.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worklet.js (ecmascript, worklet)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_export_value__("/crates/turbopack-tests/tests/snapshot/workers/basic/output/crates_turbopack-tests_tests_snapshot_workers_basic_input_worklet_518acf.js");
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worker.js (ecmascript, shared worker)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_export_value__("/crates/turbopack-tests/tests/snapshot/workers/basic/output/crates_turbopack-tests_tests_snapshot_workers_basic_input_worker_7d6b97.js");
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worker.js (ecmascript, worker)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname }) => (() => {
+
+__turbopack_export_value__("/crates/turbopack-tests/tests/snapshot/workers/basic/output/crates_turbopack-tests_tests_snapshot_workers_basic_input_worker_26f705.js");
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/index.js (ecmascript)": (function({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, k: __turbopack_refresh__, m: module, e: exports }) { !function() {
+
+const __TURBOPACK__import$2e$meta__ = {
+    url: "file:///ROOT/crates/turbopack-tests/tests/snapshot/workers/basic/input/index.js"
+};
+"__TURBOPACK__ecmascript__hoisting__location__";
+const worker = new Worker(new URL(__turbopack_require__("[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worker.js (ecmascript, worker)"), location.origin), {
+    name: "greeter"
+});
+worker.postMessage("turbopack");
+const sharedWorker = new SharedWorker(new URL(__turbopack_require__("[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worker.js (ecmascript, shared worker)"), location.origin));
+sharedWorker.port.start();
+CSS.paintWorklet.addModule(new URL(__turbopack_require__("[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worklet.js (ecmascript, worklet)"), location.origin));
+
+}.call(this) }),
+}]);
+
+//# sourceMappingURL=crates_turbopack-tests_tests_snapshot_workers_basic_input_index_b53fce.js.map
\ No newline at end of file
@@ -0,0 +1,5 @@
+importScripts(
+    "./crates_turbopack-tests_tests_snapshot_workers_basic_input_worker_b53fce.js",
+    "./crates_turbopack-tests_tests_snapshot_workers_basic_input_worker_5771e1.js",
+    "./crates_turbopack-tests_tests_snapshot_workers_basic_input_worker_e14c08.js",
+);
\ No newline at end of file
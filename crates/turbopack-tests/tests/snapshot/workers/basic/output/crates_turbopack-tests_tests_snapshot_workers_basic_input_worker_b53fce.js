@@ -0,0 +1,23 @@
+(globalThis.TURBOPACK = globalThis.TURBOPACK || []).push(["output/crates_turbopack-tests_tests_snapshot_workers_basic_input_worker_b53fce.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/greet.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, k: __turbopack_refresh__ }) => (() => {
+
+__turbopack_esm__({
+    "greet": ()=>greet
+});
+const greet = (name)=>`hello ${name}`;
+
+})()),
+"[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worker.js (ecmascript)": (({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, k: __turbopack_refresh__ }) => (() => {
+
+var __TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$workers$2f$basic$2f$input$2f$greet$2e$js__$28$ecmascript$29$__ = __turbopack_import__("[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/greet.js (ecmascript)");
+"__TURBOPACK__ecmascript__hoisting__location__";
+;
+self.onmessage = (event)=>{
+    self.postMessage(__TURBOPACK__imported__module__$5b$project$5d2f$crates$2f$turbopack$2d$tests$2f$tests$2f$snapshot$2f$workers$2f$basic$2f$input$2f$greet$2e$js__$28$ecmascript$29$__["greet"](event.data));
+};
+
+})()),
+}]);
+
+//# sourceMappingURL=crates_turbopack-tests_tests_snapshot_workers_basic_input_worker_b53fce.js.map
\ No newline at end of file
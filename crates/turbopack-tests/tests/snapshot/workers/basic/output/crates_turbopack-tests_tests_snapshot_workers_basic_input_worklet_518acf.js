@@ -0,0 +1,3 @@
+import "./crates_turbopack-tests_tests_snapshot_workers_basic_input_worklet_b53fce.js";
+import "./crates_turbopack-tests_tests_snapshot_workers_basic_input_worklet_5771e1.js";
+import "./crates_turbopack-tests_tests_snapshot_workers_basic_input_worklet_1416c2.js";
\ No newline at end of file
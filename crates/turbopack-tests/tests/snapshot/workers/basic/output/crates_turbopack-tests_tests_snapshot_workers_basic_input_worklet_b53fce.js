@@ -0,0 +1,14 @@
+(globalThis.TURBOPACK = globalThis.TURBOPACK || []).push(["output/crates_turbopack-tests_tests_snapshot_workers_basic_input_worklet_b53fce.js", {
+
+"[project]/crates/turbopack-tests/tests/snapshot/workers/basic/input/worklet.js (ecmascript)": (function({ r: __turbopack_require__, f: __turbopack_require_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, l: __turbopack_load__, j: __turbopack_dynamic__, g: global, __dirname, k: __turbopack_refresh__, m: module, e: exports }) { !function() {
+
+registerPaint("checkerboard", class {
+    paint(ctx, size) {
+        ctx.fillRect(0, 0, size.width / 2, size.height / 2);
+    }
+});
+
+}.call(this) }),
+}]);
+
+//# sourceMappingURL=crates_turbopack-tests_tests_snapshot_workers_basic_input_worklet_b53fce.js.map
\ No newline at end of file
@@ -0,0 +1,55 @@
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+use turbo_tasks_fs::FileContent;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    source::SourceVc,
+};
+use wasmparser::{Parser, Payload};
+
+/// The imports and exports of a WebAssembly module.
+#[turbo_tasks::value]
+#[derive(Default)]
+pub(crate) struct WebAssemblyAnalysis {
+    /// The names of the imported items, by the module they are imported from.
+    pub imports: IndexMap<String, Vec<String>>,
+    pub exports: Vec<String>,
+}
+
+/// Reads the import and export sections of the WebAssembly module in
+/// `source`.
+#[turbo_tasks::function]
+pub(crate) async fn analyze(source: SourceVc) -> Result<WebAssemblyAnalysisVc> {
+    let content = source.content().await?;
+    let AssetContent::File(file) = &*content else {
+        bail!("WebAssembly module must be a file");
+    };
+    let FileContent::Content(file) = &*file.await? else {
+        bail!("WebAssembly module file not found");
+    };
+    let bytes = file.content().to_bytes()?;
+
+    let mut analysis = WebAssemblyAnalysis::default();
+    for payload in Parser::new(0).parse_all(&bytes) {
+        match payload? {
+            Payload::ImportSection(imports) => {
+                for import in imports {
+                    let import = import?;
+                    analysis
+                        .imports
+                        .entry(import.module.to_string())
+                        .or_default()
+                        .push(import.name.to_string());
+                }
+            }
+            Payload::ExportSection(exports) => {
+                for export in exports {
+                    analysis.exports.push(export?.name.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(analysis.cell())
+}
@@ -0,0 +1,23 @@
+//! WebAssembly support for turbopack.
+//!
+//! WebAssembly modules are emitted to the output folder as they are.
+//!
+//! When imported from ES modules, they produce an async module which
+//! instantiates the WebAssembly module and exports its exports. The imports of
+//! the WebAssembly module are resolved like ESM imports and passed to it on
+//! instantiation.
+
+#![feature(min_specialization)]
+
+pub(crate) mod analysis;
+pub mod module_asset;
+pub(crate) mod output_asset;
+pub mod references;
+
+pub fn register() {
+    turbo_tasks::register();
+    turbo_tasks_fs::register();
+    turbopack_core::register();
+    turbopack_ecmascript::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
@@ -0,0 +1,294 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use turbo_tasks::{
+    primitives::{BoolVc, StringVc},
+    Value, ValueToString,
+};
+use turbo_tasks_fs::FileSystemPathVc;
+use turbopack_core::{
+    asset::{Asset, AssetContentVc},
+    chunk::{
+        availability_info::AvailabilityInfo, ChunkItem, ChunkItemVc, ChunkVc, ChunkableModule,
+        ChunkableModuleVc, ChunkingContext, ChunkingContextVc,
+    },
+    context::AssetContextVc,
+    ident::AssetIdentVc,
+    module::{Module, ModuleVc},
+    reference::{AssetReference, AssetReferencesVc, SingleAssetReferenceVc},
+    resolve::{
+        origin::{ResolveOrigin, ResolveOriginVc},
+        parse::RequestVc,
+    },
+    source::SourceVc,
+};
+use turbopack_ecmascript::{
+    chunk::{
+        AsyncModuleOptions, EcmascriptChunkItem, EcmascriptChunkItemContent,
+        EcmascriptChunkItemContentVc, EcmascriptChunkItemOptions, EcmascriptChunkItemVc,
+        EcmascriptChunkPlaceable, EcmascriptChunkPlaceableVc, EcmascriptChunkVc,
+        EcmascriptChunkingContextVc, EcmascriptExports, EcmascriptExportsVc,
+    },
+    utils::StringifyJs,
+};
+
+use crate::{
+    analysis::analyze, output_asset::WebAssemblyAssetVc, references::WebAssemblyImportReferenceVc,
+};
+
+#[turbo_tasks::function]
+fn modifier() -> StringVc {
+    StringVc::cell("wasm module".to_string())
+}
+
+/// A WebAssembly module imported from an ES module. Its exports are available
+/// as named exports once the module has been instantiated asynchronously.
+#[turbo_tasks::value]
+#[derive(Clone)]
+pub struct WebAssemblyModuleAsset {
+    pub source: SourceVc,
+    pub context: AssetContextVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WebAssemblyModuleAssetVc {
+    #[turbo_tasks::function]
+    pub fn new(source: SourceVc, context: AssetContextVc) -> Self {
+        Self::cell(WebAssemblyModuleAsset { source, context })
+    }
+
+    #[turbo_tasks::function]
+    async fn wasm_asset(self, context: ChunkingContextVc) -> Result<WebAssemblyAssetVc> {
+        Ok(WebAssemblyAssetVc::new(self.await?.source, context))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WebAssemblyModuleAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> AssetIdentVc {
+        self.source.ident().with_modifier(modifier())
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        self.source.content()
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self_vc: WebAssemblyModuleAssetVc) -> Result<AssetReferencesVc> {
+        let this = self_vc.await?;
+        let analysis = analyze(this.source).await?;
+        Ok(AssetReferencesVc::cell(
+            analysis
+                .imports
+                .keys()
+                .map(|module| {
+                    WebAssemblyImportReferenceVc::new(
+                        self_vc.into(),
+                        RequestVc::parse(Value::new(module.to_string().into())),
+                    )
+                    .into()
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Module for WebAssemblyModuleAsset {}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModule for WebAssemblyModuleAsset {
+    #[turbo_tasks::function]
+    fn as_chunk(
+        self_vc: WebAssemblyModuleAssetVc,
+        context: ChunkingContextVc,
+        availability_info: Value<AvailabilityInfo>,
+    ) -> ChunkVc {
+        EcmascriptChunkVc::new(
+            context,
+            self_vc.as_ecmascript_chunk_placeable(),
+            availability_info,
+        )
+        .into()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for WebAssemblyModuleAsset {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self_vc: WebAssemblyModuleAssetVc,
+        context: EcmascriptChunkingContextVc,
+    ) -> EcmascriptChunkItemVc {
+        ModuleChunkItemVc::cell(ModuleChunkItem {
+            module: self_vc,
+            context,
+            wasm_asset: self_vc.wasm_asset(context.into()),
+        })
+        .into()
+    }
+
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> EcmascriptExportsVc {
+        EcmascriptExports::DynamicNamespace.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn is_self_async(&self) -> BoolVc {
+        BoolVc::cell(true)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ResolveOrigin for WebAssemblyModuleAsset {
+    #[turbo_tasks::function]
+    fn origin_path(&self) -> FileSystemPathVc {
+        self.source.ident().path()
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> AssetContextVc {
+        self.context
+    }
+}
+
+#[turbo_tasks::value]
+struct ModuleChunkItem {
+    module: WebAssemblyModuleAssetVc,
+    context: EcmascriptChunkingContextVc,
+    wasm_asset: WebAssemblyAssetVc,
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for ModuleChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> AssetIdentVc {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    async fn references(&self) -> Result<AssetReferencesVc> {
+        let mut references = self.module.references().await?.clone_value();
+        references.push(
+            SingleAssetReferenceVc::new(
+                self.wasm_asset.into(),
+                StringVc::cell(format!(
+                    "wasm(url) {}",
+                    self.wasm_asset.ident().to_string().await?
+                )),
+            )
+            .into(),
+        );
+        Ok(AssetReferencesVc::cell(references))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for ModuleChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> EcmascriptChunkingContextVc {
+        self.context
+    }
+
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<EcmascriptChunkItemContentVc> {
+        let analysis = analyze(self.module.await?.source).await?;
+        let references = self.module.references().await?;
+
+        let output_root = self.context.output_root().await?;
+        let wasm_path = self.wasm_asset.ident().path().await?;
+        let Some(wasm_path) = output_root.get_path_to(&wasm_path) else {
+            bail!(
+                "WebAssembly file {} is not in the output directory",
+                wasm_path
+            );
+        };
+
+        let mut code = String::new();
+
+        // The exports are only accessed once the async module has been
+        // evaluated, so they can refer to the instance's exports, which are
+        // assigned below.
+        writeln!(code, "__turbopack_esm__({{")?;
+        for export in &analysis.exports {
+            writeln!(
+                code,
+                "    {name}: () => __turbopack_wasm_exports__[{name}],",
+                name = StringifyJs(export)
+            )?;
+        }
+        writeln!(code, "}});")?;
+
+        let mut idents = Vec::new();
+        for ((module, _), reference) in analysis.imports.iter().zip(references.iter()) {
+            let Some(asset) = *reference.resolve_reference().first_asset().await? else {
+                bail!(
+                    "unable to resolve the import {} of a WebAssembly module",
+                    module
+                );
+            };
+            let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(asset).await? else {
+                bail!(
+                    "the import {} of a WebAssembly module is not an ES module",
+                    module
+                );
+            };
+            let id = placeable.as_chunk_item(self.context).id().await?;
+            let ident = format!("__turbopack_wasm_import_{}__", idents.len());
+            writeln!(
+                code,
+                "var {ident} = __turbopack_import__({id});",
+                id = StringifyJs(&*id)
+            )?;
+            idents.push(ident);
+        }
+
+        // Imported modules might be async modules themselves.
+        if !idents.is_empty() {
+            let idents = idents.join(", ");
+            writeln!(
+                code,
+                "var __turbopack_async_dependencies__ = \
+                 __turbopack_handle_async_dependencies__([{idents}]);"
+            )?;
+            writeln!(
+                code,
+                "[{idents}] = __turbopack_async_dependencies__.then ? (await \
+                 __turbopack_async_dependencies__)() : __turbopack_async_dependencies__;"
+            )?;
+        }
+
+        writeln!(
+            code,
+            "var __turbopack_wasm_exports__ = await __turbopack_wasm__({path}, {{",
+            path = StringifyJs(wasm_path)
+        )?;
+        for (index, (module, names)) in analysis.imports.iter().enumerate() {
+            writeln!(code, "    {}: {{", StringifyJs(module))?;
+            for name in names {
+                writeln!(
+                    code,
+                    "        {name}: __turbopack_wasm_import_{index}__[{name}],",
+                    name = StringifyJs(name)
+                )?;
+            }
+            writeln!(code, "    }},")?;
+        }
+        writeln!(code, "}});")?;
+
+        Ok(EcmascriptChunkItemContent {
+            inner_code: code.into(),
+            options: EcmascriptChunkItemOptions {
+                wasm: true,
+                async_module: Some(AsyncModuleOptions {
+                    has_top_level_await: true,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .into())
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::{bail, Result};
+use turbo_tasks_fs::FileContent;
+use turbopack_core::{
+    asset::{Asset, AssetContent, AssetContentVc},
+    chunk::{ChunkingContext, ChunkingContextVc},
+    ident::AssetIdentVc,
+    source::SourceVc,
+};
+
+/// The `.wasm` file of a WebAssembly module, which is emitted to the output
+/// directory and fetched by the runtime when the module is instantiated.
+#[turbo_tasks::value]
+pub(crate) struct WebAssemblyAsset {
+    context: ChunkingContextVc,
+    source: SourceVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WebAssemblyAssetVc {
+    #[turbo_tasks::function]
+    pub(crate) fn new(source: SourceVc, context: ChunkingContextVc) -> Self {
+        WebAssemblyAsset { context, source }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WebAssemblyAsset {
+    #[turbo_tasks::function]
+    async fn ident(&self) -> Result<AssetIdentVc> {
+        let content = self.source.content();
+        let content_hashing = self.context.content_hashing().await?;
+        let content_hash = if let AssetContent::File(file) = &*content.await? {
+            if let FileContent::Content(file) = &*file.await? {
+                content_hashing.hash(file.content())?
+            } else {
+                bail!("WebAssemblyAsset::path: not found");
+            }
+        } else {
+            bail!("WebAssemblyAsset::path: unsupported file content");
+        };
+        let asset_path = self.context.asset_path(&content_hash, self.source.ident());
+        Ok(AssetIdentVc::from_path(asset_path))
+    }
+
+    #[turbo_tasks::function]
+    fn content(&self) -> AssetContentVc {
+        self.source.content()
+    }
+}
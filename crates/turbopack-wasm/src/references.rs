@@ -0,0 +1,55 @@
+use anyhow::Result;
+use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
+use turbopack_core::{
+    chunk::{ChunkableModuleReference, ChunkableModuleReferenceVc},
+    issue::{IssueSeverity, OptionIssueSourceVc},
+    reference::{AssetReference, AssetReferenceVc},
+    reference_type::EcmaScriptModulesReferenceSubType,
+    resolve::{origin::ResolveOriginVc, parse::RequestVc, ResolveResultVc},
+};
+use turbopack_ecmascript::resolve::esm_resolve;
+
+/// A module imported by a WebAssembly module. The module's exports are passed
+/// to the WebAssembly module when it is instantiated.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct WebAssemblyImportReference {
+    pub origin: ResolveOriginVc,
+    pub request: RequestVc,
+}
+
+#[turbo_tasks::value_impl]
+impl WebAssemblyImportReferenceVc {
+    #[turbo_tasks::function]
+    pub fn new(origin: ResolveOriginVc, request: RequestVc) -> Self {
+        Self::cell(WebAssemblyImportReference { origin, request })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AssetReference for WebAssemblyImportReference {
+    #[turbo_tasks::function]
+    fn resolve_reference(&self) -> ResolveResultVc {
+        esm_resolve(
+            self.origin,
+            self.request,
+            Value::new(EcmaScriptModulesReferenceSubType::Undefined),
+            OptionIssueSourceVc::none(),
+            IssueSeverity::Error.cell(),
+        )
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for WebAssemblyImportReference {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<StringVc> {
+        Ok(StringVc::cell(format!(
+            "wasm import {}",
+            self.request.to_string().await?,
+        )))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for WebAssemblyImportReference {}
@@ -55,6 +55,7 @@ pub async fn node_evaluate_asset_context(
                 compile_time_defines!(
                     process.turbopack = true,
                     process.env.NODE_ENV = node_env.clone(),
+                    typeof window = "undefined",
                 )
                 .cell(),
             )
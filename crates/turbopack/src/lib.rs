@@ -24,11 +24,11 @@ use module_options::{
 pub use resolve::resolve_options;
 use turbo_tasks::{
     primitives::{BoolVc, StringVc},
-    CompletionVc, Value,
+    CompletionVc, CompletionsVc, Value,
 };
 use turbo_tasks_fs::FileSystemPathVc;
 use turbopack_core::{
-    asset::{Asset, AssetVc},
+    asset::{Asset, AssetVc, AssetsVc},
     compile_time_info::CompileTimeInfoVc,
     context::{AssetContext, AssetContextVc},
     ident::AssetIdentVc,
@@ -53,6 +53,7 @@ pub mod module_options;
 pub mod rebase;
 pub mod resolve;
 pub mod resolve_options_context;
+pub mod resolve_overrides;
 pub mod transition;
 pub(crate) mod unsupported_sass;
 
@@ -61,6 +62,7 @@ pub use turbopack_ecmascript as ecmascript;
 use turbopack_json::JsonModuleAssetVc;
 use turbopack_mdx::MdxModuleAssetVc;
 use turbopack_static::StaticModuleAssetVc;
+use turbopack_wasm::module_asset::WebAssemblyModuleAssetVc;
 
 use self::{
     module_options::CustomModuleType,
@@ -174,6 +176,7 @@ async fn apply_module_type(
             CssModuleAssetVc::new(source, context.into(), *transforms, *ty).into()
         }
         ModuleType::Static => StaticModuleAssetVc::new(source, context.into()).into(),
+        ModuleType::WebAssembly => WebAssemblyModuleAssetVc::new(source, context.into()).into(),
         ModuleType::Mdx {
             transforms,
             options,
@@ -524,14 +527,33 @@ async fn emit_aggregated_assets(
     Ok(match &*aggregated.content().await? {
         AggregatedGraphNodeContent::Asset(asset) => emit_asset_into_dir(*asset, output_dir),
         AggregatedGraphNodeContent::Children(children) => {
-            for aggregated in children {
-                emit_aggregated_assets(*aggregated, output_dir).await?;
-            }
-            CompletionVc::new()
+            // All children are emitted in parallel, the number of concurrent
+            // disk writes is limited by the file system.
+            CompletionsVc::cell(
+                children
+                    .iter()
+                    .map(|aggregated| emit_aggregated_assets(*aggregated, output_dir))
+                    .collect(),
+            )
+            .completed()
         }
     })
 }
 
+/// Emits a batch of `assets` into `output_dir` in parallel. Assets outside of
+/// `output_dir` are skipped, as are assets whose content is unchanged on disk.
+#[turbo_tasks::function]
+pub async fn write_out(assets: AssetsVc, output_dir: FileSystemPathVc) -> Result<CompletionVc> {
+    Ok(CompletionsVc::cell(
+        assets
+            .await?
+            .iter()
+            .map(|asset| emit_asset_into_dir(*asset, output_dir))
+            .collect(),
+    )
+    .completed())
+}
+
 #[turbo_tasks::function]
 pub async fn emit_asset(asset: AssetVc) -> CompletionVc {
     asset.content().write(asset.ident().path())
@@ -618,10 +640,12 @@ pub fn register() {
     turbopack_core::register();
     turbopack_css::register();
     turbopack_ecmascript::register();
+    turbopack_ecmascript_plugins::register();
     turbopack_node::register();
     turbopack_env::register();
     turbopack_mdx::register();
     turbopack_json::register();
     turbopack_static::register();
+    turbopack_wasm::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
 }
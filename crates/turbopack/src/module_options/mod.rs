@@ -9,18 +9,34 @@ pub use module_options_context::*;
 pub use module_rule::*;
 pub use rule_condition::*;
 use turbo_tasks::primitives::OptionStringVc;
-use turbo_tasks_fs::{glob::GlobVc, FileSystemPathVc};
+use turbo_tasks_fs::{glob::GlobVc, FileJsonContentVc, FileSystemPathVc};
 use turbopack_core::{
+    file_source::FileSourceVc,
     reference_type::{CssReferenceSubType, ReferenceType, UrlReferenceSubType},
-    resolve::options::{ImportMap, ImportMapVc, ImportMapping, ImportMappingVc},
+    resolve::{
+        find_context_file,
+        node::node_cjs_resolve_options,
+        options::{ImportMap, ImportMapVc, ImportMapping, ImportMappingVc},
+        FindContextFileResult,
+    },
+    source::SourceVc,
     source_transform::SourceTransformsVc,
 };
 use turbopack_css::{CssInputTransform, CssInputTransformsVc, CssModuleAssetType};
 use turbopack_ecmascript::{
+    typescript::resolve::{read_from_tsconfigs, read_tsconfigs, tsconfig},
     EcmascriptInputTransform, EcmascriptInputTransformsVc, EcmascriptOptions, SpecifiedModuleType,
+    TransformPluginVc,
+};
+use turbopack_ecmascript_plugins::transform::{
+    styled_components::StyledComponentsTransformer, styled_jsx::StyledJsxTransformer,
+    swc_ecma_transform_plugins::SwcEcmaTransformPluginsTransformer,
 };
 use turbopack_mdx::MdxTransformOptions;
-use turbopack_node::transforms::{postcss::PostCssTransformVc, webpack::WebpackLoadersVc};
+use turbopack_node::transforms::{
+    postcss::PostCssTransformVc,
+    webpack::{WebpackLoaderItem, WebpackLoaderItemsVc, WebpackLoadersVc},
+};
 
 use crate::evaluate_context::node_evaluate_asset_context;
 
@@ -50,6 +66,118 @@ async fn package_import_map_from_context(
     Ok(import_map.cell())
 }
 
+/// Reads the tsconfig.json or jsconfig.json which applies to `path`, including
+/// the configs it extends.
+async fn context_tsconfigs(path: FileSystemPathVc) -> Result<Vec<(FileJsonContentVc, SourceVc)>> {
+    let FindContextFileResult::Found(tsconfig_path, _) =
+        *find_context_file(path, tsconfig()).await?
+    else {
+        return Ok(Vec::new());
+    };
+    read_tsconfigs(
+        tsconfig_path.read(),
+        FileSourceVc::new(tsconfig_path).into(),
+        node_cjs_resolve_options(tsconfig_path.root()),
+    )
+    .await
+}
+
+/// Returns true when the `target` of the tsconfigs is ES2022 or later. Like in
+/// TypeScript, `useDefineForClassFields` defaults to true for these targets.
+async fn targets_es2022(configs: &[(FileJsonContentVc, SourceVc)]) -> Result<bool> {
+    let target = read_from_tsconfigs(configs, |json, _| {
+        json["compilerOptions"]["target"]
+            .as_str()
+            .map(|s| s.to_ascii_lowercase())
+    })
+    .await?;
+    Ok(match target.as_deref().and_then(|t| t.strip_prefix("es")) {
+        Some("next") => true,
+        Some(version) => version.parse::<u32>().map_or(false, |v| v >= 2022),
+        None => false,
+    })
+}
+
+/// Reads the decorators options from the `compilerOptions` of the
+/// tsconfig.json or jsconfig.json which applies to `path`. There are none
+/// unless `experimentalDecorators` is enabled.
+#[turbo_tasks::function]
+async fn tsconfig_decorators_options(path: FileSystemPathVc) -> Result<OptionDecoratorsOptionsVc> {
+    let configs = context_tsconfigs(path).await?;
+    let compiler_option = |name: &'static str| {
+        read_from_tsconfigs(&configs, move |json, _| {
+            json["compilerOptions"][name].as_bool()
+        })
+    };
+
+    if !compiler_option("experimentalDecorators")
+        .await?
+        .unwrap_or(false)
+    {
+        return Ok(OptionDecoratorsOptionsVc::cell(None));
+    }
+    Ok(OptionDecoratorsOptionsVc::cell(Some(
+        DecoratorsOptions {
+            decorators_kind: Some(DecoratorsKind::Legacy),
+            emit_decorators_metadata: compiler_option("emitDecoratorMetadata")
+                .await?
+                .unwrap_or(false),
+            use_define_for_class_fields: match compiler_option("useDefineForClassFields").await? {
+                Some(use_define_for_class_fields) => use_define_for_class_fields,
+                None => targets_es2022(&configs).await?,
+            },
+            ..Default::default()
+        }
+        .cell(),
+    )))
+}
+
+/// Completes the JSX options with the `jsx` and `jsxImportSource`
+/// `compilerOptions` of the tsconfig.json or jsconfig.json which applies to
+/// `path`. Options which are set explicitly take precedence.
+#[turbo_tasks::function]
+async fn tsconfig_jsx_options(
+    path: FileSystemPathVc,
+    options: JsxTransformOptionsVc,
+) -> Result<JsxTransformOptionsVc> {
+    let configs = context_tsconfigs(path).await?;
+    let mut options = options.await?.clone_value();
+
+    let jsx = read_from_tsconfigs(&configs, |json, _| {
+        json["compilerOptions"]["jsx"]
+            .as_str()
+            .map(|s| s.to_string())
+    })
+    .await?;
+    match jsx.as_deref() {
+        Some("react") => {
+            options.runtime.get_or_insert_with(|| "classic".to_string());
+        }
+        Some("react-jsx") => {
+            options
+                .runtime
+                .get_or_insert_with(|| "automatic".to_string());
+        }
+        Some("react-jsxdev") => {
+            options
+                .runtime
+                .get_or_insert_with(|| "automatic".to_string());
+            options.development = true;
+        }
+        _ => {}
+    }
+    if options.import_source.is_none() {
+        options.import_source = read_from_tsconfigs(&configs, |json, _| {
+            json["compilerOptions"]["jsxImportSource"]
+                .as_str()
+                .map(|s| s.to_string())
+        })
+        .await?;
+    }
+
+    Ok(options.cell())
+}
+
 #[turbo_tasks::value(cell = "new", eq = "manual")]
 pub struct ModuleOptions {
     pub rules: Vec<ModuleRule>,
@@ -64,17 +192,22 @@ impl ModuleOptionsVc {
     ) -> Result<ModuleOptionsVc> {
         let ModuleOptionsContext {
             enable_jsx,
+            enable_styled_jsx,
+            enable_styled_components,
+            enable_import_meta_glob,
             enable_types,
             enable_tree_shaking,
             ref enable_typescript_transform,
-            ref decorators,
+            decorators,
             enable_mdx,
             enable_mdx_rs,
             enable_raw_css,
             ref enable_postcss_transform,
             ref enable_webpack_loaders,
+            enable_babel_loader,
             preset_env_versions,
             ref custom_ecma_transform_plugins,
+            ref swc_plugins,
             ref custom_rules,
             execution_context,
             ref rules,
@@ -113,12 +246,34 @@ impl ModuleOptionsVc {
 
         let mut transforms = before_transform_plugins;
 
+        if !swc_plugins.is_empty() {
+            transforms.push(EcmascriptInputTransform::Plugin(TransformPluginVc::cell(
+                Box::new(SwcEcmaTransformPluginsTransformer::new(swc_plugins.clone())),
+            )));
+        }
+
+        if enable_import_meta_glob {
+            transforms.push(EcmascriptInputTransform::ImportMetaGlob);
+        }
+
+        if let Some(config) = enable_styled_components {
+            transforms.push(EcmascriptInputTransform::Plugin(TransformPluginVc::cell(
+                Box::new(StyledComponentsTransformer::new(&*config.await?)),
+            )));
+        }
+
+        if enable_styled_jsx {
+            transforms.push(EcmascriptInputTransform::Plugin(TransformPluginVc::cell(
+                Box::new(StyledJsxTransformer::new()),
+            )));
+        }
+
         // Order of transforms is important. e.g. if the React transform occurs before
         // Styled JSX, there won't be JSX nodes for Styled JSX to transform.
         // If a custom plugin requires specific order _before_ core transform kicks in,
         // should use `before_transform_plugins`.
         if let Some(enable_jsx) = enable_jsx {
-            let jsx = enable_jsx.await?;
+            let jsx = tsconfig_jsx_options(path, enable_jsx).await?;
 
             transforms.push(EcmascriptInputTransform::React {
                 development: jsx.development,
@@ -142,12 +297,20 @@ impl ModuleOptionsVc {
             let options = options.await?;
             Some(EcmascriptInputTransform::TypeScript {
                 use_define_for_class_fields: options.use_define_for_class_fields,
+                treat_const_enum_as_enum: options.treat_const_enum_as_enum,
             })
         } else {
             None
         };
 
-        let decorators_transform = if let Some(options) = &decorators {
+        let decorators = match decorators {
+            Some(options) => Some(options),
+            None if enable_typescript_transform.is_some() => {
+                *tsconfig_decorators_options(path).await?
+            }
+            None => None,
+        };
+        let decorators_transform = if let Some(options) = decorators {
             let options = options.await?;
             options
                 .decorators_kind
@@ -342,6 +505,10 @@ impl ModuleOptionsVc {
                 ]),
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Static)],
             ),
+            ModuleRule::new(
+                ModuleRuleCondition::ResourcePathEndsWith(".wasm".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::WebAssembly)],
+            ),
             ModuleRule::new(
                 ModuleRuleCondition::ResourcePathHasNoExtension,
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Ecmascript {
@@ -562,6 +729,56 @@ impl ModuleOptionsVc {
             }
         }
 
+        // This rule isn't covered by the execution tests: they don't run with an
+        // execution context for loaders, and `babel-loader` and `@babel/core`
+        // aren't dependencies of the execution tests' package.json.
+        if let Some(babel_loader_options) = enable_babel_loader {
+            let babel_loader_options = babel_loader_options.await?;
+            let execution_context = execution_context
+                .context("execution_context is required for babel_loader")?
+                .with_layer("babel_loader");
+            let import_map =
+                if let Some(loader_runner_package) = babel_loader_options.loader_runner_package {
+                    package_import_map_from_import_mapping("loader-runner", loader_runner_package)
+                } else {
+                    package_import_map_from_context("loader-runner", path)
+                };
+            let mut options = serde_json::Map::new();
+            if let Some(config_file) = &babel_loader_options.config_file {
+                options.insert("configFile".to_string(), config_file.clone().into());
+            }
+            let loaders = WebpackLoaderItemsVc::cell(vec![WebpackLoaderItem {
+                loader: "babel-loader".to_string(),
+                options,
+            }]);
+            rules.push(ModuleRule::new(
+                ModuleRuleCondition::All(vec![
+                    ModuleRuleCondition::any(
+                        [".js", ".jsx", ".mjs", ".cjs", ".ts", ".tsx", ".mts", ".cts"]
+                            .into_iter()
+                            .map(|ext| ModuleRuleCondition::ResourcePathEndsWith(ext.to_string()))
+                            .collect(),
+                    ),
+                    ModuleRuleCondition::not(ModuleRuleCondition::ResourcePathEndsWith(
+                        ".d.ts".to_string(),
+                    )),
+                    ModuleRuleCondition::not(ModuleRuleCondition::ResourcePathInDirectory(
+                        "node_modules".to_string(),
+                    )),
+                    ModuleRuleCondition::not(ModuleRuleCondition::ResourceIsVirtualSource),
+                ]),
+                vec![ModuleRuleEffect::SourceTransforms(
+                    SourceTransformsVc::cell(vec![WebpackLoadersVc::new(
+                        node_evaluate_asset_context(execution_context, Some(import_map), None),
+                        execution_context,
+                        loaders,
+                        None,
+                    )
+                    .into()]),
+                )],
+            ));
+        }
+
         rules.extend(custom_rules.iter().cloned());
 
         Ok(ModuleOptionsVc::cell(ModuleOptions { rules }))
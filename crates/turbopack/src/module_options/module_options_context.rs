@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use turbo_tasks::trace::TraceRawVcs;
 use turbopack_core::{environment::EnvironmentVc, resolve::options::ImportMappingVc};
 use turbopack_ecmascript::TransformPluginVc;
+use turbopack_ecmascript_plugins::transform::{
+    styled_components::StyledComponentsTransformConfigVc,
+    swc_ecma_transform_plugins::SwcPluginModuleVc,
+};
 use turbopack_node::{
     execution_context::ExecutionContextVc, transforms::webpack::WebpackLoaderItemsVc,
 };
@@ -41,6 +45,19 @@ pub struct WebpackLoadersOptions {
 #[turbo_tasks::value(transparent)]
 pub struct OptionWebpackLoadersOptions(Option<WebpackLoadersOptionsVc>);
 
+/// Options for transforming the project's JavaScript and TypeScript files with
+/// Babel, by running `babel-loader` like the webpack loaders.
+#[turbo_tasks::value(shared)]
+#[derive(Default, Clone, Debug)]
+pub struct BabelLoaderOptions {
+    /// The path of the Babel config file, relative to the project. When not
+    /// set, Babel looks up the project's babel.config.js or .babelrc.
+    pub config_file: Option<String>,
+    /// Where to import `loader-runner` from, which runs `babel-loader`. When
+    /// not set, it's resolved from the directory of the transformed file.
+    pub loader_runner_package: Option<ImportMappingVc>,
+}
+
 /// The kind of decorators transform to use.
 /// [TODO]: might need bikeshed for the name (Ecma)
 #[derive(Clone, PartialEq, Eq, Debug, TraceRawVcs, Serialize, Deserialize)]
@@ -84,12 +101,19 @@ impl Default for DecoratorsOptionsVc {
     }
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct OptionDecoratorsOptions(Option<DecoratorsOptionsVc>);
+
 /// Subset of Typescript options configured via tsconfig.json or jsconfig.json,
 /// which affects the runtime transform output.
 #[turbo_tasks::value(shared)]
 #[derive(Default, Clone, Debug)]
 pub struct TypescriptTransformOptions {
     pub use_define_for_class_fields: bool,
+    /// Emits `const enum`s like regular enums instead of inlining their
+    /// members, for code which accesses them at runtime (e.g. with
+    /// `isolatedModules`).
+    pub treat_const_enum_as_enum: bool,
 }
 
 #[turbo_tasks::value_impl]
@@ -107,6 +131,10 @@ impl Default for TypescriptTransformOptionsVc {
 }
 
 // [TODO]: should enabled_react_refresh belong to this options?
+/// Options for the JSX transform. The runtime and the import source default
+/// to the `jsx` and `jsxImportSource` options of the applicable tsconfig.json
+/// or jsconfig.json, and can be overridden for files with a `@jsxRuntime` or
+/// `@jsxImportSource` pragma comment.
 #[turbo_tasks::value(shared)]
 #[derive(Default, Clone, Debug)]
 pub struct JsxTransformOptions {
@@ -151,10 +179,21 @@ impl MdxTransformModuleOptionsVc {
 #[serde(default)]
 pub struct ModuleOptionsContext {
     pub enable_jsx: Option<JsxTransformOptionsVc>,
+    /// Compiles `<style jsx>` elements with the styled-jsx transform. This runs
+    /// before the JSX transform.
+    pub enable_styled_jsx: bool,
+    /// Applies the styled-components transform with the given options.
+    pub enable_styled_components: Option<StyledComponentsTransformConfigVc>,
+    /// Replaces Vite-style `import.meta.glob(...)` calls with the matched
+    /// modules.
+    pub enable_import_meta_glob: bool,
     pub enable_postcss_transform: Option<PostCssTransformOptions>,
     pub enable_webpack_loaders: Option<WebpackLoadersOptionsVc>,
+    pub enable_babel_loader: Option<BabelLoaderOptionsVc>,
     pub enable_types: bool,
     pub enable_typescript_transform: Option<TypescriptTransformOptionsVc>,
+    /// When not set, TypeScript modules use the legacy decorators transform if
+    /// `experimentalDecorators` is enabled in their tsconfig.json.
     pub decorators: Option<DecoratorsOptionsVc>,
     pub enable_mdx: bool,
     /// This skips `GlobalCss` and `ModuleCss` module assets from being
@@ -168,6 +207,9 @@ pub struct ModuleOptionsContext {
     pub enable_mdx_rs: Option<MdxTransformModuleOptionsVc>,
     pub preset_env_versions: Option<EnvironmentVc>,
     pub custom_ecma_transform_plugins: Option<CustomEcmascriptTransformPluginsVc>,
+    /// SWC Wasm plugins and their configs, which run before the other
+    /// transforms. See [SwcPluginModuleVc::resolve] to load them.
+    pub swc_plugins: Vec<(SwcPluginModuleVc, serde_json::Value)>,
     /// Custom rules to be applied after all default rules.
     pub custom_rules: Vec<ModuleRule>,
     pub execution_context: Option<ExecutionContextVc>,
@@ -3,16 +3,23 @@ use turbo_tasks_fs::{FileSystem, FileSystemPathVc};
 use turbopack_core::resolve::{
     find_context_file,
     options::{
-        ConditionValue, ImportMap, ImportMapping, ResolutionConditions, ResolveInPackage,
-        ResolveIntoPackage, ResolveModules, ResolveOptions, ResolveOptionsVc,
+        ConditionValue, ExternalType, ImportMap, ImportMapping, ResolutionConditions,
+        ResolveInPackage, ResolveIntoPackage, ResolveModules, ResolveOptions, ResolveOptionsVc,
+        TypesOnlyResolve,
     },
+    package_json,
+    web_import_map::read_web_import_map,
+    workspaces::workspace_packages,
     AliasMap, AliasPattern, FindContextFileResult,
 };
 use turbopack_ecmascript::typescript::resolve::{
     apply_tsconfig_resolve_options, tsconfig, tsconfig_resolve_options,
 };
 
-use crate::resolve_options_context::ResolveOptionsContextVc;
+use crate::{
+    resolve_options_context::ResolveOptionsContextVc,
+    resolve_overrides::apply_package_resolve_overrides,
+};
 
 const NODE_EXTERNALS: [&str; 51] = [
     "assert",
@@ -91,16 +98,19 @@ async fn base_resolve_options(
         for req in NODE_EXTERNALS {
             direct_mappings.insert(
                 AliasPattern::exact(req),
-                ImportMapping::External(None).into(),
+                ImportMapping::External(None, ExternalType::CommonJs).into(),
             );
             direct_mappings.insert(
                 AliasPattern::exact(format!("node:{req}")),
-                ImportMapping::External(None).into(),
+                ImportMapping::External(None, ExternalType::CommonJs).into(),
             );
         }
     }
 
     let mut import_map = ImportMap::new(direct_mappings);
+    if let Some(web_import_map) = opt.web_import_map {
+        import_map.extend(&*read_web_import_map(web_import_map).await?);
+    }
     if let Some(additional_import_map) = opt.import_map {
         let additional_import_map = additional_import_map.await?;
         import_map.extend(&additional_import_map);
@@ -177,23 +187,29 @@ async fn base_resolve_options(
             ext.push(".json".to_string());
             ext
         },
-        modules: if let Some(environment) = emulating {
-            if *environment.resolve_node_modules().await? {
-                vec![ResolveModules::Nested(
-                    root,
-                    vec!["node_modules".to_string()],
-                )]
-            } else {
-                Vec::new()
-            }
-        } else {
+        modules: {
             let mut mods = Vec::new();
-            if let Some(dir) = opt.enable_node_modules {
+            if let Some(workspace_root) = opt.workspace_root {
+                mods.push(ResolveModules::Workspaces(workspace_packages(
+                    workspace_root,
+                )));
+            }
+            if let Some(environment) = emulating {
+                if *environment.resolve_node_modules().await? {
+                    mods.push(ResolveModules::Nested(
+                        root,
+                        vec!["node_modules".to_string()],
+                    ));
+                }
+            } else if let Some(dir) = opt.enable_node_modules {
                 mods.push(ResolveModules::Nested(
                     dir,
                     vec!["node_modules".to_string()],
                 ));
             }
+            for dir in opt.fallback_module_directories.iter() {
+                mods.push(ResolveModules::Fallback(*dir));
+            }
             mods
         },
         into_package: {
@@ -201,13 +217,19 @@ async fn base_resolve_options(
                 conditions: conditions.clone(),
                 unspecified_conditions: ConditionValue::Unset,
             }];
-            if opt.browser {
-                resolve_into.push(ResolveIntoPackage::MainField("browser".to_string()));
-            }
-            if opt.module {
-                resolve_into.push(ResolveIntoPackage::MainField("module".to_string()));
+            if let Some(main_fields) = &opt.main_fields {
+                for field in main_fields {
+                    resolve_into.push(ResolveIntoPackage::MainField(field.clone()));
+                }
+            } else {
+                if opt.browser {
+                    resolve_into.push(ResolveIntoPackage::MainField("browser".to_string()));
+                }
+                if opt.module {
+                    resolve_into.push(ResolveIntoPackage::MainField("module".to_string()));
+                }
+                resolve_into.push(ResolveIntoPackage::MainField("main".to_string()));
             }
-            resolve_into.push(ResolveIntoPackage::MainField("main".to_string()));
             resolve_into.push(ResolveIntoPackage::Default("index".to_string()));
             resolve_into
         },
@@ -217,11 +239,31 @@ async fn base_resolve_options(
                 unspecified_conditions: ConditionValue::Unset,
             }];
             if opt.browser {
-                resolve_in.push(ResolveInPackage::AliasField("browser".to_string()));
+                resolve_in.push(ResolveInPackage::AliasField(vec!["browser".to_string()]));
+            }
+            for field in opt.alias_fields.iter() {
+                let field = ResolveInPackage::AliasField(field.clone());
+                if !resolve_in.contains(&field) {
+                    resolve_in.push(field);
+                }
             }
             resolve_in
         },
         import_map: Some(import_map),
+        externals: opt.externals,
+        builtin_polyfills: if opt.browser {
+            opt.builtin_polyfills
+        } else {
+            None
+        },
+        types_only: if let Some(stand_in) = opt.types_only_stand_in {
+            Some(TypesOnlyResolve::StandIn(stand_in))
+        } else if opt.warn_on_types_only {
+            Some(TypesOnlyResolve::Warn)
+        } else {
+            None
+        },
+        enforce_extension: opt.enforce_extension,
         resolved_map: opt.resolved_map,
         plugins,
         ..Default::default()
@@ -258,6 +300,26 @@ pub async fn resolve_options(
         resolve_options
     };
 
+    let resolve_options = if options_context_value.enable_package_resolve_overrides {
+        let package_json = find_context_file(context, package_json()).await?;
+        match *package_json {
+            // Overrides of installed packages are not applied, they are meant for the
+            // packages of the project only.
+            FindContextFileResult::Found(path, _)
+                if !path
+                    .await?
+                    .path
+                    .split('/')
+                    .any(|segment| segment == "node_modules") =>
+            {
+                apply_package_resolve_overrides(resolve_options, path)
+            }
+            _ => resolve_options,
+        }
+    } else {
+        resolve_options
+    };
+
     // Make sure to always apply `options_context.import_map` last, so it properly
     // overwrites any other mappings.
     let resolve_options = options_context_value
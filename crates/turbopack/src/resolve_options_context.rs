@@ -3,7 +3,7 @@ use turbo_tasks_fs::FileSystemPathVc;
 use turbopack_core::{
     environment::EnvironmentVc,
     resolve::{
-        options::{ImportMapVc, ResolvedMapVc},
+        options::{EnforceExtension, ExternalsVc, ImportMapVc, ImportMappingVc, ResolvedMapVc},
         plugin::ResolvePluginVc,
     },
 };
@@ -28,6 +28,17 @@ pub struct ResolveOptionsContext {
     /// directory
     pub enable_node_modules: Option<FileSystemPathVc>,
     #[serde(default)]
+    /// Resolve bare imports of the workspace packages, which are defined in
+    /// the package.json or pnpm-workspace.yaml in this directory, to their
+    /// source directories.
+    pub workspace_root: Option<FileSystemPathVc>,
+    #[serde(default)]
+    /// Directories to look for packages in when they can't be found in any
+    /// node_modules folder. These can live outside of the project root, e.g.
+    /// a global node_modules or a vendored directory. Their filesystem needs
+    /// to be watched to invalidate resolving when files in them change.
+    pub fallback_module_directories: Vec<FileSystemPathVc>,
+    #[serde(default)]
     /// Mark well-known Node.js modules as external imports and load them using
     /// native `require`. e.g. url, querystring, os
     pub enable_node_externals: bool,
@@ -38,6 +49,18 @@ pub struct ResolveOptionsContext {
     /// Enables the "module" field and export condition in package.json
     pub module: bool,
     #[serde(default)]
+    /// Additional package.json fields which are used as alias maps within a
+    /// package, like enhanced-resolve's `aliasFields`. Each field is a path
+    /// of keys to support nested fields, e.g. `[["react-native"]]`. The
+    /// "browser" field is added automatically when `browser` is enabled.
+    pub alias_fields: Vec<Vec<String>>,
+    #[serde(default)]
+    /// The package.json fields which are used as entry point of a package, in
+    /// order of priority, e.g. `["browser", "module", "main"]`. When not set,
+    /// the fields are derived from `browser` and `module`. Fields can be
+    /// reordered or dropped, e.g. when packages ship broken ESM in "module".
+    pub main_fields: Option<Vec<String>>,
+    #[serde(default)]
     pub custom_conditions: Vec<String>,
     #[serde(default)]
     /// An additional import map to use when resolving modules.
@@ -45,6 +68,9 @@ pub struct ResolveOptionsContext {
     /// If set, this import map will be applied to `ResolveOption::import_map`.
     /// It is always applied last, so any mapping defined within will take
     /// precedence over any other (e.g. tsconfig.json `compilerOptions.paths`).
+    ///
+    /// Remote `http://` and `https://` imports can be enabled by extending it
+    /// with the import map of `turbopack_remote_imports::RemoteImportsVc`.
     pub import_map: Option<ImportMapVc>,
     #[serde(default)]
     /// An import map to fall back to when a request could not be resolved.
@@ -54,9 +80,43 @@ pub struct ResolveOptionsContext {
     /// any mapping defined within will take precedence over any other.
     pub fallback_import_map: Option<ImportMapVc>,
     #[serde(default)]
+    /// The path of a web import map (e.g. an import_map.json), whose `imports`
+    /// and `scopes` are applied to `ResolveOption::import_map`. Mappings of
+    /// `import_map` take precedence over it.
+    pub web_import_map: Option<FileSystemPathVc>,
+    #[serde(default)]
+    /// Rules which mark package requests as externals, e.g. by a glob over
+    /// the package name, together with how they are consumed at runtime.
+    pub externals: Option<ExternalsVc>,
+    #[serde(default)]
+    /// An import map which provides polyfills for builtins requested with a
+    /// protocol like `node:path` in browser contexts. It's looked up with the
+    /// name of the builtin, e.g. `path`. When set, builtins without polyfill
+    /// are reported as errors in browser contexts. Otherwise builtins are
+    /// left to the runtime as externals.
+    pub builtin_polyfills: Option<ImportMapVc>,
+    #[serde(default)]
+    /// Emit a warning when a request resolves to a types-only file (e.g. a
+    /// `.d.ts` file of an `@types/*` package), which has no runtime code.
+    pub warn_on_types_only: bool,
+    #[serde(default)]
+    /// A mapping which replaces requests resolving to types-only files, e.g.
+    /// an empty module. Implies `warn_on_types_only`.
+    pub types_only_stand_in: Option<ImportMappingVc>,
+    #[serde(default)]
+    /// Require or forbid extensions on relative requests. Violations are
+    /// reported as errors.
+    pub enforce_extension: Option<EnforceExtension>,
+    #[serde(default)]
     /// An additional resolved map to use after modules have been resolved.
     pub resolved_map: Option<ResolvedMapVc>,
     #[serde(default)]
+    /// Apply the resolve overrides in the `turbopack.resolve` field of the
+    /// nearest package.json, so packages of a monorepo can have their own
+    /// aliases, extensions and conditions. See
+    /// [crate::resolve_overrides::apply_package_resolve_overrides].
+    pub enable_package_resolve_overrides: bool,
+    #[serde(default)]
     /// A list of rules to use a different resolve option context for certain
     /// context paths. The first matching is used.
     pub rules: Vec<(ContextCondition, ResolveOptionsContextVc)>,
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use turbo_tasks::primitives::StringVc;
+use turbo_tasks_fs::FileSystemPathVc;
+use turbopack_core::{
+    issue::{Issue, IssueVc},
+    package_json::read_package_json,
+    resolve::{
+        options::{
+            ConditionValue, ImportMap, ImportMapping, ResolveInPackage, ResolveIntoPackage,
+            ResolveOptionsVc,
+        },
+        AliasPattern,
+    },
+};
+
+/// Applies the resolve overrides of a package to the resolve options of the
+/// files within it. The overrides are defined in the `turbopack.resolve` field
+/// of the package.json:
+///
+/// ```json
+/// {
+///   "turbopack": {
+///     "resolve": {
+///       "alias": { "@/*": "./src/*", "fs": false },
+///       "extensions": [".web.ts", ".ts", ".js"],
+///       "conditions": ["worker"]
+///     }
+///   }
+/// }
+/// ```
+///
+/// Aliases are relative to the package directory. `false` ignores a request.
+/// Extensions replace the default extensions, conditions are added to the
+/// default conditions. The package.json affects every result, so changing the
+/// overrides invalidates resolving in the package.
+#[turbo_tasks::function]
+pub async fn apply_package_resolve_overrides(
+    resolve_options: ResolveOptionsVc,
+    package_json_path: FileSystemPathVc,
+) -> Result<ResolveOptionsVc> {
+    let package_json = read_package_json(package_json_path).await?;
+    let Some(package_json) = &*package_json else {
+        return Ok(resolve_options);
+    };
+    let overrides = &package_json["turbopack"]["resolve"];
+    if overrides.is_null() {
+        return Ok(resolve_options);
+    }
+    let Some(overrides) = overrides.as_object() else {
+        emit_invalid_overrides(package_json_path, "\"turbopack.resolve\" must be an object");
+        return Ok(resolve_options);
+    };
+    let package_dir = package_json_path.parent().resolve().await?;
+
+    let mut resolve_options = resolve_options.await?.clone_value();
+    resolve_options.affecting_paths.push(package_json_path);
+
+    if let Some(alias) = overrides.get("alias") {
+        match alias.as_object() {
+            Some(alias) => {
+                let import_map = alias_import_map(alias, package_dir, package_json_path);
+                resolve_options.import_map = Some(match resolve_options.import_map {
+                    Some(current) => current.extend(import_map.cell()),
+                    None => import_map.cell(),
+                });
+            }
+            None => emit_invalid_overrides(package_json_path, "\"alias\" must be an object"),
+        }
+    }
+
+    if let Some(extensions) = overrides.get("extensions") {
+        match string_list(extensions) {
+            Some(extensions) => resolve_options.extensions = extensions,
+            None => emit_invalid_overrides(
+                package_json_path,
+                "\"extensions\" must be an array of strings",
+            ),
+        }
+    }
+
+    if let Some(conditions) = overrides.get("conditions") {
+        match string_list(conditions) {
+            Some(new_conditions) => {
+                for into in resolve_options.into_package.iter_mut() {
+                    if let ResolveIntoPackage::ExportsField { conditions, .. } = into {
+                        for condition in new_conditions.iter() {
+                            conditions.insert(condition.clone(), ConditionValue::Set);
+                        }
+                    }
+                }
+                for item in resolve_options.in_package.iter_mut() {
+                    if let ResolveInPackage::ImportsField { conditions, .. } = item {
+                        for condition in new_conditions.iter() {
+                            conditions.insert(condition.clone(), ConditionValue::Set);
+                        }
+                    }
+                }
+            }
+            None => emit_invalid_overrides(
+                package_json_path,
+                "\"conditions\" must be an array of strings",
+            ),
+        }
+    }
+
+    Ok(resolve_options.cell())
+}
+
+/// Converts the `alias` overrides into an import map. Invalid aliases are
+/// skipped with an issue.
+fn alias_import_map(
+    alias: &serde_json::Map<String, JsonValue>,
+    package_dir: FileSystemPathVc,
+    package_json_path: FileSystemPathVc,
+) -> ImportMap {
+    let mut import_map = ImportMap::empty();
+    for (key, value) in alias {
+        let mapping = match value {
+            JsonValue::String(target) => {
+                ImportMapping::PrimaryAlternative(target.clone(), Some(package_dir))
+            }
+            JsonValue::Bool(false) => ImportMapping::Ignore,
+            _ => {
+                emit_invalid_overrides(
+                    package_json_path,
+                    &format!("the alias \"{key}\" must be a string or false"),
+                );
+                continue;
+            }
+        };
+        import_map.insert_alias(AliasPattern::parse(key.as_str()), mapping.cell());
+    }
+    import_map
+}
+
+fn string_list(value: &JsonValue) -> Option<Vec<String>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|item| item.as_str().map(|item| item.to_string()))
+        .collect()
+}
+
+fn emit_invalid_overrides(path: FileSystemPathVc, message: &str) {
+    InvalidResolveOverridesIssue {
+        path,
+        message: message.to_string(),
+    }
+    .cell()
+    .as_issue()
+    .emit();
+}
+
+#[turbo_tasks::value(shared)]
+struct InvalidResolveOverridesIssue {
+    path: FileSystemPathVc,
+    message: String,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for InvalidResolveOverridesIssue {
+    #[turbo_tasks::function]
+    fn category(&self) -> StringVc {
+        StringVc::cell("resolve".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> StringVc {
+        StringVc::cell("Invalid resolve overrides in package.json".to_string())
+    }
+
+    #[turbo_tasks::function]
+    fn context(&self) -> FileSystemPathVc {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> StringVc {
+        StringVc::cell(self.message.clone())
+    }
+}
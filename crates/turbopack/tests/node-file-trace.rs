@@ -279,19 +279,19 @@ fn node_file_trace_memory(#[case] input: CaseInput) {
 
 #[cfg(feature = "test_persistent_cache")]
 #[apply(test_cases)]
-fn node_file_trace_rocksdb(#[case] input: CaseInput) {
+fn node_file_trace_persistent(#[case] input: CaseInput) {
+    use turbo_tasks_disk_cache::DiskPersistedGraph;
     use turbo_tasks_memory::MemoryBackendWithPersistedGraph;
-    use turbo_tasks_rocksdb::RocksDbPersistedGraph;
 
     node_file_trace(
         input,
-        "rockdb",
+        "persistent_cache",
         false,
         2,
         240,
         |directory_path| {
             TurboTasks::new(MemoryBackendWithPersistedGraph::new(
-                RocksDbPersistedGraph::new(directory_path.join(".db")).unwrap(),
+                DiskPersistedGraph::new(directory_path.join(".cache")).unwrap(),
             ))
         },
         |_| {},
@@ -565,7 +565,7 @@ async fn exec_node(directory: String, path: FileSystemPathVc) -> Result<CommandO
     let mut cmd = Command::new("node");
 
     let p = path.await?;
-    let f = Path::new(&directory).join(&p.path);
+    let f = Path::new(&directory).join(&*p.path);
     let dir = f.parent().unwrap();
     println!("[CWD]: {}", dir.display());
     let label = path.to_string().await?;
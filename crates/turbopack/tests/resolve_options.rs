@@ -0,0 +1,132 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::Value;
+use turbo_tasks_fs::{FileSystem, FileSystemPathVc, VirtualFileSystemVc};
+use turbo_tasks_testing::{register, run};
+use turbopack::{
+    resolve::resolve_options,
+    resolve_options_context::{ResolveOptionsContext, ResolveOptionsContextVc},
+};
+use turbopack_core::{
+    environment::{EnvironmentVc, ExecutionEnvironment, NodeJsEnvironment},
+    resolve::options::{ImportMap, ResolveIntoPackage, ResolveModules},
+};
+
+register!(turbopack::register);
+
+fn context() -> FileSystemPathVc {
+    VirtualFileSystemVc::new().root().join("src")
+}
+
+/// Returns the main fields the resolve options derived from `options_context`
+/// look at, in order.
+async fn main_fields(options_context: ResolveOptionsContextVc) -> Result<Vec<String>> {
+    let options = resolve_options(context(), options_context).await?;
+    Ok(options
+        .into_package
+        .iter()
+        .filter_map(|into_package| match into_package {
+            ResolveIntoPackage::MainField(field) => Some(field.clone()),
+            _ => None,
+        })
+        .collect())
+}
+
+#[tokio::test]
+async fn default_main_fields() {
+    run! {
+        let options_context = ResolveOptionsContext {
+            browser: true,
+            module: true,
+            ..Default::default()
+        }
+        .cell();
+        assert_eq!(
+            main_fields(options_context).await?,
+            ["browser", "module", "main"]
+        );
+        assert_eq!(
+            main_fields(ResolveOptionsContext::default().cell()).await?,
+            ["main"]
+        );
+    }
+}
+
+#[tokio::test]
+async fn configured_main_fields() {
+    run! {
+        let options_context = ResolveOptionsContext {
+            browser: true,
+            module: true,
+            main_fields: Some(vec!["main".to_string(), "module".to_string()]),
+            ..Default::default()
+        }
+        .cell();
+        // The configured fields replace the derived ones, including their order.
+        assert_eq!(main_fields(options_context).await?, ["main", "module"]);
+    }
+}
+
+#[tokio::test]
+async fn builtin_polyfills() {
+    run! {
+        let browser = ResolveOptionsContext {
+            browser: true,
+            ..Default::default()
+        }
+        .cell();
+        // Without configured polyfills builtins stay externals.
+        assert!(resolve_options(context(), browser)
+            .await?
+            .builtin_polyfills
+            .is_none());
+
+        let polyfills = ImportMap::default().cell();
+        let browser = ResolveOptionsContext {
+            browser: true,
+            builtin_polyfills: Some(polyfills),
+            ..Default::default()
+        }
+        .cell();
+        assert_eq!(
+            resolve_options(context(), browser).await?.builtin_polyfills,
+            Some(polyfills)
+        );
+
+        let node = ResolveOptionsContext {
+            builtin_polyfills: Some(polyfills),
+            ..Default::default()
+        }
+        .cell();
+        assert!(resolve_options(context(), node)
+            .await?
+            .builtin_polyfills
+            .is_none());
+    }
+}
+
+#[tokio::test]
+async fn workspace_modules() {
+    run! {
+        let workspace_root = VirtualFileSystemVc::new().root();
+        let node = EnvironmentVc::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+            NodeJsEnvironment::default().into(),
+        )));
+        for emulate_environment in [None, Some(node)] {
+            let options_context = ResolveOptionsContext {
+                emulate_environment,
+                enable_node_modules: Some(workspace_root),
+                workspace_root: Some(workspace_root),
+                ..Default::default()
+            }
+            .cell();
+            let options = resolve_options(context(), options_context).await?;
+            // Workspace packages take precedence over node_modules.
+            assert!(matches!(
+                options.modules.as_slice(),
+                [ResolveModules::Workspaces(_), ResolveModules::Nested(..)]
+            ));
+        }
+    }
+}
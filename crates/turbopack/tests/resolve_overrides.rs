@@ -0,0 +1,93 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem, FileSystemPathVc};
+use turbo_tasks_testing::{register, run};
+use turbopack::resolve_overrides::apply_package_resolve_overrides;
+use turbopack_core::{
+    issue::{Issue, IssueVc},
+    resolve::options::{
+        ConditionValue, ResolutionConditions, ResolveIntoPackage, ResolveOptions, ResolveOptionsVc,
+    },
+};
+
+register!(turbopack::register);
+
+fn package_json(package: &str) -> FileSystemPathVc {
+    DiskFileSystemVc::new(
+        "fixtures".to_string(),
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/resolve_overrides"
+        )
+        .to_string(),
+    )
+    .root()
+    .join(&format!("{package}/package.json"))
+}
+
+fn base_options() -> ResolveOptionsVc {
+    ResolveOptions {
+        extensions: vec![".js".to_string()],
+        into_package: vec![ResolveIntoPackage::ExportsField {
+            conditions: ResolutionConditions::default(),
+            unspecified_conditions: ConditionValue::Unset,
+        }],
+        ..Default::default()
+    }
+    .cell()
+}
+
+/// Returns the descriptions of the issues which were emitted while applying
+/// the overrides.
+async fn issue_descriptions(options: ResolveOptionsVc) -> Result<Vec<String>> {
+    let issues = IssueVc::peek_issues_with_path(options).await?.await?;
+    let mut descriptions = Vec::new();
+    for issue in issues.iter() {
+        descriptions.push(issue.description().await?.clone_value());
+    }
+    descriptions.sort();
+    Ok(descriptions)
+}
+
+/// Asserts that the `extensions` and `conditions` overrides of the fixtures
+/// were applied.
+fn assert_extensions_and_conditions(options: &ResolveOptions) {
+    assert_eq!(options.extensions, [".web.js", ".js"]);
+    let [ResolveIntoPackage::ExportsField { conditions, .. }] = options.into_package.as_slice()
+    else {
+        panic!("the exports field is missing");
+    };
+    assert_eq!(conditions.get("worker"), Some(&ConditionValue::Set));
+}
+
+#[tokio::test]
+async fn applies_overrides() {
+    run! {
+        let options = apply_package_resolve_overrides(base_options(), package_json("valid"));
+        let options_value = options.await?;
+        assert!(options_value.import_map.is_some());
+        assert_extensions_and_conditions(&options_value);
+        // Invalid aliases are skipped, the others still apply.
+        assert_eq!(
+            issue_descriptions(options).await?,
+            ["the alias \"invalid\" must be a string or false"]
+        );
+    }
+}
+
+#[tokio::test]
+async fn invalid_alias() {
+    run! {
+        let options =
+            apply_package_resolve_overrides(base_options(), package_json("invalid-alias"));
+        let options_value = options.await?;
+        assert!(options_value.import_map.is_none());
+        // The other overrides are still applied.
+        assert_extensions_and_conditions(&options_value);
+        assert_eq!(
+            issue_descriptions(options).await?,
+            ["\"alias\" must be an object"]
+        );
+    }
+}